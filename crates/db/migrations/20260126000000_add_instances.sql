@@ -0,0 +1,12 @@
+-- Registry of running server instances, used to detect several instances
+-- sharing the same database or workspace directory
+CREATE TABLE instances (
+    id            BLOB PRIMARY KEY,
+    pid           INTEGER NOT NULL,
+    hostname      TEXT NOT NULL,
+    workspace_dir TEXT NOT NULL,
+    started_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    heartbeat_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_instances_heartbeat_at ON instances(heartbeat_at);
@@ -0,0 +1,9 @@
+-- Per-user ownership for multi-user deployments. Projects without a row are
+-- unowned and only visible when the server runs in single-user mode.
+CREATE TABLE project_owners (
+    project_id BLOB PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    user_id    BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_project_owners_user_id ON project_owners(user_id);
@@ -0,0 +1,8 @@
+-- Peak context token usage reported by a coding agent run, captured from the
+-- normalized logs so it can be aggregated without re-parsing stored output.
+CREATE TABLE execution_process_token_usage (
+    execution_process_id BLOB PRIMARY KEY REFERENCES execution_processes(id) ON DELETE CASCADE,
+    total_tokens         INTEGER NOT NULL,
+    model_context_window INTEGER,
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
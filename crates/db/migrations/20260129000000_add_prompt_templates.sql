@@ -0,0 +1,26 @@
+-- Named prompt templates with version history. Templates without a project
+-- apply globally; a project template with the same name overrides it.
+CREATE TABLE prompt_templates (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB REFERENCES projects(id) ON DELETE CASCADE,
+    name            TEXT NOT NULL,
+    description     TEXT,
+    content         TEXT NOT NULL,
+    current_version INTEGER NOT NULL DEFAULT 1,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE UNIQUE INDEX idx_prompt_templates_project_name
+    ON prompt_templates(project_id, name) WHERE project_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_prompt_templates_global_name
+    ON prompt_templates(name) WHERE project_id IS NULL;
+
+CREATE TABLE prompt_template_versions (
+    id          BLOB PRIMARY KEY,
+    template_id BLOB NOT NULL REFERENCES prompt_templates(id) ON DELETE CASCADE,
+    version     INTEGER NOT NULL,
+    content     TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (template_id, version)
+);
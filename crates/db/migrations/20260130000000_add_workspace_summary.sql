@@ -0,0 +1,2 @@
+-- Short summary of what the latest coding agent run in a workspace changed
+ALTER TABLE workspaces ADD COLUMN summary TEXT;
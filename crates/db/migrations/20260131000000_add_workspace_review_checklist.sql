@@ -0,0 +1,2 @@
+-- Markdown review checklist generated from the workspace diff
+ALTER TABLE workspaces ADD COLUMN review_checklist TEXT;
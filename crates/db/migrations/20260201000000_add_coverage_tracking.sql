@@ -0,0 +1,44 @@
+-- Optional per-repo coverage hook run after each attempt
+CREATE TABLE repo_coverage_settings (
+    repo_id       BLOB PRIMARY KEY,
+    command       TEXT NOT NULL,
+    -- lcov or cobertura report written by the command, relative to the repo root
+    report_path   TEXT NOT NULL,
+    -- Allowed drop in line coverage, in percentage points
+    max_drop      REAL NOT NULL DEFAULT 1.0,
+    block_auto_pr INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+-- Coverage of target branch commits, measured once per merge base
+CREATE TABLE coverage_baselines (
+    repo_id       BLOB NOT NULL,
+    commit_sha    TEXT NOT NULL,
+    lines_covered INTEGER NOT NULL,
+    lines_total   INTEGER NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (repo_id, commit_sha),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE TABLE coverage_reports (
+    id                     BLOB PRIMARY KEY,
+    workspace_id           BLOB NOT NULL,
+    repo_id                BLOB NOT NULL,
+    commit_sha             TEXT NOT NULL,
+    lines_covered          INTEGER NOT NULL,
+    lines_total            INTEGER NOT NULL,
+    baseline_commit_sha    TEXT,
+    baseline_lines_covered INTEGER,
+    baseline_lines_total   INTEGER,
+    -- Percentage points gained (positive) or lost (negative) vs. the baseline
+    delta                  REAL,
+    auto_pr_blocked        INTEGER NOT NULL DEFAULT 0,
+    created_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_coverage_reports_workspace_id ON coverage_reports(workspace_id, created_at);
@@ -0,0 +1,32 @@
+-- Optional per-repo security scanners run after each attempt
+CREATE TABLE repo_security_audit_settings (
+    repo_id        BLOB PRIMARY KEY,
+    -- JSON array of scanner names: cargo_audit, npm_audit, semgrep
+    scanners       TEXT NOT NULL DEFAULT '[]',
+    -- Value passed to `semgrep --config`; defaults to "auto"
+    semgrep_config TEXT,
+    -- Block auto-PR while high or critical findings are open
+    block_auto_pr  INTEGER NOT NULL DEFAULT 1,
+    comment_on_pr  INTEGER NOT NULL DEFAULT 1,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+-- Findings of the latest audit of each workspace repo
+CREATE TABLE security_findings (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    repo_id      BLOB NOT NULL,
+    scanner      TEXT NOT NULL,
+    severity     TEXT NOT NULL CHECK (severity IN ('critical', 'high', 'medium', 'low', 'info')),
+    rule_id      TEXT NOT NULL,
+    title        TEXT NOT NULL,
+    -- File and line for code findings, package@version for dependency findings
+    location     TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_security_findings_workspace_id ON security_findings(workspace_id, repo_id);
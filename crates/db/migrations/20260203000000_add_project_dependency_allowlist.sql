@@ -0,0 +1,2 @@
+-- JSON array of dependency names (trailing * matches a prefix) agents may add
+ALTER TABLE projects ADD COLUMN dependency_allowlist TEXT;
@@ -0,0 +1,2 @@
+-- JSON array of .gitignore-style patterns hidden from attempt diffs and agent context
+ALTER TABLE projects ADD COLUMN diff_ignore_patterns TEXT;
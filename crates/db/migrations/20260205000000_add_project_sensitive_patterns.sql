@@ -0,0 +1,3 @@
+-- Project changes to the global sensitive file patterns, as JSON arrays
+ALTER TABLE projects ADD COLUMN sensitive_patterns_added TEXT;
+ALTER TABLE projects ADD COLUMN sensitive_patterns_removed TEXT;
@@ -0,0 +1,20 @@
+-- Persistent queue for background work that must survive a restart
+CREATE TABLE jobs (
+    id           BLOB PRIMARY KEY,
+    kind         TEXT NOT NULL,
+    -- JSON arguments for the job kind
+    payload      TEXT NOT NULL,
+    status       TEXT NOT NULL DEFAULT 'pending'
+                    CHECK (status IN ('pending', 'running', 'completed', 'dead')),
+    attempts     INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    last_error   TEXT,
+    -- Earliest time the job may run; pushed back between retries
+    run_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    started_at   TEXT,
+    finished_at  TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_jobs_status_run_at ON jobs(status, run_at);
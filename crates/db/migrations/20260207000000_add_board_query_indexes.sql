@@ -0,0 +1,7 @@
+-- Support the per-project aggregates behind the task board query
+CREATE INDEX IF NOT EXISTS idx_workspaces_task_id_updated_at
+    ON workspaces (task_id, updated_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_merges_workspace_open_pr
+    ON merges (workspace_id, created_at DESC)
+    WHERE merge_type = 'pr' AND pr_status = 'open';
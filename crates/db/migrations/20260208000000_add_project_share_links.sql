@@ -0,0 +1,11 @@
+-- Tokenized read-only links to a project's board, for people without an account
+CREATE TABLE project_share_links (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    token       TEXT NOT NULL UNIQUE,
+    -- NULL links stay valid until revoked
+    expires_at  TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_project_share_links_project_id ON project_share_links(project_id);
@@ -0,0 +1,6 @@
+-- The promoted attempt of a task; follow-ups and PRs go to it
+ALTER TABLE workspaces ADD COLUMN canonical INTEGER NOT NULL DEFAULT 0;
+
+CREATE UNIQUE INDEX idx_workspaces_canonical_task_id
+    ON workspaces(task_id)
+    WHERE canonical = 1;
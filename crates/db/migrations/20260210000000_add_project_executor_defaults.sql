@@ -0,0 +1,2 @@
+-- JSON object with the project's default executor and per-executor variant/model overrides
+ALTER TABLE projects ADD COLUMN executor_defaults TEXT;
@@ -0,0 +1,5 @@
+-- Attempts started together to compare executors on the same task share a group id
+ALTER TABLE workspaces ADD COLUMN comparison_group_id BLOB;
+
+CREATE INDEX idx_workspaces_comparison_group_id ON workspaces(comparison_group_id)
+    WHERE comparison_group_id IS NOT NULL;
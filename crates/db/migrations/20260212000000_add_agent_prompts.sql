@@ -0,0 +1,12 @@
+-- Every prompt sent to a coding agent, kept for debugging and replay
+CREATE TABLE agent_prompts (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    kind                 TEXT NOT NULL,
+    prompt               TEXT NOT NULL,
+    -- Set when the prompt was replayed from an earlier one
+    replayed_from_id     BLOB REFERENCES agent_prompts(id) ON DELETE SET NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_agent_prompts_execution_process_id ON agent_prompts(execution_process_id);
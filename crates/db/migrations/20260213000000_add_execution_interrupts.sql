@@ -0,0 +1,10 @@
+-- Agent turns stopped by the user mid-run, with what they had produced so the
+-- next follow-up can pick up from there
+CREATE TABLE execution_interrupts (
+    execution_process_id BLOB PRIMARY KEY REFERENCES execution_processes(id) ON DELETE CASCADE,
+    agent_session_id     TEXT,
+    partial_output       TEXT,
+    -- Set once a follow-up has been sent with the interrupt's context
+    resumed_at           TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
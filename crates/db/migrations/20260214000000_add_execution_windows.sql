@@ -0,0 +1,9 @@
+-- Hours a project's attempts may start in (JSON); NULL means any time
+ALTER TABLE projects ADD COLUMN execution_window TEXT;
+
+-- Attempts created outside their project's window, started once it opens
+CREATE TABLE deferred_attempts (
+    workspace_id        BLOB PRIMARY KEY REFERENCES workspaces(id) ON DELETE CASCADE,
+    executor_profile_id TEXT NOT NULL,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
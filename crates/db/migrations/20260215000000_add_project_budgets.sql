@@ -0,0 +1,12 @@
+-- Monthly spending limit per project (JSON); NULL means no limit
+ALTER TABLE projects ADD COLUMN budget TEXT;
+
+-- Budget thresholds already announced, so each fires once per month
+CREATE TABLE project_budget_alerts (
+    project_id BLOB NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    -- Calendar month as YYYY-MM
+    month      TEXT NOT NULL,
+    threshold  INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, month, threshold)
+);
@@ -0,0 +1,12 @@
+-- SSH identity used for fetches and pushes of a repo instead of the user's defaults
+CREATE TABLE repo_ssh_keys (
+    repo_id      BLOB PRIMARY KEY,
+    -- 'agent' or 'key_file'
+    mode         TEXT NOT NULL,
+    key_path     TEXT,
+    -- ssh-agent socket; SSH_AUTH_SOCK when NULL
+    agent_socket TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
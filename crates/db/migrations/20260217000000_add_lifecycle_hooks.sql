@@ -0,0 +1,18 @@
+-- External commands or HTTP endpoints called at attempt lifecycle points
+CREATE TABLE lifecycle_hooks (
+    id           BLOB PRIMARY KEY,
+    -- NULL applies the hook to every project
+    project_id   BLOB,
+    -- 'pre_attempt', 'post_execution', 'pre_push' or 'post_merge'
+    event        TEXT NOT NULL,
+    -- 'command' (run through the shell) or 'http' (POSTed to)
+    kind         TEXT NOT NULL,
+    target       TEXT NOT NULL,
+    timeout_secs INTEGER NOT NULL DEFAULT 30,
+    enabled      INTEGER NOT NULL DEFAULT 1,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_lifecycle_hooks_event ON lifecycle_hooks(event, project_id);
@@ -0,0 +1,15 @@
+-- Rhai scripts run when a project's tasks change or executions finish
+CREATE TABLE project_scripts (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    -- 'task_updated' or 'execution_finished'
+    event      TEXT NOT NULL,
+    source     TEXT NOT NULL,
+    enabled    INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_scripts_project_event ON project_scripts(project_id, event);
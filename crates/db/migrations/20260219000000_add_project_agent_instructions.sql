@@ -0,0 +1,3 @@
+-- Canonical agent instructions rendered into each repo's CLAUDE.md,
+-- AGENTS.md or .cursorrules (JSON); NULL leaves those files alone
+ALTER TABLE projects ADD COLUMN agent_instructions TEXT;
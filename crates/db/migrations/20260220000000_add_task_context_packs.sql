@@ -0,0 +1,11 @@
+-- Files, directories, pull requests and docs given to every attempt of a task
+CREATE TABLE task_context_packs (
+    task_id    BLOB PRIMARY KEY,
+    -- 'prompt' (appended to the initial prompt) or 'file' (written to the workspace)
+    delivery   TEXT NOT NULL DEFAULT 'prompt',
+    -- JSON array of context items
+    items      TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
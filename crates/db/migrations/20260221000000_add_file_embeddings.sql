@@ -0,0 +1,15 @@
+-- Embedding vectors of registered repo files, for suggesting task context
+CREATE TABLE file_embeddings (
+    repo_id      BLOB NOT NULL,
+    -- Path relative to the repo root
+    path         TEXT NOT NULL,
+    -- SHA-256 of the contents the vector was computed from
+    content_hash TEXT NOT NULL,
+    -- Embedder that produced the vector; rows from another one are ignored
+    model        TEXT NOT NULL,
+    -- Little-endian f32 components
+    vector       BLOB NOT NULL,
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (repo_id, path),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Completed tasks indexed for "similar past tasks" lookups
+CREATE TABLE task_knowledge (
+    task_id      BLOB PRIMARY KEY,
+    project_id   BLOB NOT NULL,
+    title        TEXT NOT NULL,
+    description  TEXT,
+    -- Summaries of what the task's attempts changed
+    diff_summary TEXT,
+    pr_url       TEXT,
+    -- Embedder that produced the vector; entries from another one are re-indexed
+    model        TEXT NOT NULL,
+    -- Little-endian f32 components
+    vector       BLOB NOT NULL,
+    -- The task's updated_at when it was indexed, so later edits re-index it
+    completed_at TEXT NOT NULL,
+    indexed_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_knowledge_project_id ON task_knowledge(project_id);
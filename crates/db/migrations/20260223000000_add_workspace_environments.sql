@@ -0,0 +1,19 @@
+-- Tool versions and lockfile hashes present when an attempt's coding agent ran.
+-- A row is only added when the environment differs from the attempt's previous one.
+CREATE TABLE workspace_environments (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    -- The run the snapshot was taken for
+    execution_process_id BLOB,
+    os                   TEXT NOT NULL,
+    arch                 TEXT NOT NULL,
+    -- JSON array of {name, version}
+    tools                TEXT NOT NULL DEFAULT '[]',
+    -- JSON array of {repo, path, sha256}
+    lockfiles            TEXT NOT NULL DEFAULT '[]',
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_workspace_environments_workspace_id ON workspace_environments(workspace_id, created_at);
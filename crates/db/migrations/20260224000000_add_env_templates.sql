@@ -0,0 +1,27 @@
+-- Env files written into every new worktree of a project's repos
+CREATE TABLE project_env_templates (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    -- NULL writes the file into every repo of the workspace
+    repo_id    BLOB,
+    -- Path relative to the repo root, e.g. '.env.local'
+    path       TEXT NOT NULL,
+    -- Text with {{port:NAME}}, {{secret:NAME}} and workspace placeholders
+    content    TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_env_templates_project_id ON project_env_templates(project_id);
+
+-- Ports handed out to workspaces by {{port:NAME}} placeholders
+CREATE TABLE workspace_ports (
+    workspace_id BLOB NOT NULL,
+    name         TEXT NOT NULL,
+    port         INTEGER NOT NULL UNIQUE,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, name),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Directory of a monorepo an attempt is limited to, relative to the repo root.
+-- NULL works on the whole repo.
+ALTER TABLE workspace_repos ADD COLUMN path_scope TEXT;
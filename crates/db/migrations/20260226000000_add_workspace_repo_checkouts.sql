@@ -0,0 +1,19 @@
+-- Extra checkouts of a workspace's repos, e.g. the old and new version of a
+-- repo side by side. Each lives in its own worktree next to the primary one.
+CREATE TABLE workspace_repo_checkouts (
+    id            BLOB PRIMARY KEY,
+    workspace_id  BLOB NOT NULL,
+    repo_id       BLOB NOT NULL,
+    -- Names the checkout's directory and branch within the workspace
+    alias         TEXT NOT NULL,
+    -- Branch the checkout starts from
+    source_branch TEXT NOT NULL,
+    -- Branch created for the checkout
+    branch        TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    UNIQUE (workspace_id, alias)
+);
+
+CREATE INDEX idx_workspace_repo_checkouts_workspace_id ON workspace_repo_checkouts(workspace_id);
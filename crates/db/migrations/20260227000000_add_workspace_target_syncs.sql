@@ -0,0 +1,18 @@
+-- Latest comparison of each attempt repo with its target branch, written by
+-- the periodic target branch sync
+CREATE TABLE workspace_target_syncs (
+    workspace_id  BLOB NOT NULL,
+    repo_id       BLOB NOT NULL,
+    target_branch TEXT NOT NULL,
+    -- Commits on the target branch that the attempt branch lacks
+    behind        INTEGER NOT NULL,
+    -- Set when the attempt fell too far behind and could not be rebased
+    needs_rebase  INTEGER NOT NULL DEFAULT 0,
+    -- Why the last automatic rebase did not happen or failed
+    last_error    TEXT,
+    rebased_at    TEXT,
+    checked_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, repo_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- JSON object saying what happens to an attempt's branch once its PR is merged
+ALTER TABLE projects ADD COLUMN branch_cleanup TEXT;
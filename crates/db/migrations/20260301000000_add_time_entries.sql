@@ -0,0 +1,28 @@
+-- Time spent on tasks: timers started and stopped by users, plus one entry
+-- per finished coding agent run
+CREATE TABLE time_entries (
+    id                   BLOB PRIMARY KEY,
+    task_id              BLOB NOT NULL,
+    -- Who ran a manual timer; NULL for agent runs and in single-user mode
+    user_id              BLOB,
+    -- The coding agent run an automatic entry was derived from
+    execution_process_id BLOB UNIQUE,
+    source               TEXT NOT NULL CHECK (source IN ('manual', 'agent')),
+    note                 TEXT,
+    started_at           TEXT NOT NULL,
+    -- NULL while a manual timer is running
+    ended_at             TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_time_entries_task_id ON time_entries(task_id);
+
+-- Entries for the coding agent runs that finished before time tracking existed
+INSERT INTO time_entries (id, task_id, execution_process_id, source, started_at, ended_at)
+SELECT randomblob(16), w.task_id, ep.id, 'agent', ep.started_at, ep.completed_at
+FROM execution_processes ep
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id
+WHERE ep.run_reason = 'codingagent' AND ep.completed_at IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Size of a task in story points, hours, or both
+CREATE TABLE task_estimates (
+    task_id    BLOB PRIMARY KEY,
+    points     REAL,
+    hours      REAL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK (points IS NOT NULL OR hours IS NOT NULL),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
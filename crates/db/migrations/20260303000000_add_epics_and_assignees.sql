@@ -0,0 +1,33 @@
+-- Lightweight parents grouping a project's tasks into larger initiatives
+CREATE TABLE epics (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    title       TEXT NOT NULL,
+    description TEXT,
+    color       TEXT,
+    -- Order of the epic's swimlane on the board
+    position    INTEGER NOT NULL DEFAULT 0,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_epics_project_id ON epics(project_id);
+
+-- A task belongs to at most one epic
+CREATE TABLE epic_tasks (
+    task_id BLOB PRIMARY KEY,
+    epic_id BLOB NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (epic_id) REFERENCES epics(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_epic_tasks_epic_id ON epic_tasks(epic_id);
+
+-- Local user a task is assigned to
+CREATE TABLE task_assignees (
+    task_id     BLOB PRIMARY KEY,
+    user_id     BLOB NOT NULL,
+    assigned_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
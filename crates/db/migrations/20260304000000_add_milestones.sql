@@ -0,0 +1,55 @@
+-- Every status a task has been in and when it got there, written by triggers
+-- so that all code paths updating tasks are covered
+CREATE TABLE task_status_events (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id    BLOB NOT NULL,
+    status     TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_status_events_task_id ON task_status_events(task_id, changed_at);
+
+CREATE TRIGGER task_status_events_on_insert
+AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO task_status_events (task_id, status) VALUES (NEW.id, NEW.status);
+END;
+
+CREATE TRIGGER task_status_events_on_update
+AFTER UPDATE OF status ON tasks
+WHEN OLD.status IS NOT NEW.status
+BEGIN
+    INSERT INTO task_status_events (task_id, status) VALUES (NEW.id, NEW.status);
+END;
+
+-- Best guess at the history of existing tasks: created as todo, and in their
+-- current status since their last update
+INSERT INTO task_status_events (task_id, status, changed_at)
+SELECT id, 'todo', created_at FROM tasks;
+
+INSERT INTO task_status_events (task_id, status, changed_at)
+SELECT id, status, updated_at FROM tasks WHERE status != 'todo';
+
+CREATE TABLE milestones (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    description TEXT,
+    -- First day of the burndown; defaults to the day the milestone was created
+    start_date  TEXT,
+    due_date    TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_milestones_project_id ON milestones(project_id);
+
+CREATE TABLE milestone_tasks (
+    milestone_id BLOB NOT NULL,
+    task_id      BLOB NOT NULL,
+    PRIMARY KEY (milestone_id, task_id),
+    FOREIGN KEY (milestone_id) REFERENCES milestones(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
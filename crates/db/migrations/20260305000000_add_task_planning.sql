@@ -0,0 +1,10 @@
+-- Priority, due date and preferred executor of a task
+CREATE TABLE task_planning (
+    task_id    BLOB PRIMARY KEY,
+    priority   TEXT CHECK (priority IN ('low', 'medium', 'high', 'urgent')),
+    due_date   TEXT,
+    executor   TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Slack channels and Telegram chats that create tasks in a project
+CREATE TABLE chat_channels (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    platform   TEXT NOT NULL CHECK (platform IN ('slack', 'telegram')),
+    channel_id TEXT NOT NULL,
+    auto_start INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (platform, channel_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
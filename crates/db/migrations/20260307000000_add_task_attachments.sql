@@ -0,0 +1,13 @@
+-- Files kept with a task, such as the voice note it was transcribed from
+CREATE TABLE task_attachments (
+    id            BLOB PRIMARY KEY,
+    task_id       BLOB NOT NULL,
+    file_path     TEXT NOT NULL,
+    original_name TEXT NOT NULL,
+    mime_type     TEXT,
+    size_bytes    INTEGER NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_attachments_task_id ON task_attachments(task_id);
@@ -0,0 +1,27 @@
+-- Title, description and status of a shared task as of the last sync, the
+-- common ancestor for merging concurrent local and remote edits
+CREATE TABLE shared_task_sync_bases (
+    task_id           BLOB PRIMARY KEY,
+    title             TEXT NOT NULL,
+    description       TEXT,
+    status            TEXT NOT NULL,
+    remote_updated_at TEXT,
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Notable events in a task's history, such as conflicting edits to a shared
+-- task and how they were resolved
+CREATE TABLE task_activity (
+    id             BLOB PRIMARY KEY,
+    task_id        BLOB NOT NULL,
+    kind           TEXT NOT NULL CHECK (kind IN ('sync_conflict')),
+    field          TEXT,
+    local_value    TEXT,
+    remote_value   TEXT,
+    resolved_value TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_activity_task_id ON task_activity(task_id);
@@ -0,0 +1,22 @@
+-- Rules that share a project's tasks automatically: every task with a label,
+-- or every task that has reached a status. Exactly one of the two is set.
+CREATE TABLE project_share_rules (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    label_id   BLOB,
+    min_status TEXT CHECK (min_status IN ('todo', 'inprogress', 'inreview', 'done')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES task_labels(id) ON DELETE CASCADE,
+    CHECK ((label_id IS NULL) <> (min_status IS NULL))
+);
+
+CREATE INDEX idx_project_share_rules_project_id ON project_share_rules(project_id);
+
+-- Tasks shared by a rule rather than by hand; only these are unshared again
+-- when they stop matching
+CREATE TABLE rule_shared_tasks (
+    task_id    BLOB PRIMARY KEY,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
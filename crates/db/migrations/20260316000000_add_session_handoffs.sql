@@ -0,0 +1,16 @@
+-- A session continued by a different executor: the new session starts from
+-- the prompts and summaries of the old one plus the changes made so far
+CREATE TABLE session_handoffs (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL,
+    from_session_id BLOB NOT NULL,
+    to_session_id   BLOB NOT NULL,
+    from_executor   TEXT,
+    to_executor     TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_session_handoffs_workspace_id ON session_handoffs(workspace_id);
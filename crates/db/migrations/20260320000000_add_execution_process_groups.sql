@@ -0,0 +1,9 @@
+-- Process group of each running execution, so groups left behind by a server
+-- that exited without stopping them can be killed on the next start
+CREATE TABLE execution_process_groups (
+    execution_process_id BLOB PRIMARY KEY,
+    pgid                 INTEGER NOT NULL,
+    hostname             TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+-- Agent output normalized into typed events as it is ingested, so logs can be
+-- rendered, searched and accounted for without replaying the raw output.
+-- A normalized entry that is updated in place (a tool call finishing) keeps
+-- its row; a finished tool call also gets a tool_result row of its own.
+CREATE TABLE execution_log_events (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    execution_process_id BLOB NOT NULL,
+    entry_index          INTEGER NOT NULL,
+    kind                 TEXT NOT NULL
+                         CHECK (kind IN ('message', 'tool_call', 'tool_result', 'error', 'usage')),
+    role                 TEXT,
+    tool_name            TEXT,
+    status               TEXT,
+    content              TEXT NOT NULL,
+    data                 TEXT,
+    total_tokens         INTEGER,
+    model_context_window INTEGER,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_process_id, entry_index, kind)
+);
+
+CREATE INDEX idx_execution_log_events_kind ON execution_log_events(execution_process_id, kind);
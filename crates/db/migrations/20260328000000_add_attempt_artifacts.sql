@@ -0,0 +1,16 @@
+-- Files produced by an attempt beyond its diff (binaries, coverage reports,
+-- test screenshots), copied under the artifacts directory of the cache
+CREATE TABLE attempt_artifacts (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    name                 TEXT NOT NULL,
+    file_path            TEXT NOT NULL,
+    size_bytes           INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (workspace_id, name)
+);
+
+CREATE INDEX idx_attempt_artifacts_created_at ON attempt_artifacts(created_at);
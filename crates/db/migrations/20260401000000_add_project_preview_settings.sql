@@ -0,0 +1,13 @@
+-- Screenshot of the dev server taken after attempts on UI tasks finish
+CREATE TABLE project_preview_settings (
+    project_id BLOB PRIMARY KEY,
+    -- Shell command writing a screenshot of {url} to {output}
+    command    TEXT NOT NULL,
+    -- Page to capture; detected from the dev server output when unset
+    url        TEXT,
+    -- Only tasks carrying this label are captured automatically
+    label      TEXT NOT NULL DEFAULT 'ui',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
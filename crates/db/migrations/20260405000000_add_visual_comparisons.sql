@@ -0,0 +1,26 @@
+-- Before/after screenshots of configured routes, the "before" side served
+-- from the target branch at baseline_url
+ALTER TABLE project_preview_settings ADD COLUMN visual_routes TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE project_preview_settings ADD COLUMN baseline_url TEXT;
+-- Percentage of changed pixels above which a route is flagged
+ALTER TABLE project_preview_settings ADD COLUMN diff_threshold REAL NOT NULL DEFAULT 0.5;
+ALTER TABLE project_preview_settings ADD COLUMN comment_on_pr INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE visual_comparisons (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    route                TEXT NOT NULL,
+    baseline_artifact_id BLOB,
+    attempt_artifact_id  BLOB,
+    diff_artifact_id     BLOB,
+    changed_percent      REAL NOT NULL,
+    -- changed_percent exceeded the threshold at the time of comparison
+    flagged              INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (baseline_artifact_id) REFERENCES attempt_artifacts(id) ON DELETE SET NULL,
+    FOREIGN KEY (attempt_artifact_id) REFERENCES attempt_artifacts(id) ON DELETE SET NULL,
+    FOREIGN KEY (diff_artifact_id) REFERENCES attempt_artifacts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_visual_comparisons_workspace_id ON visual_comparisons(workspace_id);
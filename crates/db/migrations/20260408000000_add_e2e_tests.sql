@@ -0,0 +1,46 @@
+-- Optional per-project browser test hook run against the attempt's dev
+-- server after each execution
+CREATE TABLE project_e2e_settings (
+    project_id    BLOB PRIMARY KEY,
+    command       TEXT NOT NULL,
+    -- JUnit XML report written by the command, relative to working_dir
+    report_path   TEXT NOT NULL,
+    -- Directory inside the workspace to run in; the only repo when unset
+    working_dir   TEXT,
+    -- Server under test; the attempt's dev server when unset
+    url           TEXT,
+    block_auto_pr INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE TABLE e2e_test_runs (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB,
+    url                  TEXT NOT NULL,
+    passed               INTEGER NOT NULL,
+    failed               INTEGER NOT NULL,
+    skipped              INTEGER NOT NULL,
+    auto_pr_blocked      INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_e2e_test_runs_workspace_id ON e2e_test_runs(workspace_id, created_at);
+
+CREATE TABLE e2e_test_results (
+    id          BLOB PRIMARY KEY,
+    run_id      BLOB NOT NULL,
+    suite       TEXT,
+    name        TEXT NOT NULL,
+    status      TEXT NOT NULL CHECK (status IN ('passed', 'failed', 'skipped')),
+    duration_ms INTEGER,
+    -- First lines of the failure message
+    message     TEXT,
+    FOREIGN KEY (run_id) REFERENCES e2e_test_runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_e2e_test_results_run_id ON e2e_test_results(run_id);
@@ -0,0 +1,15 @@
+-- Child tasks created by fanning a task out across repos or monorepo
+-- packages, one per target
+CREATE TABLE task_fan_out_children (
+    child_task_id  BLOB PRIMARY KEY,
+    parent_task_id BLOB NOT NULL,
+    repo_id        BLOB NOT NULL,
+    -- Package directory the child is limited to, if any
+    path_scope     TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (child_task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_fan_out_children_parent ON task_fan_out_children(parent_task_id);
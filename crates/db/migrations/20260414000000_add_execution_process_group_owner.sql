@@ -0,0 +1,4 @@
+-- Instance that started each process group and the start time of its leader,
+-- so a restart only kills groups of dead instances whose pid was not reused
+ALTER TABLE execution_process_groups ADD COLUMN instance_id BLOB;
+ALTER TABLE execution_process_groups ADD COLUMN start_time INTEGER;
@@ -1,9 +1,17 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use sqlx::{
-    Error, Pool, Sqlite, SqlitePool,
+    Error, Pool, Sqlite,
     migrate::MigrateError,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
+    sqlite::{
+        SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
 };
 use utils::assets::asset_dir;
 
@@ -67,23 +75,125 @@ async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     }
 }
 
+/// SQLite connection tuning, read from the environment:
+///
+/// - `VK_DB_JOURNAL_MODE`: `wal` (default) or `delete`
+/// - `VK_DB_SYNCHRONOUS`: `normal` (default) or `full`
+/// - `VK_DB_BUSY_TIMEOUT_MS`: how long a connection waits on a lock (default 5000)
+/// - `VK_DB_MAX_CONNECTIONS`: size of the read-write pool (default 10)
+/// - `VK_DB_READ_CONNECTIONS`: size of the read-only pool (default 8)
+#[derive(Debug, Clone, Copy)]
+pub struct DbSettings {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    pub busy_timeout: Duration,
+    pub max_connections: u32,
+    pub read_connections: u32,
+}
+
+impl Default for DbSettings {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Normal,
+            busy_timeout: Duration::from_millis(5_000),
+            max_connections: 10,
+            read_connections: 8,
+        }
+    }
+}
+
+impl DbSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_ascii_lowercase())
+        };
+        let number = |name: &str| var(name).and_then(|value| value.parse::<u32>().ok());
+
+        Self {
+            journal_mode: match var("VK_DB_JOURNAL_MODE").as_deref() {
+                Some("delete") => SqliteJournalMode::Delete,
+                _ => defaults.journal_mode,
+            },
+            synchronous: match var("VK_DB_SYNCHRONOUS").as_deref() {
+                Some("full") => SqliteSynchronous::Full,
+                _ => defaults.synchronous,
+            },
+            busy_timeout: number("VK_DB_BUSY_TIMEOUT_MS")
+                .map(|ms| Duration::from_millis(ms.into()))
+                .unwrap_or(defaults.busy_timeout),
+            max_connections: number("VK_DB_MAX_CONNECTIONS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_connections),
+            read_connections: number("VK_DB_READ_CONNECTIONS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.read_connections),
+        }
+    }
+
+    fn write_options(&self, path: &Path) -> SqliteConnectOptions {
+        SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(self.journal_mode)
+            .synchronous(self.synchronous)
+            .busy_timeout(self.busy_timeout)
+    }
+
+    /// The journal mode is left alone: it is a property of the database file
+    /// and read-only connections cannot change it.
+    fn read_options(&self, path: &Path) -> SqliteConnectOptions {
+        SqliteConnectOptions::new()
+            .filename(path)
+            .read_only(true)
+            .synchronous(self.synchronous)
+            .busy_timeout(self.busy_timeout)
+    }
+}
+
+fn database_path() -> PathBuf {
+    asset_dir().join("db.sqlite")
+}
+
+type AfterConnectHook = dyn for<'a> Fn(
+        &'a mut SqliteConnection,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<(), Error>> + Send + 'a>,
+    > + Send
+    + Sync
+    + 'static;
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
+    /// Read-only connections for heavy reads such as the task board, so
+    /// they never queue behind writers for a pooled connection
+    pub read_pool: Pool<Sqlite>,
 }
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
-        let pool = SqlitePool::connect_with(options).await?;
+        Self::open(&database_path(), &DbSettings::from_env(), None).await
+    }
+
+    /// Private in-memory database with every migration applied, for tests.
+    /// A single long-lived connection keeps every query on the same database.
+    pub async fn new_in_memory() -> Result<DBService, Error> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
         run_migrations(&pool).await?;
-        Ok(DBService { pool })
+        Ok(DBService {
+            read_pool: pool.clone(),
+            pool,
+        })
     }
 
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
@@ -96,44 +206,205 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let pool = Self::create_pool(Some(Arc::new(after_connect))).await?;
-        Ok(DBService { pool })
+        let hook: Arc<AfterConnectHook> = Arc::new(after_connect);
+        Self::open(&database_path(), &DbSettings::from_env(), Some(hook)).await
     }
 
-    async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, Error>
-    where
-        F: for<'a> Fn(
-                &'a mut SqliteConnection,
-            ) -> std::pin::Pin<
-                Box<dyn std::future::Future<Output = Result<(), Error>> + Send + 'a>,
-            > + Send
-            + Sync
-            + 'static,
-    {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
-
-        let pool = if let Some(hook) = after_connect {
-            SqlitePoolOptions::new()
-                .after_connect(move |conn, _meta| {
-                    let hook = hook.clone();
-                    Box::pin(async move {
-                        hook(conn).await?;
-                        Ok(())
-                    })
+    /// Open the read-write pool (running migrations) and then the read-only
+    /// pool. Hooks are only installed on read-write connections, the only
+    /// ones that can change rows.
+    async fn open(
+        path: &Path,
+        settings: &DbSettings,
+        after_connect: Option<Arc<AfterConnectHook>>,
+    ) -> Result<DBService, Error> {
+        let mut pool_options = SqlitePoolOptions::new().max_connections(settings.max_connections);
+        if let Some(hook) = after_connect {
+            pool_options = pool_options.after_connect(move |conn, _meta| {
+                let hook = hook.clone();
+                Box::pin(async move {
+                    hook(conn).await?;
+                    Ok(())
                 })
-                .connect_with(options)
-                .await?
-        } else {
-            SqlitePool::connect_with(options).await?
-        };
-
+            });
+        }
+        let pool = pool_options
+            .connect_with(settings.write_options(path))
+            .await?;
         run_migrations(&pool).await?;
-        Ok(pool)
+
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(settings.read_connections)
+            .connect_with(settings.read_options(path))
+            .await?;
+
+        tracing::debug!(?settings, "Opened database at {}", path.display());
+        Ok(DBService { pool, read_pool })
+    }
+
+    /// Problems found by SQLite's integrity check; a healthy database
+    /// reports a single `ok`
+    pub async fn integrity_check(pool: &Pool<Sqlite>) -> Result<Vec<String>, Error> {
+        sqlx::query_scalar!(
+            r#"SELECT integrity_check AS "integrity_check!: String" FROM pragma_integrity_check()"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Rows that model tests build on, each with placeholder values
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+    use sqlx::SqlitePool;
+    use uuid::Uuid;
+
+    use crate::models::{
+        execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    pub async fn project(pool: &SqlitePool) -> Project {
+        Project::create(
+            pool,
+            &CreateProject {
+                name: "Project".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap()
+    }
+
+    pub async fn task(pool: &SqlitePool, project_id: Uuid) -> Task {
+        Task::create(
+            pool,
+            &CreateTask::from_title_description(project_id, "Task".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap()
+    }
+
+    pub async fn workspace(pool: &SqlitePool, task_id: Uuid, branch: &str) -> Workspace {
+        Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: branch.to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task_id,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Running coding agent process in a new session of the workspace
+    pub async fn coding_agent_run(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        executor: Option<&str>,
+    ) -> ExecutionProcess {
+        let session = Session::create(
+            pool,
+            &CreateSession {
+                executor: executor.map(str::to_string),
+            },
+            Uuid::new_v4(),
+            workspace_id,
+        )
+        .await
+        .unwrap();
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "true".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::SetupScript,
+                working_dir: None,
+            }),
+            None,
+        );
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: action,
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            Uuid::new_v4(),
+            &[],
+        )
+        .await
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_writers_and_readers_do_not_hit_locked_errors() {
+        let path =
+            std::env::temp_dir().join(format!("vk-db-stress-{}.sqlite", uuid::Uuid::new_v4()));
+        let db = DBService::open(&path, &DbSettings::default(), None)
+            .await
+            .unwrap();
+        let mut handles = Vec::new();
+        for writer in 0..8 {
+            let pool = db.pool.clone();
+            handles.push(tokio::spawn(async move {
+                for n in 0..50 {
+                    let mut tx = pool.begin().await?;
+                    let payload = format!(r#"{{"writer":{writer},"n":{n}}}"#);
+                    sqlx::query!(
+                        "INSERT INTO jobs (id, kind, payload) VALUES ($1, 'stress', $2)",
+                        uuid::Uuid::new_v4(),
+                        payload
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                }
+                Ok::<_, Error>(())
+            }));
+        }
+        for _ in 0..8 {
+            let read_pool = db.read_pool.clone();
+            handles.push(tokio::spawn(async move {
+                for _ in 0..50 {
+                    sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!: i64" FROM jobs"#)
+                        .fetch_one(&read_pool)
+                        .await?;
+                }
+                Ok(())
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let rows = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM jobs WHERE kind = 'stress'"#
+        )
+        .fetch_one(&db.read_pool)
+        .await
+        .unwrap();
+        assert_eq!(rows, 8 * 50);
+
+        db.pool.close().await;
+        db.read_pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
     }
 }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+
+const MAX_INSTRUCTIONS_CHARS: usize = 100_000;
+
+/// Instruction file a coding agent reads from the repo root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum AgentInstructionFile {
+    ClaudeMd,
+    AgentsMd,
+    CursorRules,
+}
+
+impl AgentInstructionFile {
+    pub const ALL: [AgentInstructionFile; 3] = [
+        AgentInstructionFile::ClaudeMd,
+        AgentInstructionFile::AgentsMd,
+        AgentInstructionFile::CursorRules,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            AgentInstructionFile::ClaudeMd => "CLAUDE.md",
+            AgentInstructionFile::AgentsMd => "AGENTS.md",
+            AgentInstructionFile::CursorRules => ".cursorrules",
+        }
+    }
+}
+
+/// Project-wide agent instructions, rendered into every repo of the project.
+/// `{project_name}`, `{repo_name}` and `{file_name}` are replaced per file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ProjectAgentInstructions {
+    pub content: String,
+    /// Files written in each repo and worktree
+    pub files: Vec<AgentInstructionFile>,
+}
+
+impl Validate for ProjectAgentInstructions {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("content", &self.content, MAX_INSTRUCTIONS_CHARS);
+        if self.files.is_empty() {
+            errors.add("files", "must name at least one file");
+        }
+        errors.into_result()
+    }
+}
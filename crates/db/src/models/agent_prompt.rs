@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use executors::actions::{ExecutorAction, ExecutorActionType};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessRunReason;
+
+/// Fenced block the follow-up editor serializes PR review comments into
+const PR_COMMENT_FENCE: &str = "```gh-comment";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum AgentPromptKind {
+    Initial,
+    FollowUp,
+    PrDescription,
+    AddressComments,
+    Review,
+}
+
+impl AgentPromptKind {
+    /// Kind and text of the prompt `action` sends, if it sends one
+    pub fn classify<'a>(
+        action: &'a ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Option<(Self, &'a str)> {
+        let (kind, prompt) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                (Self::Initial, request.prompt.as_str())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                (Self::FollowUp, request.prompt.as_str())
+            }
+            ExecutorActionType::ReviewRequest(request) => (Self::Review, request.prompt.as_str()),
+            _ => return None,
+        };
+        let kind = if *run_reason == ExecutionProcessRunReason::PrDescriptionGeneration {
+            Self::PrDescription
+        } else if kind == Self::FollowUp && prompt.contains(PR_COMMENT_FENCE) {
+            Self::AddressComments
+        } else {
+            kind
+        };
+        Some((kind, prompt))
+    }
+}
+
+/// A prompt as it was sent to an agent
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct AgentPrompt {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub kind: AgentPromptKind,
+    pub prompt: String,
+    pub replayed_from_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AgentPrompt {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        kind: AgentPromptKind,
+        prompt: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO agent_prompts (id, execution_process_id, kind, prompt)
+               VALUES ($1, $2, $3, $4)"#,
+            Uuid::new_v4(),
+            execution_process_id,
+            kind,
+            prompt
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AgentPrompt,
+            r#"SELECT ap.id as "id!: Uuid",
+                      ap.execution_process_id as "execution_process_id!: Uuid",
+                      ep.session_id as "session_id!: Uuid",
+                      ap.kind as "kind!: AgentPromptKind",
+                      ap.prompt,
+                      ap.replayed_from_id as "replayed_from_id?: Uuid",
+                      ap.created_at as "created_at!: DateTime<Utc>"
+               FROM agent_prompts ap
+               JOIN execution_processes ep ON ep.id = ap.execution_process_id
+               WHERE ap.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Prompts sent in any of the workspace's sessions, oldest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AgentPrompt,
+            r#"SELECT ap.id as "id!: Uuid",
+                      ap.execution_process_id as "execution_process_id!: Uuid",
+                      ep.session_id as "session_id!: Uuid",
+                      ap.kind as "kind!: AgentPromptKind",
+                      ap.prompt,
+                      ap.replayed_from_id as "replayed_from_id?: Uuid",
+                      ap.created_at as "created_at!: DateTime<Utc>"
+               FROM agent_prompts ap
+               JOIN execution_processes ep ON ep.id = ap.execution_process_id
+               JOIN sessions s             ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               ORDER BY ap.created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_kind(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        kind: AgentPromptKind,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE agent_prompts SET kind = $1 WHERE execution_process_id = $2",
+            kind,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Link the prompt of `execution_process_id` to the prompt it replays
+    pub async fn mark_replayed_from(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        replayed_from_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE agent_prompts SET replayed_from_id = $1 WHERE execution_process_id = $2",
+            replayed_from_id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::coding_agent_follow_up::CodingAgentFollowUpRequest, executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+
+    fn follow_up(prompt: &str) -> ExecutorAction {
+        ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: prompt.to_string(),
+                session_id: "agent-session".to_string(),
+                executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                working_dir: None,
+                model: None,
+            }),
+            None,
+        )
+    }
+
+    #[test]
+    fn classifies_follow_ups_by_run_reason_and_content() {
+        let coding = ExecutionProcessRunReason::CodingAgent;
+        assert_eq!(
+            AgentPromptKind::classify(&follow_up("fix the tests"), &coding),
+            Some((AgentPromptKind::FollowUp, "fix the tests"))
+        );
+
+        let comments = "Please address:\n```gh-comment\n{}\n```";
+        assert_eq!(
+            AgentPromptKind::classify(&follow_up(comments), &coding).map(|(kind, _)| kind),
+            Some(AgentPromptKind::AddressComments)
+        );
+
+        let pr = ExecutionProcessRunReason::PrDescriptionGeneration;
+        assert_eq!(
+            AgentPromptKind::classify(&follow_up("describe the PR"), &pr).map(|(kind, _)| kind),
+            Some(AgentPromptKind::PrDescription)
+        );
+    }
+}
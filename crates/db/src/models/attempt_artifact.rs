@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A file an attempt produced, kept after its worktree is gone
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptArtifact {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    /// The run that produced the file, if it was collected from one
+    pub execution_process_id: Option<Uuid>,
+    /// Path inside the attempt's artifacts directory, e.g. `coverage/index.html`
+    pub name: String,
+    /// Path inside the artifacts directory of the cache
+    #[serde(skip)]
+    #[ts(skip)]
+    pub file_path: String,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreateAttemptArtifact {
+    pub execution_process_id: Option<Uuid>,
+    pub name: String,
+    pub file_path: String,
+    pub size_bytes: i64,
+}
+
+impl AttemptArtifact {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptArtifact,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      name,
+                      file_path,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_artifacts WHERE workspace_id = $1 ORDER BY created_at, name"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptArtifact,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      name,
+                      file_path,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_artifacts WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record an artifact, replacing the one of the same name
+    pub async fn upsert(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        data: &CreateAttemptArtifact,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptArtifact,
+            r#"INSERT INTO attempt_artifacts
+                   (id, workspace_id, execution_process_id, name, file_path, size_bytes)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(workspace_id, name) DO UPDATE SET
+                   execution_process_id = excluded.execution_process_id,
+                   file_path = excluded.file_path,
+                   size_bytes = excluded.size_bytes,
+                   created_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         name,
+                         file_path,
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            workspace_id,
+            data.execution_process_id,
+            &data.name,
+            &data.file_path,
+            data.size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM attempt_artifacts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove artifacts recorded more than `days` days ago, returning them so
+    /// their files can be deleted too
+    pub async fn delete_older_than(pool: &SqlitePool, days: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptArtifact,
+            r#"DELETE FROM attempt_artifacts
+               WHERE created_at < datetime('now', '-' || $1 || ' days')
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         name,
+                         file_path,
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            days
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Stored paths of all artifacts, to find files left behind by deleted
+    /// workspaces
+    pub async fn all_file_paths(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT file_path FROM attempt_artifacts")
+            .fetch_all(pool)
+            .await
+    }
+}
@@ -0,0 +1,80 @@
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Coding agent totals for one attempt of an executor comparison
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptRunStats {
+    pub workspace_id: Uuid,
+    /// Wall-clock time spent in coding agent runs; running ones count up to now
+    pub agent_seconds: Option<f64>,
+    /// Sum of the peak token usage reported by each run
+    pub total_tokens: Option<i64>,
+    pub running: bool,
+    pub failed: bool,
+}
+
+impl AttemptRunStats {
+    pub async fn find_by_comparison_group(
+        pool: &SqlitePool,
+        comparison_group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptRunStats,
+            r#"SELECT w.id AS "workspace_id!: Uuid",
+                      SUM((julianday(COALESCE(ep.completed_at, datetime('now', 'subsec')))
+                           - julianday(ep.started_at)) * 86400.0) AS "agent_seconds?: f64",
+                      SUM(u.total_tokens) AS "total_tokens?: i64",
+                      COALESCE(MAX(ep.status = 'running'), 0) AS "running!: bool",
+                      COALESCE(MAX(ep.status IN ('failed', 'killed')), 0) AS "failed!: bool"
+               FROM workspaces w
+               LEFT JOIN sessions s             ON s.workspace_id = w.id
+               LEFT JOIN execution_processes ep ON ep.session_id = s.id
+                                               AND ep.run_reason = 'codingagent'
+                                               AND ep.dropped = FALSE
+               LEFT JOIN execution_process_token_usage u ON u.execution_process_id = ep.id
+               WHERE w.comparison_group_id = $1
+               GROUP BY w.id
+               ORDER BY w.created_at ASC"#,
+            comparison_group_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DBService, fixtures, models::workspace::Workspace};
+
+    #[tokio::test]
+    async fn stats_cover_only_the_group_in_start_order() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project = fixtures::project(pool).await;
+        let task = fixtures::task(pool, project.id).await;
+
+        let group_id = Uuid::new_v4();
+        let mut grouped = Vec::new();
+        for branch in ["vk/a", "vk/b", "vk/c"] {
+            let workspace = fixtures::workspace(pool, task.id, branch).await;
+            if branch != "vk/c" {
+                Workspace::set_comparison_group(pool, workspace.id, group_id)
+                    .await
+                    .unwrap();
+                grouped.push(workspace.id);
+            }
+        }
+
+        let stats = AttemptRunStats::find_by_comparison_group(pool, group_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            stats.iter().map(|s| s.workspace_id).collect::<Vec<_>>(),
+            grouped
+        );
+        assert!(stats.iter().all(|s| {
+            s.agent_seconds.is_none() && s.total_tokens.is_none() && !s.running && !s.failed
+        }));
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use utils::validation::{MAX_NAME_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ChatPlatform {
+    Slack,
+    Telegram,
+}
+
+/// A chat channel whose messages create tasks in a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ChatChannel {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub platform: ChatPlatform,
+    /// Slack channel id (`C0123…`) or Telegram chat id
+    pub channel_id: String,
+    /// Start an attempt with the project's repos as soon as the task is created
+    pub auto_start: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateChatChannel {
+    pub platform: ChatPlatform,
+    pub channel_id: String,
+    #[serde(default)]
+    pub auto_start: bool,
+}
+
+impl Validate for CreateChatChannel {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("channel_id", &self.channel_id, MAX_NAME_CHARS);
+        errors.into_result()
+    }
+}
+
+impl ChatChannel {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ChatChannel,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      platform as "platform!: ChatPlatform",
+                      channel_id,
+                      auto_start as "auto_start!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM chat_channels WHERE project_id = $1 ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ChatChannel,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      platform as "platform!: ChatPlatform",
+                      channel_id,
+                      auto_start as "auto_start!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM chat_channels WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_channel(
+        pool: &SqlitePool,
+        platform: ChatPlatform,
+        channel_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ChatChannel,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      platform as "platform!: ChatPlatform",
+                      channel_id,
+                      auto_start as "auto_start!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM chat_channels WHERE platform = $1 AND channel_id = $2"#,
+            platform,
+            channel_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateChatChannel,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ChatChannel,
+            r#"INSERT INTO chat_channels (id, project_id, platform, channel_id, auto_start)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         platform as "platform!: ChatPlatform",
+                         channel_id,
+                         auto_start as "auto_start!: bool",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            data.platform,
+            data.channel_id.trim(),
+            data.auto_start
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM chat_channels WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -72,6 +72,32 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// Every turn of a session's coding agent runs that were not dropped,
+    /// oldest first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentTurn,
+            r#"SELECT cat.id as "id!: Uuid",
+                      cat.execution_process_id as "execution_process_id!: Uuid",
+                      cat.agent_session_id,
+                      cat.prompt,
+                      cat.summary,
+                      cat.seen as "seen!: bool",
+                      cat.created_at as "created_at!: DateTime<Utc>",
+                      cat.updated_at as "updated_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               WHERE ep.session_id = $1 AND ep.dropped = FALSE
+               ORDER BY cat.created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Create a new coding agent turn
     pub async fn create(
         pool: &SqlitePool,
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Coverage hook configured for a repo
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoCoverageSettings {
+    pub repo_id: Uuid,
+    /// Shell command producing the coverage report
+    pub command: String,
+    /// lcov or cobertura report written by the command, relative to the repo root
+    pub report_path: String,
+    /// Allowed drop in line coverage, in percentage points
+    pub max_drop: f64,
+    /// Block auto-PR for attempts that drop coverage by more than `max_drop`
+    pub block_auto_pr: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertRepoCoverageSettings {
+    pub command: String,
+    pub report_path: String,
+    pub max_drop: Option<f64>,
+    pub block_auto_pr: Option<bool>,
+}
+
+/// Coverage measured for one repo of an attempt
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct CoverageReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub commit_sha: String,
+    #[ts(type = "number")]
+    pub lines_covered: i64,
+    #[ts(type = "number")]
+    pub lines_total: i64,
+    pub baseline_commit_sha: Option<String>,
+    #[ts(type = "number | null")]
+    pub baseline_lines_covered: Option<i64>,
+    #[ts(type = "number | null")]
+    pub baseline_lines_total: Option<i64>,
+    /// Percentage points gained (positive) or lost (negative) vs. the target branch
+    pub delta: Option<f64>,
+    pub auto_pr_blocked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCoverageReport {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub commit_sha: String,
+    pub lines_covered: i64,
+    pub lines_total: i64,
+    pub baseline: Option<CoverageBaseline>,
+    pub delta: Option<f64>,
+    pub auto_pr_blocked: bool,
+}
+
+/// Coverage of a target branch commit, cached by merge base
+#[derive(Debug, Clone, FromRow)]
+pub struct CoverageBaseline {
+    pub repo_id: Uuid,
+    pub commit_sha: String,
+    pub lines_covered: i64,
+    pub lines_total: i64,
+}
+
+impl RepoCoverageSettings {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoCoverageSettings,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      command,
+                      report_path,
+                      max_drop,
+                      block_auto_pr as "block_auto_pr!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_coverage_settings
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertRepoCoverageSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoCoverageSettings,
+            r#"INSERT INTO repo_coverage_settings (repo_id, command, report_path, max_drop, block_auto_pr)
+               VALUES ($1, $2, $3, COALESCE($4, 1.0), COALESCE($5, 1))
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   command = excluded.command,
+                   report_path = excluded.report_path,
+                   max_drop = COALESCE($4, repo_coverage_settings.max_drop),
+                   block_auto_pr = COALESCE($5, repo_coverage_settings.block_auto_pr),
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         command,
+                         report_path,
+                         max_drop,
+                         block_auto_pr as "block_auto_pr!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            &data.command,
+            &data.report_path,
+            data.max_drop,
+            data.block_auto_pr
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, repo_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM repo_coverage_settings WHERE repo_id = $1",
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl CoverageBaseline {
+    pub async fn find(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        commit_sha: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CoverageBaseline,
+            r#"SELECT repo_id as "repo_id!: Uuid", commit_sha, lines_covered, lines_total
+               FROM coverage_baselines
+               WHERE repo_id = $1 AND commit_sha = $2"#,
+            repo_id,
+            commit_sha
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(pool: &SqlitePool, baseline: &CoverageBaseline) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO coverage_baselines (repo_id, commit_sha, lines_covered, lines_total)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(repo_id, commit_sha) DO UPDATE SET
+                   lines_covered = excluded.lines_covered,
+                   lines_total = excluded.lines_total"#,
+            baseline.repo_id,
+            &baseline.commit_sha,
+            baseline.lines_covered,
+            baseline.lines_total
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl CoverageReport {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateCoverageReport,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            CoverageReport,
+            r#"INSERT INTO coverage_reports (
+                   id, workspace_id, repo_id, commit_sha, lines_covered, lines_total,
+                   baseline_commit_sha, baseline_lines_covered, baseline_lines_total, delta,
+                   auto_pr_blocked)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         commit_sha,
+                         lines_covered,
+                         lines_total,
+                         baseline_commit_sha,
+                         baseline_lines_covered,
+                         baseline_lines_total,
+                         delta,
+                         auto_pr_blocked as "auto_pr_blocked!: bool",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            data.workspace_id,
+            data.repo_id,
+            &data.commit_sha,
+            data.lines_covered,
+            data.lines_total,
+            data.baseline.as_ref().map(|b| b.commit_sha.clone()),
+            data.baseline.as_ref().map(|b| b.lines_covered),
+            data.baseline.as_ref().map(|b| b.lines_total),
+            data.delta,
+            data.auto_pr_blocked
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent report per repo of the workspace
+    pub async fn find_latest_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CoverageReport,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      commit_sha,
+                      lines_covered,
+                      lines_total,
+                      baseline_commit_sha,
+                      baseline_lines_covered,
+                      baseline_lines_total,
+                      delta,
+                      auto_pr_blocked as "auto_pr_blocked!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM coverage_reports cr
+               WHERE workspace_id = $1
+                 AND created_at = (SELECT MAX(created_at) FROM coverage_reports
+                                   WHERE workspace_id = cr.workspace_id AND repo_id = cr.repo_id)
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the latest coverage of any repo in the workspace blocks auto-PR
+    pub async fn blocks_auto_pr(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_latest_by_workspace_id(pool, workspace_id)
+            .await?
+            .iter()
+            .any(|report| report.auto_pr_blocked))
+    }
+}
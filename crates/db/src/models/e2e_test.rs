@@ -0,0 +1,335 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+/// Report path used when none is given. Playwright's JUnit reporter is
+/// pointed at the report path through `PLAYWRIGHT_JUNIT_OUTPUT_NAME`.
+pub const DEFAULT_REPORT_PATH: &str = "test-results/junit.xml";
+
+/// Browser test hook configured for a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectE2eSettings {
+    pub project_id: Uuid,
+    /// Shell command running the tests, such as
+    /// `npx playwright test --reporter=junit`
+    pub command: String,
+    /// JUnit XML report written by the command, relative to `working_dir`
+    pub report_path: String,
+    /// Directory inside the workspace to run in; the only repo when unset
+    pub working_dir: Option<String>,
+    /// Server under test; the attempt's dev server when unset
+    pub url: Option<String>,
+    /// Block auto-PR for attempts with failing tests
+    pub block_auto_pr: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectE2eSettings {
+    pub command: String,
+    pub report_path: Option<String>,
+    pub working_dir: Option<String>,
+    pub url: Option<String>,
+    pub block_auto_pr: Option<bool>,
+}
+
+impl Validate for UpsertProjectE2eSettings {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("command", &self.command, MAX_DESCRIPTION_CHARS);
+        for (field, path) in [
+            ("report_path", &self.report_path),
+            ("working_dir", &self.working_dir),
+        ] {
+            if let Some(path) = path {
+                errors.max_chars(field, path, MAX_NAME_CHARS * 5);
+                if path.starts_with('/') || path.split(['/', '\\']).any(|part| part == "..") {
+                    errors.add(field, "must be a relative path inside the workspace");
+                }
+            }
+        }
+        if let Some(url) = self.url.as_deref().filter(|url| !url.trim().is_empty())
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            errors.add("url", "must be an http or https URL");
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum E2eTestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// One run of a project's browser tests against an attempt
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct E2eTestRun {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub url: String,
+    #[ts(type = "number")]
+    pub passed: i64,
+    #[ts(type = "number")]
+    pub failed: i64,
+    #[ts(type = "number")]
+    pub skipped: i64,
+    pub auto_pr_blocked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct E2eTestResult {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub suite: Option<String>,
+    pub name: String,
+    pub status: E2eTestStatus,
+    #[ts(type = "number | null")]
+    pub duration_ms: Option<i64>,
+    /// First lines of the failure message
+    pub message: Option<String>,
+}
+
+/// Latest run of an attempt with its per-test results
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct E2eTestReport {
+    pub run: E2eTestRun,
+    pub results: Vec<E2eTestResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateE2eTestRun {
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub url: String,
+    pub auto_pr_blocked: bool,
+}
+
+/// Test case read from a report, before it is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateE2eTestResult {
+    pub suite: Option<String>,
+    pub name: String,
+    pub status: E2eTestStatus,
+    pub duration_ms: Option<i64>,
+    pub message: Option<String>,
+}
+
+impl ProjectE2eSettings {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectE2eSettings,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      command,
+                      report_path,
+                      working_dir,
+                      url,
+                      block_auto_pr as "block_auto_pr!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_e2e_settings WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectE2eSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectE2eSettings,
+            r#"INSERT INTO project_e2e_settings
+                   (project_id, command, report_path, working_dir, url, block_auto_pr)
+               VALUES ($1, $2, COALESCE($3, $7), $4, $5, COALESCE($6, 1))
+               ON CONFLICT(project_id) DO UPDATE SET
+                   command = excluded.command,
+                   report_path = excluded.report_path,
+                   working_dir = excluded.working_dir,
+                   url = excluded.url,
+                   block_auto_pr = COALESCE($6, project_e2e_settings.block_auto_pr),
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         command,
+                         report_path,
+                         working_dir,
+                         url,
+                         block_auto_pr as "block_auto_pr!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            &data.command,
+            &data.report_path,
+            &data.working_dir,
+            &data.url,
+            data.block_auto_pr,
+            DEFAULT_REPORT_PATH
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_e2e_settings WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl E2eTestRun {
+    /// Store a run with its results; the counts are taken from the results
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateE2eTestRun,
+        results: &[CreateE2eTestResult],
+    ) -> Result<E2eTestReport, sqlx::Error> {
+        let count =
+            |status: E2eTestStatus| results.iter().filter(|r| r.status == status).count() as i64;
+
+        let mut tx = pool.begin().await?;
+        let run = sqlx::query_as!(
+            E2eTestRun,
+            r#"INSERT INTO e2e_test_runs
+                   (id, workspace_id, execution_process_id, url, passed, failed, skipped,
+                    auto_pr_blocked)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         url,
+                         passed,
+                         failed,
+                         skipped,
+                         auto_pr_blocked as "auto_pr_blocked!: bool",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            data.workspace_id,
+            data.execution_process_id,
+            &data.url,
+            count(E2eTestStatus::Passed),
+            count(E2eTestStatus::Failed),
+            count(E2eTestStatus::Skipped),
+            data.auto_pr_blocked
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut stored = Vec::with_capacity(results.len());
+        for result in results {
+            stored.push(
+                sqlx::query_as!(
+                    E2eTestResult,
+                    r#"INSERT INTO e2e_test_results
+                           (id, run_id, suite, name, status, duration_ms, message)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7)
+                       RETURNING id as "id!: Uuid",
+                                 run_id as "run_id!: Uuid",
+                                 suite,
+                                 name,
+                                 status as "status!: E2eTestStatus",
+                                 duration_ms,
+                                 message"#,
+                    Uuid::new_v4(),
+                    run.id,
+                    &result.suite,
+                    &result.name,
+                    result.status,
+                    result.duration_ms,
+                    &result.message
+                )
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+
+        tx.commit().await?;
+        Ok(E2eTestReport {
+            run,
+            results: stored,
+        })
+    }
+
+    pub async fn find_latest_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            E2eTestRun,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      url,
+                      passed,
+                      failed,
+                      skipped,
+                      auto_pr_blocked as "auto_pr_blocked!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM e2e_test_runs
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Latest run of the workspace with its results, failures first
+    pub async fn find_latest_report(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<E2eTestReport>, sqlx::Error> {
+        let Some(run) = Self::find_latest_by_workspace_id(pool, workspace_id).await? else {
+            return Ok(None);
+        };
+        let results = sqlx::query_as!(
+            E2eTestResult,
+            r#"SELECT id as "id!: Uuid",
+                      run_id as "run_id!: Uuid",
+                      suite,
+                      name,
+                      status as "status!: E2eTestStatus",
+                      duration_ms,
+                      message
+               FROM e2e_test_results
+               WHERE run_id = $1
+               ORDER BY CASE status WHEN 'failed' THEN 0 WHEN 'passed' THEN 1 ELSE 2 END,
+                        suite ASC, name ASC"#,
+            run.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Some(E2eTestReport { run, results }))
+    }
+
+    /// Whether the latest browser test run of the workspace blocks auto-PR
+    pub async fn blocks_auto_pr(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_latest_by_workspace_id(pool, workspace_id)
+            .await?
+            .is_some_and(|run| run.auto_pr_blocked))
+    }
+}
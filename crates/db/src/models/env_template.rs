@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+const MAX_TEMPLATE_BYTES: usize = 64 * 1024;
+
+/// Env file rendered into the worktrees of a project's repos
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectEnvTemplate {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// `None` writes the file into every repo of the workspace
+    pub repo_id: Option<Uuid>,
+    /// Path relative to the repo root, e.g. `.env.local`
+    pub path: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectEnvTemplate {
+    pub repo_id: Option<Uuid>,
+    pub path: String,
+    pub content: String,
+}
+
+impl Validate for UpsertProjectEnvTemplate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let path = self.path.trim();
+        if path.is_empty() {
+            errors.add("path", "must not be empty");
+        } else if path.starts_with('/')
+            || path.starts_with('\\')
+            || path
+                .split(['/', '\\'])
+                .any(|part| part == ".." || part == ".git")
+        {
+            errors.add("path", "must be relative to the repo and stay inside it");
+        }
+        if self.content.len() > MAX_TEMPLATE_BYTES {
+            errors.add(
+                "content",
+                format!("must be at most {MAX_TEMPLATE_BYTES} bytes"),
+            );
+        }
+        errors.into_result()
+    }
+}
+
+/// Port handed out to a workspace for a named service
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspacePort {
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub port: u16,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectEnvTemplate {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectEnvTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id?: Uuid",
+                      path,
+                      content,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_env_templates
+             WHERE project_id = $1
+             ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectEnvTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id?: Uuid",
+                      path,
+                      content,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_env_templates WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectEnvTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectEnvTemplate,
+            r#"INSERT INTO project_env_templates (id, project_id, repo_id, path, content)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id as "id!: Uuid",
+                       project_id as "project_id!: Uuid",
+                       repo_id as "repo_id?: Uuid",
+                       path,
+                       content,
+                       created_at as "created_at!: DateTime<Utc>",
+                       updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            data.repo_id,
+            data.path.trim(),
+            &data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpsertProjectEnvTemplate,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectEnvTemplate,
+            r#"UPDATE project_env_templates
+             SET repo_id = $2, path = $3, content = $4, updated_at = datetime('now', 'subsec')
+             WHERE id = $1
+             RETURNING id as "id!: Uuid",
+                       project_id as "project_id!: Uuid",
+                       repo_id as "repo_id?: Uuid",
+                       path,
+                       content,
+                       created_at as "created_at!: DateTime<Utc>",
+                       updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.repo_id,
+            data.path.trim(),
+            &data.content
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_env_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WorkspacePort {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspacePort,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      name,
+                      port as "port!: u16",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_ports
+               WHERE workspace_id = $1
+               ORDER BY name ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every port handed out to any workspace
+    pub async fn find_all_ports(pool: &SqlitePool) -> Result<Vec<u16>, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT port as "port!: u16" FROM workspace_ports"#)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Claim `port` for the workspace; `None` when another workspace holds it
+    /// or the workspace already has a port under `name`
+    pub async fn try_create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        name: &str,
+        port: u16,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspacePort,
+            r#"INSERT INTO workspace_ports (workspace_id, name, port)
+               VALUES ($1, $2, $3)
+               ON CONFLICT DO NOTHING
+               RETURNING workspace_id as "workspace_id!: Uuid",
+                         name,
+                         port as "port!: u16",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            workspace_id,
+            name,
+            port
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_TITLE_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+/// Lightweight parent that groups a project's tasks into a larger initiative
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Epic {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Hex color of the epic's swimlane
+    pub color: Option<String>,
+    /// Order of the epic's swimlane on the board
+    #[ts(type = "number")]
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateEpic {
+    pub title: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateEpic {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    #[ts(type = "number | null")]
+    pub position: Option<i64>,
+}
+
+impl Validate for CreateEpic {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("title", &self.title, MAX_TITLE_CHARS);
+        errors.optional_text(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_CHARS,
+        );
+        if let Some(color) = &self.color {
+            errors.hex_color("color", color);
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateEpic {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.optional_text("title", self.title.as_deref(), MAX_TITLE_CHARS);
+        errors.optional_text(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_CHARS,
+        );
+        if let Some(color) = &self.color {
+            errors.hex_color("color", color);
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(FromRow)]
+struct EpicTaskRow {
+    task_id: Uuid,
+    epic_id: Uuid,
+}
+
+impl Epic {
+    /// Epics of a project in swimlane order
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Epic,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      color,
+                      position,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM epics WHERE project_id = $1 ORDER BY position, created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Epic,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      color,
+                      position,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM epics WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// New epics get the last swimlane
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateEpic,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Epic,
+            r#"INSERT INTO epics (id, project_id, title, description, color, position)
+               VALUES ($1, $2, $3, $4, $5,
+                       (SELECT COALESCE(MAX(position) + 1, 0) FROM epics WHERE project_id = $2))
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         color,
+                         position,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            data.title.trim(),
+            &data.description,
+            &data.color
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateEpic,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Epic,
+            r#"UPDATE epics
+               SET title = COALESCE($2, title),
+                   description = COALESCE($3, description),
+                   color = COALESCE($4, color),
+                   position = COALESCE($5, position),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         color,
+                         position,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.title.as_deref().map(str::trim),
+            &data.description,
+            &data.color,
+            data.position
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Deleting an epic leaves its tasks without one
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM epics WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Move a task into an epic, or out of its epic with `None`
+    pub async fn set_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        epic_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        match epic_id {
+            Some(epic_id) => {
+                sqlx::query!(
+                    r#"INSERT INTO epic_tasks (task_id, epic_id) VALUES ($1, $2)
+                       ON CONFLICT(task_id) DO UPDATE SET epic_id = excluded.epic_id"#,
+                    task_id,
+                    epic_id
+                )
+                .execute(pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!("DELETE FROM epic_tasks WHERE task_id = $1", task_id)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Epic of every task of a project that has one
+    pub async fn find_by_project_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Uuid>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            EpicTaskRow,
+            r#"SELECT et.task_id as "task_id!: Uuid", et.epic_id as "epic_id!: Uuid"
+               FROM epic_tasks et
+               JOIN epics e ON e.id = et.epic_id
+               WHERE e.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.task_id, row.epic_id))
+            .collect())
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A coding agent turn the user interrupted, and where it had got to
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutionInterrupt {
+    pub execution_process_id: Uuid,
+    /// The agent's own session id, so the next turn continues the conversation
+    pub agent_session_id: Option<String>,
+    /// What the agent had said and done in the interrupted turn
+    pub partial_output: Option<String>,
+    pub resumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionInterrupt {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        agent_session_id: Option<&str>,
+        partial_output: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionInterrupt,
+            r#"INSERT INTO execution_interrupts (execution_process_id, agent_session_id, partial_output)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   agent_session_id = excluded.agent_session_id,
+                   partial_output = excluded.partial_output
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         agent_session_id,
+                         partial_output,
+                         resumed_at as "resumed_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            agent_session_id,
+            partial_output
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The interrupt a follow-up in `session_id` should resume from: the
+    /// latest coding agent turn of the session, if it was interrupted and has
+    /// not been resumed yet
+    pub async fn find_pending_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionInterrupt,
+            r#"SELECT ei.execution_process_id as "execution_process_id!: Uuid",
+                      ei.agent_session_id,
+                      ei.partial_output,
+                      ei.resumed_at as "resumed_at?: DateTime<Utc>",
+                      ei.created_at as "created_at!: DateTime<Utc>"
+               FROM execution_interrupts ei
+               WHERE ei.resumed_at IS NULL
+                 AND ei.execution_process_id = (
+                     SELECT ep.id FROM execution_processes ep
+                     WHERE ep.session_id = $1
+                       AND ep.run_reason = 'codingagent'
+                       AND ep.dropped = FALSE
+                     ORDER BY ep.created_at DESC
+                     LIMIT 1
+                 )"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_resumed(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_interrupts SET resumed_at = datetime('now', 'subsec') \
+             WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
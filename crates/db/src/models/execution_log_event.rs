@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ExecutionLogEventKind {
+    /// Text from the user, the agent or the system, including reasoning
+    Message,
+    ToolCall,
+    ToolResult,
+    Error,
+    /// Token usage reported by the agent
+    Usage,
+}
+
+/// One typed event of an execution's normalized output
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutionLogEvent {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub execution_process_id: Uuid,
+    /// Position of the normalized entry the event comes from
+    #[ts(type = "number")]
+    pub entry_index: i64,
+    pub kind: ExecutionLogEventKind,
+    /// `user`, `assistant`, `system` or `thinking`, for messages
+    pub role: Option<String>,
+    pub tool_name: Option<String>,
+    /// Tool call status, or the error type for errors
+    pub status: Option<String>,
+    pub content: String,
+    /// The tool action, or other structured detail of the entry
+    #[ts(type = "unknown")]
+    pub data: Option<Json<serde_json::Value>>,
+    #[ts(type = "number | null")]
+    pub total_tokens: Option<i64>,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An event derived from a normalized entry, as stored and as streamed live
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct ExecutionLogEventData {
+    #[ts(type = "number")]
+    pub entry_index: i64,
+    pub kind: ExecutionLogEventKind,
+    pub role: Option<String>,
+    pub tool_name: Option<String>,
+    pub status: Option<String>,
+    pub content: String,
+    #[ts(type = "unknown")]
+    pub data: Option<serde_json::Value>,
+    #[ts(type = "number | null")]
+    pub total_tokens: Option<i64>,
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+}
+
+impl ExecutionLogEvent {
+    /// Store an event, replacing the one the same entry produced before
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        event: &ExecutionLogEventData,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_log_events
+                   (execution_process_id, entry_index, kind, role, tool_name, status, content,
+                    data, total_tokens, model_context_window)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               ON CONFLICT(execution_process_id, entry_index, kind) DO UPDATE SET
+                   role = excluded.role,
+                   tool_name = excluded.tool_name,
+                   status = excluded.status,
+                   content = excluded.content,
+                   data = excluded.data,
+                   total_tokens = excluded.total_tokens,
+                   model_context_window = excluded.model_context_window,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            event.entry_index,
+            event.kind,
+            &event.role,
+            &event.tool_name,
+            &event.status,
+            &event.content,
+            event.data.as_ref().map(Json),
+            event.total_tokens,
+            event.model_context_window
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Events of an execution in output order, optionally of one kind only
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        kind: Option<ExecutionLogEventKind>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionLogEvent,
+            r#"SELECT id as "id!: i64",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      entry_index,
+                      kind as "kind!: ExecutionLogEventKind",
+                      role,
+                      tool_name,
+                      status,
+                      content,
+                      data as "data?: Json<serde_json::Value>",
+                      total_tokens,
+                      model_context_window,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_log_events
+               WHERE execution_process_id = $1 AND ($2 IS NULL OR kind = $2)
+               ORDER BY entry_index, id"#,
+            execution_process_id,
+            kind
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Events of a workspace's executions whose content or tool name contains
+    /// `query`, ignoring case, most recent first
+    pub async fn search(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        query: &str,
+        kind: Option<ExecutionLogEventKind>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionLogEvent,
+            r#"SELECT e.id as "id!: i64",
+                      e.execution_process_id as "execution_process_id!: Uuid",
+                      e.entry_index,
+                      e.kind as "kind!: ExecutionLogEventKind",
+                      e.role,
+                      e.tool_name,
+                      e.status,
+                      e.content,
+                      e.data as "data?: Json<serde_json::Value>",
+                      e.total_tokens,
+                      e.model_context_window,
+                      e.created_at as "created_at!: DateTime<Utc>",
+                      e.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_log_events e
+               JOIN execution_processes ep ON ep.id = e.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+                 AND (instr(lower(e.content), lower($2)) > 0
+                      OR instr(lower(COALESCE(e.tool_name, '')), lower($2)) > 0)
+                 AND ($3 IS NULL OR e.kind = $3)
+               ORDER BY e.updated_at DESC, e.id DESC
+               LIMIT $4"#,
+            workspace_id,
+            query,
+            kind,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
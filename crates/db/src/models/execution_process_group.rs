@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// The OS process group an execution process runs in, kept while it runs
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExecutionProcessGroup {
+    pub execution_process_id: Uuid,
+    pub pgid: i64,
+    pub hostname: String,
+    /// Server instance that started the group
+    pub instance_id: Option<Uuid>,
+    /// Start time of the group leader, in clock ticks since boot, used to tell
+    /// it apart from a later process that reused its pid
+    pub start_time: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateExecutionProcessGroup {
+    pub execution_process_id: Uuid,
+    pub pgid: i64,
+    pub hostname: String,
+    pub instance_id: Uuid,
+    pub start_time: Option<i64>,
+}
+
+impl ExecutionProcessGroup {
+    pub async fn record(
+        pool: &SqlitePool,
+        data: &CreateExecutionProcessGroup,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_process_groups
+                   (execution_process_id, pgid, hostname, instance_id, start_time)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   pgid = excluded.pgid,
+                   hostname = excluded.hostname,
+                   instance_id = excluded.instance_id,
+                   start_time = excluded.start_time"#,
+            data.execution_process_id,
+            data.pgid,
+            &data.hostname,
+            data.instance_id,
+            data.start_time
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every group recorded on `hostname`, by any instance
+    pub async fn find_by_hostname(
+        pool: &SqlitePool,
+        hostname: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessGroup,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      pgid,
+                      hostname,
+                      instance_id as "instance_id?: Uuid",
+                      start_time,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_groups
+               WHERE hostname = $1"#,
+            hostname
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, execution_process_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM execution_process_groups WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -156,4 +156,25 @@ impl ExecutionProcessRepoState {
         .fetch_all(pool)
         .await
     }
+
+    /// Workspace in which a run left HEAD at the commit starting with
+    /// `sha_prefix` (lowercase hex)
+    pub async fn find_workspace_id_by_commit(
+        pool: &SqlitePool,
+        sha_prefix: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT s.workspace_id as "workspace_id!: Uuid"
+               FROM execution_process_repo_states eprs
+               JOIN execution_processes ep ON ep.id = eprs.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE substr(lower(eprs.after_head_commit), 1, length($1)) = $1
+                  OR substr(lower(eprs.merge_commit), 1, length($1)) = $1
+               ORDER BY eprs.created_at DESC
+               LIMIT 1"#,
+            sha_prefix
+        )
+        .fetch_optional(pool)
+        .await
+    }
 }
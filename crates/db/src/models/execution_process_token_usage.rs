@@ -0,0 +1,30 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Token usage reported by an execution process, keeping the highest value seen
+pub struct ExecutionProcessTokenUsage;
+
+impl ExecutionProcessTokenUsage {
+    pub async fn record(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        total_tokens: i64,
+        model_context_window: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_process_token_usage
+                   (execution_process_id, total_tokens, model_context_window)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   total_tokens = MAX(total_tokens, excluded.total_tokens),
+                   model_context_window = COALESCE(excluded.model_context_window, model_context_window),
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            total_tokens,
+            model_context_window
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
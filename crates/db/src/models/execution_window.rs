@@ -0,0 +1,207 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Hours in which a project's attempts may start, in the server's local time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ExecutionWindow {
+    /// Opening time as `HH:MM`
+    pub start: String,
+    /// Closing time as `HH:MM`; before `start` means the window runs overnight
+    pub end: String,
+    /// ISO weekdays (1 = Monday) on which the window opens; empty means every day
+    #[serde(default)]
+    pub days: Vec<u8>,
+}
+
+impl Validate for ExecutionWindow {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        for (field, value) in [("start", &self.start), ("end", &self.end)] {
+            if NaiveTime::parse_from_str(value, TIME_FORMAT).is_err() {
+                errors.add(field, "must be a time as HH:MM");
+            }
+        }
+        if self.days.iter().any(|day| !(1..=7).contains(day)) {
+            errors.add("days", "must be weekdays from 1 (Monday) to 7 (Sunday)");
+        }
+        errors.into_result()
+    }
+}
+
+impl ExecutionWindow {
+    fn times(&self) -> Option<(NaiveTime, NaiveTime)> {
+        Some((
+            NaiveTime::parse_from_str(&self.start, TIME_FORMAT).ok()?,
+            NaiveTime::parse_from_str(&self.end, TIME_FORMAT).ok()?,
+        ))
+    }
+
+    fn opens_on(&self, date: chrono::NaiveDate) -> bool {
+        self.days.is_empty()
+            || self
+                .days
+                .contains(&(date.weekday().number_from_monday() as u8))
+    }
+
+    /// Whether an attempt may start at local time `at`. An unparseable
+    /// window never blocks anything.
+    pub fn is_open_at(&self, at: NaiveDateTime) -> bool {
+        let Some((start, end)) = self.times() else {
+            return true;
+        };
+        let (date, time) = (at.date(), at.time());
+        if start == end {
+            self.opens_on(date)
+        } else if start < end {
+            self.opens_on(date) && start <= time && time < end
+        } else {
+            // Overnight: open from `start` today, or until `end` if it
+            // opened yesterday
+            (time >= start && self.opens_on(date))
+                || (time < end && date.pred_opt().is_some_and(|day| self.opens_on(day)))
+        }
+    }
+
+    /// The first local time at or after `after` when the window is open
+    pub fn next_open(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.is_open_at(after) {
+            return Some(after);
+        }
+        let (start, _) = self.times()?;
+        (0..=7)
+            .map(|offset| after.date() + Duration::days(offset))
+            .filter(|date| self.opens_on(*date))
+            .map(|date| date.and_time(start))
+            .find(|opens| *opens > after)
+    }
+}
+
+/// An attempt waiting for its project's execution window to open
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct DeferredAttempt {
+    pub workspace_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub project_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeferredAttempt {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO deferred_attempts (workspace_id, executor_profile_id) VALUES ($1, $2)",
+            workspace_id,
+            Json(executor_profile_id)
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DeferredAttempt,
+            r#"SELECT da.workspace_id as "workspace_id!: Uuid",
+                      da.executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      t.project_id as "project_id!: Uuid",
+                      da.created_at as "created_at!: DateTime<Utc>"
+               FROM deferred_attempts da
+               JOIN workspaces w ON w.id = da.workspace_id
+               JOIN tasks t      ON t.id = w.task_id
+               ORDER BY da.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DeferredAttempt,
+            r#"SELECT da.workspace_id as "workspace_id!: Uuid",
+                      da.executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      t.project_id as "project_id!: Uuid",
+                      da.created_at as "created_at!: DateTime<Utc>"
+               FROM deferred_attempts da
+               JOIN workspaces w ON w.id = da.workspace_id
+               JOIN tasks t      ON t.id = w.task_id
+               WHERE da.workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove the deferral; `false` if it was already taken, so only one
+    /// caller starts the attempt
+    pub async fn take(pool: &SqlitePool, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM deferred_attempts WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        // 2026-02-02 is a Monday
+        NaiveDate::from_ymd_opt(2026, 2, day)
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, TIME_FORMAT).unwrap())
+    }
+
+    #[test]
+    fn overnight_window_opens_on_listed_days_and_closes_the_next_morning() {
+        let window = ExecutionWindow {
+            start: "22:00".to_string(),
+            end: "06:00".to_string(),
+            days: vec![1, 2, 3, 4, 5],
+        };
+        assert!(window.validate().is_ok());
+
+        assert!(!window.is_open_at(at(2, "21:59")));
+        assert!(window.is_open_at(at(2, "23:30")));
+        // Tuesday morning belongs to Monday's window
+        assert!(window.is_open_at(at(3, "05:00")));
+        // Friday night's window runs into Saturday, none opens on Saturday
+        assert!(window.is_open_at(at(7, "02:00")));
+        assert!(!window.is_open_at(at(7, "23:00")));
+
+        assert_eq!(window.next_open(at(2, "12:00")), Some(at(2, "22:00")));
+        assert_eq!(window.next_open(at(7, "12:00")), Some(at(9, "22:00")));
+        assert_eq!(window.next_open(at(3, "01:00")), Some(at(3, "01:00")));
+    }
+
+    #[test]
+    fn rejects_malformed_windows() {
+        let window = ExecutionWindow {
+            start: "9am".to_string(),
+            end: "17:00".to_string(),
+            days: vec![0],
+        };
+        assert!(window.validate().is_err());
+    }
+}
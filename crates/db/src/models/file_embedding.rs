@@ -0,0 +1,111 @@
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Embedding vector of one file in a registered repo
+#[derive(Debug, Clone, FromRow)]
+pub struct FileEmbedding {
+    pub repo_id: Uuid,
+    pub path: String,
+    pub content_hash: String,
+    pub model: String,
+    pub vector: Vec<u8>,
+}
+
+impl FileEmbedding {
+    pub fn encode(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    }
+
+    pub fn decode(&self) -> Vec<f32> {
+        Self::decode_vector(&self.vector)
+    }
+
+    /// Embeddings of a repo produced by `model`
+    pub async fn find_by_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        model: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FileEmbedding,
+            r#"SELECT repo_id as "repo_id!: Uuid", path, content_hash, model, vector
+               FROM file_embeddings
+               WHERE repo_id = $1 AND model = $2"#,
+            repo_id,
+            model
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Content hash of every indexed file of a repo, keyed by path
+    pub async fn find_hashes(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        model: &str,
+    ) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            "SELECT path, content_hash FROM file_embeddings WHERE repo_id = $1 AND model = $2",
+            repo_id,
+            model
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.path, row.content_hash))
+            .collect())
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        path: &str,
+        content_hash: &str,
+        model: &str,
+        vector: &[f32],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO file_embeddings (repo_id, path, content_hash, model, vector)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(repo_id, path) DO UPDATE SET
+                   content_hash = excluded.content_hash,
+                   model = excluded.model,
+                   vector = excluded.vector,
+                   updated_at = datetime('now', 'subsec')"#,
+            repo_id,
+            path,
+            content_hash,
+            model,
+            Self::encode(vector)
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, repo_id: Uuid, path: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM file_embeddings WHERE repo_id = $1 AND path = $2",
+            repo_id,
+            path
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_repo(pool: &SqlitePool, repo_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM file_embeddings WHERE repo_id = $1", repo_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -179,10 +179,11 @@ impl Image {
 impl TaskImage {
     /// Associate multiple images with a task, skipping duplicates.
     pub async fn associate_many_dedup(
-        pool: &SqlitePool,
+        conn: impl Acquire<'_, Database = Sqlite>,
         task_id: Uuid,
         image_ids: &[Uuid],
     ) -> Result<(), sqlx::Error> {
+        let mut conn = conn.acquire().await?;
         for &image_id in image_ids {
             let id = Uuid::new_v4();
             sqlx::query!(
@@ -195,12 +196,32 @@ impl TaskImage {
                 task_id,
                 image_id
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
         Ok(())
     }
 
+    /// Move a task's images to another task, dropping ones it already has.
+    pub async fn reassign_task(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        from_task_id: Uuid,
+        to_task_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+        sqlx::query!(
+            "UPDATE OR IGNORE task_images SET task_id = $2 WHERE task_id = $1",
+            from_task_id,
+            to_task_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!("DELETE FROM task_images WHERE task_id = $1", from_task_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
     pub async fn delete_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!(r#"DELETE FROM task_images WHERE task_id = $1"#, task_id)
             .execute(pool)
@@ -208,6 +229,22 @@ impl TaskImage {
         Ok(())
     }
 
+    /// Projects of the tasks an image is attached to
+    pub async fn find_project_ids_by_image_id(
+        pool: &SqlitePool,
+        image_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT t.project_id as "project_id!: Uuid"
+               FROM task_images ti
+               JOIN tasks t ON t.id = ti.task_id
+               WHERE ti.image_id = $1"#,
+            image_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Check if an image is associated with a specific task.
     pub async fn is_associated(
         pool: &SqlitePool,
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A running server instance registered in the database
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Instance {
+    pub id: Uuid,
+    pub pid: i64,
+    pub hostname: String,
+    pub workspace_dir: String,
+    #[ts(type = "Date")]
+    pub started_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl Instance {
+    /// Register (or re-register) an instance with a fresh heartbeat
+    pub async fn register(
+        pool: &SqlitePool,
+        id: Uuid,
+        pid: i64,
+        hostname: &str,
+        workspace_dir: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query_as!(
+            Instance,
+            r#"INSERT INTO instances (id, pid, hostname, workspace_dir, started_at, heartbeat_at)
+               VALUES ($1, $2, $3, $4, $5, $5)
+               ON CONFLICT(id) DO UPDATE SET
+                   pid = excluded.pid,
+                   hostname = excluded.hostname,
+                   workspace_dir = excluded.workspace_dir,
+                   heartbeat_at = excluded.heartbeat_at
+               RETURNING id as "id!: Uuid",
+                         pid,
+                         hostname,
+                         workspace_dir,
+                         started_at as "started_at!: DateTime<Utc>",
+                         heartbeat_at as "heartbeat_at!: DateTime<Utc>""#,
+            id,
+            pid,
+            hostname,
+            workspace_dir,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn heartbeat(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE instances SET heartbeat_at = $2 WHERE id = $1",
+            id,
+            Utc::now()
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn deregister(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM instances WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Instances whose heartbeat is newer than `stale_after`
+    pub async fn find_live(
+        pool: &SqlitePool,
+        stale_after: Duration,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Instance,
+            r#"SELECT id as "id!: Uuid",
+                      pid,
+                      hostname,
+                      workspace_dir,
+                      started_at as "started_at!: DateTime<Utc>",
+                      heartbeat_at as "heartbeat_at!: DateTime<Utc>"
+               FROM instances
+               WHERE heartbeat_at >= $1
+               ORDER BY started_at ASC"#,
+            Utc::now() - stale_after
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Remove instances that stopped heartbeating (crashed or killed)
+    pub async fn delete_stale(
+        pool: &SqlitePool,
+        stale_after: Duration,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM instances WHERE heartbeat_at < $1",
+            Utc::now() - stale_after
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
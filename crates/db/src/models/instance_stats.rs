@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Success counts for one executor over the stats period
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutorStats {
+    pub executor: String,
+    #[ts(type = "number")]
+    pub runs: i64,
+    #[ts(type = "number")]
+    pub completed: i64,
+    #[ts(type = "number")]
+    pub failed: i64,
+    #[ts(type = "number")]
+    pub killed: i64,
+    /// Completed runs over finished runs, `None` while nothing has finished
+    pub success_rate: Option<f64>,
+}
+
+/// Counters aggregated from this instance's own database
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct InstanceStats {
+    pub since: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub attempts_run: i64,
+    #[ts(type = "number")]
+    pub coding_agent_runs: i64,
+    /// Peak context tokens per coding agent run, summed
+    #[ts(type = "number")]
+    pub tokens_used: i64,
+    /// Repo states where a run moved HEAD, i.e. the agent produced commits
+    #[ts(type = "number")]
+    pub runs_with_commits: i64,
+    #[ts(type = "number")]
+    pub prs_opened: i64,
+    #[ts(type = "number")]
+    pub prs_merged: i64,
+    #[ts(type = "number")]
+    pub direct_merges: i64,
+    pub executors: Vec<ExecutorStats>,
+}
+
+impl InstanceStats {
+    /// Aggregate counters for activity since `since`, optionally limited to
+    /// the projects owned by `owner_id`
+    pub async fn collect(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+        owner_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        // Every query restricts `t` to projects owned by `$2` when it is set
+        // (multi-user mode)
+        let attempts_run = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               WHERE datetime(w.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let coding_agent_runs = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ep.run_reason = 'codingagent'
+                 AND datetime(ep.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let tokens_used = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(u.total_tokens), 0) AS "total!: i64" FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE datetime(ep.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let runs_with_commits = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM execution_process_repo_states eprs
+               JOIN execution_processes ep ON ep.id = eprs.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ep.run_reason = 'codingagent'
+                 AND eprs.after_head_commit IS NOT NULL
+                 AND eprs.after_head_commit IS NOT eprs.before_head_commit
+                 AND datetime(ep.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let prs_opened = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE m.merge_type = 'pr'
+                 AND datetime(m.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let prs_merged = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE m.merge_type = 'pr' AND m.pr_status = 'merged'
+                 AND datetime(COALESCE(m.pr_merged_at, m.created_at)) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let direct_merges = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE m.merge_type = 'direct'
+                 AND datetime(m.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))"#,
+            since,
+            owner_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let executors = sqlx::query_as!(
+            ExecutorStats,
+            r#"SELECT COALESCE(s.executor, 'UNKNOWN') AS "executor!: String",
+                      COUNT(*) AS "runs!: i64",
+                      SUM(ep.status = 'completed') AS "completed!: i64",
+                      SUM(ep.status = 'failed') AS "failed!: i64",
+                      SUM(ep.status = 'killed') AS "killed!: i64",
+                      CAST(SUM(ep.status = 'completed') AS REAL)
+                          / NULLIF(SUM(ep.status != 'running'), 0) AS "success_rate?: f64"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ep.run_reason = 'codingagent'
+                 AND datetime(ep.created_at) >= datetime($1)
+                 AND ($2 IS NULL OR t.project_id IN (SELECT project_id FROM project_owners WHERE user_id = $2))
+               GROUP BY COALESCE(s.executor, 'UNKNOWN')
+               ORDER BY COUNT(*) DESC"#,
+            since,
+            owner_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            since,
+            attempts_run,
+            coding_agent_runs,
+            tokens_used,
+            runs_with_commits,
+            prs_opened,
+            prs_merged,
+            direct_merges,
+            executors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DBService, fixtures,
+        models::{
+            execution_process::{ExecutionProcess, ExecutionProcessStatus},
+            execution_process_token_usage::ExecutionProcessTokenUsage,
+        },
+    };
+
+    #[tokio::test]
+    async fn counts_agent_runs_tokens_and_success_rate() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let since = Utc::now() - chrono::Duration::days(1);
+        let project = fixtures::project(pool).await;
+        let task = fixtures::task(pool, project.id).await;
+        let workspace = fixtures::workspace(pool, task.id, "vk/stats").await;
+
+        for (status, tokens) in [
+            (ExecutionProcessStatus::Completed, 100),
+            (ExecutionProcessStatus::Failed, 50),
+        ] {
+            let process = fixtures::coding_agent_run(pool, workspace.id, Some("CLAUDE_CODE")).await;
+            // Only the peak usage of a run counts
+            ExecutionProcessTokenUsage::record(pool, process.id, tokens, None)
+                .await
+                .unwrap();
+            ExecutionProcessTokenUsage::record(pool, process.id, tokens / 2, None)
+                .await
+                .unwrap();
+            ExecutionProcess::update_completion(pool, process.id, status, Some(0))
+                .await
+                .unwrap();
+        }
+
+        let stats = InstanceStats::collect(pool, since, None).await.unwrap();
+        assert_eq!(stats.attempts_run, 1);
+        assert_eq!(stats.coding_agent_runs, 2);
+        assert_eq!(stats.tokens_used, 150);
+        assert_eq!(stats.executors.len(), 1);
+        let executor = &stats.executors[0];
+        assert_eq!(executor.executor, "CLAUDE_CODE");
+        assert_eq!((executor.completed, executor.failed), (1, 1));
+        assert_eq!(executor.success_rate, Some(0.5));
+
+        // Other users only see their own projects
+        let stranger = InstanceStats::collect(pool, since, Some(Uuid::new_v4()))
+            .await
+            .unwrap();
+        assert_eq!(stranger.attempts_run, 0);
+        assert!(stranger.executors.is_empty());
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Dead,
+}
+
+/// Background job persisted so it survives a restart
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    #[ts(type = "unknown")]
+    pub payload: Json<serde_json::Value>,
+    /// `dead` jobs ran out of attempts and stay until retried by hand
+    pub status: JobStatus,
+    #[ts(type = "number")]
+    pub attempts: i64,
+    #[ts(type = "number")]
+    pub max_attempts: i64,
+    pub last_error: Option<String>,
+    /// Earliest time the job may run; pushed back between retries
+    pub run_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Job {
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        kind: &str,
+        payload: &serde_json::Value,
+        max_attempts: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"INSERT INTO jobs (id, kind, payload, max_attempts)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         kind,
+                         payload as "payload!: Json<serde_json::Value>",
+                         status as "status!: JobStatus",
+                         attempts,
+                         max_attempts,
+                         last_error,
+                         run_at as "run_at!: DateTime<Utc>",
+                         started_at as "started_at?: DateTime<Utc>",
+                         finished_at as "finished_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            kind,
+            Json(payload),
+            max_attempts
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"SELECT id as "id!: Uuid",
+                      kind,
+                      payload as "payload!: Json<serde_json::Value>",
+                      status as "status!: JobStatus",
+                      attempts,
+                      max_attempts,
+                      last_error,
+                      run_at as "run_at!: DateTime<Utc>",
+                      started_at as "started_at?: DateTime<Utc>",
+                      finished_at as "finished_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM jobs WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent jobs first, optionally only those in `status`
+    pub async fn list(
+        pool: &SqlitePool,
+        status: Option<JobStatus>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"SELECT id as "id!: Uuid",
+                      kind,
+                      payload as "payload!: Json<serde_json::Value>",
+                      status as "status!: JobStatus",
+                      attempts,
+                      max_attempts,
+                      last_error,
+                      run_at as "run_at!: DateTime<Utc>",
+                      started_at as "started_at?: DateTime<Utc>",
+                      finished_at as "finished_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM jobs
+               WHERE $1 IS NULL OR status = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            status,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Atomically mark the oldest due job as running and return it
+    pub async fn claim_next(pool: &SqlitePool) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"UPDATE jobs
+               SET status = 'running',
+                   attempts = attempts + 1,
+                   started_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = (
+                   SELECT id FROM jobs
+                   WHERE status = 'pending' AND run_at <= datetime('now', 'subsec')
+                   ORDER BY run_at ASC, created_at ASC
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid",
+                         kind,
+                         payload as "payload!: Json<serde_json::Value>",
+                         status as "status!: JobStatus",
+                         attempts,
+                         max_attempts,
+                         last_error,
+                         run_at as "run_at!: DateTime<Utc>",
+                         started_at as "started_at?: DateTime<Utc>",
+                         finished_at as "finished_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_completed(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'completed',
+                   last_error = NULL,
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Put a failed job back in the queue to run again after `delay_secs`
+    pub async fn schedule_retry(
+        pool: &SqlitePool,
+        id: Uuid,
+        error: &str,
+        delay_secs: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'pending',
+                   last_error = $2,
+                   run_at = datetime('now', 'subsec', '+' || $3 || ' seconds'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error,
+            delay_secs
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move a job that will not be retried to the dead-letter state
+    pub async fn mark_dead(pool: &SqlitePool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'dead',
+                   last_error = $2,
+                   finished_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Give a dead job a fresh set of attempts
+    pub async fn revive(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Job,
+            r#"UPDATE jobs
+               SET status = 'pending',
+                   attempts = 0,
+                   run_at = datetime('now', 'subsec'),
+                   finished_at = NULL,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'dead'
+               RETURNING id as "id!: Uuid",
+                         kind,
+                         payload as "payload!: Json<serde_json::Value>",
+                         status as "status!: JobStatus",
+                         attempts,
+                         max_attempts,
+                         last_error,
+                         run_at as "run_at!: DateTime<Utc>",
+                         started_at as "started_at?: DateTime<Utc>",
+                         finished_at as "finished_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Requeue jobs left running by a previous process that exited mid-job
+    pub async fn requeue_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE jobs
+               SET status = 'pending', updated_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop completed jobs finished more than `days` ago
+    pub async fn prune_completed(pool: &SqlitePool, days: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM jobs
+               WHERE status = 'completed'
+                 AND finished_at < datetime('now', '-' || $1 || ' days')"#,
+            days
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
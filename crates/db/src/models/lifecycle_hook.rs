@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Lifecycle point a hook runs at. `pre_*` hooks can veto the action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    PreAttempt,
+    PostExecution,
+    PrePush,
+    PostMerge,
+    /// An attempt fell behind its target branch and was not rebased
+    NeedsRebase,
+}
+
+impl LifecycleEvent {
+    pub fn can_veto(self) -> bool {
+        matches!(self, LifecycleEvent::PreAttempt | LifecycleEvent::PrePush)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LifecycleEvent::PreAttempt => "pre_attempt",
+            LifecycleEvent::PostExecution => "post_execution",
+            LifecycleEvent::PrePush => "pre_push",
+            LifecycleEvent::PostMerge => "post_merge",
+            LifecycleEvent::NeedsRebase => "needs_rebase",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum HookKind {
+    Command,
+    Http,
+}
+
+/// Command or HTTP endpoint receiving a JSON payload at a lifecycle point
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct LifecycleHook {
+    pub id: Uuid,
+    /// `None` runs the hook for every project
+    pub project_id: Option<Uuid>,
+    pub event: LifecycleEvent,
+    pub kind: HookKind,
+    /// Shell command for `command` hooks, URL for `http` hooks
+    pub target: String,
+    #[ts(type = "number")]
+    pub timeout_secs: i64,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateLifecycleHook {
+    pub project_id: Option<Uuid>,
+    pub event: LifecycleEvent,
+    pub kind: HookKind,
+    pub target: String,
+    #[ts(type = "number | null")]
+    pub timeout_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateLifecycleHook {
+    pub target: Option<String>,
+    #[ts(type = "number | null")]
+    pub timeout_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+impl LifecycleHook {
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LifecycleHook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      event as "event!: LifecycleEvent",
+                      kind as "kind!: HookKind",
+                      target,
+                      timeout_secs,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM lifecycle_hooks
+               WHERE $1 IS NULL OR project_id IS NULL OR project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LifecycleHook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      event as "event!: LifecycleEvent",
+                      kind as "kind!: HookKind",
+                      target,
+                      timeout_secs,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM lifecycle_hooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled hooks for `event` that apply to `project_id`, global ones first
+    pub async fn find_for_event(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        event: LifecycleEvent,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LifecycleHook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      event as "event!: LifecycleEvent",
+                      kind as "kind!: HookKind",
+                      target,
+                      timeout_secs,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM lifecycle_hooks
+               WHERE event = $1
+                 AND enabled = 1
+                 AND (project_id IS NULL OR project_id = $2)
+               ORDER BY project_id IS NOT NULL, created_at ASC"#,
+            event,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateLifecycleHook,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            LifecycleHook,
+            r#"INSERT INTO lifecycle_hooks (id, project_id, event, kind, target, timeout_secs, enabled)
+               VALUES ($1, $2, $3, $4, $5, COALESCE($6, 30), COALESCE($7, 1))
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id?: Uuid",
+                         event as "event!: LifecycleEvent",
+                         kind as "kind!: HookKind",
+                         target,
+                         timeout_secs,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            data.project_id,
+            data.event,
+            data.kind,
+            &data.target,
+            data.timeout_secs,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateLifecycleHook,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LifecycleHook,
+            r#"UPDATE lifecycle_hooks
+               SET target = COALESCE($2, target),
+                   timeout_secs = COALESCE($3, timeout_secs),
+                   enabled = COALESCE($4, enabled),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id?: Uuid",
+                         event as "event!: LifecycleEvent",
+                         kind as "kind!: HookKind",
+                         target,
+                         timeout_secs,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            &data.target,
+            data.timeout_secs,
+            data.enabled
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM lifecycle_hooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -354,6 +354,57 @@ impl Merge {
             .filter_map(|row| row.pr_status.map(|status| (row.workspace_id, status)))
             .collect())
     }
+
+    /// Workspace whose direct merge or merged PR produced the commit starting
+    /// with `sha_prefix` (lowercase hex)
+    pub async fn find_workspace_id_by_commit(
+        pool: &SqlitePool,
+        sha_prefix: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid" FROM merges
+               WHERE substr(lower(merge_commit), 1, length($1)) = $1
+                  OR substr(lower(pr_merge_commit_sha), 1, length($1)) = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            sha_prefix
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Workspace a pull request was opened or attached for
+    pub async fn find_workspace_id_by_pr_url(
+        pool: &SqlitePool,
+        pr_url: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid" FROM merges
+               WHERE merge_type = 'pr' AND rtrim(pr_url, '/') = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            pr_url
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// URL of the newest pull request opened from any of the task's attempts
+    pub async fn find_latest_pr_url_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT m.pr_url as "pr_url!: String" FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               WHERE w.task_id = $1 AND m.merge_type = 'pr' AND m.pr_url IS NOT NULL
+               ORDER BY m.created_at DESC
+               LIMIT 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
 }
 
 // Conversion implementations
@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+/// Longest burndown series returned, in days
+const MAX_BURNDOWN_DAYS: u64 = 366;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Milestone {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// First day of the burndown; the creation day when unset
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateMilestone {
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateMilestone {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+}
+
+fn validate_dates(
+    errors: &mut ValidationErrors,
+    start_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+) {
+    if let (Some(start), Some(due)) = (start_date, due_date)
+        && due < start
+    {
+        errors.add("due_date", "must not be before start_date");
+    }
+}
+
+impl Validate for CreateMilestone {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", &self.name, MAX_NAME_CHARS);
+        errors.optional_text(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_CHARS,
+        );
+        validate_dates(&mut errors, self.start_date, self.due_date);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateMilestone {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.optional_text("name", self.name.as_deref(), MAX_NAME_CHARS);
+        errors.optional_text(
+            "description",
+            self.description.as_deref(),
+            MAX_DESCRIPTION_CHARS,
+        );
+        validate_dates(&mut errors, self.start_date, self.due_date);
+        errors.into_result()
+    }
+}
+
+/// Work left in a milestone at the end of one day (UTC)
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    /// Member tasks that existed and were not done or cancelled
+    #[ts(type = "number")]
+    pub remaining_tasks: i64,
+    /// Estimated points of the remaining tasks
+    pub remaining_points: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MilestoneBurndown {
+    pub milestone: Milestone,
+    #[ts(type = "number")]
+    pub total_tasks: i64,
+    pub total_points: f64,
+    /// One point per day from the start date through today
+    pub series: Vec<BurndownPoint>,
+}
+
+#[derive(FromRow)]
+struct MemberTask {
+    task_id: Uuid,
+    created_at: DateTime<Utc>,
+    points: Option<f64>,
+}
+
+#[derive(FromRow)]
+struct StatusEvent {
+    task_id: Uuid,
+    status: String,
+    changed_at: DateTime<Utc>,
+}
+
+impl Milestone {
+    /// Milestones of a project, soonest due first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Milestone,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      description,
+                      start_date as "start_date?: NaiveDate",
+                      due_date as "due_date?: NaiveDate",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM milestones WHERE project_id = $1
+               ORDER BY due_date IS NULL, due_date, created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Milestone,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      description,
+                      start_date as "start_date?: NaiveDate",
+                      due_date as "due_date?: NaiveDate",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM milestones WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateMilestone,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Milestone,
+            r#"INSERT INTO milestones (id, project_id, name, description, start_date, due_date)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         description,
+                         start_date as "start_date?: NaiveDate",
+                         due_date as "due_date?: NaiveDate",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            data.name.trim(),
+            &data.description,
+            data.start_date,
+            data.due_date
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateMilestone,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Milestone,
+            r#"UPDATE milestones
+               SET name = COALESCE($2, name),
+                   description = COALESCE($3, description),
+                   start_date = COALESCE($4, start_date),
+                   due_date = COALESCE($5, due_date),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         description,
+                         start_date as "start_date?: NaiveDate",
+                         due_date as "due_date?: NaiveDate",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name.as_deref().map(str::trim),
+            &data.description,
+            data.start_date,
+            data.due_date
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM milestones WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn task_ids(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id as "task_id!: Uuid" FROM milestone_tasks WHERE milestone_id = $1"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the milestone's tasks; ids of tasks outside its project are ignored
+    pub async fn set_tasks(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM milestone_tasks WHERE milestone_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        for task_id in task_ids {
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO milestone_tasks (milestone_id, task_id)
+                   SELECT m.id, t.id FROM milestones m
+                   JOIN tasks t ON t.project_id = m.project_id
+                   WHERE m.id = $1 AND t.id = $2"#,
+                id,
+                task_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Self::task_ids(pool, id).await
+    }
+}
+
+impl MilestoneBurndown {
+    /// Remaining work per day, replayed from the status history of the
+    /// milestone's tasks
+    pub async fn collect(pool: &SqlitePool, milestone: Milestone) -> Result<Self, sqlx::Error> {
+        let members = sqlx::query_as!(
+            MemberTask,
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.created_at AS "created_at!: DateTime<Utc>",
+                      e.points AS "points?: f64"
+               FROM milestone_tasks mt
+               JOIN tasks t ON t.id = mt.task_id
+               LEFT JOIN task_estimates e ON e.task_id = t.id
+               WHERE mt.milestone_id = $1"#,
+            milestone.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let events = sqlx::query_as!(
+            StatusEvent,
+            r#"SELECT se.task_id as "task_id!: Uuid",
+                      se.status,
+                      se.changed_at as "changed_at!: DateTime<Utc>"
+               FROM task_status_events se
+               JOIN milestone_tasks mt ON mt.task_id = se.task_id
+               WHERE mt.milestone_id = $1
+               ORDER BY se.changed_at, se.id"#,
+            milestone.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let start = milestone
+            .start_date
+            .unwrap_or_else(|| milestone.created_at.date_naive());
+        let series = burndown(start, Utc::now().date_naive(), &members, &events);
+
+        Ok(Self {
+            total_tasks: members.len() as i64,
+            total_points: members.iter().filter_map(|m| m.points).sum(),
+            milestone,
+            series,
+        })
+    }
+}
+
+fn burndown(
+    start: NaiveDate,
+    today: NaiveDate,
+    members: &[MemberTask],
+    events: &[StatusEvent],
+) -> Vec<BurndownPoint> {
+    let mut history: HashMap<Uuid, Vec<&StatusEvent>> = HashMap::new();
+    for event in events {
+        history.entry(event.task_id).or_default().push(event);
+    }
+
+    start
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .take(MAX_BURNDOWN_DAYS as usize)
+        .map(|date| {
+            let end_of_day = date
+                .checked_add_days(Days::new(1))
+                .and_then(|next| next.and_hms_opt(0, 0, 0))
+                .map(|next| next.and_utc())
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            let remaining: Vec<&MemberTask> = members
+                .iter()
+                .filter(|task| task.created_at < end_of_day)
+                .filter(|task| {
+                    let status = history
+                        .get(&task.task_id)
+                        .and_then(|events| {
+                            events
+                                .iter()
+                                .take_while(|event| event.changed_at < end_of_day)
+                                .last()
+                        })
+                        .map(|event| event.status.as_str());
+                    !matches!(status, Some("done" | "cancelled"))
+                })
+                .collect();
+            BurndownPoint {
+                date,
+                remaining_tasks: remaining.len() as i64,
+                remaining_points: remaining.iter().filter_map(|task| task.points).sum(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn replays_status_history_per_day() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let members = [
+            MemberTask {
+                task_id: a,
+                created_at: at(1, 9),
+                points: Some(3.0),
+            },
+            MemberTask {
+                task_id: b,
+                created_at: at(1, 9),
+                points: Some(5.0),
+            },
+            MemberTask {
+                task_id: c,
+                created_at: at(3, 9),
+                points: None,
+            },
+        ];
+        let event = |task_id, status: &str, changed_at| StatusEvent {
+            task_id,
+            status: status.to_string(),
+            changed_at,
+        };
+        let events = [
+            event(a, "todo", at(1, 9)),
+            event(b, "todo", at(1, 9)),
+            event(a, "done", at(2, 15)),
+            event(c, "todo", at(3, 9)),
+            event(b, "cancelled", at(3, 10)),
+            event(b, "todo", at(3, 11)),
+        ];
+
+        let series = burndown(
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 3).unwrap(),
+            &members,
+            &events,
+        );
+
+        let remaining: Vec<(i64, f64)> = series
+            .iter()
+            .map(|point| (point.remaining_tasks, point.remaining_points))
+            .collect();
+        assert_eq!(remaining, [(2, 8.0), (1, 5.0), (2, 5.0)]);
+    }
+}
@@ -1,18 +1,62 @@
+pub mod agent_instructions;
+pub mod agent_prompt;
+pub mod attempt_artifact;
+pub mod attempt_comparison;
+pub mod chat_channel;
 pub mod coding_agent_turn;
 pub mod commands;
+pub mod coverage;
+pub mod e2e_test;
+pub mod env_template;
+pub mod epic;
+pub mod execution_interrupt;
+pub mod execution_log_event;
 pub mod execution_process;
+pub mod execution_process_group;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_token_usage;
+pub mod execution_window;
+pub mod file_embedding;
 pub mod image;
+pub mod instance;
+pub mod instance_stats;
+pub mod job;
+pub mod lifecycle_hook;
 pub mod merge;
+pub mod milestone;
 pub mod pending_commit;
+pub mod preview;
 pub mod project;
+pub mod project_budget;
+pub mod project_owner;
 pub mod project_repo;
+pub mod project_report;
+pub mod project_script;
+pub mod project_share_link;
+pub mod project_share_rule;
+pub mod prompt_template;
 pub mod repo;
+pub mod repo_ssh_key;
 pub mod scratch;
+pub mod security_audit;
 pub mod session;
+pub mod session_handoff;
+pub mod shared_task_sync_base;
 pub mod tag;
 pub mod task;
+pub mod task_activity;
+pub mod task_assignee;
+pub mod task_attachment;
+pub mod task_context_pack;
+pub mod task_estimate;
+pub mod task_fan_out;
+pub mod task_knowledge;
 pub mod task_label;
+pub mod task_planning;
+pub mod time_entry;
+pub mod velocity;
 pub mod workspace;
+pub mod workspace_environment;
 pub mod workspace_repo;
+pub mod workspace_target_sync;
@@ -84,6 +84,30 @@ impl PendingCommit {
         .await
     }
 
+    /// encontrar los pending commits de los proyectos de un usuario (modo multi-usuario)
+    pub async fn find_by_owner(pool: &SqlitePool, user_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PendingCommit,
+            r#"SELECT
+                pc.id as "id!: Uuid",
+                pc.workspace_id as "workspace_id!: Uuid",
+                pc.repo_id as "repo_id!: Uuid",
+                pc.repo_path,
+                pc.diff_summary,
+                pc.agent_summary,
+                pc.created_at as "created_at!: DateTime<Utc>"
+            FROM pending_commits pc
+            JOIN workspaces w ON w.id = pc.workspace_id
+            JOIN tasks t ON t.id = w.task_id
+            JOIN project_owners po ON po.project_id = t.project_id
+            WHERE po.user_id = $1
+            ORDER BY pc.created_at ASC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// encontrar pending commits por workspace
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -164,3 +188,57 @@ impl PendingCommit {
         Ok(result.rows_affected())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        DBService, fixtures,
+        models::{project_owner::ProjectOwner, repo::Repo},
+    };
+
+    /// Pending commit in a new workspace of the project
+    async fn pending_in(pool: &SqlitePool, project_id: Uuid, repo_id: Uuid) -> PendingCommit {
+        let task = fixtures::task(pool, project_id).await;
+        let workspace = fixtures::workspace(pool, task.id, "vk/feature").await;
+        PendingCommit::create(
+            pool,
+            &CreatePendingCommit {
+                workspace_id: workspace.id,
+                repo_id,
+                repo_path: "widgets".to_string(),
+                diff_summary: "1 file changed".to_string(),
+                agent_summary: None,
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn finds_only_pending_commits_of_owned_projects() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let repo = Repo::find_or_create(pool, Path::new("/tmp/vk-test-widgets"), "widgets")
+            .await
+            .unwrap();
+        let user_id = Uuid::new_v4();
+        let owned = fixtures::project(pool).await;
+        let other = fixtures::project(pool).await;
+        ProjectOwner::assign(pool, owned.id, user_id).await.unwrap();
+        ProjectOwner::assign(pool, other.id, Uuid::new_v4())
+            .await
+            .unwrap();
+        let mine = pending_in(pool, owned.id, repo.id).await;
+        pending_in(pool, other.id, repo.id).await;
+
+        let found = PendingCommit::find_by_owner(pool, user_id).await.unwrap();
+        assert_eq!(
+            found.iter().map(|pending| pending.id).collect::<Vec<_>>(),
+            vec![mine.id]
+        );
+        assert_eq!(PendingCommit::find_all(pool).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,268 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+/// Dev server screenshot configured for a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectPreviewSettings {
+    pub project_id: Uuid,
+    /// Shell command writing a screenshot of `{url}` to `{output}`
+    pub command: String,
+    /// Page to capture; detected from the dev server output when unset
+    pub url: Option<String>,
+    /// Only tasks carrying this label are captured after their runs
+    pub label: String,
+    /// Paths compared between the target branch and the attempt, such as
+    /// `/` or `/settings`
+    #[ts(type = "Array<string>")]
+    pub visual_routes: Json<Vec<String>>,
+    /// Where the target branch is served, e.g. a staging deployment
+    pub baseline_url: Option<String>,
+    /// Percentage of changed pixels above which a route is flagged
+    pub diff_threshold: f64,
+    /// Post the comparison on the attempt's pull requests
+    pub comment_on_pr: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectPreviewSettings {
+    /// Empty uses headless Chromium
+    pub command: String,
+    pub url: Option<String>,
+    pub label: Option<String>,
+    #[serde(default)]
+    pub visual_routes: Vec<String>,
+    pub baseline_url: Option<String>,
+    pub diff_threshold: Option<f64>,
+    pub comment_on_pr: Option<bool>,
+}
+
+/// Pixel comparison of one route between the target branch and an attempt
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct VisualComparison {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub route: String,
+    pub baseline_artifact_id: Option<Uuid>,
+    pub attempt_artifact_id: Option<Uuid>,
+    /// Changed pixels highlighted over the attempt's screenshot
+    pub diff_artifact_id: Option<Uuid>,
+    pub changed_percent: f64,
+    /// `changed_percent` exceeded the project's threshold
+    pub flagged: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Comparison result, before it is stored
+#[derive(Debug, Clone)]
+pub struct CreateVisualComparison {
+    pub route: String,
+    pub baseline_artifact_id: Uuid,
+    pub attempt_artifact_id: Uuid,
+    pub diff_artifact_id: Uuid,
+    pub changed_percent: f64,
+    pub flagged: bool,
+}
+
+impl Validate for UpsertProjectPreviewSettings {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.max_chars("command", &self.command, MAX_DESCRIPTION_CHARS);
+        // An empty label captures every task
+        if let Some(label) = &self.label {
+            errors.max_chars("label", label, MAX_NAME_CHARS);
+        }
+        for (field, url) in [("url", &self.url), ("baseline_url", &self.baseline_url)] {
+            if let Some(url) = url.as_deref().filter(|url| !url.trim().is_empty())
+                && !url.starts_with("http://")
+                && !url.starts_with("https://")
+            {
+                errors.add(field, "must be an http or https URL");
+            }
+        }
+        for route in &self.visual_routes {
+            if !route.starts_with('/') {
+                errors.add("visual_routes", format!("{route} must start with /"));
+            }
+            errors.max_chars("visual_routes", route, MAX_NAME_CHARS);
+        }
+        if !self.visual_routes.is_empty()
+            && self
+                .baseline_url
+                .as_deref()
+                .is_none_or(|url| url.trim().is_empty())
+        {
+            errors.add(
+                "baseline_url",
+                "is required to compare routes with the target branch",
+            );
+        }
+        if let Some(threshold) = self.diff_threshold
+            && !(0.0..=100.0).contains(&threshold)
+        {
+            errors.add("diff_threshold", "must be between 0 and 100");
+        }
+        errors.into_result()
+    }
+}
+
+impl ProjectPreviewSettings {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPreviewSettings,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      command,
+                      url,
+                      label,
+                      visual_routes as "visual_routes!: Json<Vec<String>>",
+                      baseline_url,
+                      diff_threshold,
+                      comment_on_pr as "comment_on_pr!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_preview_settings WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectPreviewSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPreviewSettings,
+            r#"INSERT INTO project_preview_settings
+                   (project_id, command, url, label, visual_routes, baseline_url,
+                    diff_threshold, comment_on_pr)
+               VALUES ($1, $2, $3, COALESCE($4, 'ui'), $5, $6, COALESCE($7, 0.5),
+                       COALESCE($8, 1))
+               ON CONFLICT(project_id) DO UPDATE SET
+                   command = excluded.command,
+                   url = excluded.url,
+                   label = COALESCE($4, project_preview_settings.label),
+                   visual_routes = excluded.visual_routes,
+                   baseline_url = excluded.baseline_url,
+                   diff_threshold = COALESCE($7, project_preview_settings.diff_threshold),
+                   comment_on_pr = COALESCE($8, project_preview_settings.comment_on_pr),
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         command,
+                         url,
+                         label,
+                         visual_routes as "visual_routes!: Json<Vec<String>>",
+                         baseline_url,
+                         diff_threshold,
+                         comment_on_pr as "comment_on_pr!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            &data.command,
+            &data.url,
+            &data.label,
+            Json(&data.visual_routes),
+            &data.baseline_url,
+            data.diff_threshold,
+            data.comment_on_pr
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_preview_settings WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl VisualComparison {
+    /// Replace the workspace's comparisons with the results of a new run
+    pub async fn replace_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        comparisons: &[CreateVisualComparison],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM visual_comparisons WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut stored = Vec::with_capacity(comparisons.len());
+        for comparison in comparisons {
+            stored.push(
+                sqlx::query_as!(
+                    VisualComparison,
+                    r#"INSERT INTO visual_comparisons
+                           (id, workspace_id, route, baseline_artifact_id, attempt_artifact_id,
+                            diff_artifact_id, changed_percent, flagged)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                       RETURNING id as "id!: Uuid",
+                                 workspace_id as "workspace_id!: Uuid",
+                                 route,
+                                 baseline_artifact_id as "baseline_artifact_id?: Uuid",
+                                 attempt_artifact_id as "attempt_artifact_id?: Uuid",
+                                 diff_artifact_id as "diff_artifact_id?: Uuid",
+                                 changed_percent,
+                                 flagged as "flagged!: bool",
+                                 created_at as "created_at!: DateTime<Utc>""#,
+                    Uuid::new_v4(),
+                    workspace_id,
+                    &comparison.route,
+                    comparison.baseline_artifact_id,
+                    comparison.attempt_artifact_id,
+                    comparison.diff_artifact_id,
+                    comparison.changed_percent,
+                    comparison.flagged
+                )
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+
+        tx.commit().await?;
+        Ok(stored)
+    }
+
+    /// Comparisons of the workspace, most changed first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VisualComparison,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      route,
+                      baseline_artifact_id as "baseline_artifact_id?: Uuid",
+                      attempt_artifact_id as "attempt_artifact_id?: Uuid",
+                      diff_artifact_id as "diff_artifact_id?: Uuid",
+                      changed_percent,
+                      flagged as "flagged!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM visual_comparisons
+               WHERE workspace_id = $1
+               ORDER BY changed_percent DESC, route ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -1,11 +1,19 @@
+use std::collections::HashSet;
+
 use chrono::{DateTime, Utc};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
+use utils::validation::{MAX_NAME_CHARS, Validate, ValidationErrors};
 use uuid::Uuid;
 
-use super::project_repo::CreateProjectRepo;
+use super::{
+    agent_instructions::ProjectAgentInstructions, execution_window::ExecutionWindow,
+    project_budget::ProjectBudget, project_repo::CreateProjectRepo,
+};
 
 #[derive(Debug, Error)]
 pub enum ProjectError {
@@ -17,7 +25,7 @@ pub enum ProjectError {
     CreateFailed(String),
 }
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS, JsonSchema)]
 pub struct Project {
     pub id: Uuid,
     pub name: String,
@@ -60,7 +68,7 @@ pub struct ProjectWithTaskCounts {
     pub task_counts: ProjectTaskCounts,
 }
 
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Deserialize, TS, JsonSchema)]
 pub struct CreateProject {
     pub name: String,
     pub repositories: Vec<CreateProjectRepo>,
@@ -123,6 +131,117 @@ pub enum SearchMatchType {
     FullPath,
 }
 
+/// Variant and model a project runs a given executor with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ExecutorDefault {
+    pub executor: BaseCodingAgent,
+    pub variant: Option<String>,
+    /// Model passed to the agent instead of the variant's own
+    pub model: Option<String>,
+}
+
+/// What happens to an attempt's branch once its PR is merged
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ProjectBranchCleanup {
+    /// Delete the branch from the remote the PR was opened from
+    #[serde(default)]
+    pub delete_remote_branch: bool,
+    /// Remove the worktrees and local branch once the attempt is archived
+    #[serde(default)]
+    pub delete_local_branch: bool,
+}
+
+/// Project-level executor defaults, used when an attempt is started without
+/// an explicit executor profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct ProjectExecutorDefaults {
+    /// Executor for new attempts; `None` falls back to the global config
+    pub default_executor: Option<BaseCodingAgent>,
+    #[serde(default)]
+    pub executors: Vec<ExecutorDefault>,
+}
+
+impl ProjectExecutorDefaults {
+    pub fn for_executor(&self, executor: BaseCodingAgent) -> Option<&ExecutorDefault> {
+        self.executors
+            .iter()
+            .find(|entry| entry.executor == executor)
+    }
+
+    /// Profile of the project's default executor, with its configured variant
+    pub fn default_profile(&self) -> Option<ExecutorProfileId> {
+        let executor = self.default_executor?;
+        Some(ExecutorProfileId {
+            executor,
+            variant: self
+                .for_executor(executor)
+                .and_then(|entry| entry.variant.clone()),
+        })
+    }
+
+    /// Model override for `profile`. Only applies when the profile is the
+    /// executor/variant pair configured here, so picking another variant
+    /// keeps that variant's own model.
+    pub fn model_for(&self, profile: &ExecutorProfileId) -> Option<String> {
+        self.for_executor(profile.executor)
+            .filter(|entry| entry.variant == profile.variant)
+            .and_then(|entry| entry.model.clone())
+    }
+}
+
+impl Validate for ProjectExecutorDefaults {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let mut seen = HashSet::new();
+        for entry in &self.executors {
+            if !seen.insert(entry.executor) {
+                errors.add(
+                    "executors",
+                    format!("{} is listed more than once", entry.executor),
+                );
+            }
+        }
+        errors.into_result()
+    }
+}
+
+/// Accepted `git_commit_title_mode` overrides, matching the config's variants
+const GIT_COMMIT_TITLE_MODES: &[&str] = &["AgentSummary", "AiGenerated", "Manual"];
+/// Accepted `git_auto_push_mode` overrides, matching the config's variants
+const GIT_AUTO_PUSH_MODES: &[&str] = &["Never", "Always", "IfPrExists"];
+
+impl Validate for CreateProject {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", &self.name, MAX_NAME_CHARS);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateProject {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.optional_text("name", self.name.as_deref(), MAX_NAME_CHARS);
+        if let Some(Some(mode)) = &self.git_commit_title_mode
+            && !GIT_COMMIT_TITLE_MODES.contains(&mode.as_str())
+        {
+            errors.add(
+                "git_commit_title_mode",
+                format!("must be one of {}", GIT_COMMIT_TITLE_MODES.join(", ")),
+            );
+        }
+        if let Some(Some(mode)) = &self.git_auto_push_mode
+            && !GIT_AUTO_PUSH_MODES.contains(&mode.as_str())
+        {
+            errors.add(
+                "git_auto_push_mode",
+                format!("must be one of {}", GIT_AUTO_PUSH_MODES.join(", ")),
+            );
+        }
+        errors.into_result()
+    }
+}
+
 impl Project {
     pub async fn count(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
         sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM projects"#)
@@ -392,6 +511,280 @@ impl Project {
         Ok(())
     }
 
+    /// Dependencies agents may add without a warning; `None` disables the check
+    pub async fn find_dependency_allowlist(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<Vec<String>>, sqlx::Error> {
+        let allowlist = sqlx::query_scalar!(
+            r#"SELECT dependency_allowlist as "dependency_allowlist?: sqlx::types::Json<Vec<String>>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(allowlist.flatten().map(|json| json.0))
+    }
+
+    pub async fn update_dependency_allowlist(
+        pool: &SqlitePool,
+        id: Uuid,
+        allowlist: Option<&[String]>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET dependency_allowlist = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            allowlist.map(sqlx::types::Json),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// `.gitignore`-style patterns excluded from the project's attempt diffs
+    pub async fn find_diff_ignore_patterns(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let patterns = sqlx::query_scalar!(
+            r#"SELECT diff_ignore_patterns as "diff_ignore_patterns?: sqlx::types::Json<Vec<String>>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(patterns.flatten().map(|json| json.0).unwrap_or_default())
+    }
+
+    pub async fn update_diff_ignore_patterns(
+        pool: &SqlitePool,
+        id: Uuid,
+        patterns: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET diff_ignore_patterns = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            sqlx::types::Json(patterns),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Patterns the project adds to and removes from the global sensitive
+    /// file list, as `(added, removed)`
+    pub async fn find_sensitive_patterns(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<(Vec<String>, Vec<String>), sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT sensitive_patterns_added as "sensitive_patterns_added?: sqlx::types::Json<Vec<String>>",
+                      sensitive_patterns_removed as "sensitive_patterns_removed?: sqlx::types::Json<Vec<String>>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+        Ok((
+            row.sensitive_patterns_added
+                .map(|json| json.0)
+                .unwrap_or_default(),
+            row.sensitive_patterns_removed
+                .map(|json| json.0)
+                .unwrap_or_default(),
+        ))
+    }
+
+    pub async fn update_sensitive_patterns(
+        pool: &SqlitePool,
+        id: Uuid,
+        added: &[String],
+        removed: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET sensitive_patterns_added = $1,
+                   sensitive_patterns_removed = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $3"#,
+            sqlx::types::Json(added),
+            sqlx::types::Json(removed),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_executor_defaults(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectExecutorDefaults, sqlx::Error> {
+        let defaults = sqlx::query_scalar!(
+            r#"SELECT executor_defaults as "executor_defaults?: sqlx::types::Json<ProjectExecutorDefaults>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(defaults.flatten().map(|json| json.0).unwrap_or_default())
+    }
+
+    /// Executor defaults of the project a workspace belongs to
+    pub async fn find_executor_defaults_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<ProjectExecutorDefaults, sqlx::Error> {
+        let defaults = sqlx::query_scalar!(
+            r#"SELECT p.executor_defaults as "executor_defaults?: sqlx::types::Json<ProjectExecutorDefaults>"
+               FROM workspaces w
+               JOIN tasks t    ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE w.id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(defaults.flatten().map(|json| json.0).unwrap_or_default())
+    }
+
+    pub async fn update_executor_defaults(
+        pool: &SqlitePool,
+        id: Uuid,
+        defaults: &ProjectExecutorDefaults,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET executor_defaults = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            sqlx::types::Json(defaults),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_branch_cleanup(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectBranchCleanup, sqlx::Error> {
+        let cleanup = sqlx::query_scalar!(
+            r#"SELECT branch_cleanup as "branch_cleanup?: sqlx::types::Json<ProjectBranchCleanup>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(cleanup.flatten().map(|json| json.0).unwrap_or_default())
+    }
+
+    pub async fn update_branch_cleanup(
+        pool: &SqlitePool,
+        id: Uuid,
+        cleanup: &ProjectBranchCleanup,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET branch_cleanup = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            sqlx::types::Json(cleanup),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_execution_window(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ExecutionWindow>, sqlx::Error> {
+        let window = sqlx::query_scalar!(
+            r#"SELECT execution_window as "execution_window?: sqlx::types::Json<ExecutionWindow>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(window.flatten().map(|json| json.0))
+    }
+
+    /// Set or clear (`None`) the hours the project's attempts may start in
+    pub async fn update_execution_window(
+        pool: &SqlitePool,
+        id: Uuid,
+        window: Option<&ExecutionWindow>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET execution_window = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            window.map(sqlx::types::Json),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_budget(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ProjectBudget>, sqlx::Error> {
+        let budget = sqlx::query_scalar!(
+            r#"SELECT budget as "budget?: sqlx::types::Json<ProjectBudget>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(budget.flatten().map(|json| json.0))
+    }
+
+    /// Set or clear (`None`) the project's monthly spending limit
+    pub async fn update_budget(
+        pool: &SqlitePool,
+        id: Uuid,
+        budget: Option<&ProjectBudget>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET budget = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            budget.map(sqlx::types::Json),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_agent_instructions(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ProjectAgentInstructions>, sqlx::Error> {
+        let instructions = sqlx::query_scalar!(
+            r#"SELECT agent_instructions as "agent_instructions?: sqlx::types::Json<ProjectAgentInstructions>"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(instructions.flatten().map(|json| json.0))
+    }
+
+    /// Set or clear (`None`) the instructions rendered into the project's repos
+    pub async fn update_agent_instructions(
+        pool: &SqlitePool,
+        id: Uuid,
+        instructions: Option<&ProjectAgentInstructions>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET agent_instructions = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            instructions.map(sqlx::types::Json),
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM projects WHERE id = $1", id)
             .execute(pool)
@@ -519,3 +912,35 @@ impl Project {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_override_only_applies_to_the_configured_variant() {
+        let defaults = ProjectExecutorDefaults {
+            default_executor: Some(BaseCodingAgent::ClaudeCode),
+            executors: vec![ExecutorDefault {
+                executor: BaseCodingAgent::ClaudeCode,
+                variant: Some("PLAN".to_string()),
+                model: Some("opus".to_string()),
+            }],
+        };
+
+        let profile = defaults.default_profile().unwrap();
+        assert_eq!(profile.variant.as_deref(), Some("PLAN"));
+        assert_eq!(defaults.model_for(&profile).as_deref(), Some("opus"));
+
+        let other_variant = ExecutorProfileId {
+            executor: BaseCodingAgent::ClaudeCode,
+            variant: None,
+        };
+        assert_eq!(defaults.model_for(&other_variant), None);
+        assert!(
+            ProjectExecutorDefaults::default()
+                .default_profile()
+                .is_none()
+        );
+    }
+}
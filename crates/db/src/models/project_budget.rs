@@ -0,0 +1,208 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+use super::project::Project;
+
+/// Share of the monthly limit, in percent, at which the user is notified
+pub const BUDGET_ALERT_THRESHOLDS: [i64; 2] = [80, 100];
+
+/// Coding agent runs averaged to project the cost of the next one
+const PROJECTION_SAMPLE_RUNS: i64 = 20;
+
+/// What happens when a new run is expected to go over budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum BudgetEnforcement {
+    /// The run starts once the user confirms it
+    #[default]
+    Confirm,
+    /// The run is refused
+    Block,
+}
+
+/// Monthly spending limit for a project's agent runs. Agents report tokens,
+/// not money, so cost is estimated from a flat per-token price.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ProjectBudget {
+    pub monthly_limit_usd: f64,
+    /// Price used to turn token usage into cost
+    pub usd_per_million_tokens: f64,
+    #[serde(default)]
+    pub enforcement: BudgetEnforcement,
+}
+
+impl Validate for ProjectBudget {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if !(self.monthly_limit_usd.is_finite() && self.monthly_limit_usd > 0.0) {
+            errors.add("monthly_limit_usd", "must be a positive amount");
+        }
+        if !(self.usd_per_million_tokens.is_finite() && self.usd_per_million_tokens > 0.0) {
+            errors.add("usd_per_million_tokens", "must be a positive price");
+        }
+        errors.into_result()
+    }
+}
+
+/// Where a project stands against its budget this month
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BudgetStatus {
+    /// Calendar month (UTC) as `YYYY-MM`
+    pub month: String,
+    pub limit_usd: f64,
+    pub spent_usd: f64,
+    pub remaining_usd: f64,
+    pub percent_used: f64,
+    /// Expected cost of one more run, from the project's recent runs
+    pub projected_run_usd: f64,
+    pub enforcement: BudgetEnforcement,
+}
+
+impl BudgetStatus {
+    /// Whether starting `runs` more runs is expected to go over the limit
+    pub fn exceeded_by_runs(&self, runs: usize) -> bool {
+        self.spent_usd + self.projected_run_usd * runs as f64 > self.limit_usd
+    }
+
+    /// Alert thresholds the month's spending has reached
+    pub fn thresholds_reached(&self) -> impl Iterator<Item = i64> + '_ {
+        BUDGET_ALERT_THRESHOLDS
+            .into_iter()
+            .filter(|threshold| self.percent_used >= *threshold as f64)
+    }
+}
+
+impl ProjectBudget {
+    fn usd(&self, tokens: f64) -> f64 {
+        tokens * self.usd_per_million_tokens / 1_000_000.0
+    }
+
+    pub fn status(&self, month: String, month_tokens: i64, run_tokens: f64) -> BudgetStatus {
+        let spent_usd = self.usd(month_tokens as f64);
+        BudgetStatus {
+            month,
+            limit_usd: self.monthly_limit_usd,
+            spent_usd,
+            remaining_usd: (self.monthly_limit_usd - spent_usd).max(0.0),
+            percent_used: spent_usd / self.monthly_limit_usd * 100.0,
+            projected_run_usd: self.usd(run_tokens),
+            enforcement: self.enforcement,
+        }
+    }
+
+    /// This month's status for a project, `None` if it has no budget
+    pub async fn find_status(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<BudgetStatus>, sqlx::Error> {
+        let Some(budget) = Project::find_budget(pool, project_id).await? else {
+            return Ok(None);
+        };
+        let month = Utc::now().format("%Y-%m").to_string();
+        let month_tokens = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(u.total_tokens), 0) as "total!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s             ON s.id = ep.session_id
+               JOIN workspaces w           ON w.id = s.workspace_id
+               JOIN tasks t                ON t.id = w.task_id
+               WHERE t.project_id = $1 AND strftime('%Y-%m', ep.created_at) = $2"#,
+            project_id,
+            &month
+        )
+        .fetch_one(pool)
+        .await?;
+        let run_tokens = sqlx::query_scalar!(
+            r#"SELECT AVG(total_tokens) as "average?: f64" FROM (
+                   SELECT u.total_tokens
+                   FROM execution_process_token_usage u
+                   JOIN execution_processes ep ON ep.id = u.execution_process_id
+                   JOIN sessions s             ON s.id = ep.session_id
+                   JOIN workspaces w           ON w.id = s.workspace_id
+                   JOIN tasks t                ON t.id = w.task_id
+                   WHERE t.project_id = $1 AND ep.run_reason = 'codingagent'
+                   ORDER BY ep.created_at DESC
+                   LIMIT $2
+               )"#,
+            project_id,
+            PROJECTION_SAMPLE_RUNS
+        )
+        .fetch_one(pool)
+        .await?
+        .unwrap_or(0.0);
+        Ok(Some(budget.status(month, month_tokens, run_tokens)))
+    }
+}
+
+pub struct BudgetAlert;
+
+impl BudgetAlert {
+    /// Record that `threshold` was announced for `month`; `false` if it
+    /// already had been
+    pub async fn record_once(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        month: &str,
+        threshold: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"INSERT INTO project_budget_alerts (project_id, month, threshold)
+               VALUES ($1, $2, $3)
+               ON CONFLICT DO NOTHING"#,
+            project_id,
+            month,
+            threshold
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DBService, fixtures};
+
+    #[test]
+    fn projects_the_next_run_against_the_remaining_budget() {
+        let budget = ProjectBudget {
+            monthly_limit_usd: 10.0,
+            usd_per_million_tokens: 5.0,
+            enforcement: BudgetEnforcement::Block,
+        };
+        // $8.50 spent, runs average $1
+        let status = budget.status("2026-02".to_string(), 1_700_000, 200_000.0);
+        assert!((status.remaining_usd - 1.5).abs() < 1e-9);
+        assert_eq!(status.thresholds_reached().collect::<Vec<_>>(), vec![80]);
+        assert!(!status.exceeded_by_runs(1));
+        assert!(status.exceeded_by_runs(2));
+    }
+
+    #[tokio::test]
+    async fn alerts_fire_once_per_month() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = fixtures::project(&db.pool).await.id;
+
+        assert!(
+            BudgetAlert::record_once(&db.pool, project_id, "2026-02", 80)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !BudgetAlert::record_once(&db.pool, project_id, "2026-02", 80)
+                .await
+                .unwrap()
+        );
+        assert!(
+            BudgetAlert::record_once(&db.pool, project_id, "2026-03", 80)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Owner of a project when the server runs in multi-user mode
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectOwner {
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectOwner {
+    pub async fn assign(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectOwner,
+            r#"INSERT INTO project_owners (project_id, user_id)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET user_id = excluded.user_id
+               RETURNING project_id as "project_id!: Uuid",
+                         user_id as "user_id!: Uuid",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            project_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_owner(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT user_id as "user_id!: Uuid" FROM project_owners WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn project_ids_for_user(
+        pool: &SqlitePool,
+        user_id: Uuid,
+    ) -> Result<HashSet<Uuid>, sqlx::Error> {
+        let ids = sqlx::query_scalar!(
+            r#"SELECT project_id as "project_id!: Uuid" FROM project_owners WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Owner of the project a workspace's task belongs to
+    pub async fn find_owner_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT po.user_id as "user_id!: Uuid"
+               FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               JOIN project_owners po ON po.project_id = t.project_id
+               WHERE w.id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Project a workspace belongs to, used to authorize workspace-scoped routes
+    pub async fn find_project_id_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT t.project_id as "project_id!: Uuid"
+               FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               WHERE w.id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
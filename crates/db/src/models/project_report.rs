@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Task listed in a project report, with the summary of its latest attempt
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ReportTask {
+    pub id: Uuid,
+    pub title: String,
+    pub summary: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ReportPullRequest {
+    pub task_id: Uuid,
+    pub task_title: String,
+    #[ts(type = "number")]
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub pr_status: String,
+    pub created_at: DateTime<Utc>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+}
+
+/// Attempt whose coding agent run failed during the report period
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ReportFailedAttempt {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub executor: Option<String>,
+    #[ts(type = "number")]
+    pub failed_runs: i64,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// Agent activity in one project since a point in time
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectActivity {
+    pub since: DateTime<Utc>,
+    pub completed_tasks: Vec<ReportTask>,
+    pub prs_opened: Vec<ReportPullRequest>,
+    pub prs_merged: Vec<ReportPullRequest>,
+    pub failed_attempts: Vec<ReportFailedAttempt>,
+    /// Tasks currently waiting in review, regardless of `since`
+    pub pending_reviews: Vec<ReportTask>,
+}
+
+impl ProjectActivity {
+    pub async fn collect(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let completed_tasks = sqlx::query_as!(
+            ReportTask,
+            r#"SELECT t.id AS "id!: Uuid",
+                      t.title,
+                      t.updated_at AS "updated_at!: DateTime<Utc>",
+                      -- Summary of the task's most recent attempt that has one
+                      (SELECT w.summary FROM workspaces w
+                       WHERE w.task_id = t.id AND w.summary IS NOT NULL
+                       ORDER BY w.created_at DESC LIMIT 1) AS summary
+               FROM tasks t
+               WHERE t.project_id = $1 AND t.status = 'done'
+                 AND datetime(t.updated_at) >= datetime($2)
+               ORDER BY t.updated_at ASC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let prs_opened = sqlx::query_as!(
+            ReportPullRequest,
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      m.pr_number AS "pr_number!: i64",
+                      m.pr_url AS "pr_url!: String",
+                      m.pr_status AS "pr_status!: String",
+                      m.created_at AS "created_at!: DateTime<Utc>",
+                      m.pr_merged_at AS "pr_merged_at?: DateTime<Utc>"
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1 AND m.merge_type = 'pr'
+                 AND datetime(m.created_at) >= datetime($2)
+               ORDER BY m.created_at ASC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let prs_merged = sqlx::query_as!(
+            ReportPullRequest,
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      m.pr_number AS "pr_number!: i64",
+                      m.pr_url AS "pr_url!: String",
+                      m.pr_status AS "pr_status!: String",
+                      m.created_at AS "created_at!: DateTime<Utc>",
+                      m.pr_merged_at AS "pr_merged_at?: DateTime<Utc>"
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1 AND m.merge_type = 'pr' AND m.pr_status = 'merged'
+                 AND datetime(COALESCE(m.pr_merged_at, m.created_at)) >= datetime($2)
+               ORDER BY COALESCE(m.pr_merged_at, m.created_at) ASC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let failed_attempts = sqlx::query_as!(
+            ReportFailedAttempt,
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      w.id AS "workspace_id!: Uuid",
+                      w.branch,
+                      MAX(s.executor) AS "executor?: String",
+                      COUNT(*) AS "failed_runs!: i64",
+                      MAX(COALESCE(ep.completed_at, ep.created_at)) AS "last_failed_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1 AND ep.run_reason = 'codingagent'
+                 AND ep.status = 'failed'
+                 AND datetime(ep.created_at) >= datetime($2)
+               GROUP BY w.id
+               ORDER BY MAX(COALESCE(ep.completed_at, ep.created_at)) ASC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let pending_reviews = sqlx::query_as!(
+            ReportTask,
+            r#"SELECT t.id AS "id!: Uuid",
+                      t.title,
+                      t.updated_at AS "updated_at!: DateTime<Utc>",
+                      -- Summary of the task's most recent attempt that has one
+                      (SELECT w.summary FROM workspaces w
+                       WHERE w.task_id = t.id AND w.summary IS NOT NULL
+                       ORDER BY w.created_at DESC LIMIT 1) AS summary
+               FROM tasks t
+               WHERE t.project_id = $1 AND t.status = 'inreview'
+               ORDER BY t.updated_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            since,
+            completed_tasks,
+            prs_opened,
+            prs_merged,
+            failed_attempts,
+            pending_reviews,
+        })
+    }
+}
+
+/// Task whose attempt was merged into a repo, listed in release notes
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ReleaseTask {
+    pub task_id: Uuid,
+    pub task_title: String,
+    #[ts(type = "number | null")]
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    pub merged_at: DateTime<Utc>,
+    /// Names of the task's labels
+    #[sqlx(skip)]
+    pub labels: Vec<String>,
+}
+
+impl ReleaseTask {
+    /// Tasks of the project merged into `repo_id` after `since`, or ever when
+    /// `None`, oldest first; a task merged more than once is listed with its
+    /// latest merge
+    pub async fn find_merged_since(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      m.pr_number,
+                      m.pr_url,
+                      MAX(COALESCE(m.pr_merged_at, m.created_at)) AS "merged_at!: DateTime<Utc>"
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1 AND m.repo_id = $2
+                 AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+                 AND ($3 IS NULL
+                      OR datetime(COALESCE(m.pr_merged_at, m.created_at)) > datetime($3))
+               GROUP BY t.id
+               ORDER BY MAX(COALESCE(m.pr_merged_at, m.created_at)) ASC"#,
+            project_id,
+            repo_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ReleaseTask {
+                task_id: row.task_id,
+                task_title: row.task_title,
+                pr_number: row.pr_number,
+                pr_url: row.pr_url,
+                merged_at: row.merged_at,
+                labels: Vec::new(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Event a project script reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ScriptEvent {
+    TaskUpdated,
+    ExecutionFinished,
+}
+
+impl ScriptEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScriptEvent::TaskUpdated => "task_updated",
+            ScriptEvent::ExecutionFinished => "execution_finished",
+        }
+    }
+}
+
+/// Rhai automation script stored with a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectScript {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub event: ScriptEvent,
+    pub source: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateProjectScript {
+    pub name: String,
+    pub event: ScriptEvent,
+    pub source: String,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectScript {
+    pub name: Option<String>,
+    pub source: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+impl ProjectScript {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      event as "event!: ScriptEvent",
+                      source,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_scripts
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      event as "event!: ScriptEvent",
+                      source,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_scripts
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled scripts of `project_id` for `event`, oldest first
+    pub async fn find_for_event(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        event: ScriptEvent,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      event as "event!: ScriptEvent",
+                      source,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_scripts
+               WHERE project_id = $1 AND event = $2 AND enabled = 1
+               ORDER BY created_at ASC"#,
+            project_id,
+            event
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateProjectScript,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"INSERT INTO project_scripts (id, project_id, name, event, source, enabled)
+               VALUES ($1, $2, $3, $4, $5, COALESCE($6, 1))
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         event as "event!: ScriptEvent",
+                         source,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            &data.name,
+            data.event,
+            &data.source,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectScript,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"UPDATE project_scripts
+               SET name = COALESCE($2, name),
+                   source = COALESCE($3, source),
+                   enabled = COALESCE($4, enabled),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         event as "event!: ScriptEvent",
+                         source,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            &data.name,
+            &data.source,
+            data.enabled
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_scripts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
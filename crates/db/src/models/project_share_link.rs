@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+use super::task::{TaskStatus, TaskWithAttemptStatus};
+
+pub const MAX_SHARE_LINK_DAYS: i64 = 365;
+
+/// Tokenized read-only link to a project's board
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectShareLink {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub token: String,
+    /// `None` keeps the link valid until it is revoked
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectShareLink {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        token: &str,
+        expires_in_days: Option<i64>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectShareLink,
+            r#"INSERT INTO project_share_links (id, project_id, token, expires_at)
+               VALUES ($1, $2, $3, datetime('now', 'subsec', '+' || $4 || ' days'))
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         token,
+                         expires_at as "expires_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            token,
+            expires_in_days
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectShareLink,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      token,
+                      expires_at as "expires_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM project_share_links
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Link for `token`, unless it has expired
+    pub async fn find_active_by_token(
+        pool: &SqlitePool,
+        token: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectShareLink,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      token,
+                      expires_at as "expires_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM project_share_links
+               WHERE token = $1
+                 AND (expires_at IS NULL OR expires_at > datetime('now', 'subsec'))"#,
+            token
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_share_links WHERE id = $1 AND project_id = $2",
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Label as shown on a public board
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PublicBoardLabel {
+    pub name: String,
+    pub color: String,
+}
+
+/// Task as shown on a public board. Only what a stakeholder needs to follow
+/// progress: no ids, repo paths, PR links, summaries or logs.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PublicBoardTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub labels: Vec<PublicBoardLabel>,
+    /// An agent is working on the task right now
+    pub in_progress: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TaskWithAttemptStatus> for PublicBoardTask {
+    fn from(task: TaskWithAttemptStatus) -> Self {
+        Self {
+            title: task.task.title,
+            description: task.task.description,
+            status: task.task.status,
+            labels: task
+                .labels
+                .into_iter()
+                .map(|label| PublicBoardLabel {
+                    name: label.name,
+                    color: label.color,
+                })
+                .collect(),
+            in_progress: task.has_in_progress_attempt,
+            updated_at: task.task.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PublicBoard {
+    pub project_name: String,
+    pub tasks: Vec<PublicBoardTask>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectShareLink {
+    /// Days until the link stops working; omit for a link that lasts until revoked
+    #[ts(type = "number | null")]
+    pub expires_in_days: Option<i64>,
+}
+
+impl Validate for CreateProjectShareLink {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(days) = self.expires_in_days
+            && !(1..=MAX_SHARE_LINK_DAYS).contains(&days)
+        {
+            errors.add(
+                "expires_in_days",
+                format!("must be between 1 and {MAX_SHARE_LINK_DAYS}"),
+            );
+        }
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DBService, fixtures};
+
+    #[tokio::test]
+    async fn expired_and_revoked_links_are_not_found() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project = fixtures::project(pool).await;
+
+        let open = ProjectShareLink::create(pool, project.id, "open", None)
+            .await
+            .unwrap();
+        assert!(open.expires_at.is_none());
+        let dated = ProjectShareLink::create(pool, project.id, "dated", Some(7))
+            .await
+            .unwrap();
+        assert!(dated.expires_at.is_some_and(|at| at > Utc::now()));
+        assert!(
+            ProjectShareLink::find_active_by_token(pool, "dated")
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        sqlx::query!(
+            "UPDATE project_share_links SET expires_at = datetime('now', '-1 day') WHERE id = $1",
+            dated.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        assert!(
+            ProjectShareLink::find_active_by_token(pool, "dated")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(
+            ProjectShareLink::delete(pool, project.id, open.id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(
+            ProjectShareLink::find_active_by_token(pool, "open")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// Shares a project's tasks automatically: every task carrying `label_id`, or
+/// every task at `min_status` or further along. Exactly one is set.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectShareRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub label_id: Option<Uuid>,
+    pub min_status: Option<TaskStatus>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectShareRule {
+    pub label_id: Option<Uuid>,
+    pub min_status: Option<TaskStatus>,
+}
+
+impl Validate for CreateProjectShareRule {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        match (&self.label_id, &self.min_status) {
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => errors.add("label_id", "set either a label or a minimum status"),
+        }
+        if self.min_status == Some(TaskStatus::Cancelled) {
+            errors.add("min_status", "cancelled tasks are never shared by a rule");
+        }
+        errors.into_result()
+    }
+}
+
+/// How far along the board a status is. Cancelled tasks are off the board.
+fn progress(status: &TaskStatus) -> Option<u8> {
+    match status {
+        TaskStatus::Todo => Some(0),
+        TaskStatus::InProgress => Some(1),
+        TaskStatus::InReview => Some(2),
+        TaskStatus::Done => Some(3),
+        TaskStatus::Cancelled => None,
+    }
+}
+
+impl ProjectShareRule {
+    /// Whether a task with `status` and `label_ids` falls under the rule
+    pub fn matches(&self, status: &TaskStatus, label_ids: &[Uuid]) -> bool {
+        if let Some(label_id) = &self.label_id {
+            return label_ids.contains(label_id);
+        }
+        match (&self.min_status, progress(status)) {
+            (Some(min_status), Some(progress_made)) => {
+                progress(min_status).is_some_and(|required| progress_made >= required)
+            }
+            _ => false,
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectShareRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      label_id as "label_id?: Uuid",
+                      min_status as "min_status?: TaskStatus",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM project_share_rules WHERE project_id = $1 ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateProjectShareRule,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectShareRule,
+            r#"INSERT INTO project_share_rules (id, project_id, label_id, min_status)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         label_id as "label_id?: Uuid",
+                         min_status as "min_status?: TaskStatus",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            project_id,
+            data.label_id,
+            &data.min_status
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_share_rules WHERE id = $1 AND project_id = $2",
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remember that a rule, not the user, shared `task_id`
+    pub async fn mark_rule_shared(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO rule_shared_tasks (task_id) VALUES ($1)",
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn is_rule_shared(pool: &SqlitePool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let found = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM rule_shared_tasks WHERE task_id = $1) AS "found!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(found)
+    }
+
+    pub async fn unmark_rule_shared(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM rule_shared_tasks WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(label_id: Option<Uuid>, min_status: Option<TaskStatus>) -> ProjectShareRule {
+        ProjectShareRule {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            label_id,
+            min_status,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn label_rules_match_tasks_with_the_label() {
+        let team = Uuid::new_v4();
+        let rule = rule(Some(team), None);
+        assert!(rule.matches(&TaskStatus::Todo, &[Uuid::new_v4(), team]));
+        assert!(!rule.matches(&TaskStatus::Done, &[Uuid::new_v4()]));
+    }
+
+    #[test]
+    fn status_rules_match_tasks_at_or_past_the_status() {
+        let rule = rule(None, Some(TaskStatus::InReview));
+        assert!(rule.matches(&TaskStatus::InReview, &[]));
+        assert!(rule.matches(&TaskStatus::Done, &[]));
+        assert!(!rule.matches(&TaskStatus::InProgress, &[]));
+        assert!(!rule.matches(&TaskStatus::Cancelled, &[]));
+    }
+
+    #[test]
+    fn create_payload_needs_exactly_one_condition() {
+        let both = CreateProjectShareRule {
+            label_id: Some(Uuid::new_v4()),
+            min_status: Some(TaskStatus::Done),
+        };
+        let neither = CreateProjectShareRule {
+            label_id: None,
+            min_status: None,
+        };
+        let cancelled = CreateProjectShareRule {
+            label_id: None,
+            min_status: Some(TaskStatus::Cancelled),
+        };
+        assert!(both.validate().is_err());
+        assert!(neither.validate().is_err());
+        assert!(cancelled.validate().is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{
+    MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, MAX_TITLE_CHARS, Validate, ValidationErrors,
+};
+use uuid::Uuid;
+
+/// Well-known template names looked up by the server
+pub const PR_DESCRIPTION_PROMPT: &str = "pr_description";
+pub const COMMIT_TITLE_PROMPT: &str = "commit_title";
+
+/// Named prompt template. Templates without a project apply everywhere; a
+/// project template with the same name overrides the global one.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+    #[ts(type = "number")]
+    pub current_version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Content of a template as of one version
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PromptTemplateVersion {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    #[ts(type = "number")]
+    pub version: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreatePromptTemplate {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdatePromptTemplate {
+    pub description: Option<String>,
+    /// New content; saved as a new version when it differs from the current one
+    pub content: Option<String>,
+}
+
+impl Validate for CreatePromptTemplate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", &self.name, MAX_NAME_CHARS);
+        if let Some(description) = &self.description {
+            errors.max_chars("description", description, MAX_TITLE_CHARS);
+        }
+        errors.required_text("content", &self.content, MAX_DESCRIPTION_CHARS);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdatePromptTemplate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(description) = &self.description {
+            errors.max_chars("description", description, MAX_TITLE_CHARS);
+        }
+        errors.optional_text("content", self.content.as_deref(), MAX_DESCRIPTION_CHARS);
+        errors.into_result()
+    }
+}
+
+impl PromptTemplate {
+    /// Global templates plus, when given, the project's own templates
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      name,
+                      description,
+                      content,
+                      current_version,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates
+               WHERE project_id IS NULL OR project_id = $1
+               ORDER BY name ASC, project_id IS NOT NULL ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      name,
+                      description,
+                      content,
+                      current_version,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Template that applies to `name` in a project: the project override if
+    /// there is one, else the global template
+    pub async fn find_effective(
+        pool: &SqlitePool,
+        name: &str,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id?: Uuid",
+                      name,
+                      description,
+                      content,
+                      current_version,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates
+               WHERE name = $1 AND (project_id IS NULL OR project_id = $2)
+               ORDER BY project_id IS NULL ASC
+               LIMIT 1"#,
+            name,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePromptTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+
+        let template = sqlx::query_as!(
+            PromptTemplate,
+            r#"INSERT INTO prompt_templates (id, project_id, name, description, content)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id?: Uuid",
+                         name,
+                         description,
+                         content,
+                         current_version,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            &data.name,
+            &data.description,
+            &data.content
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"INSERT INTO prompt_template_versions (id, template_id, version, content)
+               VALUES ($1, $2, 1, $3)"#,
+            Uuid::new_v4(),
+            id,
+            &data.content
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(template)
+    }
+
+    /// Update the description and/or content; changed content becomes a new version
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdatePromptTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let description = data.description.clone().or(existing.description);
+        let content = data.content.as_ref().unwrap_or(&existing.content);
+        let version = if *content == existing.content {
+            existing.current_version
+        } else {
+            existing.current_version + 1
+        };
+
+        let mut tx = pool.begin().await?;
+        if version != existing.current_version {
+            sqlx::query!(
+                r#"INSERT INTO prompt_template_versions (id, template_id, version, content)
+                   VALUES ($1, $2, $3, $4)"#,
+                Uuid::new_v4(),
+                id,
+                version,
+                content
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let template = sqlx::query_as!(
+            PromptTemplate,
+            r#"UPDATE prompt_templates
+               SET description = $2, content = $3, current_version = $4,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id?: Uuid",
+                         name,
+                         description,
+                         content,
+                         current_version,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            description,
+            content,
+            version
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(template)
+    }
+
+    /// Make an earlier version current again, recorded as a new version
+    pub async fn restore_version(
+        pool: &SqlitePool,
+        id: Uuid,
+        version: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let old = PromptTemplateVersion::find(pool, id, version)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Self::update(
+            pool,
+            id,
+            &UpdatePromptTemplate {
+                description: None,
+                content: Some(old.content),
+            },
+        )
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM prompt_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl PromptTemplateVersion {
+    /// All versions of a template, newest first
+    pub async fn find_by_template_id(
+        pool: &SqlitePool,
+        template_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptTemplateVersion,
+            r#"SELECT id as "id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      version,
+                      content,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM prompt_template_versions
+               WHERE template_id = $1
+               ORDER BY version DESC"#,
+            template_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        template_id: Uuid,
+        version: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptTemplateVersion,
+            r#"SELECT id as "id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      version,
+                      content,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM prompt_template_versions
+               WHERE template_id = $1 AND version = $2"#,
+            template_id,
+            version
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
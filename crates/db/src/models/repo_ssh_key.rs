@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Authenticate with the keys of an ssh-agent, or with a single private key
+/// file such as a deploy key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SshKeyMode {
+    Agent,
+    KeyFile,
+}
+
+/// SSH identity used when fetching from and pushing to a repo's remotes
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoSshKey {
+    pub repo_id: Uuid,
+    pub mode: SshKeyMode,
+    /// Private key passed to `ssh -i` in `key_file` mode
+    pub key_path: Option<String>,
+    /// ssh-agent socket used in `agent` mode; defaults to `SSH_AUTH_SOCK`
+    pub agent_socket: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertRepoSshKey {
+    pub mode: SshKeyMode,
+    pub key_path: Option<String>,
+    pub agent_socket: Option<String>,
+}
+
+impl RepoSshKey {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSshKey,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      mode as "mode!: SshKeyMode",
+                      key_path,
+                      agent_socket,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_ssh_keys
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSshKey,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      mode as "mode!: SshKeyMode",
+                      key_path,
+                      agent_socket,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_ssh_keys"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertRepoSshKey,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSshKey,
+            r#"INSERT INTO repo_ssh_keys (repo_id, mode, key_path, agent_socket)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   mode = excluded.mode,
+                   key_path = excluded.key_path,
+                   agent_socket = excluded.agent_socket,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         mode as "mode!: SshKeyMode",
+                         key_path,
+                         agent_socket,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            data.mode,
+            &data.key_path,
+            &data.agent_socket
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, repo_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM repo_ssh_keys WHERE repo_id = $1", repo_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SecurityScanner {
+    CargoAudit,
+    NpmAudit,
+    Semgrep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Security scanners configured for a repo
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoSecurityAuditSettings {
+    pub repo_id: Uuid,
+    #[ts(type = "Array<SecurityScanner>")]
+    pub scanners: Json<Vec<SecurityScanner>>,
+    /// Value passed to `semgrep --config`; defaults to "auto"
+    pub semgrep_config: Option<String>,
+    /// Block auto-PR while high or critical findings are open
+    pub block_auto_pr: bool,
+    /// Post a summary of the findings on the attempt's pull request
+    pub comment_on_pr: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertRepoSecurityAuditSettings {
+    pub scanners: Vec<SecurityScanner>,
+    pub semgrep_config: Option<String>,
+    pub block_auto_pr: Option<bool>,
+    pub comment_on_pr: Option<bool>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct SecurityFinding {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub scanner: SecurityScanner,
+    pub severity: FindingSeverity,
+    pub rule_id: String,
+    pub title: String,
+    /// File and line for code findings, package@version for dependency findings
+    pub location: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Finding reported by a scanner, before it is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateSecurityFinding {
+    pub scanner: SecurityScanner,
+    pub severity: FindingSeverity,
+    pub rule_id: String,
+    pub title: String,
+    pub location: Option<String>,
+}
+
+impl RepoSecurityAuditSettings {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSecurityAuditSettings,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      scanners as "scanners!: Json<Vec<SecurityScanner>>",
+                      semgrep_config,
+                      block_auto_pr as "block_auto_pr!: bool",
+                      comment_on_pr as "comment_on_pr!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_security_audit_settings WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertRepoSecurityAuditSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSecurityAuditSettings,
+            r#"INSERT INTO repo_security_audit_settings
+                   (repo_id, scanners, semgrep_config, block_auto_pr, comment_on_pr)
+               VALUES ($1, $2, $3, COALESCE($4, 1), COALESCE($5, 1))
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   scanners = excluded.scanners,
+                   semgrep_config = excluded.semgrep_config,
+                   block_auto_pr = COALESCE($4, repo_security_audit_settings.block_auto_pr),
+                   comment_on_pr = COALESCE($5, repo_security_audit_settings.comment_on_pr),
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         scanners as "scanners!: Json<Vec<SecurityScanner>>",
+                         semgrep_config,
+                         block_auto_pr as "block_auto_pr!: bool",
+                         comment_on_pr as "comment_on_pr!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            Json(&data.scanners),
+            &data.semgrep_config,
+            data.block_auto_pr,
+            data.comment_on_pr
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, repo_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM repo_security_audit_settings WHERE repo_id = $1",
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl SecurityFinding {
+    /// Replace the stored findings of one workspace repo with a new audit's results
+    pub async fn replace_for_repo(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        findings: &[CreateSecurityFinding],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM security_findings WHERE workspace_id = $1 AND repo_id = $2",
+            workspace_id,
+            repo_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut stored = Vec::with_capacity(findings.len());
+        for finding in findings {
+            stored.push(
+                sqlx::query_as!(
+                    SecurityFinding,
+                    r#"INSERT INTO security_findings
+                           (id, workspace_id, repo_id, scanner, severity, rule_id, title, location)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                       RETURNING id as "id!: Uuid",
+                                 workspace_id as "workspace_id!: Uuid",
+                                 repo_id as "repo_id!: Uuid",
+                                 scanner as "scanner!: SecurityScanner",
+                                 severity as "severity!: FindingSeverity",
+                                 rule_id,
+                                 title,
+                                 location,
+                                 created_at as "created_at!: DateTime<Utc>""#,
+                    Uuid::new_v4(),
+                    workspace_id,
+                    repo_id,
+                    finding.scanner,
+                    finding.severity,
+                    &finding.rule_id,
+                    &finding.title,
+                    &finding.location
+                )
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+
+        tx.commit().await?;
+        Ok(stored)
+    }
+
+    /// Findings of the workspace, most severe first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SecurityFinding,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      scanner as "scanner!: SecurityScanner",
+                      severity as "severity!: FindingSeverity",
+                      rule_id,
+                      title,
+                      location,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM security_findings
+               WHERE workspace_id = $1
+               ORDER BY CASE severity
+                            WHEN 'critical' THEN 0 WHEN 'high' THEN 1 WHEN 'medium' THEN 2
+                            WHEN 'low' THEN 3 ELSE 4
+                        END,
+                        created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether high or critical findings block auto-PR for the workspace
+    pub async fn blocks_auto_pr(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM security_findings f
+                   JOIN repo_security_audit_settings s ON s.repo_id = f.repo_id
+                   WHERE f.workspace_id = $1 AND s.block_auto_pr = 1
+                     AND f.severity IN ('critical', 'high')
+               ) as "exists!: bool""#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A session of an attempt continued by another executor in a new session
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SessionHandoff {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub from_session_id: Uuid,
+    pub to_session_id: Uuid,
+    pub from_executor: Option<String>,
+    pub to_executor: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionHandoff {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionHandoff,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      from_session_id as "from_session_id!: Uuid",
+                      to_session_id as "to_session_id!: Uuid",
+                      from_executor,
+                      to_executor,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM session_handoffs WHERE workspace_id = $1 ORDER BY created_at"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        from_session_id: Uuid,
+        to_session_id: Uuid,
+        from_executor: Option<&str>,
+        to_executor: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            SessionHandoff,
+            r#"INSERT INTO session_handoffs
+                   (id, workspace_id, from_session_id, to_session_id, from_executor, to_executor)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         from_session_id as "from_session_id!: Uuid",
+                         to_session_id as "to_session_id!: Uuid",
+                         from_executor,
+                         to_executor,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            workspace_id,
+            from_session_id,
+            to_session_id,
+            from_executor,
+            to_executor
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// A shared task's fields as of the last sync, used as the common ancestor
+/// when local and remote edits are merged
+#[derive(Debug, Clone, FromRow)]
+pub struct SharedTaskSyncBase {
+    pub task_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub remote_updated_at: Option<DateTime<Utc>>,
+}
+
+impl SharedTaskSyncBase {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SharedTaskSyncBase,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      title,
+                      description,
+                      status as "status!: TaskStatus",
+                      remote_updated_at as "remote_updated_at?: DateTime<Utc>"
+               FROM shared_task_sync_bases WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(pool: &SqlitePool, base: &SharedTaskSyncBase) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO shared_task_sync_bases
+                   (task_id, title, description, status, remote_updated_at)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   title = excluded.title,
+                   description = excluded.description,
+                   status = excluded.status,
+                   remote_updated_at = excluded.remote_updated_at,
+                   updated_at = datetime('now', 'subsec')"#,
+            base.task_id,
+            &base.title,
+            &base.description,
+            &base.status,
+            base.remote_updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, Validate, ValidationErrors};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -25,6 +26,36 @@ pub struct UpdateTag {
     pub content: Option<String>,
 }
 
+fn validate_tag_name(errors: &mut ValidationErrors, tag_name: &str) {
+    errors.required_text("tag_name", tag_name, MAX_NAME_CHARS);
+    // Tags are inserted into prompts as @tag_name
+    if tag_name.chars().any(char::is_whitespace) {
+        errors.add("tag_name", "must not contain spaces");
+    }
+}
+
+impl Validate for CreateTag {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_tag_name(&mut errors, &self.tag_name);
+        errors.max_chars("content", &self.content, MAX_DESCRIPTION_CHARS);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateTag {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(tag_name) = &self.tag_name {
+            validate_tag_name(&mut errors, tag_name);
+        }
+        if let Some(content) = &self.content {
+            errors.max_chars("content", content, MAX_DESCRIPTION_CHARS);
+        }
+        errors.into_result()
+    }
+}
+
 impl Tag {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
@@ -1,14 +1,26 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use strum_macros::{Display, EnumString};
 use ts_rs::TS;
+use utils::validation::{MAX_DESCRIPTION_CHARS, MAX_TITLE_CHARS, Validate, ValidationErrors};
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{project::Project, task_label::TaskLabel, workspace::Workspace};
 
 #[derive(
-    Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
+    Debug,
+    Clone,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    TS,
+    JsonSchema,
+    EnumString,
+    Display,
+    Default,
 )]
 #[ts(export)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -23,7 +35,7 @@ pub enum TaskStatus {
     Cancelled,
 }
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS, JsonSchema)]
 pub struct Task {
     pub id: Uuid,
     pub project_id: Uuid, // Foreign key to Project
@@ -39,7 +51,7 @@ pub struct Task {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
 pub struct TaskWithAttemptStatus {
     #[serde(flatten)]
     #[ts(flatten)]
@@ -49,6 +61,18 @@ pub struct TaskWithAttemptStatus {
     pub executor: String,
     pub pr_number: Option<i64>,
     pub pr_url: Option<String>,
+    /// Summary of what the latest attempt changed
+    #[serde(default)]
+    pub latest_summary: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<TaskLabel>,
+    /// Items in the latest attempt's review checklist, if one was generated
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub checklist_items: Option<i64>,
+    /// Attempt promoted as canonical; follow-ups and PRs go to it
+    #[serde(default)]
+    pub canonical_workspace_id: Option<Uuid>,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -85,7 +109,7 @@ pub struct TaskRelationships {
     pub children: Vec<Task>,       // Tasks created from this workspace
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
 pub struct CreateTask {
     pub project_id: Uuid,
     pub title: String,
@@ -144,7 +168,7 @@ impl CreateTask {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS, JsonSchema)]
 pub struct UpdateTask {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -157,6 +181,65 @@ pub struct UpdateTask {
     pub label_ids: Option<Vec<Uuid>>,
 }
 
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct MoveTask {
+    /// Destination project; it must contain every repo the task's workspaces use
+    pub project_id: Uuid,
+}
+
+/// Highest iteration cap accepted for the Ralph Wiggum loop
+pub const MAX_RALPH_ITERATIONS: i64 = 100;
+
+fn validate_ralph(
+    errors: &mut ValidationErrors,
+    max_iterations: Option<i64>,
+    completion_promise: Option<&str>,
+) {
+    if let Some(max) = max_iterations
+        && !(1..=MAX_RALPH_ITERATIONS).contains(&max)
+    {
+        errors.add(
+            "ralph_max_iterations",
+            format!("must be between 1 and {MAX_RALPH_ITERATIONS}"),
+        );
+    }
+    if let Some(promise) = completion_promise {
+        errors.max_chars("ralph_completion_promise", promise, MAX_TITLE_CHARS);
+    }
+}
+
+impl Validate for CreateTask {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("title", &self.title, MAX_TITLE_CHARS);
+        if let Some(description) = &self.description {
+            errors.max_chars("description", description, MAX_DESCRIPTION_CHARS);
+        }
+        validate_ralph(
+            &mut errors,
+            self.ralph_max_iterations,
+            self.ralph_completion_promise.as_deref(),
+        );
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateTask {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.optional_text("title", self.title.as_deref(), MAX_TITLE_CHARS);
+        if let Some(description) = &self.description {
+            errors.max_chars("description", description, MAX_DESCRIPTION_CHARS);
+        }
+        validate_ralph(
+            &mut errors,
+            self.ralph_max_iterations,
+            self.ralph_completion_promise.as_deref(),
+        );
+        errors.into_result()
+    }
+}
+
 impl Task {
     pub fn to_prompt(&self) -> String {
         let base_prompt =
@@ -199,89 +282,100 @@ The task will loop until you output the completion signal or reach the iteration
         Project::find_by_id(pool, self.project_id).await
     }
 
+    /// Board rows for a project. Per-task aggregates are computed once per
+    /// project in CTEs rather than in correlated subqueries per task, and
+    /// labels come from one batched query.
     pub async fn find_by_project_id_with_attempt_status(
         pool: &SqlitePool,
         project_id: Uuid,
     ) -> Result<Vec<TaskWithAttemptStatus>, sqlx::Error> {
         let records = sqlx::query!(
-            r#"SELECT
-  t.id                            AS "id!: Uuid",
-  t.project_id                    AS "project_id!: Uuid",
-  t.title,
-  t.description,
-  t.status                        AS "status!: TaskStatus",
-  t.parent_workspace_id           AS "parent_workspace_id: Uuid",
-  t.shared_task_id                AS "shared_task_id: Uuid",
-  t.use_ralph_wiggum              AS "use_ralph_wiggum!: bool",
-  t.ralph_max_iterations          AS "ralph_max_iterations: i64",
-  t.ralph_completion_promise      AS "ralph_completion_promise: String",
-  t.created_at                    AS "created_at!: DateTime<Utc>",
-  t.updated_at                    AS "updated_at!: DateTime<Utc>",
-
-  CASE WHEN EXISTS (
-    SELECT 1
-      FROM workspaces w
-      JOIN sessions s ON s.workspace_id = w.id
-      JOIN execution_processes ep ON ep.session_id = s.id
-     WHERE w.task_id       = t.id
-       AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
-     LIMIT 1
-  ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
-
-  CASE WHEN (
-    SELECT ep.status
+            r#"WITH project_workspaces AS (
+    SELECT w.id, w.task_id, w.summary, w.review_checklist, w.updated_at
       FROM workspaces w
-      JOIN sessions s ON s.workspace_id = w.id
+      JOIN tasks t ON t.id = w.task_id
+     WHERE t.project_id = $1
+),
+attempt_processes AS (
+    SELECT pw.task_id, ep.status,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY ep.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN sessions s ON s.workspace_id = pw.id
       JOIN execution_processes ep ON ep.session_id = s.id
-     WHERE w.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
-     ORDER BY ep.created_at DESC
-     LIMIT 1
-  ) IN ('failed','killed') THEN 1 ELSE 0 END
-                                 AS "last_attempt_failed!: i64",
-
-  COALESCE(
-    ( SELECT s.executor
-        FROM workspaces w
-        JOIN sessions s ON s.workspace_id = w.id
-        WHERE w.task_id = t.id
-       ORDER BY s.created_at DESC
-        LIMIT 1
-      ), 'unknown'
-    )                               AS "executor!: String",
-
-  ( SELECT m.pr_number
-      FROM workspaces w
-      JOIN merges m ON m.workspace_id = w.id
-     WHERE w.task_id = t.id
-       AND m.merge_type = 'pr'
-       AND m.pr_status = 'open'
-     ORDER BY m.created_at DESC
-     LIMIT 1
-    )                               AS "pr_number: i64",
-
-  ( SELECT m.pr_url
-      FROM workspaces w
-      JOIN merges m ON m.workspace_id = w.id
-     WHERE w.task_id = t.id
-       AND m.merge_type = 'pr'
-       AND m.pr_status = 'open'
-     ORDER BY m.created_at DESC
-     LIMIT 1
-    )                               AS "pr_url: String"
-
-FROM tasks t
-WHERE t.project_id = $1
-ORDER BY t.created_at DESC"#,
+     WHERE ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+),
+attempt_status AS (
+    SELECT task_id,
+           MAX(status = 'running') AS has_running,
+           MAX(rn = 1 AND status IN ('failed','killed')) AS last_failed
+      FROM attempt_processes
+     GROUP BY task_id
+),
+latest_sessions AS (
+    SELECT pw.task_id, s.executor,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY s.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN sessions s ON s.workspace_id = pw.id
+),
+open_prs AS (
+    SELECT pw.task_id, m.pr_number, m.pr_url,
+           ROW_NUMBER() OVER (PARTITION BY pw.task_id ORDER BY m.created_at DESC) AS rn
+      FROM project_workspaces pw
+      JOIN merges m ON m.workspace_id = pw.id
+     WHERE m.merge_type = 'pr' AND m.pr_status = 'open'
+),
+summaries AS (
+    SELECT task_id, summary,
+           ROW_NUMBER() OVER (PARTITION BY task_id ORDER BY updated_at DESC) AS rn
+      FROM project_workspaces
+     WHERE summary IS NOT NULL
+),
+checklists AS (
+    SELECT task_id, json_array_length(review_checklist, '$.items') AS items,
+           ROW_NUMBER() OVER (PARTITION BY task_id ORDER BY updated_at DESC) AS rn
+      FROM project_workspaces
+     WHERE review_checklist IS NOT NULL
+)
+SELECT t.id                             AS "id!: Uuid",
+       t.project_id                     AS "project_id!: Uuid",
+       t.title,
+       t.description,
+       t.status                         AS "status!: TaskStatus",
+       t.parent_workspace_id            AS "parent_workspace_id: Uuid",
+       t.shared_task_id                 AS "shared_task_id: Uuid",
+       t.use_ralph_wiggum               AS "use_ralph_wiggum!: bool",
+       t.ralph_max_iterations           AS "ralph_max_iterations: i64",
+       t.ralph_completion_promise       AS "ralph_completion_promise: String",
+       t.created_at                     AS "created_at!: DateTime<Utc>",
+       t.updated_at                     AS "updated_at!: DateTime<Utc>",
+       COALESCE(a.has_running, 0)       AS "has_in_progress_attempt!: bool",
+       COALESCE(a.last_failed, 0)       AS "last_attempt_failed!: bool",
+       COALESCE(ls.executor, 'unknown') AS "executor!: String",
+       pr.pr_number                     AS "pr_number?: i64",
+       pr.pr_url                        AS "pr_url?: String",
+       su.summary                       AS "latest_summary?: String",
+       cl.items                         AS "checklist_items?: i64",
+       cw.id                            AS "canonical_workspace_id?: Uuid"
+  FROM tasks t
+  LEFT JOIN attempt_status a   ON a.task_id = t.id
+  LEFT JOIN latest_sessions ls ON ls.task_id = t.id AND ls.rn = 1
+  LEFT JOIN open_prs pr        ON pr.task_id = t.id AND pr.rn = 1
+  LEFT JOIN summaries su       ON su.task_id = t.id AND su.rn = 1
+  LEFT JOIN checklists cl      ON cl.task_id = t.id AND cl.rn = 1
+  LEFT JOIN workspaces cw      ON cw.task_id = t.id AND cw.canonical = 1
+ WHERE t.project_id = $1
+ ORDER BY t.created_at DESC"#,
             project_id
         )
         .fetch_all(pool)
         .await?;
 
+        let mut labels = TaskLabel::find_by_project_tasks(pool, project_id).await?;
+
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
+                labels: labels.remove(&rec.id).unwrap_or_default(),
                 task: Task {
                     id: rec.id,
                     project_id: rec.project_id,
@@ -296,11 +390,14 @@ ORDER BY t.created_at DESC"#,
                     created_at: rec.created_at,
                     updated_at: rec.updated_at,
                 },
-                has_in_progress_attempt: rec.has_in_progress_attempt != 0,
-                last_attempt_failed: rec.last_attempt_failed != 0,
+                has_in_progress_attempt: rec.has_in_progress_attempt,
+                last_attempt_failed: rec.last_attempt_failed,
                 executor: rec.executor,
                 pr_number: rec.pr_number,
                 pr_url: rec.pr_url,
+                latest_summary: rec.latest_summary,
+                checklist_items: rec.checklist_items,
+                canonical_workspace_id: rec.canonical_workspace_id,
             })
             .collect();
 
@@ -471,8 +568,65 @@ ORDER BY t.updated_at DESC"#
         .await
     }
 
-    pub async fn create(
+    pub async fn find_by_project_id(
         pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      status as "status!: TaskStatus",
+                      parent_workspace_id as "parent_workspace_id?: Uuid",
+                      shared_task_id as "shared_task_id?: Uuid",
+                      use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                      ralph_max_iterations,
+                      ralph_completion_promise,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks of a project that are neither shared nor finished, oldest first
+    pub async fn find_open_unshared_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      status as "status!: TaskStatus",
+                      parent_workspace_id as "parent_workspace_id?: Uuid",
+                      shared_task_id as "shared_task_id?: Uuid",
+                      use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                      ralph_max_iterations,
+                      ralph_completion_promise,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+                 AND shared_task_id IS NULL
+                 AND status NOT IN ('done', 'cancelled')
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateTask,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
@@ -494,7 +648,7 @@ ORDER BY t.updated_at DESC"#
             data.ralph_max_iterations,
             data.ralph_completion_promise
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
     }
 
@@ -531,6 +685,35 @@ ORDER BY t.updated_at DESC"#
         .await
     }
 
+    pub async fn update_description(
+        pool: &SqlitePool,
+        id: Uuid,
+        description: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks
+               SET description = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         status as "status!: TaskStatus",
+                         parent_workspace_id as "parent_workspace_id?: Uuid",
+                         shared_task_id as "shared_task_id?: Uuid",
+                         use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                         ralph_max_iterations,
+                         ralph_completion_promise,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            description
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
@@ -548,7 +731,7 @@ ORDER BY t.updated_at DESC"#
 
     /// Update the parent_workspace_id field for a task
     pub async fn update_parent_workspace_id(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         task_id: Uuid,
         parent_workspace_id: Option<Uuid>,
     ) -> Result<(), sqlx::Error> {
@@ -557,7 +740,7 @@ ORDER BY t.updated_at DESC"#
             task_id,
             parent_workspace_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
@@ -602,6 +785,67 @@ ORDER BY t.updated_at DESC"#
         Ok(result.rows_affected())
     }
 
+    /// Reassign a task to another project; workspaces follow through their task_id
+    pub async fn move_to_project(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks
+               SET project_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         status as "status!: TaskStatus",
+                         parent_workspace_id as "parent_workspace_id?: Uuid",
+                         shared_task_id as "shared_task_id?: Uuid",
+                         use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                         ralph_max_iterations,
+                         ralph_completion_promise,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Cancel a task merged into another, replacing its description with one
+    /// that links to the surviving task
+    pub async fn cancel_as_duplicate(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+        description: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks
+               SET status = 'cancelled', description = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         status as "status!: TaskStatus",
+                         parent_workspace_id as "parent_workspace_id?: Uuid",
+                         shared_task_id as "shared_task_id?: Uuid",
+                         use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                         ralph_max_iterations,
+                         ralph_completion_promise,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            description
+        )
+        .fetch_one(executor)
+        .await
+    }
+
     pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<u64, sqlx::Error>
     where
         E: Executor<'e, Database = Sqlite>,
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskActivityKind {
+    /// A shared task was edited here and by a teammate since the last sync
+    SyncConflict,
+}
+
+/// A notable event in a task's history
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskActivity {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub kind: TaskActivityKind,
+    /// Task field the event concerns
+    pub field: Option<String>,
+    pub local_value: Option<String>,
+    pub remote_value: Option<String>,
+    /// Value the task was left with
+    pub resolved_value: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTaskActivity {
+    pub kind: TaskActivityKind,
+    pub field: Option<String>,
+    pub local_value: Option<String>,
+    pub remote_value: Option<String>,
+    pub resolved_value: Option<String>,
+}
+
+impl TaskActivity {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskActivity,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      kind as "kind!: TaskActivityKind",
+                      field,
+                      local_value,
+                      remote_value,
+                      resolved_value,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_activity WHERE task_id = $1 ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskActivity,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskActivity,
+            r#"INSERT INTO task_activity
+                   (id, task_id, kind, field, local_value, remote_value, resolved_value)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         kind as "kind!: TaskActivityKind",
+                         field,
+                         local_value,
+                         remote_value,
+                         resolved_value,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            task_id,
+            data.kind,
+            &data.field,
+            &data.local_value,
+            &data.remote_value,
+            &data.resolved_value
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
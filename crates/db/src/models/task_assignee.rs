@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Local user a task is assigned to
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAssignee {
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub assigned_at: DateTime<Utc>,
+}
+
+impl TaskAssignee {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAssignee,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      user_id as "user_id!: Uuid",
+                      assigned_at as "assigned_at!: DateTime<Utc>"
+               FROM task_assignees WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Assign the task to `user_id`, or unassign it with `None`
+    pub async fn set(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(user_id) = user_id else {
+            sqlx::query!("DELETE FROM task_assignees WHERE task_id = $1", task_id)
+                .execute(pool)
+                .await?;
+            return Ok(None);
+        };
+        sqlx::query_as!(
+            TaskAssignee,
+            r#"INSERT INTO task_assignees (task_id, user_id) VALUES ($1, $2)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   user_id = excluded.user_id,
+                   assigned_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         user_id as "user_id!: Uuid",
+                         assigned_at as "assigned_at!: DateTime<Utc>""#,
+            task_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+        .map(Some)
+    }
+
+    /// Assignee of every assigned task of a project
+    pub async fn find_by_project_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Uuid>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskAssignee,
+            r#"SELECT ta.task_id as "task_id!: Uuid",
+                      ta.user_id as "user_id!: Uuid",
+                      ta.assigned_at as "assigned_at!: DateTime<Utc>"
+               FROM task_assignees ta
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.task_id, row.user_id))
+            .collect())
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A file kept with a task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAttachment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// File name inside the attachments directory
+    #[serde(skip)]
+    #[ts(skip)]
+    pub file_path: String,
+    pub original_name: String,
+    pub mime_type: Option<String>,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreateTaskAttachment {
+    pub file_path: String,
+    pub original_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+}
+
+impl TaskAttachment {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      file_path,
+                      original_name,
+                      mime_type,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments WHERE task_id = $1 ORDER BY created_at"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      file_path,
+                      original_name,
+                      mime_type,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskAttachment,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttachment,
+            r#"INSERT INTO task_attachments (id, task_id, file_path, original_name, mime_type, size_bytes)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         file_path,
+                         original_name,
+                         mime_type,
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            task_id,
+            &data.file_path,
+            &data.original_name,
+            &data.mime_type,
+            data.size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// File names of all attachments, to find files left behind by deleted
+    /// tasks
+    pub async fn all_file_paths(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT file_path FROM task_attachments")
+            .fetch_all(pool)
+            .await
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::validation::{MAX_TITLE_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+const MAX_CONTEXT_ITEMS: usize = 50;
+const MAX_DOC_CHARS: usize = 50_000;
+
+/// How a context pack reaches the coding agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ContextDelivery {
+    /// Appended to the attempt's initial prompt
+    #[default]
+    Prompt,
+    /// Written to a file in the workspace that the prompt points to
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContextItem {
+    /// File contents; the path is relative to the workspace, so it starts
+    /// with the repo name when the project has several repos
+    File {
+        path: String,
+    },
+    /// A listing of the directory's files
+    Directory {
+        path: String,
+    },
+    PullRequest {
+        url: String,
+        title: Option<String>,
+    },
+    /// Notes or a link to documentation
+    Doc {
+        title: String,
+        url: Option<String>,
+        content: Option<String>,
+    },
+}
+
+/// Context attached to a task and rendered for each of its attempts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskContextPack {
+    pub task_id: Uuid,
+    pub delivery: ContextDelivery,
+    #[ts(type = "Array<ContextItem>")]
+    pub items: Json<Vec<ContextItem>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertTaskContextPack {
+    pub delivery: Option<ContextDelivery>,
+    pub items: Vec<ContextItem>,
+}
+
+impl Validate for UpsertTaskContextPack {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.items.len() > MAX_CONTEXT_ITEMS {
+            errors.add(
+                "items",
+                format!("must have at most {MAX_CONTEXT_ITEMS} entries"),
+            );
+        }
+        for (i, item) in self.items.iter().enumerate() {
+            let field = format!("items.{i}");
+            match item {
+                ContextItem::File { path } | ContextItem::Directory { path } => {
+                    if path.trim().is_empty() {
+                        errors.add(&field, "path must not be empty");
+                    } else if path.split(['/', '\\']).any(|part| part == "..") {
+                        errors.add(&field, "path must stay inside the workspace");
+                    }
+                }
+                ContextItem::PullRequest { url, .. } => {
+                    if !(url.starts_with("https://") || url.starts_with("http://")) {
+                        errors.add(&field, "url must be an http(s) link");
+                    }
+                }
+                ContextItem::Doc { title, content, .. } => {
+                    errors.required_text(&field, title, MAX_TITLE_CHARS);
+                    if let Some(content) = content {
+                        errors.max_chars(&field, content, MAX_DOC_CHARS);
+                    }
+                }
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl TaskContextPack {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskContextPack,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      delivery as "delivery!: ContextDelivery",
+                      items as "items!: Json<Vec<ContextItem>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_context_packs WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &UpsertTaskContextPack,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskContextPack,
+            r#"INSERT INTO task_context_packs (task_id, delivery, items)
+               VALUES ($1, COALESCE($2, 'prompt'), $3)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   delivery = COALESCE($2, task_context_packs.delivery),
+                   items = excluded.items,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         delivery as "delivery!: ContextDelivery",
+                         items as "items!: Json<Vec<ContextItem>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            data.delivery,
+            Json(&data.items)
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_context_packs WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+const MAX_ESTIMATE: f64 = 10_000.0;
+
+/// Size of a task in story points, hours, or both
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskEstimate {
+    pub task_id: Uuid,
+    pub points: Option<f64>,
+    pub hours: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertTaskEstimate {
+    pub points: Option<f64>,
+    pub hours: Option<f64>,
+}
+
+impl Validate for UpsertTaskEstimate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.points.is_none() && self.hours.is_none() {
+            errors.add("points", "points or hours is required");
+        }
+        for (field, value) in [("points", self.points), ("hours", self.hours)] {
+            if let Some(value) = value
+                && !(0.0..=MAX_ESTIMATE).contains(&value)
+            {
+                errors.add(field, format!("must be between 0 and {MAX_ESTIMATE}"));
+            }
+        }
+        errors.into_result()
+    }
+}
+
+impl TaskEstimate {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEstimate,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      points,
+                      hours,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_estimates WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &UpsertTaskEstimate,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEstimate,
+            r#"INSERT INTO task_estimates (task_id, points, hours)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   points = excluded.points,
+                   hours = excluded.hours,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         points,
+                         hours,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            data.points,
+            data.hours
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_estimates WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{merge::MergeStatus, task::TaskStatus};
+
+/// Child task created for one target of a fanned-out task, with the newest
+/// pull request opened from its attempts
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskFanOutChild {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Package directory the child is limited to, if any
+    pub path_scope: Option<String>,
+    #[ts(type = "number | null")]
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    pub pr_status: Option<MergeStatus>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Children of a fanned-out task with their pull requests rolled up
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskFanOut {
+    pub parent_task_id: Uuid,
+    pub children: Vec<TaskFanOutChild>,
+    #[ts(type = "number")]
+    pub open_prs: i64,
+    #[ts(type = "number")]
+    pub merged_prs: i64,
+    #[ts(type = "number")]
+    pub closed_prs: i64,
+    /// Children without a pull request yet
+    #[ts(type = "number")]
+    pub pending: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTaskFanOutChild {
+    pub parent_task_id: Uuid,
+    pub child_task_id: Uuid,
+    pub repo_id: Uuid,
+    pub path_scope: Option<String>,
+}
+
+impl TaskFanOut {
+    pub async fn add_child(
+        executor: impl Executor<'_, Database = Sqlite>,
+        data: &CreateTaskFanOutChild,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_fan_out_children
+                   (child_task_id, parent_task_id, repo_id, path_scope)
+               VALUES ($1, $2, $3, $4)"#,
+            data.child_task_id,
+            data.parent_task_id,
+            data.repo_id,
+            &data.path_scope
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// The fan-out of `parent_task_id`; it has no children when the task was
+    /// never fanned out
+    pub async fn find_by_parent_task_id(
+        pool: &SqlitePool,
+        parent_task_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let children = sqlx::query_as!(
+            TaskFanOutChild,
+            r#"SELECT f.child_task_id AS "task_id!: Uuid",
+                      t.title AS "title!: String",
+                      t.status AS "status!: TaskStatus",
+                      f.repo_id AS "repo_id!: Uuid",
+                      r.display_name AS "repo_name!: String",
+                      f.path_scope AS "path_scope?: String",
+                      pr.pr_number AS "pr_number?: i64",
+                      pr.pr_url AS "pr_url?: String",
+                      pr.pr_status AS "pr_status?: MergeStatus",
+                      f.created_at AS "created_at!: DateTime<Utc>"
+               FROM task_fan_out_children f
+               JOIN tasks t ON t.id = f.child_task_id
+               JOIN repos r ON r.id = f.repo_id
+               LEFT JOIN merges pr ON pr.id = (
+                   SELECT m.id FROM merges m
+                   JOIN workspaces w ON w.id = m.workspace_id
+                   WHERE w.task_id = f.child_task_id AND m.merge_type = 'pr'
+                   ORDER BY m.created_at DESC
+                   LIMIT 1
+               )
+               WHERE f.parent_task_id = $1
+               ORDER BY f.created_at ASC, r.display_name ASC"#,
+            parent_task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let (mut open_prs, mut merged_prs, mut closed_prs, mut pending) = (0, 0, 0, 0);
+        for child in &children {
+            match child.pr_status {
+                Some(MergeStatus::Open) => open_prs += 1,
+                Some(MergeStatus::Merged) => merged_prs += 1,
+                Some(MergeStatus::Closed) => closed_prs += 1,
+                Some(MergeStatus::Unknown) => {}
+                None => pending += 1,
+            }
+        }
+        Ok(Self {
+            parent_task_id,
+            children,
+            open_prs,
+            merged_prs,
+            closed_prs,
+            pending,
+        })
+    }
+
+    /// Parent task of a fan-out child, if `child_task_id` is one
+    pub async fn find_parent_task_id(
+        pool: &SqlitePool,
+        child_task_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT parent_task_id as "parent_task_id!: Uuid"
+               FROM task_fan_out_children WHERE child_task_id = $1"#,
+            child_task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Status the parent should have given its children's pull requests:
+    /// done once every child is merged or done, in review once every child
+    /// has a pull request open. `None` leaves the parent as it is.
+    pub fn parent_status(&self) -> Option<TaskStatus> {
+        if self.children.is_empty() {
+            return None;
+        }
+        let merged = |child: &TaskFanOutChild| {
+            matches!(child.pr_status, Some(MergeStatus::Merged)) || child.status == TaskStatus::Done
+        };
+        if self.children.iter().all(merged) {
+            return Some(TaskStatus::Done);
+        }
+        self.children
+            .iter()
+            .all(|child| merged(child) || matches!(child.pr_status, Some(MergeStatus::Open)))
+            .then_some(TaskStatus::InReview)
+    }
+}
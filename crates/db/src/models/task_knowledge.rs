@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+use super::{
+    file_embedding::FileEmbedding,
+    task::{Task, TaskStatus},
+};
+
+/// A completed task as remembered for similarity lookups
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskKnowledge {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Summaries of what the task's attempts changed
+    pub diff_summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub model: String,
+    pub vector: Vec<u8>,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpsertTaskKnowledge {
+    pub title: String,
+    pub description: Option<String>,
+    pub diff_summary: Option<String>,
+    pub pr_url: Option<String>,
+}
+
+impl TaskKnowledge {
+    pub fn decode(&self) -> Vec<f32> {
+        FileEmbedding::decode_vector(&self.vector)
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        model: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskKnowledge,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      diff_summary,
+                      pr_url,
+                      model,
+                      vector,
+                      completed_at as "completed_at!: DateTime<Utc>"
+               FROM task_knowledge WHERE project_id = $1 AND model = $2"#,
+            project_id,
+            model
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Done tasks of the project that are missing from the index, were
+    /// edited since they were indexed, or were indexed by another embedder
+    pub async fn find_unindexed(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        model: &str,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid",
+                      t.project_id as "project_id!: Uuid",
+                      t.title as "title!: String",
+                      t.description as "description?: String",
+                      t.status as "status!: TaskStatus",
+                      t.parent_workspace_id as "parent_workspace_id?: Uuid",
+                      t.shared_task_id as "shared_task_id?: Uuid",
+                      t.use_ralph_wiggum as "use_ralph_wiggum!: bool",
+                      t.ralph_max_iterations as "ralph_max_iterations?: i64",
+                      t.ralph_completion_promise as "ralph_completion_promise?: String",
+                      t.created_at as "created_at!: DateTime<Utc>",
+                      t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               LEFT JOIN task_knowledge k ON k.task_id = t.id
+               WHERE t.project_id = $1
+                 AND t.status = 'done'
+                 AND (k.task_id IS NULL OR k.model != $2 OR k.completed_at < t.updated_at)"#,
+            project_id,
+            model
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task: &Task,
+        data: &UpsertTaskKnowledge,
+        model: &str,
+        vector: &[f32],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_knowledge
+                   (task_id, project_id, title, description, diff_summary, pr_url, model, vector, completed_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8,
+                       (SELECT updated_at FROM tasks WHERE id = $1))
+               ON CONFLICT(task_id) DO UPDATE SET
+                   project_id = excluded.project_id,
+                   title = excluded.title,
+                   description = excluded.description,
+                   diff_summary = excluded.diff_summary,
+                   pr_url = excluded.pr_url,
+                   model = excluded.model,
+                   vector = excluded.vector,
+                   completed_at = excluded.completed_at,
+                   indexed_at = datetime('now', 'subsec')"#,
+            task.id,
+            task.project_id,
+            &data.title,
+            &data.description,
+            &data.diff_summary,
+            &data.pr_url,
+            model,
+            FileEmbedding::encode(vector)
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget tasks of the project that were reopened or moved elsewhere
+    pub async fn delete_stale(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM task_knowledge
+               WHERE project_id = $1
+                 AND task_id NOT IN (
+                     SELECT id FROM tasks WHERE project_id = $1 AND status = 'done'
+                 )"#,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
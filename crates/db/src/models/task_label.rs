@@ -1,10 +1,14 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
+use utils::validation::{MAX_NAME_CHARS, Validate, ValidationErrors};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct TaskLabel {
     pub id: Uuid,
@@ -15,7 +19,7 @@ pub struct TaskLabel {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct CreateTaskLabel {
     pub project_id: Uuid,
@@ -23,13 +27,33 @@ pub struct CreateTaskLabel {
     pub color: String,
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct UpdateTaskLabel {
     pub name: Option<String>,
     pub color: Option<String>,
 }
 
+impl Validate for CreateTaskLabel {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", &self.name, MAX_NAME_CHARS);
+        errors.hex_color("color", &self.color);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateTaskLabel {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.optional_text("name", self.name.as_deref(), MAX_NAME_CHARS);
+        if let Some(color) = &self.color {
+            errors.hex_color("color", color);
+        }
+        errors.into_result()
+    }
+}
+
 impl TaskLabel {
     /// obtener todas las etiquetas de un proyecto
     pub async fn find_by_project_id(
@@ -136,6 +160,43 @@ impl TaskLabel {
         .await
     }
 
+    /// etiquetas de todas las tareas de un proyecto, agrupadas por tarea
+    pub async fn find_by_project_tasks(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Vec<Self>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT tla.task_id as "task_id!: Uuid",
+                      tl.id as "id!: Uuid",
+                      tl.project_id as "project_id!: Uuid",
+                      tl.name,
+                      tl.color,
+                      tl.created_at as "created_at!: DateTime<Utc>",
+                      tl.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_label_associations tla
+               INNER JOIN task_labels tl ON tl.id = tla.label_id
+               INNER JOIN tasks t ON t.id = tla.task_id
+               WHERE t.project_id = $1
+               ORDER BY tl.name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut labels: HashMap<Uuid, Vec<Self>> = HashMap::new();
+        for row in rows {
+            labels.entry(row.task_id).or_default().push(TaskLabel {
+                id: row.id,
+                project_id: row.project_id,
+                name: row.name,
+                color: row.color,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            });
+        }
+        Ok(labels)
+    }
+
     /// asociar etiqueta con tarea
     pub async fn associate_with_task(
         pool: &SqlitePool,
@@ -152,6 +213,25 @@ impl TaskLabel {
         Ok(())
     }
 
+    /// asociar varias etiquetas con una tarea, ignorando duplicados
+    pub async fn associate_many(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        task_id: Uuid,
+        label_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+        for label_id in label_ids {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO task_label_associations (task_id, label_id) VALUES ($1, $2)",
+                task_id,
+                label_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
     /// desasociar etiqueta de tarea
     pub async fn disassociate_from_task(
         pool: &SqlitePool,
@@ -168,6 +248,148 @@ impl TaskLabel {
         Ok(())
     }
 
+    /// buscar etiqueta por nombre (sin distinguir mayúsculas) o crearla con
+    /// el color dado
+    pub async fn find_or_create_by_name(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        project_id: Uuid,
+        name: &str,
+        color: &str,
+    ) -> Result<Uuid, sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid"
+               FROM task_labels WHERE project_id = $1 AND name = $2 COLLATE NOCASE"#,
+            project_id,
+            name
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+        sqlx::query_scalar!(
+            r#"INSERT INTO task_labels (id, project_id, name, color)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid""#,
+            Uuid::new_v4(),
+            project_id,
+            name,
+            color
+        )
+        .fetch_one(&mut *conn)
+        .await
+    }
+
+    /// cambiar las etiquetas de una tarea por las del mismo nombre en otro
+    /// proyecto, creando las que falten con el mismo color
+    pub async fn remap_to_project(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+        let current = sqlx::query_as!(
+            TaskLabel,
+            r#"SELECT tl.id as "id!: Uuid",
+                      tl.project_id as "project_id!: Uuid",
+                      tl.name,
+                      tl.color,
+                      tl.created_at as "created_at!: DateTime<Utc>",
+                      tl.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               INNER JOIN task_label_associations tla ON tla.label_id = tl.id
+               WHERE tla.task_id = $1 AND tl.project_id != $2"#,
+            task_id,
+            project_id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for label in current {
+            let existing = sqlx::query_scalar!(
+                r#"SELECT id as "id!: Uuid"
+                   FROM task_labels WHERE project_id = $1 AND name = $2 COLLATE NOCASE"#,
+                project_id,
+                &label.name
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+            let target_id = match existing {
+                Some(id) => id,
+                None => {
+                    sqlx::query_scalar!(
+                        r#"INSERT INTO task_labels (id, project_id, name, color)
+                           VALUES ($1, $2, $3, $4)
+                           RETURNING id as "id!: Uuid""#,
+                        Uuid::new_v4(),
+                        project_id,
+                        &label.name,
+                        &label.color
+                    )
+                    .fetch_one(&mut *conn)
+                    .await?
+                }
+            };
+
+            sqlx::query!(
+                "DELETE FROM task_label_associations WHERE task_id = $1 AND label_id = $2",
+                task_id,
+                label.id
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query!(
+                "INSERT OR IGNORE INTO task_label_associations (task_id, label_id) VALUES ($1, $2)",
+                task_id,
+                target_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        sqlx::query_as!(
+            TaskLabel,
+            r#"SELECT tl.id as "id!: Uuid",
+                      tl.project_id as "project_id!: Uuid",
+                      tl.name,
+                      tl.color,
+                      tl.created_at as "created_at!: DateTime<Utc>",
+                      tl.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               INNER JOIN task_label_associations tla ON tla.label_id = tl.id
+               WHERE tla.task_id = $1
+               ORDER BY tl.name ASC"#,
+            task_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+    }
+
+    /// mover las etiquetas de una tarea a otra del mismo proyecto,
+    /// ignorando las que ya tenga
+    pub async fn reassign_task(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        from_task_id: Uuid,
+        to_task_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+        sqlx::query!(
+            "UPDATE OR IGNORE task_label_associations SET task_id = $2 WHERE task_id = $1",
+            from_task_id,
+            to_task_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM task_label_associations WHERE task_id = $1",
+            from_task_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
     /// reemplazar todas las etiquetas de una tarea (útil para actualizaciones)
     pub async fn sync_task_labels(
         pool: &SqlitePool,
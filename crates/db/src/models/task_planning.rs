@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    TS,
+    EnumString,
+    Display,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+/// Scheduling details of a task, kept beside it on the board
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskPlanning {
+    pub task_id: Uuid,
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<NaiveDate>,
+    /// Coding agent to start the task with; the project default when unset
+    pub executor: Option<BaseCodingAgent>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct UpsertTaskPlanning {
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<NaiveDate>,
+    pub executor: Option<BaseCodingAgent>,
+}
+
+impl UpsertTaskPlanning {
+    pub fn is_empty(&self) -> bool {
+        self.priority.is_none() && self.due_date.is_none() && self.executor.is_none()
+    }
+}
+
+impl TaskPlanning {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPlanning,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      priority as "priority?: TaskPriority",
+                      due_date as "due_date?: NaiveDate",
+                      executor as "executor?: BaseCodingAgent",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_planning WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        conn: impl Executor<'_, Database = Sqlite>,
+        task_id: Uuid,
+        data: &UpsertTaskPlanning,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPlanning,
+            r#"INSERT INTO task_planning (task_id, priority, due_date, executor)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   priority = excluded.priority,
+                   due_date = excluded.due_date,
+                   executor = excluded.executor,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         priority as "priority?: TaskPriority",
+                         due_date as "due_date?: NaiveDate",
+                         executor as "executor?: BaseCodingAgent",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            data.priority,
+            data.due_date,
+            data.executor
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
@@ -0,0 +1,385 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "time_entry_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TimeEntrySource {
+    /// Timer started and stopped by a user
+    Manual,
+    /// Duration of a coding agent run
+    Agent,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TimeEntry {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// Who ran a manual timer; `None` for agent runs and in single-user mode
+    pub user_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    pub source: TimeEntrySource,
+    pub note: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the timer is running
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tracked seconds split by source; running timers count up to now
+#[derive(Debug, Clone, Default, FromRow, Serialize, TS)]
+pub struct TimeTotals {
+    #[ts(type = "number")]
+    pub manual_seconds: i64,
+    #[ts(type = "number")]
+    pub agent_seconds: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskTimeTotals {
+    pub task_id: Uuid,
+    pub task_title: String,
+    #[ts(type = "number")]
+    pub manual_seconds: i64,
+    #[ts(type = "number")]
+    pub agent_seconds: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct UserTimeTotals {
+    /// `None` groups agent runs and timers tracked in single-user mode
+    pub user_id: Option<Uuid>,
+    #[ts(type = "number")]
+    pub manual_seconds: i64,
+    #[ts(type = "number")]
+    pub agent_seconds: i64,
+}
+
+/// Time tracked on one task
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskTime {
+    pub totals: TimeTotals,
+    pub entries: Vec<TimeEntry>,
+}
+
+/// Time tracked on a project's tasks since a point in time
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectTime {
+    pub since: DateTime<Utc>,
+    pub totals: TimeTotals,
+    pub tasks: Vec<TaskTimeTotals>,
+    pub users: Vec<UserTimeTotals>,
+}
+
+impl TimeEntry {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TimeEntry,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      user_id as "user_id?: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      source as "source!: TimeEntrySource",
+                      note,
+                      started_at as "started_at!: DateTime<Utc>",
+                      ended_at as "ended_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM time_entries WHERE task_id = $1 ORDER BY started_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Manual timer of `user_id` on the task that has not been stopped yet
+    pub async fn find_running(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TimeEntry,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      user_id as "user_id?: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      source as "source!: TimeEntrySource",
+                      note,
+                      started_at as "started_at!: DateTime<Utc>",
+                      ended_at as "ended_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM time_entries
+             WHERE task_id = $1 AND user_id IS $2 AND source = 'manual' AND ended_at IS NULL"#,
+            task_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn start(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        user_id: Option<Uuid>,
+        note: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TimeEntry,
+            r#"INSERT INTO time_entries (id, task_id, user_id, source, note, started_at)
+             VALUES ($1, $2, $3, 'manual', $4, $5)
+             RETURNING id as "id!: Uuid",
+                       task_id as "task_id!: Uuid",
+                       user_id as "user_id?: Uuid",
+                       execution_process_id as "execution_process_id?: Uuid",
+                       source as "source!: TimeEntrySource",
+                       note,
+                       started_at as "started_at!: DateTime<Utc>",
+                       ended_at as "ended_at?: DateTime<Utc>",
+                       created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            task_id,
+            user_id,
+            note,
+            Utc::now()
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn stop(pool: &SqlitePool, id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TimeEntry,
+            r#"UPDATE time_entries SET ended_at = $2 WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         user_id as "user_id?: Uuid",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         source as "source!: TimeEntrySource",
+                         note,
+                         started_at as "started_at!: DateTime<Utc>",
+                         ended_at as "ended_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            Utc::now()
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Add the entry of a finished coding agent run; other runs and runs that
+    /// already have one are left alone
+    pub async fn record_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO time_entries (id, task_id, execution_process_id, source, started_at, ended_at)
+               SELECT $1, w.task_id, ep.id, 'agent', ep.started_at, ep.completed_at
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE ep.id = $2 AND ep.run_reason = 'codingagent'
+                 AND ep.completed_at IS NOT NULL
+               ON CONFLICT (execution_process_id) DO NOTHING"#,
+            Uuid::new_v4(),
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl TaskTime {
+    pub async fn collect(pool: &SqlitePool, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        // Seconds per source, counting running timers up to now
+        let totals = sqlx::query_as!(
+            TimeTotals,
+            r#"SELECT CAST(COALESCE(SUM(CASE WHEN e.source = 'manual' THEN
+                          (julianday(COALESCE(e.ended_at, datetime('now', 'subsec')))
+                              - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "manual_seconds!: i64",
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'agent' THEN
+                          (julianday(e.ended_at) - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "agent_seconds!: i64"
+               FROM time_entries e WHERE e.task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        let entries = TimeEntry::find_by_task_id(pool, task_id).await?;
+        Ok(Self { totals, entries })
+    }
+}
+
+impl ProjectTime {
+    /// Time of the entries started since `since` on the project's tasks
+    pub async fn collect(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        // Seconds per source, counting running timers up to now
+        let totals = sqlx::query_as!(
+            TimeTotals,
+            r#"SELECT CAST(COALESCE(SUM(CASE WHEN e.source = 'manual' THEN
+                          (julianday(COALESCE(e.ended_at, datetime('now', 'subsec')))
+                              - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "manual_seconds!: i64",
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'agent' THEN
+                          (julianday(e.ended_at) - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "agent_seconds!: i64"
+               FROM time_entries e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1 AND datetime(e.started_at) >= datetime($2)"#,
+            project_id,
+            since
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let tasks = sqlx::query_as!(
+            TaskTimeTotals,
+            r#"SELECT t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'manual' THEN
+                          (julianday(COALESCE(e.ended_at, datetime('now', 'subsec')))
+                              - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "manual_seconds!: i64",
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'agent' THEN
+                          (julianday(e.ended_at) - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "agent_seconds!: i64"
+               FROM time_entries e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1 AND datetime(e.started_at) >= datetime($2)
+               GROUP BY t.id
+               ORDER BY "manual_seconds!: i64" + "agent_seconds!: i64" DESC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let users = sqlx::query_as!(
+            UserTimeTotals,
+            r#"SELECT e.user_id AS "user_id?: Uuid",
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'manual' THEN
+                          (julianday(COALESCE(e.ended_at, datetime('now', 'subsec')))
+                              - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "manual_seconds!: i64",
+                      CAST(COALESCE(SUM(CASE WHEN e.source = 'agent' THEN
+                          (julianday(e.ended_at) - julianday(e.started_at)) * 86400
+                      END), 0) AS INTEGER) AS "agent_seconds!: i64"
+               FROM time_entries e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1 AND datetime(e.started_at) >= datetime($2)
+               GROUP BY e.user_id
+               ORDER BY "manual_seconds!: i64" + "agent_seconds!: i64" DESC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            since,
+            totals,
+            tasks,
+            users,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DBService, fixtures,
+        models::execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    };
+
+    #[tokio::test]
+    async fn tracks_manual_timers_and_finished_agent_runs() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project = fixtures::project(pool).await;
+        let task = fixtures::task(pool, project.id).await;
+
+        let user_id = Some(Uuid::new_v4());
+        let timer = TimeEntry::start(pool, task.id, user_id, Some("review"))
+            .await
+            .unwrap();
+        assert_eq!(
+            TimeEntry::find_running(pool, task.id, user_id)
+                .await
+                .unwrap()
+                .map(|entry| entry.id),
+            Some(timer.id)
+        );
+        // Timers belong to the user who started them
+        assert!(
+            TimeEntry::find_running(pool, task.id, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let stopped = TimeEntry::stop(pool, timer.id).await.unwrap();
+        assert!(stopped.ended_at.is_some());
+        assert!(
+            TimeEntry::find_running(pool, task.id, user_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let workspace = fixtures::workspace(pool, task.id, "vk/time").await;
+        let process = fixtures::coding_agent_run(pool, workspace.id, None).await;
+
+        // Runs only count once they finish, and only once
+        TimeEntry::record_execution(pool, process.id).await.unwrap();
+        assert_eq!(
+            TaskTime::collect(pool, task.id)
+                .await
+                .unwrap()
+                .entries
+                .len(),
+            1
+        );
+        ExecutionProcess::update_completion(
+            pool,
+            process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        TimeEntry::record_execution(pool, process.id).await.unwrap();
+        TimeEntry::record_execution(pool, process.id).await.unwrap();
+
+        let time = TaskTime::collect(pool, task.id).await.unwrap();
+        let agent_entries: Vec<_> = time
+            .entries
+            .iter()
+            .filter(|entry| entry.source == TimeEntrySource::Agent)
+            .collect();
+        assert_eq!(time.entries.len(), 2);
+        assert_eq!(agent_entries.len(), 1);
+        assert_eq!(agent_entries[0].execution_process_id, Some(process.id));
+        assert_eq!(agent_entries[0].user_id, None);
+
+        let project_time =
+            ProjectTime::collect(pool, project.id, Utc::now() - chrono::Duration::hours(1))
+                .await
+                .unwrap();
+        assert_eq!(project_time.tasks.len(), 1);
+        let mut users: Vec<_> = project_time.users.iter().map(|u| u.user_id).collect();
+        users.sort();
+        assert_eq!(users, vec![None, user_id]);
+    }
+}
@@ -0,0 +1,271 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Work completed in one window of a velocity series
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct VelocityWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub completed_tasks: i64,
+    /// Estimates of the completed tasks; unestimated tasks count as zero
+    pub completed_points: f64,
+    pub completed_hours: f64,
+    /// Time coding agents spent running in the window
+    #[ts(type = "number")]
+    pub agent_seconds: i64,
+}
+
+/// Completed work per rolling window, oldest window first
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectVelocity {
+    pub window_days: u32,
+    pub windows: Vec<VelocityWindow>,
+    pub avg_tasks: f64,
+    pub avg_points: f64,
+    pub avg_hours: f64,
+    pub avg_agent_hours: f64,
+}
+
+/// Open tasks of a project and their estimates
+#[derive(Debug, Clone, Default, FromRow, Serialize, TS)]
+pub struct RemainingWork {
+    #[ts(type = "number")]
+    pub tasks: i64,
+    #[ts(type = "number")]
+    pub unestimated_tasks: i64,
+    pub points: f64,
+    pub hours: f64,
+}
+
+/// When the open tasks are expected to be done at the current pace; each date
+/// is `None` when there is nothing to measure the pace by
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectForecast {
+    pub velocity: ProjectVelocity,
+    pub remaining: RemainingWork,
+    /// From the points completed per window
+    pub by_points: Option<DateTime<Utc>>,
+    /// From the number of tasks completed per window
+    pub by_tasks: Option<DateTime<Utc>>,
+    /// From the hour estimates left and the hours agents run per window
+    pub by_agent_hours: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct CompletedTask {
+    completed_at: DateTime<Utc>,
+    points: Option<f64>,
+    hours: Option<f64>,
+}
+
+#[derive(FromRow)]
+struct AgentRun {
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+}
+
+impl ProjectVelocity {
+    /// Velocity over the `windows` windows of `window_days` days ending now.
+    /// A task counts as completed when it was last updated while done.
+    pub async fn collect(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        window_days: u32,
+        windows: u32,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+        let since = now - Duration::days(i64::from(window_days) * i64::from(windows));
+
+        let completed = sqlx::query_as!(
+            CompletedTask,
+            r#"SELECT t.updated_at AS "completed_at!: DateTime<Utc>",
+                      e.points AS "points?: f64",
+                      e.hours AS "hours?: f64"
+               FROM tasks t
+               LEFT JOIN task_estimates e ON e.task_id = t.id
+               WHERE t.project_id = $1 AND t.status = 'done'
+                 AND datetime(t.updated_at) >= datetime($2)"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let agent_runs = sqlx::query_as!(
+            AgentRun,
+            r#"SELECT e.started_at as "started_at!: DateTime<Utc>",
+                      e.ended_at as "ended_at!: DateTime<Utc>"
+               FROM time_entries e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1 AND e.source = 'agent' AND e.ended_at IS NOT NULL
+                 AND datetime(e.started_at) >= datetime($2)"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self::from_samples(
+            now,
+            window_days,
+            windows,
+            &completed,
+            &agent_runs,
+        ))
+    }
+
+    fn from_samples(
+        now: DateTime<Utc>,
+        window_days: u32,
+        windows: u32,
+        completed: &[CompletedTask],
+        agent_runs: &[AgentRun],
+    ) -> Self {
+        let length = Duration::days(i64::from(window_days));
+        let windows: Vec<VelocityWindow> = (0..windows)
+            .rev()
+            .map(|back| {
+                let end = now - length * back as i32;
+                let start = end - length;
+                let in_window = |at: DateTime<Utc>| at > start && at <= end;
+                let done: Vec<&CompletedTask> = completed
+                    .iter()
+                    .filter(|task| in_window(task.completed_at))
+                    .collect();
+                VelocityWindow {
+                    start,
+                    end,
+                    completed_tasks: done.len() as i64,
+                    completed_points: done.iter().filter_map(|task| task.points).sum(),
+                    completed_hours: done.iter().filter_map(|task| task.hours).sum(),
+                    agent_seconds: agent_runs
+                        .iter()
+                        .filter(|run| in_window(run.started_at))
+                        .map(|run| (run.ended_at - run.started_at).num_seconds().max(0))
+                        .sum(),
+                }
+            })
+            .collect();
+
+        let average = |value: fn(&VelocityWindow) -> f64| {
+            if windows.is_empty() {
+                0.0
+            } else {
+                windows.iter().map(value).sum::<f64>() / windows.len() as f64
+            }
+        };
+        Self {
+            window_days,
+            avg_tasks: average(|w| w.completed_tasks as f64),
+            avg_points: average(|w| w.completed_points),
+            avg_hours: average(|w| w.completed_hours),
+            avg_agent_hours: average(|w| w.agent_seconds as f64 / 3600.0),
+            windows,
+        }
+    }
+}
+
+impl RemainingWork {
+    pub async fn collect(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RemainingWork,
+            r#"SELECT COUNT(*) AS "tasks!: i64",
+                      COALESCE(SUM(e.task_id IS NULL), 0) AS "unestimated_tasks!: i64",
+                      COALESCE(SUM(e.points), 0.0) AS "points!: f64",
+                      COALESCE(SUM(e.hours), 0.0) AS "hours!: f64"
+               FROM tasks t
+               LEFT JOIN task_estimates e ON e.task_id = t.id
+               WHERE t.project_id = $1 AND t.status IN ('todo', 'inprogress', 'inreview')"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+/// Date the remaining amount is done at `per_window` per window
+fn completion_date(
+    now: DateTime<Utc>,
+    window_days: u32,
+    remaining: f64,
+    per_window: f64,
+) -> Option<DateTime<Utc>> {
+    if remaining <= 0.0 {
+        return Some(now);
+    }
+    if per_window <= 0.0 {
+        return None;
+    }
+    let days = remaining / per_window * f64::from(window_days);
+    Some(now + Duration::seconds((days * 86_400.0).ceil() as i64))
+}
+
+impl ProjectForecast {
+    pub fn new(velocity: ProjectVelocity, remaining: RemainingWork) -> Self {
+        let now = Utc::now();
+        let days = velocity.window_days;
+        Self {
+            by_points: completion_date(now, days, remaining.points, velocity.avg_points),
+            by_tasks: completion_date(now, days, remaining.tasks as f64, velocity.avg_tasks),
+            by_agent_hours: completion_date(now, days, remaining.hours, velocity.avg_agent_hours),
+            velocity,
+            remaining,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_completed_work_into_windows() {
+        let now = Utc::now();
+        let completed = [
+            CompletedTask {
+                completed_at: now - Duration::days(1),
+                points: Some(3.0),
+                hours: None,
+            },
+            CompletedTask {
+                completed_at: now - Duration::days(9),
+                points: Some(5.0),
+                hours: Some(2.0),
+            },
+            CompletedTask {
+                completed_at: now - Duration::days(10),
+                points: None,
+                hours: None,
+            },
+        ];
+        let agent_runs = [AgentRun {
+            started_at: now - Duration::days(2),
+            ended_at: now - Duration::days(2) + Duration::hours(2),
+        }];
+
+        let velocity = ProjectVelocity::from_samples(now, 7, 2, &completed, &agent_runs);
+
+        assert_eq!(velocity.windows.len(), 2);
+        assert_eq!(velocity.windows[0].completed_tasks, 2);
+        assert_eq!(velocity.windows[0].completed_points, 5.0);
+        assert_eq!(velocity.windows[1].completed_tasks, 1);
+        assert_eq!(velocity.windows[1].agent_seconds, 7200);
+        assert_eq!(velocity.avg_points, 4.0);
+        assert_eq!(velocity.avg_agent_hours, 1.0);
+    }
+
+    #[test]
+    fn forecasts_nothing_without_a_pace() {
+        let now = Utc::now();
+        assert_eq!(completion_date(now, 7, 8.0, 0.0), None);
+        assert_eq!(completion_date(now, 7, 0.0, 0.0), Some(now));
+        assert_eq!(
+            completion_date(now, 7, 8.0, 4.0),
+            Some(now + Duration::days(14))
+        );
+    }
+}
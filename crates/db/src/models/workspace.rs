@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -372,7 +372,7 @@ impl Workspace {
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateWorkspace,
         id: Uuid,
         task_id: Uuid,
@@ -389,7 +389,7 @@ impl Workspace {
             data.agent_working_dir,
             Option::<DateTime<Utc>>::None
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?)
     }
 
@@ -455,6 +455,201 @@ impl Workspace {
         Err(sqlx::Error::RowNotFound)
     }
 
+    /// Find the workspace working on `branch` of the repository at `repo_path`,
+    /// for a developer who checked the attempt branch out in the main repo
+    /// instead of opening the worktree. Prefers active, recently updated ones.
+    pub async fn resolve_repo_branch(
+        pool: &SqlitePool,
+        repo_path: &str,
+        branch: &str,
+    ) -> Result<ContainerInfo, sqlx::Error> {
+        sqlx::query_as!(
+            ContainerInfo,
+            r#"SELECT w.id as "workspace_id!: Uuid",
+                      w.task_id as "task_id!: Uuid",
+                      t.project_id as "project_id!: Uuid"
+               FROM workspaces w
+               JOIN tasks t ON w.task_id = t.id
+               JOIN workspace_repos wr ON wr.workspace_id = w.id
+               JOIN repos r ON r.id = wr.repo_id
+               LEFT JOIN workspace_repo_checkouts c
+                      ON c.workspace_id = w.id AND c.repo_id = r.id
+               WHERE r.path = $1 AND (w.branch = $2 OR c.branch = $2)
+               ORDER BY w.archived ASC, w.updated_at DESC
+               LIMIT 1"#,
+            repo_path,
+            branch
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Whether an unarchived workspace other than `workspace_id` still uses
+    /// `branch` of `repo_id`: as its own branch, as the target it merges
+    /// into, or for one of its extra checkouts
+    pub async fn is_branch_used_elsewhere(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        branch: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM workspaces w
+                   JOIN workspace_repos wr ON wr.workspace_id = w.id
+                   WHERE w.id != $1 AND w.archived = 0 AND wr.repo_id = $2
+                     AND (w.branch = $3 OR wr.target_branch = $3)
+               ) OR EXISTS(
+                   SELECT 1 FROM workspace_repo_checkouts c
+                   JOIN workspaces w ON w.id = c.workspace_id
+                   WHERE w.id != $1 AND w.archived = 0 AND c.repo_id = $2
+                     AND (c.branch = $3 OR c.source_branch = $3)
+               ) as "used!: bool""#,
+            workspace_id,
+            repo_id,
+            branch
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.used)
+    }
+
+    /// Move every workspace of one task to another, with their sessions,
+    /// processes and merges. Moved workspaces lose their canonical mark.
+    pub async fn reassign_task(
+        executor: impl Executor<'_, Database = Sqlite>,
+        from_task_id: Uuid,
+        to_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE workspaces SET task_id = $2, canonical = 0, updated_at = datetime('now', 'subsec') WHERE task_id = $1",
+            from_task_id,
+            to_task_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Make `workspace_id` the canonical attempt of its task and archive the
+    /// task's other unpinned attempts. Returns the ids archived.
+    pub async fn promote(
+        conn: impl Acquire<'_, Database = Sqlite>,
+        task_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut tx = conn.begin().await?;
+        sqlx::query!(
+            "UPDATE workspaces SET canonical = 0 WHERE task_id = $1 AND canonical = 1",
+            task_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE workspaces
+               SET canonical = 1, archived = 0, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND task_id = $2"#,
+            workspace_id,
+            task_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let archived = sqlx::query_scalar!(
+            r#"UPDATE workspaces
+               SET archived = 1, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $1 AND id != $2 AND archived = 0 AND pinned = 0
+               RETURNING id as "id!: Uuid""#,
+            task_id,
+            workspace_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(archived)
+    }
+
+    /// Canonical attempt of a task, if one was promoted
+    pub async fn find_canonical_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid" FROM workspaces WHERE task_id = $1 AND canonical = 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set_comparison_group(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        comparison_group_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET comparison_group_id = $1 WHERE id = $2",
+            comparison_group_id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Attempts of an executor comparison, in the order they were started
+    pub async fn find_by_comparison_group(
+        pool: &SqlitePool,
+        comparison_group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      container_ref,
+                      branch,
+                      agent_working_dir,
+                      setup_completed_at as "setup_completed_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      archived as "archived!: bool",
+                      pinned as "pinned!: bool",
+                      name
+               FROM workspaces
+               WHERE comparison_group_id = $1
+               ORDER BY created_at ASC"#,
+            comparison_group_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Unarchived attempts of tasks that are in progress or in review
+    pub async fn find_active(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT w.id as "id!: Uuid",
+                      w.task_id as "task_id!: Uuid",
+                      w.container_ref,
+                      w.branch,
+                      w.agent_working_dir,
+                      w.setup_completed_at as "setup_completed_at?: DateTime<Utc>",
+                      w.created_at as "created_at!: DateTime<Utc>",
+                      w.updated_at as "updated_at!: DateTime<Utc>",
+                      w.archived as "archived!: bool",
+                      w.pinned as "pinned!: bool",
+                      w.name
+               FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               WHERE w.archived = 0
+                 AND t.status IN ('inprogress', 'inreview')
+               ORDER BY w.updated_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn set_archived(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -470,6 +665,59 @@ impl Workspace {
         Ok(())
     }
 
+    pub async fn update_summary(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        summary: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET summary = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            summary,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_summary(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT summary FROM workspaces WHERE id = $1", workspace_id)
+            .fetch_optional(pool)
+            .await
+            .map(Option::flatten)
+    }
+
+    pub async fn update_review_checklist(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        checklist: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET review_checklist = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            checklist,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_review_checklist(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT review_checklist FROM workspaces WHERE id = $1",
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+
     /// Update workspace fields. Only non-None values will be updated.
     /// For `name`, pass `Some("")` to clear the name, `Some("foo")` to set it, or `None` to leave unchanged.
     pub async fn update(
@@ -717,3 +965,92 @@ impl Workspace {
         Ok(Some(ws))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        DBService, fixtures,
+        models::{
+            repo::Repo,
+            workspace_repo::{
+                CreateWorkspaceRepo, CreateWorkspaceRepoCheckout, WorkspaceRepoCheckout,
+            },
+        },
+    };
+
+    /// Workspace of a new task with `repo` checked out on `branch`
+    async fn workspace_on(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo: &Repo,
+        branch: &str,
+    ) -> Workspace {
+        let task = fixtures::task(pool, project_id).await;
+        let workspace = fixtures::workspace(pool, task.id, branch).await;
+        WorkspaceRepo::create_many(
+            pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+                path_scope: None,
+            }],
+        )
+        .await
+        .unwrap();
+        workspace
+    }
+
+    #[tokio::test]
+    async fn resolves_the_active_workspace_on_a_repo_branch() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project = fixtures::project(pool).await;
+        let repo = Repo::find_or_create(pool, Path::new("/tmp/vk-test-widgets"), "widgets")
+            .await
+            .unwrap();
+        let repo_path = repo.path.to_string_lossy();
+
+        let active = workspace_on(pool, project.id, &repo, "vk/feature").await;
+        let archived = workspace_on(pool, project.id, &repo, "vk/feature").await;
+        Workspace::set_archived(pool, archived.id, true)
+            .await
+            .unwrap();
+
+        let found = Workspace::resolve_repo_branch(pool, &repo_path, "vk/feature")
+            .await
+            .unwrap();
+        assert_eq!(found.workspace_id, active.id);
+        assert_eq!(found.project_id, project.id);
+
+        // Extra checkouts resolve to the workspace that owns them
+        let checkout = WorkspaceRepoCheckout::create(
+            pool,
+            active.id,
+            &active.branch,
+            &CreateWorkspaceRepoCheckout {
+                repo_id: repo.id,
+                alias: "old".to_string(),
+                source_branch: "release".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let found = Workspace::resolve_repo_branch(pool, &repo_path, &checkout.branch)
+            .await
+            .unwrap();
+        assert_eq!(found.workspace_id, active.id);
+
+        assert!(matches!(
+            Workspace::resolve_repo_branch(pool, &repo_path, "vk/unknown").await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+        assert!(matches!(
+            Workspace::resolve_repo_branch(pool, "/tmp/elsewhere", "vk/feature").await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+    }
+}
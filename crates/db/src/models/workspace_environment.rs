@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Version reported by a tool; `None` when it is not installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ToolVersion {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Hash of a lockfile or toolchain pin in one of the attempt's repos
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct LockfileHash {
+    pub repo: String,
+    /// Path relative to the repo root
+    pub path: String,
+    pub sha256: String,
+}
+
+/// The environment an attempt's coding agent ran in
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct WorkspaceEnvironment {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub os: String,
+    pub arch: String,
+    #[ts(type = "Array<ToolVersion>")]
+    pub tools: Json<Vec<ToolVersion>>,
+    #[ts(type = "Array<LockfileHash>")]
+    pub lockfiles: Json<Vec<LockfileHash>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateWorkspaceEnvironment {
+    pub os: String,
+    pub arch: String,
+    pub tools: Vec<ToolVersion>,
+    pub lockfiles: Vec<LockfileHash>,
+}
+
+impl WorkspaceEnvironment {
+    /// Whether `data` describes the same environment as this snapshot
+    pub fn matches(&self, data: &CreateWorkspaceEnvironment) -> bool {
+        self.os == data.os
+            && self.arch == data.arch
+            && self.tools.0 == data.tools
+            && self.lockfiles.0 == data.lockfiles
+    }
+
+    /// Snapshots of an attempt, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceEnvironment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      os,
+                      arch,
+                      tools as "tools!: Json<Vec<ToolVersion>>",
+                      lockfiles as "lockfiles!: Json<Vec<LockfileHash>>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_environments
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_latest(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceEnvironment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      os,
+                      arch,
+                      tools as "tools!: Json<Vec<ToolVersion>>",
+                      lockfiles as "lockfiles!: Json<Vec<LockfileHash>>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_environments
+             WHERE workspace_id = $1
+             ORDER BY created_at DESC
+             LIMIT 1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        data: &CreateWorkspaceEnvironment,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceEnvironment,
+            r#"INSERT INTO workspace_environments
+                 (id, workspace_id, execution_process_id, os, arch, tools, lockfiles)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id as "id!: Uuid",
+                       workspace_id as "workspace_id!: Uuid",
+                       execution_process_id as "execution_process_id?: Uuid",
+                       os,
+                       arch,
+                       tools as "tools!: Json<Vec<ToolVersion>>",
+                       lockfiles as "lockfiles!: Json<Vec<LockfileHash>>",
+                       created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            workspace_id,
+            execution_process_id,
+            &data.os,
+            &data.arch,
+            Json(&data.tools),
+            Json(&data.lockfiles)
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
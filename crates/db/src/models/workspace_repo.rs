@@ -1,9 +1,10 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
 use uuid::Uuid;
 
 use super::repo::Repo;
@@ -24,6 +25,9 @@ pub struct WorkspaceRepo {
 pub struct CreateWorkspaceRepo {
     pub repo_id: Uuid,
     pub target_branch: String,
+    /// Directory of the repo the attempt is limited to
+    #[serde(default)]
+    pub path_scope: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -32,6 +36,53 @@ pub struct RepoWithTargetBranch {
     #[serde(flatten)]
     pub repo: Repo,
     pub target_branch: String,
+    /// Directory of the repo the attempt is limited to
+    pub path_scope: Option<String>,
+}
+
+/// Extra checkout of one of a workspace's repos, in its own worktree next to
+/// the primary one
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceRepoCheckout {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub alias: String,
+    /// Branch the checkout started from
+    pub source_branch: String,
+    /// Branch created for the checkout
+    pub branch: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateWorkspaceRepoCheckout {
+    pub repo_id: Uuid,
+    /// Letters, digits, `-` and `_`; names the checkout's directory
+    pub alias: String,
+    pub source_branch: String,
+}
+
+const MAX_ALIAS_CHARS: usize = 32;
+
+impl Validate for CreateWorkspaceRepoCheckout {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.alias.is_empty()
+            || self.alias.chars().count() > MAX_ALIAS_CHARS
+            || !self
+                .alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            errors.add(
+                "alias",
+                format!("must be 1 to {MAX_ALIAS_CHARS} letters, digits, dashes or underscores"),
+            );
+        }
+        errors.branch_name("source_branch", &self.source_branch);
+        errors.into_result()
+    }
 }
 
 /// Repo info with copy_files configuration.
@@ -45,7 +96,7 @@ pub struct RepoWithCopyFiles {
 
 impl WorkspaceRepo {
     pub async fn create_many(
-        pool: &SqlitePool,
+        conn: impl Acquire<'_, Database = Sqlite>,
         workspace_id: Uuid,
         repos: &[CreateWorkspaceRepo],
     ) -> Result<Vec<Self>, sqlx::Error> {
@@ -56,7 +107,7 @@ impl WorkspaceRepo {
         // Build bulk insert query with VALUES for each repo
         // SQLite doesn't have great support for bulk inserts with RETURNING,
         // so we'll use a transaction to batch the inserts efficiently
-        let mut tx = pool.begin().await?;
+        let mut tx = conn.begin().await?;
         let mut results = Vec::with_capacity(repos.len());
 
         for repo in repos {
@@ -78,6 +129,15 @@ impl WorkspaceRepo {
             )
             .fetch_one(&mut *tx)
             .await?;
+            if let Some(path_scope) = &repo.path_scope {
+                sqlx::query!(
+                    "UPDATE workspace_repos SET path_scope = $1 WHERE id = $2",
+                    path_scope,
+                    id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
             results.push(workspace_repo);
         }
 
@@ -159,10 +219,12 @@ impl WorkspaceRepo {
         )
         .fetch_all(pool)
         .await?;
+        let mut path_scopes = Self::find_path_scopes(pool, workspace_id).await?;
 
         Ok(rows
             .into_iter()
             .map(|row| RepoWithTargetBranch {
+                path_scope: path_scopes.remove(&row.id),
                 repo: Repo {
                     id: row.id,
                     path: PathBuf::from(row.path),
@@ -182,6 +244,26 @@ impl WorkspaceRepo {
             .collect())
     }
 
+    /// Path scopes of the workspace's repos, keyed by repo id; unscoped
+    /// repos are absent
+    pub async fn find_path_scopes(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<HashMap<Uuid, String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT repo_id as "repo_id!: Uuid", path_scope as "path_scope!: String"
+               FROM workspace_repos
+               WHERE workspace_id = $1 AND path_scope IS NOT NULL"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.repo_id, row.path_scope))
+            .collect())
+    }
+
     pub async fn find_by_workspace_and_repo_id(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -300,3 +382,132 @@ impl WorkspaceRepo {
             .collect())
     }
 }
+
+impl WorkspaceRepoCheckout {
+    /// Directory of the checkout within the workspace
+    pub fn dir_name(&self, repo_name: &str) -> String {
+        format!("{repo_name}-{}", self.alias)
+    }
+
+    /// Branch of a checkout of an attempt working on `workspace_branch`
+    pub fn branch_name(workspace_branch: &str, alias: &str) -> String {
+        format!("{workspace_branch}-{alias}")
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRepoCheckout,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      alias,
+                      source_branch,
+                      branch,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_repo_checkouts
+             WHERE workspace_id = $1
+             ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRepoCheckout,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      alias,
+                      source_branch,
+                      branch,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_repo_checkouts WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        executor: impl sqlx::Executor<'_, Database = Sqlite>,
+        workspace_id: Uuid,
+        workspace_branch: &str,
+        data: &CreateWorkspaceRepoCheckout,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRepoCheckout,
+            r#"INSERT INTO workspace_repo_checkouts
+                 (id, workspace_id, repo_id, alias, source_branch, branch)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id as "id!: Uuid",
+                       workspace_id as "workspace_id!: Uuid",
+                       repo_id as "repo_id!: Uuid",
+                       alias,
+                       source_branch,
+                       branch,
+                       created_at as "created_at!: DateTime<Utc>""#,
+            Uuid::new_v4(),
+            workspace_id,
+            data.repo_id,
+            &data.alias,
+            &data.source_branch,
+            Self::branch_name(workspace_branch, &data.alias)
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspace_repo_checkouts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkout(alias: &str, source_branch: &str) -> CreateWorkspaceRepoCheckout {
+        CreateWorkspaceRepoCheckout {
+            repo_id: Uuid::new_v4(),
+            alias: alias.to_string(),
+            source_branch: source_branch.to_string(),
+        }
+    }
+
+    #[test]
+    fn checkout_aliases_must_be_safe_directory_names() {
+        assert!(checkout("old_api-2", "main").validate().is_ok());
+        assert!(checkout("", "main").validate().is_err());
+        assert!(checkout("../escape", "main").validate().is_err());
+        assert!(checkout("with space", "main").validate().is_err());
+        assert!(
+            checkout(&"a".repeat(MAX_ALIAS_CHARS + 1), "main")
+                .validate()
+                .is_err()
+        );
+        assert!(checkout("old", "bad..branch").validate().is_err());
+    }
+
+    #[test]
+    fn checkouts_get_their_own_directory_and_branch() {
+        let checkout = WorkspaceRepoCheckout {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            alias: "old".to_string(),
+            source_branch: "release/1.0".to_string(),
+            branch: WorkspaceRepoCheckout::branch_name("vk/1a2b-fix", "old"),
+            created_at: Utc::now(),
+        };
+        assert_eq!(checkout.branch, "vk/1a2b-fix-old");
+        assert_eq!(checkout.dir_name("widgets"), "widgets-old");
+    }
+}
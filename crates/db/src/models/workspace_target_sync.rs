@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How far an attempt repo was behind its target branch when last checked
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceTargetSync {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Commits on the target branch that the attempt branch lacks
+    pub behind: i64,
+    /// The attempt fell too far behind and could not be rebased automatically
+    pub needs_rebase: bool,
+    /// Why the last automatic rebase did not happen or failed
+    pub last_error: Option<String>,
+    pub rebased_at: Option<DateTime<Utc>>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl WorkspaceTargetSync {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTargetSync,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      behind,
+                      needs_rebase as "needs_rebase!: bool",
+                      last_error,
+                      rebased_at as "rebased_at?: DateTime<Utc>",
+                      checked_at as "checked_at!: DateTime<Utc>"
+               FROM workspace_target_syncs WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTargetSync,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      behind,
+                      needs_rebase as "needs_rebase!: bool",
+                      last_error,
+                      rebased_at as "rebased_at?: DateTime<Utc>",
+                      checked_at as "checked_at!: DateTime<Utc>"
+               FROM workspace_target_syncs
+             WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store the result of a check, keeping when the repo was last rebased
+    pub async fn record(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+        behind: i64,
+        needs_rebase: bool,
+        last_error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTargetSync,
+            r#"INSERT INTO workspace_target_syncs
+                 (workspace_id, repo_id, target_branch, behind, needs_rebase, last_error)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (workspace_id, repo_id) DO UPDATE SET
+                 target_branch = excluded.target_branch,
+                 behind = excluded.behind,
+                 needs_rebase = excluded.needs_rebase,
+                 last_error = excluded.last_error,
+                 checked_at = datetime('now', 'subsec')
+             RETURNING workspace_id as "workspace_id!: Uuid",
+                       repo_id as "repo_id!: Uuid",
+                       target_branch,
+                       behind,
+                       needs_rebase as "needs_rebase!: bool",
+                       last_error,
+                       rebased_at as "rebased_at?: DateTime<Utc>",
+                       checked_at as "checked_at!: DateTime<Utc>""#,
+            workspace_id,
+            repo_id,
+            target_branch,
+            behind,
+            needs_rebase,
+            last_error
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The repo was just rebased onto `target_branch`, by hand or automatically
+    pub async fn mark_rebased(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTargetSync,
+            r#"INSERT INTO workspace_target_syncs
+                 (workspace_id, repo_id, target_branch, behind, rebased_at)
+             VALUES ($1, $2, $3, 0, datetime('now', 'subsec'))
+             ON CONFLICT (workspace_id, repo_id) DO UPDATE SET
+                 target_branch = excluded.target_branch,
+                 behind = 0,
+                 needs_rebase = 0,
+                 last_error = NULL,
+                 rebased_at = excluded.rebased_at,
+                 checked_at = datetime('now', 'subsec')
+             RETURNING workspace_id as "workspace_id!: Uuid",
+                       repo_id as "repo_id!: Uuid",
+                       target_branch,
+                       behind,
+                       needs_rebase as "needs_rebase!: bool",
+                       last_error,
+                       rebased_at as "rebased_at?: DateTime<Utc>",
+                       checked_at as "checked_at!: DateTime<Utc>""#,
+            workspace_id,
+            repo_id,
+            target_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
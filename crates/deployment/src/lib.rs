@@ -26,12 +26,17 @@ use services::services::{
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
+    jobs::JobService,
+    multi_user::MultiUserService,
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    secrets::SecretStore,
     share::SharePublisher,
+    target_sync::TargetSyncService,
     worktree_manager::WorktreeError,
+    worktree_watch::WorktreeWatchService,
 };
 use sqlx::Error as SqlxError;
 use thiserror::Error;
@@ -107,8 +112,17 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn queued_message_service(&self) -> &QueuedMessageService;
 
+    /// Persistent queue for background work
+    fn jobs(&self) -> &JobService;
+
     fn auth_context(&self) -> &AuthContext;
 
+    /// API keys and tokens, kept out of the plaintext config
+    fn secrets(&self) -> &Arc<SecretStore>;
+
+    /// Set when the server authenticates requests and isolates data per user
+    fn multi_user(&self) -> &Option<MultiUserService>;
+
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured>;
 
     async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
@@ -120,7 +134,30 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
-        PrMonitorService::spawn(db, analytics).await
+        PrMonitorService::spawn(
+            db,
+            analytics,
+            self.jobs().clone(),
+            self.share_publisher().ok(),
+        )
+        .await
+    }
+
+    /// Keep active attempts in sync with their target branches, see
+    /// `Config::target_sync_mode`
+    async fn spawn_target_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        TargetSyncService::spawn(self.db().clone(), self.git().clone(), self.config().clone()).await
+    }
+
+    /// Watch the worktrees of active attempts for hand edits, see
+    /// `Config::live_worktree_watch`
+    async fn spawn_worktree_watch_service(&self) -> tokio::task::JoinHandle<()> {
+        WorktreeWatchService::spawn(
+            self.db().clone(),
+            self.config().clone(),
+            self.events().msg_store().clone(),
+        )
+        .await
     }
 
     /// Trigger background auto-setup of default projects for new users
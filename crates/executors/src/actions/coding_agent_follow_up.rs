@@ -26,6 +26,9 @@ pub struct CodingAgentFollowUpRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Model to run instead of the profile's own, from the project's executor defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 impl CodingAgentFollowUpRequest {
@@ -76,6 +79,9 @@ impl Executable for CodingAgentFollowUpRequest {
                 ))?;
 
             agent.use_approvals(approvals.clone());
+            if let Some(model) = &self.model {
+                agent.override_model(model);
+            }
 
             agent
                 .spawn_follow_up(&effective_dir, &self.prompt, &self.session_id, env)
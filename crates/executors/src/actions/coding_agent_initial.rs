@@ -25,6 +25,9 @@ pub struct CodingAgentInitialRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Model to run instead of the profile's own, from the project's executor defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 impl CodingAgentInitialRequest {
@@ -68,6 +71,9 @@ impl Executable for CodingAgentInitialRequest {
                 ))?;
 
             agent.use_approvals(approvals.clone());
+            if let Some(model) = &self.model {
+                agent.override_model(model);
+            }
 
             agent.spawn(&effective_dir, &self.prompt, env).await
         }
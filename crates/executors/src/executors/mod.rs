@@ -177,6 +177,24 @@ impl CodingAgent {
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
     }
+
+    /// Run with `model` instead of the profile's configured model. Agents
+    /// without a model setting ignore it.
+    pub fn override_model(&mut self, model: &str) {
+        let slot = match self {
+            Self::ClaudeCode(agent) => &mut agent.model,
+            Self::Gemini(agent) => &mut agent.model,
+            Self::Codex(agent) => &mut agent.model,
+            Self::Opencode(agent) => &mut agent.model,
+            Self::CursorAgent(agent) => &mut agent.model,
+            Self::Copilot(agent) => &mut agent.model,
+            Self::Droid(agent) => &mut agent.model,
+            Self::Amp(_) | Self::QwenCode(_) => return,
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => return,
+        };
+        *slot = Some(model.to_string());
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
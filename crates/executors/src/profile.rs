@@ -210,9 +210,8 @@ impl ExecutorConfigs {
     pub fn load() -> Self {
         let profiles_path = workspace_utils::assets::profiles_path();
 
-        // Load defaults first
-        let mut defaults = Self::from_defaults();
-        defaults.canonicalise();
+        // Load defaults (with any organization profiles) first
+        let defaults = Self::baseline();
 
         // Try to load user overrides
         let content = match fs::read_to_string(&profiles_path) {
@@ -243,8 +242,7 @@ impl ExecutorConfigs {
     /// Save user profile overrides to file (only saves what differs from defaults)
     pub fn save_overrides(&self) -> Result<(), ProfileError> {
         let profiles_path = workspace_utils::assets::profiles_path();
-        let mut defaults = Self::from_defaults();
-        defaults.canonicalise();
+        let defaults = Self::baseline();
 
         // Canonicalise current config before computing overrides
         let mut self_clone = self.clone();
@@ -265,6 +263,28 @@ impl ExecutorConfigs {
         Ok(())
     }
 
+    /// Built-in defaults with the organization's shared profiles merged in;
+    /// user overrides apply on top of this
+    fn baseline() -> Self {
+        let mut defaults = Self::from_defaults();
+        defaults.canonicalise();
+
+        let content = match fs::read_to_string(workspace_utils::assets::org_profiles_path()) {
+            Ok(content) => content,
+            Err(_) => return defaults,
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(mut org_profiles) => {
+                org_profiles.canonicalise();
+                Self::merge_with_defaults(defaults, org_profiles)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse organization profiles: {}", e);
+                defaults
+            }
+        }
+    }
+
     /// Deep merge defaults with user overrides
     fn merge_with_defaults(mut defaults: Self, overrides: Self) -> Self {
         for (executor_key, override_profile) in overrides.executors {
@@ -1,47 +1,377 @@
 use command_group::AsyncGroupChild;
+use db::models::execution_process_group::ExecutionProcessGroup;
 #[cfg(unix)]
 use nix::{
-    sys::signal::{Signal, killpg},
-    unistd::{Pid, getpgid},
+    errno::Errno,
+    sys::signal::{Signal, kill, killpg},
+    unistd::{Pid, getpgrp},
 };
-use services::services::container::ContainerError;
+use services::services::{container::ContainerError, workspace_lock::WorkspaceLock};
+use sqlx::SqlitePool;
 #[cfg(unix)]
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 
-pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), ContainerError> {
-    // hit the whole process group, not just the leader
+/// How long each signal gets to take the process tree down before the next,
+/// harsher one is sent
+#[cfg(unix)]
+const SIGNAL_GRACE: Duration = Duration::from_secs(2);
+#[cfg(unix)]
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Process group of a freshly spawned child. `group_spawn` makes the child
+/// the leader of a new group, so the group id is its pid. Read it before the
+/// child is reaped, after which the pid is no longer available.
+pub fn process_group_id(child: &mut AsyncGroupChild) -> Option<u32> {
+    child.inner().id()
+}
+
+/// Stop a child and everything it started. The whole process group is
+/// signalled, along with descendants that moved to a group or session of
+/// their own, escalating from SIGINT to SIGKILL until all of them are gone.
+/// Also sweeps up what a child that already exited left running.
+pub async fn kill_process_group(
+    child: &mut AsyncGroupChild,
+    pgid: Option<u32>,
+) -> Result<(), ContainerError> {
     #[cfg(unix)]
+    if let Some(pgid) = pgid.or_else(|| child.inner().id()) {
+        let pgid = Pid::from_raw(pgid as i32);
+        take_down(
+            pgid,
+            &[Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL],
+            Some(child),
+            None,
+        )
+        .await?;
+    }
+    #[cfg(not(unix))]
+    let _ = pgid;
+
+    let _ = child.kill().await;
+    let _ = child.wait().await;
+    Ok(())
+}
+
+/// Start time of a process, in clock ticks since boot. Recorded with each
+/// process group so that a pid reused after the group exited is never
+/// mistaken for it. Only known on Linux.
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
     {
-        if let Some(pid) = child.inner().id() {
-            let pgid = getpgid(Some(Pid::from_raw(pid as i32)))
-                .map_err(|e| ContainerError::KillFailed(std::io::Error::other(e)))?;
-
-            for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
-                tracing::info!("Sending {:?} to process group {}", sig, pgid);
-                if let Err(e) = killpg(pgid, sig) {
-                    tracing::warn!(
-                        "Failed to send signal {:?} to process group {}: {}",
-                        sig,
-                        pgid,
-                        e
-                    );
-                }
-                tracing::info!("Waiting 2s for process group {} to exit", pgid);
-                tokio::time::sleep(Duration::from_secs(2)).await;
-                if child
-                    .inner()
-                    .try_wait()
-                    .map_err(ContainerError::Io)?
-                    .is_some()
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        parse_start_time(&stat)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Kill the process groups that instances no longer running left behind on
+/// this host, then forget them. Groups of live instances are left alone, and
+/// a group is only signalled while its leader is still the process that was
+/// recorded. Call at startup, after this instance is registered and before
+/// orphaned executions are marked failed.
+pub async fn reap_orphaned_process_groups(pool: &SqlitePool) {
+    let hostname = WorkspaceLock::hostname();
+    let groups = match ExecutionProcessGroup::find_by_hostname(pool, &hostname).await {
+        Ok(groups) if groups.is_empty() => return,
+        Ok(groups) => groups,
+        Err(e) => {
+            tracing::warn!("Failed to load recorded process groups: {}", e);
+            return;
+        }
+    };
+    let live = match WorkspaceLock::live_instance_ids(pool).await {
+        Ok(live) => live,
+        Err(e) => {
+            tracing::warn!("Failed to list running instances: {}", e);
+            return;
+        }
+    };
+    // Without our own heartbeat the registry cannot be trusted to tell which
+    // instances are alive
+    if !live.contains(&WorkspaceLock::instance_id()) {
+        tracing::warn!("Not reaping orphaned process groups: this instance is not registered");
+        return;
+    }
+
+    for group in groups {
+        if group.instance_id.is_some_and(|id| live.contains(&id)) {
+            continue;
+        }
+
+        #[cfg(unix)]
+        match (u32::try_from(group.pgid), group.start_time) {
+            (Ok(pgid), Some(start_time)) => {
+                tracing::info!(
+                    "Killing process group {} left behind by execution process {}",
+                    pgid,
+                    group.execution_process_id
+                );
+                if let Err(e) = take_down(
+                    Pid::from_raw(pgid as i32),
+                    &[Signal::SIGTERM, Signal::SIGKILL],
+                    None,
+                    Some(start_time as u64),
+                )
+                .await
                 {
-                    tracing::info!("Process group {} exited after {:?}", pgid, sig);
-                    break;
+                    tracing::warn!("Failed to kill process group {}: {}", pgid, e);
                 }
             }
+            _ => tracing::info!(
+                "Not killing process group {} of execution process {}: its leader cannot be verified",
+                group.pgid,
+                group.execution_process_id
+            ),
+        }
+
+        if let Err(e) = ExecutionProcessGroup::delete(pool, group.execution_process_id).await {
+            tracing::warn!("Failed to forget process group {}: {}", group.pgid, e);
         }
     }
+}
 
-    let _ = child.kill().await;
-    let _ = child.wait().await;
+/// Send each signal in turn to the group and its stray descendants until
+/// none of them is left. With `start_time`, the group is only signalled while
+/// its leader still has that start time; once the leader is gone its pid may
+/// belong to an unrelated process.
+#[cfg(unix)]
+async fn take_down(
+    pgid: Pid,
+    signals: &[Signal],
+    mut leader: Option<&mut AsyncGroupChild>,
+    start_time: Option<u64>,
+) -> Result<(), ContainerError> {
+    // never signal init or our own group, whatever was recorded
+    if pgid.as_raw() <= 1 || pgid == getpgrp() {
+        tracing::warn!("Refusing to signal process group {}", pgid);
+        return Ok(());
+    }
+
+    // Collected up front: once their parents die they are reparented to init
+    // and can no longer be traced back to this tree
+    let strays = stray_descendants(pgid);
+    if !strays.is_empty() {
+        tracing::info!(
+            "Process group {} has {} descendants outside the group",
+            pgid,
+            strays.len()
+        );
+    }
+
+    for &sig in signals {
+        if let Some(start_time) = start_time
+            && process_start_time(pgid.as_raw() as u32) != Some(start_time)
+        {
+            tracing::info!("Process group {} leader is gone; not signalling it", pgid);
+            return Ok(());
+        }
+        tracing::info!("Sending {:?} to process group {}", sig, pgid);
+        if let Err(e) = killpg(pgid, sig)
+            && e != Errno::ESRCH
+        {
+            tracing::warn!(
+                "Failed to send signal {:?} to process group {}: {}",
+                sig,
+                pgid,
+                e
+            );
+        }
+        for &pid in &strays {
+            let _ = kill(pid, sig);
+        }
+
+        let deadline = Instant::now() + SIGNAL_GRACE;
+        loop {
+            // Reap the leader as soon as it exits
+            if let Some(child) = leader.as_deref_mut() {
+                child.inner().try_wait().map_err(ContainerError::Io)?;
+            }
+            if !is_alive(pgid, &strays) {
+                tracing::info!("Process group {} exited after {:?}", pgid, sig);
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    tracing::warn!(
+        "Process group {} is still running after {:?}",
+        pgid,
+        signals
+    );
     Ok(())
 }
+
+/// Whether anything in the group or among the strays is still running.
+/// Zombies do not count: they are dead, just not yet reaped by their parent.
+#[cfg(target_os = "linux")]
+fn is_alive(pgid: Pid, strays: &[Pid]) -> bool {
+    processes().iter().any(|process| {
+        process.state != 'Z'
+            && (process.pgrp == pgid.as_raw() || strays.contains(&Pid::from_raw(process.pid)))
+    })
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_alive(pgid: Pid, strays: &[Pid]) -> bool {
+    killpg(pgid, None).is_ok() || strays.iter().any(|&pid| kill(pid, None).is_ok())
+}
+
+#[cfg(target_os = "linux")]
+struct ProcStat {
+    pid: i32,
+    state: char,
+    ppid: i32,
+    pgrp: i32,
+}
+
+#[cfg(target_os = "linux")]
+fn processes() -> Vec<ProcStat> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let pid: i32 = entry.ok()?.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+            parse_stat(pid, &stat)
+        })
+        .collect()
+}
+
+/// Descendants of the group's members that are no longer in the group, such
+/// as daemonizing dev servers and shells started with `setsid`
+#[cfg(target_os = "linux")]
+fn stray_descendants(pgid: Pid) -> Vec<Pid> {
+    use std::collections::HashSet;
+
+    let processes = processes();
+    let mut tree: HashSet<i32> = processes
+        .iter()
+        .filter(|process| process.pgrp == pgid.as_raw())
+        .map(|process| process.pid)
+        .collect();
+    tree.insert(pgid.as_raw());
+
+    let mut strays = Vec::new();
+    loop {
+        let mut grew = false;
+        for process in &processes {
+            if tree.contains(&process.ppid) && tree.insert(process.pid) {
+                strays.push(Pid::from_raw(process.pid));
+                grew = true;
+            }
+        }
+        if !grew {
+            return strays;
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn stray_descendants(_pgid: Pid) -> Vec<Pid> {
+    Vec::new()
+}
+
+/// State, parent pid and process group from `/proc/<pid>/stat`. The command
+/// name is parenthesised and may itself contain spaces and parentheses, so the
+/// fields are read after the last `)`.
+#[cfg(target_os = "linux")]
+fn parse_stat(pid: i32, stat: &str) -> Option<ProcStat> {
+    let rest = stat.get(stat.rfind(')')? + 1..)?;
+    let mut fields = rest.split_whitespace();
+    let state = fields.next()?.chars().next()?;
+    let ppid = fields.next()?.parse().ok()?;
+    let pgrp = fields.next()?.parse().ok()?;
+    Some(ProcStat {
+        pid,
+        state,
+        ppid,
+        pgrp,
+    })
+}
+
+/// Field 22 of `/proc/<pid>/stat`, the 20th after the command name
+#[cfg(target_os = "linux")]
+fn parse_start_time(stat: &str) -> Option<u64> {
+    let rest = stat.get(stat.rfind(')')? + 1..)?;
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use command_group::AsyncCommandGroup;
+    use tokio::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn parses_stat_with_odd_command_names() {
+        let stat = parse_stat(
+            4242,
+            "4242 (my (odd) cmd) S 4200 4100 4100 0 -1 4194560 120 0 0 0",
+        )
+        .unwrap();
+        assert_eq!((stat.state, stat.ppid, stat.pgrp), ('S', 4200, 4100));
+        assert!(parse_stat(4242, "4242 (truncated").is_none());
+    }
+
+    #[test]
+    fn parses_start_time() {
+        let stat = "4242 (my (odd) cmd) S 4200 4100 4100 0 -1 4194560 120 0 0 0 \
+                    3 1 0 0 20 0 1 0 987654 1234567 89";
+        assert_eq!(parse_start_time(stat), Some(987654));
+        assert!(process_start_time(std::process::id()).is_some());
+    }
+
+    #[tokio::test]
+    async fn leaves_groups_with_another_leader_alone() {
+        let mut child = Command::new("sleep").arg("30").group_spawn().unwrap();
+        let pgid = process_group_id(&mut child).unwrap();
+        let start_time = process_start_time(pgid).unwrap();
+
+        // A different start time means the pid now belongs to another process
+        take_down(
+            Pid::from_raw(pgid as i32),
+            &[Signal::SIGKILL],
+            None,
+            Some(start_time + 1),
+        )
+        .await
+        .unwrap();
+        assert!(is_alive(Pid::from_raw(pgid as i32), &[]));
+
+        take_down(
+            Pid::from_raw(pgid as i32),
+            &[Signal::SIGKILL],
+            Some(&mut child),
+            Some(start_time),
+        )
+        .await
+        .unwrap();
+        assert!(!is_alive(Pid::from_raw(pgid as i32), &[]));
+    }
+
+    #[tokio::test]
+    async fn kills_descendants_that_left_the_group() {
+        let mut child = Command::new("sh")
+            .args(["-c", "setsid sleep 30 & sleep 30"])
+            .group_spawn()
+            .unwrap();
+        let pgid = process_group_id(&mut child).unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let strays = stray_descendants(Pid::from_raw(pgid as i32));
+        assert!(!strays.is_empty());
+
+        kill_process_group(&mut child, Some(pgid)).await.unwrap();
+        assert!(!is_alive(Pid::from_raw(pgid as i32), &strays));
+    }
+}
@@ -13,16 +13,26 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        e2e_test::ProjectE2eSettings,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_group::{CreateExecutionProcessGroup, ExecutionProcessGroup},
         execution_process_repo_state::ExecutionProcessRepoState,
+        execution_window::DeferredAttempt,
+        lifecycle_hook::LifecycleEvent,
+        preview::ProjectPreviewSettings,
+        project::Project,
+        project_budget::{BudgetAlert, ProjectBudget},
+        project_owner::ProjectOwner,
+        project_script::ScriptEvent,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         session::{Session, SessionError},
         task::{Task, TaskStatus},
+        time_entry::TimeEntry,
         workspace::Workspace,
-        workspace_repo::WorkspaceRepo,
+        workspace_repo::{WorkspaceRepo, WorkspaceRepoCheckout},
     },
 };
 use deployment::DeploymentError;
@@ -40,16 +50,30 @@ use executors::{
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
+    agent_instructions,
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    artifacts, attempt_summary, automation_scripts,
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
+    diff_ignore::DiffIgnore,
     diff_stream::{self, DiffStreamHandle},
+    embedding_index::Embedder,
+    env_templates,
     git::{GitCli, GitService},
     image::ImageService,
+    jobs::{JobKind, JobService},
+    lifecycle_hooks,
+    multi_user::MultiUserService,
     notification::NotificationService,
+    path_scope, preview_capture,
     queued_message::QueuedMessageService,
+    secrets::SecretStore,
+    share::{SharePublisher, SharedTaskExecutionState},
+    task_knowledge, traceability, visual_regression,
+    workspace_lock::WorkspaceLock,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
+    worktree_watch,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
@@ -66,6 +90,9 @@ use crate::{command, copy};
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
+    /// Process group of each running child, which outlives the child's pid
+    /// once the child itself has been reaped
+    process_groups: Arc<RwLock<HashMap<Uuid, u32>>>,
     interrupt_senders: Arc<RwLock<HashMap<Uuid, InterruptSender>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     config: Arc<RwLock<Config>>,
@@ -75,6 +102,9 @@ pub struct LocalContainerService {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    share_publisher: Option<SharePublisher>,
+    jobs: JobService,
+    secrets: Arc<SecretStore>,
 }
 
 impl LocalContainerService {
@@ -88,14 +118,19 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+        share_publisher: Option<SharePublisher>,
+        jobs: JobService,
+        secrets: Arc<SecretStore>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
+        let process_groups = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
             db,
             child_store,
+            process_groups,
             interrupt_senders,
             msg_stores,
             config,
@@ -105,9 +140,20 @@ impl LocalContainerService {
             approvals,
             queued_message_service,
             notification_service,
+            share_publisher,
+            jobs,
+            secrets,
         };
 
+        // The reap only trusts the instance registry once this instance is in it
+        WorkspaceLock::spawn_heartbeat(
+            container.db.clone(),
+            WorkspaceManager::get_workspace_base_dir(),
+        )
+        .await;
+        command::reap_orphaned_process_groups(&container.db.pool).await;
         container.spawn_workspace_cleanup();
+        container.spawn_deferred_attempt_starter();
 
         container
     }
@@ -127,6 +173,36 @@ impl LocalContainerService {
         map.remove(id);
     }
 
+    async fn process_group(&self, id: &Uuid) -> Option<u32> {
+        self.process_groups.read().await.get(id).copied()
+    }
+
+    /// Remember the child's process group, in the db too so that a later
+    /// start can kill it if this server exits without stopping it
+    async fn record_process_group(&self, id: Uuid, child: &mut AsyncGroupChild) {
+        let Some(pgid) = command::process_group_id(child) else {
+            return;
+        };
+        self.process_groups.write().await.insert(id, pgid);
+        let group = CreateExecutionProcessGroup {
+            execution_process_id: id,
+            pgid: i64::from(pgid),
+            hostname: WorkspaceLock::hostname(),
+            instance_id: WorkspaceLock::instance_id(),
+            start_time: command::process_start_time(pgid).map(|ticks| ticks as i64),
+        };
+        if let Err(e) = ExecutionProcessGroup::record(&self.db.pool, &group).await {
+            tracing::warn!("Failed to record process group of {}: {}", id, e);
+        }
+    }
+
+    async fn forget_process_group(&self, id: &Uuid) {
+        self.process_groups.write().await.remove(id);
+        if let Err(e) = ExecutionProcessGroup::delete(&self.db.pool, *id).await {
+            tracing::warn!("Failed to forget process group of {}: {}", id, e);
+        }
+    }
+
     async fn add_interrupt_sender(&self, id: Uuid, sender: InterruptSender) {
         let mut map = self.interrupt_senders.write().await;
         map.insert(id, sender);
@@ -207,6 +283,62 @@ impl LocalContainerService {
         });
     }
 
+    /// Start attempts that were deferred by their project's execution window
+    /// once the window opens. Checked every minute, so a start can lag the
+    /// opening time by up to that much.
+    pub fn spawn_deferred_attempt_starter(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                if let Err(e) = container.start_due_deferred_attempts().await {
+                    tracing::error!("Failed to start deferred attempts: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn start_due_deferred_attempts(&self) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let now = chrono::Local::now().naive_local();
+        for deferred in DeferredAttempt::find_all(pool).await? {
+            let window = Project::find_execution_window(pool, deferred.project_id).await?;
+            if window.is_some_and(|window| !window.is_open_at(now)) {
+                continue;
+            }
+            if !DeferredAttempt::take(pool, deferred.workspace_id).await? {
+                continue;
+            }
+            let Some(workspace) = Workspace::find_by_id(pool, deferred.workspace_id).await? else {
+                continue;
+            };
+            let executor_profile_id = deferred.executor_profile_id.0;
+            tracing::info!(
+                "Execution window open, starting deferred attempt {}",
+                workspace.id
+            );
+            match self
+                .start_workspace(&workspace, executor_profile_id.clone())
+                .await
+            {
+                Ok(_) => {
+                    if let Some(task) = workspace.parent_task(pool).await? {
+                        self.spawn_execution_report(
+                            &task,
+                            SharedTaskExecutionState::Started,
+                            Some(executor_profile_id.executor.to_string()),
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start deferred attempt {}: {}", workspace.id, e)
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -240,7 +372,12 @@ impl LocalContainerService {
                 )
                 .await
                 {
-                    Ok(Some(turn)) if turn.summary.is_some() => turn.summary.unwrap(),
+                    Ok(Some(CodingAgentTurn {
+                        summary: Some(summary),
+                        ..
+                    })) => attempt_summary::summarize(&summary)
+                        .map(|short| attempt_summary::commit_message(&short))
+                        .unwrap_or(summary),
                     Ok(_) => {
                         tracing::debug!(
                             "No summary found for execution process {}, using default message",
@@ -395,8 +532,9 @@ impl LocalContainerService {
                 exit_result = &mut exit_signal_future => {
                     // Executor signaled completion: kill group and use the provided result
                     if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let pgid = container.process_group(&exec_id).await;
                         let mut child = child_lock.write().await ;
-                        if let Err(err) = command::kill_process_group(&mut child).await {
+                        if let Err(err) = command::kill_process_group(&mut child, pgid).await {
                             tracing::error!("Failed to kill process group after exit signal: {} {}", exec_id, err);
                         }
                     }
@@ -411,6 +549,15 @@ impl LocalContainerService {
                 // Process exit
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
+
+                    // The child is gone, but whatever it started in the background may not be
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let pgid = container.process_group(&exec_id).await;
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child, pgid).await {
+                            tracing::error!("Failed to kill leftover processes of {}: {}", exec_id, err);
+                        }
+                    }
                 }
             }
 
@@ -434,11 +581,23 @@ impl LocalContainerService {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
+            if let Err(e) = TimeEntry::record_execution(&db.pool, exec_id).await {
+                tracing::warn!(
+                    "Failed to record time entry for execution {}: {}",
+                    exec_id,
+                    e
+                );
+            }
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
+                if let Err(e) = container.update_workspace_summary(&ctx).await {
+                    tracing::warn!("Failed to update workspace summary: {}", e);
+                }
+                container.spawn_attempt_publish(&ctx.task, ctx.workspace.id);
 
                 let success = matches!(
                     ctx.execution_process.status,
@@ -463,6 +622,7 @@ impl LocalContainerService {
                             true
                         }
                     };
+                    container.restore_hand_edits(&ctx).await;
 
                     let should_start_next = if matches!(
                         ctx.execution_process.run_reason,
@@ -492,9 +652,23 @@ impl LocalContainerService {
                         // Manually finalize task since we're bypassing normal execution flow
                         container.finalize_task(&ctx).await;
                     }
+                } else {
+                    container.restore_hand_edits(&ctx).await;
+                }
+
+                if container.should_finalize(&ctx)
+                    && matches!(
+                        ctx.execution_process.status,
+                        ExecutionProcessStatus::Completed
+                    )
+                {
+                    container.enqueue_post_run_checks(&ctx).await;
+                    container.spawn_preview_capture(&ctx);
                 }
 
                 if container.should_finalize(&ctx) {
+                    let scripted_follow_up = container.run_execution_scripts(&ctx).await;
+
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -531,23 +705,82 @@ impl LocalContainerService {
                                 .await
                             {
                                 tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
+                                // Fall back to finalization if follow-up fails; the rest
+                                // of the queue would never be reached
+                                container
+                                    .queued_message_service
+                                    .cancel_queued(ctx.session.id);
                                 container.finalize_task(&ctx).await;
                             }
                         } else {
-                            // Execution failed or was killed - discard the queued message and finalize
+                            // Execution failed or was killed - discard the queue and finalize
+                            let discarded = 1 + container
+                                .queued_message_service
+                                .cancel_queued(ctx.session.id)
+                                .len();
                             tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
+                                "Discarding {} queued message(s) for session {} due to execution status {:?}",
+                                discarded,
                                 ctx.session.id,
                                 ctx.execution_process.status
                             );
                             container.finalize_task(&ctx).await;
                         }
+                    } else if let Some(follow_up) =
+                        scripted_follow_up.filter(|_| should_execute_queued)
+                    {
+                        tracing::info!(
+                            "Starting follow-up requested by automation script for session {}",
+                            ctx.session.id
+                        );
+                        if let Err(e) = container.start_queued_follow_up(&ctx, &follow_up).await {
+                            tracing::error!("Failed to start scripted follow-up: {}", e);
+                            container.finalize_task(&ctx).await;
+                        }
                     } else {
                         container.finalize_task(&ctx).await;
                     }
                 }
 
+                if matches!(
+                    &ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    let (state, detail) = match ctx.execution_process.status {
+                        ExecutionProcessStatus::Completed => {
+                            (SharedTaskExecutionState::Completed, None)
+                        }
+                        ExecutionProcessStatus::Killed => (
+                            SharedTaskExecutionState::Failed,
+                            Some("Stopped by user".to_string()),
+                        ),
+                        _ => (
+                            SharedTaskExecutionState::Failed,
+                            ctx.execution_process
+                                .exit_code
+                                .map(|code| format!("Exit code {code}")),
+                        ),
+                    };
+                    container.spawn_execution_report(&ctx.task, state, detail);
+                    container
+                        .notify_budget_thresholds(ctx.task.project_id)
+                        .await;
+                }
+
+                lifecycle_hooks::spawn(
+                    db.pool.clone(),
+                    ctx.task.project_id,
+                    LifecycleEvent::PostExecution,
+                    json!({
+                        "task_id": ctx.task.id,
+                        "workspace_id": ctx.workspace.id,
+                        "execution_process_id": ctx.execution_process.id,
+                        "run_reason": ctx.execution_process.run_reason,
+                        "status": ctx.execution_process.status,
+                        "exit_code": ctx.execution_process.exit_code,
+                    }),
+                );
+
                 // Fire analytics event when CodingAgent execution has finished
                 if matches!(
                     &ctx.execution_process.run_reason,
@@ -569,6 +802,23 @@ impl LocalContainerService {
             // capture the HEAD OID as the definitive "after" state (best-effort).
             container.update_after_head_commits(exec_id).await;
 
+            // Record what the run left in the attempt's artifacts directory
+            if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                match artifacts::collect(&db.pool, ctx.workspace.id, exec_id).await {
+                    Ok(collected) if !collected.is_empty() => tracing::info!(
+                        "Collected {} artifacts from execution {}",
+                        collected.len(),
+                        exec_id
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(
+                        "Failed to collect artifacts of execution {}: {}",
+                        exec_id,
+                        e
+                    ),
+                }
+            }
+
             // Cleanup msg store
             if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
                 msg_arc.push_finished();
@@ -585,6 +835,7 @@ impl LocalContainerService {
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
+            container.forget_process_group(&exec_id).await;
         })
     }
 
@@ -625,6 +876,18 @@ impl LocalContainerService {
         rx
     }
 
+    /// Base directory for a workspace's files. Workspaces of owned projects
+    /// (multi-user mode) are isolated under a per-user directory.
+    async fn workspace_base_dir(&self, workspace: &Workspace) -> Result<PathBuf, ContainerError> {
+        let base_dir = WorkspaceManager::get_workspace_base_dir();
+        Ok(
+            match ProjectOwner::find_owner_by_workspace_id(&self.db.pool, workspace.id).await? {
+                Some(user_id) => MultiUserService::user_workspace_dir(&base_dir, user_id),
+                None => base_dir,
+            },
+        )
+    }
+
     pub fn dir_name_from_workspace(workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
@@ -715,6 +978,288 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Condense the agent's final message into the workspace's short summary
+    async fn update_workspace_summary(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            return Ok(());
+        }
+
+        let turn =
+            CodingAgentTurn::find_by_execution_process_id(&self.db.pool, ctx.execution_process.id)
+                .await?;
+        if let Some(summary) = turn
+            .and_then(|turn| turn.summary)
+            .and_then(|message| attempt_summary::summarize(&message))
+        {
+            Workspace::update_summary(&self.db.pool, ctx.workspace.id, &summary).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Queue the configured coverage hooks and security scanners for the workspace
+    /// Pop the hand edits stashed before a coding agent run, after its changes
+    /// were committed so they stay out of the agent's commit
+    async fn restore_hand_edits(&self, ctx: &ExecutionContext) {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return;
+        }
+        let repo_names = ctx.repos.iter().map(|repo| repo.name.clone()).collect();
+        worktree_watch::restore_hand_edits(&ctx.workspace, repo_names, ctx.execution_process.id)
+            .await;
+    }
+
+    async fn enqueue_post_run_checks(&self, ctx: &ExecutionContext) {
+        let workspace_id = ctx.workspace.id;
+        self.jobs
+            .enqueue_or_log(JobKind::MeasureCoverage { workspace_id })
+            .await;
+        self.jobs
+            .enqueue_or_log(JobKind::SecurityAudit { workspace_id })
+            .await;
+
+        let settings = match ProjectE2eSettings::find_by_project_id(
+            &self.db.pool,
+            ctx.task.project_id,
+        )
+        .await
+        {
+            Ok(Some(settings)) => settings,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to load browser test settings: {}", e);
+                return;
+            }
+        };
+        let url = match settings.url.filter(|url| !url.trim().is_empty()) {
+            Some(url) => url,
+            None => match self.dev_server_url(workspace_id).await {
+                Some(url) => url,
+                None => {
+                    tracing::debug!(
+                        "No running dev server to test for workspace {}",
+                        workspace_id
+                    );
+                    return;
+                }
+            },
+        };
+        self.jobs
+            .enqueue_or_log(JobKind::RunE2eTests {
+                workspace_id,
+                execution_process_id: Some(ctx.execution_process.id),
+                url,
+            })
+            .await;
+    }
+
+    /// Screenshot the dev server of attempts on the project's preview tasks
+    /// and tell teammates following a shared task (best-effort)
+    fn spawn_preview_capture(&self, ctx: &ExecutionContext) {
+        let container = self.clone();
+        let task = ctx.task.clone();
+        let workspace = ctx.workspace.clone();
+        let workspace_id = workspace.id;
+        let exec_id = ctx.execution_process.id;
+        tokio::spawn(async move {
+            let pool = &container.db.pool;
+            let settings =
+                match ProjectPreviewSettings::find_by_project_id(pool, task.project_id).await {
+                    Ok(Some(settings)) => settings,
+                    Ok(None) => return,
+                    Err(e) => {
+                        tracing::warn!("Failed to load preview settings: {}", e);
+                        return;
+                    }
+                };
+            match preview_capture::applies_to(pool, &settings, &task).await {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => {
+                    tracing::warn!("Failed to load labels of task {}: {}", task.id, e);
+                    return;
+                }
+            }
+
+            let url = match settings.url.clone().filter(|url| !url.trim().is_empty()) {
+                Some(url) => url,
+                None => match container.dev_server_url(workspace_id).await {
+                    Some(url) => url,
+                    None => {
+                        tracing::debug!(
+                            "No running dev server to capture for workspace {}",
+                            workspace_id
+                        );
+                        return;
+                    }
+                },
+            };
+            let mut details = Vec::new();
+            match preview_capture::capture(
+                pool,
+                &settings.command,
+                workspace_id,
+                Some(exec_id),
+                &url,
+            )
+            .await
+            {
+                Ok(artifact) => {
+                    details.push(format!("Preview of {url} captured as {}", artifact.name))
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to capture preview for workspace {}: {}",
+                    workspace_id,
+                    e
+                ),
+            }
+
+            let compare_routes = !settings.visual_routes.is_empty()
+                && settings
+                    .baseline_url
+                    .as_deref()
+                    .is_some_and(|url| !url.trim().is_empty());
+            if compare_routes {
+                match visual_regression::compare_workspace(
+                    pool,
+                    &container.git,
+                    &workspace,
+                    &settings,
+                    Some(exec_id),
+                    &url,
+                )
+                .await
+                {
+                    Ok(comparisons) => details.push(format!(
+                        "{} of {} routes changed visually",
+                        comparisons.iter().filter(|c| c.flagged).count(),
+                        comparisons.len()
+                    )),
+                    Err(e) => tracing::warn!(
+                        "Failed to compare screenshots for workspace {}: {}",
+                        workspace_id,
+                        e
+                    ),
+                }
+            }
+
+            if !details.is_empty() {
+                container.spawn_execution_report(
+                    &task,
+                    SharedTaskExecutionState::Completed,
+                    Some(details.join("; ")),
+                );
+            }
+        });
+    }
+
+    /// Tell the user once a month when a project's agent spending crosses
+    /// each budget alert threshold
+    async fn notify_budget_thresholds(&self, project_id: Uuid) {
+        let status = match ProjectBudget::find_status(&self.db.pool, project_id).await {
+            Ok(Some(status)) => status,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to check budget for project {}: {}", project_id, e);
+                return;
+            }
+        };
+        let mut reached = None;
+        for threshold in status.thresholds_reached() {
+            match BudgetAlert::record_once(&self.db.pool, project_id, &status.month, threshold)
+                .await
+            {
+                Ok(true) => reached = Some(threshold),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to record budget alert: {}", e),
+            }
+        }
+        // Only the highest new threshold is worth a notification
+        let Some(threshold) = reached else {
+            return;
+        };
+        let project_name = Project::find_by_id(&self.db.pool, project_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|project| project.name)
+            .unwrap_or_else(|| "Project".to_string());
+        let message = if threshold >= 100 {
+            format!(
+                "{project_name} has used its ${:.2} budget for {}",
+                status.limit_usd, status.month
+            )
+        } else {
+            format!(
+                "{project_name} has passed {threshold}% of its ${:.2} budget for {} (${:.2} spent)",
+                status.limit_usd, status.month, status.spent_usd
+            )
+        };
+        self.notification_service
+            .notify("Budget alert", &message)
+            .await;
+    }
+
+    /// Let teammates on a shared task follow the attempt (best-effort)
+    fn spawn_execution_report(
+        &self,
+        task: &Task,
+        state: SharedTaskExecutionState,
+        detail: Option<String>,
+    ) {
+        let Some(publisher) = self.share_publisher.clone() else {
+            return;
+        };
+        if task.shared_task_id.is_none() {
+            return;
+        }
+        let task = task.clone();
+        tokio::spawn(async move {
+            if let Err(e) = publisher
+                .report_execution_status(&task, state, detail)
+                .await
+            {
+                tracing::debug!(
+                    "Failed to report execution status for task {}: {}",
+                    task.id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Let teammates on a shared task see the attempt's summary and PR state
+    /// (best-effort)
+    fn spawn_attempt_publish(&self, task: &Task, workspace_id: Uuid) {
+        let Some(publisher) = self.share_publisher.clone() else {
+            return;
+        };
+        if task.shared_task_id.is_none() {
+            return;
+        }
+        tokio::spawn(async move {
+            if let Err(e) = publisher.publish_attempt(workspace_id).await {
+                tracing::debug!("Failed to publish attempt {}: {}", workspace_id, e);
+            }
+        });
+    }
+
+    /// Share or unshare the task in the background as its project's share
+    /// rules dictate
+    fn spawn_share_rules(&self, task_id: Uuid) {
+        let Some(publisher) = self.share_publisher.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if let Err(e) = publisher.apply_share_rules(task_id).await {
+                tracing::debug!("Failed to apply share rules to task {}: {}", task_id, e);
+            }
+        });
+    }
+
     /// Copy project files and images to the workspace.
     /// Skips files/images that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -812,6 +1357,139 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Create or restore the worktrees of the workspace's extra checkouts,
+    /// next to the primary worktrees of their repos
+    async fn ensure_checkouts(
+        &self,
+        workspace_id: Uuid,
+        workspace_dir: &Path,
+        repos: &[Repo],
+    ) -> Result<(), ContainerError> {
+        let checkouts: Vec<(Repo, WorkspaceRepoCheckout)> =
+            WorkspaceRepoCheckout::find_by_workspace_id(&self.db.pool, workspace_id)
+                .await?
+                .into_iter()
+                .filter_map(|checkout| {
+                    repos
+                        .iter()
+                        .find(|repo| repo.id == checkout.repo_id)
+                        .map(|repo| (repo.clone(), checkout))
+                })
+                .collect();
+        if checkouts.is_empty() {
+            return Ok(());
+        }
+        WorkspaceManager::ensure_checkouts_exist(workspace_dir, &checkouts).await?;
+        Ok(())
+    }
+
+    /// Limit the worktrees of scoped repos to their path scope. Worktrees that
+    /// are already sparse are left alone.
+    async fn apply_path_scopes(
+        &self,
+        workspace_id: Uuid,
+        workspace_dir: &Path,
+        repos: &[Repo],
+    ) -> Result<(), ContainerError> {
+        let path_scopes = WorkspaceRepo::find_path_scopes(&self.db.pool, workspace_id).await?;
+        if path_scopes.is_empty() {
+            return Ok(());
+        }
+        let git = GitCli::new();
+        for repo in repos {
+            let Some(scope) = path_scopes.get(&repo.id) else {
+                continue;
+            };
+            let worktree = workspace_dir.join(&repo.name);
+            match path_scope::apply_sparse_checkout(&git, &worktree, scope) {
+                Ok(true) => {
+                    tracing::info!("Limited checkout of {} to {}", worktree.display(), scope)
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to set sparse checkout of {} to {}: {}",
+                    worktree.display(),
+                    scope,
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the project's env templates into worktrees that lack them.
+    /// Failures are logged so they never block creating the workspace.
+    async fn materialize_env_files(
+        &self,
+        project_id: Uuid,
+        workspace: &Workspace,
+        workspace_dir: &Path,
+        repos: &[Repo],
+    ) {
+        match env_templates::materialize(
+            &self.db.pool,
+            &self.secrets,
+            project_id,
+            workspace,
+            workspace_dir,
+            repos,
+            false,
+        )
+        .await
+        {
+            Ok(written) if !written.is_empty() => tracing::info!(
+                "Wrote {} env file(s) into workspace {}",
+                written.len(),
+                workspace.id
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                "Failed to write env files into workspace {}: {}",
+                workspace.id,
+                e
+            ),
+        }
+    }
+
+    /// Run the project's `execution_finished` scripts. Follow-ups they request
+    /// are combined into one message for the session.
+    async fn run_execution_scripts(&self, ctx: &ExecutionContext) -> Option<DraftFollowUpData> {
+        let data = json!({
+            "workspace_id": ctx.workspace.id,
+            "session_id": ctx.session.id,
+            "execution_process_id": ctx.execution_process.id,
+            "run_reason": ctx.execution_process.run_reason,
+            "status": ctx.execution_process.status,
+            "exit_code": ctx.execution_process.exit_code,
+        });
+        let prompts = match automation_scripts::run(
+            &self.db.pool,
+            &self.git,
+            &ctx.task,
+            ScriptEvent::ExecutionFinished,
+            data,
+        )
+        .await
+        {
+            Ok(prompts) if !prompts.is_empty() => prompts,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to run execution_finished scripts: {}", e);
+                return None;
+            }
+        };
+
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await
+                .ok()
+                .flatten()?;
+        Some(DraftFollowUpData {
+            message: prompts.join("\n\n"),
+            executor_profile_id,
+        })
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -866,18 +1544,24 @@ impl LocalContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
+        let model = Project::find_executor_defaults_for_workspace(&self.db.pool, ctx.workspace.id)
+            .await?
+            .model_for(&executor_profile_id);
+
         let action_type = if let Some(agent_session_id) = latest_agent_session_id {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
                 prompt: queued_data.message.clone(),
                 session_id: agent_session_id,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir: working_dir.clone(),
+                model: model.clone(),
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
                 prompt: queued_data.message.clone(),
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                model,
             })
         };
 
@@ -928,6 +1612,13 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn similar_tasks_embedder(&self) -> Option<Embedder> {
+        let config = self.config.read().await;
+        config
+            .similar_tasks_in_prompt
+            .then(|| task_knowledge::embedder(&config))
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -940,7 +1631,10 @@ impl ContainerService for LocalContainerService {
 
         let workspace_dir_name =
             LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
-        let workspace_dir = WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name);
+        let workspace_dir = self
+            .workspace_base_dir(workspace)
+            .await?
+            .join(&workspace_dir_name);
 
         let workspace_repos =
             WorkspaceRepo::find_by_workspace_id(&self.db.pool, workspace.id).await?;
@@ -973,10 +1667,40 @@ impl ContainerService for LocalContainerService {
         )
         .await?;
 
+        self.ensure_checkouts(
+            workspace.id,
+            &created_workspace.workspace_dir,
+            &repositories,
+        )
+        .await?;
+
+        self.apply_path_scopes(
+            workspace.id,
+            &created_workspace.workspace_dir,
+            &repositories,
+        )
+        .await?;
+
         // Copy project files and images to workspace
         self.copy_files_and_images(&created_workspace.workspace_dir, workspace)
             .await?;
 
+        self.materialize_env_files(
+            task.project_id,
+            workspace,
+            &created_workspace.workspace_dir,
+            &repositories,
+        )
+        .await;
+
+        agent_instructions::sync_workspace(
+            &self.db.pool,
+            task.project_id,
+            &created_workspace.workspace_dir,
+            &repositories,
+        )
+        .await?;
+
         Self::create_workspace_config_files(&created_workspace.workspace_dir, &repositories)
             .await?;
 
@@ -1022,11 +1746,17 @@ impl ContainerService for LocalContainerService {
                 .ok_or(sqlx::Error::RowNotFound)?;
             let workspace_dir_name =
                 LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
-            WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name)
+            self.workspace_base_dir(workspace)
+                .await?
+                .join(&workspace_dir_name)
         };
 
         WorkspaceManager::ensure_workspace_exists(&workspace_dir, &repositories, &workspace.branch)
             .await?;
+        self.ensure_checkouts(workspace.id, &workspace_dir, &repositories)
+            .await?;
+        self.apply_path_scopes(workspace.id, &workspace_dir, &repositories)
+            .await?;
 
         if workspace.container_ref.is_none() {
             Workspace::update_container_ref(
@@ -1041,6 +1771,12 @@ impl ContainerService for LocalContainerService {
         self.copy_files_and_images(&workspace_dir, workspace)
             .await?;
 
+        // Worktrees recreated after cleanup get their env files back
+        if let Some(task) = workspace.parent_task(&self.db.pool).await? {
+            self.materialize_env_files(task.project_id, workspace, &workspace_dir, &repositories)
+                .await;
+        }
+
         Self::create_workspace_config_files(&workspace_dir, &repositories).await?;
 
         Ok(workspace_dir.to_string_lossy().to_string())
@@ -1105,9 +1841,18 @@ impl ContainerService for LocalContainerService {
         let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
         let repo_context = RepoContext::new(current_dir.clone(), repo_names);
 
-        let commit_reminder = self.config.read().await.commit_reminder;
+        let (commit_reminder, auto_stash_hand_edits) = {
+            let config = self.config.read().await;
+            (config.commit_reminder, config.auto_stash_hand_edits)
+        };
         let mut env = ExecutionEnv::new(repo_context, commit_reminder);
 
+        if auto_stash_hand_edits
+            && execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+        {
+            worktree_watch::stash_hand_edits(workspace, execution_process.id).await;
+        }
+
         // Load task and project context for environment variables
         let task = workspace
             .parent_task(&self.db.pool)
@@ -1120,12 +1865,27 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
 
+        // Provider keys first, so the values below always win
+        env.merge(&self.secrets.agent_env().await);
         env.insert("VK_PROJECT_NAME", &project.name);
         env.insert("VK_PROJECT_ID", project.id.to_string());
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        let artifacts_dir = artifacts::workspace_dir(workspace.id);
+        if let Err(e) = tokio::fs::create_dir_all(&artifacts_dir).await {
+            tracing::warn!(
+                "Failed to create artifacts directory {}: {}",
+                artifacts_dir.display(),
+                e
+            );
+        }
+        env.insert(
+            artifacts::ARTIFACTS_DIR_ENV,
+            artifacts_dir.to_string_lossy().to_string(),
+        );
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1141,6 +1901,8 @@ impl ContainerService for LocalContainerService {
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
+        self.record_process_group(execution_process.id, &mut spawned.child)
+            .await;
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1153,6 +1915,18 @@ impl ContainerService for LocalContainerService {
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
 
+        if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            self.spawn_execution_report(
+                &task,
+                SharedTaskExecutionState::Executing,
+                executor_action.base_executor().map(|e| e.to_string()),
+            );
+            self.spawn_attempt_publish(&task, workspace.id);
+        }
+
         Ok(())
     }
 
@@ -1208,8 +1982,9 @@ impl ContainerService for LocalContainerService {
 
         // Kill the child process and remove from the store
         {
+            let pgid = self.process_group(&execution_process.id).await;
             let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
+            if let Err(e) = command::kill_process_group(&mut child_guard, pgid).await {
                 tracing::error!(
                     "Failed to stop execution process {}: {}",
                     execution_process.id,
@@ -1231,10 +2006,11 @@ impl ContainerService for LocalContainerService {
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
-            && let Err(e) =
-                Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
         {
-            tracing::error!("Failed to update task status to InReview: {e}");
+            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
+                Ok(()) => self.spawn_share_rules(ctx.task.id),
+                Err(e) => tracing::error!("Failed to update task status to InReview: {e}"),
+            }
         }
 
         tracing::debug!(
@@ -1268,6 +2044,8 @@ impl ContainerService for LocalContainerService {
 
         let container_ref = self.ensure_container_exists(workspace).await?;
         let workspace_root = PathBuf::from(container_ref);
+        let ignore = DiffIgnore::for_workspace(&self.db.pool, workspace).await?;
+        let path_scopes = WorkspaceRepo::find_path_scopes(&self.db.pool, workspace.id).await?;
 
         for repo in repositories {
             let worktree_path = workspace_root.join(&repo.name);
@@ -1309,6 +2087,9 @@ impl ContainerService for LocalContainerService {
                     base_commit: base_commit.clone(),
                     stats_only,
                     path_prefix: Some(repo.name.clone()),
+                    ignore: ignore
+                        .clone()
+                        .with_scope(path_scopes.get(&repo.id).map(String::as_str)),
                 })
                 .await?;
 
@@ -1331,7 +2112,10 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        let message = self.get_commit_message(ctx).await;
+        let mut message = self.get_commit_message(ctx).await;
+        if let Some(key) = self.config.read().await.task_trailer.clone() {
+            message = traceability::append_trailer(&message, &key, ctx.task.id);
+        }
 
         let container_ref = ctx
             .workspace
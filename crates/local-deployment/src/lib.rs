@@ -5,27 +5,39 @@ use db::DBService;
 use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured};
 use executors::profile::ExecutorConfigs;
 use services::services::{
-    analytics::{AnalyticsConfig, AnalyticsService, generate_user_id},
+    analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
+    artifacts, attachments,
     auth::AuthContext,
+    bounded_command::OperationTimeouts,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
+    git_host::{
+        bitbucket::{BitbucketCredentials, BitbucketProvider},
+        github::GitHubProvider,
+    },
+    github_app::GitHubAppAuth,
+    i18n::Locale,
     image::ImageService,
+    jobs::JobService,
+    multi_user::MultiUserService,
     oauth_credentials::OAuthCredentials,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    secrets::SecretStore,
+    share::SharePublisher,
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
 use utils::{
     api::oauth::LoginStatus,
-    assets::{config_path, credentials_path},
+    assets::{config_path, credentials_path, secrets_key_path, secrets_path},
     msg_store::MsgStore,
 };
 use uuid::Uuid;
@@ -36,6 +48,9 @@ pub mod container;
 mod copy;
 pub mod pty;
 
+/// How often expired attempt artifacts are looked for, starting at launch
+const ARTIFACT_PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
 #[derive(Clone)]
 pub struct LocalDeployment {
     config: Arc<RwLock<Config>>,
@@ -52,8 +67,11 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    jobs: JobService,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     auth_context: AuthContext,
+    secrets: Arc<SecretStore>,
+    multi_user: Option<MultiUserService>,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
     pty: PtyService,
 }
@@ -76,17 +94,47 @@ impl Deployment for LocalDeployment {
             raw_config.executor_profile = recommended_executor;
         }
 
-        // Always save config (may have been migrated)
-        save_config_to_file(&raw_config, &config_path()).await?;
+        let secrets = Arc::new(SecretStore::new(secrets_path(), secrets_key_path()));
+        // Saving strips the secrets from the config file, so it has to wait
+        // until they are safely in the store
+        let secrets_ready = match secrets.load().await {
+            Ok(()) => secrets
+                .hydrate_config(&mut raw_config)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match secrets_ready {
+            // Always save config (may have been migrated)
+            Ok(()) => save_config_to_file(&raw_config, &config_path()).await?,
+            Err(e) => tracing::error!(
+                "Secret store unavailable, leaving the config file as it is: {}",
+                e
+            ),
+        }
 
         if let Some(workspace_dir) = &raw_config.workspace_dir {
             let path = utils::path::expand_tilde(workspace_dir);
             WorktreeManager::set_workspace_dir_override(path);
         }
 
+        GitHubAppAuth::init_global(raw_config.github.app_installation_id).await;
+        GitHubProvider::set_config_token(raw_config.github.token());
+        GitHubProvider::set_enterprise_hosts(&raw_config.github.enterprise_hosts);
+        BitbucketProvider::set_config_credentials(BitbucketCredentials::from_config(&raw_config));
+        Locale::set_current(raw_config.language);
+        OperationTimeouts::set_current(OperationTimeouts::from_config(&raw_config));
+
+        let analytics = AnalyticsService::new(AnalyticsConfig::from_config(&raw_config));
+        analytics.spawn_flusher();
+
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
-        let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
+        let analytics_context = AnalyticsContext {
+            user_id: user_id.clone(),
+            analytics_service: analytics.clone(),
+        };
+        let analytics = Some(analytics);
         let git = GitService::new();
         let project = ProjectService::new();
         let repo = RepoService::new();
@@ -107,6 +155,10 @@ impl Deployment for LocalDeployment {
             DBService::new_with_after_connect(hook).await?
         };
 
+        if let Err(e) = repo.load_ssh_keys(&db.pool).await {
+            tracing::warn!("Failed to load repo SSH keys: {}", e);
+        }
+
         let image = ImageService::new(db.clone().pool)?;
         {
             let image_service = image.clone();
@@ -117,9 +169,32 @@ impl Deployment for LocalDeployment {
                 }
             });
         }
+        {
+            let pool = db.pool.clone();
+            tokio::spawn(async move {
+                if let Err(e) = attachments::delete_orphaned_files(&pool).await {
+                    tracing::error!("Failed to clean up orphaned attachments: {}", e);
+                }
+            });
+        }
+        {
+            let pool = db.pool.clone();
+            let config = config.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(ARTIFACT_PRUNE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let retention_days = config.read().await.artifact_retention_days;
+                    if let Err(e) = artifacts::prune(&pool, retention_days).await {
+                        tracing::error!("Failed to prune attempt artifacts: {}", e);
+                    }
+                }
+            });
+        }
 
         let approvals = Approvals::new(msg_stores.clone());
         let queued_message_service = QueuedMessageService::new();
+        let jobs = JobService::new(db.clone(), git.clone());
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -158,9 +233,15 @@ impl Deployment for LocalDeployment {
             config.clone(),
             git.clone(),
             image.clone(),
-            None, // analytics was removed
+            Some(analytics_context),
             approvals.clone(),
             queued_message_service.clone(),
+            remote_client
+                .clone()
+                .ok()
+                .map(|client| SharePublisher::new(db.clone(), client)),
+            jobs.clone(),
+            secrets.clone(),
         )
         .await;
 
@@ -170,6 +251,11 @@ impl Deployment for LocalDeployment {
 
         let pty = PtyService::new();
 
+        let multi_user = MultiUserService::from_env();
+        if multi_user.is_some() {
+            tracing::info!("Multi-user mode enabled; API requests require an access token");
+        }
+
         let deployment = Self {
             config,
             user_id,
@@ -185,8 +271,11 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             queued_message_service,
+            jobs,
             remote_client,
             auth_context,
+            secrets,
+            multi_user,
             oauth_handoffs,
             pty,
         };
@@ -250,10 +339,22 @@ impl Deployment for LocalDeployment {
         &self.queued_message_service
     }
 
+    fn jobs(&self) -> &JobService {
+        &self.jobs
+    }
+
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
 
+    fn secrets(&self) -> &Arc<SecretStore> {
+        &self.secrets
+    }
+
+    fn multi_user(&self) -> &Option<MultiUserService> {
+        &self.multi_user
+    }
+
     fn share_publisher(
         &self,
     ) -> Result<services::services::share::SharePublisher, RemoteClientNotConfigured> {
@@ -266,9 +367,11 @@ impl Deployment for LocalDeployment {
 }
 
 impl LocalDeployment {
-    // stub para track_if_analytics_allowed (analytics fue removido)
-    pub async fn track_if_analytics_allowed(&self, _event_name: &str, _data: serde_json::Value) {
-        // analytics removido - este método no hace nada
+    /// Queue a usage event; a no-op unless the user opted into analytics
+    pub async fn track_if_analytics_allowed(&self, event_name: &str, data: serde_json::Value) {
+        if let Some(analytics) = &self.analytics {
+            analytics.track_event(&self.user_id, event_name, Some(data));
+        }
     }
 
     pub fn remote_client(&self) -> Result<RemoteClient, RemoteClientNotConfigured> {
@@ -0,0 +1,8 @@
+-- Baseline configuration an organization distributes to its members' local instances
+CREATE TABLE IF NOT EXISTS organization_shared_configs (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    config          JSONB NOT NULL DEFAULT '{}'::jsonb,
+    version         BIGINT NOT NULL DEFAULT 1,
+    updated_by      UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
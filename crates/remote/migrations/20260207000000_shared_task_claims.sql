@@ -0,0 +1,10 @@
+-- Short-lived claims on shared tasks, taken when a member starts an agent on
+-- the task so that other members do not start a duplicate run
+CREATE TABLE IF NOT EXISTS shared_task_claims (
+    task_id    UUID PRIMARY KEY REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    user_id    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_shared_task_claims_user_id ON shared_task_claims(user_id);
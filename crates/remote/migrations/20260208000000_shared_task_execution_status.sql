@@ -0,0 +1,9 @@
+-- Latest agent lifecycle state reported for each shared task, so teammates
+-- can follow a run in progress rather than only the final status change
+CREATE TABLE IF NOT EXISTS shared_task_execution_status (
+    task_id    UUID PRIMARY KEY REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    user_id    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    state      TEXT NOT NULL,
+    detail     TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
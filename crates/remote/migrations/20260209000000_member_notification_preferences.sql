@@ -0,0 +1,20 @@
+-- Which shared task events reach each member of an organization, so
+-- teammates can opt out of routine agent activity
+CREATE TABLE IF NOT EXISTS member_notification_preferences (
+    organization_id         UUID NOT NULL,
+    user_id                 UUID NOT NULL,
+    notify_on_assignment    BOOLEAN NOT NULL DEFAULT TRUE,
+    notify_on_mention       BOOLEAN NOT NULL DEFAULT TRUE,
+    notify_on_status_change BOOLEAN NOT NULL DEFAULT TRUE,
+    digest                  TEXT NOT NULL DEFAULT 'off'
+        CHECK (digest IN ('off', 'daily', 'weekly')),
+    updated_at              TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id),
+    FOREIGN KEY (organization_id, user_id)
+        REFERENCES organization_member_metadata (organization_id, user_id)
+        ON DELETE CASCADE
+);
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'task_assigned';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'task_status_changed';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'task_activity_digest';
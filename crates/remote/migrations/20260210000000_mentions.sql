@@ -0,0 +1,30 @@
+-- Members mentioned by @username in a shared task description or an issue
+-- comment. One row per member and source, so editing the text does not
+-- mention them again.
+CREATE TABLE IF NOT EXISTS mentions (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id   UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    mentioned_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    author_user_id    UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    shared_task_id    UUID REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    issue_id          UUID REFERENCES issues(id) ON DELETE CASCADE,
+    issue_comment_id  UUID REFERENCES issue_comments(id) ON DELETE CASCADE,
+    excerpt           TEXT NOT NULL,
+    read_at           TIMESTAMPTZ,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((shared_task_id IS NOT NULL) <> (issue_comment_id IS NOT NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_mentions_user_shared_task
+    ON mentions (mentioned_user_id, shared_task_id)
+    WHERE shared_task_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_mentions_user_issue_comment
+    ON mentions (mentioned_user_id, issue_comment_id)
+    WHERE issue_comment_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_mentions_user_unread
+    ON mentions (mentioned_user_id, created_at DESC)
+    WHERE read_at IS NULL;
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'mentioned';
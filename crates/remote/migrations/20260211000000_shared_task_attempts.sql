@@ -0,0 +1,24 @@
+-- Summary and pull request state of each attempt on a shared task, synced to
+-- teammates through Electric so their boards show live PR/merge state
+CREATE TABLE IF NOT EXISTS shared_task_attempts (
+    -- Id of the workspace on the reporting member's local instance
+    id           UUID PRIMARY KEY,
+    task_id      UUID NOT NULL REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    project_id   UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id      UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    branch       TEXT NOT NULL,
+    summary      TEXT,
+    pr_url       TEXT,
+    pr_number    INTEGER,
+    pr_status    pull_request_status,
+    pr_merged_at TIMESTAMPTZ,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_shared_task_attempts_project
+    ON shared_task_attempts (project_id);
+CREATE INDEX IF NOT EXISTS idx_shared_task_attempts_task
+    ON shared_task_attempts (task_id);
+
+SELECT electric_sync_table('public', 'shared_task_attempts');
@@ -14,6 +14,7 @@ use remote::{
         project_statuses::ProjectStatus,
         projects::Project,
         pull_requests::PullRequest,
+        shared_task_attempts::SharedTaskAttempt,
         tags::Tag,
         types::{IssuePriority, IssueRelationshipType, PullRequestStatus},
         users::User,
@@ -98,6 +99,7 @@ fn export_shapes() -> String {
         IssuePriority::decl(),
         PullRequestStatus::decl(),
         PullRequest::decl(),
+        SharedTaskAttempt::decl(),
         UserData::decl(),
         User::decl(),
         MemberRole::decl(),
@@ -138,6 +138,74 @@ impl<'a> InvitationRepository<'a> {
         Ok(invitations)
     }
 
+    /// Mark an organization's pending invitations that are past their expiry
+    pub async fn expire_stale(&self, organization_id: Uuid) -> Result<(), IdentityError> {
+        sqlx::query!(
+            r#"
+            UPDATE organization_invitations
+            SET status = 'expired'
+            WHERE organization_id = $1 AND status = 'pending' AND expires_at < NOW()
+            "#,
+            organization_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Give a pending or expired invitation a fresh token and expiry so a new
+    /// email can be sent; links from earlier emails stop working
+    pub async fn resend_invitation(
+        &self,
+        organization_id: Uuid,
+        invitation_id: Uuid,
+        requesting_user_id: Uuid,
+        expires_at: DateTime<Utc>,
+        token: &str,
+    ) -> Result<Invitation, IdentityError> {
+        assert_admin(self.pool, organization_id, requesting_user_id).await?;
+
+        sqlx::query_as!(
+            Invitation,
+            r#"
+            UPDATE organization_invitations
+            SET token = $3, expires_at = $4, status = 'pending'
+            WHERE id = $1
+              AND organization_id = $2
+              AND status IN ('pending', 'expired')
+            RETURNING
+                id AS "id!",
+                organization_id AS "organization_id!: Uuid",
+                invited_by_user_id AS "invited_by_user_id?: Uuid",
+                email AS "email!",
+                role AS "role!: MemberRole",
+                status AS "status!: InvitationStatus",
+                token AS "token!",
+                expires_at AS "expires_at!",
+                created_at AS "created_at!",
+                updated_at AS "updated_at!"
+            "#,
+            invitation_id,
+            organization_id,
+            token,
+            expires_at
+        )
+        .fetch_optional(self.pool)
+        .await
+        .map_err(|e| {
+            if let Some(db_err) = e.as_database_error()
+                && db_err.is_unique_violation()
+            {
+                return IdentityError::InvitationError(
+                    "A pending invitation already exists for this email".to_string(),
+                );
+            }
+            IdentityError::from(e)
+        })?
+        .ok_or(IdentityError::NotFound)
+    }
+
     pub async fn get_invitation_by_token(&self, token: &str) -> Result<Invitation, IdentityError> {
         sqlx::query_as!(
             Invitation,
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::users::UserData;
+
+/// Longest excerpt of the mentioning text kept with a mention
+pub const MAX_MENTION_EXCERPT_CHARS: usize = 280;
+
+/// Where a member was mentioned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionSource {
+    SharedTask(Uuid),
+    IssueComment { issue_id: Uuid, comment_id: Uuid },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Mention {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub author: UserData,
+    pub shared_task_id: Option<Uuid>,
+    pub issue_id: Option<Uuid>,
+    pub issue_comment_id: Option<Uuid>,
+    pub excerpt: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+struct MentionRow {
+    id: Uuid,
+    organization_id: Uuid,
+    author_user_id: Uuid,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    username: Option<String>,
+    shared_task_id: Option<Uuid>,
+    issue_id: Option<Uuid>,
+    issue_comment_id: Option<Uuid>,
+    excerpt: String,
+    read_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<MentionRow> for Mention {
+    fn from(row: MentionRow) -> Self {
+        Self {
+            id: row.id,
+            organization_id: row.organization_id,
+            author: UserData {
+                user_id: row.author_user_id,
+                first_name: row.first_name,
+                last_name: row.last_name,
+                username: row.username,
+            },
+            shared_task_id: row.shared_task_id,
+            issue_id: row.issue_id,
+            issue_comment_id: row.issue_comment_id,
+            excerpt: row.excerpt,
+            read_at: row.read_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+pub struct MentionRepository;
+
+impl MentionRepository {
+    /// Members of the organization with one of the given usernames, matched
+    /// case-insensitively
+    pub async fn resolve_usernames(
+        pool: &PgPool,
+        organization_id: Uuid,
+        usernames: &[String],
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        if usernames.is_empty() {
+            return Ok(Vec::new());
+        }
+        let lowered: Vec<String> = usernames.iter().map(|u| u.to_lowercase()).collect();
+        sqlx::query_scalar!(
+            r#"
+            SELECT u.id AS "id!: Uuid"
+            FROM users u
+            JOIN organization_member_metadata omm ON omm.user_id = u.id
+            WHERE omm.organization_id = $1 AND LOWER(u.username) = ANY($2)
+            "#,
+            organization_id,
+            &lowered
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record mentions of `user_ids`, returning the members who were not
+    /// already mentioned in the same source
+    pub async fn record(
+        pool: &PgPool,
+        organization_id: Uuid,
+        author_user_id: Uuid,
+        source: MentionSource,
+        user_ids: &[Uuid],
+        excerpt: &str,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let (shared_task_id, issue_id, issue_comment_id) = match source {
+            MentionSource::SharedTask(task_id) => (Some(task_id), None, None),
+            MentionSource::IssueComment {
+                issue_id,
+                comment_id,
+            } => (None, Some(issue_id), Some(comment_id)),
+        };
+        let excerpt: String = excerpt.chars().take(MAX_MENTION_EXCERPT_CHARS).collect();
+
+        let mut recorded = Vec::new();
+        for user_id in user_ids {
+            let inserted = sqlx::query_scalar!(
+                r#"
+                INSERT INTO mentions
+                    (organization_id, mentioned_user_id, author_user_id, shared_task_id,
+                     issue_id, issue_comment_id, excerpt)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT DO NOTHING
+                RETURNING mentioned_user_id AS "mentioned_user_id!: Uuid"
+                "#,
+                organization_id,
+                *user_id,
+                author_user_id,
+                shared_task_id,
+                issue_id,
+                issue_comment_id,
+                &excerpt
+            )
+            .fetch_optional(pool)
+            .await?;
+            recorded.extend(inserted);
+        }
+
+        Ok(recorded)
+    }
+
+    pub async fn list_unread(pool: &PgPool, user_id: Uuid) -> Result<Vec<Mention>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            MentionRow,
+            r#"
+            SELECT
+                m.id                AS "id!: Uuid",
+                m.organization_id   AS "organization_id!: Uuid",
+                m.author_user_id    AS "author_user_id!: Uuid",
+                u.first_name        AS "first_name: String",
+                u.last_name         AS "last_name: String",
+                u.username          AS "username: String",
+                m.shared_task_id    AS "shared_task_id: Uuid",
+                m.issue_id          AS "issue_id: Uuid",
+                m.issue_comment_id  AS "issue_comment_id: Uuid",
+                m.excerpt           AS "excerpt!: String",
+                m.read_at           AS "read_at: DateTime<Utc>",
+                m.created_at        AS "created_at!: DateTime<Utc>"
+            FROM mentions m
+            JOIN users u ON u.id = m.author_user_id
+            WHERE m.mentioned_user_id = $1 AND m.read_at IS NULL
+            ORDER BY m.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Mark the given mentions of `user_id` as read, or all of them when
+    /// `mention_ids` is `None`
+    pub async fn mark_read(
+        pool: &PgPool,
+        user_id: Uuid,
+        mention_ids: Option<&[Uuid]>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE mentions
+            SET read_at = NOW()
+            WHERE mentioned_user_id = $1
+              AND read_at IS NULL
+              AND ($2::uuid[] IS NULL OR id = ANY($2))
+            "#,
+            user_id,
+            mention_ids
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -9,17 +9,22 @@ pub mod issue_followers;
 pub mod issue_relationships;
 pub mod issue_tags;
 pub mod issues;
+pub mod mentions;
 pub mod notifications;
 pub mod oauth;
 pub mod oauth_accounts;
 pub mod organization_members;
+pub mod organization_shared_configs;
 pub mod organizations;
 pub mod project_notification_preferences;
 pub mod project_statuses;
 pub mod projects;
 pub mod pull_requests;
 pub mod reviews;
+pub mod shared_task_attempts;
 pub mod tags;
+pub mod task_claims;
+pub mod task_execution_status;
 pub mod tasks;
 pub mod types;
 pub mod users;
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::{Executor, Postgres, Type};
+use sqlx::{Executor, PgPool, Postgres, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -14,6 +14,10 @@ pub enum NotificationType {
     IssueStatusChanged,
     IssueAssigneeChanged,
     IssueDeleted,
+    TaskAssigned,
+    TaskStatusChanged,
+    TaskActivityDigest,
+    Mentioned,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -308,6 +312,60 @@ impl NotificationRepository {
         Ok(record)
     }
 
+    /// Add an event to the member's open digest for the period starting at
+    /// `period_start`, opening a new digest when there is none yet
+    pub async fn append_to_digest(
+        pool: &PgPool,
+        organization_id: Uuid,
+        user_id: Uuid,
+        period_start: DateTime<Utc>,
+        event: Value,
+    ) -> Result<(), NotificationError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET payload = jsonb_set(
+                    payload,
+                    '{events}',
+                    COALESCE(payload->'events', '[]'::jsonb) || jsonb_build_array($4::jsonb)
+                ),
+                seen = FALSE
+            WHERE id = (
+                SELECT id
+                FROM notifications
+                WHERE organization_id = $1
+                  AND user_id = $2
+                  AND notification_type = 'task_activity_digest'
+                  AND dismissed_at IS NULL
+                  AND created_at >= $3
+                ORDER BY created_at DESC
+                LIMIT 1
+            )
+            "#,
+            organization_id,
+            user_id,
+            period_start,
+            &event
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            Self::create(
+                pool,
+                organization_id,
+                user_id,
+                NotificationType::TaskActivityDigest,
+                serde_json::json!({ "period_start": period_start, "events": [event] }),
+                None,
+                None,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<(), NotificationError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, PgPool, Postgres};
 use ts_rs::TS;
+use utils::api::organizations::MemberNotificationPreferences;
 pub use utils::api::organizations::MemberRole;
 use uuid::Uuid;
 
@@ -150,3 +151,85 @@ pub(super) async fn assert_admin(
         _ => Err(IdentityError::PermissionDenied),
     }
 }
+
+struct NotificationPreferencesRow {
+    notify_on_assignment: bool,
+    notify_on_mention: bool,
+    notify_on_status_change: bool,
+    digest: String,
+}
+
+impl From<NotificationPreferencesRow> for MemberNotificationPreferences {
+    fn from(row: NotificationPreferencesRow) -> Self {
+        Self {
+            notify_on_assignment: row.notify_on_assignment,
+            notify_on_mention: row.notify_on_mention,
+            notify_on_status_change: row.notify_on_status_change,
+            digest: row.digest.parse().unwrap_or_default(),
+        }
+    }
+}
+
+/// A member's notification preferences; the defaults until they change them
+pub async fn notification_preferences(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<MemberNotificationPreferences, sqlx::Error> {
+    let row = sqlx::query_as!(
+        NotificationPreferencesRow,
+        r#"
+        SELECT
+            notify_on_assignment    AS "notify_on_assignment!",
+            notify_on_mention       AS "notify_on_mention!",
+            notify_on_status_change AS "notify_on_status_change!",
+            digest                  AS "digest!"
+        FROM member_notification_preferences
+        WHERE organization_id = $1 AND user_id = $2
+        "#,
+        organization_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(Into::into).unwrap_or_default())
+}
+
+pub async fn set_notification_preferences(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+    preferences: &MemberNotificationPreferences,
+) -> Result<MemberNotificationPreferences, sqlx::Error> {
+    let row = sqlx::query_as!(
+        NotificationPreferencesRow,
+        r#"
+        INSERT INTO member_notification_preferences
+            (organization_id, user_id, notify_on_assignment, notify_on_mention,
+             notify_on_status_change, digest)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (organization_id, user_id) DO UPDATE
+        SET notify_on_assignment    = EXCLUDED.notify_on_assignment,
+            notify_on_mention       = EXCLUDED.notify_on_mention,
+            notify_on_status_change = EXCLUDED.notify_on_status_change,
+            digest                  = EXCLUDED.digest,
+            updated_at              = NOW()
+        RETURNING
+            notify_on_assignment    AS "notify_on_assignment!",
+            notify_on_mention       AS "notify_on_mention!",
+            notify_on_status_change AS "notify_on_status_change!",
+            digest                  AS "digest!"
+        "#,
+        organization_id,
+        user_id,
+        preferences.notify_on_assignment,
+        preferences.notify_on_mention,
+        preferences.notify_on_status_change,
+        preferences.digest.as_str()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.into())
+}
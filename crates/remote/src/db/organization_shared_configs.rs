@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, types::Json};
+use utils::api::organizations::{OrganizationSharedConfig, SharedConfigBaseline};
+use uuid::Uuid;
+
+struct SharedConfigRow {
+    organization_id: Uuid,
+    config: Json<SharedConfigBaseline>,
+    version: i64,
+    updated_by: Option<Uuid>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SharedConfigRow> for OrganizationSharedConfig {
+    fn from(row: SharedConfigRow) -> Self {
+        Self {
+            organization_id: row.organization_id,
+            config: row.config.0,
+            version: row.version,
+            updated_by: row.updated_by,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+pub struct OrganizationSharedConfigRepository;
+
+impl OrganizationSharedConfigRepository {
+    pub async fn find(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Option<OrganizationSharedConfig>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            SharedConfigRow,
+            r#"
+            SELECT
+                organization_id AS "organization_id!: Uuid",
+                config          AS "config!: Json<SharedConfigBaseline>",
+                version         AS "version!",
+                updated_by      AS "updated_by: Uuid",
+                updated_at      AS "updated_at!: DateTime<Utc>"
+            FROM organization_shared_configs
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Replace the organization's baseline, bumping its version
+    pub async fn upsert(
+        pool: &PgPool,
+        organization_id: Uuid,
+        config: &SharedConfigBaseline,
+        updated_by: Uuid,
+    ) -> Result<OrganizationSharedConfig, sqlx::Error> {
+        let row = sqlx::query_as!(
+            SharedConfigRow,
+            r#"
+            INSERT INTO organization_shared_configs (organization_id, config, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (organization_id) DO UPDATE
+            SET config     = EXCLUDED.config,
+                version    = organization_shared_configs.version + 1,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING
+                organization_id AS "organization_id!: Uuid",
+                config          AS "config!: Json<SharedConfigBaseline>",
+                version         AS "version!",
+                updated_by      AS "updated_by: Uuid",
+                updated_at      AS "updated_at!: DateTime<Utc>"
+            "#,
+            organization_id,
+            Json(config) as _,
+            updated_by
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    pub async fn delete(pool: &PgPool, organization_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM organization_shared_configs WHERE organization_id = $1",
+            organization_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
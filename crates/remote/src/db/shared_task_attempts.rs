@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::types::PullRequestStatus;
+
+/// Longest attempt summary kept for a shared task attempt
+pub const MAX_ATTEMPT_SUMMARY_CHARS: usize = 1000;
+
+/// Summary and pull request state of an attempt on a shared task, pushed from
+/// the local instance of the member running it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct SharedTaskAttempt {
+    /// Id of the workspace on the member's local instance
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub branch: String,
+    pub summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i32>,
+    pub pr_status: Option<PullRequestStatus>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct UpsertSharedTaskAttemptData<'a> {
+    pub attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub branch: &'a str,
+    pub summary: Option<&'a str>,
+    pub pr_url: Option<&'a str>,
+    pub pr_number: Option<i32>,
+    pub pr_status: Option<PullRequestStatus>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+}
+
+pub struct SharedTaskAttemptRepository;
+
+impl SharedTaskAttemptRepository {
+    /// Record the latest state of an attempt. Returns `None` when the attempt
+    /// id is already taken by another member's attempt or another task.
+    pub async fn upsert(
+        pool: &PgPool,
+        data: UpsertSharedTaskAttemptData<'_>,
+    ) -> Result<Option<SharedTaskAttempt>, sqlx::Error> {
+        let summary = data.summary.map(|s| {
+            s.chars()
+                .take(MAX_ATTEMPT_SUMMARY_CHARS)
+                .collect::<String>()
+        });
+        sqlx::query_as!(
+            SharedTaskAttempt,
+            r#"
+            INSERT INTO shared_task_attempts
+                (id, task_id, project_id, user_id, branch, summary, pr_url, pr_number,
+                 pr_status, pr_merged_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO UPDATE
+            SET branch       = EXCLUDED.branch,
+                summary      = EXCLUDED.summary,
+                pr_url       = EXCLUDED.pr_url,
+                pr_number    = EXCLUDED.pr_number,
+                pr_status    = EXCLUDED.pr_status,
+                pr_merged_at = EXCLUDED.pr_merged_at,
+                updated_at   = NOW()
+            WHERE shared_task_attempts.user_id = EXCLUDED.user_id
+              AND shared_task_attempts.task_id = EXCLUDED.task_id
+            RETURNING
+                id           AS "id!: Uuid",
+                task_id      AS "task_id!: Uuid",
+                project_id   AS "project_id!: Uuid",
+                user_id      AS "user_id!: Uuid",
+                branch       AS "branch!: String",
+                summary      AS "summary: String",
+                pr_url       AS "pr_url: String",
+                pr_number    AS "pr_number: i32",
+                pr_status    AS "pr_status: PullRequestStatus",
+                pr_merged_at AS "pr_merged_at: DateTime<Utc>",
+                created_at   AS "created_at!: DateTime<Utc>",
+                updated_at   AS "updated_at!: DateTime<Utc>"
+            "#,
+            data.attempt_id,
+            data.task_id,
+            data.project_id,
+            data.user_id,
+            data.branch,
+            summary,
+            data.pr_url,
+            data.pr_number,
+            data.pr_status as Option<PullRequestStatus>,
+            data.pr_merged_at
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -7,7 +7,7 @@ use uuid::Uuid;
 use super::get_txid;
 use crate::mutation_types::{DeleteResponse, MutationResponse};
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, TS)]
 #[ts(export)]
 pub struct Tag {
     pub id: Uuid,
@@ -190,4 +190,52 @@ impl TagRepository {
 
         Ok(tags)
     }
+
+    /// Create the given tags in a project, skipping names it already has.
+    /// Returns the project's tags with those names, existing or new.
+    pub async fn create_many<'e, E>(
+        executor: E,
+        project_id: Uuid,
+        names: &[String],
+        colors: &[String],
+    ) -> Result<Vec<Tag>, TagError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let tags = sqlx::query_as!(
+            Tag,
+            r#"
+            WITH requested AS (
+                SELECT DISTINCT ON (name) name, color
+                FROM UNNEST($2::text[], $3::text[]) AS t(name, color)
+            ),
+            inserted AS (
+                INSERT INTO tags (id, project_id, name, color)
+                SELECT gen_random_uuid(), $1, name, color
+                FROM requested
+                ON CONFLICT (project_id, name) DO NOTHING
+                RETURNING id, project_id, name, color
+            )
+            SELECT
+                id          AS "id!: Uuid",
+                project_id  AS "project_id!: Uuid",
+                name        AS "name!",
+                color       AS "color!"
+            FROM inserted
+            UNION ALL
+            SELECT t.id, t.project_id, t.name, t.color
+            FROM tags t
+            JOIN requested r ON r.name = t.name
+            WHERE t.project_id = $1
+              AND NOT EXISTS (SELECT 1 FROM inserted i WHERE i.name = t.name)
+            "#,
+            project_id,
+            names,
+            colors
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(tags)
+    }
 }
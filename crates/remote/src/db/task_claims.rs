@@ -0,0 +1,284 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::users::UserData;
+
+/// How long a claim lasts unless the holder renews or releases it
+pub const DEFAULT_CLAIM_TTL_SECS: i64 = 2 * 60 * 60;
+pub const MAX_CLAIM_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedTaskClaim {
+    pub task_id: Uuid,
+    pub holder: UserData,
+    pub claimed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct ClaimRow {
+    task_id: Uuid,
+    user_id: Uuid,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    username: Option<String>,
+    claimed_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<ClaimRow> for SharedTaskClaim {
+    fn from(row: ClaimRow) -> Self {
+        Self {
+            task_id: row.task_id,
+            holder: UserData {
+                user_id: row.user_id,
+                first_name: row.first_name,
+                last_name: row.last_name,
+                username: row.username,
+            },
+            claimed_at: row.claimed_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClaimOutcome {
+    /// The caller now holds the claim (new or renewed)
+    Claimed(SharedTaskClaim),
+    /// Another user holds an unexpired claim
+    HeldByOther(SharedTaskClaim),
+}
+
+/// Stored claim on a task, possibly expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimHolder {
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// What a member claiming a task does to its current claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimAction {
+    /// Nobody holds an unexpired claim: start a new one
+    Take,
+    /// The member already holds it: push back its expiry
+    Renew,
+    /// Another member holds an unexpired claim
+    Refuse,
+}
+
+impl ClaimAction {
+    pub fn decide(current: Option<&ClaimHolder>, user_id: Uuid, now: DateTime<Utc>) -> Self {
+        match current {
+            Some(holder) if holder.expires_at > now => {
+                if holder.user_id == user_id {
+                    Self::Renew
+                } else {
+                    Self::Refuse
+                }
+            }
+            _ => Self::Take,
+        }
+    }
+}
+
+/// Only the member holding a claim can release it, expired or not
+pub fn can_release(current: Option<&ClaimHolder>, user_id: Uuid) -> bool {
+    current.is_some_and(|holder| holder.user_id == user_id)
+}
+
+pub struct TaskClaimRepository;
+
+impl TaskClaimRepository {
+    /// Unexpired claim on a task, if any
+    pub async fn find_active(
+        pool: &PgPool,
+        task_id: Uuid,
+    ) -> Result<Option<SharedTaskClaim>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ClaimRow,
+            r#"
+            SELECT
+                c.task_id       AS "task_id!: Uuid",
+                c.user_id       AS "user_id!: Uuid",
+                u.first_name    AS "first_name: String",
+                u.last_name     AS "last_name: String",
+                u.username      AS "username: String",
+                c.claimed_at    AS "claimed_at!: DateTime<Utc>",
+                c.expires_at    AS "expires_at!: DateTime<Utc>"
+            FROM shared_task_claims c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.task_id = $1 AND c.expires_at > NOW()
+            "#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Lock the task so concurrent claims and releases of it run one after
+    /// the other, then read its current claim
+    async fn lock_holder(
+        tx: &mut Transaction<'_, Postgres>,
+        task_id: Uuid,
+    ) -> Result<Option<ClaimHolder>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT id FROM shared_tasks WHERE id = $1 FOR UPDATE",
+            task_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+        sqlx::query_as!(
+            ClaimHolder,
+            r#"
+            SELECT
+                user_id     AS "user_id!: Uuid",
+                expires_at  AS "expires_at!: DateTime<Utc>"
+            FROM shared_task_claims
+            WHERE task_id = $1
+            "#,
+            task_id
+        )
+        .fetch_optional(&mut **tx)
+        .await
+    }
+
+    /// Claim a task for `user_id`, or renew their claim. Taking over only
+    /// succeeds once the previous holder's claim has expired.
+    pub async fn claim(
+        pool: &PgPool,
+        task_id: Uuid,
+        user_id: Uuid,
+        ttl_secs: i64,
+    ) -> Result<ClaimOutcome, sqlx::Error> {
+        let ttl_secs = ttl_secs.clamp(60, MAX_CLAIM_TTL_SECS);
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(ttl_secs);
+
+        let mut tx = pool.begin().await?;
+        let current = Self::lock_holder(&mut tx, task_id).await?;
+        let action = ClaimAction::decide(current.as_ref(), user_id, now);
+        match action {
+            ClaimAction::Take => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO shared_task_claims (task_id, user_id, claimed_at, expires_at)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (task_id) DO UPDATE
+                    SET user_id    = EXCLUDED.user_id,
+                        claimed_at = EXCLUDED.claimed_at,
+                        expires_at = EXCLUDED.expires_at
+                    "#,
+                    task_id,
+                    user_id,
+                    now,
+                    expires_at
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            ClaimAction::Renew => {
+                sqlx::query!(
+                    "UPDATE shared_task_claims SET expires_at = $2 WHERE task_id = $1",
+                    task_id,
+                    expires_at
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            ClaimAction::Refuse => {}
+        }
+        tx.commit().await?;
+
+        // The claim row exists either way; only its holder differs
+        let claim = Self::find_active(pool, task_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Ok(match action {
+            ClaimAction::Take | ClaimAction::Renew => ClaimOutcome::Claimed(claim),
+            ClaimAction::Refuse => ClaimOutcome::HeldByOther(claim),
+        })
+    }
+
+    /// Release `user_id`'s claim on a task; false when they did not hold one
+    pub async fn release(pool: &PgPool, task_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let current = Self::lock_holder(&mut tx, task_id).await?;
+        if !can_release(current.as_ref(), user_id) {
+            return Ok(false);
+        }
+        sqlx::query!("DELETE FROM shared_task_claims WHERE task_id = $1", task_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 7, 12, 0, 0).unwrap()
+    }
+
+    fn held_by(user_id: Uuid, expires_in_secs: i64) -> ClaimHolder {
+        ClaimHolder {
+            user_id,
+            expires_at: now() + Duration::seconds(expires_in_secs),
+        }
+    }
+
+    #[test]
+    fn claiming_a_task_held_by_someone_else_conflicts() {
+        let holder = held_by(Uuid::new_v4(), 600);
+        assert_eq!(
+            ClaimAction::decide(Some(&holder), Uuid::new_v4(), now()),
+            ClaimAction::Refuse
+        );
+    }
+
+    #[test]
+    fn holder_renews_their_own_claim() {
+        let user_id = Uuid::new_v4();
+        let holder = held_by(user_id, 600);
+        assert_eq!(
+            ClaimAction::decide(Some(&holder), user_id, now()),
+            ClaimAction::Renew
+        );
+    }
+
+    #[test]
+    fn expired_claim_can_be_taken_over() {
+        let holder = held_by(Uuid::new_v4(), -1);
+        assert_eq!(
+            ClaimAction::decide(Some(&holder), Uuid::new_v4(), now()),
+            ClaimAction::Take
+        );
+        // Expiring exactly now counts as expired
+        let holder = held_by(Uuid::new_v4(), 0);
+        assert_eq!(
+            ClaimAction::decide(Some(&holder), Uuid::new_v4(), now()),
+            ClaimAction::Take
+        );
+    }
+
+    #[test]
+    fn only_the_holder_can_release_a_claim() {
+        let user_id = Uuid::new_v4();
+        let holder = held_by(user_id, 600);
+        assert!(!can_release(Some(&holder), Uuid::new_v4()));
+        assert!(can_release(Some(&holder), user_id));
+        assert!(!can_release(None, user_id));
+    }
+}
@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::users::UserData;
+
+/// Longest detail (executor name, error summary, PR URL) stored with a state
+pub const MAX_EXECUTION_DETAIL_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SharedTaskExecutionState {
+    Started,
+    Executing,
+    Completed,
+    Failed,
+    PrOpened,
+}
+
+impl SharedTaskExecutionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Executing => "executing",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::PrOpened => "pr_opened",
+        }
+    }
+
+    /// Outcomes worth telling teammates about, as opposed to progress updates
+    pub fn is_outcome(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::PrOpened)
+    }
+}
+
+impl FromStr for SharedTaskExecutionState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "started" => Ok(Self::Started),
+            "executing" => Ok(Self::Executing),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            "pr_opened" => Ok(Self::PrOpened),
+            other => Err(format!("unknown execution state '{other}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedTaskExecutionStatus {
+    pub task_id: Uuid,
+    /// Member whose local instance reported the state
+    pub reporter: UserData,
+    pub state: SharedTaskExecutionState,
+    pub detail: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+struct StatusRow {
+    task_id: Uuid,
+    user_id: Uuid,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    username: Option<String>,
+    state: String,
+    detail: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl StatusRow {
+    fn into_status(self) -> Option<SharedTaskExecutionStatus> {
+        let state = match self.state.parse() {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::warn!(task_id = %self.task_id, "skipping execution status: {e}");
+                return None;
+            }
+        };
+        Some(SharedTaskExecutionStatus {
+            task_id: self.task_id,
+            reporter: UserData {
+                user_id: self.user_id,
+                first_name: self.first_name,
+                last_name: self.last_name,
+                username: self.username,
+            },
+            state,
+            detail: self.detail,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+fn truncate_detail(detail: &str) -> String {
+    detail.chars().take(MAX_EXECUTION_DETAIL_CHARS).collect()
+}
+
+pub struct TaskExecutionStatusRepository;
+
+impl TaskExecutionStatusRepository {
+    /// Record the latest state for a task, replacing the previous one
+    pub async fn upsert(
+        pool: &PgPool,
+        task_id: Uuid,
+        user_id: Uuid,
+        state: SharedTaskExecutionState,
+        detail: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let detail = detail.map(truncate_detail);
+        sqlx::query!(
+            r#"
+            INSERT INTO shared_task_execution_status (task_id, user_id, state, detail)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (task_id) DO UPDATE
+            SET user_id    = EXCLUDED.user_id,
+                state      = EXCLUDED.state,
+                detail     = EXCLUDED.detail,
+                updated_at = NOW()
+            "#,
+            task_id,
+            user_id,
+            state.as_str(),
+            detail
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Latest state of every live shared task in a project that has one
+    pub async fn list_by_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<SharedTaskExecutionStatus>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            StatusRow,
+            r#"
+            SELECT
+                s.task_id       AS "task_id!: Uuid",
+                s.user_id       AS "user_id!: Uuid",
+                u.first_name    AS "first_name: String",
+                u.last_name     AS "last_name: String",
+                u.username      AS "username: String",
+                s.state         AS "state!: String",
+                s.detail        AS "detail: String",
+                s.updated_at    AS "updated_at!: DateTime<Utc>"
+            FROM shared_task_execution_status s
+            JOIN shared_tasks t ON t.id = s.task_id
+            JOIN users u ON u.id = s.user_id
+            WHERE t.project_id = $1 AND t.deleted_at IS NULL
+            ORDER BY s.updated_at DESC
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(StatusRow::into_status)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATES: [SharedTaskExecutionState; 5] = [
+        SharedTaskExecutionState::Started,
+        SharedTaskExecutionState::Executing,
+        SharedTaskExecutionState::Completed,
+        SharedTaskExecutionState::Failed,
+        SharedTaskExecutionState::PrOpened,
+    ];
+
+    #[test]
+    fn stored_states_match_the_wire_format() {
+        for state in STATES {
+            assert_eq!(
+                state.as_str().parse::<SharedTaskExecutionState>(),
+                Ok(state)
+            );
+            assert_eq!(
+                serde_json::to_value(state).unwrap(),
+                serde_json::Value::from(state.as_str())
+            );
+        }
+        assert!("paused".parse::<SharedTaskExecutionState>().is_err());
+    }
+
+    #[test]
+    fn only_outcomes_notify_teammates() {
+        let outcomes: Vec<_> = STATES.into_iter().filter(|s| s.is_outcome()).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                SharedTaskExecutionState::Completed,
+                SharedTaskExecutionState::Failed,
+                SharedTaskExecutionState::PrOpened,
+            ]
+        );
+    }
+
+    #[test]
+    fn long_details_are_cut_on_character_boundaries() {
+        let detail = "é".repeat(MAX_EXECUTION_DETAIL_CHARS + 10);
+        let stored = truncate_detail(&detail);
+        assert_eq!(stored.chars().count(), MAX_EXECUTION_DETAIL_CHARS);
+        assert_eq!(truncate_detail("exit code 1"), "exit code 1");
+    }
+}
@@ -64,6 +64,14 @@ pub struct CreateSharedTaskData {
     pub assignee_user_id: Option<Uuid>,
 }
 
+/// A task created in bulk when a local project is published
+#[derive(Debug, Clone)]
+pub struct ImportSharedTaskData {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateSharedTaskData {
     pub title: Option<String>,
@@ -213,6 +221,69 @@ impl<'a> SharedTaskRepository<'a> {
         Ok(SharedTaskWithUser::new(task, user))
     }
 
+    /// Create `tasks` in a project in one go, created by and assigned to
+    /// `user_id`. Tasks are returned in the order given.
+    pub async fn create_many(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        tasks: Vec<ImportSharedTaskData>,
+    ) -> Result<Vec<SharedTask>, SharedTaskError> {
+        for task in &tasks {
+            ensure_text_size(&task.title, task.description.as_deref())?;
+        }
+
+        let mut tx = self.pool.begin().await.map_err(SharedTaskError::from)?;
+        let project = ProjectRepository::find_by_id(&mut *tx, project_id)
+            .await?
+            .ok_or(SharedTaskError::NotFound)?;
+
+        let mut created = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let row = sqlx::query_as!(
+                SharedTask,
+                r#"
+                INSERT INTO shared_tasks (
+                    organization_id,
+                    project_id,
+                    creator_user_id,
+                    assignee_user_id,
+                    title,
+                    description,
+                    status,
+                    shared_at
+                )
+                VALUES ($1, $2, $3, $3, $4, $5, $6, NOW())
+                RETURNING id                 AS "id!",
+                          organization_id    AS "organization_id!: Uuid",
+                          project_id         AS "project_id!",
+                          creator_user_id    AS "creator_user_id?: Uuid",
+                          assignee_user_id   AS "assignee_user_id?: Uuid",
+                          deleted_by_user_id AS "deleted_by_user_id?: Uuid",
+                          title              AS "title!",
+                          description        AS "description?",
+                          status             AS "status!: TaskStatus",
+                          deleted_at         AS "deleted_at?",
+                          shared_at          AS "shared_at?",
+                          created_at         AS "created_at!",
+                          updated_at         AS "updated_at!"
+                "#,
+                project.organization_id,
+                project_id,
+                user_id,
+                task.title,
+                task.description,
+                task.status as TaskStatus
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            created.push(row);
+        }
+
+        tx.commit().await.map_err(SharedTaskError::from)?;
+        Ok(created)
+    }
+
     pub async fn update(
         &self,
         task_id: Uuid,
@@ -22,6 +22,7 @@ use crate::{
         project_statuses::ProjectStatus,
         projects::Project,
         pull_requests::PullRequest,
+        shared_task_attempts::SharedTaskAttempt,
         tags::Tag,
         types::{IssuePriority, IssueRelationshipType},
         users::User,
@@ -187,6 +188,17 @@ crate::define_entity!(
     },
 );
 
+// SharedTaskAttempt: shape-only (no mutations), pushed from members' local instances
+crate::define_entity!(
+    SharedTaskAttempt,
+    table: "shared_task_attempts",
+    shape: {
+        where_clause: r#""project_id" = $1"#,
+        params: ["project_id"],
+        url: "/shape/project/{project_id}/shared_task_attempts",
+    },
+);
+
 // =============================================================================
 // Issue-scoped entities (both mutations and streaming at issue level)
 // =============================================================================
@@ -238,6 +250,7 @@ pub fn all_entities() -> Vec<&'static dyn EntityExport> {
         &PROJECT_STATUS_ENTITY,
         &ISSUE_ENTITY,
         &WORKSPACE_ENTITY,
+        &SHARED_TASK_ATTEMPT_ENTITY,
         // Issue-scoped (project streaming)
         &ISSUE_ASSIGNEE_ENTITY,
         &ISSUE_FOLLOWER_ENTITY,
@@ -262,6 +275,7 @@ pub fn all_shapes() -> Vec<&'static dyn crate::shapes::ShapeExport> {
         &PROJECT_STATUS_SHAPE,
         &ISSUE_SHAPE,
         &WORKSPACE_SHAPE,
+        &SHARED_TASK_ATTEMPT_SHAPE,
         &ISSUE_ASSIGNEE_SHAPE,
         &ISSUE_FOLLOWER_SHAPE,
         &ISSUE_TAG_SHAPE,
@@ -5,5 +5,8 @@ mod webhook;
 
 pub use jwt::GitHubAppJwt;
 pub use pr_review::{PrReviewError, PrReviewParams, PrReviewService};
-pub use service::{GitHubAppService, InstallationInfo, PrDetails, PrRef, Repository};
+pub use service::{
+    GitHubAppError, GitHubAppService, InstallationAccount, InstallationInfo, PrDetails, PrRef,
+    Repository,
+};
 pub use webhook::verify_webhook_signature;
@@ -7,6 +7,7 @@ pub mod entity;
 pub mod github_app;
 pub mod mail;
 pub mod mutation_types;
+pub mod notifier;
 pub mod r2;
 pub mod routes;
 pub mod shapes;
@@ -15,6 +16,7 @@ mod state;
 use std::env;
 
 pub use app::Server;
+pub use auth::{JwtError, JwtService};
 pub use state::AppState;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{
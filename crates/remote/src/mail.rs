@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde_json::json;
 
 use crate::db::organization_members::MemberRole;
@@ -18,6 +19,7 @@ pub trait Mailer: Send + Sync {
         accept_url: &str,
         role: MemberRole,
         invited_by: Option<&str>,
+        expires_at: DateTime<Utc>,
     );
 
     async fn send_review_ready(&self, email: &str, review_url: &str, pr_name: &str);
@@ -50,12 +52,14 @@ impl Mailer for LoopsMailer {
         accept_url: &str,
         role: MemberRole,
         invited_by: Option<&str>,
+        expires_at: DateTime<Utc>,
     ) {
         let role_str = match role {
             MemberRole::Admin => "admin",
             MemberRole::Member => "member",
         };
         let inviter = invited_by.unwrap_or("someone");
+        let expires_on = expires_at.format("%B %-d, %Y").to_string();
 
         if cfg!(debug_assertions) {
             tracing::info!(
@@ -63,6 +67,7 @@ impl Mailer for LoopsMailer {
                  Organization: {org_name}\n\
                  Role: {role_str}\n\
                  Invited by: {inviter}\n\
+                 Expires: {expires_on}\n\
                  Accept URL: {accept_url}"
             );
         }
@@ -74,6 +79,8 @@ impl Mailer for LoopsMailer {
                 "org_name": org_name,
                 "accept_url": accept_url,
                 "invited_by": inviter,
+                "role": role_str,
+                "expires_on": expires_on,
             }
         });
 
@@ -0,0 +1,388 @@
+//! Turns shared task events and @mentions into notifications for the
+//! teammates involved, honouring each member's notification preferences.
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use utils::api::organizations::{MemberNotificationPreferences, NotificationDigest};
+use uuid::Uuid;
+
+use crate::db::{
+    mentions::{MentionRepository, MentionSource},
+    notifications::{NotificationRepository, NotificationType},
+    organization_members,
+    task_execution_status::SharedTaskExecutionState,
+    tasks::{SharedTask, TaskStatus},
+};
+
+/// The preference a kind of event is governed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    Assignment,
+    Mention,
+    StatusChange,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SharedTaskEvent {
+    Assigned,
+    StatusChanged(TaskStatus),
+    ExecutionStateChanged(SharedTaskExecutionState),
+}
+
+impl SharedTaskEvent {
+    fn category(&self) -> NotificationCategory {
+        match self {
+            Self::Assigned => NotificationCategory::Assignment,
+            Self::StatusChanged(_) | Self::ExecutionStateChanged(_) => {
+                NotificationCategory::StatusChange
+            }
+        }
+    }
+
+    fn notification_type(&self) -> NotificationType {
+        match self {
+            Self::Assigned => NotificationType::TaskAssigned,
+            Self::StatusChanged(_) | Self::ExecutionStateChanged(_) => {
+                NotificationType::TaskStatusChanged
+            }
+        }
+    }
+
+    /// Members who hear about the event: the assignee for an assignment, the
+    /// creator and assignee otherwise. Never the member who caused it.
+    fn recipients(&self, task: &SharedTask, actor_user_id: Uuid) -> Vec<Uuid> {
+        let candidates = match self {
+            Self::Assigned => vec![task.assignee_user_id],
+            Self::StatusChanged(_) | Self::ExecutionStateChanged(_) => {
+                vec![task.creator_user_id, task.assignee_user_id]
+            }
+        };
+        let mut recipients = Vec::new();
+        for user_id in candidates.into_iter().flatten() {
+            if user_id != actor_user_id && !recipients.contains(&user_id) {
+                recipients.push(user_id);
+            }
+        }
+        recipients
+    }
+
+    fn payload(&self, task: &SharedTask, actor_user_id: Uuid) -> Value {
+        let mut payload = json!({
+            "task_id": task.id,
+            "project_id": task.project_id,
+            "title": task.title,
+            "actor_user_id": actor_user_id,
+        });
+        match self {
+            Self::Assigned => {}
+            Self::StatusChanged(status) => payload["status"] = json!(status),
+            Self::ExecutionStateChanged(state) => payload["execution_state"] = json!(state),
+        }
+        payload
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    Skip,
+    Immediate,
+    /// Added to the digest for the period starting at the given time
+    Digest(DateTime<Utc>),
+}
+
+/// Assignments and mentions are addressed to the member, so only status
+/// changes are rolled up into a digest
+fn delivery(
+    preferences: &MemberNotificationPreferences,
+    category: NotificationCategory,
+    now: DateTime<Utc>,
+) -> Delivery {
+    let enabled = match category {
+        NotificationCategory::Assignment => preferences.notify_on_assignment,
+        NotificationCategory::Mention => preferences.notify_on_mention,
+        NotificationCategory::StatusChange => preferences.notify_on_status_change,
+    };
+    if !enabled {
+        return Delivery::Skip;
+    }
+    match (category, digest_period_start(preferences.digest, now)) {
+        (NotificationCategory::StatusChange, Some(start)) => Delivery::Digest(start),
+        _ => Delivery::Immediate,
+    }
+}
+
+/// Start of the digest period containing `now`: midnight UTC, or the Monday
+/// of the week for weekly digests
+fn digest_period_start(digest: NotificationDigest, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let midnight = now.date_naive().and_hms_opt(0, 0, 0)?.and_utc();
+    match digest {
+        NotificationDigest::Off => None,
+        NotificationDigest::Daily => Some(midnight),
+        NotificationDigest::Weekly => {
+            Some(midnight - Duration::days(i64::from(now.weekday().num_days_from_monday())))
+        }
+    }
+}
+
+/// A notification before it is addressed to a member
+pub struct NotificationContent {
+    pub category: NotificationCategory,
+    pub notification_type: NotificationType,
+    pub payload: Value,
+    pub issue_id: Option<Uuid>,
+    pub comment_id: Option<Uuid>,
+}
+
+/// Deliver `content` to each recipient according to their preferences.
+/// Failures are logged rather than returned so the change that triggered
+/// them still succeeds.
+pub async fn notify_members(
+    pool: &PgPool,
+    organization_id: Uuid,
+    recipients: &[Uuid],
+    content: &NotificationContent,
+) {
+    let now = Utc::now();
+    for &user_id in recipients {
+        let preferences =
+            match organization_members::notification_preferences(pool, organization_id, user_id)
+                .await
+            {
+                Ok(preferences) => preferences,
+                Err(error) => {
+                    tracing::warn!(?error, %user_id, "failed to load notification preferences");
+                    continue;
+                }
+            };
+
+        let result = match delivery(&preferences, content.category, now) {
+            Delivery::Skip => continue,
+            Delivery::Immediate => NotificationRepository::create(
+                pool,
+                organization_id,
+                user_id,
+                content.notification_type,
+                content.payload.clone(),
+                content.issue_id,
+                content.comment_id,
+            )
+            .await
+            .map(|_| ()),
+            Delivery::Digest(period_start) => {
+                NotificationRepository::append_to_digest(
+                    pool,
+                    organization_id,
+                    user_id,
+                    period_start,
+                    content.payload.clone(),
+                )
+                .await
+            }
+        };
+        if let Err(error) = result {
+            tracing::warn!(?error, %user_id, "failed to send notification");
+        }
+    }
+}
+
+/// Notify the members involved in `task` about `event`
+pub async fn notify_task_event(
+    pool: &PgPool,
+    task: &SharedTask,
+    actor_user_id: Uuid,
+    event: SharedTaskEvent,
+) {
+    let content = NotificationContent {
+        category: event.category(),
+        notification_type: event.notification_type(),
+        payload: event.payload(task, actor_user_id),
+        issue_id: None,
+        comment_id: None,
+    };
+    notify_members(
+        pool,
+        task.organization_id,
+        &event.recipients(task, actor_user_id),
+        &content,
+    )
+    .await;
+}
+
+/// Lowercased usernames mentioned as `@username` in `text`. An `@` inside a
+/// word, as in an email address, is not a mention.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut usernames: Vec<String> = Vec::new();
+    let mut previous = None;
+    for (index, c) in text.char_indices() {
+        let starts_mention =
+            c == '@' && !previous.is_some_and(|p: char| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+        let username: String = text[index + 1..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            .collect();
+        let username = username.trim_end_matches(['.', '-']).to_lowercase();
+        if !username.is_empty() && !usernames.contains(&username) {
+            usernames.push(username);
+        }
+    }
+    usernames
+}
+
+/// Record the organization members mentioned in `text` and notify the ones
+/// mentioned there for the first time
+pub async fn notify_mentions(
+    pool: &PgPool,
+    organization_id: Uuid,
+    author_user_id: Uuid,
+    source: MentionSource,
+    text: &str,
+) {
+    let usernames = parse_mentions(text);
+    if usernames.is_empty() {
+        return;
+    }
+
+    let result: Result<Vec<Uuid>, sqlx::Error> = async {
+        let mut user_ids =
+            MentionRepository::resolve_usernames(pool, organization_id, &usernames).await?;
+        user_ids.retain(|user_id| *user_id != author_user_id);
+        MentionRepository::record(
+            pool,
+            organization_id,
+            author_user_id,
+            source,
+            &user_ids,
+            text,
+        )
+        .await
+    }
+    .await;
+    let recipients = match result {
+        Ok(recipients) => recipients,
+        Err(error) => {
+            tracing::warn!(?error, "failed to record mentions");
+            return;
+        }
+    };
+
+    let (payload, issue_id, comment_id) = match source {
+        MentionSource::SharedTask(task_id) => (
+            json!({ "task_id": task_id, "actor_user_id": author_user_id }),
+            None,
+            None,
+        ),
+        MentionSource::IssueComment {
+            issue_id,
+            comment_id,
+        } => (
+            json!({ "issue_id": issue_id, "comment_id": comment_id, "actor_user_id": author_user_id }),
+            Some(issue_id),
+            Some(comment_id),
+        ),
+    };
+    let content = NotificationContent {
+        category: NotificationCategory::Mention,
+        notification_type: NotificationType::Mentioned,
+        payload,
+        issue_id,
+        comment_id,
+    };
+    notify_members(pool, organization_id, &recipients, &content).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn task(creator: Option<Uuid>, assignee: Option<Uuid>) -> SharedTask {
+        let now = Utc::now();
+        SharedTask {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            creator_user_id: creator,
+            assignee_user_id: assignee,
+            deleted_by_user_id: None,
+            title: "Fix login".to_string(),
+            description: None,
+            status: TaskStatus::Todo,
+            deleted_at: None,
+            shared_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn recipients_skip_the_actor_and_duplicates() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let event = SharedTaskEvent::StatusChanged(TaskStatus::Done);
+
+        assert_eq!(
+            event.recipients(&task(Some(alice), Some(bob)), bob),
+            vec![alice]
+        );
+        assert_eq!(
+            event.recipients(&task(Some(alice), Some(alice)), bob),
+            vec![alice]
+        );
+        assert!(
+            SharedTaskEvent::Assigned
+                .recipients(&task(Some(alice), Some(bob)), bob)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn delivery_follows_preferences() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 12, 15, 30, 0).unwrap();
+        let mut preferences = MemberNotificationPreferences::default();
+        assert_eq!(
+            delivery(&preferences, NotificationCategory::StatusChange, now),
+            Delivery::Immediate
+        );
+
+        preferences.digest = NotificationDigest::Weekly;
+        let monday = Utc.with_ymd_and_hms(2026, 2, 9, 0, 0, 0).unwrap();
+        assert_eq!(
+            delivery(&preferences, NotificationCategory::StatusChange, now),
+            Delivery::Digest(monday)
+        );
+        assert_eq!(
+            delivery(&preferences, NotificationCategory::Assignment, now),
+            Delivery::Immediate
+        );
+
+        preferences.notify_on_status_change = false;
+        assert_eq!(
+            delivery(&preferences, NotificationCategory::StatusChange, now),
+            Delivery::Skip
+        );
+    }
+
+    #[test]
+    fn mentions_are_parsed_outside_words() {
+        assert_eq!(
+            parse_mentions("cc @Alice, @bob.smith. and @alice again"),
+            vec!["alice", "bob.smith"]
+        );
+        assert!(parse_mentions("mail me at dev@example.com or @ nobody").is_empty());
+    }
+
+    #[test]
+    fn daily_digest_starts_at_midnight() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 12, 15, 30, 0).unwrap();
+        assert_eq!(
+            digest_period_start(NotificationDigest::Daily, now),
+            Some(Utc.with_ymd_and_hms(2026, 2, 12, 0, 0, 0).unwrap())
+        );
+        assert_eq!(digest_period_start(NotificationDigest::Off, now), None);
+    }
+}
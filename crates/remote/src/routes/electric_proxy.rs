@@ -55,6 +55,10 @@ pub fn router() -> Router<AppState> {
             get(proxy_issue_relationships),
         )
         .route(shapes::PULL_REQUESTS.url, get(proxy_pull_requests))
+        .route(
+            shapes::SHARED_TASK_ATTEMPTS.url,
+            get(proxy_shared_task_attempts),
+        )
         // Issue-scoped
         .route(shapes::ISSUE_COMMENTS.url, get(proxy_issue_comments))
         .route(
@@ -325,6 +329,25 @@ async fn proxy_pull_requests(
     .await
 }
 
+async fn proxy_shared_task_attempts(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<ShapeQuery>,
+) -> Result<Response, ProxyError> {
+    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
+        .await
+        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+
+    proxy_table(
+        &state,
+        &shapes::SHARED_TASK_ATTEMPTS,
+        &query.params,
+        &[project_id.to_string()],
+    )
+    .await
+}
+
 async fn proxy_issue_comment_reactions(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
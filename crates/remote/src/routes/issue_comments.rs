@@ -10,13 +10,17 @@ use super::{error::ErrorResponse, organization_members::ensure_issue_access};
 use crate::{
     AppState,
     auth::RequestContext,
-    db::issue_comments::{IssueComment, IssueCommentRepository},
+    db::{
+        issue_comments::{IssueComment, IssueCommentRepository},
+        mentions::MentionSource,
+    },
     define_mutation_router,
     entities::{
         CreateIssueCommentRequest, ListIssueCommentsQuery, ListIssueCommentsResponse,
         UpdateIssueCommentRequest,
     },
     mutation_types::{DeleteResponse, MutationResponse},
+    notifier,
 };
 
 // Generate router that references handlers below
@@ -83,7 +87,7 @@ async fn create_issue_comment(
     Extension(ctx): Extension<RequestContext>,
     Json(payload): Json<CreateIssueCommentRequest>,
 ) -> Result<Json<MutationResponse<IssueComment>>, ErrorResponse> {
-    ensure_issue_access(state.pool(), ctx.user.id, payload.issue_id).await?;
+    let organization_id = ensure_issue_access(state.pool(), ctx.user.id, payload.issue_id).await?;
 
     let response = IssueCommentRepository::create(
         state.pool(),
@@ -98,6 +102,8 @@ async fn create_issue_comment(
         ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
     })?;
 
+    notify_comment_mentions(&state, organization_id, ctx.user.id, &response.data).await;
+
     Ok(Json(response))
 }
 
@@ -130,7 +136,7 @@ async fn update_issue_comment(
         ));
     }
 
-    ensure_issue_access(state.pool(), ctx.user.id, comment.issue_id).await?;
+    let organization_id = ensure_issue_access(state.pool(), ctx.user.id, comment.issue_id).await?;
 
     let response = IssueCommentRepository::update(state.pool(), issue_comment_id, payload.message)
         .await
@@ -139,6 +145,8 @@ async fn update_issue_comment(
             ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
         })?;
 
+    notify_comment_mentions(&state, organization_id, ctx.user.id, &response.data).await;
+
     Ok(Json(response))
 }
 
@@ -181,3 +189,23 @@ async fn delete_issue_comment(
 
     Ok(Json(response))
 }
+
+/// Notify members newly @mentioned in a comment
+async fn notify_comment_mentions(
+    state: &AppState,
+    organization_id: Uuid,
+    author_id: Uuid,
+    comment: &IssueComment,
+) {
+    notifier::notify_mentions(
+        state.pool(),
+        organization_id,
+        author_id,
+        MentionSource::IssueComment {
+            issue_id: comment.issue_id,
+            comment_id: comment.id,
+        },
+        &comment.message,
+    )
+    .await;
+}
@@ -0,0 +1,79 @@
+use axum::{
+    Json, Router,
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::error::ErrorResponse;
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::mentions::{Mention, MentionRepository},
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarkMentionsReadRequest {
+    /// Mentions to mark as read; all unread mentions when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub mention_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarkMentionsReadResponse {
+    #[ts(type = "number")]
+    pub updated: u64,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/mentions/unread", get(list_unread_mentions))
+        .route("/mentions/mark-read", post(mark_mentions_read))
+}
+
+#[instrument(
+    name = "mentions.list_unread",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn list_unread_mentions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<Vec<Mention>>, ErrorResponse> {
+    let mentions = MentionRepository::list_unread(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to list unread mentions");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(mentions))
+}
+
+#[instrument(
+    name = "mentions.mark_read",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id)
+)]
+async fn mark_mentions_read(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<MarkMentionsReadRequest>,
+) -> Result<Json<MarkMentionsReadResponse>, ErrorResponse> {
+    let updated =
+        MentionRepository::mark_read(state.pool(), ctx.user.id, payload.mention_ids.as_deref())
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, "failed to mark mentions as read");
+                ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+            })?;
+
+    Ok(Json(MarkMentionsReadResponse { updated }))
+}
@@ -25,6 +25,7 @@ mod issue_followers;
 mod issue_relationships;
 mod issue_tags;
 mod issues;
+pub mod mentions;
 mod notifications;
 mod oauth;
 pub(crate) mod organization_members;
@@ -85,6 +86,7 @@ pub fn router(state: AppState) -> Router {
         .merge(issue_relationships::router())
         .merge(pull_requests::router())
         .merge(notifications::router())
+        .merge(mentions::router())
         .merge(tasks::router())
         .layer(middleware::from_fn_with_state(
             state.clone(),
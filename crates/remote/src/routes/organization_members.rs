@@ -5,13 +5,14 @@ use axum::{
     response::IntoResponse,
     routing::{delete, get, patch, post},
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::warn;
 use utils::api::organizations::{
-    ListMembersResponse, OrganizationMemberWithProfile, RevokeInvitationRequest,
-    UpdateMemberRoleRequest, UpdateMemberRoleResponse,
+    ListMembersResponse, OrganizationMemberWithProfile, ResendInvitationRequest,
+    RevokeInvitationRequest, UpdateMemberNotificationPreferencesRequest, UpdateMemberRoleRequest,
+    UpdateMemberRoleResponse,
 };
 use uuid::Uuid;
 
@@ -30,6 +31,9 @@ use crate::{
     },
 };
 
+const DEFAULT_INVITATION_TTL_DAYS: u32 = 7;
+const MAX_INVITATION_TTL_DAYS: u32 = 30;
+
 pub fn public_router() -> Router<AppState> {
     Router::new().route("/invitations/{token}", get(get_invitation))
 }
@@ -45,6 +49,10 @@ pub fn protected_router() -> Router<AppState> {
             "/organizations/{org_id}/invitations/revoke",
             post(revoke_invitation),
         )
+        .route(
+            "/organizations/{org_id}/invitations/resend",
+            post(resend_invitation),
+        )
         .route("/invitations/{token}/accept", post(accept_invitation))
         .route("/organizations/{org_id}/members", get(list_members))
         .route(
@@ -55,12 +63,18 @@ pub fn protected_router() -> Router<AppState> {
             "/organizations/{org_id}/members/{user_id}/role",
             patch(update_member_role),
         )
+        .route(
+            "/organizations/{org_id}/members/me/notification-preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateInvitationRequest {
     pub email: String,
     pub role: MemberRole,
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -102,7 +116,7 @@ pub async fn create_invitation(
     ensure_admin_access(&state.pool, org_id, user.id).await?;
 
     let token = Uuid::new_v4().to_string();
-    let expires_at = Utc::now() + Duration::days(7);
+    let expires_at = invitation_expiry(Utc::now(), payload.expires_in_days);
 
     let invitation = invitation_repo
         .create_invitation(
@@ -129,25 +143,96 @@ pub async fn create_invitation(
         )
     })?;
 
+    send_invitation_email(
+        &state,
+        &organization.name,
+        &invitation,
+        user.username.as_deref(),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateInvitationResponse { invitation }),
+    ))
+}
+
+/// Issue a fresh link for a pending or expired invitation and email it again
+pub async fn resend_invitation(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<ResendInvitationRequest>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let user = ctx.user;
+    let org_repo = OrganizationRepository::new(&state.pool);
+    let invitation_repo = InvitationRepository::new(&state.pool);
+
+    ensure_admin_access(&state.pool, org_id, user.id).await?;
+
+    let token = Uuid::new_v4().to_string();
+    let expires_at = invitation_expiry(Utc::now(), payload.expires_in_days);
+
+    let invitation = invitation_repo
+        .resend_invitation(org_id, payload.invitation_id, user.id, expires_at, &token)
+        .await
+        .map_err(|e| match e {
+            IdentityError::PermissionDenied => {
+                ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required")
+            }
+            IdentityError::NotFound => {
+                ErrorResponse::new(StatusCode::NOT_FOUND, "Invitation not found")
+            }
+            IdentityError::InvitationError(msg) => ErrorResponse::new(StatusCode::BAD_REQUEST, msg),
+            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        })?;
+
+    let organization = org_repo.fetch_organization(org_id).await.map_err(|_| {
+        ErrorResponse::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch organization",
+        )
+    })?;
+
+    send_invitation_email(
+        &state,
+        &organization.name,
+        &invitation,
+        user.username.as_deref(),
+    )
+    .await;
+
+    Ok(Json(CreateInvitationResponse { invitation }))
+}
+
+fn invitation_expiry(now: DateTime<Utc>, expires_in_days: Option<u32>) -> DateTime<Utc> {
+    let days = expires_in_days
+        .unwrap_or(DEFAULT_INVITATION_TTL_DAYS)
+        .clamp(1, MAX_INVITATION_TTL_DAYS);
+    now + Duration::days(days.into())
+}
+
+async fn send_invitation_email(
+    state: &AppState,
+    org_name: &str,
+    invitation: &Invitation,
+    invited_by: Option<&str>,
+) {
     let accept_url = format!(
         "{}/invitations/{}/accept",
-        state.server_public_base_url, token
+        state.server_public_base_url, invitation.token
     );
     state
         .mailer
         .send_org_invitation(
-            &organization.name,
-            &payload.email,
+            org_name,
+            &invitation.email,
             &accept_url,
-            payload.role,
-            user.username.as_deref(),
+            invitation.role,
+            invited_by,
+            invitation.expires_at,
         )
         .await;
-
-    Ok((
-        StatusCode::CREATED,
-        Json(CreateInvitationResponse { invitation }),
-    ))
 }
 
 pub async fn list_invitations(
@@ -160,6 +245,10 @@ pub async fn list_invitations(
 
     ensure_admin_access(&state.pool, org_id, user.id).await?;
 
+    if let Err(error) = invitation_repo.expire_stale(org_id).await {
+        warn!(?error, %org_id, "failed to expire stale invitations");
+    }
+
     let invitations = invitation_repo
         .list_invitations(org_id, user.id)
         .await
@@ -490,6 +579,47 @@ pub async fn update_member_role(
     }))
 }
 
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_member_access(&state.pool, org_id, ctx.user.id).await?;
+
+    let preferences =
+        organization_members::notification_preferences(&state.pool, org_id, ctx.user.id)
+            .await
+            .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(preferences))
+}
+
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<UpdateMemberNotificationPreferencesRequest>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_member_access(&state.pool, org_id, ctx.user.id).await?;
+
+    let mut preferences =
+        organization_members::notification_preferences(&state.pool, org_id, ctx.user.id)
+            .await
+            .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    preferences.apply(&payload);
+
+    let preferences = organization_members::set_notification_preferences(
+        &state.pool,
+        org_id,
+        ctx.user.id,
+        &preferences,
+    )
+    .await
+    .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(preferences))
+}
+
 pub(crate) async fn ensure_member_access(
     pool: &PgPool,
     organization_id: Uuid,
@@ -645,3 +775,18 @@ pub(crate) async fn ensure_task_access(
 
     Ok(organization_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invitation_lifetime_defaults_to_a_week_and_is_bounded() {
+        let now = Utc::now();
+        let days = |requested| (invitation_expiry(now, requested) - now).num_days();
+        assert_eq!(days(None), 7);
+        assert_eq!(days(Some(14)), 14);
+        assert_eq!(days(Some(0)), 1);
+        assert_eq!(days(Some(365)), 30);
+    }
+}
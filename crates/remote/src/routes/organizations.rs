@@ -7,19 +7,27 @@ use axum::{
 };
 use utils::api::organizations::{
     CreateOrganizationRequest, CreateOrganizationResponse, GetOrganizationResponse,
-    ListOrganizationsResponse, MemberRole, UpdateOrganizationRequest,
+    ListOrganizationsResponse, MemberRole, UpdateOrganizationRequest, UpdateSharedConfigRequest,
 };
 use uuid::Uuid;
 
-use super::error::ErrorResponse;
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access},
+};
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        identity_errors::IdentityError, organization_members, organizations::OrganizationRepository,
+        identity_errors::IdentityError, organization_members,
+        organization_shared_configs::OrganizationSharedConfigRepository,
+        organizations::OrganizationRepository,
     },
 };
 
+/// Upper bound on the serialized shared config
+const MAX_SHARED_CONFIG_BYTES: usize = 256 * 1024;
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/organizations", post(create_organization))
@@ -27,6 +35,12 @@ pub fn router() -> Router<AppState> {
         .route("/organizations/{org_id}", get(get_organization))
         .route("/organizations/{org_id}", patch(update_organization))
         .route("/organizations/{org_id}", delete(delete_organization))
+        .route(
+            "/organizations/{org_id}/shared-config",
+            get(get_shared_config)
+                .put(update_shared_config)
+                .delete(delete_shared_config),
+        )
 }
 
 pub async fn create_organization(
@@ -192,3 +206,83 @@ pub async fn delete_organization(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+pub async fn get_shared_config(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_member_access(&state.pool, org_id, ctx.user.id).await?;
+
+    let config = OrganizationSharedConfigRepository::find(&state.pool, org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(config))
+}
+
+pub async fn update_shared_config(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<UpdateSharedConfigRequest>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_admin_access(&state.pool, org_id, ctx.user.id).await?;
+
+    let size = serde_json::to_vec(&payload.config)
+        .map(|bytes| bytes.len())
+        .unwrap_or(usize::MAX);
+    if size > MAX_SHARED_CONFIG_BYTES {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "Shared config cannot exceed 256 KiB",
+        ));
+    }
+    if payload
+        .config
+        .executor_profiles
+        .as_ref()
+        .is_some_and(|profiles| !profiles.is_object())
+    {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "Executor profiles must be a JSON object",
+        ));
+    }
+    if payload
+        .config
+        .prompts
+        .keys()
+        .any(|name| name.trim().is_empty())
+    {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "Prompt names cannot be empty",
+        ));
+    }
+
+    let config = OrganizationSharedConfigRepository::upsert(
+        &state.pool,
+        org_id,
+        &payload.config,
+        ctx.user.id,
+    )
+    .await
+    .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(config))
+}
+
+pub async fn delete_shared_config(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_admin_access(&state.pool, org_id, ctx.user.id).await?;
+
+    OrganizationSharedConfigRepository::delete(&state.pool, org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
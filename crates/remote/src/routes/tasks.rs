@@ -3,8 +3,9 @@ use axum::{
     extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{Span, instrument};
@@ -12,31 +13,58 @@ use ts_rs::TS;
 use uuid::Uuid;
 
 use super::{
-    error::{identity_error_response, task_error_response},
+    error::{ErrorResponse, identity_error_response, task_error_response},
     organization_members::{ensure_project_access, ensure_task_access},
 };
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
+        mentions::MentionSource,
         organization_members,
+        shared_task_attempts::{SharedTaskAttemptRepository, UpsertSharedTaskAttemptData},
+        tags::{Tag, TagRepository},
+        task_claims::{ClaimOutcome, DEFAULT_CLAIM_TTL_SECS, SharedTaskClaim, TaskClaimRepository},
+        task_execution_status::{SharedTaskExecutionState, TaskExecutionStatusRepository},
         tasks::{
-            AssignTaskData, CreateSharedTaskData, DeleteTaskData, SharedTask, SharedTaskError,
-            SharedTaskRepository, SharedTaskWithUser, TaskStatus, UpdateSharedTaskData,
-            ensure_text_size,
+            AssignTaskData, CreateSharedTaskData, DeleteTaskData, ImportSharedTaskData, SharedTask,
+            SharedTaskError, SharedTaskRepository, SharedTaskWithUser, TaskStatus,
+            UpdateSharedTaskData, ensure_text_size,
         },
+        types::{PullRequestStatus, is_valid_hsl_color},
         users::{UserData, UserRepository},
     },
+    notifier::{self, SharedTaskEvent},
 };
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/tasks", post(create_shared_task))
         .route("/tasks/check", post(check_tasks_existence))
+        .route("/tasks/import", post(import_shared_tasks))
+        .route("/tasks/{task_id}", get(get_shared_task))
         .route("/tasks/{task_id}", patch(update_shared_task))
         .route("/tasks/{task_id}", delete(delete_shared_task))
         .route("/tasks/{task_id}/assign", post(assign_task))
+        .route(
+            "/tasks/{task_id}/claim",
+            get(get_task_claim)
+                .post(claim_task)
+                .delete(release_task_claim),
+        )
+        .route(
+            "/tasks/{task_id}/execution-status",
+            post(report_execution_status),
+        )
+        .route(
+            "/tasks/{task_id}/attempts/{attempt_id}",
+            put(report_shared_task_attempt),
+        )
         .route("/tasks/assignees", get(get_task_assignees_by_project))
+        .route(
+            "/tasks/execution-status",
+            get(get_execution_statuses_by_project),
+        )
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -133,11 +161,130 @@ pub async fn create_shared_task(
     };
 
     match repo.create(data).await {
-        Ok(task) => (StatusCode::CREATED, Json(SharedTaskResponse::from(task))).into_response(),
+        Ok(task) => {
+            notifier::notify_task_event(pool, &task.task, ctx.user.id, SharedTaskEvent::Assigned)
+                .await;
+            if let Some(description) = task.task.description.as_deref() {
+                notifier::notify_mentions(
+                    pool,
+                    task.task.organization_id,
+                    ctx.user.id,
+                    MentionSource::SharedTask(task.task.id),
+                    description,
+                )
+                .await;
+            }
+            (StatusCode::CREATED, Json(SharedTaskResponse::from(task))).into_response()
+        }
         Err(error) => task_error_response(error, "failed to create shared task"),
     }
 }
 
+/// Longest tag name the tags table accepts
+const MAX_TAG_NAME_CHARS: usize = 50;
+
+#[instrument(
+    name = "tasks.import_shared_tasks",
+    skip(state, ctx, payload),
+    fields(
+        user_id = %ctx.user.id,
+        project_id = %payload.project_id,
+        tags = payload.tags.len(),
+        tasks = payload.tasks.len(),
+        org_id = tracing::field::Empty
+    )
+)]
+pub async fn import_shared_tasks(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<ImportSharedTasksRequest>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_project_access(pool, ctx.user.id, payload.project_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    for tag in &payload.tags {
+        if tag.name.trim().is_empty() || tag.name.chars().count() > MAX_TAG_NAME_CHARS {
+            return ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "tag names must be between 1 and 50 characters",
+            )
+            .into_response();
+        }
+        if !is_valid_hsl_color(&tag.color) {
+            return ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "Invalid color format. Expected HSL format: 'H S% L%'",
+            )
+            .into_response();
+        }
+    }
+
+    let (names, colors): (Vec<String>, Vec<String>) = payload
+        .tags
+        .into_iter()
+        .map(|tag| (tag.name, tag.color))
+        .unzip();
+    let tags = match TagRepository::create_many(pool, payload.project_id, &names, &colors).await {
+        Ok(tags) => tags,
+        Err(error) => {
+            tracing::error!(?error, "failed to import tags");
+            return ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to import tags")
+                .into_response();
+        }
+    };
+
+    let tasks = payload
+        .tasks
+        .into_iter()
+        .map(|task| ImportSharedTaskData {
+            title: task.title,
+            description: task.description,
+            status: task.status,
+        })
+        .collect();
+    match SharedTaskRepository::new(pool)
+        .create_many(payload.project_id, ctx.user.id, tasks)
+        .await
+    {
+        Ok(tasks) => (
+            StatusCode::CREATED,
+            Json(ImportSharedTasksResponse { tags, tasks }),
+        )
+            .into_response(),
+        Err(error) => task_error_response(error, "failed to import shared tasks"),
+    }
+}
+
+#[instrument(
+    name = "tasks.get_shared_task",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn get_shared_task(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    match SharedTaskRepository::new(pool).find_by_id(task_id).await {
+        Ok(Some(task)) => (StatusCode::OK, Json(task)).into_response(),
+        Ok(None) => task_error_response(SharedTaskError::NotFound, "shared task not found"),
+        Err(error) => task_error_response(error, "failed to load shared task"),
+    }
+}
+
 #[instrument(
     name = "tasks.update_shared_task",
     skip(state, ctx, payload),
@@ -197,7 +344,30 @@ pub async fn update_shared_task(
     };
 
     match repo.update(task_id, data).await {
-        Ok(task) => (StatusCode::OK, Json(SharedTaskResponse::from(task))).into_response(),
+        Ok(task) => {
+            if task.task.status != existing.status {
+                notifier::notify_task_event(
+                    pool,
+                    &task.task,
+                    ctx.user.id,
+                    SharedTaskEvent::StatusChanged(task.task.status),
+                )
+                .await;
+            }
+            if let Some(description) = task.task.description.as_deref()
+                && task.task.description != existing.description
+            {
+                notifier::notify_mentions(
+                    pool,
+                    task.task.organization_id,
+                    ctx.user.id,
+                    MentionSource::SharedTask(task.task.id),
+                    description,
+                )
+                .await;
+            }
+            (StatusCode::OK, Json(SharedTaskResponse::from(task))).into_response()
+        }
         Err(error) => task_error_response(error, "failed to update shared task"),
     }
 }
@@ -259,7 +429,11 @@ pub async fn assign_task(
     };
 
     match repo.assign_task(task_id, data).await {
-        Ok(task) => (StatusCode::OK, Json(SharedTaskResponse::from(task))).into_response(),
+        Ok(task) => {
+            notifier::notify_task_event(pool, &task.task, ctx.user.id, SharedTaskEvent::Assigned)
+                .await;
+            (StatusCode::OK, Json(SharedTaskResponse::from(task))).into_response()
+        }
         Err(error) => task_error_response(error, "failed to transfer task assignment"),
     }
 }
@@ -331,6 +505,209 @@ pub async fn check_tasks_existence(
     }
 }
 
+#[instrument(
+    name = "tasks.get_task_claim",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn get_task_claim(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    match TaskClaimRepository::find_active(pool, task_id).await {
+        Ok(claim) => (StatusCode::OK, Json(claim)).into_response(),
+        Err(error) => task_error_response(error.into(), "failed to load task claim"),
+    }
+}
+
+#[instrument(
+    name = "tasks.claim_task",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn claim_task(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<ClaimSharedTaskRequest>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    let ttl_secs = payload.ttl_secs.unwrap_or(DEFAULT_CLAIM_TTL_SECS);
+    match TaskClaimRepository::claim(pool, task_id, ctx.user.id, ttl_secs).await {
+        Ok(ClaimOutcome::Claimed(claim)) => (StatusCode::OK, Json(claim)).into_response(),
+        Ok(ClaimOutcome::HeldByOther(claim)) => (
+            StatusCode::CONFLICT,
+            Json(ClaimConflictResponse {
+                error: "task is claimed by another user".to_string(),
+                claim,
+            }),
+        )
+            .into_response(),
+        Err(error) => task_error_response(error.into(), "failed to claim task"),
+    }
+}
+
+#[instrument(
+    name = "tasks.release_task_claim",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn release_task_claim(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    match TaskClaimRepository::release(pool, task_id, ctx.user.id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(error) => task_error_response(error.into(), "failed to release task claim"),
+    }
+}
+
+#[instrument(
+    name = "tasks.report_execution_status",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn report_execution_status(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<ReportExecutionStatusRequest>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    if let Err(error) = TaskExecutionStatusRepository::upsert(
+        pool,
+        task_id,
+        ctx.user.id,
+        payload.state,
+        payload.detail.as_deref(),
+    )
+    .await
+    {
+        return task_error_response(error.into(), "failed to record execution status");
+    }
+
+    if payload.state.is_outcome() {
+        match SharedTaskRepository::new(pool).find_by_id(task_id).await {
+            Ok(Some(task)) => {
+                notifier::notify_task_event(
+                    pool,
+                    &task,
+                    ctx.user.id,
+                    SharedTaskEvent::ExecutionStateChanged(payload.state),
+                )
+                .await;
+            }
+            Ok(None) => {}
+            Err(error) => tracing::warn!(?error, %task_id, "failed to load shared task"),
+        }
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[instrument(
+    name = "tasks.report_shared_task_attempt",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, attempt_id = %attempt_id, org_id = tracing::field::Empty)
+)]
+pub async fn report_shared_task_attempt(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((task_id, attempt_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ReportSharedTaskAttemptRequest>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    let task = match SharedTaskRepository::new(pool).find_by_id(task_id).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return task_error_response(SharedTaskError::NotFound, "task not found"),
+        Err(error) => return task_error_response(error, "failed to load shared task"),
+    };
+
+    let data = UpsertSharedTaskAttemptData {
+        attempt_id,
+        task_id,
+        project_id: task.project_id,
+        user_id: ctx.user.id,
+        branch: &payload.branch,
+        summary: payload.summary.as_deref(),
+        pr_url: payload.pr_url.as_deref(),
+        pr_number: payload.pr_number,
+        pr_status: payload.pr_status,
+        pr_merged_at: payload.pr_merged_at,
+    };
+    match SharedTaskAttemptRepository::upsert(pool, data).await {
+        Ok(Some(attempt)) => (StatusCode::OK, Json(attempt)).into_response(),
+        Ok(None) => task_error_response(
+            SharedTaskError::Conflict("attempt belongs to another member or task".to_string()),
+            "attempt id conflict",
+        ),
+        Err(error) => task_error_response(error.into(), "failed to record shared task attempt"),
+    }
+}
+
+#[instrument(
+    name = "tasks.get_execution_statuses_by_project",
+    skip(state, ctx, query),
+    fields(user_id = %ctx.user.id, project_id = %query.project_id, org_id = tracing::field::Empty)
+)]
+pub async fn get_execution_statuses_by_project(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<AssigneesQuery>,
+) -> Response {
+    let pool = state.pool();
+    match ensure_project_access(pool, ctx.user.id, query.project_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    match TaskExecutionStatusRepository::list_by_project(pool, query.project_id).await {
+        Ok(statuses) => (StatusCode::OK, Json(statuses)).into_response(),
+        Err(error) => task_error_response(error.into(), "failed to load execution statuses"),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckTasksRequest {
     pub task_ids: Vec<Uuid>,
@@ -344,6 +721,37 @@ pub struct CreateSharedTaskRequest {
     pub assignee_user_id: Option<Uuid>,
 }
 
+/// A tag to create in a project being published from a local instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportTagRequest {
+    pub name: String,
+    /// HSL color: "H S% L%"
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSharedTaskRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+}
+
+/// Tags and tasks of a local project, created in bulk when it is published.
+/// Tasks are created by and assigned to the acting user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSharedTasksRequest {
+    pub project_id: Uuid,
+    pub tags: Vec<ImportTagRequest>,
+    pub tasks: Vec<ImportSharedTaskRequest>,
+}
+
+/// Tags matching the requested names and the created tasks, in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSharedTasksResponse {
+    pub tags: Vec<Tag>,
+    pub tasks: Vec<SharedTask>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateSharedTaskRequest {
     pub title: Option<String>,
@@ -356,6 +764,36 @@ pub struct AssignSharedTaskRequest {
     pub new_assignee_user_id: Option<Uuid>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportExecutionStatusRequest {
+    pub state: SharedTaskExecutionState,
+    pub detail: Option<String>,
+}
+
+/// Latest state of an attempt on a shared task, keyed by the local workspace id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSharedTaskAttemptRequest {
+    pub branch: String,
+    pub summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i32>,
+    pub pr_status: Option<PullRequestStatus>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClaimSharedTaskRequest {
+    /// Claim duration in seconds; defaults to two hours
+    pub ttl_secs: Option<i64>,
+}
+
+/// Body of the 409 returned when another user holds the claim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimConflictResponse {
+    pub error: String,
+    pub claim: SharedTaskClaim,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct SharedTaskResponse {
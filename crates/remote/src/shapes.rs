@@ -108,5 +108,6 @@ pub use crate::entities::{
     ISSUE_SHAPE as ISSUES, ISSUE_TAG_SHAPE as ISSUE_TAGS, NOTIFICATION_SHAPE as NOTIFICATIONS,
     ORGANIZATION_MEMBER_SHAPE as ORGANIZATION_MEMBERS, PROJECT_SHAPE as PROJECTS,
     PROJECT_STATUS_SHAPE as PROJECT_STATUSES, PULL_REQUEST_SHAPE as PULL_REQUESTS,
-    TAG_SHAPE as TAGS, USER_SHAPE as USERS, WORKSPACE_SHAPE as WORKSPACES, all_shapes,
+    SHARED_TASK_ATTEMPT_SHAPE as SHARED_TASK_ATTEMPTS, TAG_SHAPE as TAGS, USER_SHAPE as USERS,
+    WORKSPACE_SHAPE as WORKSPACES, all_shapes,
 };
@@ -17,6 +17,26 @@ fn generate_types_content() -> String {
         db::models::project::ProjectWithTaskCounts::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
+        db::models::project::ExecutorDefault::decl(),
+        db::models::project::ProjectBranchCleanup::decl(),
+        db::models::project::ProjectExecutorDefaults::decl(),
+        db::models::execution_window::ExecutionWindow::decl(),
+        db::models::execution_window::DeferredAttempt::decl(),
+        db::models::project_budget::BudgetEnforcement::decl(),
+        db::models::project_budget::ProjectBudget::decl(),
+        db::models::project_budget::BudgetStatus::decl(),
+        db::models::e2e_test::ProjectE2eSettings::decl(),
+        db::models::e2e_test::UpsertProjectE2eSettings::decl(),
+        db::models::e2e_test::E2eTestStatus::decl(),
+        db::models::e2e_test::E2eTestRun::decl(),
+        db::models::e2e_test::E2eTestResult::decl(),
+        db::models::e2e_test::E2eTestReport::decl(),
+        db::models::preview::ProjectPreviewSettings::decl(),
+        db::models::preview::UpsertProjectPreviewSettings::decl(),
+        db::models::preview::VisualComparison::decl(),
+        db::models::agent_instructions::AgentInstructionFile::decl(),
+        db::models::agent_instructions::ProjectAgentInstructions::decl(),
+        services::services::agent_instructions::InstructionFileStatus::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
         db::models::repo::Repo::decl(),
@@ -26,9 +46,16 @@ fn generate_types_content() -> String {
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
+        db::models::workspace_repo::WorkspaceRepoCheckout::decl(),
+        db::models::workspace_repo::CreateWorkspaceRepoCheckout::decl(),
+        db::models::workspace_target_sync::WorkspaceTargetSync::decl(),
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
+        db::models::prompt_template::PromptTemplate::decl(),
+        db::models::prompt_template::PromptTemplateVersion::decl(),
+        db::models::prompt_template::CreatePromptTemplate::decl(),
+        db::models::prompt_template::UpdatePromptTemplate::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
@@ -36,9 +63,14 @@ fn generate_types_content() -> String {
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task::MoveTask::decl(),
         db::models::task_label::TaskLabel::decl(),
         db::models::task_label::CreateTaskLabel::decl(),
         db::models::task_label::UpdateTaskLabel::decl(),
+        db::models::task_context_pack::ContextDelivery::decl(),
+        db::models::task_context_pack::ContextItem::decl(),
+        db::models::task_context_pack::TaskContextPack::decl(),
+        db::models::task_context_pack::UpsertTaskContextPack::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
@@ -58,6 +90,10 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_log_event::ExecutionLogEventKind::decl(),
+        db::models::execution_log_event::ExecutionLogEvent::decl(),
+        db::models::execution_log_event::ExecutionLogEventData::decl(),
+        server::routes::execution_processes::ExecutionLogEventMessage::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -69,6 +105,8 @@ fn generate_types_content() -> String {
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
+        utils::validation::FieldError::decl(),
+        utils::validation::ValidationErrors::decl(),
         utils::api::oauth::LoginStatus::decl(),
         utils::api::oauth::ProfileResponse::decl(),
         utils::api::oauth::ProviderProfile::decl(),
@@ -89,19 +127,47 @@ fn generate_types_content() -> String {
         utils::api::organizations::GetInvitationResponse::decl(),
         utils::api::organizations::AcceptInvitationResponse::decl(),
         utils::api::organizations::RevokeInvitationRequest::decl(),
+        utils::api::organizations::ResendInvitationRequest::decl(),
         utils::api::organizations::OrganizationMember::decl(),
         utils::api::organizations::OrganizationMemberWithProfile::decl(),
         utils::api::organizations::ListMembersResponse::decl(),
         utils::api::organizations::UpdateMemberRoleRequest::decl(),
         utils::api::organizations::UpdateMemberRoleResponse::decl(),
+        utils::api::organizations::NotificationDigest::decl(),
+        utils::api::organizations::MemberNotificationPreferences::decl(),
+        utils::api::organizations::UpdateMemberNotificationPreferencesRequest::decl(),
+        utils::api::organizations::SharedConfigBaseline::decl(),
+        utils::api::organizations::OrganizationSharedConfig::decl(),
+        utils::api::organizations::UpdateSharedConfigRequest::decl(),
+        server::routes::organizations::PullSharedConfigResponse::decl(),
         utils::api::projects::RemoteProject::decl(),
         utils::api::projects::ListProjectsResponse::decl(),
         utils::api::projects::RemoteProjectMembersResponse::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
+        server::routes::projects::PublishProjectRequest::decl(),
+        server::routes::projects::PublishProjectResponse::decl(),
+        server::routes::projects::ProjectReportQuery::decl(),
+        server::routes::projects::SimilarTasksQuery::decl(),
+        server::routes::projects::ProjectReport::decl(),
+        server::routes::projects::DependencyAllowlist::decl(),
+        server::routes::projects::DiffIgnorePatterns::decl(),
+        server::routes::projects::SensitivePathTestRequest::decl(),
+        server::routes::projects::SensitivePathTestResult::decl(),
+        services::services::sensitive_files::ProjectSensitivePatterns::decl(),
+        db::models::project_report::ReportTask::decl(),
+        db::models::project_report::ReportPullRequest::decl(),
+        db::models::project_report::ReportFailedAttempt::decl(),
+        db::models::project_report::ProjectActivity::decl(),
+        db::models::project_report::ReleaseTask::decl(),
+        services::services::release_notes::DraftReleaseRequest::decl(),
+        services::services::release_notes::ReleaseDraft::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::prompts::PromptTemplateQuery::decl(),
+        server::routes::prompts::PreviewPromptRequest::decl(),
+        services::services::prompt_library::RenderedPrompt::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -118,19 +184,109 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
         server::routes::task_attempts::PushTaskAttemptRequest::decl(),
         server::routes::task_attempts::RenameBranchRequest::decl(),
+        server::routes::task_attempts::PromoteWorkspaceRequest::decl(),
+        server::routes::task_attempts::PromoteWorkspaceResponse::decl(),
+        server::routes::task_attempts::DiffDigestQuery::decl(),
+        server::routes::task_attempts::DiffDigestResponse::decl(),
+        server::routes::task_attempts::EnvironmentCompareQuery::decl(),
+        server::routes::task_attempts::EnvironmentComparison::decl(),
         server::routes::task_attempts::RenameBranchResponse::decl(),
         server::routes::task_attempts::RenameBranchError::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
         server::routes::sessions::review::ReviewError::decl(),
+        server::routes::sessions::handoff::HandoffSessionRequest::decl(),
+        server::routes::sessions::handoff::HandoffSessionResponse::decl(),
+        db::models::session_handoff::SessionHandoff::decl(),
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
+        server::routes::task_attempts::EditorLinkQuery::decl(),
+        server::routes::task_attempts::EditorLinkResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::FanOutTaskRequest::decl(),
+        server::routes::projects::UpdateExecutionWindowRequest::decl(),
+        server::routes::projects::UpdateProjectBudgetRequest::decl(),
+        server::routes::projects::UpdateAgentInstructionsRequest::decl(),
+        server::routes::projects::WriteInstructionFileRequest::decl(),
         server::routes::tasks::ShareTaskResponse::decl(),
+        server::routes::tasks::EnrichTaskQuery::decl(),
+        server::routes::tasks::EnrichTaskResponse::decl(),
+        services::services::task_enrichment::RelevantFile::decl(),
+        services::services::task_enrichment::TaskBrief::decl(),
+        server::routes::tasks::SuggestedContextQuery::decl(),
+        services::services::embedding_index::IndexStats::decl(),
+        services::services::embedding_index::SuggestedFile::decl(),
+        server::routes::tasks::SimilarTasksLimitQuery::decl(),
+        services::services::task_knowledge::SimilarTask::decl(),
+        server::routes::tasks::TaskTraceQuery::decl(),
+        services::services::worktree_watch::RepoWorktreeState::decl(),
+        services::services::worktree_watch::WorktreeState::decl(),
+        services::services::traceability::TraceSource::decl(),
+        services::services::traceability::TaskTrace::decl(),
+        server::routes::tasks::BlameTraceQuery::decl(),
+        services::services::traceability::BlameTrace::decl(),
+        db::models::time_entry::TimeEntrySource::decl(),
+        db::models::time_entry::TimeEntry::decl(),
+        db::models::time_entry::TimeTotals::decl(),
+        db::models::time_entry::TaskTimeTotals::decl(),
+        db::models::time_entry::UserTimeTotals::decl(),
+        db::models::time_entry::TaskTime::decl(),
+        db::models::time_entry::ProjectTime::decl(),
+        server::routes::tasks::StartTimerRequest::decl(),
+        server::routes::stats::ProjectStats::decl(),
+        db::models::task_estimate::TaskEstimate::decl(),
+        db::models::task_estimate::UpsertTaskEstimate::decl(),
+        db::models::task_fan_out::TaskFanOutChild::decl(),
+        db::models::task_fan_out::TaskFanOut::decl(),
+        db::models::velocity::VelocityWindow::decl(),
+        db::models::velocity::ProjectVelocity::decl(),
+        db::models::velocity::RemainingWork::decl(),
+        db::models::velocity::ProjectForecast::decl(),
+        server::routes::projects::VelocityQuery::decl(),
+        db::models::epic::Epic::decl(),
+        db::models::epic::CreateEpic::decl(),
+        db::models::epic::UpdateEpic::decl(),
+        db::models::task_assignee::TaskAssignee::decl(),
+        services::services::swimlanes::LaneGrouping::decl(),
+        services::services::swimlanes::BoardLane::decl(),
+        server::routes::epics::LanesQuery::decl(),
+        server::routes::tasks::SetTaskEpicRequest::decl(),
+        server::routes::tasks::SetTaskAssigneeRequest::decl(),
+        db::models::milestone::Milestone::decl(),
+        db::models::milestone::CreateMilestone::decl(),
+        db::models::milestone::UpdateMilestone::decl(),
+        db::models::milestone::BurndownPoint::decl(),
+        db::models::milestone::MilestoneBurndown::decl(),
+        server::routes::milestones::SetMilestoneTasksRequest::decl(),
+        db::models::task_planning::TaskPriority::decl(),
+        db::models::task_planning::TaskPlanning::decl(),
+        db::models::task_planning::UpsertTaskPlanning::decl(),
+        services::services::quick_add::QuickAddParse::decl(),
+        server::routes::projects::QuickAddTaskRequest::decl(),
+        server::routes::projects::QuickAddTaskResponse::decl(),
+        db::models::chat_channel::ChatPlatform::decl(),
+        db::models::chat_channel::ChatChannel::decl(),
+        db::models::chat_channel::CreateChatChannel::decl(),
+        db::models::task_attachment::TaskAttachment::decl(),
+        db::models::attempt_artifact::AttemptArtifact::decl(),
+        server::routes::task_attempts::artifacts::RegisterArtifactRequest::decl(),
+        db::models::task_activity::TaskActivityKind::decl(),
+        db::models::task_activity::TaskActivity::decl(),
+        server::routes::projects::VoiceNoteTask::decl(),
+        services::services::review_checklist::ChecklistCategory::decl(),
+        services::services::review_checklist::ChecklistItem::decl(),
+        services::services::review_checklist::ReviewChecklist::decl(),
+        services::services::dependency_changes::Ecosystem::decl(),
+        services::services::dependency_changes::DependencyChangeKind::decl(),
+        services::services::dependency_changes::DependencyChange::decl(),
+        services::services::dependency_changes::DependencyChanges::decl(),
+        services::services::diff_digest::FileStat::decl(),
+        services::services::diff_digest::DiffDigest::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
+        server::routes::task_attempts::RepoCheckoutInput::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
@@ -146,6 +302,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::pr::OpenPrCommentRequest::decl(),
         // AutoPrResult y AutoPrError removidos en upstream
         // TaskUpdateResponse removido en upstream
         services::services::git_host::UnifiedPrComment::decl(),
@@ -156,6 +313,16 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::task_attempts::comparison::CompareExecutorsRequest::decl(),
+        server::routes::task_attempts::comparison::CompareExecutorsResponse::decl(),
+        server::routes::task_attempts::comparison::ComparisonAttempt::decl(),
+        server::routes::task_attempts::comparison::ComparisonReport::decl(),
+        server::routes::task_attempts::ReplayPromptRequest::decl(),
+        server::routes::task_attempts::DeferredStart::decl(),
+        server::routes::task_attempts::TranscriptQuery::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::TranscriptTurn::decl(),
+        services::services::transcript::Transcript::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
@@ -166,18 +333,94 @@ fn generate_types_content() -> String {
         services::services::config::EditorType::decl(),
         services::services::config::EditorOpenError::decl(),
         services::services::config::GitHubConfig::decl(),
+        services::services::analytics::QueuedEvent::decl(),
+        services::services::analytics::AnalyticsQueue::decl(),
+        services::services::secrets::SecretInfo::decl(),
+        server::routes::secrets::SetSecretRequest::decl(),
+        db::models::lifecycle_hook::LifecycleEvent::decl(),
+        db::models::lifecycle_hook::HookKind::decl(),
+        db::models::lifecycle_hook::LifecycleHook::decl(),
+        db::models::lifecycle_hook::CreateLifecycleHook::decl(),
+        db::models::lifecycle_hook::UpdateLifecycleHook::decl(),
+        server::routes::hooks::LifecycleHookQuery::decl(),
+        db::models::project_script::ScriptEvent::decl(),
+        db::models::project_script::ProjectScript::decl(),
+        db::models::project_script::CreateProjectScript::decl(),
+        db::models::project_script::UpdateProjectScript::decl(),
+        db::models::env_template::ProjectEnvTemplate::decl(),
+        db::models::env_template::UpsertProjectEnvTemplate::decl(),
+        db::models::env_template::WorkspacePort::decl(),
+        services::services::env_templates::EnvTemplateCheck::decl(),
+        server::routes::env_templates::CheckEnvTemplateRequest::decl(),
+        services::services::project_template::ProjectTemplateSource::decl(),
+        services::services::project_template::CreateProjectFromTemplate::decl(),
+        services::services::project_template::ProjectFromTemplate::decl(),
+        server::routes::capabilities::FeatureStatus::decl(),
+        server::routes::capabilities::FeatureMap::decl(),
+        server::routes::capabilities::Capabilities::decl(),
+        services::services::github_app::GitHubAppStatus::decl(),
+        services::services::github_app::GitHubAppInstallUrl::decl(),
+        services::services::diagnostics::CheckStatus::decl(),
+        services::services::diagnostics::DiagnosticCheck::decl(),
+        services::services::diagnostics::SystemDiagnostics::decl(),
+        services::services::diagnostics::RemoteTransport::decl(),
+        services::services::diagnostics::RemoteCredentialCheck::decl(),
+        services::services::diagnostics::HostCredentialStatus::decl(),
+        services::services::diagnostics::GitHubDiagnostics::decl(),
+        server::routes::diagnostics::StoreCredentialsRequest::decl(),
+        db::models::coverage::RepoCoverageSettings::decl(),
+        db::models::coverage::UpsertRepoCoverageSettings::decl(),
+        db::models::coverage::CoverageReport::decl(),
+        db::models::repo_ssh_key::SshKeyMode::decl(),
+        db::models::repo_ssh_key::RepoSshKey::decl(),
+        db::models::repo_ssh_key::UpsertRepoSshKey::decl(),
+        db::models::security_audit::SecurityScanner::decl(),
+        db::models::security_audit::FindingSeverity::decl(),
+        db::models::security_audit::RepoSecurityAuditSettings::decl(),
+        db::models::security_audit::UpsertRepoSecurityAuditSettings::decl(),
+        db::models::security_audit::SecurityFinding::decl(),
+        db::models::workspace_environment::ToolVersion::decl(),
+        db::models::workspace_environment::LockfileHash::decl(),
+        db::models::workspace_environment::WorkspaceEnvironment::decl(),
+        services::services::environment_snapshot::EnvironmentDifferenceKind::decl(),
+        services::services::environment_snapshot::EnvironmentDifference::decl(),
+        db::models::instance_stats::ExecutorStats::decl(),
+        db::models::instance_stats::InstanceStats::decl(),
+        db::models::job::JobStatus::decl(),
+        db::models::job::Job::decl(),
+        db::models::project_share_link::ProjectShareLink::decl(),
+        db::models::project_share_link::CreateProjectShareLink::decl(),
+        db::models::project_share_link::PublicBoardLabel::decl(),
+        db::models::project_share_link::PublicBoardTask::decl(),
+        db::models::project_share_link::PublicBoard::decl(),
+        db::models::project_share_rule::ProjectShareRule::decl(),
+        db::models::project_share_rule::CreateProjectShareRule::decl(),
+        db::models::agent_prompt::AgentPromptKind::decl(),
+        db::models::agent_prompt::AgentPrompt::decl(),
+        db::models::execution_interrupt::ExecutionInterrupt::decl(),
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::GitCommitTitleMode::decl(),
         services::services::config::GitAutoPushMode::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::EmbeddingProvider::decl(),
+        services::services::config::TargetSyncMode::decl(),
+        services::services::config::TranscriptionProvider::decl(),
         db::models::pending_commit::PendingCommit::decl(),
         db::models::pending_commit::CreatePendingCommit::decl(),
         server::routes::pending_commits::CommitPendingRequest::decl(),
         server::routes::shared_tasks::SharedTask::decl(),
         server::routes::shared_tasks::UserData::decl(),
         server::routes::shared_tasks::AssigneesQuery::decl(),
+        server::routes::shared_tasks::SharedTaskClaim::decl(),
+        server::routes::shared_tasks::SharedTaskExecutionState::decl(),
+        server::routes::shared_tasks::SharedTaskExecutionStatus::decl(),
+        server::routes::shared_tasks::PullRequestStatus::decl(),
+        server::routes::shared_tasks::SharedTaskAttempt::decl(),
+        server::routes::shared_tasks::Mention::decl(),
+        remote::routes::mentions::MarkMentionsReadRequest::decl(),
+        remote::routes::mentions::MarkMentionsReadResponse::decl(),
         server::routes::shared_tasks::SharedTaskResponse::decl(),
         server::routes::shared_tasks::AssignSharedTaskRequest::decl(),
         services::services::git::GitBranch::decl(),
@@ -415,5 +658,11 @@ fn main() {
         write_schemas(&schemas_path, schema_content).expect("unable to write schemas");
 
         println!("✅ JSON schemas generated in shared/schemas/");
+
+        let api_schema = serde_json::to_string_pretty(&server::routes::api_schema::document())
+            .expect("unable to serialize the API schema");
+        fs::write(shared_path.join("api-schema.json"), api_schema)
+            .expect("unable to write api-schema.json");
+        println!("✅ API schema generated in shared/api-schema.json");
     }
 }
@@ -5,7 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
+    execution_process::ExecutionProcessError, project::ProjectError, project_budget::BudgetStatus,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
     workspace::WorkspaceError,
 };
@@ -14,19 +14,36 @@ use executors::{command::CommandBuildError, executors::ExecutorError};
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
 use services::services::{
+    artifacts::ArtifactError,
+    automation_scripts::AutomationScriptError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
-    git::GitServiceError,
+    embedding_index::EmbeddingError,
+    env_templates::EnvTemplateError,
+    git::{GitCliError, GitServiceError},
     git_host::GitHostError,
+    i18n::{Text, tr, tr_with},
     image::ImageError,
+    jobs::JobError,
+    lifecycle_hooks::HookError,
+    multi_user::MultiUserError,
+    preview_capture::PreviewError,
     project::ProjectServiceError,
+    project_template::ProjectTemplateError,
+    release_notes::ReleaseNotesError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    secrets::SecretsError,
     share::ShareError,
+    task_knowledge::TaskKnowledgeError,
+    traceability::TraceError,
+    transcription::TranscriptionError,
+    visual_regression::VisualRegressionError,
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
-use utils::response::ApiResponse;
+use utils::{response::ApiResponse, validation::ValidationErrors};
 
 #[derive(Debug, Error, ts_rs::TS)]
 #[ts(type = "string")]
@@ -73,16 +90,24 @@ pub enum ApiError {
     Unauthorized,
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Invalid request: {0}")]
+    Validation(ValidationErrors),
     #[error("Conflict: {0}")]
     Conflict(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
+    #[error("Starting this run is expected to exceed the project's monthly budget")]
+    BudgetExceeded(BudgetStatus),
     #[error(transparent)]
     CommandBuilder(#[from] CommandBuildError),
     #[error(transparent)]
     Pty(#[from] PtyError),
     #[error(transparent)]
     Share(#[from] ShareError),
+    #[error(transparent)]
+    MultiUser(#[from] MultiUserError),
+    #[error(transparent)]
+    Job(#[from] JobError),
 }
 
 impl From<&'static str> for ApiError {
@@ -97,6 +122,108 @@ impl From<Git2Error> for ApiError {
     }
 }
 
+impl From<ArtifactError> for ApiError {
+    fn from(err: ArtifactError) -> Self {
+        match err {
+            ArtifactError::Database(err) => ApiError::Database(err),
+            ArtifactError::Io(err) => ApiError::Io(err),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<PreviewError> for ApiError {
+    fn from(err: PreviewError) -> Self {
+        match err {
+            PreviewError::Database(err) => ApiError::Database(err),
+            PreviewError::Artifact(err) => err.into(),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<VisualRegressionError> for ApiError {
+    fn from(err: VisualRegressionError) -> Self {
+        match err {
+            VisualRegressionError::Database(err) => ApiError::Database(err),
+            VisualRegressionError::Preview(err) => err.into(),
+            VisualRegressionError::Artifact(err) => err.into(),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<SecretsError> for ApiError {
+    fn from(err: SecretsError) -> Self {
+        match err {
+            SecretsError::Io(err) => ApiError::Io(err),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<HookError> for ApiError {
+    fn from(err: HookError) -> Self {
+        match err {
+            HookError::Database(err) => ApiError::Database(err),
+            err @ HookError::Vetoed { .. } => ApiError::Forbidden(err.to_string()),
+        }
+    }
+}
+
+impl From<AutomationScriptError> for ApiError {
+    fn from(err: AutomationScriptError) -> Self {
+        match err {
+            AutomationScriptError::Database(err) => ApiError::Database(err),
+            AutomationScriptError::Workspace(err) => ApiError::Workspace(err),
+            err @ AutomationScriptError::Invalid(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<EmbeddingError> for ApiError {
+    fn from(err: EmbeddingError) -> Self {
+        match err {
+            EmbeddingError::Database(err) => ApiError::Database(err),
+            err @ EmbeddingError::Disabled => ApiError::Conflict(err.to_string()),
+            err @ EmbeddingError::Request(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<TranscriptionError> for ApiError {
+    fn from(err: TranscriptionError) -> Self {
+        match err {
+            TranscriptionError::Io(err) => ApiError::Io(err),
+            err @ (TranscriptionError::Disabled | TranscriptionError::MissingCommand) => {
+                ApiError::Conflict(err.to_string())
+            }
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<EnvTemplateError> for ApiError {
+    fn from(err: EnvTemplateError) -> Self {
+        match err {
+            EnvTemplateError::Database(err) => ApiError::Database(err),
+            EnvTemplateError::Io(err) => ApiError::Io(err),
+            EnvTemplateError::Git(err) => err.into(),
+            err @ EnvTemplateError::NoFreePort(_) => ApiError::Conflict(err.to_string()),
+        }
+    }
+}
+
+impl From<TaskKnowledgeError> for ApiError {
+    fn from(err: TaskKnowledgeError) -> Self {
+        match err {
+            TaskKnowledgeError::Embedding(err) => err.into(),
+            TaskKnowledgeError::Database(err) => ApiError::Database(err),
+            TaskKnowledgeError::Workspace(err) => ApiError::Workspace(err),
+        }
+    }
+}
+
 impl From<RemoteClientNotConfigured> for ApiError {
     fn from(_: RemoteClientNotConfigured) -> Self {
         ApiError::BadRequest("Remote client not configured".to_string())
@@ -105,6 +232,24 @@ impl From<RemoteClientNotConfigured> for ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        // Field errors go in error_data so forms can show them next to the inputs
+        if let ApiError::Validation(errors) = self {
+            let response = ApiResponse::<(), ValidationErrors>::error_with_message_and_data(
+                &errors.summary(),
+                errors,
+            );
+            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        }
+        // The budget lets the client show spending and, unless the project
+        // blocks, offer to start the run anyway
+        if let ApiError::BudgetExceeded(status) = self {
+            let response = ApiResponse::<(), BudgetStatus>::error_with_message_and_data(
+                "Starting this run is expected to exceed the project's monthly budget",
+                status,
+            );
+            return (StatusCode::CONFLICT, Json(response)).into_response();
+        }
+
         let (status_code, error_type) = match &self {
             ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectError"),
             ApiError::Repo(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectRepoError"),
@@ -125,10 +270,19 @@ impl IntoResponse for ApiError {
                 services::services::git::GitServiceError::RebaseInProgress => {
                     (StatusCode::CONFLICT, "GitServiceError")
                 }
+                services::services::git::GitServiceError::GitCLI(GitCliError::Timeout(_)) => {
+                    (StatusCode::GATEWAY_TIMEOUT, "GitTimeoutError")
+                }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
+            ApiError::GitHost(GitHostError::Timeout(_)) => {
+                (StatusCode::GATEWAY_TIMEOUT, "GitHostTimeoutError")
+            }
             ApiError::GitHost(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHostError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
+            ApiError::Container(ContainerError::WorkspaceManager(
+                WorkspaceManagerError::Locked(_),
+            )) => (StatusCode::CONFLICT, "WorkspaceLockedError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::CommandBuilder(_) => (StatusCode::INTERNAL_SERVER_ERROR, "CommandBuildError"),
@@ -183,8 +337,10 @@ impl IntoResponse for ApiError {
             },
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
+            ApiError::Validation(_) => (StatusCode::BAD_REQUEST, "ValidationError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
+            ApiError::BudgetExceeded(_) => (StatusCode::CONFLICT, "BudgetExceeded"),
             ApiError::Pty(err) => match err {
                 PtyError::SessionNotFound(_) => (StatusCode::NOT_FOUND, "PtyError"),
                 PtyError::SessionClosed => (StatusCode::GONE, "PtyError"),
@@ -199,57 +355,66 @@ impl IntoResponse for ApiError {
                 ShareError::MissingConfig(_) => (StatusCode::BAD_REQUEST, "ShareError"),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ShareError"),
             },
+            ApiError::MultiUser(err) => match err {
+                MultiUserError::MissingToken | MultiUserError::InvalidToken(_) => {
+                    (StatusCode::UNAUTHORIZED, "MultiUserError")
+                }
+                MultiUserError::NotFound => (StatusCode::NOT_FOUND, "MultiUserError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "MultiUserError"),
+            },
+            ApiError::Job(_) => (StatusCode::INTERNAL_SERVER_ERROR, "JobError"),
         };
 
         let error_message = match &self {
             ApiError::Image(img_err) => match img_err {
-                ImageError::InvalidFormat => "This file type is not supported. Please upload an image file (PNG, JPG, GIF, WebP, or BMP).".to_string(),
-                ImageError::TooLarge(size, max) => format!(
-                    "This image is too large ({:.1} MB). Maximum file size is {:.1} MB.",
-                    *size as f64 / 1_048_576.0,
-                    *max as f64 / 1_048_576.0
+                ImageError::InvalidFormat => tr(Text::ImageInvalidFormat).to_string(),
+                ImageError::TooLarge(size, max) => tr_with(
+                    Text::ImageTooLarge,
+                    &[
+                        ("size", &format!("{:.1}", *size as f64 / 1_048_576.0)),
+                        ("max", &format!("{:.1}", *max as f64 / 1_048_576.0)),
+                    ],
                 ),
-                ImageError::NotFound => "Image not found.".to_string(),
-                _ => {
-                    "Failed to process image. Please try again.".to_string()
-                }
+                ImageError::NotFound => tr(Text::ImageNotFound).to_string(),
+                _ => tr(Text::ImageProcessingFailed).to_string(),
             },
             ApiError::GitService(git_err) => match git_err {
                 services::services::git::GitServiceError::MergeConflicts { message, .. } => {
                     message.clone()
                 }
                 services::services::git::GitServiceError::RebaseInProgress => {
-                    "A rebase is already in progress. Resolve conflicts or abort the rebase, then retry.".to_string()
+                    tr(Text::RebaseInProgress).to_string()
                 }
                 _ => format!("{}: {}", error_type, self),
             },
-            ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
+            ApiError::Multipart(_) => tr(Text::UploadFailed).to_string(),
             ApiError::RemoteClient(err) => match err {
-                RemoteClientError::Auth => "Unauthorized. Please sign in again.".to_string(),
-                RemoteClientError::Timeout => "Remote service timeout. Please try again.".to_string(),
-                RemoteClientError::Transport(_) => "Remote service unavailable. Please try again.".to_string(),
+                RemoteClientError::Auth => tr(Text::Unauthorized).to_string(),
+                RemoteClientError::Timeout => tr(Text::RemoteTimeout).to_string(),
+                RemoteClientError::Transport(_) => tr(Text::RemoteUnavailable).to_string(),
                 RemoteClientError::Http { body, .. } => {
                     if body.is_empty() {
-                        "Remote service error. Please try again.".to_string()
+                        tr(Text::RemoteError).to_string()
                     } else {
                         body.clone()
                     }
                 }
                 RemoteClientError::Token(_) => {
-                    "Remote service returned an invalid access token. Please sign in again.".to_string()
+                    "Remote service returned an invalid access token. Please sign in again."
+                        .to_string()
                 }
                 RemoteClientError::Storage(_) => {
                     "Failed to persist credentials locally. Please retry.".to_string()
                 }
                 RemoteClientError::Api(code) => match code {
                     services::services::remote_client::HandoffErrorCode::NotFound => {
-                        "The requested resource was not found.".to_string()
+                        tr(Text::ResourceNotFound).to_string()
                     }
                     services::services::remote_client::HandoffErrorCode::Expired => {
                         "The link or token has expired.".to_string()
                     }
                     services::services::remote_client::HandoffErrorCode::AccessDenied => {
-                        "Access denied.".to_string()
+                        tr(Text::AccessDenied).to_string()
                     }
                     services::services::remote_client::HandoffErrorCode::UnsupportedProvider => {
                         "Unsupported authentication provider.".to_string()
@@ -270,10 +435,15 @@ impl IntoResponse for ApiError {
                         format!("Authentication error: {}", msg)
                     }
                 },
-                RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
+                RemoteClientError::Serde(_) => {
+                    "Unexpected response from remote service.".to_string()
+                }
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
-            ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
+            ApiError::Container(ContainerError::WorkspaceManager(
+                WorkspaceManagerError::Locked(lock_err),
+            )) => lock_err.to_string(),
+            ApiError::Unauthorized => tr(Text::Unauthorized).to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
@@ -318,6 +488,44 @@ impl From<ProjectServiceError> for ApiError {
     }
 }
 
+impl From<ProjectTemplateError> for ApiError {
+    fn from(err: ProjectTemplateError) -> Self {
+        match err {
+            ProjectTemplateError::Database(db_err) => ApiError::Database(db_err),
+            ProjectTemplateError::Io(io_err) => ApiError::Io(io_err),
+            ProjectTemplateError::Project(project_err) => project_err.into(),
+            ProjectTemplateError::Git(git_err) => ApiError::GitService(git_err),
+            ProjectTemplateError::DirectoryAlreadyExists(path) => {
+                ApiError::Conflict(format!("Directory already exists: {}", path.display()))
+            }
+            err @ (ProjectTemplateError::Clone(_)
+            | ProjectTemplateError::GitHub(_)
+            | ProjectTemplateError::PathNotFound(_)) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<ReleaseNotesError> for ApiError {
+    fn from(err: ReleaseNotesError) -> Self {
+        match err {
+            ReleaseNotesError::Database(db_err) => ApiError::Database(db_err),
+            ReleaseNotesError::Git(git_err) => ApiError::GitService(git_err),
+            err @ ReleaseNotesError::GitHub(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<TraceError> for ApiError {
+    fn from(err: TraceError) -> Self {
+        match err {
+            TraceError::Database(db_err) => ApiError::Database(db_err),
+            err @ (TraceError::UnknownRepo(_) | TraceError::Blame(_)) => {
+                ApiError::BadRequest(err.to_string())
+            }
+        }
+    }
+}
+
 impl From<RepoServiceError> for ApiError {
     fn from(err: RepoServiceError) -> Self {
         match err {
@@ -342,6 +550,9 @@ impl From<RepoServiceError> for ApiError {
             RepoServiceError::InvalidFolderName(name) => {
                 ApiError::BadRequest(format!("Invalid folder name: {}", name))
             }
+            RepoServiceError::InvalidSshKey(msg) => {
+                ApiError::BadRequest(format!("Invalid SSH key configuration: {}", msg))
+            }
         }
     }
 }
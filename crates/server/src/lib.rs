@@ -2,6 +2,7 @@ pub mod error;
 pub mod mcp;
 pub mod middleware;
 pub mod routes;
+pub mod validation;
 pub mod ws_utils;
 
 // #[cfg(feature = "cloud")]
@@ -1,12 +1,24 @@
+use std::path::PathBuf;
+
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
-use services::services::container::ContainerService;
+use server::{DeploymentImpl, middleware, routes};
+use services::services::{
+    bootstrap::{BootstrapError, BootstrapService, BootstrapSpec},
+    config::save_config_to_file,
+    container::ContainerService,
+    workspace_lock::WorkspaceLock,
+    workspace_manager::WorkspaceManager,
+};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
 use tracing_subscriber::EnvFilter;
-use utils::{assets::asset_dir, browser::open_browser, port_file::write_port_file};
+use utils::{
+    assets::{asset_dir, config_path},
+    browser::open_browser,
+    port_file::{register_instance, unregister_instance, write_port_file},
+};
 
 #[derive(Debug, Error)]
 pub enum VibeKanbanError {
@@ -17,9 +29,50 @@ pub enum VibeKanbanError {
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
+    Bootstrap(#[from] BootstrapError),
+    #[error(transparent)]
     Other(#[from] AnyhowError),
 }
 
+/// Startup options read from CLI flags, falling back to environment variables
+#[derive(Debug, Default)]
+struct StartupOptions {
+    /// Never open a browser or wait on onboarding; suited for CI and shared servers
+    headless: bool,
+    /// Declarative bootstrap file reconciled into the database before serving
+    bootstrap_file: Option<PathBuf>,
+    /// Reject every mutating API request; suited for demos and shared instances
+    read_only: bool,
+}
+
+impl StartupOptions {
+    fn parse() -> Self {
+        let mut options = Self {
+            headless: std::env::var("VK_HEADLESS")
+                .is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes")),
+            bootstrap_file: std::env::var("VK_BOOTSTRAP_FILE").ok().map(PathBuf::from),
+            read_only: std::env::var("VK_READ_ONLY")
+                .is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes")),
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => options.headless = true,
+                "--read-only" => options.read_only = true,
+                "--bootstrap" => options.bootstrap_file = args.next().map(PathBuf::from),
+                other => {
+                    if let Some(path) = other.strip_prefix("--bootstrap=") {
+                        options.bootstrap_file = Some(PathBuf::from(path));
+                    }
+                }
+            }
+        }
+
+        options
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), VibeKanbanError> {
     // Install rustls crypto provider before any TLS operations
@@ -35,6 +88,8 @@ async fn main() -> Result<(), VibeKanbanError> {
     let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
     tracing_subscriber::fmt().with_env_filter(env_filter).init();
 
+    let startup = StartupOptions::parse();
+
     // Create asset directory if it doesn't exist
     if !asset_dir().exists() {
         std::fs::create_dir_all(asset_dir())?;
@@ -57,6 +112,34 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_target_sync_service().await;
+    deployment.spawn_worktree_watch_service().await;
+    deployment.jobs().spawn_workers().await;
+
+    if startup.headless {
+        acknowledge_onboarding(&deployment).await?;
+    }
+
+    if let Some(bootstrap_file) = &startup.bootstrap_file {
+        let spec = BootstrapSpec::from_file(bootstrap_file)?;
+        let report = BootstrapService::new()
+            .reconcile(
+                &deployment.db().pool,
+                deployment.project(),
+                deployment.repo(),
+                &spec,
+            )
+            .await?;
+        tracing::info!(
+            "Applied bootstrap file {}: {} project(s) created, {} repo(s) added, {} label(s) created, {} label(s) updated, profiles updated: {}",
+            bootstrap_file.display(),
+            report.projects_created.len(),
+            report.repositories_added.len(),
+            report.labels_created.len(),
+            report.labels_updated.len(),
+            report.profiles_updated
+        );
+    }
 
     // Pre-warm file search cache for most active projects
     let deployment_for_cache = deployment.clone();
@@ -70,6 +153,11 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
+    if startup.read_only {
+        tracing::info!("Read-only mode: mutating API requests will be rejected");
+        middleware::set_read_only(true);
+    }
+
     let app_router = routes::router(deployment.clone());
 
     let port = std::env::var("BACKEND_PORT")
@@ -110,10 +198,19 @@ async fn main() -> Result<(), VibeKanbanError> {
     if let Err(e) = write_port_file(actual_port).await {
         tracing::warn!("Failed to write port file: {}", e);
     }
+    if let Err(e) = register_instance(
+        "vibe-kanban",
+        actual_port,
+        Some(&WorkspaceManager::get_workspace_base_dir()),
+    )
+    .await
+    {
+        tracing::warn!("Failed to register instance in port registry: {}", e);
+    }
 
     tracing::info!("Server running on http://{host}:{actual_port}");
 
-    if !cfg!(debug_assertions) {
+    if !cfg!(debug_assertions) && !startup.headless {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
             if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
@@ -135,6 +232,21 @@ async fn main() -> Result<(), VibeKanbanError> {
     Ok(())
 }
 
+/// Headless servers have nobody to click through the disclaimer and onboarding
+/// dialogs, so mark them as acknowledged up front.
+async fn acknowledge_onboarding(deployment: &DeploymentImpl) -> Result<(), VibeKanbanError> {
+    let mut config = deployment.config().write().await;
+    if config.disclaimer_acknowledged && config.onboarding_acknowledged {
+        return Ok(());
+    }
+    config.disclaimer_acknowledged = true;
+    config.onboarding_acknowledged = true;
+    save_config_to_file(&config, &config_path())
+        .await
+        .map_err(DeploymentError::from)?;
+    Ok(())
+}
+
 pub async fn shutdown_signal() {
     // Always wait for Ctrl+C
     let ctrl_c = async {
@@ -177,4 +289,8 @@ pub async fn perform_cleanup_actions(deployment: &DeploymentImpl) {
         .kill_all_running_processes()
         .await
         .expect("Failed to cleanly kill running execution processes");
+    WorkspaceLock::shutdown(deployment.db()).await;
+    if let Err(e) = unregister_instance("vibe-kanban").await {
+        tracing::warn!("Failed to remove instance from port registry: {}", e);
+    }
 }
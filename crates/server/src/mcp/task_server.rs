@@ -23,7 +23,7 @@ use uuid::Uuid;
 
 use crate::routes::{
     containers::ContainerQuery,
-    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
+    task_attempts::{CreateTaskAttemptBody, RepoCheckoutInput, WorkspaceRepoInput},
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -281,6 +281,23 @@ pub struct McpWorkspaceRepoInput {
     pub repo_id: Uuid,
     #[schemars(description = "The base branch for this repository")]
     pub base_branch: String,
+    #[schemars(
+        description = "Optional directory of a monorepo to limit the attempt to, e.g. 'packages/web'"
+    )]
+    pub path_scope: Option<String>,
+    #[schemars(
+        description = "Optional extra checkouts of the repository at other branches, next to the primary one"
+    )]
+    #[serde(default)]
+    pub checkouts: Option<Vec<McpRepoCheckoutInput>>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct McpRepoCheckoutInput {
+    #[schemars(description = "Name of the checkout; its directory is '<repo name>-<alias>'")]
+    pub alias: String,
+    #[schemars(description = "The branch to start the checkout from")]
+    pub source_branch: String,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -905,6 +922,16 @@ impl TaskServer {
             .map(|r| WorkspaceRepoInput {
                 repo_id: r.repo_id,
                 target_branch: r.base_branch,
+                path_scope: r.path_scope,
+                checkouts: r.checkouts.map(|checkouts| {
+                    checkouts
+                        .into_iter()
+                        .map(|c| RepoCheckoutInput {
+                            alias: c.alias,
+                            source_branch: c.source_branch,
+                        })
+                        .collect()
+                }),
             })
             .collect();
 
@@ -912,6 +939,8 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            ignore_execution_window: false,
+            confirm_over_budget: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -0,0 +1,129 @@
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use deployment::Deployment;
+use services::services::multi_user::{MultiUserError, MultiUserService, RequestUser};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Query parameter accepted in place of the Authorization header, for
+/// WebSocket and EventSource connections that cannot set headers.
+const ACCESS_TOKEN_QUERY_PARAM: &str = "access_token";
+
+fn request_token(request: &Request) -> Option<String> {
+    let from_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    from_header.or_else(|| {
+        url::form_urlencoded::parse(request.uri().query()?.as_bytes())
+            .find(|(key, _)| key == ACCESS_TOKEN_QUERY_PARAM)
+            .map(|(_, value)| value.into_owned())
+    })
+}
+
+/// In multi-user mode, authenticate the request and attach its [`RequestUser`].
+/// A no-op in single-user mode.
+pub async fn require_user(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let Some(multi_user) = deployment.multi_user() else {
+        return Ok(next.run(request).await);
+    };
+
+    match multi_user.authenticate(request_token(&request).as_deref()) {
+        Ok(user) => {
+            request.extensions_mut().insert(user);
+            Ok(next.run(request).await)
+        }
+        Err(e) => {
+            tracing::debug!("Rejected unauthenticated request: {}", e);
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+fn access_status(e: MultiUserError) -> StatusCode {
+    match e {
+        MultiUserError::NotFound => StatusCode::NOT_FOUND,
+        e => {
+            tracing::error!("Failed to check resource ownership: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Reject access to another user's project. Always allowed in single-user mode.
+pub async fn authorize_project(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    project_id: Uuid,
+) -> Result<(), StatusCode> {
+    match user {
+        Some(user) => {
+            MultiUserService::ensure_project_access(&deployment.db().pool, user, project_id)
+                .await
+                .map_err(access_status)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Reject access to a workspace of another user's project
+pub async fn authorize_workspace(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    workspace_id: Uuid,
+) -> Result<(), StatusCode> {
+    match user {
+        Some(user) => {
+            MultiUserService::ensure_workspace_access(&deployment.db().pool, user, workspace_id)
+                .await
+                .map_err(access_status)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Route-level variant of [`authorize_project`] for ids taken from a query or body
+pub async fn ensure_project_access(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    project_id: Uuid,
+) -> Result<(), ApiError> {
+    if let Some(user) = user {
+        MultiUserService::ensure_project_access(&deployment.db().pool, user, project_id).await?;
+    }
+    Ok(())
+}
+
+/// Route-level variant of [`authorize_workspace`] for ids taken from a query or body
+pub async fn ensure_workspace_access(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    workspace_id: Uuid,
+) -> Result<(), ApiError> {
+    if let Some(user) = user {
+        MultiUserService::ensure_workspace_access(&deployment.db().pool, user, workspace_id)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Reject a request for an instance-wide feature in multi-user mode. `message`
+/// names the feature, e.g. "Secrets are only available in single-user mode".
+pub fn ensure_single_user(user: Option<&RequestUser>, message: &str) -> Result<(), ApiError> {
+    match user {
+        Some(_) => Err(ApiError::Forbidden(message.to_string())),
+        None => Ok(()),
+    }
+}
@@ -1,5 +1,11 @@
+pub mod auth;
 pub mod model_loaders;
 pub mod origin;
+pub mod rate_limit;
+pub mod read_only;
 
+pub use auth::*;
 pub use model_loaders::*;
 pub use origin::*;
+pub use rate_limit::*;
+pub use read_only::*;
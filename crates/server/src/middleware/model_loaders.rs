@@ -9,8 +9,10 @@ use db::models::{
     workspace::Workspace,
 };
 use deployment::Deployment;
+use services::services::multi_user::RequestUser;
 use uuid::Uuid;
 
+use super::auth::{authorize_project, authorize_workspace};
 use crate::DeploymentImpl;
 
 pub async fn load_project_middleware(
@@ -32,6 +34,9 @@ pub async fn load_project_middleware(
         }
     };
 
+    let user = request.extensions().get::<RequestUser>().copied();
+    authorize_project(&deployment, user.as_ref(), project.id).await?;
+
     // Insert the project as an extension
     let mut request = request;
     request.extensions_mut().insert(project);
@@ -59,6 +64,9 @@ pub async fn load_task_middleware(
         }
     };
 
+    let user = request.extensions().get::<RequestUser>().copied();
+    authorize_project(&deployment, user.as_ref(), task.project_id).await?;
+
     // Insert both models as extensions
     let mut request = request;
     request.extensions_mut().insert(task);
@@ -86,6 +94,9 @@ pub async fn load_workspace_middleware(
         }
     };
 
+    let user = request.extensions().get::<RequestUser>().copied();
+    authorize_workspace(&deployment, user.as_ref(), workspace.id).await?;
+
     // Insert the workspace into extensions
     request.extensions_mut().insert(workspace);
 
@@ -113,6 +124,21 @@ pub async fn load_execution_process_middleware(
             }
         };
 
+    if let Some(user) = request.extensions().get::<RequestUser>().copied() {
+        let session = Session::find_by_id(&deployment.db().pool, execution_process.session_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to fetch session {}: {}",
+                    execution_process.session_id,
+                    e
+                );
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        authorize_workspace(&deployment, Some(&user), session.workspace_id).await?;
+    }
+
     // Inject the execution process into the request
     request.extensions_mut().insert(execution_process);
 
@@ -166,6 +192,9 @@ pub async fn load_session_middleware(
         }
     };
 
+    let user = request.extensions().get::<RequestUser>().copied();
+    authorize_workspace(&deployment, user.as_ref(), session.workspace_id).await?;
+
     request.extensions_mut().insert(session);
     Ok(next.run(request).await)
 }
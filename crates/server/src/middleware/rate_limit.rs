@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{LazyLock, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use deployment::Deployment;
+use services::services::multi_user::RequestUser;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::DeploymentImpl;
+
+/// POST routes that start an agent, a script or a headless browser, with
+/// `*` standing for one path segment such as an id
+const EXECUTION_ROUTES: &[&str] = &[
+    "/task-attempts",
+    "/task-attempts/compare",
+    "/task-attempts/*/run-agent-setup",
+    "/task-attempts/*/run-setup-script",
+    "/task-attempts/*/run-cleanup-script",
+    "/task-attempts/*/start-dev-server",
+    "/task-attempts/*/interrupt",
+    "/task-attempts/*/pr",
+    "/task-attempts/*/coverage",
+    "/task-attempts/*/e2e-tests",
+    "/task-attempts/*/preview",
+    "/task-attempts/*/visual-comparisons",
+    "/task-attempts/*/security-audit",
+    "/task-attempts/*/review-checklist",
+    "/task-attempts/*/prompts/replay",
+    "/tasks/create-and-start",
+    "/tasks/*/fan-out",
+    "/sessions/*/follow-up",
+    "/sessions/*/review",
+    "/sessions/*/handoff",
+];
+
+/// Buckets idle this long are full again and can be dropped
+const IDLE_EVICTION: Duration = Duration::from_secs(10 * 60);
+const EVICTION_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RouteGroup {
+    Read,
+    Write,
+    Execution,
+}
+
+fn route_group(method: &Method, path: &str) -> RouteGroup {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return RouteGroup::Read;
+    }
+    let path = path.trim_end_matches('/');
+    if *method == Method::POST
+        && EXECUTION_ROUTES
+            .iter()
+            .any(|pattern| matches_route(pattern, path))
+    {
+        return RouteGroup::Execution;
+    }
+    RouteGroup::Write
+}
+
+fn matches_route(pattern: &str, path: &str) -> bool {
+    let mut segments = path.split('/');
+    let mut parts = pattern.split('/');
+    loop {
+        match (parts.next(), segments.next()) {
+            (None, None) => return true,
+            (Some("*"), Some(segment)) if !segment.is_empty() => {}
+            (Some(part), Some(segment)) if part == segment => {}
+            _ => return false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientKey {
+    User(Uuid),
+    Ip(IpAddr),
+    Unknown,
+}
+
+/// Requests per minute for each route group
+#[derive(Debug, Clone, Copy)]
+struct Budgets {
+    reads: u32,
+    writes: u32,
+    executions: u32,
+}
+
+impl Budgets {
+    fn per_minute(&self, group: RouteGroup) -> u32 {
+        match group {
+            RouteGroup::Read => self.reads,
+            RouteGroup::Write => self.writes,
+            RouteGroup::Execution => self.executions,
+        }
+    }
+}
+
+/// `VK_RATE_LIMIT` forces limiting on or off; by default it only applies in
+/// multi-user mode. Budgets come from `VK_RATE_LIMIT_READS`,
+/// `VK_RATE_LIMIT_WRITES` and `VK_RATE_LIMIT_EXECUTIONS` (requests per minute).
+#[derive(Debug, Clone, Copy)]
+struct RateLimitSettings {
+    enabled: Option<bool>,
+    budgets: Budgets,
+}
+
+fn settings() -> &'static RateLimitSettings {
+    static SETTINGS: OnceLock<RateLimitSettings> = OnceLock::new();
+    SETTINGS.get_or_init(|| {
+        let enabled = std::env::var("VK_RATE_LIMIT").ok().and_then(|value| {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Some(true),
+                "0" | "false" | "no" | "off" => Some(false),
+                _ => None,
+            }
+        });
+        let budget = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse::<u32>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        RateLimitSettings {
+            enabled,
+            budgets: Budgets {
+                reads: budget("VK_RATE_LIMIT_READS", 600),
+                writes: budget("VK_RATE_LIMIT_WRITES", 120),
+                executions: budget("VK_RATE_LIMIT_EXECUTIONS", 20),
+            },
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Default)]
+struct RateLimiter {
+    buckets: Mutex<HashMap<(ClientKey, RouteGroup), Bucket>>,
+}
+
+impl RateLimiter {
+    /// Take a token from the client's bucket for `group`, or return how long
+    /// until one is available. Buckets hold a minute's budget and refill
+    /// continuously, so short bursts are fine.
+    fn check(
+        &self,
+        key: ClientKey,
+        group: RouteGroup,
+        per_minute: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let capacity = f64::from(per_minute);
+        let refill_per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > EVICTION_THRESHOLD {
+            buckets.retain(|_, bucket| now.duration_since(bucket.refilled_at) < IDLE_EVICTION);
+        }
+
+        let bucket = buckets.entry((key, group)).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / refill_per_sec,
+            ))
+        }
+    }
+}
+
+static LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::default);
+
+fn client_key(request: &Request) -> ClientKey {
+    if let Some(user) = request.extensions().get::<RequestUser>() {
+        return ClientKey::User(user.user_id);
+    }
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| ClientKey::Ip(addr.ip()))
+        .unwrap_or(ClientKey::Unknown)
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(ApiResponse::<()>::error(&format!(
+            "Too many requests; retry in {secs}s"
+        ))),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    response
+}
+
+/// Limit requests per authenticated user, or per client IP when there is no
+/// user, with separate budgets for reads, writes and execution-starting
+/// routes. Must run after [`super::require_user`] to key on the user.
+pub async fn rate_limit(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let settings = settings();
+    if !settings
+        .enabled
+        .unwrap_or_else(|| deployment.multi_user().is_some())
+    {
+        return next.run(request).await;
+    }
+
+    let group = route_group(request.method(), request.uri().path());
+    let key = client_key(&request);
+    match LIMITER.check(
+        key,
+        group,
+        settings.budgets.per_minute(group),
+        Instant::now(),
+    ) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::debug!(?key, ?group, "Rate limit exceeded");
+            too_many_requests(retry_after)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_route_groups() {
+        assert_eq!(route_group(&Method::GET, "/tasks"), RouteGroup::Read);
+        assert_eq!(route_group(&Method::PUT, "/tasks/abc"), RouteGroup::Write);
+        assert_eq!(
+            route_group(&Method::POST, "/task-attempts"),
+            RouteGroup::Execution
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/sessions/abc/follow-up"),
+            RouteGroup::Execution
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/tasks/create-and-start"),
+            RouteGroup::Execution
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/task-attempts/abc/pr/attach"),
+            RouteGroup::Write
+        );
+        assert_eq!(
+            route_group(&Method::GET, "/task-attempts/abc/coverage"),
+            RouteGroup::Read
+        );
+        for path in [
+            "/sessions/abc/handoff",
+            "/task-attempts/abc/interrupt",
+            "/task-attempts/compare",
+            "/tasks/abc/fan-out",
+            "/task-attempts/abc/e2e-tests",
+            "/task-attempts/abc/preview",
+            "/task-attempts/abc/visual-comparisons",
+            "/task-attempts/abc/prompts/replay",
+        ] {
+            assert_eq!(
+                route_group(&Method::POST, path),
+                RouteGroup::Execution,
+                "{path}"
+            );
+        }
+        assert_eq!(
+            route_group(&Method::POST, "/prompts/preview"),
+            RouteGroup::Write
+        );
+        assert_eq!(
+            route_group(&Method::PUT, "/projects/abc/preview"),
+            RouteGroup::Write
+        );
+    }
+
+    #[test]
+    fn rejects_once_budget_is_spent_and_refills_over_time() {
+        let limiter = RateLimiter::default();
+        let key = ClientKey::Ip(IpAddr::from([127, 0, 0, 1]));
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check(key, RouteGroup::Write, 3, start).is_ok());
+        }
+        let retry_after = limiter.check(key, RouteGroup::Write, 3, start).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(20));
+
+        // Other groups and clients have their own buckets
+        assert!(limiter.check(key, RouteGroup::Read, 3, start).is_ok());
+        let other = ClientKey::Ip(IpAddr::from([10, 0, 0, 1]));
+        assert!(limiter.check(other, RouteGroup::Write, 3, start).is_ok());
+
+        let later = start + Duration::from_secs(20);
+        assert!(limiter.check(key, RouteGroup::Write, 3, later).is_ok());
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use axum::{
+    Json,
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use utils::response::ApiResponse;
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// POST routes that only read or sign in and out, allowed in read-only mode
+const ALLOWED_POSTS: &[&str] = &[
+    "/repos/batch",
+    "/prompts/preview",
+    "/auth/handoff/init",
+    "/auth/logout",
+];
+
+/// GET routes that can change state, rejected in read-only mode
+const WRITE_GETS: &[&str] = &["/terminal/ws"];
+
+/// Turn read-only mode on or off for the whole server
+pub fn set_read_only(enabled: bool) {
+    READ_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+fn is_mutating(method: &Method, path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        WRITE_GETS.contains(&path)
+    } else {
+        !(*method == Method::POST && ALLOWED_POSTS.contains(&path))
+    }
+}
+
+/// Reject every request that could change state while the server runs in
+/// read-only mode, so a demo or a shared live instance can be browsed safely
+pub async fn read_only(request: Request, next: Next) -> Response {
+    if is_read_only() && is_mutating(request.method(), request.uri().path()) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "This instance is read-only; changes are disabled",
+            )),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_mutating_requests() {
+        assert!(!is_mutating(&Method::GET, "/tasks"));
+        assert!(!is_mutating(&Method::GET, "/info"));
+        assert!(is_mutating(&Method::PUT, "/config"));
+        assert!(is_mutating(&Method::POST, "/tasks"));
+        assert!(is_mutating(&Method::DELETE, "/tasks/abc"));
+        assert!(!is_mutating(&Method::POST, "/repos/batch"));
+        assert!(is_mutating(&Method::GET, "/terminal/ws"));
+    }
+}
@@ -0,0 +1,63 @@
+//! JSON Schema for the request and response types of the core API, so
+//! scripts written in other languages can validate the payloads they send and
+//! receive. `npm run generate-types` also writes it to `shared/api-schema.json`.
+
+use axum::response::Json as ResponseJson;
+use db::models::{
+    project::{CreateProject, Project},
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_label::{CreateTaskLabel, TaskLabel, UpdateTaskLabel},
+};
+use schemars::{JsonSchema, SchemaGenerator, generate::SchemaSettings};
+use serde_json::{Value, json};
+use utils::response::ApiResponse;
+
+fn register<T: JsonSchema>(generator: &mut SchemaGenerator) {
+    generator.subschema_for::<T>();
+}
+
+/// One document with every exported type under `$defs`. Endpoints wrap their
+/// result in `ApiResponse`, whose `data` holds the documented type.
+pub fn document() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+
+    register::<ApiResponse<Value>>(&mut generator);
+    register::<Project>(&mut generator);
+    register::<CreateProject>(&mut generator);
+    register::<TaskStatus>(&mut generator);
+    register::<Task>(&mut generator);
+    register::<TaskWithAttemptStatus>(&mut generator);
+    register::<CreateTask>(&mut generator);
+    register::<UpdateTask>(&mut generator);
+    register::<TaskLabel>(&mut generator);
+    register::<CreateTaskLabel>(&mut generator);
+    register::<UpdateTaskLabel>(&mut generator);
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Vibe Kanban API",
+        "$defs": generator.definitions(),
+    })
+}
+
+pub async fn get_api_schema() -> ResponseJson<Value> {
+    ResponseJson(document())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_defines_the_exported_types() {
+        let document = document();
+        let defs = document["$defs"].as_object().unwrap();
+        for name in ["Project", "Task", "CreateTask", "UpdateTask", "TaskStatus"] {
+            assert!(defs.contains_key(name), "missing {name}");
+        }
+        assert_eq!(
+            defs["CreateTask"]["properties"]["status"]["anyOf"][0]["$ref"],
+            "#/$defs/TaskStatus"
+        );
+    }
+}
@@ -1,23 +1,66 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Path, State},
     http::StatusCode,
     response::Json as ResponseJson,
     routing::post,
 };
+use db::models::{execution_process::ExecutionProcess, session::Session};
 use deployment::Deployment;
+use services::services::multi_user::RequestUser;
 use utils::{
     approvals::{ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, middleware::authorize_workspace};
+
+/// Reject a response to an approval raised in another user's workspace
+async fn authorize_approval(
+    deployment: &DeploymentImpl,
+    user: &RequestUser,
+    id: &str,
+) -> Result<(), StatusCode> {
+    let pool = &deployment.db().pool;
+    let execution_process_id = deployment
+        .approvals()
+        .pending_execution_process_id(id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let execution_process = ExecutionProcess::find_by_id(pool, execution_process_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch execution process {}: {}",
+                execution_process_id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let session = Session::find_by_id(pool, execution_process.session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch session {}: {}",
+                execution_process.session_id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_workspace(deployment, Some(user), session.workspace_id).await
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(id): Path<String>,
     ResponseJson(request): ResponseJson<ApprovalResponse>,
 ) -> Result<ResponseJson<ApiResponse<ApprovalStatus>>, StatusCode> {
+    if let Some(Extension(user)) = user {
+        authorize_approval(&deployment, &user, &id).await?;
+    }
+
     let service = deployment.approvals();
 
     match service.respond(&deployment.db().pool, &id, request).await {
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, response::Json as ResponseJson};
+use deployment::Deployment;
+use executors::{
+    executors::BaseAgentCapability,
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use serde::Serialize;
+use services::services::{
+    config::{Config, EmbeddingProvider, GitCommitTitleMode, TranscriptionProvider},
+    git_host::github::GitHubProvider,
+};
+use ts_rs::TS;
+use utils::{api::oauth::LoginStatus, response::ApiResponse, shell::resolve_executable_path};
+
+use crate::{DeploymentImpl, middleware};
+
+/// Whether an optional feature can work on this server, and whether the
+/// config turns it on
+#[derive(Debug, Clone, Copy, Serialize, TS)]
+pub struct FeatureStatus {
+    /// The build and environment support it
+    pub available: bool,
+    /// The config has it switched on
+    pub enabled: bool,
+}
+
+impl FeatureStatus {
+    fn new(available: bool, enabled: bool) -> Self {
+        Self { available, enabled }
+    }
+}
+
+/// Optional features, keyed by name
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FeatureMap {
+    /// Open a pull request when an attempt moves to review
+    pub auto_pr: FeatureStatus,
+    /// Generate pull request descriptions with the coding agent
+    pub pr_auto_description: FeatureStatus,
+    /// Generate commit titles with the coding agent
+    pub ai_commit_titles: FeatureStatus,
+    pub analytics: FeatureStatus,
+    /// Suggest task context from an embedding index of repo files
+    pub embedding_index: FeatureStatus,
+    /// Create tasks from transcribed voice notes
+    pub voice_notes: FeatureStatus,
+    /// Share projects and tasks through the remote service
+    pub remote_sharing: FeatureStatus,
+    /// Several users with their own accounts on one server
+    pub multi_user: FeatureStatus,
+    /// Mock executors for QA builds
+    pub qa_mode: FeatureStatus,
+}
+
+/// What this server allows, so clients can hide UI that would fail
+#[derive(Debug, Serialize, TS)]
+pub struct Capabilities {
+    /// Mutating requests are rejected with 403 (demo or shared instances)
+    pub read_only: bool,
+    pub features: FeatureMap,
+    /// Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+    pub executors: HashMap<String, Vec<BaseAgentCapability>>,
+}
+
+/// Capabilities of every configured coding agent, keyed by executor
+pub fn executor_capabilities() -> HashMap<String, Vec<BaseAgentCapability>> {
+    let mut caps: HashMap<String, Vec<BaseAgentCapability>> = HashMap::new();
+    let profs = ExecutorConfigs::get_cached();
+    for key in profs.executors.keys() {
+        if let Some(agent) = profs.get_coding_agent(&ExecutorProfileId::new(*key)) {
+            caps.insert(key.to_string(), agent.capabilities());
+        }
+    }
+    caps
+}
+
+/// What the running server can offer, independent of the config
+#[derive(Debug, Clone, Copy, Default)]
+struct ServerEnvironment {
+    read_only: bool,
+    /// `gh` is installed or a GitHub token is configured
+    git_host_ready: bool,
+    analytics: bool,
+    remote_available: bool,
+    logged_in: bool,
+    multi_user: bool,
+}
+
+fn feature_map(config: &Config, env: ServerEnvironment) -> FeatureMap {
+    FeatureMap {
+        auto_pr: FeatureStatus::new(
+            env.git_host_ready && !env.read_only,
+            config.auto_pr_on_review_enabled,
+        ),
+        pr_auto_description: FeatureStatus::new(
+            env.git_host_ready && !env.read_only,
+            config.pr_auto_description_enabled,
+        ),
+        ai_commit_titles: FeatureStatus::new(
+            !env.read_only,
+            config.git_commit_title_mode == GitCommitTitleMode::AiGenerated,
+        ),
+        analytics: FeatureStatus::new(env.analytics, config.analytics_enabled),
+        embedding_index: FeatureStatus::new(
+            true,
+            config.embedding_provider != EmbeddingProvider::Disabled,
+        ),
+        voice_notes: FeatureStatus::new(
+            !env.read_only,
+            config.transcription_provider != TranscriptionProvider::Disabled,
+        ),
+        remote_sharing: FeatureStatus::new(env.remote_available, env.logged_in),
+        multi_user: FeatureStatus::new(env.multi_user, env.multi_user),
+        qa_mode: FeatureStatus::new(cfg!(feature = "qa-mode"), cfg!(feature = "qa-mode")),
+    }
+}
+
+pub async fn get_capabilities(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Capabilities>> {
+    let read_only = middleware::is_read_only();
+    let config = deployment.config().read().await.clone();
+    let remote_available = deployment.remote_client().is_ok();
+    let env = ServerEnvironment {
+        read_only,
+        git_host_ready: resolve_executable_path("gh").await.is_some()
+            || GitHubProvider::has_config_token(),
+        analytics: deployment.analytics().is_some(),
+        remote_available,
+        logged_in: remote_available
+            && matches!(
+                deployment.get_login_status().await,
+                LoginStatus::LoggedIn { .. }
+            ),
+        multi_user: deployment.multi_user().is_some(),
+    };
+
+    ResponseJson(ApiResponse::success(Capabilities {
+        read_only,
+        features: feature_map(&config, env),
+        executors: executor_capabilities(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_host_features_need_gh_and_a_writable_server() {
+        let config = Config {
+            auto_pr_on_review_enabled: true,
+            ..Config::default()
+        };
+        let ready = ServerEnvironment {
+            git_host_ready: true,
+            ..ServerEnvironment::default()
+        };
+
+        let features = feature_map(&config, ready);
+        assert!(features.auto_pr.available && features.auto_pr.enabled);
+
+        let features = feature_map(&config, ServerEnvironment::default());
+        assert!(!features.auto_pr.available && features.auto_pr.enabled);
+
+        let read_only = ServerEnvironment {
+            read_only: true,
+            ..ready
+        };
+        let features = feature_map(&config, read_only);
+        assert!(!features.auto_pr.available);
+        assert!(!features.ai_commit_titles.available);
+        assert!(!features.voice_notes.available);
+    }
+
+    #[test]
+    fn remote_sharing_is_enabled_only_once_logged_in() {
+        let config = Config::default();
+        let env = ServerEnvironment {
+            remote_available: true,
+            ..ServerEnvironment::default()
+        };
+        let features = feature_map(&config, env);
+        assert!(features.remote_sharing.available && !features.remote_sharing.enabled);
+
+        let features = feature_map(
+            &config,
+            ServerEnvironment {
+                logged_in: true,
+                ..env
+            },
+        );
+        assert!(features.remote_sharing.enabled);
+    }
+}
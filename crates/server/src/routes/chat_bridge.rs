@@ -0,0 +1,355 @@
+use axum::{
+    Extension, Router,
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get, post},
+};
+use chrono::{Local, Utc};
+use db::models::{
+    chat_channel::{ChatChannel, ChatPlatform, CreateChatChannel},
+    merge::Merge,
+    project::Project,
+    project_repo::ProjectRepo,
+    task::Task,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use secrecy::ExposeSecret;
+use services::services::{
+    chat_bridge::{
+        self, ChatFollowUp, ChatRequest, SlackCommand, SlackReply, TelegramReply, TelegramUpdate,
+    },
+    multi_user::RequestUser,
+    quick_add,
+    secrets::{SLACK_SIGNING_SECRET, TELEGRAM_BOT_TOKEN, TELEGRAM_WEBHOOK_SECRET},
+};
+use utils::{response::ApiResponse, validation::Validate};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::ensure_project_access,
+    routes::task_attempts::{self, WorkspaceRepoInput},
+    validation::ValidatedJson,
+};
+
+/// Routes to link chat channels to a project
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/chat-channels",
+            get(list_chat_channels).post(create_chat_channel),
+        )
+        .route(
+            "/projects/{project_id}/chat-channels/{channel_id}",
+            delete(delete_chat_channel),
+        )
+}
+
+/// Webhooks called by Slack and Telegram; verified by their signing secrets
+/// instead of a user session
+pub fn public_router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/integrations/slack/commands", post(slack_command))
+        .route("/integrations/telegram/webhook", post(telegram_webhook))
+}
+
+async fn list_chat_channels(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ChatChannel>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let channels = ChatChannel::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(channels)))
+}
+
+async fn create_chat_channel(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateChatChannel>,
+) -> Result<ResponseJson<ApiResponse<ChatChannel>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let pool = &deployment.db().pool;
+    if ChatChannel::find_by_channel(pool, payload.platform, payload.channel_id.trim())
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(
+            "This chat is already linked to a project".to_string(),
+        ));
+    }
+    let channel = ChatChannel::create(pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(channel)))
+}
+
+async fn delete_chat_channel(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, channel_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let pool = &deployment.db().pool;
+    ChatChannel::find_by_id(pool, channel_id)
+        .await?
+        .filter(|channel| channel.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Chat channel not found in project".to_string()))?;
+    ChatChannel::delete(pool, channel_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+async fn optional_secret(deployment: &DeploymentImpl, name: &str) -> Option<String> {
+    deployment
+        .secrets()
+        .get(name)
+        .await
+        .map(|secret| secret.expose_secret().to_string())
+}
+
+async fn secret(deployment: &DeploymentImpl, name: &str) -> Result<String, ApiError> {
+    optional_secret(deployment, name)
+        .await
+        .ok_or_else(|| ApiError::Forbidden(format!("Set the {name} secret to enable this")))
+}
+
+/// Public URL the chat service reached us on, for links in replies
+fn base_url(headers: &HeaderMap) -> String {
+    let host = header(headers, "x-forwarded-host")
+        .or_else(|| header(headers, "host"))
+        .unwrap_or("127.0.0.1");
+    let scheme = header(headers, "x-forwarded-proto").unwrap_or(
+        if host.starts_with("localhost") || host.starts_with("127.") {
+            "http"
+        } else {
+            "https"
+        },
+    );
+    format!("{scheme}://{host}")
+}
+
+async fn slack_command(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<SlackReply>, ApiError> {
+    let signing_secret = secret(&deployment, SLACK_SIGNING_SECRET).await?;
+    let verified = chat_bridge::verify_slack_signature(
+        &signing_secret,
+        header(&headers, "x-slack-request-timestamp").unwrap_or_default(),
+        header(&headers, "x-slack-signature").unwrap_or_default(),
+        &body,
+        Utc::now(),
+    );
+    if !verified {
+        return Err(ApiError::Unauthorized);
+    }
+    let command = SlackCommand::from_form(&body)
+        .ok_or_else(|| ApiError::BadRequest("Missing channel_id".to_string()))?;
+    let follow_up = command
+        .response_url
+        .clone()
+        .map(|response_url| ChatFollowUp::Slack { response_url });
+
+    let reply = reply(
+        &deployment,
+        ChatPlatform::Slack,
+        &command.channel_id,
+        &command.text,
+        &base_url(&headers),
+        follow_up,
+    )
+    .await;
+    Ok(ResponseJson(SlackReply::new(reply)))
+}
+
+async fn telegram_webhook(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let webhook_secret = secret(&deployment, TELEGRAM_WEBHOOK_SECRET).await?;
+    let provided = header(&headers, "x-telegram-bot-api-secret-token").unwrap_or_default();
+    if !chat_bridge::verify_telegram_secret(&webhook_secret, provided) {
+        return Err(ApiError::Unauthorized);
+    }
+    let update: TelegramUpdate = serde_json::from_slice(&body)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid Telegram update: {err}")))?;
+    let Some((chat_id, text)) = update.task_command() else {
+        return Ok(StatusCode::OK.into_response());
+    };
+    // Follow-ups need the bot token; without it only the first reply is sent
+    let follow_up = optional_secret(&deployment, TELEGRAM_BOT_TOKEN)
+        .await
+        .map(|bot_token| ChatFollowUp::Telegram { bot_token, chat_id });
+
+    let reply = reply(
+        &deployment,
+        ChatPlatform::Telegram,
+        &chat_id.to_string(),
+        &text,
+        &base_url(&headers),
+        follow_up,
+    )
+    .await;
+    Ok(ResponseJson(TelegramReply::new(chat_id, reply)).into_response())
+}
+
+/// Text to send back to the chat; failures are reported there too
+async fn reply(
+    deployment: &DeploymentImpl,
+    platform: ChatPlatform,
+    channel_id: &str,
+    text: &str,
+    base_url: &str,
+    follow_up: Option<ChatFollowUp>,
+) -> String {
+    match handle(deployment, platform, channel_id, text, base_url, follow_up).await {
+        Ok(reply) => reply,
+        Err(err) => {
+            tracing::warn!("{platform} command in {channel_id} failed: {err}");
+            format!("Something went wrong: {err}")
+        }
+    }
+}
+
+async fn handle(
+    deployment: &DeploymentImpl,
+    platform: ChatPlatform,
+    channel_id: &str,
+    text: &str,
+    base_url: &str,
+    follow_up: Option<ChatFollowUp>,
+) -> Result<String, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(channel) = ChatChannel::find_by_channel(pool, platform, channel_id).await? else {
+        return Ok(format!(
+            "This chat isn't linked to a project. Link {platform} chat `{channel_id}` in the project's settings."
+        ));
+    };
+    let task_url =
+        |task: &Task| format!("{base_url}/projects/{}/tasks/{}", task.project_id, task.id);
+
+    match ChatRequest::parse(text) {
+        ChatRequest::Help => Ok(chat_bridge::HELP.to_string()),
+        ChatRequest::Status(task_id) => {
+            let Some(task) = Task::find_by_id(pool, task_id)
+                .await?
+                .filter(|task| task.project_id == channel.project_id)
+            else {
+                return Ok("No such task in this project.".to_string());
+            };
+            let mut reply = format!("{} is {}: {}", task.title, task.status, task_url(&task));
+            if let Some(pr_url) = Merge::find_latest_pr_url_for_task(pool, task.id).await? {
+                reply.push_str(&format!("\nPull request: {pr_url}"));
+            }
+            Ok(reply)
+        }
+        ChatRequest::Create(text) => {
+            let parsed = quick_add::parse(&text, Local::now().date_naive());
+            if let Err(errors) = parsed.validate() {
+                return Ok(format!("Couldn't create the task: {}", errors.summary()));
+            }
+            let (task, planning) =
+                quick_add::create_task(pool, channel.project_id, &parsed).await?;
+            let mut reply = format!("Created {}: {}", task.title, task_url(&task));
+
+            if channel.auto_start {
+                let executor = planning.and_then(|planning| planning.executor);
+                spawn_auto_start(deployment.clone(), task.clone(), executor, follow_up);
+                reply.push_str("\nStarting an attempt...");
+            }
+
+            deployment
+                .track_if_analytics_allowed(
+                    "task_created",
+                    serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": task.project_id,
+                        "source": platform.to_string(),
+                        "auto_start": channel.auto_start,
+                    }),
+                )
+                .await;
+            Ok(reply)
+        }
+    }
+}
+
+/// Start the task in the background and post the outcome to the chat, as
+/// creating worktrees and launching the agent outlasts webhook timeouts
+fn spawn_auto_start(
+    deployment: DeploymentImpl,
+    task: Task,
+    executor: Option<BaseCodingAgent>,
+    follow_up: Option<ChatFollowUp>,
+) {
+    tokio::spawn(async move {
+        let text = match auto_start(&deployment, &task, executor).await {
+            Ok(workspace) => format!("Started {} on branch `{}`", task.title, workspace.branch),
+            Err(err) => {
+                tracing::warn!(task_id = %task.id, "Chat auto-start failed: {err}");
+                format!("Couldn't start an attempt on {}: {err}", task.title)
+            }
+        };
+        if let Some(follow_up) = follow_up
+            && let Err(err) = follow_up.send(text).await
+        {
+            tracing::warn!(task_id = %task.id, "Failed to post chat follow-up: {err}");
+        }
+    });
+}
+
+/// Start the task on all of its project's repos, with the executor asked for
+/// in the message or the project's default
+async fn auto_start(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    executor: Option<BaseCodingAgent>,
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
+    task_attempts::util::ensure_within_budget(pool, task.project_id, 1, false).await?;
+
+    let defaults = Project::find_executor_defaults(pool, task.project_id).await?;
+    let executor_profile_id = match executor {
+        Some(executor) => ExecutorProfileId {
+            executor,
+            variant: defaults
+                .for_executor(executor)
+                .and_then(|entry| entry.variant.clone()),
+        },
+        None => match defaults.default_profile() {
+            Some(profile_id) => profile_id,
+            None => deployment.config().read().await.executor_profile.clone(),
+        },
+    };
+
+    let mut repos = Vec::new();
+    for repo in ProjectRepo::find_repos_for_project(pool, task.project_id).await? {
+        let target_branch = match repo.default_target_branch.clone() {
+            Some(branch) => branch,
+            None => deployment.git().get_current_branch(&repo.path)?,
+        };
+        repos.push(WorkspaceRepoInput {
+            repo_id: repo.id,
+            target_branch,
+            path_scope: None,
+            checkouts: None,
+        });
+    }
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "The project has no repositories".to_string(),
+        ));
+    }
+
+    task_attempts::start_task_attempt(deployment, task, &executor_profile_id, &repos, false).await
+}
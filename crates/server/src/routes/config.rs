@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     body::Body,
     extract::{Path, Query, State},
     http,
@@ -21,16 +21,27 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    analytics::{AnalyticsConfig, AnalyticsQueue},
+    bounded_command::OperationTimeouts,
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    git_host::{
+        bitbucket::{BitbucketCredentials, BitbucketProvider},
+        github::GitHubProvider,
+    },
+    i18n::Locale,
+    multi_user::RequestUser,
+    secrets, traceability,
 };
 use tokio::fs;
 use ts_rs::TS;
 use utils::{api::oauth::LoginStatus, assets::config_path, response::ApiResponse};
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, routes::capabilities::executor_capabilities};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -44,6 +55,7 @@ pub fn router() -> Router<DeploymentImpl> {
             get(check_editor_availability),
         )
         .route("/agents/check-availability", get(check_agent_availability))
+        .route("/analytics/queue", get(get_analytics_queue))
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -89,25 +101,16 @@ pub struct UserSystemInfo {
 async fn get_user_system_info(
     State(deployment): State<DeploymentImpl>,
 ) -> ResponseJson<ApiResponse<UserSystemInfo>> {
-    let config = deployment.config().read().await;
+    let config = deployment.config().read().await.clone();
     let login_status = deployment.get_login_status().await;
 
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config: secrets::masked(&config),
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
         profiles: ExecutorConfigs::get_cached(),
         environment: Environment::new(),
-        capabilities: {
-            let mut caps: HashMap<String, Vec<BaseAgentCapability>> = HashMap::new();
-            let profs = ExecutorConfigs::get_cached();
-            for key in profs.executors.keys() {
-                if let Some(agent) = profs.get_coding_agent(&ExecutorProfileId::new(*key)) {
-                    caps.insert(key.to_string(), agent.capabilities());
-                }
-            }
-            caps
-        },
+        capabilities: executor_capabilities(),
     };
 
     ResponseJson(ApiResponse::success(user_system_info))
@@ -115,8 +118,15 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
-    Json(new_config): Json<Config>,
+    user: Option<Extension<RequestUser>>,
+    Json(mut new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
+    // Settings are shared by every user of the server
+    if user.is_some() {
+        return ResponseJson(ApiResponse::error(
+            "Settings can only be changed in single-user mode",
+        ));
+    }
     let config_path = config_path();
 
     // Validate git branch prefix
@@ -126,19 +136,50 @@ async fn update_config(
         ));
     }
 
+    new_config.task_trailer = new_config
+        .task_trailer
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+    if let Some(key) = &new_config.task_trailer
+        && !traceability::is_valid_trailer_key(key)
+    {
+        return ResponseJson(ApiResponse::error(
+            "Invalid task trailer. Use letters, digits and dashes, e.g. VK-Task.",
+        ));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
+    secrets::restore_masked(&mut new_config, &old_config);
+    if let Err(e) = deployment.secrets().sync_config(&new_config).await {
+        return ResponseJson(ApiResponse::error(&format!(
+            "Failed to save secrets: {}",
+            e
+        )));
+    }
+
     match save_config_to_file(&new_config, &config_path).await {
         Ok(_) => {
             let mut config = deployment.config().write().await;
             *config = new_config.clone();
             drop(config);
 
+            GitHubProvider::set_config_token(new_config.github.token());
+            GitHubProvider::set_enterprise_hosts(&new_config.github.enterprise_hosts);
+            BitbucketProvider::set_config_credentials(BitbucketCredentials::from_config(
+                &new_config,
+            ));
+            Locale::set_current(new_config.language);
+            OperationTimeouts::set_current(OperationTimeouts::from_config(&new_config));
+            if let Some(analytics) = deployment.analytics() {
+                analytics.update_config(AnalyticsConfig::from_config(&new_config));
+            }
+
             // Track config events when fields transition from false → true and run side effects
             handle_config_events(&deployment, &old_config, &new_config).await;
 
-            ResponseJson(ApiResponse::success(new_config))
+            ResponseJson(ApiResponse::success(secrets::masked(&new_config)))
         }
         Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
     }
@@ -154,6 +195,21 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
     }
 }
 
+/// Usage events waiting to be sent, so users can see exactly what is shared
+async fn get_analytics_queue(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<AnalyticsQueue>> {
+    let queue = match deployment.analytics() {
+        Some(analytics) => analytics.queued(),
+        None => AnalyticsQueue {
+            enabled: false,
+            endpoint: None,
+            events: vec![],
+        },
+    };
+    ResponseJson(ApiResponse::success(queue))
+}
+
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
     let sound = sound.serve().await.map_err(DeploymentError::Other)?;
     let response = Response::builder()
@@ -308,6 +364,52 @@ async fn update_mcp_servers_in_config(
     Ok(message)
 }
 
+/// Add an organization's shared MCP servers to each executor's config,
+/// keeping any server the user already has under the same name. Returns the
+/// executors whose config changed.
+pub(crate) async fn add_shared_mcp_servers(
+    shared: &HashMap<String, HashMap<String, Value>>,
+) -> Vec<BaseCodingAgent> {
+    let profiles = ExecutorConfigs::get_cached();
+    let mut updated = Vec::new();
+    for (executor, servers) in shared {
+        let normalized = executor.trim().replace('-', "_").to_ascii_uppercase();
+        let Ok(base) = BaseCodingAgent::from_str(&normalized) else {
+            tracing::warn!("Skipping shared MCP servers for unknown executor '{executor}'");
+            continue;
+        };
+        let Some(agent) = profiles.get_coding_agent(&ExecutorProfileId::new(base)) else {
+            continue;
+        };
+        let Some(config_path) = agent.default_mcp_config_path() else {
+            continue;
+        };
+        if !agent.supports_mcp() {
+            continue;
+        }
+        let mcpc = agent.get_mcp_config();
+        let current = match read_agent_config(&config_path, &mcpc).await {
+            Ok(config) => get_mcp_servers_from_config_path(&config, &mcpc.servers_path),
+            Err(e) => {
+                tracing::warn!("Failed to read MCP config for {base}: {e}");
+                continue;
+            }
+        };
+        let mut merged = current.clone();
+        for (name, server) in servers {
+            merged.entry(name.clone()).or_insert_with(|| server.clone());
+        }
+        if merged.len() == current.len() {
+            continue;
+        }
+        match update_mcp_servers_in_config(&config_path, &mcpc, merged).await {
+            Ok(_) => updated.push(base),
+            Err(e) => tracing::warn!("Failed to add shared MCP servers for {base}: {e}"),
+        }
+    }
+    updated
+}
+
 /// Helper function to get MCP servers from config using a path
 fn get_mcp_servers_from_config_path(raw_config: &Value, path: &[String]) -> HashMap<String, Value> {
     let mut current = raw_config;
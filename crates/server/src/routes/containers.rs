@@ -1,16 +1,21 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Query, State},
     response::Json as ResponseJson,
     routing::get,
 };
-use db::models::workspace::{Workspace, WorkspaceContext};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    session::Session,
+    workspace::{self, Workspace, WorkspaceContext},
+};
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
+use services::services::multi_user::RequestUser;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_project_access};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ContainerQuery {
@@ -63,6 +68,82 @@ pub async fn get_context(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RepoBranchQuery {
+    /// Directory open in the editor: a workspace, one of its repos, or a
+    /// project repo with the attempt branch checked out
+    pub path: String,
+    pub branch: Option<String>,
+}
+
+/// Everything an editor needs to follow the attempt open in it: the workspace
+/// with its task and project, the session to send follow-ups to and the
+/// latest coding agent run to stream logs from
+#[derive(Debug, Serialize)]
+pub struct CurrentContext {
+    #[serde(flatten)]
+    pub context: WorkspaceContext,
+    pub session: Option<Session>,
+    pub latest_coding_agent_process: Option<ExecutionProcess>,
+}
+
+/// Resolve a path and branch to its workspace: first as a workspace
+/// directory, then as a project repo checked out on an attempt branch
+async fn resolve_repo_branch(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    query: &RepoBranchQuery,
+) -> Result<workspace::ContainerInfo, ApiError> {
+    let pool = &deployment.db().pool;
+    let info = match Workspace::resolve_container_ref_by_prefix(pool, &query.path).await {
+        Ok(info) => info,
+        Err(sqlx::Error::RowNotFound) => match &query.branch {
+            Some(branch) => Workspace::resolve_repo_branch(pool, &query.path, branch).await?,
+            None => return Err(ApiError::Database(sqlx::Error::RowNotFound)),
+        },
+        Err(e) => return Err(ApiError::Database(e)),
+    };
+    ensure_project_access(deployment, user, info.project_id).await?;
+    Ok(info)
+}
+
+pub async fn lookup_repo_branch(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<RepoBranchQuery>,
+) -> Result<ResponseJson<ApiResponse<ContainerInfo>>, ApiError> {
+    let info = resolve_repo_branch(&deployment, user.as_deref(), &query).await?;
+    Ok(ResponseJson(ApiResponse::success(ContainerInfo {
+        project_id: info.project_id,
+        task_id: info.task_id,
+        attempt_id: info.workspace_id,
+    })))
+}
+
+pub async fn get_current_context(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<RepoBranchQuery>,
+) -> Result<ResponseJson<ApiResponse<CurrentContext>>, ApiError> {
+    let info = resolve_repo_branch(&deployment, user.as_deref(), &query).await?;
+    let pool = &deployment.db().pool;
+    let context =
+        Workspace::load_context(pool, info.workspace_id, info.task_id, info.project_id).await?;
+    let session = Session::find_latest_by_workspace_id(pool, info.workspace_id).await?;
+    let latest_coding_agent_process = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        info.workspace_id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(CurrentContext {
+        context,
+        session,
+        latest_coding_agent_process,
+    })))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         // NOTE: /containers/info is required by the VSCode extension (vibe-kanban-vscode)
@@ -70,4 +151,6 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         // Do not remove this endpoint without updating the extension.
         .route("/containers/info", get(get_container_info))
         .route("/containers/attempt-context", get(get_context))
+        .route("/containers/lookup", get(lookup_repo_branch))
+        .route("/containers/current", get(get_current_context))
 }
@@ -0,0 +1,127 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{project_repo::ProjectRepo, repo::Repo};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    diagnostics::{DiagnosticsService, GitHubDiagnostics, HostCredentialStatus, SystemDiagnostics},
+    git::GitServiceError,
+    multi_user::{MultiUserService, RequestUser},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/diagnostics", get(system_diagnostics))
+        .route("/diagnostics/github", get(github_diagnostics))
+        .route("/diagnostics/credentials", post(store_credentials))
+}
+
+/// Username sent with a token when none is given; GitHub and GitLab accept
+/// any username alongside a personal access token
+const TOKEN_USERNAME: &str = "x-access-token";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StoreCredentialsRequest {
+    /// Host of an HTTPS remote, e.g. `github.com`
+    pub host: String,
+    pub username: Option<String>,
+    /// Personal access token for the host
+    pub token: String,
+}
+
+async fn system_diagnostics(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<SystemDiagnostics>> {
+    ResponseJson(ApiResponse::success(
+        DiagnosticsService::system(&deployment.db().pool).await,
+    ))
+}
+
+/// Repos whose remotes are checked; limited to the user's own projects in
+/// multi-user mode.
+async fn visible_repos(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+) -> Result<Vec<Repo>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(user) = user else {
+        return Ok(Repo::list_all(pool).await?);
+    };
+
+    let mut repos: Vec<Repo> = Vec::new();
+    for project_id in MultiUserService::visible_project_ids(pool, user).await? {
+        for repo in ProjectRepo::find_repos_for_project(pool, project_id).await? {
+            if !repos.iter().any(|r| r.id == repo.id) {
+                repos.push(repo);
+            }
+        }
+    }
+    Ok(repos)
+}
+
+async fn github_diagnostics(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+) -> Result<ResponseJson<ApiResponse<GitHubDiagnostics>>, ApiError> {
+    let repos = visible_repos(&deployment, user.as_deref()).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        DiagnosticsService::github(repos).await,
+    )))
+}
+
+/// Save a token for an HTTPS host through git's credential helpers, for
+/// hosts the diagnostics report without credentials
+async fn store_credentials(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Json(payload): Json<StoreCredentialsRequest>,
+) -> Result<ResponseJson<ApiResponse<HostCredentialStatus>>, ApiError> {
+    // Credential helpers belong to the account the server runs as
+    if user.is_some() {
+        return Err(ApiError::Forbidden(
+            "Git credentials can only be stored in single-user mode".to_string(),
+        ));
+    }
+    let host = payload.host.trim().to_ascii_lowercase();
+    let token = payload.token.trim();
+    if host.is_empty()
+        || host.contains(['\n', '/', '@'])
+        || token.is_empty()
+        || token.contains('\n')
+    {
+        return Err(ApiError::BadRequest(
+            "A host and a single-line token are required".to_string(),
+        ));
+    }
+    let username = payload
+        .username
+        .map(|username| username.trim().to_string())
+        .filter(|username| !username.is_empty() && !username.contains('\n'))
+        .unwrap_or_else(|| TOKEN_USERNAME.to_string());
+
+    let repos = visible_repos(&deployment, None).await?;
+    let status = DiagnosticsService::store_https_credentials(
+        repos,
+        host.clone(),
+        username,
+        token.to_string(),
+    )
+    .await
+    .map_err(GitServiceError::from)?
+    .ok_or_else(|| ApiError::BadRequest(format!("No repository has a remote on {host}")))?;
+    if !status.has_credentials {
+        tracing::warn!(
+            "Stored credentials for {} but git still cannot use them",
+            host
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
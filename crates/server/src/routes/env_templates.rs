@@ -0,0 +1,142 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    env_template::{ProjectEnvTemplate, UpsertProjectEnvTemplate},
+    project::Project,
+    project_repo::ProjectRepo,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    env_templates::{self, EnvTemplateCheck},
+    multi_user::RequestUser,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_single_user, validation::ValidatedJson,
+};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/env-templates",
+            get(list_templates).post(create_template),
+        )
+        .route(
+            "/projects/{project_id}/env-templates/check",
+            post(check_template),
+        )
+        .route(
+            "/projects/{project_id}/env-templates/{template_id}",
+            put(update_template).delete(delete_template),
+        )
+}
+
+/// Templates pull secrets from the instance's secret store into worktrees,
+/// so they are only managed on single-user instances.
+pub(crate) const SINGLE_USER_ONLY: &str = "Env templates are only available in single-user mode";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CheckEnvTemplateRequest {
+    pub content: String,
+}
+
+async fn find_template(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    template_id: Uuid,
+) -> Result<ProjectEnvTemplate, ApiError> {
+    ProjectEnvTemplate::find_by_id(&deployment.db().pool, template_id)
+        .await?
+        .filter(|template| template.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Env template not found".to_string()))
+}
+
+async fn ensure_project_repo(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    if let Some(repo_id) = repo_id
+        && ProjectRepo::find_by_project_and_repo(&deployment.db().pool, project_id, repo_id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository is not part of this project".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn list_templates(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvTemplate>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let templates =
+        ProjectEnvTemplate::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+async fn create_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpsertProjectEnvTemplate>,
+) -> Result<ResponseJson<ApiResponse<ProjectEnvTemplate>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, project_id).await?.is_none() {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    ensure_project_repo(&deployment, project_id, payload.repo_id).await?;
+    let template = ProjectEnvTemplate::create(pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+/// Ports, secrets and unknown placeholders a template refers to
+async fn check_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(_project_id): Path<Uuid>,
+    Json(payload): Json<CheckEnvTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<EnvTemplateCheck>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let check = env_templates::check(deployment.secrets(), &payload.content).await;
+    Ok(ResponseJson(ApiResponse::success(check)))
+}
+
+async fn update_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, template_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpsertProjectEnvTemplate>,
+) -> Result<ResponseJson<ApiResponse<ProjectEnvTemplate>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    find_template(&deployment, project_id, template_id).await?;
+    ensure_project_repo(&deployment, project_id, payload.repo_id).await?;
+    let template = ProjectEnvTemplate::update(&deployment.db().pool, template_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Env template not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+async fn delete_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, template_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    find_template(&deployment, project_id, template_id).await?;
+    ProjectEnvTemplate::delete(&deployment.db().pool, template_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
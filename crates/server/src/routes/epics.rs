@@ -0,0 +1,108 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::epic::{CreateEpic, Epic, UpdateEpic};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    multi_user::RequestUser,
+    swimlanes::{self, BoardLane, LaneGrouping},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_project_access, validation::ValidatedJson,
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct LanesQuery {
+    pub group_by: LaneGrouping,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/epics",
+            get(get_epics).post(create_epic),
+        )
+        .route(
+            "/projects/{project_id}/epics/{epic_id}",
+            put(update_epic).delete(delete_epic),
+        )
+        .route("/projects/{project_id}/lanes", get(get_lanes))
+}
+
+/// The epic, if it belongs to the project
+async fn project_epic(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    epic_id: Uuid,
+) -> Result<Epic, ApiError> {
+    Epic::find_by_id(&deployment.db().pool, epic_id)
+        .await?
+        .filter(|epic| epic.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Epic not found in project".to_string()))
+}
+
+/// Epics of the project in swimlane order
+pub async fn get_epics(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<Epic>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let epics = Epic::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(epics)))
+}
+
+pub async fn create_epic(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateEpic>,
+) -> Result<ResponseJson<ApiResponse<Epic>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let epic = Epic::create(&deployment.db().pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(epic)))
+}
+
+pub async fn update_epic(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, epic_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdateEpic>,
+) -> Result<ResponseJson<ApiResponse<Epic>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_epic(&deployment, project_id, epic_id).await?;
+    let epic = Epic::update(&deployment.db().pool, epic_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(epic)))
+}
+
+/// Delete an epic; its tasks stay, without an epic
+pub async fn delete_epic(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, epic_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_epic(&deployment, project_id, epic_id).await?;
+    Epic::delete(&deployment.db().pool, epic_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// The project's tasks grouped into swimlanes
+pub async fn get_lanes(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<LanesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<BoardLane>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let lanes = swimlanes::lanes(&deployment.db().pool, project_id, query.group_by).await?;
+    Ok(ResponseJson(ApiResponse::success(lanes)))
+}
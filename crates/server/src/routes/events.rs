@@ -1,5 +1,5 @@
 use axum::{
-    BoxError, Router,
+    BoxError, Extension, Router,
     extract::State,
     response::{
         Sse,
@@ -9,13 +9,20 @@ use axum::{
 };
 use deployment::Deployment;
 use futures_util::TryStreamExt;
+use services::services::multi_user::RequestUser;
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
 
+/// The stream carries every project's changes, so it is only served on
+/// single-user instances.
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
-) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
-{
+    user: Option<Extension<RequestUser>>,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, ApiError> {
+    ensure_single_user(
+        user.as_deref(),
+        "The event stream is only available in single-user mode",
+    )?;
     // Ask the container service for a combined "history + live" stream
     let stream = deployment.stream_events().await;
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
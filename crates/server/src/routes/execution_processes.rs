@@ -3,25 +3,36 @@ use axum::{
     Extension, Router,
     extract::{
         Path, Query, State,
-        ws::{WebSocket, WebSocketUpgrade},
+        ws::{Message, WebSocket, WebSocketUpgrade},
     },
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
 use db::models::{
+    execution_log_event::{ExecutionLogEvent, ExecutionLogEventData, ExecutionLogEventKind},
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
+    session::Session,
 };
 use deployment::Deployment;
+use executors::logs::utils::patch::extract_normalized_entry_from_patch;
 use futures_util::TryStreamExt;
-use serde::Deserialize;
-use services::services::container::ContainerService;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    execution_events,
+    multi_user::{MultiUserError, MultiUserService, RequestUser},
+};
+use sqlx::SqlitePool;
+use ts_rs::TS;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_workspace_access, load_execution_process_middleware},
     ws_utils::stream_with_heartbeat,
 };
 
@@ -138,6 +149,118 @@ async fn handle_normalized_logs_ws(
     stream_with_heartbeat(socket, stream).await
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LogEventsQuery {
+    pub kind: Option<ExecutionLogEventKind>,
+}
+
+pub async fn get_log_events(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogEventsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionLogEvent>>>, ApiError> {
+    let events = ExecutionLogEvent::find_by_execution_process_id(
+        &deployment.db().pool,
+        execution_process.id,
+        query.kind,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(events)))
+}
+
+/// Message of the typed log event stream. Events of an entry that changes,
+/// like a tool call finishing, are sent again with the same `entry_index`.
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ExecutionLogEventMessage {
+    Event(ExecutionLogEventData),
+    Finished,
+}
+
+pub async fn stream_log_events_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Path(exec_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stream = deployment
+        .container()
+        .stream_normalized_logs(&exec_id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+
+    let stream = stream
+        .map_ok(|msg| {
+            let messages: Vec<ExecutionLogEventMessage> = match msg {
+                LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(&patch)
+                    .map(|(index, entry)| execution_events::events_from_entry(index, &entry))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(ExecutionLogEventMessage::Event)
+                    .collect(),
+                LogMsg::Finished => vec![ExecutionLogEventMessage::Finished],
+                _ => Vec::new(),
+            };
+            futures_util::stream::iter(messages.into_iter().map(Ok::<_, std::io::Error>))
+        })
+        .try_flatten()
+        .map_ok(|message| {
+            Message::Text(
+                serde_json::to_string(&message)
+                    .unwrap_or_else(|_| r#"{"error":"serialization_failed"}"#.to_string())
+                    .into(),
+            )
+        })
+        .err_into::<anyhow::Error>();
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = stream_with_heartbeat(socket, stream).await {
+            tracing::warn!("log events WS closed: {}", e);
+        }
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchLogEventsQuery {
+    pub workspace_id: Uuid,
+    pub q: String,
+    pub kind: Option<ExecutionLogEventKind>,
+    pub limit: Option<i64>,
+}
+
+/// Search the typed output of every execution of a workspace
+pub async fn search_log_events(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<SearchLogEventsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionLogEvent>>>, ApiError> {
+    let events = search_workspace_events(&deployment.db().pool, user.as_deref(), &query).await?;
+    Ok(ResponseJson(ApiResponse::success(events)))
+}
+
+async fn search_workspace_events(
+    pool: &SqlitePool,
+    user: Option<&RequestUser>,
+    query: &SearchLogEventsQuery,
+) -> Result<Vec<ExecutionLogEvent>, ApiError> {
+    if let Some(user) = user {
+        MultiUserService::ensure_workspace_access(pool, user, query.workspace_id).await?;
+    }
+    if query.q.trim().is_empty() {
+        return Err(ApiError::BadRequest("Search query is empty".to_string()));
+    }
+    Ok(ExecutionLogEvent::search(
+        pool,
+        query.workspace_id,
+        query.q.trim(),
+        query.kind,
+        query.limit.unwrap_or(50).clamp(1, 200),
+    )
+    .await?)
+}
+
 pub async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
@@ -153,9 +276,17 @@ pub async fn stop_execution_process(
 pub async fn stream_execution_processes_by_session_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<SessionExecutionProcessQuery>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async move {
+) -> Result<impl IntoResponse, ApiError> {
+    if user.is_some() {
+        let session = Session::find_by_id(&deployment.db().pool, query.session_id)
+            .await?
+            .ok_or(ApiError::MultiUser(MultiUserError::NotFound))?;
+        ensure_workspace_access(&deployment, user.as_deref(), session.workspace_id).await?;
+    }
+
+    Ok(ws.on_upgrade(move |socket| async move {
         if let Err(e) = handle_execution_processes_by_session_ws(
             socket,
             deployment,
@@ -166,7 +297,7 @@ pub async fn stream_execution_processes_by_session_ws(
         {
             tracing::warn!("execution processes by session WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_execution_processes_by_session_ws(
@@ -203,6 +334,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/repo-states", get(get_execution_process_repo_states))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .route("/events", get(get_log_events))
+        .route("/events/ws", get(stream_log_events_ws))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
@@ -213,7 +346,82 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/stream/session/ws",
             get(stream_execution_processes_by_session_ws),
         )
+        .route("/events/search", get(search_log_events))
         .nest("/{id}", workspace_id_router);
 
     Router::new().nest("/execution-processes", workspaces_router)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use db::{
+        DBService,
+        models::{
+            project::{CreateProject, Project},
+            project_owner::ProjectOwner,
+            task::{CreateTask, Task},
+            workspace::{CreateWorkspace, Workspace},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn search_hides_workspaces_of_other_users() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project = Project::create(
+            &db.pool,
+            &CreateProject {
+                name: "Project".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let owner = RequestUser {
+            user_id: Uuid::new_v4(),
+        };
+        ProjectOwner::assign(&db.pool, project.id, owner.user_id)
+            .await
+            .unwrap();
+        let task = Task::create(
+            &db.pool,
+            &CreateTask::from_title_description(project.id, "Task".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let workspace = Workspace::create(
+            &db.pool,
+            &CreateWorkspace {
+                branch: "vk/test".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        let query = SearchLogEventsQuery {
+            workspace_id: workspace.id,
+            q: "error".to_string(),
+            kind: None,
+            limit: None,
+        };
+
+        let other = RequestUser {
+            user_id: Uuid::new_v4(),
+        };
+        let err = search_workspace_events(&db.pool, Some(&other), &query)
+            .await
+            .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+
+        let events = search_workspace_events(&db.pool, Some(&owner), &query)
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Query, State},
     response::Json as ResponseJson,
     routing::get,
@@ -8,11 +8,16 @@ use deployment::Deployment;
 use serde::Deserialize;
 use services::services::{
     filesystem::{DirectoryEntry, DirectoryListResponse, FilesystemError},
+    multi_user::RequestUser,
     slash_commands::SlashCommandService,
 };
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
+
+/// Browsing reaches the whole host filesystem, so it is only offered on
+/// single-user instances.
+const SINGLE_USER_ONLY: &str = "Browsing the filesystem is only available in single-user mode";
 
 #[derive(Debug, Deserialize)]
 pub struct ListDirectoryQuery {
@@ -21,8 +26,10 @@ pub struct ListDirectoryQuery {
 
 pub async fn list_directory(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<ListDirectoryQuery>,
 ) -> Result<ResponseJson<ApiResponse<DirectoryListResponse>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
     match deployment.filesystem().list_directory(query.path).await {
         Ok(response) => Ok(ResponseJson(ApiResponse::success(response))),
         Err(FilesystemError::DirectoryDoesNotExist) => {
@@ -43,8 +50,10 @@ pub async fn list_directory(
 
 pub async fn list_git_repos(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<ListDirectoryQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<DirectoryEntry>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
     let res = if let Some(ref path) = query.path {
         deployment
             .filesystem()
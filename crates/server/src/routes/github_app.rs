@@ -0,0 +1,113 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::{Response, StatusCode},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    config::save_config_to_file,
+    github_app::{GitHubAppAuth, GitHubAppInstallUrl, GitHubAppStatus},
+};
+use utils::{assets::config_path, response::ApiResponse};
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::oauth::{close_window_response, simple_html_response},
+};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/github-app", get(status).delete(disconnect))
+        .route("/github-app/install", post(install))
+        .route("/github-app/callback", get(callback))
+}
+
+async fn status() -> ResponseJson<ApiResponse<GitHubAppStatus>> {
+    ResponseJson(ApiResponse::success(GitHubAppAuth::global_status().await))
+}
+
+async fn install() -> Result<ResponseJson<ApiResponse<GitHubAppInstallUrl>>, ApiError> {
+    let auth = GitHubAppAuth::global()
+        .ok_or_else(|| ApiError::BadRequest("GitHub App is not configured".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(auth.install_url().await)))
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    installation_id: Option<i64>,
+    setup_action: Option<String>,
+    state: Option<String>,
+}
+
+/// Setup URL GitHub redirects to once the app has been installed
+async fn callback(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CallbackQuery>,
+) -> Result<Response<String>, ApiError> {
+    let Some(auth) = GitHubAppAuth::global() else {
+        return Ok(simple_html_response(
+            StatusCode::BAD_REQUEST,
+            "GitHub App is not configured".to_string(),
+        ));
+    };
+
+    let (Some(installation_id), Some(state)) = (query.installation_id, query.state) else {
+        return Ok(simple_html_response(
+            StatusCode::BAD_REQUEST,
+            "Missing installation_id or state in callback".to_string(),
+        ));
+    };
+
+    let installation = match auth.complete_installation(installation_id, &state).await {
+        Ok(installation) => installation,
+        Err(e) => {
+            tracing::warn!(
+                installation_id,
+                setup_action = ?query.setup_action,
+                "GitHub App installation failed: {}",
+                e
+            );
+            return Ok(simple_html_response(
+                StatusCode::BAD_REQUEST,
+                format!("GitHub App installation failed: {e}"),
+            ));
+        }
+    };
+
+    let mut config = deployment.config().write().await;
+    config.github.app_installation_id = Some(installation_id);
+    save_config_to_file(&config, &config_path()).await?;
+    drop(config);
+
+    tracing::info!(
+        installation_id,
+        account = %installation.account.login,
+        "GitHub App installed"
+    );
+
+    Ok(close_window_response(format!(
+        "GitHub App installed for {}. You can return to the app.",
+        installation.account.login
+    )))
+}
+
+async fn disconnect(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<GitHubAppStatus>>, ApiError> {
+    if let Some(auth) = GitHubAppAuth::global() {
+        auth.clear_installation().await;
+    }
+
+    let mut config = deployment.config().write().await;
+    config.github.app_installation_id = None;
+    save_config_to_file(&config, &config_path()).await?;
+    drop(config);
+
+    Ok(ResponseJson(ApiResponse::success(
+        GitHubAppAuth::global_status().await,
+    )))
+}
@@ -0,0 +1,120 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    lifecycle_hook::{CreateLifecycleHook, HookKind, LifecycleHook, UpdateLifecycleHook},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::multi_user::RequestUser;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
+
+const MAX_TIMEOUT_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct LifecycleHookQuery {
+    /// Hooks that apply to this project (its own and global ones); all hooks when omitted
+    pub project_id: Option<Uuid>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/hooks", get(list_hooks).post(create_hook))
+        .route("/hooks/{hook_id}", put(update_hook).delete(delete_hook))
+}
+
+/// Hooks run arbitrary commands on the server, so they are only managed on
+/// single-user instances.
+const SINGLE_USER_ONLY: &str = "Lifecycle hooks are only available in single-user mode";
+
+fn validate_target(kind: HookKind, target: &str) -> Result<(), ApiError> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err(ApiError::BadRequest("Hook target is empty".to_string()));
+    }
+    if kind == HookKind::Http && !(target.starts_with("http://") || target.starts_with("https://"))
+    {
+        return Err(ApiError::BadRequest(
+            "HTTP hooks need an http:// or https:// URL".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_timeout(timeout_secs: Option<i64>) -> Result<(), ApiError> {
+    match timeout_secs {
+        Some(secs) if !(1..=MAX_TIMEOUT_SECS).contains(&secs) => Err(ApiError::BadRequest(
+            format!("Hook timeout must be between 1 and {MAX_TIMEOUT_SECS} seconds"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn list_hooks(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<LifecycleHookQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LifecycleHook>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let hooks = LifecycleHook::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(hooks)))
+}
+
+async fn create_hook(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Json(payload): Json<CreateLifecycleHook>,
+) -> Result<ResponseJson<ApiResponse<LifecycleHook>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    validate_target(payload.kind, &payload.target)?;
+    validate_timeout(payload.timeout_secs)?;
+    let pool = &deployment.db().pool;
+    if let Some(project_id) = payload.project_id
+        && Project::find_by_id(pool, project_id).await?.is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    let hook = LifecycleHook::create(pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(hook)))
+}
+
+async fn update_hook(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(hook_id): Path<Uuid>,
+    Json(payload): Json<UpdateLifecycleHook>,
+) -> Result<ResponseJson<ApiResponse<LifecycleHook>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let pool = &deployment.db().pool;
+    let hook = LifecycleHook::find_by_id(pool, hook_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Hook not found".to_string()))?;
+    if let Some(target) = &payload.target {
+        validate_target(hook.kind, target)?;
+    }
+    validate_timeout(payload.timeout_secs)?;
+    let hook = LifecycleHook::update(pool, hook_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Hook not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(hook)))
+}
+
+async fn delete_hook(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(hook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    if LifecycleHook::delete(&deployment.db().pool, hook_id).await? == 0 {
+        return Err(ApiError::BadRequest("Hook not found".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
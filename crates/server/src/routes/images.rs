@@ -1,7 +1,7 @@
 use std::path::Path as StdPath;
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     http::{StatusCode, header},
@@ -15,7 +15,10 @@ use db::models::{
 };
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
-use services::services::image::ImageError;
+use services::services::{
+    image::ImageError,
+    multi_user::{MultiUserError, MultiUserService, RequestUser},
+};
 use sqlx::Error as SqlxError;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
@@ -23,7 +26,7 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_project_access};
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ImageResponse {
@@ -72,6 +75,42 @@ pub struct ImageMetadata {
     pub proxy_url: Option<String>,
 }
 
+/// Reject access to another user's task in multi-user mode
+async fn authorize_task(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    task_id: Uuid,
+) -> Result<(), ApiError> {
+    let task = Task::find_by_id(&deployment.db().pool, task_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    ensure_project_access(deployment, user, task.project_id).await
+}
+
+/// In multi-user mode an image attached to tasks is only available to the
+/// owners of their projects. Images not attached yet, such as one uploaded
+/// for a task that is still being created, stay available.
+async fn authorize_image(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    image_id: Uuid,
+) -> Result<(), ApiError> {
+    let Some(user) = user else {
+        return Ok(());
+    };
+    let pool = &deployment.db().pool;
+    let project_ids = TaskImage::find_project_ids_by_image_id(pool, image_id).await?;
+    if project_ids.is_empty() {
+        return Ok(());
+    }
+    let visible = MultiUserService::visible_project_ids(pool, user).await?;
+    if project_ids.iter().any(|id| visible.contains(id)) {
+        Ok(())
+    } else {
+        Err(ApiError::MultiUser(MultiUserError::NotFound))
+    }
+}
+
 pub async fn upload_image(
     State(deployment): State<DeploymentImpl>,
     multipart: Multipart,
@@ -116,11 +155,10 @@ pub(crate) async fn process_image_upload(
 pub async fn upload_task_image(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
-    Task::find_by_id(&deployment.db().pool, task_id)
-        .await?
-        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    authorize_task(&deployment, user.as_deref(), task_id).await?;
 
     let image_response = process_image_upload(&deployment, multipart, Some(task_id)).await?;
     Ok(ResponseJson(ApiResponse::success(image_response)))
@@ -130,7 +168,9 @@ pub async fn upload_task_image(
 pub async fn serve_image(
     Path(image_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<Response, ApiError> {
+    authorize_image(&deployment, user.as_deref(), image_id).await?;
     let image_service = deployment.image();
     let image = image_service
         .get_image(image_id)
@@ -163,7 +203,9 @@ pub async fn serve_image(
 pub async fn delete_image(
     Path(image_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    authorize_image(&deployment, user.as_deref(), image_id).await?;
     let image_service = deployment.image();
     image_service.delete_image(image_id).await?;
     Ok(ResponseJson(ApiResponse::success(())))
@@ -172,7 +214,9 @@ pub async fn delete_image(
 pub async fn get_task_images(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<Vec<ImageResponse>>>, ApiError> {
+    authorize_task(&deployment, user.as_deref(), task_id).await?;
     let images = Image::find_by_task_id(&deployment.db().pool, task_id).await?;
     let image_responses = images.into_iter().map(ImageResponse::from_image).collect();
     Ok(ResponseJson(ApiResponse::success(image_responses)))
@@ -183,8 +227,10 @@ pub async fn get_task_images(
 pub async fn get_task_image_metadata(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<ImageMetadataQuery>,
 ) -> Result<ResponseJson<ApiResponse<ImageMetadata>>, ApiError> {
+    authorize_task(&deployment, user.as_deref(), task_id).await?;
     let not_found_response = || ImageMetadata {
         exists: false,
         file_name: None,
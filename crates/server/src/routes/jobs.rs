@@ -0,0 +1,59 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::job::{Job, JobStatus};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::multi_user::RequestUser;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    pub status: Option<JobStatus>,
+    pub limit: Option<i64>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/jobs", get(list_jobs))
+        .route("/jobs/{job_id}/retry", post(retry_job))
+}
+
+/// Job payloads hold workspace paths of every user, so the queue is only
+/// exposed on single-user instances.
+const SINGLE_USER_ONLY: &str = "The job queue is only available in single-user mode";
+
+async fn list_jobs(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<JobsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Job>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let jobs = deployment.jobs().list(query.status, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(jobs)))
+}
+
+/// Requeue a dead-lettered job with a fresh set of attempts
+async fn retry_job(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Job>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    match deployment.jobs().retry(job_id).await? {
+        Some(job) => Ok(ResponseJson(ApiResponse::success(job))),
+        None => Err(ApiError::Conflict(
+            "Only dead jobs can be retried".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,160 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    milestone::{CreateMilestone, Milestone, MilestoneBurndown, UpdateMilestone},
+    task::Task,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::multi_user::RequestUser;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_project_access, validation::ValidatedJson,
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetMilestoneTasksRequest {
+    pub task_ids: Vec<Uuid>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/milestones",
+            get(get_milestones).post(create_milestone),
+        )
+        .route(
+            "/projects/{project_id}/milestones/{milestone_id}",
+            put(update_milestone).delete(delete_milestone),
+        )
+        .route(
+            "/projects/{project_id}/milestones/{milestone_id}/tasks",
+            get(get_milestone_tasks).put(set_milestone_tasks),
+        )
+        .route(
+            "/projects/{project_id}/milestones/{milestone_id}/burndown",
+            get(get_burndown),
+        )
+}
+
+/// The milestone, if it belongs to the project
+async fn project_milestone(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    milestone_id: Uuid,
+) -> Result<Milestone, ApiError> {
+    Milestone::find_by_id(&deployment.db().pool, milestone_id)
+        .await?
+        .filter(|milestone| milestone.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Milestone not found in project".to_string()))
+}
+
+/// Milestones of the project, soonest due first
+pub async fn get_milestones(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<Milestone>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let milestones = Milestone::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(milestones)))
+}
+
+pub async fn create_milestone(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateMilestone>,
+) -> Result<ResponseJson<ApiResponse<Milestone>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let milestone = Milestone::create(&deployment.db().pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(milestone)))
+}
+
+pub async fn update_milestone(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, milestone_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdateMilestone>,
+) -> Result<ResponseJson<ApiResponse<Milestone>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let existing = project_milestone(&deployment, project_id, milestone_id).await?;
+    let start_date = payload.start_date.or(existing.start_date);
+    let due_date = payload.due_date.or(existing.due_date);
+    if let (Some(start), Some(due)) = (start_date, due_date)
+        && due < start
+    {
+        return Err(ApiError::BadRequest(
+            "Due date must not be before the start date".to_string(),
+        ));
+    }
+    let milestone = Milestone::update(&deployment.db().pool, milestone_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(milestone)))
+}
+
+/// Delete a milestone; its tasks are kept
+pub async fn delete_milestone(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, milestone_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_milestone(&deployment, project_id, milestone_id).await?;
+    Milestone::delete(&deployment.db().pool, milestone_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Ids of the milestone's tasks
+pub async fn get_milestone_tasks(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, milestone_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Vec<Uuid>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_milestone(&deployment, project_id, milestone_id).await?;
+    let task_ids = Milestone::task_ids(&deployment.db().pool, milestone_id).await?;
+    Ok(ResponseJson(ApiResponse::success(task_ids)))
+}
+
+/// Replace the milestone's tasks
+pub async fn set_milestone_tasks(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, milestone_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SetMilestoneTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<Uuid>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_milestone(&deployment, project_id, milestone_id).await?;
+    let pool = &deployment.db().pool;
+    for task_id in &payload.task_ids {
+        let in_project = Task::find_by_id(pool, *task_id)
+            .await?
+            .is_some_and(|task| task.project_id == project_id);
+        if !in_project {
+            return Err(ApiError::BadRequest(format!(
+                "Task {task_id} not found in project"
+            )));
+        }
+    }
+    let task_ids = Milestone::set_tasks(pool, milestone_id, &payload.task_ids).await?;
+    Ok(ResponseJson(ApiResponse::success(task_ids)))
+}
+
+/// Remaining tasks and points per day, for progress charts
+pub async fn get_burndown(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, milestone_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<MilestoneBurndown>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let milestone = project_milestone(&deployment, project_id, milestone_id).await?;
+    let burndown = MilestoneBurndown::collect(&deployment.db().pool, milestone).await?;
+    Ok(ResponseJson(ApiResponse::success(burndown)))
+}
@@ -1,59 +1,117 @@
+use std::net::SocketAddr;
+
 use axum::{
     Router,
-    routing::{IntoMakeService, get},
+    extract::connect_info::IntoMakeServiceWithConnectInfo,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
 };
 use tower_http::validate_request::ValidateRequestHeaderLayer;
 
 use crate::{DeploymentImpl, middleware};
 
+pub mod api_schema;
 pub mod approvals;
+pub mod capabilities;
+pub mod chat_bridge;
 pub mod config;
 pub mod containers;
+pub mod diagnostics;
+pub mod env_templates;
+pub mod epics;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
 pub mod execution_processes;
 pub mod frontend;
+pub mod github_app;
 pub mod health;
+pub mod hooks;
 pub mod images;
+pub mod jobs;
+pub mod milestones;
 pub mod oauth;
 pub mod organizations;
 pub mod pending_commits;
 pub mod projects;
+pub mod prompts;
 pub mod repo;
+pub mod rpc;
 pub mod scratch;
+pub mod scripts;
+pub mod secrets;
 pub mod sessions;
+pub mod share_links;
+pub mod share_rules;
 pub mod shared_tasks;
+pub mod stats;
 pub mod tags;
 pub mod task_attempts;
 pub mod task_labels;
 pub mod tasks;
 pub mod terminal;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl) -> IntoMakeServiceWithConnectInfo<Router, SocketAddr> {
+    // Share links are opened by people without an account and chat webhooks
+    // carry their own signatures, so they skip authentication but are still
+    // rate limited per client
+    let public_routes = share_links::public_router()
+        .merge(chat_bridge::public_router())
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            middleware::rate_limit,
+        ));
+
     // Create routers with different middleware layers
     let base_routes = Router::new()
-        .route("/health", get(health::health_check))
         .merge(config::router())
         .merge(containers::router(&deployment))
+        .merge(diagnostics::router())
         .merge(projects::router(&deployment))
+        .merge(prompts::router())
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(task_labels::routes())
+        .merge(epics::router())
+        .merge(milestones::router())
+        .merge(chat_bridge::router())
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
+        .merge(github_app::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
+        .merge(rpc::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
+        .merge(secrets::router())
+        .merge(hooks::router())
+        .merge(scripts::router())
+        .merge(env_templates::router())
         .merge(pending_commits::router())
         .merge(terminal::router())
+        .merge(share_links::router(&deployment))
+        .merge(share_rules::router(&deployment))
         .merge(shared_tasks::router())
+        .merge(stats::router())
+        .merge(jobs::router())
         .nest("/images", images::routes())
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            middleware::rate_limit,
+        ))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            middleware::require_user,
+        ))
+        .merge(public_routes)
+        .route("/health", get(health::health_check))
+        .route("/capabilities", get(capabilities::get_capabilities))
+        .route("/schema", get(api_schema::get_api_schema))
+        .layer(from_fn(middleware::read_only))
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
         ))
@@ -63,5 +121,5 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
-        .into_make_service()
+        .into_make_service_with_connect_info::<SocketAddr>()
 }
@@ -273,7 +273,7 @@ fn hash_sha256_hex(input: &str) -> String {
     output
 }
 
-fn simple_html_response(status: StatusCode, message: String) -> Response<String> {
+pub(crate) fn simple_html_response(status: StatusCode, message: String) -> Response<String> {
     let body = format!(
         "<!doctype html><html><head><meta charset=\"utf-8\"><title>OAuth</title></head>\
          <body style=\"font-family: sans-serif; margin: 3rem;\"><h1>{}</h1></body></html>",
@@ -286,7 +286,7 @@ fn simple_html_response(status: StatusCode, message: String) -> Response<String>
         .unwrap()
 }
 
-fn close_window_response(message: String) -> Response<String> {
+pub(crate) fn close_window_response(message: String) -> Response<String> {
     let body = format!(
         "<!doctype html>\
          <html>\
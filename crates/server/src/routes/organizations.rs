@@ -3,16 +3,22 @@ use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
     response::Json as ResponseJson,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
 };
+use executors::executors::BaseCodingAgent;
+use serde::Serialize;
+use services::services::org_config::{self, OrgConfigError};
+use ts_rs::TS;
 use utils::{
     api::{
         organizations::{
             AcceptInvitationResponse, CreateInvitationRequest, CreateInvitationResponse,
             CreateOrganizationRequest, CreateOrganizationResponse, GetInvitationResponse,
             GetOrganizationResponse, ListInvitationsResponse, ListMembersResponse,
-            ListOrganizationsResponse, Organization, RevokeInvitationRequest,
-            UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
+            ListOrganizationsResponse, MemberNotificationPreferences, Organization,
+            OrganizationSharedConfig, ResendInvitationRequest, RevokeInvitationRequest,
+            UpdateMemberNotificationPreferencesRequest, UpdateMemberRoleRequest,
+            UpdateMemberRoleResponse, UpdateOrganizationRequest, UpdateSharedConfigRequest,
         },
         projects::RemoteProject,
     },
@@ -20,7 +26,8 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use super::config::add_shared_mcp_servers;
+use crate::{DeploymentImpl, error::ApiError, validation::ValidatedJson};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -42,6 +49,10 @@ pub fn router() -> Router<DeploymentImpl> {
             "/organizations/{org_id}/invitations/revoke",
             post(revoke_invitation),
         )
+        .route(
+            "/organizations/{org_id}/invitations/resend",
+            post(resend_invitation),
+        )
         .route("/invitations/{token}", get(get_invitation))
         .route("/invitations/{token}/accept", post(accept_invitation))
         .route("/organizations/{org_id}/members", get(list_members))
@@ -53,6 +64,22 @@ pub fn router() -> Router<DeploymentImpl> {
             "/organizations/{org_id}/members/{user_id}/role",
             patch(update_member_role),
         )
+        .route(
+            "/organizations/{org_id}/notification-preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route(
+            "/organizations/shared-config",
+            get(get_applied_shared_config).delete(clear_shared_config),
+        )
+        .route(
+            "/organizations/{org_id}/shared-config",
+            put(update_shared_config).delete(delete_shared_config),
+        )
+        .route(
+            "/organizations/{org_id}/shared-config/pull",
+            post(pull_shared_config),
+        )
 }
 
 async fn list_organization_projects(
@@ -89,7 +116,7 @@ async fn get_organization(
 
 async fn create_organization(
     State(deployment): State<DeploymentImpl>,
-    Json(request): Json<CreateOrganizationRequest>,
+    ValidatedJson(request): ValidatedJson<CreateOrganizationRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateOrganizationResponse>>, ApiError> {
     let client = deployment.remote_client()?;
 
@@ -101,7 +128,7 @@ async fn create_organization(
 async fn update_organization(
     State(deployment): State<DeploymentImpl>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdateOrganizationRequest>,
+    ValidatedJson(request): ValidatedJson<UpdateOrganizationRequest>,
 ) -> Result<ResponseJson<ApiResponse<Organization>>, ApiError> {
     let client = deployment.remote_client()?;
 
@@ -124,7 +151,7 @@ async fn delete_organization(
 async fn create_invitation(
     State(deployment): State<DeploymentImpl>,
     Path(org_id): Path<Uuid>,
-    Json(request): Json<CreateInvitationRequest>,
+    ValidatedJson(request): ValidatedJson<CreateInvitationRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateInvitationResponse>>, ApiError> {
     let client = deployment.remote_client()?;
 
@@ -169,6 +196,18 @@ async fn revoke_invitation(
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn resend_invitation(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+    Json(request): Json<ResendInvitationRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateInvitationResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.resend_invitation(org_id, &request).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
 async fn accept_invitation(
     State(deployment): State<DeploymentImpl>,
     Path(invitation_token): Path<String>,
@@ -213,3 +252,105 @@ async fn update_member_role(
 
     Ok(ResponseJson(ApiResponse::success(response)))
 }
+
+async fn get_notification_preferences(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<MemberNotificationPreferences>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.get_notification_preferences(org_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+async fn update_notification_preferences(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+    Json(request): Json<UpdateMemberNotificationPreferencesRequest>,
+) -> Result<ResponseJson<ApiResponse<MemberNotificationPreferences>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client
+        .update_notification_preferences(org_id, &request)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PullSharedConfigResponse {
+    /// `None` when the organization has not published a shared config
+    pub config: Option<OrganizationSharedConfig>,
+    /// Executors that received new MCP servers from the shared config
+    pub mcp_updated: Vec<BaseCodingAgent>,
+}
+
+fn org_config_error(err: OrgConfigError) -> ApiError {
+    match err {
+        OrgConfigError::Remote(e) => ApiError::RemoteClient(e),
+        OrgConfigError::Io(e) => ApiError::Io(e),
+        OrgConfigError::Serde(e) => ApiError::BadRequest(e.to_string()),
+    }
+}
+
+/// Shared config currently applied to this instance
+async fn get_applied_shared_config()
+-> Result<ResponseJson<ApiResponse<Option<OrganizationSharedConfig>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(org_config::current())))
+}
+
+/// Stop applying the organization's shared config
+async fn clear_shared_config() -> Result<StatusCode, ApiError> {
+    org_config::clear().await.map_err(org_config_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Pull an organization's shared config and apply it under the user's settings
+async fn pull_shared_config(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<PullSharedConfigResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let config = org_config::pull(&client, org_id)
+        .await
+        .map_err(org_config_error)?;
+    let mcp_updated = match &config {
+        Some(shared) => add_shared_mcp_servers(&shared.config.mcp_servers).await,
+        None => Vec::new(),
+    };
+
+    Ok(ResponseJson(ApiResponse::success(
+        PullSharedConfigResponse {
+            config,
+            mcp_updated,
+        },
+    )))
+}
+
+async fn update_shared_config(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+    Json(request): Json<UpdateSharedConfigRequest>,
+) -> Result<ResponseJson<ApiResponse<OrganizationSharedConfig>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client
+        .update_organization_shared_config(org_id, &request)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+async fn delete_shared_config(
+    State(deployment): State<DeploymentImpl>,
+    Path(org_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let client = deployment.remote_client()?;
+
+    client.delete_organization_shared_config(org_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use axum::{
-    Json, Router,
+    Extension, Router,
     extract::{Path, State},
     response::Json as ResponseJson,
     routing::{get, post},
@@ -9,12 +9,18 @@ use axum::{
 use db::models::{merge::Merge, pending_commit::PendingCommit};
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::{config::GitAutoPushMode, git::GitCli};
+use services::services::{config::GitAutoPushMode, git::GitCli, multi_user::RequestUser};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    response::ApiResponse,
+    validation::{Validate, ValidationErrors},
+};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_workspace_access,
+    routes::task_attempts::util, validation::ValidatedJson,
+};
 
 /// request para ejecutar un pending commit con título personalizado
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -22,45 +28,63 @@ pub struct CommitPendingRequest {
     pub title: String,
 }
 
+impl Validate for CommitPendingRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("title", self.title.trim(), 500);
+        errors.into_result()
+    }
+}
+
+/// pending commits visibles: en modo multi-usuario solo los de proyectos propios
+async fn visible_pending_commits(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+) -> Result<Vec<PendingCommit>, ApiError> {
+    let pool = &deployment.db().pool;
+    Ok(match user {
+        Some(user) => PendingCommit::find_by_owner(pool, user.user_id).await?,
+        None => PendingCommit::find_all(pool).await?,
+    })
+}
+
 /// obtener todos los pending commits
 pub async fn get_pending_commits(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<Vec<PendingCommit>>>, ApiError> {
-    let pending_commits = PendingCommit::find_all(&deployment.db().pool).await?;
+    let pending_commits = visible_pending_commits(&deployment, user.as_deref()).await?;
     Ok(ResponseJson(ApiResponse::success(pending_commits)))
 }
 
 /// obtener el conteo de pending commits
 pub async fn get_pending_commits_count(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<i64>>, ApiError> {
-    let count = PendingCommit::count(&deployment.db().pool).await?;
+    let count = match user.as_deref() {
+        Some(user) => visible_pending_commits(&deployment, Some(user))
+            .await?
+            .len() as i64,
+        None => PendingCommit::count(&deployment.db().pool).await?,
+    };
     Ok(ResponseJson(ApiResponse::success(count)))
 }
 
 /// ejecutar un pending commit con el título proporcionado por el usuario
 pub async fn commit_pending(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(pending_commit_id): Path<Uuid>,
-    Json(payload): Json<CommitPendingRequest>,
+    ValidatedJson(payload): ValidatedJson<CommitPendingRequest>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
-    // validar el título del commit
     let title = payload.title.trim();
-    if title.is_empty() {
-        return Err(ApiError::BadRequest(
-            "Commit title cannot be empty".to_string(),
-        ));
-    }
-    if title.len() > 500 {
-        return Err(ApiError::BadRequest(
-            "Commit title too long (max 500 characters)".to_string(),
-        ));
-    }
 
     // obtener el pending commit
     let pending_commit = PendingCommit::find_by_id(&deployment.db().pool, pending_commit_id)
         .await?
         .ok_or(ApiError::BadRequest("Pending commit not found".to_string()))?;
+    ensure_workspace_access(&deployment, user.as_deref(), pending_commit.workspace_id).await?;
 
     // obtener el workspace para acceder al container_ref
     let workspace = db::models::workspace::Workspace::find_by_id(
@@ -128,9 +152,21 @@ pub async fn commit_pending(
                 branch_name,
                 workspace.id
             );
-            if let Err(e) = deployment
-                .git()
-                .push_to_remote(&worktree_path, &branch_name, false)
+            if let Err(e) = util::run_pre_push_hooks(
+                &deployment.db().pool,
+                &workspace,
+                pending_commit.repo_id,
+                &worktree_path,
+                &branch_name,
+                false,
+            )
+            .await
+            {
+                tracing::warn!("Auto-push skipped after manual commit: {}", e);
+            } else if let Err(e) =
+                deployment
+                    .git()
+                    .push_to_remote(&worktree_path, &branch_name, false)
             {
                 tracing::warn!("Auto-push failed after manual commit: {}", e);
                 // no retornamos error - el commit fue exitoso, solo el push falló
@@ -146,8 +182,15 @@ pub async fn commit_pending(
 /// descartar un pending commit sin ejecutar
 pub async fn discard_pending(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(pending_commit_id): Path<Uuid>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if let Some(user) = user.as_deref()
+        && let Some(pending_commit) =
+            PendingCommit::find_by_id(&deployment.db().pool, pending_commit_id).await?
+    {
+        ensure_workspace_access(&deployment, Some(user), pending_commit.workspace_id).await?;
+    }
     let rows_affected = PendingCommit::delete(&deployment.db().pool, pending_commit_id).await?;
     if rows_affected == 0 {
         Err(ApiError::BadRequest("Pending commit not found".to_string()))
@@ -160,8 +203,20 @@ pub async fn discard_pending(
 /// descartar todos los pending commits
 pub async fn discard_all_pending(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<u64>>, ApiError> {
-    let total_deleted = PendingCommit::delete_all(&deployment.db().pool).await?;
+    let pool = &deployment.db().pool;
+    let total_deleted = match user.as_deref() {
+        // en modo multi-usuario solo se descartan los de proyectos propios
+        Some(user) => {
+            let mut deleted = 0;
+            for pending_commit in visible_pending_commits(&deployment, Some(user)).await? {
+                deleted += PendingCommit::delete(pool, pending_commit.id).await?;
+            }
+            deleted
+        }
+        None => PendingCommit::delete_all(pool).await?,
+    };
 
     tracing::info!("Discarded {} pending commits", total_deleted);
     Ok(ResponseJson(ApiResponse::success(total_deleted)))
@@ -4,7 +4,7 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Path, Query, State,
+        DefaultBodyLimit, Multipart, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -12,27 +12,58 @@ use axum::{
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
 use db::models::{
-    project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    agent_instructions::{AgentInstructionFile, ProjectAgentInstructions},
+    e2e_test::{ProjectE2eSettings, UpsertProjectE2eSettings},
+    execution_window::ExecutionWindow,
+    preview::{ProjectPreviewSettings, UpsertProjectPreviewSettings},
+    project::{
+        CreateProject, Project, ProjectBranchCleanup, ProjectError, ProjectExecutorDefaults,
+        SearchResult, UpdateProject,
+    },
+    project_budget::{BudgetStatus, ProjectBudget},
     project_repo::{CreateProjectRepo, ProjectRepo},
+    project_report::ProjectActivity,
     repo::Repo,
+    task::{CreateTask, Task},
+    task_attachment::TaskAttachment,
+    task_planning::TaskPlanning,
+    velocity::{ProjectForecast, ProjectVelocity, RemainingWork},
 };
 use deployment::Deployment;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
 use futures_util::TryStreamExt;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::{
-    file_search::SearchQuery, git::GitRemote, project::ProjectServiceError,
+    agent_instructions::{self, InstructionFileStatus},
+    attachments, diff_ignore,
+    file_search::SearchQuery,
+    git::GitRemote,
+    multi_user::{MultiUserService, RequestUser},
+    project::ProjectServiceError,
+    project_report,
+    project_template::{self, CreateProjectFromTemplate, ProjectFromTemplate},
+    quick_add::{self, QuickAddParse},
+    release_notes::{self, DraftReleaseRequest, ReleaseDraft},
     remote_client::CreateRemoteProjectPayload,
+    sensitive_files::{ProjectSensitivePatterns, SensitiveFileRules},
+    task_knowledge::{self, SimilarTask},
+    transcription::{self, Transcriber, TranscriptionError},
 };
 use ts_rs::TS;
 use utils::{
     api::projects::{RemoteProject, RemoteProjectMembersResponse},
     response::ApiResponse,
+    validation::{MAX_TITLE_CHARS, Validate, ValidationErrors},
 };
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_project_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, load_project_middleware},
+    validation::ValidatedJson,
     ws_utils::stream_with_heartbeat,
 };
 
@@ -47,28 +78,54 @@ pub struct CreateRemoteProjectRequest {
     pub name: String,
 }
 
+#[derive(Deserialize, TS)]
+pub struct PublishProjectRequest {
+    pub organization_id: Uuid,
+    /// Name of the remote project; defaults to the local project's name
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PublishProjectResponse {
+    pub project: Project,
+    pub tags_published: usize,
+    pub tasks_published: usize,
+}
+
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
-    let projects = Project::find_all(&deployment.db().pool).await?;
+    let pool = &deployment.db().pool;
+    let mut projects = Project::find_all(pool).await?;
+    if let Some(Extension(user)) = user {
+        let visible = MultiUserService::visible_project_ids(pool, &user).await?;
+        projects.retain(|project| visible.contains(&project.id));
+    }
     Ok(ResponseJson(ApiResponse::success(projects)))
 }
 
 pub async fn stream_projects_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> impl IntoResponse {
+    let user = user.map(|Extension(user)| user);
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_projects_ws(socket, deployment).await {
+        if let Err(e) = handle_projects_ws(socket, deployment, user).await {
             tracing::warn!("projects WS closed: {}", e);
         }
     })
 }
 
-async fn handle_projects_ws(socket: WebSocket, deployment: DeploymentImpl) -> anyhow::Result<()> {
+async fn handle_projects_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    user: Option<RequestUser>,
+) -> anyhow::Result<()> {
     let stream = deployment
         .events()
-        .stream_projects_raw()
+        .stream_projects_raw(user.map(|user| user.user_id))
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked())
         .map_err(|e| anyhow::anyhow!("{}", e));
@@ -123,6 +180,57 @@ pub async fn create_and_link_remote_project(
     Ok(ResponseJson(ApiResponse::success(updated_project)))
 }
 
+/// Create a remote project from a local one and push its labels and open
+/// tasks, so an existing board can be shared without recreating it
+pub async fn publish_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PublishProjectRequest>,
+) -> Result<ResponseJson<ApiResponse<PublishProjectResponse>>, ApiError> {
+    if project.remote_project_id.is_some() {
+        return Err(ApiError::Conflict(
+            "Project is already linked to a remote project. Unlink it first.".to_string(),
+        ));
+    }
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&project.name)
+        .to_string();
+
+    let client = deployment.remote_client()?;
+    let publisher = deployment.share_publisher()?;
+
+    let remote_project = client
+        .create_project(&CreateRemoteProjectPayload {
+            organization_id: payload.organization_id,
+            name,
+            metadata: None,
+        })
+        .await?;
+    let remote_project_id = remote_project.id;
+    let project = apply_remote_project_link(&deployment, project, remote_project).await?;
+
+    let published = publisher
+        .publish_project_contents(project.id, remote_project_id)
+        .await?;
+    tracing::info!(
+        "Published project {} as remote project {} with {} tags and {} tasks",
+        project.id,
+        remote_project_id,
+        published.tags_published,
+        published.tasks_published
+    );
+
+    Ok(ResponseJson(ApiResponse::success(PublishProjectResponse {
+        project,
+        tags_published: published.tags_published,
+        tasks_published: published.tasks_published,
+    })))
+}
+
 pub async fn unlink_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -206,7 +314,8 @@ pub async fn get_project_remotes(
 
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateProject>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
     tracing::debug!("Creating project '{}'", payload.name);
 
@@ -215,7 +324,12 @@ pub async fn create_project(
         .create_project(&deployment.db().pool, deployment.repo(), payload)
         .await
     {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
+        Ok(project) => {
+            if let Some(Extension(user)) = user {
+                MultiUserService::assign_project(&deployment.db().pool, &user, project.id).await?;
+            }
+            Ok(ResponseJson(ApiResponse::success(project)))
+        }
         Err(ProjectServiceError::DuplicateGitRepoPath) => Ok(ResponseJson(ApiResponse::error(
             "Duplicate repository path provided",
         ))),
@@ -235,10 +349,32 @@ pub async fn create_project(
     }
 }
 
+/// Create a new repo from a template and a project around it, seeded with
+/// the template's starter tasks
+pub async fn create_project_from_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateProjectFromTemplate>,
+) -> Result<ResponseJson<ApiResponse<ProjectFromTemplate>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let created = project_template::create_project(
+        pool,
+        deployment.project(),
+        deployment.repo(),
+        deployment.git(),
+        &payload,
+    )
+    .await?;
+    if let Some(Extension(user)) = user {
+        MultiUserService::assign_project(pool, &user, created.project.id).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<UpdateProject>,
+    ValidatedJson(payload): ValidatedJson<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
     match deployment
         .project()
@@ -406,6 +542,708 @@ pub async fn search_project_files(
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct ProjectReportQuery {
+    /// Start of the period; defaults to 24 hours ago
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, serde::Serialize, TS)]
+pub struct ProjectReport {
+    pub activity: ProjectActivity,
+    /// Ready-to-paste Markdown version of `activity`
+    pub markdown: String,
+}
+
+pub async fn get_project_report(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ProjectReportQuery>,
+) -> Result<ResponseJson<ApiResponse<ProjectReport>>, ApiError> {
+    let since = query
+        .since
+        .unwrap_or_else(|| Utc::now() - Duration::hours(24));
+    let activity = ProjectActivity::collect(&deployment.db().pool, project.id, since).await?;
+    let markdown = project_report::render_markdown(&project.name, &activity);
+    Ok(ResponseJson(ApiResponse::success(ProjectReport {
+        activity,
+        markdown,
+    })))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct VelocityQuery {
+    /// Length of a window in days; defaults to 7
+    pub window_days: Option<u32>,
+    /// Number of windows ending now; defaults to 4
+    pub windows: Option<u32>,
+}
+
+impl VelocityQuery {
+    async fn collect(
+        &self,
+        pool: &sqlx::SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProjectVelocity, sqlx::Error> {
+        let window_days = self.window_days.unwrap_or(7).clamp(1, 90);
+        let windows = self.windows.unwrap_or(4).clamp(1, 26);
+        ProjectVelocity::collect(pool, project_id, window_days, windows).await
+    }
+}
+
+pub async fn get_project_velocity(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<VelocityQuery>,
+) -> Result<ResponseJson<ApiResponse<ProjectVelocity>>, ApiError> {
+    let velocity = query.collect(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(velocity)))
+}
+
+/// Expected completion dates of the open tasks at the current velocity
+pub async fn get_project_forecast(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<VelocityQuery>,
+) -> Result<ResponseJson<ApiResponse<ProjectForecast>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let velocity = query.collect(pool, project.id).await?;
+    let remaining = RemainingWork::collect(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(ProjectForecast::new(
+        velocity, remaining,
+    ))))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct QuickAddTaskRequest {
+    /// One line such as `Fix login bug #bug !high @claude-code ^friday`
+    pub text: String,
+    /// The client's local date, for relative due dates; the server's when
+    /// omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub today: Option<NaiveDate>,
+}
+
+impl Validate for QuickAddTaskRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("text", &self.text, MAX_TITLE_CHARS);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, serde::Serialize, TS)]
+pub struct QuickAddTaskResponse {
+    pub task: Task,
+    pub parsed: QuickAddParse,
+    pub planning: Option<TaskPlanning>,
+}
+
+/// Create a task from one line of text with inline labels, priority,
+/// executor and due date
+pub async fn quick_add_task(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<QuickAddTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<QuickAddTaskResponse>>, ApiError> {
+    let today = payload.today.unwrap_or_else(|| Local::now().date_naive());
+    let parsed = quick_add::parse(&payload.text, today);
+    parsed.validate().map_err(ApiError::Validation)?;
+    let (task, planning) =
+        quick_add::create_task(&deployment.db().pool, project.id, &parsed).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": project.id,
+                "quick_add": true,
+                "label_count": parsed.labels.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(QuickAddTaskResponse {
+        task,
+        parsed,
+        planning,
+    })))
+}
+
+#[derive(Debug, serde::Serialize, TS)]
+pub struct VoiceNoteTask {
+    pub task: Task,
+    pub attachment: TaskAttachment,
+}
+
+/// Create a task from a recorded voice note: the transcript becomes the
+/// description and the audio is kept as an attachment
+pub async fn create_task_from_voice_note(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    mut multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<VoiceNoteTask>>, ApiError> {
+    let transcriber = Transcriber::from_config(&*deployment.config().read().await)
+        .ok_or(TranscriptionError::Disabled)?;
+
+    let mut audio = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("audio") {
+            let file_name = field
+                .file_name()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "voice-note.webm".to_string());
+            audio = Some((file_name, field.bytes().await?));
+            break;
+        }
+    }
+    let (file_name, data) =
+        audio.ok_or_else(|| ApiError::BadRequest("Missing 'audio' field".to_string()))?;
+
+    let transcript = transcriber.transcribe(&data, &file_name).await?;
+    let pool = &deployment.db().pool;
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            project.id,
+            transcription::title_from_transcript(&transcript),
+            Some(transcript),
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+    let attachment = attachments::store(
+        pool,
+        task.id,
+        &data,
+        &file_name,
+        transcription::audio_mime_type(&file_name).map(str::to_string),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": project.id,
+                "voice_note": true,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(VoiceNoteTask {
+        task,
+        attachment,
+    })))
+}
+
+/// Release notes for the tasks merged into one of the project's repos since
+/// its latest tag, optionally published as a GitHub draft release
+pub async fn draft_release(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<DraftReleaseRequest>,
+) -> Result<ResponseJson<ApiResponse<ReleaseDraft>>, ApiError> {
+    let (_, repo) = find_project_repo(&deployment, project.id, payload.repo_id).await?;
+    let draft =
+        release_notes::draft_release(&deployment.db().pool, project.id, &repo, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(draft)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SimilarTasksQuery {
+    pub title: String,
+    pub description: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Completed tasks resembling a task that is still being written
+pub async fn get_similar_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SimilarTasksQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SimilarTask>>>, ApiError> {
+    if query.title.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    }
+    let embedder = task_knowledge::embedder(&*deployment.config().read().await);
+    let similar = task_knowledge::similar(
+        &deployment.db().pool,
+        &embedder,
+        project.id,
+        &query.title,
+        query.description.as_deref(),
+        None,
+        query
+            .limit
+            .unwrap_or(task_knowledge::DEFAULT_LIMIT)
+            .clamp(1, 20),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(similar)))
+}
+
+#[derive(Debug, serde::Serialize, Deserialize, TS)]
+pub struct DependencyAllowlist {
+    /// Dependency names agents may add; a trailing `*` matches a prefix.
+    /// `None` turns the check off.
+    pub allowlist: Option<Vec<String>>,
+}
+
+pub async fn get_dependency_allowlist(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DependencyAllowlist>>, ApiError> {
+    let allowlist = Project::find_dependency_allowlist(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DependencyAllowlist {
+        allowlist,
+    })))
+}
+
+pub async fn update_dependency_allowlist(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DependencyAllowlist>,
+) -> Result<ResponseJson<ApiResponse<DependencyAllowlist>>, ApiError> {
+    let allowlist = payload.allowlist.map(|entries| {
+        entries
+            .into_iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect::<Vec<_>>()
+    });
+    Project::update_dependency_allowlist(&deployment.db().pool, project.id, allowlist.as_deref())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(DependencyAllowlist {
+        allowlist,
+    })))
+}
+
+#[derive(Debug, serde::Serialize, Deserialize, TS)]
+pub struct DiffIgnorePatterns {
+    /// `.gitignore`-style patterns left out of attempt diffs and agent context
+    pub patterns: Vec<String>,
+}
+
+pub async fn get_diff_ignore_patterns(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DiffIgnorePatterns>>, ApiError> {
+    let patterns = Project::find_diff_ignore_patterns(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DiffIgnorePatterns {
+        patterns,
+    })))
+}
+
+pub async fn update_diff_ignore_patterns(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DiffIgnorePatterns>,
+) -> Result<ResponseJson<ApiResponse<DiffIgnorePatterns>>, ApiError> {
+    let patterns: Vec<String> = payload
+        .patterns
+        .into_iter()
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+    diff_ignore::validate_patterns(&patterns).map_err(ApiError::BadRequest)?;
+    Project::update_diff_ignore_patterns(&deployment.db().pool, project.id, &patterns).await?;
+    Ok(ResponseJson(ApiResponse::success(DiffIgnorePatterns {
+        patterns,
+    })))
+}
+
+pub async fn get_sensitive_patterns(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectSensitivePatterns>>, ApiError> {
+    let (added, removed) =
+        Project::find_sensitive_patterns(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        ProjectSensitivePatterns { added, removed },
+    )))
+}
+
+pub async fn update_sensitive_patterns(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectSensitivePatterns>,
+) -> Result<ResponseJson<ApiResponse<ProjectSensitivePatterns>>, ApiError> {
+    let clean = |patterns: Vec<String>| -> Vec<String> {
+        patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    };
+    let patterns = ProjectSensitivePatterns {
+        added: clean(payload.added),
+        removed: clean(payload.removed),
+    };
+    diff_ignore::validate_patterns(&patterns.added).map_err(ApiError::BadRequest)?;
+    Project::update_sensitive_patterns(
+        &deployment.db().pool,
+        project.id,
+        &patterns.added,
+        &patterns.removed,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(patterns)))
+}
+
+pub async fn get_executor_defaults(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectExecutorDefaults>>, ApiError> {
+    let defaults = Project::find_executor_defaults(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(defaults)))
+}
+
+pub async fn update_executor_defaults(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(mut payload): ValidatedJson<ProjectExecutorDefaults>,
+) -> Result<ResponseJson<ApiResponse<ProjectExecutorDefaults>>, ApiError> {
+    let clean = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let profiles = ExecutorConfigs::get_cached();
+    for entry in &mut payload.executors {
+        entry.variant = clean(entry.variant.take());
+        entry.model = clean(entry.model.take());
+        let profile_id = ExecutorProfileId {
+            executor: entry.executor,
+            variant: entry.variant.clone(),
+        };
+        if profiles.get_coding_agent(&profile_id).is_none() {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown executor profile: {profile_id}"
+            )));
+        }
+    }
+    Project::update_executor_defaults(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+pub async fn get_branch_cleanup(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectBranchCleanup>>, ApiError> {
+    let cleanup = Project::find_branch_cleanup(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(cleanup)))
+}
+
+pub async fn update_branch_cleanup(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectBranchCleanup>,
+) -> Result<ResponseJson<ApiResponse<ProjectBranchCleanup>>, ApiError> {
+    Project::update_branch_cleanup(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateExecutionWindowRequest {
+    /// `null` lets attempts start at any time
+    pub window: Option<ExecutionWindow>,
+}
+
+impl Validate for UpdateExecutionWindowRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(window) = &self.window {
+            errors.nested("window", window.validate());
+        }
+        errors.into_result()
+    }
+}
+
+pub async fn get_execution_window(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionWindow>>>, ApiError> {
+    let window = Project::find_execution_window(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(window)))
+}
+
+/// Set the hours new attempts may start in. Attempts already waiting for the
+/// window follow the new one from the next check.
+pub async fn update_execution_window(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpdateExecutionWindowRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionWindow>>>, ApiError> {
+    Project::update_execution_window(&deployment.db().pool, project.id, payload.window.as_ref())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(payload.window)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectBudgetRequest {
+    /// `null` removes the limit
+    pub budget: Option<ProjectBudget>,
+}
+
+impl Validate for UpdateProjectBudgetRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(budget) = &self.budget {
+            errors.nested("budget", budget.validate());
+        }
+        errors.into_result()
+    }
+}
+
+pub async fn get_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBudget>>>, ApiError> {
+    let budget = Project::find_budget(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn update_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpdateProjectBudgetRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectBudget>>>, ApiError> {
+    Project::update_budget(&deployment.db().pool, project.id, payload.budget.as_ref()).await?;
+    Ok(ResponseJson(ApiResponse::success(payload.budget)))
+}
+
+/// This month's spending against the budget; `null` if the project has none
+pub async fn get_budget_status(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<BudgetStatus>>>, ApiError> {
+    let status = ProjectBudget::find_status(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn get_preview_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectPreviewSettings>>>, ApiError> {
+    let settings =
+        ProjectPreviewSettings::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_preview_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpsertProjectPreviewSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectPreviewSettings>>, ApiError> {
+    let settings =
+        ProjectPreviewSettings::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Stop capturing previews for the project
+pub async fn delete_preview_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectPreviewSettings::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_e2e_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectE2eSettings>>>, ApiError> {
+    let settings =
+        ProjectE2eSettings::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_e2e_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpsertProjectE2eSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectE2eSettings>>, ApiError> {
+    let settings = ProjectE2eSettings::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Stop running browser tests after the project's executions
+pub async fn delete_e2e_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectE2eSettings::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateAgentInstructionsRequest {
+    /// `null` stops managing the repos' instruction files
+    pub instructions: Option<ProjectAgentInstructions>,
+}
+
+impl Validate for UpdateAgentInstructionsRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(instructions) = &self.instructions {
+            errors.nested("instructions", instructions.validate());
+        }
+        errors.into_result()
+    }
+}
+
+pub async fn get_agent_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectAgentInstructions>>>, ApiError> {
+    let instructions = Project::find_agent_instructions(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(instructions)))
+}
+
+pub async fn update_agent_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpdateAgentInstructionsRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectAgentInstructions>>>, ApiError> {
+    Project::update_agent_instructions(
+        &deployment.db().pool,
+        project.id,
+        payload.instructions.as_ref(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(payload.instructions)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct WriteInstructionFileRequest {
+    pub file: AgentInstructionFile,
+    pub content: String,
+}
+
+/// The project and one of its repos. Handlers outside the project router
+/// check access to the project first.
+async fn find_project_repo(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_id: Uuid,
+) -> Result<(Project, Repo), ApiError> {
+    let pool = &deployment.db().pool;
+    let project = Project::find_by_id(pool, project_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Project not found".to_string()))?;
+    if ProjectRepo::find_by_project_and_repo(pool, project_id, repo_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository not found in project".to_string(),
+        ));
+    }
+    let repo = Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Repository not found".to_string()))?;
+    Ok((project, repo))
+}
+
+async fn repo_instruction_files(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    repo: &Repo,
+) -> Result<Vec<InstructionFileStatus>, ApiError> {
+    let instructions = Project::find_agent_instructions(&deployment.db().pool, project.id).await?;
+    let files = agent_instructions::read_files(
+        &repo.path,
+        instructions.as_ref(),
+        &project.name,
+        &repo.name,
+    )
+    .await?;
+    Ok(files)
+}
+
+/// Instruction files in the registered repo checkout
+pub async fn get_repo_agent_instructions(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Vec<InstructionFileStatus>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let (project, repo) = find_project_repo(&deployment, project_id, repo_id).await?;
+    let files = repo_instruction_files(&deployment, &project, &repo).await?;
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+pub async fn write_repo_agent_instructions(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<WriteInstructionFileRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<InstructionFileStatus>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let (project, repo) = find_project_repo(&deployment, project_id, repo_id).await?;
+    agent_instructions::write_file(&repo.path, payload.file, &payload.content).await?;
+    let files = repo_instruction_files(&deployment, &project, &repo).await?;
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+/// Render the project's instructions into the registered repo checkout
+pub async fn apply_repo_agent_instructions(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Vec<InstructionFileStatus>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let (project, repo) = find_project_repo(&deployment, project_id, repo_id).await?;
+    let instructions = Project::find_agent_instructions(&deployment.db().pool, project.id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("The project has no agent instructions".to_string()))?;
+    agent_instructions::apply(&repo.path, &instructions, &project.name, &repo.name).await?;
+    let files = repo_instruction_files(&deployment, &project, &repo).await?;
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SensitivePathTestRequest {
+    /// Repo-relative path to check
+    pub path: String,
+}
+
+#[derive(Debug, serde::Serialize, TS)]
+pub struct SensitivePathTestResult {
+    pub sensitive: bool,
+    /// Pattern that matched, if any
+    pub pattern: Option<String>,
+    /// Global patterns with the project's changes applied
+    pub effective_patterns: Vec<String>,
+}
+
+/// Check a path against the project's effective sensitive file rules
+pub async fn test_sensitive_path(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SensitivePathTestRequest>,
+) -> Result<ResponseJson<ApiResponse<SensitivePathTestResult>>, ApiError> {
+    let global = deployment
+        .config()
+        .read()
+        .await
+        .sensitive_file_patterns
+        .clone();
+    let rules = SensitiveFileRules::for_project(&deployment.db().pool, &global, project.id).await?;
+    let pattern = rules.matching_pattern(&payload.path);
+    Ok(ResponseJson(ApiResponse::success(
+        SensitivePathTestResult {
+            sensitive: pattern.is_some(),
+            pattern,
+            effective_patterns: rules.patterns().to_vec(),
+        },
+    )))
+}
+
 pub async fn get_project_repositories(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -539,6 +1377,60 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/remote/members", get(get_project_remote_members))
         .route("/remotes", get(get_project_remotes))
         .route("/search", get(search_project_files))
+        .route("/report", get(get_project_report))
+        .route("/velocity", get(get_project_velocity))
+        .route("/forecast", get(get_project_forecast))
+        .route("/release-draft", post(draft_release))
+        .route("/similar-tasks", get(get_similar_tasks))
+        .route("/tasks/quick-add", post(quick_add_task))
+        .route(
+            "/tasks/voice",
+            post(create_task_from_voice_note)
+                .layer(DefaultBodyLimit::max(transcription::MAX_AUDIO_BYTES)),
+        )
+        .route(
+            "/dependency-allowlist",
+            get(get_dependency_allowlist).put(update_dependency_allowlist),
+        )
+        .route(
+            "/diff-ignore",
+            get(get_diff_ignore_patterns).put(update_diff_ignore_patterns),
+        )
+        .route(
+            "/sensitive-files",
+            get(get_sensitive_patterns).put(update_sensitive_patterns),
+        )
+        .route("/sensitive-files/test", post(test_sensitive_path))
+        .route(
+            "/executor-defaults",
+            get(get_executor_defaults).put(update_executor_defaults),
+        )
+        .route(
+            "/branch-cleanup",
+            get(get_branch_cleanup).put(update_branch_cleanup),
+        )
+        .route(
+            "/execution-window",
+            get(get_execution_window).put(update_execution_window),
+        )
+        .route("/budget", get(get_budget).put(update_budget))
+        .route("/budget/status", get(get_budget_status))
+        .route(
+            "/preview",
+            get(get_preview_settings)
+                .put(update_preview_settings)
+                .delete(delete_preview_settings),
+        )
+        .route(
+            "/e2e",
+            get(get_e2e_settings)
+                .put(update_e2e_settings)
+                .delete(delete_e2e_settings),
+        )
+        .route(
+            "/agent-instructions",
+            get(get_agent_instructions).put(update_agent_instructions),
+        )
         .route("/open-editor", post(open_project_in_editor))
         .route("/open-terminal", post(open_project_in_terminal))
         .route(
@@ -546,6 +1438,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             post(link_project_to_existing_remote).delete(unlink_project),
         )
         .route("/link/create", post(create_and_link_remote_project))
+        .route("/publish", post(publish_project))
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
@@ -557,10 +1450,19 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let projects_router = Router::new()
         .route("/", get(get_projects).post(create_project))
+        .route("/from-template", post(create_project_from_template))
         .route(
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository).delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/repositories/{repo_id}/agent-instructions",
+            get(get_repo_agent_instructions).put(write_repo_agent_instructions),
+        )
+        .route(
+            "/{project_id}/repositories/{repo_id}/agent-instructions/apply",
+            post(apply_repo_agent_instructions),
+        )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::prompt_template::{
+    CreatePromptTemplate, PromptTemplate, PromptTemplateVersion, UpdatePromptTemplate,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    multi_user::RequestUser,
+    prompt_library::{self, RenderedPrompt},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_project_access, validation::ValidatedJson,
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PromptTemplateQuery {
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PreviewPromptRequest {
+    /// Unsaved content to preview; takes precedence over `template_id`
+    pub content: Option<String>,
+    pub template_id: Option<Uuid>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/prompts", get(list_templates).post(create_template))
+        .route("/prompts/preview", post(preview_template))
+        .route(
+            "/prompts/{id}",
+            get(get_template)
+                .put(update_template)
+                .delete(delete_template),
+        )
+        .route("/prompts/{id}/versions", get(list_versions))
+        .route(
+            "/prompts/{id}/versions/{version}/restore",
+            post(restore_version),
+        )
+}
+
+/// Load a template, checking project access for project-scoped templates
+async fn load_template(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    id: Uuid,
+) -> Result<PromptTemplate, ApiError> {
+    let template = PromptTemplate::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    if let Some(project_id) = template.project_id {
+        ensure_project_access(deployment, user, project_id).await?;
+    }
+    Ok(template)
+}
+
+async fn list_templates(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<PromptTemplateQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<PromptTemplate>>>, ApiError> {
+    if let Some(project_id) = query.project_id {
+        ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    }
+    let templates = PromptTemplate::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+async fn create_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreatePromptTemplate>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplate>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Prompt template name cannot be empty".to_string(),
+        ));
+    }
+    if let Some(project_id) = payload.project_id {
+        ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    }
+    let pool = &deployment.db().pool;
+    if let Some(existing) =
+        PromptTemplate::find_effective(pool, &payload.name, payload.project_id).await?
+        && existing.project_id == payload.project_id
+    {
+        return Err(ApiError::Conflict(format!(
+            "A prompt template named '{}' already exists",
+            existing.name
+        )));
+    }
+    let template = PromptTemplate::create(pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+async fn get_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplate>>, ApiError> {
+    let template = load_template(&deployment, user.as_deref(), id).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+async fn update_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdatePromptTemplate>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplate>>, ApiError> {
+    load_template(&deployment, user.as_deref(), id).await?;
+    let template = PromptTemplate::update(&deployment.db().pool, id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+async fn delete_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    load_template(&deployment, user.as_deref(), id).await?;
+    PromptTemplate::delete(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn list_versions(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<PromptTemplateVersion>>>, ApiError> {
+    load_template(&deployment, user.as_deref(), id).await?;
+    let versions = PromptTemplateVersion::find_by_template_id(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(versions)))
+}
+
+async fn restore_version(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((id, version)): Path<(Uuid, i64)>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplate>>, ApiError> {
+    load_template(&deployment, user.as_deref(), id).await?;
+    let template = PromptTemplate::restore_version(&deployment.db().pool, id, version).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+async fn preview_template(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Json(payload): Json<PreviewPromptRequest>,
+) -> Result<ResponseJson<ApiResponse<RenderedPrompt>>, ApiError> {
+    let content = match (payload.content, payload.template_id) {
+        (Some(content), _) => content,
+        (None, Some(id)) => {
+            load_template(&deployment, user.as_deref(), id)
+                .await?
+                .content
+        }
+        (None, None) => {
+            return Err(ApiError::BadRequest(
+                "Either content or template_id is required".to_string(),
+            ));
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(prompt_library::render(
+        &content,
+        &payload.variables,
+    ))))
+}
@@ -6,12 +6,20 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    coverage::{RepoCoverageSettings, UpsertRepoCoverageSettings},
+    file_embedding::FileEmbedding,
     project::SearchResult,
     repo::{Repo, UpdateRepo},
+    repo_ssh_key::{RepoSshKey, UpsertRepoSshKey},
+    security_audit::{RepoSecurityAuditSettings, UpsertRepoSecurityAuditSettings},
 };
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::{file_search::SearchQuery, git::GitBranch};
+use services::services::{
+    embedding_index::{self, Embedder, EmbeddingError, IndexStats},
+    file_search::SearchQuery,
+    git::GitBranch,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -123,6 +131,124 @@ pub async fn update_repo(
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+pub async fn get_repo_coverage_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<RepoCoverageSettings>>>, ApiError> {
+    let settings = RepoCoverageSettings::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_repo_coverage_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpsertRepoCoverageSettings>,
+) -> Result<ResponseJson<ApiResponse<RepoCoverageSettings>>, ApiError> {
+    if payload.command.trim().is_empty() || payload.report_path.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Coverage command and report path are required".to_string(),
+        ));
+    }
+    if payload.max_drop.is_some_and(|drop| drop < 0.0) {
+        return Err(ApiError::BadRequest(
+            "max_drop cannot be negative".to_string(),
+        ));
+    }
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let settings = RepoCoverageSettings::upsert(pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn delete_repo_coverage_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    RepoCoverageSettings::delete(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_repo_ssh_key(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<RepoSshKey>>>, ApiError> {
+    let key = RepoSshKey::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
+
+pub async fn update_repo_ssh_key(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpsertRepoSshKey>,
+) -> Result<ResponseJson<ApiResponse<RepoSshKey>>, ApiError> {
+    let key = deployment
+        .repo()
+        .set_ssh_key(&deployment.db().pool, repo_id, &payload)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
+
+pub async fn delete_repo_ssh_key(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment
+        .repo()
+        .clear_ssh_key(&deployment.db().pool, repo_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_repo_security_audit_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<RepoSecurityAuditSettings>>>, ApiError> {
+    let settings =
+        RepoSecurityAuditSettings::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_repo_security_audit_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpsertRepoSecurityAuditSettings>,
+) -> Result<ResponseJson<ApiResponse<RepoSecurityAuditSettings>>, ApiError> {
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let settings = RepoSecurityAuditSettings::upsert(pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn delete_repo_security_audit_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    RepoSecurityAuditSettings::delete(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Bring the repo's embedding index up to date
+pub async fn refresh_repo_embedding_index(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<IndexStats>>, ApiError> {
+    let embedder = Embedder::from_config(&*deployment.config().read().await)
+        .ok_or(EmbeddingError::Disabled)?;
+    let pool = &deployment.db().pool;
+    let repo = deployment.repo().get_by_id(pool, repo_id).await?;
+    let stats = embedding_index::index_repo(pool, &embedder, &repo).await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
+/// Drop the repo's embedding index; it is rebuilt on the next suggestion
+pub async fn delete_repo_embedding_index(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    FileEmbedding::delete_by_repo(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub async fn open_repo_in_editor(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -202,5 +328,27 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/{repo_id}", get(get_repo).put(update_repo))
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
         .route("/repos/{repo_id}/search", get(search_repo))
+        .route(
+            "/repos/{repo_id}/coverage",
+            get(get_repo_coverage_settings)
+                .put(update_repo_coverage_settings)
+                .delete(delete_repo_coverage_settings),
+        )
+        .route(
+            "/repos/{repo_id}/ssh-key",
+            get(get_repo_ssh_key)
+                .put(update_repo_ssh_key)
+                .delete(delete_repo_ssh_key),
+        )
+        .route(
+            "/repos/{repo_id}/security-audit",
+            get(get_repo_security_audit_settings)
+                .put(update_repo_security_audit_settings)
+                .delete(delete_repo_security_audit_settings),
+        )
+        .route(
+            "/repos/{repo_id}/embedding-index",
+            post(refresh_repo_embedding_index).delete(delete_repo_embedding_index),
+        )
         .route("/repos/{repo_id}/open-editor", post(open_repo_in_editor))
 }
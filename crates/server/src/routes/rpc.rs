@@ -0,0 +1,414 @@
+//! JSON-RPC 2.0 over a WebSocket for IDE plugins: the core operations behind
+//! one long-lived connection instead of separate REST calls and log sockets.
+//!
+//! Methods:
+//! - `tasks.list` `{ project_id }` → tasks with attempt status
+//! - `attempts.start` `CreateTaskAttemptBody` → the new workspace
+//! - `diff.get` `{ workspace_id }` → the workspace diffs
+//! - `logs.subscribe` `{ execution_process_id, raw? }` → `{ subscription }`,
+//!   followed by `logs.event` notifications and a final `logs.finished`
+//! - `logs.unsubscribe` `{ subscription }` → `true`
+
+use std::{collections::HashMap, time::Duration};
+
+use axum::{
+    Extension, Router,
+    extract::{
+        State,
+        ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+    routing::get,
+};
+use db::models::{execution_process::ExecutionProcess, task::Task, workspace::Workspace};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use services::services::{container::ContainerService, multi_user::RequestUser};
+use tokio::{
+    sync::mpsc::{UnboundedSender, unbounded_channel},
+    task::AbortHandle,
+    time::{MissedTickBehavior, interval},
+};
+use utils::{log_msg::LogMsg, validation::Validate};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, is_read_only},
+    routes::task_attempts::{CreateTaskAttemptBody, create_attempt, util::workspace_diffs},
+};
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// Any failure of the operation itself; `data.status` carries the HTTP
+/// status the REST endpoint would have answered with
+const SERVER_ERROR: i32 = -32000;
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/rpc/ws", get(rpc_ws))
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RpcRequest {
+    /// Always "2.0"
+    pub jsonrpc: String,
+    /// Omitted for notifications, which get no response
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<ApiError> for RpcError {
+    fn from(error: ApiError) -> Self {
+        let message = error.to_string();
+        let status = error.into_response().status().as_u16();
+        Self {
+            code: SERVER_ERROR,
+            message,
+            data: Some(json!({ "status": status })),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListTasksParams {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDiffParams {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SubscribeLogsParams {
+    pub execution_process_id: Uuid,
+    /// Raw stdout/stderr instead of the normalized conversation patches
+    #[serde(default)]
+    pub raw: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UnsubscribeLogsParams {
+    pub subscription: Uuid,
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_result(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))
+}
+
+async fn rpc_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+) -> impl IntoResponse {
+    let user = user.map(|Extension(user)| user);
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_rpc_ws(socket, deployment, user).await {
+            tracing::warn!("RPC WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_rpc_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    user: Option<RequestUser>,
+) -> anyhow::Result<()> {
+    let (mut sender, mut receiver) = socket.split();
+    let (outgoing, mut outgoing_rx) = unbounded_channel::<Value>();
+    let mut subscriptions: HashMap<Uuid, AbortHandle> = HashMap::new();
+
+    let mut ping_interval = interval(Duration::from_secs(30));
+    ping_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            Some(message) = outgoing_rx.recv() => {
+                if sender.send(WsMessage::Text(message.to_string().into())).await.is_err() {
+                    break;
+                }
+            }
+            _ = ping_interval.tick() => {
+                if sender.send(WsMessage::Ping(vec![].into())).await.is_err() {
+                    break;
+                }
+            }
+            message = receiver.next() => match message {
+                Some(Ok(WsMessage::Text(text))) => {
+                    handle_message(&deployment, user, &text, &outgoing, &mut subscriptions);
+                }
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            }
+        }
+    }
+
+    for subscription in subscriptions.values() {
+        subscription.abort();
+    }
+    Ok(())
+}
+
+/// Answer a request in the background so slow operations don't hold up the
+/// rest of the connection
+fn handle_message(
+    deployment: &DeploymentImpl,
+    user: Option<RequestUser>,
+    text: &str,
+    outgoing: &UnboundedSender<Value>,
+    subscriptions: &mut HashMap<Uuid, AbortHandle>,
+) {
+    let request: RpcRequest = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(e) => {
+            let _ = outgoing.send(response(
+                Value::Null,
+                Err(RpcError::new(PARSE_ERROR, e.to_string())),
+            ));
+            return;
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        let _ = outgoing.send(response(
+            request.id.unwrap_or(Value::Null),
+            Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")),
+        ));
+        return;
+    }
+
+    match request.method.as_str() {
+        "logs.subscribe" => {
+            subscriptions.retain(|_, handle| !handle.is_finished());
+            let subscription = Uuid::new_v4();
+            let handle = tokio::spawn(stream_logs(
+                deployment.clone(),
+                user,
+                request,
+                subscription,
+                outgoing.clone(),
+            ));
+            subscriptions.insert(subscription, handle.abort_handle());
+        }
+        "logs.unsubscribe" => {
+            let result = parse_params::<UnsubscribeLogsParams>(request.params).map(|params| {
+                if let Some(handle) = subscriptions.remove(&params.subscription) {
+                    handle.abort();
+                }
+                Value::Bool(true)
+            });
+            if let Some(id) = request.id {
+                let _ = outgoing.send(response(id, result));
+            }
+        }
+        _ => {
+            let deployment = deployment.clone();
+            let outgoing = outgoing.clone();
+            tokio::spawn(async move {
+                let result = dispatch(&deployment, user, &request.method, request.params).await;
+                if let Some(id) = request.id {
+                    let _ = outgoing.send(response(id, result));
+                }
+            });
+        }
+    }
+}
+
+async fn dispatch(
+    deployment: &DeploymentImpl,
+    user: Option<RequestUser>,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "tasks.list" => {
+            let params: ListTasksParams = parse_params(params)?;
+            ensure_project_access(deployment, user.as_ref(), params.project_id).await?;
+            let tasks = Task::find_by_project_id_with_attempt_status(
+                &deployment.db().read_pool,
+                params.project_id,
+            )
+            .await
+            .map_err(ApiError::from)?;
+            to_result(tasks)
+        }
+        "attempts.start" => {
+            // The socket itself is a read-only GET, so the read-only mode
+            // middleware cannot see this write
+            if is_read_only() {
+                return Err(ApiError::Forbidden(
+                    "This instance is read-only; changes are disabled".to_string(),
+                )
+                .into());
+            }
+            let params: CreateTaskAttemptBody = parse_params(params)?;
+            params.validate().map_err(|errors| RpcError {
+                code: INVALID_PARAMS,
+                message: errors.to_string(),
+                data: serde_json::to_value(&errors).ok(),
+            })?;
+            let workspace = create_attempt(deployment, user.as_ref(), &params).await?;
+            to_result(workspace)
+        }
+        "diff.get" => {
+            let params: GetDiffParams = parse_params(params)?;
+            let workspace = find_workspace(deployment, user, params.workspace_id).await?;
+            let diffs = workspace_diffs(deployment, &workspace).await?;
+            to_result(diffs)
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {method}"),
+        )),
+    }
+}
+
+async fn find_workspace(
+    deployment: &DeploymentImpl,
+    user: Option<RequestUser>,
+    workspace_id: Uuid,
+) -> Result<Workspace, RpcError> {
+    let pool = &deployment.db().pool;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "workspace not found"))?;
+    let task = Task::find_by_id(pool, workspace.task_id)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "task not found"))?;
+    ensure_project_access(deployment, user.as_ref(), task.project_id).await?;
+    Ok(workspace)
+}
+
+/// Confirm the subscription, then forward the process's log messages as
+/// `logs.event` notifications until it finishes
+async fn stream_logs(
+    deployment: DeploymentImpl,
+    user: Option<RequestUser>,
+    request: RpcRequest,
+    subscription: Uuid,
+    outgoing: UnboundedSender<Value>,
+) {
+    let opened = async {
+        let params: SubscribeLogsParams = parse_params(request.params)?;
+        let context =
+            ExecutionProcess::load_context(&deployment.db().pool, params.execution_process_id)
+                .await
+                .map_err(ApiError::from)?;
+        ensure_project_access(&deployment, user.as_ref(), context.project.id).await?;
+        let container = deployment.container();
+        let stream = if params.raw {
+            container
+                .stream_raw_logs(&params.execution_process_id)
+                .await
+        } else {
+            container
+                .stream_normalized_logs(&params.execution_process_id)
+                .await
+        };
+        stream.ok_or_else(|| RpcError::new(INVALID_PARAMS, "execution process has no logs"))
+    }
+    .await;
+
+    let mut stream = match opened {
+        Ok(stream) => {
+            if let Some(id) = request.id {
+                let _ = outgoing.send(response(id, Ok(json!({ "subscription": subscription }))));
+            }
+            stream
+        }
+        Err(error) => {
+            if let Some(id) = request.id {
+                let _ = outgoing.send(response(id, Err(error)));
+            }
+            return;
+        }
+    };
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(LogMsg::Finished) => break,
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("RPC log stream error: {}", e);
+                break;
+            }
+        };
+        let event = notification(
+            "logs.event",
+            json!({ "subscription": subscription, "event": message }),
+        );
+        if outgoing.send(event).is_err() {
+            return;
+        }
+    }
+    let _ = outgoing.send(notification(
+        "logs.finished",
+        json!({ "subscription": subscription }),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_errors_keep_their_http_status() {
+        let error = RpcError::from(ApiError::Forbidden("no".to_string()));
+        assert_eq!(error.code, SERVER_ERROR);
+        assert_eq!(error.data, Some(json!({ "status": 403 })));
+
+        let message = response(json!(7), Err(error));
+        assert_eq!(message["id"], 7);
+        assert_eq!(message["error"]["message"], "Forbidden: no");
+        assert!(message.get("result").is_none());
+    }
+
+    #[test]
+    fn params_that_do_not_match_are_invalid() {
+        let error = parse_params::<ListTasksParams>(json!({ "project": 1 })).unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{
         Path, State,
         ws::{WebSocket, WebSocketUpgrade},
@@ -7,14 +7,23 @@ use axum::{
     response::{IntoResponse, Json as ResponseJson},
     routing::get,
 };
-use db::models::scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch};
+use db::models::{
+    scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch},
+    session::Session,
+};
 use deployment::Deployment;
 use futures_util::TryStreamExt;
 use serde::Deserialize;
+use services::services::multi_user::{MultiUserError, RequestUser};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, ws_utils::stream_with_heartbeat};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_single_user, ensure_workspace_access},
+    ws_utils::stream_with_heartbeat,
+};
 
 /// Path parameters for scratch routes with composite key
 #[derive(Deserialize)]
@@ -23,17 +32,51 @@ pub struct ScratchPath {
     id: Uuid,
 }
 
+/// Reject access to a scratch of another user's workspace or session. Drafts
+/// that belong to no workspace are shared by the whole instance, so they are
+/// only available in single-user mode.
+async fn authorize_scratch(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    scratch_type: ScratchType,
+    id: Uuid,
+) -> Result<(), ApiError> {
+    if user.is_none() {
+        return Ok(());
+    }
+    let workspace_id = match scratch_type {
+        ScratchType::DraftFollowUp => {
+            Session::find_by_id(&deployment.db().pool, id)
+                .await?
+                .ok_or(ApiError::MultiUser(MultiUserError::NotFound))?
+                .workspace_id
+        }
+        ScratchType::PreviewSettings | ScratchType::WorkspaceNotes => id,
+        ScratchType::DraftTask | ScratchType::DraftWorkspace => {
+            return ensure_single_user(user, "Drafts are only saved in single-user mode");
+        }
+    };
+    ensure_workspace_access(deployment, user, workspace_id).await
+}
+
 pub async fn list_scratch(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Scratch>>>, ApiError> {
+    ensure_single_user(
+        user.as_deref(),
+        "Listing scratch items is only available in single-user mode",
+    )?;
     let scratch_items = Scratch::find_all(&deployment.db().pool).await?;
     Ok(ResponseJson(ApiResponse::success(scratch_items)))
 }
 
 pub async fn get_scratch(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
+    authorize_scratch(&deployment, user.as_deref(), scratch_type, id).await?;
     let scratch = Scratch::find_by_id(&deployment.db().pool, id, &scratch_type)
         .await?
         .ok_or_else(|| ApiError::BadRequest("Scratch not found".to_string()))?;
@@ -42,9 +85,12 @@ pub async fn get_scratch(
 
 pub async fn create_scratch(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
     Json(payload): Json<CreateScratch>,
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
+    authorize_scratch(&deployment, user.as_deref(), scratch_type, id).await?;
+
     // Reject edits to draft_follow_up if a message is queued for this task attempt
     if matches!(scratch_type, ScratchType::DraftFollowUp)
         && deployment.queued_message_service().has_queued(id)
@@ -66,9 +112,12 @@ pub async fn create_scratch(
 
 pub async fn update_scratch(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
     Json(payload): Json<UpdateScratch>,
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
+    authorize_scratch(&deployment, user.as_deref(), scratch_type, id).await?;
+
     // Reject edits to draft_follow_up if a message is queued for this task attempt
     if matches!(scratch_type, ScratchType::DraftFollowUp)
         && deployment.queued_message_service().has_queued(id)
@@ -91,8 +140,10 @@ pub async fn update_scratch(
 
 pub async fn delete_scratch(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    authorize_scratch(&deployment, user.as_deref(), scratch_type, id).await?;
     let rows = Scratch::delete(&deployment.db().pool, id, &scratch_type).await?;
     if rows == 0 {
         return Err(ApiError::BadRequest("Scratch not found".to_string()));
@@ -103,13 +154,16 @@ pub async fn delete_scratch(
 pub async fn stream_scratch_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async move {
+) -> Result<impl IntoResponse, ApiError> {
+    authorize_scratch(&deployment, user.as_deref(), scratch_type, id).await?;
+
+    Ok(ws.on_upgrade(move |socket| async move {
         if let Err(e) = handle_scratch_ws(socket, deployment, id, scratch_type).await {
             tracing::warn!("scratch WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_scratch_ws(
@@ -0,0 +1,108 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    project::Project,
+    project_script::{CreateProjectScript, ProjectScript, UpdateProjectScript},
+};
+use deployment::Deployment;
+use services::services::{automation_scripts, multi_user::RequestUser};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/scripts",
+            get(list_scripts).post(create_script),
+        )
+        .route(
+            "/projects/{project_id}/scripts/{script_id}",
+            put(update_script).delete(delete_script),
+        )
+}
+
+/// Scripts act on every task of a project without a user behind them, so
+/// they are only managed on single-user instances.
+const SINGLE_USER_ONLY: &str = "Automation scripts are only available in single-user mode";
+
+fn validate_name(name: &str) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Script name is empty".to_string()));
+    }
+    Ok(())
+}
+
+async fn find_script(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    script_id: Uuid,
+) -> Result<ProjectScript, ApiError> {
+    ProjectScript::find_by_id(&deployment.db().pool, script_id)
+        .await?
+        .filter(|script| script.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Script not found".to_string()))
+}
+
+async fn list_scripts(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectScript>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    let scripts = ProjectScript::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(scripts)))
+}
+
+async fn create_script(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<CreateProjectScript>,
+) -> Result<ResponseJson<ApiResponse<ProjectScript>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    validate_name(&payload.name)?;
+    automation_scripts::check(&payload.source)?;
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, project_id).await?.is_none() {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    let script = ProjectScript::create(pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+async fn update_script(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, script_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateProjectScript>,
+) -> Result<ResponseJson<ApiResponse<ProjectScript>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    find_script(&deployment, project_id, script_id).await?;
+    if let Some(name) = &payload.name {
+        validate_name(name)?;
+    }
+    if let Some(source) = &payload.source {
+        automation_scripts::check(source)?;
+    }
+    let script = ProjectScript::update(&deployment.db().pool, script_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Script not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+async fn delete_script(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, script_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    find_script(&deployment, project_id, script_id).await?;
+    ProjectScript::delete(&deployment.db().pool, script_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,70 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{multi_user::RequestUser, secrets::SecretInfo};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_single_user};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetSecretRequest {
+    pub value: String,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/secrets", get(list_secrets))
+        .route("/secrets/{name}", put(set_secret).delete(delete_secret))
+}
+
+/// Secrets are shared by everyone on the instance, so they are only
+/// managed on single-user instances.
+const SINGLE_USER_ONLY: &str = "Secrets are only available in single-user mode";
+
+/// Stored secrets with their values masked
+async fn list_secrets(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecretInfo>>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.secrets().list().await,
+    )))
+}
+
+/// Store a provider key; coding agents started afterwards receive it as an
+/// environment variable named `name`
+async fn set_secret(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(name): Path<String>,
+    Json(payload): Json<SetSecretRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    if payload.value.trim().is_empty() {
+        return Err(ApiError::BadRequest("Secret value is empty".to_string()));
+    }
+    deployment
+        .secrets()
+        .set(&name, payload.value.trim())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn delete_secret(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_single_user(user.as_deref(), SINGLE_USER_ONLY)?;
+    if !deployment.secrets().remove(&name).await? {
+        return Err(ApiError::BadRequest(format!("No secret named {name}")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,193 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    session::{CreateSession, Session},
+    session_handoff::SessionHandoff,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+    },
+    profile::ExecutorProfileId,
+};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::{ContainerService, RepoWithName},
+    handoff,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use super::SessionQuery;
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::util};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct HandoffSessionRequest {
+    pub executor_profile_id: ExecutorProfileId,
+    /// Replaces the default request to carry on where the previous agent
+    /// stopped
+    pub instructions: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct HandoffSessionResponse {
+    pub session: Session,
+    pub execution_process: ExecutionProcess,
+    pub handoff: SessionHandoff,
+}
+
+/// Continue a session with a different executor. Agents cannot resume each
+/// other's conversations, so the new one starts a fresh session from the
+/// prompt history and the changes made so far. Anything still running in the
+/// workspace is stopped first, since the previous agent is usually stuck.
+pub async fn handoff_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<HandoffSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<HandoffSessionResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let executor_profile_id = payload.executor_profile_id;
+    let to_executor = executor_profile_id.executor.to_string();
+    let from_executor = ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+        .await?
+        .map(|profile| profile.executor.to_string())
+        .or_else(|| session.executor.clone());
+    if from_executor.as_deref() == Some(to_executor.as_str()) {
+        return Err(ApiError::BadRequest(
+            "The session already uses this executor; send a follow-up instead".to_string(),
+        ));
+    }
+
+    util::ensure_within_budget(
+        pool,
+        task.project_id,
+        1,
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
+
+    deployment.container().try_stop(&workspace, false).await;
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let turns = CodingAgentTurn::find_by_session_id(pool, session.id).await?;
+    let diffs = match util::workspace_diffs(&deployment, &workspace).await {
+        Ok(diffs) => diffs,
+        Err(e) => {
+            tracing::warn!("Handing off session {} without its diff: {}", session.id, e);
+            Vec::new()
+        }
+    };
+    let prompt = handoff::handoff_prompt(
+        from_executor.as_deref(),
+        &turns,
+        &diffs,
+        payload.instructions.as_deref(),
+    );
+
+    let new_session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some(to_executor.clone()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await?;
+
+    let repos_raw = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let repos: Vec<_> = repos_raw.iter().map(RepoWithName::from).collect();
+    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let model = Project::find_executor_defaults_for_workspace(pool, workspace.id)
+        .await?
+        .model_for(&executor_profile_id);
+
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt,
+            executor_profile_id: executor_profile_id.clone(),
+            working_dir: workspace
+                .agent_working_dir
+                .clone()
+                .filter(|dir| !dir.is_empty()),
+            model,
+        }),
+        cleanup_action.map(Box::new),
+    );
+
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &new_session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+
+    let handoff = SessionHandoff::create(
+        pool,
+        workspace.id,
+        session.id,
+        new_session.id,
+        from_executor.as_deref(),
+        &to_executor,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_handed_off",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "from_executor": from_executor,
+                "to_executor": to_executor,
+                "turns": turns.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(HandoffSessionResponse {
+        session: new_session,
+        execution_process,
+        handoff,
+    })))
+}
+
+pub async fn get_handoffs(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SessionQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SessionHandoff>>>, ApiError> {
+    let handoffs =
+        SessionHandoff::find_by_workspace_id(&deployment.db().pool, query.workspace_id).await?;
+    Ok(ResponseJson(ApiResponse::success(handoffs)))
+}
@@ -1,3 +1,4 @@
+pub mod handoff;
 pub mod queue;
 pub mod review;
 
@@ -9,7 +10,9 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    execution_interrupt::ExecutionInterrupt,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session, SessionError},
     workspace::{Workspace, WorkspaceError},
@@ -23,14 +26,17 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, interrupt, multi_user::RequestUser};
+use sqlx::SqlitePool;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
-    routes::task_attempts::util::restore_worktrees_to_process,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_workspace_access, load_session_middleware},
+    routes::task_attempts::util::{self, restore_worktrees_to_process},
 };
 
 #[derive(Debug, Deserialize)]
@@ -46,8 +52,10 @@ pub struct CreateSessionRequest {
 
 pub async fn get_sessions(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<SessionQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Session>>>, ApiError> {
+    ensure_workspace_access(&deployment, user.as_deref(), query.workspace_id).await?;
     let pool = &deployment.db().pool;
     let sessions = Session::find_by_workspace_id(pool, query.workspace_id).await?;
     Ok(ResponseJson(ApiResponse::success(sessions)))
@@ -61,8 +69,10 @@ pub async fn get_session(
 
 pub async fn create_session(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Json(payload): Json<CreateSessionRequest>,
 ) -> Result<ResponseJson<ApiResponse<Session>>, ApiError> {
+    ensure_workspace_access(&deployment, user.as_deref(), payload.workspace_id).await?;
     let pool = &deployment.db().pool;
 
     // Verify workspace exists
@@ -92,6 +102,40 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Start even if the run is expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+/// The session a follow-up should run in. Once another attempt of the task
+/// has been promoted, follow-ups go to the canonical attempt's latest
+/// session instead, starting one if it has none.
+pub(crate) async fn follow_up_session(
+    pool: &SqlitePool,
+    session: Session,
+) -> Result<Session, ApiError> {
+    let Some(workspace) = Workspace::find_by_id(pool, session.workspace_id).await? else {
+        return Ok(session);
+    };
+    let canonical_id = match Workspace::find_canonical_id(pool, workspace.task_id).await? {
+        Some(id) if id != workspace.id => id,
+        _ => return Ok(session),
+    };
+
+    if let Some(canonical) = Session::find_latest_by_workspace_id(pool, canonical_id).await? {
+        return Ok(canonical);
+    }
+    Ok(Session::create(
+        pool,
+        &CreateSession {
+            executor: session.executor.clone(),
+        },
+        Uuid::new_v4(),
+        canonical_id,
+    )
+    .await?)
 }
 
 pub async fn follow_up(
@@ -100,6 +144,8 @@ pub async fn follow_up(
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
     let pool = &deployment.db().pool;
+    let draft_session_id = session.id;
+    let session = follow_up_session(pool, session).await?;
 
     // Load workspace from session
     let workspace = Workspace::find_by_id(pool, session.workspace_id)
@@ -110,6 +156,18 @@ pub async fn follow_up(
 
     tracing::info!("{:?}", workspace);
 
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    util::ensure_within_budget(
+        pool,
+        task.project_id,
+        1,
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
+
     deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -177,7 +235,12 @@ pub async fn follow_up(
     let latest_agent_session_id =
         ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
 
-    let prompt = payload.prompt;
+    // Continue an interrupted turn with what the agent had done so far
+    let interrupted = ExecutionInterrupt::find_pending_for_session(pool, session.id).await?;
+    let prompt = match &interrupted {
+        Some(interrupted) => interrupt::resume_prompt(interrupted, &payload.prompt),
+        None => payload.prompt,
+    };
 
     let repos_raw = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
 
@@ -193,12 +256,17 @@ pub async fn follow_up(
         .filter(|dir| !dir.is_empty())
         .cloned();
 
+    let model = Project::find_executor_defaults_for_workspace(pool, workspace.id)
+        .await?
+        .model_for(&executor_profile_id);
+
     let action_type = if let Some(agent_session_id) = latest_agent_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
             prompt: prompt.clone(),
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            model: model.clone(),
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(
@@ -206,6 +274,7 @@ pub async fn follow_up(
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                model,
             },
         )
     };
@@ -222,13 +291,17 @@ pub async fn follow_up(
         )
         .await?;
 
+    if let Some(interrupted) = interrupted {
+        ExecutionInterrupt::mark_resumed(pool, interrupted.execution_process_id).await?;
+    }
+
     // Clear the draft follow-up scratch on successful spawn
     // This ensures the scratch is wiped even if the user navigates away quickly
-    if let Err(e) = Scratch::delete(pool, session.id, &ScratchType::DraftFollowUp).await {
+    if let Err(e) = Scratch::delete(pool, draft_session_id, &ScratchType::DraftFollowUp).await {
         // Log but don't fail the request - scratch deletion is best-effort
         tracing::debug!(
             "Failed to delete draft follow-up scratch for session {}: {}",
-            session.id,
+            draft_session_id,
             e
         );
     }
@@ -241,6 +314,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .route("/handoff", post(handoff::handoff_session))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -248,6 +322,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let sessions_router = Router::new()
         .route("/", get(get_sessions).post(create_session))
+        .route("/handoffs", get(handoff::get_handoffs))
         .nest("/{session_id}", session_id_router)
         .nest("/{session_id}/queue", queue::router(deployment));
 
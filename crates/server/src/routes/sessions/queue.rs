@@ -1,6 +1,9 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
@@ -9,6 +12,7 @@ use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
@@ -17,6 +21,16 @@ use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware
 pub struct QueueMessageRequest {
     pub message: String,
     pub executor_profile_id: ExecutorProfileId,
+    /// Add to the end of the queue instead of replacing it
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// Request body for editing a queued follow-up message
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateQueuedMessageRequest {
+    pub message: String,
+    pub executor_profile_id: ExecutorProfileId,
 }
 
 /// Queue a follow-up message to be executed when the current execution finishes
@@ -25,20 +39,24 @@ pub async fn queue_message(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<QueueMessageRequest>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let session = super::follow_up_session(&deployment.db().pool, session).await?;
     let data = DraftFollowUpData {
         message: payload.message,
         executor_profile_id: payload.executor_profile_id,
     };
 
-    let queued = deployment
-        .queued_message_service()
-        .queue_message(session.id, data);
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    let queue = deployment.queued_message_service();
+    if payload.append {
+        queue.enqueue_message(session.id, data);
+    } else {
+        queue.queue_message(session.id, data);
+    }
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session.id),
+    )))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -49,6 +67,39 @@ pub async fn cancel_queued_message(
     Ok(ResponseJson(ApiResponse::success(QueueStatus::Empty)))
 }
 
+/// Edit a queued follow-up message in place
+pub async fn update_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateQueuedMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        executor_profile_id: payload.executor_profile_id,
+    };
+    let queue = deployment.queued_message_service();
+    queue
+        .update_queued(session_id, message_id, data)
+        .ok_or_else(|| ApiError::BadRequest("Queued message not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session_id),
+    )))
+}
+
+/// Remove one message from the queue, keeping the others
+pub async fn cancel_one_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue
+        .cancel_queued_message(session_id, message_id)
+        .ok_or_else(|| ApiError::BadRequest("Queued message not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session_id),
+    )))
+}
+
 /// Get the current queue status for a session's workspace
 pub async fn get_queue_status(
     Extension(session): Extension<Session>,
@@ -60,6 +111,13 @@ pub async fn get_queue_status(
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // The queue lives in memory keyed by session id, so single-message routes
+    // do not need the session loaded
+    let message_router = Router::new().route(
+        "/{message_id}",
+        put(update_queued_message).delete(cancel_one_queued_message),
+    );
+
     Router::new()
         .route(
             "/",
@@ -71,4 +129,5 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             deployment.clone(),
             load_session_middleware,
         ))
+        .merge(message_router)
 }
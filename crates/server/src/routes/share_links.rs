@@ -0,0 +1,125 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    project::Project,
+    project_share_link::{CreateProjectShareLink, ProjectShareLink, PublicBoard},
+    task::Task,
+};
+use deployment::Deployment;
+use rand::{Rng, distributions::Alphanumeric};
+use services::services::multi_user::RequestUser;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, load_project_middleware},
+    validation::ValidatedJson,
+};
+
+/// Owner-facing routes to create, list and revoke a project's share links
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_share_links).post(create_share_link))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .nest("/projects/{id}/share-links", project_router)
+        .route(
+            "/projects/{project_id}/share-links/{link_id}",
+            delete(revoke_share_link),
+        )
+}
+
+/// Routes reachable without an account; the token is the only credential
+pub fn public_router() -> Router<DeploymentImpl> {
+    Router::new().route("/public/boards/{token}", get(get_public_board))
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect()
+}
+
+async fn list_share_links(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectShareLink>>>, ApiError> {
+    let links = ProjectShareLink::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(links)))
+}
+
+async fn create_share_link(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<CreateProjectShareLink>,
+) -> Result<ResponseJson<ApiResponse<ProjectShareLink>>, ApiError> {
+    let link = ProjectShareLink::create(
+        &deployment.db().pool,
+        project.id,
+        &generate_token(),
+        payload.expires_in_days,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_share_link_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "expires": payload.expires_in_days.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(link)))
+}
+
+async fn revoke_share_link(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let rows_affected =
+        ProjectShareLink::delete(&deployment.db().pool, project_id, link_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::BadRequest("Share link not found".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Sanitized, read-only view of the board behind a share link. Unknown,
+/// revoked and expired tokens all look the same to the caller.
+async fn get_public_board(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+) -> Result<ResponseJson<ApiResponse<PublicBoard>>, ApiError> {
+    let not_found = || ApiError::BadRequest("Share link not found or expired".to_string());
+    let pool = &deployment.db().read_pool;
+
+    let link = ProjectShareLink::find_active_by_token(pool, &token)
+        .await?
+        .ok_or_else(not_found)?;
+    let project = Project::find_by_id(pool, link.project_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, project.id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(PublicBoard {
+        project_name: project.name,
+        tasks: tasks.into_iter().map(Into::into).collect(),
+    })))
+}
@@ -0,0 +1,120 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    project::Project,
+    project_share_rule::{CreateProjectShareRule, ProjectShareRule},
+    task_label::TaskLabel,
+};
+use deployment::Deployment;
+use services::services::multi_user::RequestUser;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, load_project_middleware},
+    validation::ValidatedJson,
+};
+
+/// Routes to manage the rules that share a project's tasks automatically
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_share_rules).post(create_share_rule))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .nest("/projects/{id}/share-rules", project_router)
+        .route(
+            "/projects/{project_id}/share-rules/{rule_id}",
+            delete(delete_share_rule),
+        )
+}
+
+/// Re-evaluate every task of the project in the background once its rules
+/// change
+fn spawn_apply_rules(deployment: &DeploymentImpl, project_id: Uuid) {
+    let Ok(publisher) = deployment.share_publisher() else {
+        return;
+    };
+    tokio::spawn(async move {
+        match publisher.apply_project_share_rules(project_id).await {
+            Ok(changed) => tracing::info!(
+                "Share rules of project {} shared or unshared {} tasks",
+                project_id,
+                changed
+            ),
+            Err(e) => tracing::warn!(
+                "Failed to apply share rules of project {}: {}",
+                project_id,
+                e
+            ),
+        }
+    });
+}
+
+async fn list_share_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectShareRule>>>, ApiError> {
+    let rules = ProjectShareRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+async fn create_share_rule(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<CreateProjectShareRule>,
+) -> Result<ResponseJson<ApiResponse<ProjectShareRule>>, ApiError> {
+    if project.remote_project_id.is_none() {
+        return Err(ApiError::BadRequest(
+            "Link the project to a remote project before adding share rules".to_string(),
+        ));
+    }
+    if let Some(label_id) = payload.label_id {
+        let label = TaskLabel::find_by_id(&deployment.db().pool, label_id).await?;
+        if label.is_none_or(|label| label.project_id != project.id) {
+            return Err(ApiError::BadRequest(
+                "Label not found in this project".to_string(),
+            ));
+        }
+    }
+
+    let rule = ProjectShareRule::create(&deployment.db().pool, project.id, &payload).await?;
+    spawn_apply_rules(&deployment, project.id);
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_share_rule_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "by_label": rule.label_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+async fn delete_share_rule(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((project_id, rule_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let rows_affected =
+        ProjectShareRule::delete(&deployment.db().pool, project_id, rule_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::BadRequest("Share rule not found".to_string()));
+    }
+    spawn_apply_rules(&deployment, project_id);
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -1,15 +1,30 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
-    routing::{delete, post},
+    routing::{delete, get, post},
 };
 use chrono::{DateTime, Utc};
 use db::models::task::{Task, TaskStatus};
 use deployment::Deployment;
-use remote::routes::tasks::SharedTaskResponse as RemoteSharedTaskResponse;
+use remote::{
+    db::{
+        mentions::Mention as RemoteMention, task_claims::SharedTaskClaim as RemoteSharedTaskClaim,
+        task_execution_status::SharedTaskExecutionStatus as RemoteSharedTaskExecutionStatus,
+    },
+    routes::{
+        mentions::{MarkMentionsReadRequest, MarkMentionsReadResponse},
+        tasks::{
+            ClaimConflictResponse, ClaimSharedTaskRequest,
+            SharedTaskResponse as RemoteSharedTaskResponse,
+        },
+    },
+};
 use serde::{Deserialize, Serialize};
-use services::services::share::{ShareError, SharedTaskDetails};
+use services::services::{
+    remote_client::RemoteClientError,
+    share::{ShareError, SharedTaskDetails},
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -68,6 +83,90 @@ pub struct SharedTaskResponse {
     pub user: Option<UserData>,
 }
 
+/// Claim a member holds on a shared task while running an agent on it.
+/// This mirrors the remote crate's SharedTaskClaim for TypeScript generation.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedTaskClaim {
+    pub task_id: Uuid,
+    pub holder: UserData,
+    pub claimed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Lifecycle state of the latest attempt on a shared task.
+/// This mirrors the remote crate's SharedTaskExecutionState for TypeScript generation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SharedTaskExecutionState {
+    Started,
+    Executing,
+    Completed,
+    Failed,
+    PrOpened,
+}
+
+/// Latest attempt state a member's instance reported for a shared task.
+/// This mirrors the remote crate's SharedTaskExecutionStatus for TypeScript generation.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedTaskExecutionStatus {
+    pub task_id: Uuid,
+    /// Member whose local instance reported the state
+    pub reporter: UserData,
+    pub state: SharedTaskExecutionState,
+    pub detail: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// State of the pull request opened from a shared task attempt.
+/// This mirrors the remote crate's PullRequestStatus for TypeScript generation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum PullRequestStatus {
+    Open,
+    Merged,
+    Closed,
+}
+
+/// Summary and PR state of a teammate's attempt, synced via Electric.
+/// This mirrors the remote crate's SharedTaskAttempt for TypeScript generation.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedTaskAttempt {
+    /// Id of the workspace on the member's local instance
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub branch: String,
+    pub summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i32>,
+    pub pr_status: Option<PullRequestStatus>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A member @mentioned the current user in a shared task or issue comment.
+/// This mirrors the remote crate's Mention for TypeScript generation.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Mention {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub author: UserData,
+    pub shared_task_id: Option<Uuid>,
+    pub issue_id: Option<Uuid>,
+    pub issue_comment_id: Option<Uuid>,
+    pub excerpt: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ----------------------------------------------------------
 // Request/Response types used at runtime
 // ----------------------------------------------------------
@@ -85,10 +184,20 @@ pub fn router() -> Router<DeploymentImpl> {
             post(assign_shared_task),
         )
         .route("/shared-tasks/{shared_task_id}", delete(delete_shared_task))
+        .route(
+            "/shared-tasks/{shared_task_id}/claim",
+            get(get_shared_task_claim).delete(release_shared_task_claim),
+        )
+        .route(
+            "/shared-tasks/execution-status",
+            get(get_execution_statuses_by_project),
+        )
         .route(
             "/shared-tasks/link-to-local",
             post(link_shared_task_to_local),
         )
+        .route("/mentions/unread", get(list_unread_mentions))
+        .route("/mentions/mark-read", post(mark_mentions_read))
 }
 
 pub async fn assign_shared_task(
@@ -132,3 +241,103 @@ pub async fn link_shared_task_to_local(
 
     Ok(ResponseJson(ApiResponse::success(task)))
 }
+
+/// Who holds the claim on a shared task, if anyone
+pub async fn get_shared_task_claim(
+    Path(shared_task_id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<RemoteSharedTaskClaim>>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let claim = client.get_shared_task_claim(shared_task_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(claim)))
+}
+
+pub async fn release_shared_task_claim(
+    Path(shared_task_id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    client.release_shared_task_claim(shared_task_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Latest attempt state teammates reported for each shared task in a project
+pub async fn get_execution_statuses_by_project(
+    Query(query): Query<AssigneesQuery>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RemoteSharedTaskExecutionStatus>>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let statuses = client
+        .list_shared_task_execution_statuses(query.project_id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+/// Unread @mentions of the current user in shared tasks and comments
+pub async fn list_unread_mentions(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RemoteMention>>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let mentions = client.list_unread_mentions().await?;
+
+    Ok(ResponseJson(ApiResponse::success(mentions)))
+}
+
+pub async fn mark_mentions_read(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<MarkMentionsReadRequest>,
+) -> Result<ResponseJson<ApiResponse<MarkMentionsReadResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.mark_mentions_read(&payload).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Claim a shared task before starting an agent on it. Fails with a conflict
+/// naming the holder when another member has an unexpired claim; when the
+/// remote cannot be reached the attempt goes ahead unclaimed.
+pub(crate) async fn claim_for_attempt(
+    deployment: &DeploymentImpl,
+    shared_task_id: Uuid,
+) -> Result<(), ApiError> {
+    let Ok(client) = deployment.remote_client() else {
+        return Ok(());
+    };
+
+    match client
+        .claim_shared_task(shared_task_id, &ClaimSharedTaskRequest::default())
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(RemoteClientError::Http { status: 409, body }) => {
+            let message = match serde_json::from_str::<ClaimConflictResponse>(&body) {
+                Ok(conflict) => {
+                    let holder = &conflict.claim.holder;
+                    let name = holder
+                        .username
+                        .clone()
+                        .or_else(|| holder.first_name.clone())
+                        .unwrap_or_else(|| holder.user_id.to_string());
+                    format!(
+                        "Task is claimed by {name} until {}",
+                        conflict.claim.expires_at.format("%Y-%m-%d %H:%M UTC")
+                    )
+                }
+                Err(_) => "Task is claimed by another user".to_string(),
+            };
+            Err(ApiError::Conflict(message))
+        }
+        Err(e) => {
+            tracing::warn!("Could not claim shared task {}: {}", shared_task_id, e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use db::models::{instance_stats::InstanceStats, time_entry::ProjectTime};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::multi_user::RequestUser;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_project_access};
+
+#[derive(Debug, Deserialize)]
+pub struct InstanceStatsQuery {
+    /// Start of the period; defaults to the start of the current month (UTC)
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Per-project counters over the same period as the instance stats
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectStats {
+    pub time: ProjectTime,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/stats/instance", get(get_instance_stats))
+        .route("/stats/projects/{project_id}", get(get_project_stats))
+}
+
+fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+/// Local usage counters, computed from the database only; nothing is sent anywhere
+async fn get_instance_stats(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<InstanceStatsQuery>,
+) -> Result<ResponseJson<ApiResponse<InstanceStats>>, ApiError> {
+    let since = query.since.unwrap_or_else(|| start_of_month(Utc::now()));
+    let owner_id = user.map(|Extension(user)| user.user_id);
+    let stats = InstanceStats::collect(&deployment.db().read_pool, since, owner_id).await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
+async fn get_project_stats(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<InstanceStatsQuery>,
+) -> Result<ResponseJson<ApiResponse<ProjectStats>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    let since = query.since.unwrap_or_else(|| start_of_month(Utc::now()));
+    let time = ProjectTime::collect(&deployment.db().read_pool, project_id, since).await?;
+    Ok(ResponseJson(ApiResponse::success(ProjectStats { time })))
+}
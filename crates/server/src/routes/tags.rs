@@ -1,5 +1,5 @@
 use axum::{
-    Extension, Json, Router,
+    Extension, Router,
     extract::{Query, State},
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
@@ -11,7 +11,9 @@ use serde::Deserialize;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_tag_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_tag_middleware, validation::ValidatedJson,
+};
 
 #[derive(Deserialize, TS)]
 pub struct TagSearchParams {
@@ -36,7 +38,7 @@ pub async fn get_tags(
 
 pub async fn create_tag(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateTag>,
+    ValidatedJson(payload): ValidatedJson<CreateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
     let tag = Tag::create(&deployment.db().pool, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(tag)))
@@ -45,7 +47,7 @@ pub async fn create_tag(
 pub async fn update_tag(
     Extension(tag): Extension<Tag>,
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<UpdateTag>,
+    ValidatedJson(payload): ValidatedJson<UpdateTag>,
 ) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
     let updated_tag = Tag::update(&deployment.db().pool, tag.id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(updated_tag)))
@@ -1,4 +1,6 @@
+pub mod artifacts;
 pub mod codex_setup;
+pub mod comparison;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
@@ -7,7 +9,7 @@ pub mod util;
 pub mod workspace_summary;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
@@ -17,21 +19,36 @@ use axum::{
         Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post, put},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get, post, put},
 };
 use db::models::{
+    agent_prompt::AgentPrompt,
+    attempt_artifact::AttemptArtifact,
     coding_agent_turn::CodingAgentTurn,
+    coverage::CoverageReport,
+    e2e_test::{E2eTestReport, E2eTestRun, ProjectE2eSettings},
+    env_template::WorkspacePort,
+    execution_interrupt::ExecutionInterrupt,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_window::DeferredAttempt,
+    lifecycle_hook::LifecycleEvent,
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    preview::{ProjectPreviewSettings, VisualComparison},
     project::SearchResult,
     repo::{Repo, RepoError},
+    security_audit::SecurityFinding,
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
-    workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
+    workspace_environment::WorkspaceEnvironment,
+    workspace_repo::{
+        CreateWorkspaceRepo, CreateWorkspaceRepoCheckout, RepoWithTargetBranch, WorkspaceRepo,
+        WorkspaceRepoCheckout,
+    },
+    workspace_target_sync::WorkspaceTargetSync,
 };
 use deployment::Deployment;
 use executors::{
@@ -45,19 +62,48 @@ use executors::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::{ContainerService, RepoWithName},
+    container::{ContainerError, ContainerService, RepoWithName},
+    coverage,
+    dependency_changes::DependencyChanges,
+    diff_digest::{self, DiffDigest},
+    e2e_tests, env_templates,
+    environment_snapshot::{self, EnvironmentDifference},
     file_search::SearchQuery,
     git::{ConflictOp, GitCliError, GitServiceError},
+    interrupt,
+    jobs::JobKind,
+    lifecycle_hooks,
+    multi_user::{MultiUserService, RequestUser},
+    path_scope, preview_capture,
+    review_checklist::{self, ReviewChecklist},
+    security_audit,
+    sensitive_files::SensitiveFileRules,
+    share::SharedTaskExecutionState,
+    traceability,
+    transcript::{self, Transcript, TranscriptFormat, TranscriptTurn},
+    visual_regression::{self, VisualRegressionError},
     workspace_manager::WorkspaceManager,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{
+    response::ApiResponse,
+    validation::{MAX_TITLE_CHARS, Validate, ValidationErrors},
+};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, ensure_single_user, load_workspace_middleware},
+    routes::{
+        env_templates::SINGLE_USER_ONLY as ENV_TEMPLATES_SINGLE_USER_ONLY,
+        sessions::{self, CreateFollowUpAttempt},
+        shared_tasks,
+        task_attempts::gh_cli_setup::GhCliSetupError,
+    },
+    validation::ValidatedJson,
+    ws_utils::stream_with_heartbeat,
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -72,6 +118,81 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Default, Deserialize, TS)]
+pub struct PromoteWorkspaceRequest {
+    /// Also delete the branches of the archived attempts, after removing
+    /// their worktrees
+    #[serde(default)]
+    pub delete_branches: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PromoteWorkspaceResponse {
+    pub workspace: Workspace,
+    pub archived_workspace_ids: Vec<Uuid>,
+}
+
+/// Mark this attempt as the canonical one for its task. The task's other
+/// attempts are stopped and archived; follow-ups and PRs then go to this one.
+pub async fn promote_workspace(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<PromoteWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<PromoteWorkspaceResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let others: Vec<Workspace> = Workspace::fetch_all(pool, Some(workspace.task_id))
+        .await?
+        .into_iter()
+        .filter(|other| other.id != workspace.id)
+        .collect();
+    for other in &others {
+        deployment.container().try_stop(other, true).await;
+    }
+
+    let archived_workspace_ids = Workspace::promote(pool, workspace.task_id, workspace.id).await?;
+
+    if request.delete_branches {
+        for other in others
+            .iter()
+            .filter(|o| archived_workspace_ids.contains(&o.id))
+        {
+            let repositories = WorkspaceRepo::find_repos_for_workspace(pool, other.id).await?;
+            deployment
+                .jobs()
+                .enqueue_or_log(JobKind::DeleteWorkspaceBranch {
+                    workspace_id: other.id,
+                    workspace_dir: other.container_ref.clone().map(PathBuf::from),
+                    branch: other.branch.clone(),
+                    repositories,
+                })
+                .await;
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_promoted",
+            serde_json::json!({
+                "task_id": workspace.task_id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "archived_count": archived_workspace_ids.len(),
+                "delete_branches": request.delete_branches,
+            }),
+        )
+        .await;
+
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(WorkspaceError::TaskNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(
+        PromoteWorkspaceResponse {
+            workspace,
+            archived_workspace_ids,
+        },
+    )))
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -96,6 +217,21 @@ pub struct DiffStreamQuery {
     pub stats_only: bool,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct DiffDigestQuery {
+    /// Character budget for the digest and for each chunk
+    pub budget: Option<usize>,
+    /// Also split every hunk into budget-sized chunks for map-reduce summaries
+    #[serde(default)]
+    pub chunked: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DiffDigestResponse {
+    pub digest: DiffDigest,
+    pub chunks: Option<Vec<String>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WorkspaceStreamQuery {
     pub archived: Option<bool>,
@@ -109,12 +245,35 @@ pub struct UpdateWorkspace {
     pub name: Option<String>,
 }
 
+impl Validate for UpdateWorkspace {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Some(name) = &self.name {
+            errors.max_chars("name", name, MAX_TITLE_CHARS);
+        }
+        errors.into_result()
+    }
+}
+
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<TaskAttemptQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Workspace>>>, ApiError> {
     let pool = &deployment.db().pool;
-    let workspaces = Workspace::fetch_all(pool, query.task_id).await?;
+    let mut workspaces = Workspace::fetch_all(pool, query.task_id).await?;
+    if let Some(Extension(user)) = user {
+        let mut visible = Vec::with_capacity(workspaces.len());
+        for workspace in workspaces {
+            if MultiUserService::ensure_workspace_access(pool, &user, workspace.id)
+                .await
+                .is_ok()
+            {
+                visible.push(workspace);
+            }
+        }
+        workspaces = visible;
+    }
     Ok(ResponseJson(ApiResponse::success(workspaces)))
 }
 
@@ -135,7 +294,7 @@ pub async fn get_task_attempt(
 pub async fn update_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
-    Json(request): Json<UpdateWorkspace>,
+    ValidatedJson(request): ValidatedJson<UpdateWorkspace>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
     let pool = &deployment.db().pool;
     Workspace::update(
@@ -157,12 +316,109 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Start now even if the project's execution window is closed
+    #[serde(default)]
+    pub ignore_execution_window: bool,
+    /// Start even if the run is expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+impl Validate for CreateTaskAttemptBody {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_repo_inputs(&mut errors, &self.repos);
+        errors.into_result()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
 pub struct WorkspaceRepoInput {
     pub repo_id: Uuid,
     pub target_branch: String,
+    /// Directory of a monorepo to limit the attempt to, e.g. `packages/web`
+    #[serde(default)]
+    #[ts(optional)]
+    pub path_scope: Option<String>,
+    /// Extra checkouts of the repo at other branches, next to the primary one
+    #[serde(default)]
+    #[ts(optional)]
+    pub checkouts: Option<Vec<RepoCheckoutInput>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoCheckoutInput {
+    pub alias: String,
+    pub source_branch: String,
+}
+
+impl WorkspaceRepoInput {
+    pub fn to_create(&self) -> CreateWorkspaceRepo {
+        CreateWorkspaceRepo {
+            repo_id: self.repo_id,
+            target_branch: self.target_branch.clone(),
+            path_scope: self.path_scope.as_deref().and_then(path_scope::normalize),
+        }
+    }
+
+    pub fn to_create_checkouts(&self) -> Vec<CreateWorkspaceRepoCheckout> {
+        self.checkouts
+            .iter()
+            .flatten()
+            .map(|checkout| CreateWorkspaceRepoCheckout {
+                repo_id: self.repo_id,
+                alias: checkout.alias.clone(),
+                source_branch: checkout.source_branch.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Working directory of the coding agent: the repo, or its path scope, for a
+/// single-repo attempt without extra checkouts and the workspace root
+/// otherwise
+pub(crate) async fn agent_working_dir(
+    pool: &SqlitePool,
+    repos: &[WorkspaceRepoInput],
+) -> Result<Option<String>, ApiError> {
+    let [input] = repos else {
+        return Ok(None);
+    };
+    if input.checkouts.as_ref().is_some_and(|c| !c.is_empty()) {
+        return Ok(None);
+    }
+    let repo = Repo::find_by_id(pool, input.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let scope = input.path_scope.as_deref().and_then(path_scope::normalize);
+    Ok(Some(path_scope::agent_working_dir(
+        &repo.name,
+        scope.as_deref(),
+    )))
+}
+
+/// At least one repo, each with a legal target branch and path scope, and
+/// checkouts whose aliases are unique within the workspace
+pub(crate) fn validate_repo_inputs(errors: &mut ValidationErrors, repos: &[WorkspaceRepoInput]) {
+    if repos.is_empty() {
+        errors.add("repos", "at least one repository is required");
+    }
+    let mut aliases = HashSet::new();
+    for (i, repo) in repos.iter().enumerate() {
+        errors.branch_name(&format!("repos[{i}].target_branch"), &repo.target_branch);
+        if let Some(scope) = &repo.path_scope {
+            path_scope::validate(errors, &format!("repos[{i}].path_scope"), scope);
+        }
+        for (j, checkout) in repo.to_create_checkouts().iter().enumerate() {
+            let field = format!("repos[{i}].checkouts[{j}]");
+            errors.nested(&field, checkout.validate());
+            if !aliases.insert(checkout.alias.clone()) {
+                errors.add(&format!("{field}.alias"), "is used by another checkout");
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -176,32 +432,72 @@ pub struct RunAgentSetupResponse {}
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateTaskAttemptBody>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
+    let workspace = create_attempt(&deployment, user.as_deref(), &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(workspace)))
+}
 
-    let pool = &deployment.db().pool;
+/// Check access, budget and the shared task claim, then start the attempt
+/// described by an already validated `payload`
+pub(crate) async fn create_attempt(
+    deployment: &DeploymentImpl,
+    user: Option<&RequestUser>,
+    payload: &CreateTaskAttemptBody,
+) -> Result<Workspace, ApiError> {
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    ensure_project_access(deployment, user, task.project_id).await?;
+    util::ensure_within_budget(
+        &deployment.db().pool,
+        task.project_id,
+        1,
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
+    if let Some(shared_task_id) = task.shared_task_id {
+        shared_tasks::claim_for_attempt(deployment, shared_task_id).await?;
+    }
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
+    start_task_attempt(
+        deployment,
+        &task,
+        &payload.executor_profile_id,
+        &payload.repos,
+        payload.ignore_execution_window,
+    )
+    .await
+}
+
+/// Create a workspace for `task` on `repos` and start `executor_profile_id`
+/// in it. A failed start is logged rather than returned, leaving the
+/// workspace in place so the user can retry from it. Outside the project's
+/// execution window the start is deferred until the window opens, unless
+/// `ignore_execution_window` is set.
+pub(crate) async fn start_task_attempt(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    executor_profile_id: &ExecutorProfileId,
+    repos: &[WorkspaceRepoInput],
+    ignore_execution_window: bool,
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
+
+    lifecycle_hooks::run(
+        pool,
+        task.project_id,
+        LifecycleEvent::PreAttempt,
+        serde_json::json!({
+            "task": task,
+            "executor_profile_id": executor_profile_id,
+            "repos": repos,
+        }),
+    )
+    .await?;
+
+    let agent_working_dir = agent_working_dir(pool, repos).await?;
 
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
@@ -216,26 +512,47 @@ pub async fn create_task_attempt(
             agent_working_dir,
         },
         attempt_id,
-        payload.task_id,
+        task.id,
     )
     .await?;
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
-        .iter()
-        .map(|r| CreateWorkspaceRepo {
-            repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
-        })
-        .collect();
+    let workspace_repos: Vec<CreateWorkspaceRepo> =
+        repos.iter().map(WorkspaceRepoInput::to_create).collect();
 
     WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
-    if let Err(err) = deployment
-        .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
-        .await
+    for checkout in repos
+        .iter()
+        .flat_map(WorkspaceRepoInput::to_create_checkouts)
     {
-        tracing::error!("Failed to start task attempt: {}", err);
+        WorkspaceRepoCheckout::create(pool, workspace.id, &workspace.branch, &checkout).await?;
+    }
+
+    let deferred_until = if ignore_execution_window {
+        None
+    } else {
+        util::execution_window_opens_at(pool, task.project_id).await?
+    };
+    if let Some(opens_at) = deferred_until {
+        DeferredAttempt::create(pool, workspace.id, executor_profile_id).await?;
+        tracing::info!(
+            "Deferred attempt {} until the execution window opens at {}",
+            workspace.id,
+            opens_at
+        );
+    } else {
+        match deployment
+            .container()
+            .start_workspace(&workspace, executor_profile_id.clone())
+            .await
+        {
+            Ok(_) => util::spawn_execution_report(
+                deployment,
+                task.clone(),
+                SharedTaskExecutionState::Started,
+                Some(executor_profile_id.executor.to_string()),
+            ),
+            Err(err) => tracing::error!("Failed to start task attempt: {}", err),
+        }
     }
 
     deployment
@@ -246,14 +563,15 @@ pub async fn create_task_attempt(
                 "variant": &executor_profile_id.variant,
                 "executor": &executor_profile_id.executor,
                 "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
+                "repository_count": repos.len(),
+                "deferred": deferred_until.is_some(),
             }),
         )
         .await;
 
     tracing::info!("Created attempt for task {}", task.id);
 
-    Ok(ResponseJson(ApiResponse::success(workspace)))
+    Ok(workspace)
 }
 
 #[axum::debug_handler]
@@ -352,6 +670,35 @@ async fn handle_task_attempt_diff_ws(
     Ok(())
 }
 
+pub async fn stream_worktree_state_ws(
+    ws: WebSocketUpgrade,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_worktree_state_ws(socket, deployment, workspace).await {
+            tracing::warn!("worktree state WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_worktree_state_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    workspace: Workspace,
+) -> anyhow::Result<()> {
+    use futures_util::TryStreamExt;
+
+    let stream = deployment
+        .events()
+        .stream_worktree_state_raw(&workspace)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked())
+        .map_err(|e| anyhow::anyhow!("{}", e));
+
+    stream_with_heartbeat(socket, stream).await
+}
+
 pub async fn stream_workspaces_ws(
     ws: WebSocketUpgrade,
     Query(query): Query<WorkspaceStreamQuery>,
@@ -457,6 +804,9 @@ pub async fn merge_task_attempt(
         commit_message.push_str("\n\n");
         commit_message.push_str(description);
     }
+    if let Some(key) = deployment.config().read().await.task_trailer.clone() {
+        commit_message = traceability::append_trailer(&commit_message, &key, task.id);
+    }
 
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
@@ -478,6 +828,19 @@ pub async fn merge_task_attempt(
     if !workspace.pinned {
         Workspace::set_archived(pool, workspace.id, true).await?;
     }
+    lifecycle_hooks::spawn(
+        pool.clone(),
+        task.project_id,
+        LifecycleEvent::PostMerge,
+        serde_json::json!({
+            "task_id": task.id,
+            "workspace_id": workspace.id,
+            "repo_id": workspace_repo.repo_id,
+            "branch": &workspace.branch,
+            "target_branch": &workspace_repo.target_branch,
+            "merge_commit": &merge_commit_id,
+        }),
+    );
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -540,10 +903,16 @@ pub async fn push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    match deployment
-        .git()
-        .push_to_remote(&worktree_path, &workspace.branch, false)
-    {
+    util::run_pre_push_hooks(
+        pool,
+        &workspace,
+        repo.id,
+        &worktree_path,
+        &workspace.branch,
+        false,
+    )
+    .await?;
+    match util::push_branch(worktree_path, workspace.branch.clone(), false).await {
         Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
         Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => Ok(ResponseJson(
             ApiResponse::error_with_data(PushError::ForcePushRequired),
@@ -575,9 +944,16 @@ pub async fn force_push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    deployment
-        .git()
-        .push_to_remote(&worktree_path, &workspace.branch, true)?;
+    util::run_pre_push_hooks(
+        pool,
+        &workspace,
+        repo.id,
+        &worktree_path,
+        &workspace.branch,
+        true,
+    )
+    .await?;
+    util::push_branch(worktree_path, workspace.branch.clone(), true).await?;
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -670,6 +1046,93 @@ pub async fn open_task_attempt_in_editor(
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct EditorLinkQuery {
+    /// Repo the file belongs to; may be left out for single-repo attempts
+    pub repo_id: Option<Uuid>,
+    /// Relative to the repo root, e.g. the path of a PR review comment
+    pub file_path: String,
+    /// 1-based
+    pub line: Option<u32>,
+    /// 1-based
+    pub column: Option<u32>,
+    pub editor_type: Option<String>,
+}
+
+impl Validate for EditorLinkQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.file_path.trim().is_empty() {
+            errors.add("file_path", "must not be empty");
+        }
+        path_scope::validate(&mut errors, "file_path", &self.file_path);
+        if self.line == Some(0) {
+            errors.add("line", "must be at least 1");
+        }
+        if self.column == Some(0) {
+            errors.add("column", "must be at least 1");
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct EditorLinkResponse {
+    /// Deep link into the editor, or `null` when it has no URL scheme
+    pub url: Option<String>,
+    /// Absolute path of the file in the attempt's worktree
+    pub path: String,
+}
+
+/// Directory of one of the attempt's repos inside its worktree. `repo_id`
+/// may be left out when the attempt has a single repo.
+pub(crate) async fn workspace_repo_root(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Option<Uuid>,
+) -> Result<PathBuf, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+    let repo = match repo_id {
+        Some(repo_id) => repos.iter().find(|repo| repo.id == repo_id),
+        None if repos.len() == 1 => repos.first(),
+        None => {
+            return Err(ApiError::BadRequest(
+                "repo_id is required for attempts with several repos".to_string(),
+            ));
+        }
+    }
+    .ok_or(RepoError::NotFound)?;
+    Ok(Path::new(&container_ref).join(&repo.name))
+}
+
+/// Editor deep link (vscode://, jetbrains://, zed://, ...) to a line of a file
+/// in the attempt's worktree
+pub async fn get_editor_link(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<EditorLinkQuery>,
+) -> Result<ResponseJson<ApiResponse<EditorLinkResponse>>, ApiError> {
+    query.validate().map_err(ApiError::Validation)?;
+    let repo_root = workspace_repo_root(&deployment, &workspace, query.repo_id).await?;
+    let file_path = path_scope::normalize(&query.file_path).unwrap_or_default();
+
+    let editor_config = {
+        let config = deployment.config().read().await;
+        config.editor.with_override(query.editor_type.as_deref())
+    };
+    let url = editor_config.deep_link(&repo_root, &file_path, query.line, query.column);
+
+    Ok(ResponseJson(ApiResponse::success(EditorLinkResponse {
+        url,
+        path: repo_root.join(&file_path).to_string_lossy().into_owned(),
+    })))
+}
+
 pub async fn open_task_attempt_in_terminal(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -904,6 +1367,14 @@ pub struct ChangeTargetBranchRequest {
     pub new_target_branch: String,
 }
 
+impl Validate for ChangeTargetBranchRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.branch_name("new_target_branch", &self.new_target_branch);
+        errors.into_result()
+    }
+}
+
 #[derive(serde::Serialize, Debug, TS)]
 pub struct ChangeTargetBranchResponse {
     pub repo_id: Uuid,
@@ -937,7 +1408,7 @@ pub enum RenameBranchError {
 pub async fn change_target_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<ChangeTargetBranchRequest>,
+    ValidatedJson(payload): ValidatedJson<ChangeTargetBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<ChangeTargetBranchResponse>>, ApiError> {
     let repo_id = payload.repo_id;
     let new_target_branch = payload.new_target_branch;
@@ -1214,6 +1685,9 @@ pub async fn rebase_task_attempt(
         };
     }
 
+    WorkspaceTargetSync::mark_rebased(pool, workspace.id, payload.repo_id, &new_base_branch)
+        .await?;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1415,6 +1889,90 @@ pub async fn stop_task_attempt_execution(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// A deferred attempt and when its project's execution window next opens
+#[derive(Debug, Serialize, TS)]
+pub struct DeferredStart {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub attempt: DeferredAttempt,
+    /// `None` once the window is open and the attempt is about to start
+    pub starts_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// `None` unless the attempt is waiting for its project's execution window
+pub async fn get_deferred_start(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<DeferredStart>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(attempt) = DeferredAttempt::find_by_workspace_id(pool, workspace.id).await? else {
+        return Ok(ResponseJson(ApiResponse::success(None)));
+    };
+    let starts_at = util::execution_window_opens_at(pool, attempt.project_id)
+        .await?
+        .and_then(|opens_at| opens_at.and_local_timezone(chrono::Local).earliest())
+        .map(|opens_at| opens_at.with_timezone(&chrono::Utc));
+    Ok(ResponseJson(ApiResponse::success(Some(DeferredStart {
+        attempt,
+        starts_at,
+    }))))
+}
+
+/// Stop the running agent turn gracefully and keep what it had produced, so
+/// the next follow-up resumes with that context
+pub async fn interrupt_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionInterrupt>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let process = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?
+    .filter(|process| process.status == ExecutionProcessStatus::Running)
+    .ok_or_else(|| ApiError::BadRequest("No agent turn is running".to_string()))?;
+
+    // Hold on to the log store: stopping drops it from the container, but the
+    // agent keeps writing while it winds down
+    let store = deployment
+        .container()
+        .get_msg_store_by_id(&process.id)
+        .await;
+    deployment
+        .container()
+        .stop_execution(&process, ExecutionProcessStatus::Killed)
+        .await?;
+    let entries = match store {
+        Some(store) => transcript::entries_from_history(&store.get_history()),
+        None => transcript::collect_entries(deployment.container(), process.id).await,
+    };
+
+    let agent_session_id = CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+        .await?
+        .and_then(|turn| turn.agent_session_id);
+    let interrupted = ExecutionInterrupt::create(
+        pool,
+        process.id,
+        agent_session_id.as_deref(),
+        interrupt::partial_output(&entries).as_deref(),
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_interrupted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "has_partial_output": interrupted.partial_output.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(interrupted)))
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -1680,6 +2238,603 @@ pub async fn get_first_user_message(
     Ok(ResponseJson(ApiResponse::success(message)))
 }
 
+/// Latest coverage report per repo of the attempt
+pub async fn get_coverage(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CoverageReport>>>, ApiError> {
+    let reports =
+        CoverageReport::find_latest_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+/// Run the coverage hooks now instead of waiting for the next execution
+pub async fn measure_coverage(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CoverageReport>>>, ApiError> {
+    let reports = coverage::measure_workspace(&deployment.db().pool, &workspace)
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+/// Latest browser test run of the attempt, failures first
+pub async fn get_e2e_tests(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<E2eTestReport>>>, ApiError> {
+    let report = E2eTestRun::find_latest_report(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Run the project's browser tests now instead of waiting for the next
+/// execution
+pub async fn run_e2e_tests(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<E2eTestReport>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let settings = ProjectE2eSettings::find_by_project_id(pool, task.project_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("The project has no browser test hook".to_string()))?;
+
+    let url = match settings.url.clone().filter(|url| !url.trim().is_empty()) {
+        Some(url) => url,
+        None => deployment
+            .container()
+            .dev_server_url(workspace.id)
+            .await
+            .ok_or_else(|| {
+                ApiError::BadRequest("Start the dev server to run browser tests".to_string())
+            })?,
+    };
+    let report = e2e_tests::run_workspace(pool, &workspace, &settings, None, &url)
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Screenshot the attempt's dev server now, whatever the task's labels
+pub async fn capture_preview(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptArtifact>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let settings = ProjectPreviewSettings::find_by_project_id(pool, task.project_id).await?;
+
+    let configured_url = settings
+        .as_ref()
+        .and_then(|settings| settings.url.clone())
+        .filter(|url| !url.trim().is_empty());
+    let url = match configured_url {
+        Some(url) => url,
+        None => deployment
+            .container()
+            .dev_server_url(workspace.id)
+            .await
+            .ok_or(preview_capture::PreviewError::NoDevServer)?,
+    };
+    let command = settings
+        .map(|settings| settings.command)
+        .unwrap_or_default();
+    let artifact = preview_capture::capture(pool, &command, workspace.id, None, &url).await?;
+    Ok(ResponseJson(ApiResponse::success(artifact)))
+}
+
+pub async fn get_visual_comparisons(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VisualComparison>>>, ApiError> {
+    let comparisons =
+        VisualComparison::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(comparisons)))
+}
+
+/// Compare the project's routes with the target branch now instead of
+/// waiting for the next execution
+pub async fn compare_visuals(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VisualComparison>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let settings = ProjectPreviewSettings::find_by_project_id(pool, task.project_id)
+        .await?
+        .ok_or(VisualRegressionError::NotConfigured)?;
+
+    let url = match settings.url.clone().filter(|url| !url.trim().is_empty()) {
+        Some(url) => url,
+        None => deployment
+            .container()
+            .dev_server_url(workspace.id)
+            .await
+            .ok_or(preview_capture::PreviewError::NoDevServer)?,
+    };
+    let comparisons = visual_regression::compare_workspace(
+        pool,
+        deployment.git(),
+        &workspace,
+        &settings,
+        None,
+        &url,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(comparisons)))
+}
+
+/// Budgeted summary of the attempt diff for title and description prompts
+pub async fn get_diff_digest(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffDigestQuery>,
+) -> Result<ResponseJson<ApiResponse<DiffDigestResponse>>, ApiError> {
+    let budget = query.budget.unwrap_or(diff_digest::DEFAULT_BUDGET);
+    let diffs = util::workspace_diffs(&deployment, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(DiffDigestResponse {
+        digest: diff_digest::digest(&diffs, budget),
+        chunks: query.chunked.then(|| diff_digest::chunks(&diffs, budget)),
+    })))
+}
+
+/// Dependencies added, removed or re-versioned by the attempt
+pub async fn get_dependency_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DependencyChanges>>, ApiError> {
+    let changes = util::workspace_dependency_changes(&deployment, &workspace, None).await?;
+    Ok(ResponseJson(ApiResponse::success(changes)))
+}
+
+/// Environment snapshots of the attempt's coding agent runs, newest first
+pub async fn get_environment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceEnvironment>>>, ApiError> {
+    let snapshots =
+        WorkspaceEnvironment::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct EnvironmentCompareQuery {
+    /// Attempt to compare against
+    pub other: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct EnvironmentComparison {
+    pub left: WorkspaceEnvironment,
+    pub right: WorkspaceEnvironment,
+    pub differences: Vec<EnvironmentDifference>,
+}
+
+/// Compare the latest environment of this attempt with another attempt's
+pub async fn compare_environment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<EnvironmentCompareQuery>,
+) -> Result<ResponseJson<ApiResponse<EnvironmentComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let other = Workspace::find_by_id(pool, query.other)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let other_task = other
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ensure_project_access(&deployment, user.as_deref(), other_task.project_id).await?;
+
+    let missing = |id: Uuid| {
+        ApiError::BadRequest(format!(
+            "Attempt {id} has no recorded environment; it is captured when its coding agent runs"
+        ))
+    };
+    let left = WorkspaceEnvironment::find_latest(pool, workspace.id)
+        .await?
+        .ok_or_else(|| missing(workspace.id))?;
+    let right = WorkspaceEnvironment::find_latest(pool, other.id)
+        .await?
+        .ok_or_else(|| missing(other.id))?;
+    let differences = environment_snapshot::compare(&left, &right);
+    Ok(ResponseJson(ApiResponse::success(EnvironmentComparison {
+        left,
+        right,
+        differences,
+    })))
+}
+
+/// Ports allocated to the attempt by its env templates
+pub async fn get_ports(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspacePort>>>, ApiError> {
+    let ports = WorkspacePort::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(ports)))
+}
+
+/// Render the project's env templates into the attempt's worktrees again,
+/// replacing the files written before. Returns the paths written.
+pub async fn rewrite_env_files(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+) -> Result<ResponseJson<ApiResponse<Vec<String>>>, ApiError> {
+    ensure_single_user(user.as_deref(), ENV_TEMPLATES_SINGLE_USER_ONLY)?;
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let written = env_templates::materialize(
+        pool,
+        deployment.secrets(),
+        task.project_id,
+        &workspace,
+        Path::new(&container_ref),
+        &repos,
+        true,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        written
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+    )))
+}
+
+/// Extra checkouts of the attempt's repos
+/// How far each repo of the attempt was behind its target branch when the
+/// background sync last checked
+pub async fn get_target_sync(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceTargetSync>>>, ApiError> {
+    let syncs =
+        WorkspaceTargetSync::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(syncs)))
+}
+
+pub async fn get_checkouts(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceRepoCheckout>>>, ApiError> {
+    let checkouts =
+        WorkspaceRepoCheckout::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checkouts)))
+}
+
+/// Add a checkout of one of the attempt's repos at another branch. Its
+/// worktree is created right away when the attempt's worktrees exist.
+pub async fn create_checkout(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<CreateWorkspaceRepoCheckout>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceRepoCheckout>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository is not part of this attempt".to_string(),
+        ));
+    }
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let Some(repo) = repos
+        .iter()
+        .find(|repo| repo.id == payload.repo_id)
+        .cloned()
+    else {
+        return Err(ApiError::BadRequest("Repository not found".to_string()));
+    };
+    let existing = WorkspaceRepoCheckout::find_by_workspace_id(pool, workspace.id).await?;
+    if existing
+        .iter()
+        .any(|checkout| checkout.alias == payload.alias)
+    {
+        return Err(ApiError::Conflict(format!(
+            "A checkout named '{}' already exists",
+            payload.alias
+        )));
+    }
+    let dir_name = format!("{}-{}", repo.name, payload.alias);
+    if repos.iter().any(|other| other.name == dir_name) {
+        return Err(ApiError::Conflict(format!(
+            "'{dir_name}' is already used by a repository of this attempt"
+        )));
+    }
+
+    let checkout =
+        WorkspaceRepoCheckout::create(pool, workspace.id, &workspace.branch, &payload).await?;
+    if let Some(container_ref) = &workspace.container_ref
+        && let Err(e) = WorkspaceManager::ensure_checkouts_exist(
+            Path::new(container_ref),
+            &[(repo, checkout.clone())],
+        )
+        .await
+    {
+        WorkspaceRepoCheckout::delete(pool, checkout.id).await?;
+        return Err(ContainerError::from(e).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(checkout)))
+}
+
+/// Remove a checkout and its worktree. Its branch is kept.
+pub async fn delete_checkout(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    axum::extract::Path((_id, checkout_id)): axum::extract::Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let checkout = WorkspaceRepoCheckout::find_by_id(pool, checkout_id)
+        .await?
+        .filter(|checkout| checkout.workspace_id == workspace.id)
+        .ok_or_else(|| ApiError::BadRequest("Checkout not found".to_string()))?;
+    if let Some(container_ref) = &workspace.container_ref
+        && let Some(repo) = Repo::find_by_id(pool, checkout.repo_id).await?
+    {
+        WorkspaceManager::remove_checkout(Path::new(container_ref), &repo, &checkout)
+            .await
+            .map_err(ContainerError::from)?;
+    }
+    WorkspaceRepoCheckout::delete(pool, checkout.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Findings of the attempt's latest security audit, most severe first
+pub async fn get_security_findings(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecurityFinding>>>, ApiError> {
+    let findings =
+        SecurityFinding::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(findings)))
+}
+
+/// Run the configured security scanners now instead of waiting for the next execution
+pub async fn run_security_audit(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecurityFinding>>>, ApiError> {
+    let findings =
+        security_audit::audit_workspace(&deployment.db().pool, deployment.git(), &workspace)
+            .await
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(findings)))
+}
+
+/// Analyze the workspace diff and store a checklist for human reviewers
+pub async fn generate_review_checklist(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ReviewChecklist>>, ApiError> {
+    let diffs = util::workspace_diffs(&deployment, &workspace).await?;
+    let checklist = review_checklist::generate(&diffs);
+    Workspace::update_review_checklist(&deployment.db().pool, workspace.id, &checklist.markdown)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(checklist)))
+}
+
+/// Markdown of the last generated review checklist, if any
+pub async fn get_review_checklist(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, ApiError> {
+    let checklist = Workspace::find_review_checklist(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checklist)))
+}
+
+/// Every prompt sent to an agent in this attempt, oldest first
+pub async fn get_prompt_history(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AgentPrompt>>>, ApiError> {
+    let prompts = AgentPrompt::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(prompts)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReplayPromptRequest {
+    pub prompt_id: Uuid,
+    /// Edited text to send instead of the original
+    pub prompt: Option<String>,
+    /// Start even if the run is expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+/// Send an earlier prompt again, optionally edited, as a new follow-up in
+/// the session it was first sent in
+pub async fn replay_prompt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ReplayPromptRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let not_found = || ApiError::BadRequest("Prompt not found".to_string());
+    let original = AgentPrompt::find_by_id(pool, request.prompt_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let session = Session::find_by_id(pool, original.session_id)
+        .await?
+        .filter(|session| session.workspace_id == workspace.id)
+        .ok_or_else(not_found)?;
+    let executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest("Session has no coding agent to replay with".to_string())
+            })?;
+
+    let prompt = request
+        .prompt
+        .filter(|prompt| !prompt.trim().is_empty())
+        .unwrap_or(original.prompt);
+    let ResponseJson(response) = sessions::follow_up(
+        Extension(session),
+        State(deployment.clone()),
+        Json(CreateFollowUpAttempt {
+            prompt,
+            executor_profile_id,
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+            confirm_over_budget: request.confirm_over_budget,
+        }),
+    )
+    .await?;
+    let execution_process = response
+        .into_data()
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    AgentPrompt::mark_replayed_from(pool, execution_process.id, original.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "agent_prompt_replayed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "kind": original.kind,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Export every coding agent run of the attempt with its prompt, as Markdown
+/// or JSON. Contents of sensitive files are stripped first.
+pub async fn get_transcript(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let prompts: HashMap<Uuid, AgentPrompt> = AgentPrompt::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|prompt| (prompt.execution_process_id, prompt))
+        .collect();
+
+    let mut processes = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+        processes.extend(
+            ExecutionProcess::find_by_session_id(pool, session.id, false)
+                .await?
+                .into_iter()
+                .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent),
+        );
+    }
+    processes.sort_by_key(|process| process.started_at);
+
+    let mut turns = Vec::with_capacity(processes.len());
+    for process in processes {
+        let (prompt_kind, prompt) = match prompts.get(&process.id) {
+            Some(recorded) => (Some(recorded.kind), Some(recorded.prompt.clone())),
+            None => (
+                None,
+                CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+                    .await?
+                    .and_then(|turn| turn.prompt),
+            ),
+        };
+        turns.push(TranscriptTurn {
+            execution_process_id: process.id,
+            executor: process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.base_executor())
+                .map(|executor| executor.to_string()),
+            prompt_kind,
+            prompt,
+            started_at: process.started_at,
+            entries: transcript::collect_entries(deployment.container(), process.id).await,
+        });
+    }
+
+    let mut export = Transcript {
+        workspace_id: workspace.id,
+        task_title: task.title,
+        exported_at: chrono::Utc::now(),
+        turns,
+    };
+    let global_sensitive = deployment
+        .config()
+        .read()
+        .await
+        .sensitive_file_patterns
+        .clone();
+    let sensitive = SensitiveFileRules::for_workspace(pool, &global_sensitive, &workspace).await?;
+    transcript::sanitize(
+        &mut export,
+        &sensitive,
+        workspace.container_ref.as_deref().map(Path::new),
+    );
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_transcript_exported",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "format": match query.format {
+                    TranscriptFormat::Markdown => "markdown",
+                    TranscriptFormat::Json => "json",
+                },
+                "turns": export.turns.len(),
+            }),
+        )
+        .await;
+
+    Ok(match query.format {
+        TranscriptFormat::Json => ResponseJson(ApiResponse::success(export)).into_response(),
+        TranscriptFormat::Markdown => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/markdown; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"transcript-{}.md\"", workspace.id),
+                ),
+            ],
+            transcript::to_markdown(&export),
+        )
+            .into_response(),
+    })
+}
+
 pub async fn delete_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -1752,31 +2907,16 @@ pub async fn delete_workspace(
         )
         .await;
 
-    // Spawn background cleanup task for filesystem resources
+    // Queue cleanup of filesystem resources
     if let Some(workspace_dir) = workspace_dir {
-        let workspace_id = workspace.id;
-        tokio::spawn(async move {
-            tracing::info!(
-                "Starting background cleanup for workspace {} at {}",
-                workspace_id,
-                workspace_dir.display()
-            );
-
-            if let Err(e) = WorkspaceManager::cleanup_workspace(&workspace_dir, &repositories).await
-            {
-                tracing::error!(
-                    "Background workspace cleanup failed for {} at {}: {}",
-                    workspace_id,
-                    workspace_dir.display(),
-                    e
-                );
-            } else {
-                tracing::info!(
-                    "Background cleanup completed for workspace {}",
-                    workspace_id
-                );
-            }
-        });
+        deployment
+            .jobs()
+            .enqueue_or_log(JobKind::CleanupWorkspace {
+                workspace_id: workspace.id,
+                workspace_dir,
+                repositories,
+            })
+            .await;
     }
 
     // Return 202 Accepted to indicate deletion was scheduled
@@ -1811,23 +2951,54 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/run-cleanup-script", post(run_cleanup_script))
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
+        .route("/worktree-state/ws", get(stream_worktree_state_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/target-sync", get(get_target_sync))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
         .route("/pr", post(pr::create_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/comments/{comment_id}/open", post(pr::open_pr_comment))
         .route("/open-editor", post(open_task_attempt_in_editor))
+        .route("/editor-link", get(get_editor_link))
         .route("/open-terminal", post(open_task_attempt_in_terminal))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
+        .route("/interrupt", post(interrupt_task_attempt))
+        .route("/deferred-start", get(get_deferred_start))
+        .route("/promote", post(promote_workspace))
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
         .route("/repos", get(get_task_attempt_repos))
         .route("/search", get(search_workspace_files))
         .route("/first-message", get(get_first_user_message))
+        .route("/coverage", get(get_coverage).post(measure_coverage))
+        .route("/e2e-tests", get(get_e2e_tests).post(run_e2e_tests))
+        .route("/preview", post(capture_preview))
+        .route(
+            "/visual-comparisons",
+            get(get_visual_comparisons).post(compare_visuals),
+        )
+        .route("/dependency-changes", get(get_dependency_changes))
+        .route("/diff-digest", get(get_diff_digest))
+        .route("/environment", get(get_environment))
+        .route("/environment/compare", get(compare_environment))
+        .route("/ports", get(get_ports))
+        .route("/env-files", post(rewrite_env_files))
+        .route("/checkouts", get(get_checkouts).post(create_checkout))
+        .route("/checkouts/{checkout_id}", delete(delete_checkout))
+        .route("/security-findings", get(get_security_findings))
+        .route("/security-audit", post(run_security_audit))
+        .route(
+            "/review-checklist",
+            get(get_review_checklist).post(generate_review_checklist),
+        )
+        .route("/prompts", get(get_prompt_history))
+        .route("/prompts/replay", post(replay_prompt))
+        .route("/transcript", get(get_transcript))
         .route("/mark-seen", put(mark_seen))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -1839,8 +3010,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/count", get(get_workspace_count))
         .route("/stream/ws", get(stream_workspaces_ws))
         .route("/summary", post(workspace_summary::get_workspace_summaries))
+        .route("/compare", post(comparison::compare_executors))
+        .route(
+            "/comparisons/{comparison_group_id}",
+            get(comparison::get_comparison_report),
+        )
         .nest("/{id}", task_attempt_id_router)
-        .nest("/{id}/images", images::router(deployment));
+        .nest("/{id}/images", images::router(deployment))
+        .nest("/{id}/artifacts", artifacts::router(deployment));
 
     Router::new().nest("/task-attempts", task_attempts_router)
 }
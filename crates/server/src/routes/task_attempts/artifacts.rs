@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    body::Body,
+    extract::{Path, Request, State},
+    http::{StatusCode, header},
+    middleware::{Next, from_fn_with_state},
+    response::{Json as ResponseJson, Response},
+    routing::{delete, get},
+};
+use db::models::{attempt_artifact::AttemptArtifact, workspace::Workspace};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{artifacts, container::ContainerService, multi_user::RequestUser};
+use tokio::fs::File;
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{authorize_workspace, load_workspace_middleware},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RegisterArtifactRequest {
+    /// File to keep, relative to the workspace root
+    pub path: String,
+    /// Name inside the artifacts directory; defaults to `path`
+    pub name: Option<String>,
+    pub execution_process_id: Option<Uuid>,
+}
+
+pub async fn list_artifacts(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptArtifact>>>, ApiError> {
+    let artifacts =
+        AttemptArtifact::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(artifacts)))
+}
+
+/// Keep a file from the worktree, such as a build output the agent left
+/// outside the artifacts directory
+pub async fn register_artifact(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RegisterArtifactRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptArtifact>>, ApiError> {
+    let relative = artifacts::validate_name(&payload.path)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_root = tokio::fs::canonicalize(PathBuf::from(container_ref)).await?;
+    let source = tokio::fs::canonicalize(workspace_root.join(&relative))
+        .await
+        .map_err(|_| ApiError::BadRequest(format!("{} does not exist", payload.path)))?;
+    // A symlink inside the worktree must not expose files outside of it
+    if !source.starts_with(&workspace_root) {
+        return Err(ApiError::BadRequest(format!(
+            "{} is outside the workspace",
+            payload.path
+        )));
+    }
+
+    let artifact = artifacts::register(
+        &deployment.db().pool,
+        workspace.id,
+        payload.execution_process_id,
+        &source,
+        payload.name.as_deref().unwrap_or(&relative),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(artifact)))
+}
+
+pub async fn download_artifact(
+    Extension(artifact): Extension<AttemptArtifact>,
+) -> Result<Response, ApiError> {
+    let file = File::open(artifacts::absolute_path(&artifact))
+        .await
+        .map_err(|_| ApiError::Database(sqlx::Error::RowNotFound))?;
+    let size = file.metadata().await?.len();
+    let file_name = artifact
+        .name
+        .rsplit('/')
+        .next()
+        .unwrap_or(&artifact.name)
+        .replace('"', "");
+    let content_type = mime_guess::from_path(&artifact.name).first_or_octet_stream();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type.as_ref())
+        .header(header::CONTENT_LENGTH, size)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        )
+        .body(Body::from_stream(ReaderStream::new(file)))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+pub async fn delete_artifact(
+    Extension(artifact): Extension<AttemptArtifact>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    artifacts::delete(&deployment.db().pool, &artifact).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Load the artifact of a workspace the caller may access
+async fn load_artifact_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, artifact_id)): Path<(Uuid, Uuid)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let artifact = match AttemptArtifact::find_by_id(&deployment.db().pool, artifact_id).await {
+        Ok(Some(artifact)) if artifact.workspace_id == workspace_id => artifact,
+        Ok(_) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch artifact {}: {}", artifact_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let user = request.extensions().get::<RequestUser>().copied();
+    authorize_workspace(&deployment, user.as_ref(), workspace_id).await?;
+
+    request.extensions_mut().insert(artifact);
+    Ok(next.run(request).await)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let list_router = Router::new()
+        .route("/", get(list_artifacts).post(register_artifact))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let artifact_router = Router::new()
+        .route("/{artifact_id}", delete(delete_artifact))
+        .route("/{artifact_id}/file", get(download_artifact))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_artifact_middleware,
+        ));
+
+    list_router.merge(artifact_router)
+}
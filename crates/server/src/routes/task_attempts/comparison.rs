@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    attempt_comparison::AttemptRunStats,
+    coverage::{CoverageReport, RepoCoverageSettings},
+    execution_process::ExecutionProcess,
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::multi_user::RequestUser;
+use sqlx::{Error as SqlxError, SqlitePool};
+use ts_rs::TS;
+use utils::{
+    response::ApiResponse,
+    validation::{Validate, ValidationErrors},
+};
+use uuid::Uuid;
+
+use super::{
+    WorkspaceRepoInput, start_task_attempt, util, validate_repo_inputs,
+    workspace_summary::compute_workspace_diff_stats,
+};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_project_access, routes::shared_tasks,
+    validation::ValidatedJson,
+};
+
+/// Upper bound on attempts per comparison; each one is a full agent run
+pub const MAX_COMPARISON_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CompareExecutorsRequest {
+    pub task_id: Uuid,
+    /// One attempt is started per profile
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<WorkspaceRepoInput>,
+    /// Start now even if the project's execution window is closed
+    #[serde(default)]
+    pub ignore_execution_window: bool,
+    /// Start even if the runs are expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+impl Validate for CompareExecutorsRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if !(2..=MAX_COMPARISON_ATTEMPTS).contains(&self.executor_profile_ids.len()) {
+            errors.add(
+                "executor_profile_ids",
+                format!("must list between 2 and {MAX_COMPARISON_ATTEMPTS} profiles"),
+            );
+        }
+        validate_repo_inputs(&mut errors, &self.repos);
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CompareExecutorsResponse {
+    pub comparison_group_id: Uuid,
+    pub workspaces: Vec<Workspace>,
+}
+
+/// One attempt's row in a comparison report
+#[derive(Debug, Serialize, TS)]
+pub struct ComparisonAttempt {
+    pub workspace_id: Uuid,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// A coding agent run is still going; the other numbers are partial
+    pub running: bool,
+    /// One of its coding agent runs failed or was killed
+    pub failed: bool,
+    pub files_changed: Option<usize>,
+    pub lines_added: Option<usize>,
+    pub lines_removed: Option<usize>,
+    /// Whether every repo's coverage hook produced a report; `None` when no
+    /// repo has one configured or the attempt is still running
+    pub tests_passed: Option<bool>,
+    pub agent_seconds: Option<f64>,
+    #[ts(type = "number | null")]
+    pub total_tokens: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ComparisonReport {
+    pub comparison_group_id: Uuid,
+    pub task_id: Uuid,
+    pub attempts: Vec<ComparisonAttempt>,
+}
+
+/// Start the same task with several executor profiles, one workspace each,
+/// tagged as a comparison group
+pub async fn compare_executors(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CompareExecutorsRequest>,
+) -> Result<ResponseJson<ApiResponse<CompareExecutorsResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ensure_project_access(&deployment, user.as_deref(), task.project_id).await?;
+    util::ensure_within_budget(
+        pool,
+        task.project_id,
+        payload.executor_profile_ids.len(),
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
+    if let Some(shared_task_id) = task.shared_task_id {
+        shared_tasks::claim_for_attempt(&deployment, shared_task_id).await?;
+    }
+
+    let comparison_group_id = Uuid::new_v4();
+    let mut workspaces = Vec::with_capacity(payload.executor_profile_ids.len());
+    for executor_profile_id in &payload.executor_profile_ids {
+        let workspace = start_task_attempt(
+            &deployment,
+            &task,
+            executor_profile_id,
+            &payload.repos,
+            payload.ignore_execution_window,
+        )
+        .await?;
+        Workspace::set_comparison_group(pool, workspace.id, comparison_group_id).await?;
+        workspaces.push(workspace);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "executor_comparison_started",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "comparison_group_id": comparison_group_id.to_string(),
+                "executors": payload
+                    .executor_profile_ids
+                    .iter()
+                    .map(|profile| profile.to_string())
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CompareExecutorsResponse {
+            comparison_group_id,
+            workspaces,
+        },
+    )))
+}
+
+pub async fn get_comparison_report(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path(comparison_group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ComparisonReport>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspaces = Workspace::find_by_comparison_group(pool, comparison_group_id).await?;
+    let Some(first) = workspaces.first() else {
+        return Err(ApiError::BadRequest("Comparison not found".to_string()));
+    };
+    let task = Task::find_by_id(pool, first.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ensure_project_access(&deployment, user.as_deref(), task.project_id).await?;
+
+    let run_stats: HashMap<Uuid, AttemptRunStats> =
+        AttemptRunStats::find_by_comparison_group(pool, comparison_group_id)
+            .await?
+            .into_iter()
+            .map(|stats| (stats.workspace_id, stats))
+            .collect();
+
+    let mut attempts = Vec::with_capacity(workspaces.len());
+    for workspace in &workspaces {
+        let stats = run_stats.get(&workspace.id);
+        let running = stats.is_some_and(|stats| stats.running);
+        let diff = if workspace.container_ref.is_some() {
+            compute_workspace_diff_stats(&deployment, workspace)
+                .await
+                .ok()
+        } else {
+            None
+        };
+        let executor_profile_id =
+            match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+                Some(session) => {
+                    ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?
+                }
+                None => None,
+            };
+        let tests_passed = if running {
+            None
+        } else {
+            coverage_hooks_passed(pool, workspace.id).await?
+        };
+
+        attempts.push(ComparisonAttempt {
+            workspace_id: workspace.id,
+            executor_profile_id,
+            running,
+            failed: stats.is_some_and(|stats| stats.failed),
+            files_changed: diff.as_ref().map(|diff| diff.files_changed),
+            lines_added: diff.as_ref().map(|diff| diff.lines_added),
+            lines_removed: diff.as_ref().map(|diff| diff.lines_removed),
+            tests_passed,
+            agent_seconds: stats.and_then(|stats| stats.agent_seconds),
+            total_tokens: stats.and_then(|stats| stats.total_tokens),
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(ComparisonReport {
+        comparison_group_id,
+        task_id: task.id,
+        attempts,
+    })))
+}
+
+/// A coverage hook only writes a report when its command succeeds, so a
+/// report for every hooked repo means the test runs passed
+async fn coverage_hooks_passed(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Option<bool>, sqlx::Error> {
+    let mut hooked = HashSet::new();
+    for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace_id).await? {
+        if RepoCoverageSettings::find_by_repo_id(pool, repo.id)
+            .await?
+            .is_some()
+        {
+            hooked.insert(repo.id);
+        }
+    }
+    if hooked.is_empty() {
+        return Ok(None);
+    }
+    let reported: HashSet<Uuid> = CoverageReport::find_latest_by_workspace_id(pool, workspace_id)
+        .await?
+        .into_iter()
+        .map(|report| report.repo_id)
+        .collect();
+    Ok(Some(hooked.is_subset(&reported)))
+}
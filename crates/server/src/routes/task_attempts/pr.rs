@@ -2,13 +2,18 @@ use std::path::PathBuf;
 
 use axum::{
     Extension, Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
 };
 use db::models::{
+    agent_prompt::{AgentPrompt, AgentPromptKind},
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
+    preview::VisualComparison,
+    project::Project,
+    prompt_template::PR_DESCRIPTION_PROMPT,
     repo::{Repo, RepoError},
+    security_audit::SecurityFinding,
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
     workspace::{Workspace, WorkspaceError},
@@ -22,15 +27,21 @@ use executors::actions::{
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    dependency_changes,
     git::{GitCliError, GitServiceError},
     git_host::{
         self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
     },
+    i18n::localize_default_prompt,
+    path_scope, prompt_library, review_checklist, security_audit,
+    share::SharedTaskExecutionState,
+    task_fan_out, traceability, visual_regression,
 };
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{response::ApiResponse, validation::ValidationErrors};
 use uuid::Uuid;
 
+use super::{OpenEditorResponse, util};
 use crate::{DeploymentImpl, error::ApiError};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -42,6 +53,9 @@ pub struct CreatePrApiRequest {
     pub repo_id: Uuid,
     #[serde(default)]
     pub auto_generate_description: bool,
+    /// Append the attempt's stored review checklist to the body
+    #[serde(default)]
+    pub include_review_checklist: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -105,20 +119,51 @@ async fn trigger_pr_description_follow_up(
     workspace: &Workspace,
     pr_number: i64,
     pr_url: &str,
+    dependency_section: Option<&str>,
+    path_scope: Option<&str>,
 ) -> Result<(), ApiError> {
-    // Get the custom prompt from config, or use default
-    let config = deployment.config().read().await;
-    let prompt_template = config
-        .pr_auto_description_prompt
-        .as_deref()
-        .unwrap_or(DEFAULT_PR_DESCRIPTION_PROMPT);
+    // Prefer the prompt library (project override, then global), then the
+    // custom prompt from config, then the default
+    let project_id = workspace
+        .parent_task(&deployment.db().pool)
+        .await?
+        .map(|task| task.project_id);
+    let library_prompt =
+        prompt_library::resolve(&deployment.db().pool, PR_DESCRIPTION_PROMPT, project_id).await;
+    let prompt_template = match library_prompt {
+        Some(prompt) => prompt,
+        None => deployment
+            .config()
+            .read()
+            .await
+            .pr_auto_description_prompt
+            .clone()
+            .unwrap_or_else(|| localize_default_prompt(DEFAULT_PR_DESCRIPTION_PROMPT)),
+    };
 
     // Replace placeholders in prompt
-    let prompt = prompt_template
+    let mut prompt = prompt_template
         .replace("{pr_number}", &pr_number.to_string())
         .replace("{pr_url}", pr_url);
-
-    drop(config); // Release the lock before async operations
+    if let Some(project_id) = project_id {
+        let ignore = DiffIgnore::for_project(&deployment.db().pool, project_id).await?;
+        if !ignore.is_empty() {
+            prompt.push_str(&format!(
+                "\n\nDo not describe changes to files matching these patterns: {}",
+                ignore.patterns().join(", ")
+            ));
+        }
+    }
+    if let Some(scope) = path_scope {
+        prompt.push_str(&format!(
+            "\n\nThis attempt is limited to `{scope}`; only describe changes inside it."
+        ));
+    }
+    if let Some(section) = dependency_section {
+        prompt.push_str(&format!(
+            "\n\nKeep this dependency summary in the description:\n\n{section}"
+        ));
+    }
 
     // Get or create a session for this follow-up
     let session =
@@ -160,6 +205,10 @@ async fn trigger_pr_description_follow_up(
         .filter(|dir| !dir.is_empty())
         .cloned();
 
+    let model = Project::find_executor_defaults_for_workspace(&deployment.db().pool, workspace.id)
+        .await?
+        .model_for(&executor_profile_id);
+
     // Build the action type (follow-up if session exists, otherwise initial)
     let action_type = if let Some(agent_session_id) = latest_agent_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
@@ -167,18 +216,20 @@ async fn trigger_pr_description_follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            model: model.clone(),
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
             prompt,
             executor_profile_id: executor_profile_id.clone(),
             working_dir,
+            model,
         })
     };
 
     let action = ExecutorAction::new(action_type, None);
 
-    deployment
+    let execution_process = deployment
         .container()
         .start_execution(
             workspace,
@@ -187,6 +238,13 @@ async fn trigger_pr_description_follow_up(
             &ExecutionProcessRunReason::CodingAgent,
         )
         .await?;
+    // Runs as a regular agent turn, so the prompt history can't tell it apart
+    AgentPrompt::set_kind(
+        &deployment.db().pool,
+        execution_process.id,
+        AgentPromptKind::PrDescription,
+    )
+    .await?;
 
     Ok(())
 }
@@ -198,6 +256,14 @@ pub async fn create_pr(
 ) -> Result<ResponseJson<ApiResponse<String, PrError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    if let Some(canonical_id) = Workspace::find_canonical_id(pool, workspace.task_id).await?
+        && canonical_id != workspace.id
+    {
+        return Err(ApiError::Conflict(
+            "Another attempt of this task has been promoted; open the PR from it".to_string(),
+        ));
+    }
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -262,6 +328,15 @@ pub async fn create_pr(
         Ok(true) => {}
     }
 
+    util::run_pre_push_hooks(
+        pool,
+        &workspace,
+        repo.id,
+        &worktree_path,
+        &workspace.branch,
+        false,
+    )
+    .await?;
     if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
@@ -296,10 +371,58 @@ pub async fn create_pr(
 
     let provider = git_host.provider_kind();
 
+    // Generated descriptions list the dependencies the attempt changed
+    let dependency_section = match util::workspace_dependency_changes(
+        &deployment,
+        &workspace,
+        Some(workspace_repo.repo_id),
+    )
+    .await
+    {
+        Ok(changes) => Some(changes.markdown).filter(|markdown| !markdown.is_empty()),
+        Err(e) => {
+            tracing::warn!("Failed to analyze dependency changes: {}", e);
+            None
+        }
+    };
+
+    // Fall back to the attempt summary when no body was given
+    let body = match request.body.clone().filter(|body| !body.trim().is_empty()) {
+        Some(body) => Some(body),
+        None => {
+            let summary = Workspace::find_summary(pool, workspace.id).await?;
+            match &dependency_section {
+                Some(section) => Some(dependency_changes::append_to_body(
+                    summary.as_deref(),
+                    section,
+                )),
+                None => summary,
+            }
+        }
+    };
+    let body = if request.include_review_checklist
+        && let Some(checklist) = Workspace::find_review_checklist(pool, workspace.id).await?
+    {
+        Some(review_checklist::append_to_body(
+            body.as_deref(),
+            &checklist,
+        ))
+    } else {
+        body
+    };
+    let body = match deployment.config().read().await.task_trailer.clone() {
+        Some(key) => Some(traceability::append_trailer(
+            body.as_deref().unwrap_or_default(),
+            &key,
+            workspace.task_id,
+        )),
+        None => body,
+    };
+
     // Create the PR
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
         draft: request.draft,
@@ -325,6 +448,56 @@ pub async fn create_pr(
                 tracing::error!("Failed to update workspace PR status: {}", e);
             }
 
+            // Findings from an audit that ran before the PR existed
+            let has_findings = SecurityFinding::find_by_workspace_id(pool, workspace.id)
+                .await
+                .is_ok_and(|findings| findings.iter().any(|f| f.repo_id == workspace_repo.repo_id));
+            if has_findings
+                && let Err(e) = security_audit::annotate_pr(
+                    pool,
+                    workspace.id,
+                    workspace_repo.repo_id,
+                    &repo_path,
+                    &target_remote_url,
+                    pr_info.number,
+                )
+                .await
+            {
+                tracing::warn!("Failed to comment security findings on PR: {}", e);
+            }
+
+            if let Ok(Some(task)) = workspace.parent_task(pool).await {
+                // Screenshots compared before the PR existed
+                let has_comparisons = VisualComparison::find_by_workspace_id(pool, workspace.id)
+                    .await
+                    .is_ok_and(|comparisons| !comparisons.is_empty());
+                if has_comparisons
+                    && let Err(e) = visual_regression::annotate_pr(
+                        pool,
+                        workspace.id,
+                        task.project_id,
+                        &repo_path,
+                        &target_remote_url,
+                        pr_info.number,
+                    )
+                    .await
+                {
+                    tracing::warn!("Failed to comment visual changes on PR: {}", e);
+                }
+
+                if let Err(e) = task_fan_out::sync_parent_status(pool, task.id).await {
+                    tracing::warn!("Failed to update fan-out parent of task {}: {}", task.id, e);
+                }
+
+                util::spawn_attempt_publish(&deployment, &task, workspace.id);
+                util::spawn_execution_report(
+                    &deployment,
+                    task,
+                    SharedTaskExecutionState::PrOpened,
+                    Some(pr_info.url.clone()),
+                );
+            }
+
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
                 tracing::warn!("Failed to open PR in browser: {}", e);
@@ -341,12 +514,17 @@ pub async fn create_pr(
                 .await;
 
             // Trigger auto-description follow-up if enabled
+            let path_scope = WorkspaceRepo::find_path_scopes(pool, workspace.id)
+                .await?
+                .remove(&workspace_repo.repo_id);
             if request.auto_generate_description
                 && let Err(e) = trigger_pr_description_follow_up(
                     &deployment,
                     &workspace,
                     pr_info.number,
                     &pr_info.url,
+                    dependency_section.as_deref(),
+                    path_scope.as_deref(),
                 )
                 .await
             {
@@ -485,6 +663,21 @@ pub async fn attach_existing_pr(
             if !workspace.pinned {
                 Workspace::set_archived(pool, workspace.id, true).await?;
             }
+            if let Err(e) = deployment
+                .jobs()
+                .enqueue_merged_branch_cleanup(&workspace, workspace_repo.repo_id)
+                .await
+            {
+                tracing::error!(
+                    "Failed to queue branch cleanup for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+            }
+        }
+
+        if let Err(e) = task_fan_out::sync_parent_status(pool, task.id).await {
+            tracing::warn!("Failed to update fan-out parent of task {}: {}", task.id, e);
         }
 
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
@@ -503,33 +696,31 @@ pub async fn attach_existing_pr(
     }
 }
 
-pub async fn get_pr_comments(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<GetPrCommentsQuery>,
-) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+/// Comments of the PR attached to one of the attempt's repos
+async fn fetch_pr_comments(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Result<Vec<UnifiedPrComment>, GetPrCommentsError>, ApiError> {
     let pool = &deployment.db().pool;
 
     // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
     let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
     // Find the merge/PR for this specific repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo_id).await?;
 
     // Ensure there's an attached PR for this repo
     let pr_info = match merges.into_iter().next() {
         Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
         _ => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::NoPrAttached,
-            )));
+            return Ok(Err(GetPrCommentsError::NoPrAttached));
         }
     };
 
@@ -542,9 +733,7 @@ pub async fn get_pr_comments(
     let git_host = match git_host::GitHostService::from_url(&remote_url) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::CliNotInstalled { provider },
-            )));
+            return Ok(Err(GetPrCommentsError::CliNotInstalled { provider }));
         }
         Err(e) => return Err(ApiError::GitHost(e)),
     };
@@ -555,9 +744,7 @@ pub async fn get_pr_comments(
         .get_pr_comments(&repo.path, &remote_url, pr_info.number)
         .await
     {
-        Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
-            comments,
-        }))),
+        Ok(comments) => Ok(Ok(comments)),
         Err(e) => {
             tracing::error!(
                 "Failed to fetch PR comments for attempt {}, PR #{}: {}",
@@ -566,16 +753,97 @@ pub async fn get_pr_comments(
                 e
             );
             match &e {
-                GitHostError::CliNotInstalled { provider } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::CliNotInstalled {
+                GitHostError::CliNotInstalled { provider } => {
+                    Ok(Err(GetPrCommentsError::CliNotInstalled {
                         provider: *provider,
-                    }),
-                )),
-                GitHostError::AuthFailed(_) => Ok(ResponseJson(ApiResponse::error_with_data(
-                    GetPrCommentsError::CliNotLoggedIn { provider },
-                ))),
+                    }))
+                }
+                GitHostError::AuthFailed(_) => {
+                    Ok(Err(GetPrCommentsError::CliNotLoggedIn { provider }))
+                }
                 _ => Err(ApiError::GitHost(e)),
             }
         }
     }
 }
+
+pub async fn get_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+    Ok(ResponseJson(
+        match fetch_pr_comments(&deployment, &workspace, query.repo_id).await? {
+            Ok(comments) => ApiResponse::success(PrCommentsResponse { comments }),
+            Err(e) => ApiResponse::error_with_data(e),
+        },
+    ))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct OpenPrCommentRequest {
+    pub repo_id: Uuid,
+    pub editor_type: Option<String>,
+}
+
+/// Open the file and line a PR review comment points at, in the attempt's
+/// worktree
+pub async fn open_pr_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, i64)>,
+    Json(payload): Json<OpenPrCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<OpenEditorResponse, GetPrCommentsError>>, ApiError> {
+    let comments = match fetch_pr_comments(&deployment, &workspace, payload.repo_id).await? {
+        Ok(comments) => comments,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+    };
+    let (file_path, line) = comments
+        .into_iter()
+        .find_map(|comment| match comment {
+            UnifiedPrComment::Review { id, path, line, .. } if id == comment_id => {
+                Some((path, line))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("No review comment {comment_id} on this PR"))
+        })?;
+
+    let mut errors = ValidationErrors::new();
+    path_scope::validate(&mut errors, "path", &file_path);
+    errors.into_result().map_err(ApiError::Validation)?;
+    let repo_root =
+        super::workspace_repo_root(&deployment, &workspace, Some(payload.repo_id)).await?;
+    let path = repo_root.join(&file_path);
+    if !path.is_file() {
+        return Err(ApiError::BadRequest(format!(
+            "{file_path} does not exist in the attempt's worktree"
+        )));
+    }
+    // Comments on removed lines have no line in the current file
+    let line = line
+        .and_then(|line| u32::try_from(line).ok())
+        .filter(|line| *line > 0);
+
+    let editor_config = {
+        let config = deployment.config().read().await;
+        config.editor.with_override(payload.editor_type.as_deref())
+    };
+    let url = editor_config.open_file_at(&path, line).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comment_opened_in_editor",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "editor_type": payload.editor_type.as_ref(),
+                "remote_mode": url.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(OpenEditorResponse {
+        url,
+    })))
+}
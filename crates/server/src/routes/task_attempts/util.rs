@@ -1,12 +1,29 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use chrono::{Local, NaiveDateTime};
 use db::models::{
-    execution_process::ExecutionProcess, execution_process_repo_state::ExecutionProcessRepoState,
-    workspace::Workspace, workspace_repo::WorkspaceRepo,
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    lifecycle_hook::LifecycleEvent,
+    project::Project,
+    project_budget::{BudgetEnforcement, ProjectBudget},
+    task::Task,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use services::services::{container::ContainerService, git::WorktreeResetOptions};
+use services::services::{
+    container::ContainerService,
+    dependency_changes::{self, DependencyChanges},
+    diff_ignore::DiffIgnore,
+    git::{DiffTarget, GitCliError, GitService, GitServiceError, WorktreeResetOptions},
+    lifecycle_hooks,
+    sensitive_files::SensitiveFileRules,
+    share::SharedTaskExecutionState,
+};
 use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
+use utils::diff::Diff;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
@@ -81,3 +98,237 @@ pub async fn restore_worktrees_to_process(
 
     Ok(())
 }
+
+/// Changes in every repo of the workspace against the merge base with its
+/// target branch. Repos whose base or diff cannot be computed are skipped,
+/// paths matching the project's diff ignore patterns or outside a repo's path
+/// scope are left out, and sensitive files keep their stats but lose their
+/// contents.
+pub async fn workspace_diffs(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<Vec<Diff>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let container_ref = workspace
+        .container_ref
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("No container ref".to_string()))?;
+
+    let workspace_repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let ignore = DiffIgnore::for_workspace(pool, workspace).await?;
+    let global_sensitive = deployment
+        .config()
+        .read()
+        .await
+        .sensitive_file_patterns
+        .clone();
+    let sensitive = SensitiveFileRules::for_workspace(pool, &global_sensitive, workspace).await?;
+
+    let mut diffs = Vec::new();
+
+    for repo_with_branch in workspace_repos {
+        let worktree_path = PathBuf::from(container_ref).join(&repo_with_branch.repo.name);
+        let repo_path = repo_with_branch.repo.path.clone();
+
+        // Get base commit (merge base) between workspace branch and target branch
+        let base_commit_result = tokio::task::spawn_blocking({
+            let git = deployment.git().clone();
+            let repo_path = repo_path.clone();
+            let workspace_branch = workspace.branch.clone();
+            let target_branch = repo_with_branch.target_branch.clone();
+            move || git.get_base_commit(&repo_path, &workspace_branch, &target_branch)
+        })
+        .await;
+
+        let base_commit = match base_commit_result {
+            Ok(Ok(commit)) => commit,
+            _ => continue,
+        };
+
+        let diffs_result = tokio::task::spawn_blocking({
+            let git = deployment.git().clone();
+            let worktree = worktree_path.clone();
+            move || {
+                git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )
+            }
+        })
+        .await;
+
+        if let Ok(Ok(mut repo_diffs)) = diffs_result {
+            ignore
+                .clone()
+                .with_scope(repo_with_branch.path_scope.as_deref())
+                .retain(&mut repo_diffs);
+            sensitive.sanitize(&mut repo_diffs);
+            let repo_id = repo_with_branch.repo.id;
+            diffs.extend(repo_diffs.into_iter().map(|mut diff| {
+                diff.repo_id = Some(repo_id);
+                diff
+            }));
+        }
+    }
+
+    Ok(diffs)
+}
+
+/// Dependency changes in the workspace diff, checked against the project
+/// allowlist. `repo_id` limits the analysis to one repo.
+pub async fn workspace_dependency_changes(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Option<Uuid>,
+) -> Result<DependencyChanges, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut diffs = workspace_diffs(deployment, workspace).await?;
+    if let Some(repo_id) = repo_id {
+        diffs.retain(|diff| diff.repo_id == Some(repo_id));
+    }
+    let allowlist = match workspace.parent_task(pool).await? {
+        Some(task) => Project::find_dependency_allowlist(pool, task.project_id).await?,
+        None => None,
+    };
+    Ok(dependency_changes::analyze(&diffs, allowlist.as_deref()))
+}
+
+/// Local time a new attempt in `project_id` has to wait for because the
+/// project's execution window is closed; `None` if it may start now
+pub async fn execution_window_opens_at(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<Option<NaiveDateTime>, sqlx::Error> {
+    let Some(window) = Project::find_execution_window(pool, project_id).await? else {
+        return Ok(None);
+    };
+    let now = Local::now().naive_local();
+    Ok(window.next_open(now).filter(|opens_at| *opens_at > now))
+}
+
+/// Refuse to start `runs` more agent runs in `project_id` when they are
+/// expected to take it over its monthly budget, unless the project only asks
+/// for confirmation and the user has `confirmed`
+pub async fn ensure_within_budget(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    runs: usize,
+    confirmed: bool,
+) -> Result<(), ApiError> {
+    let Some(status) = ProjectBudget::find_status(pool, project_id).await? else {
+        return Ok(());
+    };
+    if !status.exceeded_by_runs(runs)
+        || (confirmed && status.enforcement == BudgetEnforcement::Confirm)
+    {
+        return Ok(());
+    }
+    Err(ApiError::BudgetExceeded(status))
+}
+
+/// Run the project's `pre_push` hooks, which can veto pushing `branch` from
+/// the worktree of `repo_id`
+pub async fn run_pre_push_hooks(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+    worktree_path: &Path,
+    branch: &str,
+    force: bool,
+) -> Result<(), ApiError> {
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(WorkspaceError::TaskNotFound)?;
+    lifecycle_hooks::run(
+        pool,
+        task.project_id,
+        LifecycleEvent::PrePush,
+        serde_json::json!({
+            "task_id": task.id,
+            "workspace_id": workspace.id,
+            "repo_id": repo_id,
+            "worktree_path": worktree_path,
+            "branch": branch,
+            "force": force,
+        }),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Report an attempt lifecycle event to teammates on a shared task (best-effort)
+pub fn spawn_execution_report(
+    deployment: &DeploymentImpl,
+    task: Task,
+    state: SharedTaskExecutionState,
+    detail: Option<String>,
+) {
+    if task.shared_task_id.is_none() {
+        return;
+    }
+    let Ok(publisher) = deployment.share_publisher() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = publisher
+            .report_execution_status(&task, state, detail)
+            .await
+        {
+            tracing::debug!(
+                "Failed to report execution status for task {}: {}",
+                task.id,
+                e
+            );
+        }
+    });
+}
+
+/// Show teammates on a shared task the attempt's summary and PR state (best-effort)
+pub fn spawn_attempt_publish(deployment: &DeploymentImpl, task: &Task, workspace_id: Uuid) {
+    if task.shared_task_id.is_none() {
+        return;
+    }
+    let Ok(publisher) = deployment.share_publisher() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = publisher.publish_attempt(workspace_id).await {
+            tracing::debug!("Failed to publish attempt {}: {}", workspace_id, e);
+        }
+    });
+}
+
+/// Share or unshare the task in the background as its project's share rules
+/// dictate
+pub fn spawn_share_rules(deployment: &DeploymentImpl, task_id: Uuid) {
+    let Ok(publisher) = deployment.share_publisher() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = publisher.apply_share_rules(task_id).await {
+            tracing::debug!("Failed to apply share rules to task {}: {}", task_id, e);
+        }
+    });
+}
+
+/// Push `branch` from `worktree_path` off the async runtime. The push is
+/// killed when the request is dropped before it finishes, e.g. because the
+/// client went away.
+pub async fn push_branch(
+    worktree_path: PathBuf,
+    branch: String,
+    force: bool,
+) -> Result<(), GitServiceError> {
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    let git = GitService::with_cancellation(cancel);
+    tokio::task::spawn_blocking(move || git.push_to_remote(&worktree_path, &branch, force))
+        .await
+        .map_err(|e| GitServiceError::GitCLI(GitCliError::CommandFailed(e.to_string())))?
+}
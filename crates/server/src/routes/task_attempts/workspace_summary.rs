@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
 use axum::{Json, extract::State, response::Json as ResponseJson};
 use db::models::{
@@ -6,15 +6,14 @@ use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
     merge::{Merge, MergeStatus},
     workspace::Workspace,
-    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
-use services::services::git::DiffTarget;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
+use super::util::workspace_diffs;
 use crate::{DeploymentImpl, error::ApiError};
 
 /// Request for fetching workspace summaries
@@ -165,65 +164,15 @@ pub async fn get_workspace_summaries(
 }
 
 /// Compute diff stats for a workspace.
-async fn compute_workspace_diff_stats(
+pub(crate) async fn compute_workspace_diff_stats(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
 ) -> Result<DiffStats, ApiError> {
-    let pool = &deployment.db().pool;
-
-    let container_ref = workspace
-        .container_ref
-        .as_ref()
-        .ok_or_else(|| ApiError::BadRequest("No container ref".to_string()))?;
-
-    let workspace_repos =
-        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
-
     let mut stats = DiffStats::default();
-
-    for repo_with_branch in workspace_repos {
-        let worktree_path = PathBuf::from(container_ref).join(&repo_with_branch.repo.name);
-        let repo_path = repo_with_branch.repo.path.clone();
-
-        // Get base commit (merge base) between workspace branch and target branch
-        let base_commit_result = tokio::task::spawn_blocking({
-            let git = deployment.git().clone();
-            let repo_path = repo_path.clone();
-            let workspace_branch = workspace.branch.clone();
-            let target_branch = repo_with_branch.target_branch.clone();
-            move || git.get_base_commit(&repo_path, &workspace_branch, &target_branch)
-        })
-        .await;
-
-        let base_commit = match base_commit_result {
-            Ok(Ok(commit)) => commit,
-            _ => continue,
-        };
-
-        // Get diffs
-        let diffs_result = tokio::task::spawn_blocking({
-            let git = deployment.git().clone();
-            let worktree = worktree_path.clone();
-            move || {
-                git.get_diffs(
-                    DiffTarget::Worktree {
-                        worktree_path: &worktree,
-                        base_commit: &base_commit,
-                    },
-                    None,
-                )
-            }
-        })
-        .await;
-
-        if let Ok(Ok(diffs)) = diffs_result {
-            for diff in diffs {
-                stats.files_changed += 1;
-                stats.lines_added += diff.additions.unwrap_or(0);
-                stats.lines_removed += diff.deletions.unwrap_or(0);
-            }
-        }
+    for diff in workspace_diffs(deployment, workspace).await? {
+        stats.files_changed += 1;
+        stats.lines_added += diff.additions.unwrap_or(0);
+        stats.lines_removed += diff.deletions.unwrap_or(0);
     }
-
     Ok(stats)
 }
@@ -1,5 +1,5 @@
 use axum::{
-    Json, Router,
+    Extension, Router,
     extract::State,
     response::Json as ResponseJson,
     routing::{delete, get, post, put},
@@ -7,11 +7,14 @@ use axum::{
 use db::models::task_label::{CreateTaskLabel, TaskLabel, UpdateTaskLabel};
 use deployment::Deployment;
 use serde::Serialize;
+use services::services::multi_user::RequestUser;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::ensure_project_access, validation::ValidatedJson,
+};
 
 pub fn routes() -> Router<DeploymentImpl> {
     Router::new()
@@ -32,11 +35,25 @@ pub struct GetLabelsResponse {
     pub labels: Vec<TaskLabel>,
 }
 
+/// The label, if it belongs to the project
+async fn project_label(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    label_id: Uuid,
+) -> Result<TaskLabel, ApiError> {
+    TaskLabel::find_by_id(&deployment.db().pool, label_id)
+        .await?
+        .filter(|label| label.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Label not found in project".to_string()))
+}
+
 /// obtener todas las etiquetas de un proyecto
 pub async fn get_labels(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     axum::extract::Path(project_id): axum::extract::Path<Uuid>,
 ) -> Result<ResponseJson<ApiResponse<GetLabelsResponse>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
     let labels = TaskLabel::find_by_project_id(&deployment.db().pool, project_id).await?;
 
     Ok(ResponseJson(ApiResponse::success(GetLabelsResponse {
@@ -47,8 +64,10 @@ pub async fn get_labels(
 /// crear nueva etiqueta
 pub async fn create_label(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateTaskLabel>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateTaskLabel>,
 ) -> Result<ResponseJson<ApiResponse<TaskLabel>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), payload.project_id).await?;
     let label = TaskLabel::create(&deployment.db().pool, &payload).await?;
 
     Ok(ResponseJson(ApiResponse::success(label)))
@@ -57,9 +76,12 @@ pub async fn create_label(
 /// actualizar etiqueta existente
 pub async fn update_label(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path((_project_id, label_id)): axum::extract::Path<(Uuid, Uuid)>,
-    Json(payload): Json<UpdateTaskLabel>,
+    user: Option<Extension<RequestUser>>,
+    axum::extract::Path((project_id, label_id)): axum::extract::Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdateTaskLabel>,
 ) -> Result<ResponseJson<ApiResponse<TaskLabel>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_label(&deployment, project_id, label_id).await?;
     let label = TaskLabel::update(&deployment.db().pool, label_id, &payload).await?;
 
     Ok(ResponseJson(ApiResponse::success(label)))
@@ -68,8 +90,11 @@ pub async fn update_label(
 /// eliminar etiqueta
 pub async fn delete_label(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path((_project_id, label_id)): axum::extract::Path<(Uuid, Uuid)>,
+    user: Option<Extension<RequestUser>>,
+    axum::extract::Path((project_id, label_id)): axum::extract::Path<(Uuid, Uuid)>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+    project_label(&deployment, project_id, label_id).await?;
     TaskLabel::delete(&deployment.db().pool, label_id).await?;
 
     Ok(ResponseJson(ApiResponse::success(())))
@@ -1,39 +1,77 @@
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 
 use anyhow;
 use axum::{
     Extension, Json, Router,
+    body::Body,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{delete, get, post, put},
 };
 use db::models::{
+    epic::Epic,
+    execution_window::DeferredAttempt,
     image::TaskImage,
-    repo::{Repo, RepoError},
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    lifecycle_hook::LifecycleEvent,
+    project::Project,
+    project_repo::ProjectRepo,
+    project_script::ScriptEvent,
+    repo::Repo,
+    task::{CreateTask, MoveTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_activity::TaskActivity,
+    task_assignee::TaskAssignee,
+    task_attachment::TaskAttachment,
+    task_context_pack::{ContextItem, TaskContextPack, UpsertTaskContextPack},
+    task_estimate::{TaskEstimate, UpsertTaskEstimate},
+    task_fan_out::{CreateTaskFanOutChild, TaskFanOut},
+    task_label::TaskLabel,
+    task_planning::{TaskPlanning, UpsertTaskPlanning},
+    time_entry::{TaskTime, TimeEntry},
     workspace::{CreateWorkspace, Workspace},
-    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+    workspace_repo::{
+        CreateWorkspaceRepo, CreateWorkspaceRepoCheckout, WorkspaceRepo, WorkspaceRepoCheckout,
+    },
 };
 use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    attachments, automation_scripts,
+    container::ContainerService,
+    context_pack,
+    embedding_index::{self, Embedder, EmbeddingError, SuggestedFile},
+    jobs::JobKind,
+    lifecycle_hooks,
+    multi_user::{MultiUserService, RequestUser},
+    path_scope,
+    share::ShareError,
+    task_enrichment::{self, TaskBrief},
+    task_fan_out,
+    task_knowledge::{self, SimilarTask},
+    traceability::{self, BlameTrace, TaskTrace},
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::{api::oauth::LoginStatus, response::ApiResponse};
+use utils::{
+    api::oauth::LoginStatus,
+    log_msg::LogMsg,
+    response::ApiResponse,
+    validation::{MAX_DESCRIPTION_CHARS, Validate, ValidationErrors},
+};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{ensure_project_access, load_task_middleware},
+    routes::task_attempts::{self, WorkspaceRepoInput},
+    validation::ValidatedJson,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,10 +81,13 @@ pub struct TaskQuery {
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<TaskQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), query.project_id).await?;
+
     let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
+        Task::find_by_project_id_with_attempt_status(&deployment.db().read_pool, query.project_id)
             .await?;
 
     Ok(ResponseJson(ApiResponse::success(tasks)))
@@ -55,13 +96,16 @@ pub async fn get_tasks(
 pub async fn stream_tasks_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<TaskQuery>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async move {
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), query.project_id).await?;
+
+    Ok(ws.on_upgrade(move |socket| async move {
         if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id).await {
             tracing::warn!("tasks WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_tasks_ws(
@@ -102,9 +146,11 @@ async fn handle_tasks_ws(
 pub async fn stream_active_tasks_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
 ) -> impl IntoResponse {
+    let user = user.map(|Extension(user)| user);
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_active_tasks_ws(socket, deployment).await {
+        if let Err(e) = handle_active_tasks_ws(socket, deployment, user).await {
             tracing::warn!("active tasks WS closed: {}", e);
         }
     })
@@ -113,11 +159,26 @@ pub async fn stream_active_tasks_ws(
 async fn handle_active_tasks_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
+    user: Option<RequestUser>,
 ) -> anyhow::Result<()> {
+    let pool = deployment.db().pool.clone();
     let mut stream = deployment
         .events()
         .stream_active_tasks_raw()
         .await?
+        .filter_map(move |item| {
+            let pool = pool.clone();
+            async move {
+                match (item, user) {
+                    (Ok(LogMsg::JsonPatch(patch)), Some(user)) => {
+                        MultiUserService::scope_task_patch(&pool, &user, patch)
+                            .await
+                            .map(|patch| Ok(LogMsg::JsonPatch(patch)))
+                    }
+                    (item, _) => Some(item),
+                }
+            }
+        })
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
     let (mut sender, mut receiver) = socket.split();
@@ -140,6 +201,82 @@ async fn handle_active_tasks_ws(
     Ok(())
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct TaskTraceQuery {
+    /// Full or abbreviated commit SHA
+    pub commit: Option<String>,
+    pub pr_url: Option<String>,
+    /// Only search the history of this project's repos for the task trailer
+    pub project_id: Option<Uuid>,
+}
+
+/// Task a commit or pull request came from, if it can be traced
+pub async fn trace_task(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<TaskTraceQuery>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskTrace>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let trace = match (query.commit.as_deref(), query.pr_url.as_deref()) {
+        (Some(commit), _) => {
+            let repos = match query.project_id {
+                Some(project_id) => {
+                    ensure_project_access(&deployment, user.as_deref(), project_id).await?;
+                    ProjectRepo::find_repos_for_project(pool, project_id).await?
+                }
+                None => Repo::list_all(pool).await?,
+            };
+            let trailer_key = deployment.config().read().await.task_trailer.clone();
+            traceability::find_by_commit(pool, &repos, trailer_key.as_deref(), commit).await?
+        }
+        (None, Some(pr_url)) => traceability::find_by_pr_url(pool, pr_url).await?,
+        (None, None) => {
+            return Err(ApiError::BadRequest(
+                "Either commit or pr_url is required".to_string(),
+            ));
+        }
+    };
+    if let Some(trace) = &trace {
+        ensure_project_access(&deployment, user.as_deref(), trace.task.project_id).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(trace)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BlameTraceQuery {
+    /// Registered repo or one of its worktrees
+    pub repo_path: String,
+    /// Relative to `repo_path`, or absolute
+    pub file: String,
+    /// 1-based line number
+    pub line: u32,
+}
+
+/// Task behind the commit that last touched a line of a file; `null` when
+/// the line is not committed yet
+pub async fn trace_blame(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Query(query): Query<BlameTraceQuery>,
+) -> Result<ResponseJson<ApiResponse<Option<BlameTrace>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repos = Repo::list_all(pool).await?;
+    let trailer_key = deployment.config().read().await.task_trailer.clone();
+    let blame = traceability::find_by_blame(
+        pool,
+        &repos,
+        trailer_key.as_deref(),
+        &PathBuf::from(query.repo_path.trim()),
+        &query.file,
+        query.line,
+    )
+    .await?;
+    if let Some(trace) = blame.as_ref().and_then(|blame| blame.trace.as_ref()) {
+        ensure_project_access(&deployment, user.as_deref(), trace.task.project_id).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(blame)))
+}
+
 pub async fn get_task(
     Extension(task): Extension<Task>,
     State(_deployment): State<DeploymentImpl>,
@@ -149,8 +286,11 @@ pub async fn get_task(
 
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateTask>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ensure_project_access(&deployment, user.as_deref(), payload.project_id).await?;
+
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -164,6 +304,7 @@ pub async fn create_task(
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
+    task_attempts::util::spawn_share_rules(&deployment, task.id);
 
     deployment
         .track_if_analytics_allowed(
@@ -183,94 +324,208 @@ pub async fn create_task(
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Omit to use the project's default executor, then the global one
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Start now even if the project's execution window is closed
+    #[serde(default)]
+    pub ignore_execution_window: bool,
+    /// Start even if the run is expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+impl Validate for CreateAndStartTaskRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.nested("task", self.task.validate());
+        task_attempts::validate_repo_inputs(&mut errors, &self.repos);
+        errors.into_result()
+    }
+}
+
+/// Insert the task with its images, labels, workspace, workspace repos and
+/// their extra checkouts in one transaction, so a failure at any step leaves
+/// nothing behind.
+async fn insert_task_with_workspace(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    data: &CreateTask,
+    workspace_id: Uuid,
+    workspace: &CreateWorkspace,
+    repos: &[CreateWorkspaceRepo],
+    checkouts: &[CreateWorkspaceRepoCheckout],
+) -> Result<(Task, Workspace), ApiError> {
+    let mut tx = pool.begin().await?;
+
+    let task = Task::create(&mut *tx, data, task_id).await?;
+    if let Some(image_ids) = &data.image_ids {
+        TaskImage::associate_many_dedup(&mut *tx, task.id, image_ids).await?;
+    }
+    if let Some(label_ids) = &data.label_ids {
+        TaskLabel::associate_many(&mut *tx, task.id, label_ids).await?;
+    }
+    let workspace = Workspace::create(&mut *tx, workspace, workspace_id, task.id).await?;
+    WorkspaceRepo::create_many(&mut *tx, workspace.id, repos).await?;
+    for checkout in checkouts {
+        WorkspaceRepoCheckout::create(&mut *tx, workspace.id, &workspace.branch, checkout).await?;
+    }
+
+    tx.commit().await?;
+    Ok((task, workspace))
+}
+
+/// Undo a create-and-start whose container failed to start: tear down
+/// whatever the container created, then delete the task (cascading to the
+/// workspace and its associations).
+async fn discard_unstarted_task(deployment: &DeploymentImpl, task_id: Uuid, workspace_id: Uuid) {
+    let pool = &deployment.db().pool;
+    match Workspace::find_by_id(pool, workspace_id).await {
+        Ok(Some(workspace)) => {
+            if let Err(e) = deployment.container().delete(&workspace).await {
+                tracing::error!("Failed to clean up workspace {}: {}", workspace_id, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to load workspace {}: {}", workspace_id, e),
+    }
+    if let Err(e) = Task::delete(pool, task_id).await {
+        tracing::error!("Failed to delete unstarted task {}: {}", task_id, e);
+    }
+}
+
+/// The requested executor, else the project's default, else the global one
+async fn resolve_executor_profile(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    requested: Option<ExecutorProfileId>,
+) -> Result<ExecutorProfileId, ApiError> {
+    if let Some(profile_id) = requested {
+        return Ok(profile_id);
+    }
+    match Project::find_executor_defaults(&deployment.db().pool, project_id)
+        .await?
+        .default_profile()
+    {
+        Some(profile_id) => Ok(profile_id),
+        None => Ok(deployment.config().read().await.executor_profile.clone()),
+    }
 }
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateAndStartTaskRequest>,
+    user: Option<Extension<RequestUser>>,
+    ValidatedJson(payload): ValidatedJson<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
+    ensure_project_access(&deployment, user.as_deref(), payload.task.project_id).await?;
 
     let pool = &deployment.db().pool;
+    task_attempts::util::ensure_within_budget(
+        pool,
+        payload.task.project_id,
+        1,
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
 
-    let task_id = Uuid::new_v4();
-    let task = Task::create(pool, &payload.task, task_id).await?;
-
-    if let Some(image_ids) = &payload.task.image_ids {
-        TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
-    }
+    let executor_profile_id = resolve_executor_profile(
+        &deployment,
+        payload.task.project_id,
+        payload.executor_profile_id.clone(),
+    )
+    .await?;
 
-    deployment
-        .track_if_analytics_allowed(
-            "task_created",
-            serde_json::json!({
-                "task_id": task.id.to_string(),
-                "project_id": task.project_id,
-                "has_description": task.description.is_some(),
-                "has_images": payload.task.image_ids.is_some(),
-            }),
-        )
-        .await;
+    lifecycle_hooks::run(
+        pool,
+        payload.task.project_id,
+        LifecycleEvent::PreAttempt,
+        serde_json::json!({
+            "task": &payload.task,
+            "executor_profile_id": &executor_profile_id,
+            "repos": &payload.repos,
+        }),
+    )
+    .await?;
 
+    let task_id = Uuid::new_v4();
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
+        .git_branch_from_workspace(&attempt_id, &payload.task.title)
         .await;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
+    let agent_working_dir = task_attempts::agent_working_dir(pool, &payload.repos).await?;
+
+    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
+        .repos
+        .iter()
+        .map(WorkspaceRepoInput::to_create)
+        .collect();
+    let checkouts: Vec<CreateWorkspaceRepoCheckout> = payload
+        .repos
+        .iter()
+        .flat_map(WorkspaceRepoInput::to_create_checkouts)
+        .collect();
 
-    let workspace = Workspace::create(
+    let (task, workspace) = insert_task_with_workspace(
         pool,
+        task_id,
+        &payload.task,
+        attempt_id,
         &CreateWorkspace {
             branch: git_branch_name,
             agent_working_dir,
         },
-        attempt_id,
-        task.id,
+        &workspace_repos,
+        &checkouts,
     )
     .await?;
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
-        .iter()
-        .map(|r| CreateWorkspaceRepo {
-            repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
-        })
-        .collect();
-    WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
-
-    let is_attempt_running = deployment
+    let deferred_until = if payload.ignore_execution_window {
+        None
+    } else {
+        task_attempts::util::execution_window_opens_at(pool, task.project_id).await?
+    };
+    if let Some(opens_at) = deferred_until {
+        DeferredAttempt::create(pool, workspace.id, &executor_profile_id).await?;
+        tracing::info!(
+            "Deferred attempt {} until the execution window opens at {}",
+            workspace.id,
+            opens_at
+        );
+    } else if let Err(err) = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .start_workspace(&workspace, executor_profile_id.clone())
         .await
-        .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+    {
+        tracing::error!("Failed to start task attempt: {}", err);
+        discard_unstarted_task(&deployment, task.id, workspace.id).await;
+        return Err(err.into());
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id,
+                "has_description": task.description.is_some(),
+                "has_images": payload.task.image_ids.is_some(),
+            }),
+        )
+        .await;
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
-                "executor": &payload.executor_profile_id.executor,
-                "variant": &payload.executor_profile_id.variant,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
                 "workspace_id": workspace.id.to_string(),
+                "deferred": deferred_until.is_some(),
             }),
         )
         .await;
@@ -278,25 +533,276 @@ pub async fn create_task_and_start(
     let task = Task::find_by_id(pool, task.id)
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    let labels = TaskLabel::find_by_task_id(pool, task.id).await?;
+    task_attempts::util::spawn_share_rules(&deployment, task.id);
 
     tracing::info!("Started attempt for task {}", task.id);
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
-        has_in_progress_attempt: is_attempt_running,
+        has_in_progress_attempt: true,
         last_attempt_failed: false,
-        executor: payload.executor_profile_id.executor.to_string(),
+        executor: executor_profile_id.executor.to_string(),
         pr_number: None,
         pr_url: None,
+        latest_summary: None,
+        labels,
+        checklist_items: None,
+        canonical_workspace_id: None,
     })))
 }
 
+/// Most child tasks a single fan-out may create
+const MAX_FAN_OUT_TARGETS: usize = 25;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct FanOutTaskRequest {
+    /// One child task per target: a repo, optionally limited to a package
+    /// directory through its path scope
+    pub targets: Vec<WorkspaceRepoInput>,
+    /// Prompt of each child, with `{title}`, `{description}`, `{repo}`,
+    /// `{path}` and `{target}` placeholders; a default template when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub prompt_template: Option<String>,
+    /// Omit to use the project's default executor, then the global one
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Start now even if the project's execution window is closed
+    #[serde(default)]
+    pub ignore_execution_window: bool,
+    /// Start even if the runs are expected to go over the project's monthly
+    /// budget, when the project asks for confirmation rather than blocking
+    #[serde(default)]
+    #[ts(optional)]
+    pub confirm_over_budget: Option<bool>,
+}
+
+impl Validate for FanOutTaskRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.targets.is_empty() {
+            errors.add("targets", "at least one target is required");
+        } else if self.targets.len() > MAX_FAN_OUT_TARGETS {
+            errors.add(
+                "targets",
+                format!("at most {MAX_FAN_OUT_TARGETS} targets are allowed"),
+            );
+        }
+        let mut seen = HashSet::new();
+        for (i, target) in self.targets.iter().enumerate() {
+            errors.branch_name(
+                &format!("targets[{i}].target_branch"),
+                &target.target_branch,
+            );
+            if let Some(scope) = &target.path_scope {
+                path_scope::validate(&mut errors, &format!("targets[{i}].path_scope"), scope);
+            }
+            for (j, checkout) in target.to_create_checkouts().iter().enumerate() {
+                errors.nested(&format!("targets[{i}].checkouts[{j}]"), checkout.validate());
+            }
+            let scope = target.path_scope.as_deref().and_then(path_scope::normalize);
+            if !seen.insert((target.repo_id, scope)) {
+                errors.add(&format!("targets[{i}]"), "is listed more than once");
+            }
+        }
+        if let Some(template) = &self.prompt_template {
+            errors.max_chars("prompt_template", template, MAX_DESCRIPTION_CHARS);
+        }
+        errors.into_result()
+    }
+}
+
+/// Create a child task per target with a prompt rendered from the template
+/// and start an attempt on each. Targets whose attempt fails to start are
+/// dropped; the request fails only when none start.
+pub async fn fan_out_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<FanOutTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskFanOut>>, ApiError> {
+    let pool = &deployment.db().pool;
+    task_attempts::util::ensure_within_budget(
+        pool,
+        task.project_id,
+        payload.targets.len(),
+        payload.confirm_over_budget.unwrap_or(false),
+    )
+    .await?;
+
+    let mut repos = Vec::with_capacity(payload.targets.len());
+    for target in &payload.targets {
+        ProjectRepo::find_by_project_and_repo(pool, task.project_id, target.repo_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Repository {} is not part of the project",
+                    target.repo_id
+                ))
+            })?;
+        let repo = Repo::find_by_id(pool, target.repo_id)
+            .await?
+            .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+        repos.push(repo);
+    }
+
+    let executor_profile_id = resolve_executor_profile(
+        &deployment,
+        task.project_id,
+        payload.executor_profile_id.clone(),
+    )
+    .await?;
+
+    lifecycle_hooks::run(
+        pool,
+        task.project_id,
+        LifecycleEvent::PreAttempt,
+        serde_json::json!({
+            "task": &task,
+            "executor_profile_id": &executor_profile_id,
+            "repos": &payload.targets,
+        }),
+    )
+    .await?;
+
+    let deferred_until = if payload.ignore_execution_window {
+        None
+    } else {
+        task_attempts::util::execution_window_opens_at(pool, task.project_id).await?
+    };
+    let label_ids: Vec<Uuid> = TaskLabel::find_by_task_id(pool, task.id)
+        .await?
+        .into_iter()
+        .map(|label| label.id)
+        .collect();
+    let template = payload.prompt_template.as_deref().unwrap_or_default();
+
+    let mut started = 0;
+    let mut last_error = None;
+    for (target, repo) in payload.targets.iter().zip(&repos) {
+        let scope = target.path_scope.as_deref().and_then(path_scope::normalize);
+        let child = CreateTask {
+            label_ids: Some(label_ids.clone()),
+            ..CreateTask::from_title_description(
+                task.project_id,
+                task_fan_out::child_title(&task, &repo.display_name, scope.as_deref()),
+                Some(task_fan_out::render_prompt(
+                    template,
+                    &task,
+                    &repo.display_name,
+                    scope.as_deref(),
+                )),
+            )
+        };
+
+        let child_id = Uuid::new_v4();
+        let attempt_id = Uuid::new_v4();
+        let git_branch_name = deployment
+            .container()
+            .git_branch_from_workspace(&attempt_id, &child.title)
+            .await;
+        let agent_working_dir =
+            task_attempts::agent_working_dir(pool, std::slice::from_ref(target)).await?;
+
+        let (child, workspace) = insert_task_with_workspace(
+            pool,
+            child_id,
+            &child,
+            attempt_id,
+            &CreateWorkspace {
+                branch: git_branch_name,
+                agent_working_dir,
+            },
+            &[target.to_create()],
+            &target.to_create_checkouts(),
+        )
+        .await?;
+        if let Err(e) = TaskFanOut::add_child(
+            pool,
+            &CreateTaskFanOutChild {
+                parent_task_id: task.id,
+                child_task_id: child.id,
+                repo_id: repo.id,
+                path_scope: scope,
+            },
+        )
+        .await
+        {
+            discard_unstarted_task(&deployment, child.id, workspace.id).await;
+            return Err(e.into());
+        }
+
+        if deferred_until.is_some() {
+            DeferredAttempt::create(pool, workspace.id, &executor_profile_id).await?;
+        } else if let Err(err) = deployment
+            .container()
+            .start_workspace(&workspace, executor_profile_id.clone())
+            .await
+        {
+            tracing::error!(
+                "Failed to start fan-out attempt for {} in task {}: {}",
+                repo.display_name,
+                task.id,
+                err
+            );
+            discard_unstarted_task(&deployment, child.id, workspace.id).await;
+            last_error = Some(err);
+            continue;
+        }
+
+        task_attempts::util::spawn_share_rules(&deployment, child.id);
+        started += 1;
+    }
+
+    if started == 0
+        && let Some(err) = last_error
+    {
+        return Err(err.into());
+    }
+    if task.status == TaskStatus::Todo {
+        Task::update_status(pool, task.id, TaskStatus::InProgress).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_fanned_out",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id,
+                "targets": payload.targets.len(),
+                "started": started,
+                "executor": &executor_profile_id.executor,
+                "deferred": deferred_until.is_some(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Fanned task {} out to {} of {} targets",
+        task.id,
+        started,
+        payload.targets.len()
+    );
+    Ok(ResponseJson(ApiResponse::success(
+        TaskFanOut::find_by_parent_task_id(pool, task.id).await?,
+    )))
+}
+
+pub async fn get_fan_out(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskFanOut>>, ApiError> {
+    let fan_out = TaskFanOut::find_by_parent_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(fan_out)))
+}
+
 pub async fn update_task(
     Extension(existing_task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
 
-    Json(payload): Json<UpdateTask>,
+    ValidatedJson(payload): ValidatedJson<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     ensure_shared_task_auth(&existing_task, &deployment).await?;
+    let previous_status = existing_task.status.clone();
 
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
@@ -329,13 +835,24 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
-    // If task has been shared, broadcast update
-    if task.shared_task_id.is_some() {
+    // If task has been shared, broadcast update merged with teammates' edits
+    let task = if task.shared_task_id.is_some() {
         let Ok(publisher) = deployment.share_publisher() else {
             return Err(ShareError::MissingConfig("share publisher unavailable").into());
         };
-        publisher.update_shared_task(&task).await?;
-    }
+        publisher.update_shared_task(&task).await?
+    } else {
+        task
+    };
+    task_attempts::util::spawn_share_rules(&deployment, task.id);
+
+    automation_scripts::spawn(
+        deployment.db().pool.clone(),
+        deployment.git().clone(),
+        task.clone(),
+        ScriptEvent::TaskUpdated,
+        serde_json::json!({ "previous_status": previous_status }),
+    );
 
     Ok(ResponseJson(ApiResponse::success(task)))
 }
@@ -432,82 +949,632 @@ pub async fn delete_task(
         )
         .await;
 
-    let task_id = task.id;
-    let pool = pool.clone();
-    tokio::spawn(async move {
-        tracing::info!(
-            "Starting background cleanup for task {} ({} workspaces, {} repos)",
-            task_id,
-            workspace_dirs.len(),
-            repositories.len()
-        );
-
-        for workspace_dir in &workspace_dirs {
-            if let Err(e) = WorkspaceManager::cleanup_workspace(workspace_dir, &repositories).await
-            {
-                tracing::error!(
-                    "Background workspace cleanup failed for task {} at {}: {}",
-                    task_id,
-                    workspace_dir.display(),
-                    e
-                );
-            }
-        }
-
-        match Repo::delete_orphaned(&pool).await {
-            Ok(count) if count > 0 => {
-                tracing::info!("Deleted {} orphaned repo records", count);
-            }
-            Err(e) => {
-                tracing::error!("Failed to delete orphaned repos: {}", e);
-            }
-            _ => {}
-        }
-
-        tracing::info!("Background cleanup completed for task {}", task_id);
-    });
+    deployment
+        .jobs()
+        .enqueue_or_log(JobKind::CleanupTask {
+            task_id: task.id,
+            workspace_dirs,
+            repositories,
+        })
+        .await;
 
     // Return 202 Accepted to indicate deletion was scheduled
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-pub struct ShareTaskResponse {
-    pub shared_task_id: Uuid,
-}
-
-pub async fn share_task(
+/// Move a task to another project, keeping its workspaces, branches and PRs.
+/// Labels are matched by name in the destination, which must contain every
+/// repo the task's workspaces use.
+pub async fn move_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<ShareTaskResponse>>, ApiError> {
-    let Ok(publisher) = deployment.share_publisher() else {
-        return Err(ShareError::MissingConfig("share publisher unavailable").into());
-    };
-    let profile = deployment
-        .auth_context()
-        .cached_profile()
-        .await
-        .ok_or(ShareError::MissingAuth)?;
-    let shared_task_id = publisher.share_task(task.id, profile.user_id).await?;
+    user: Option<Extension<RequestUser>>,
+    Json(payload): Json<MoveTask>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    if payload.project_id == task.project_id {
+        return Err(ApiError::BadRequest(
+            "Task already belongs to this project".to_string(),
+        ));
+    }
+    ensure_project_access(&deployment, user.as_deref(), payload.project_id).await?;
+
+    let pool = &deployment.db().pool;
+    let destination = Project::find_by_id(pool, payload.project_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Destination project not found".to_string()))?;
+
+    // A shared task lives in the remote project its local project is linked to
+    if task.shared_task_id.is_some() {
+        let source = task.parent_project(pool).await?;
+        if source.and_then(|p| p.remote_project_id) != destination.remote_project_id {
+            return Err(ApiError::Conflict(
+                "Shared tasks can only move to a project linked to the same remote project"
+                    .to_string(),
+            ));
+        }
+    }
+
+    let moved = move_task_to_project(pool, &task, destination.id).await?;
 
-    let props = serde_json::json!({
-        "task_id": task.id,
-        "shared_task_id": shared_task_id,
-    });
     deployment
-        .track_if_analytics_allowed("start_sharing_task", props)
+        .track_if_analytics_allowed(
+            "task_moved",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "from_project_id": task.project_id.to_string(),
+                "to_project_id": destination.id.to_string(),
+            }),
+        )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(ShareTaskResponse {
-        shared_task_id,
-    })))
+    Ok(ResponseJson(ApiResponse::success(moved)))
 }
 
-pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    let task_actions_router = Router::new()
-        .route("/", put(update_task))
-        .route("/", delete(delete_task))
-        .route("/share", post(share_task));
+async fn move_task_to_project(
+    pool: &SqlitePool,
+    task: &Task,
+    project_id: Uuid,
+) -> Result<Task, ApiError> {
+    let project_repo_ids: HashSet<Uuid> = ProjectRepo::find_by_project_id(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|project_repo| project_repo.repo_id)
+        .collect();
+    let missing: Vec<String> = WorkspaceRepo::find_unique_repos_for_task(pool, task.id)
+        .await?
+        .into_iter()
+        .filter(|repo| !project_repo_ids.contains(&repo.id))
+        .map(|repo| repo.display_name)
+        .collect();
+    if !missing.is_empty() {
+        return Err(ApiError::Conflict(format!(
+            "Destination project is missing repositories used by this task: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    let moved = Task::move_to_project(&mut *tx, task.id, project_id).await?;
+    TaskLabel::remap_to_project(&mut *tx, task.id, project_id).await?;
+    tx.commit().await?;
+    Ok(moved)
+}
+
+/// Merge a duplicate task into another task of the same project. The
+/// duplicate's workspaces (with their sessions, processes and PRs), images
+/// and labels move to the surviving task; the duplicate is cancelled with a
+/// link to it.
+pub async fn merge_task(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((task_id, other_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    if task_id == other_id {
+        return Err(ApiError::BadRequest(
+            "A task cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let not_found = || ApiError::BadRequest("Task not found".to_string());
+    let duplicate = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let survivor = Task::find_by_id(pool, other_id)
+        .await?
+        .ok_or_else(not_found)?;
+    ensure_project_access(&deployment, user.as_deref(), duplicate.project_id).await?;
+    if duplicate.project_id != survivor.project_id {
+        return Err(ApiError::BadRequest(
+            "Tasks must belong to the same project; move one first".to_string(),
+        ));
+    }
+    ensure_shared_task_auth(&duplicate, &deployment).await?;
+    ensure_shared_task_auth(&survivor, &deployment).await?;
+
+    let (duplicate, survivor) = merge_task_records(pool, &duplicate, &survivor).await?;
+
+    for task in [&duplicate, &survivor] {
+        if task.shared_task_id.is_some() {
+            let Ok(publisher) = deployment.share_publisher() else {
+                return Err(ShareError::MissingConfig("share publisher unavailable").into());
+            };
+            publisher.update_shared_task(task).await?;
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_merged",
+            serde_json::json!({
+                "task_id": duplicate.id.to_string(),
+                "merged_into_task_id": survivor.id.to_string(),
+                "project_id": survivor.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(survivor)))
+}
+
+fn merged_description(duplicate: &Task, survivor: &Task) -> String {
+    let link = format!("Merged into \"{}\" (task {}).", survivor.title, survivor.id);
+    match duplicate.description.as_deref().map(str::trim) {
+        Some(description) if !description.is_empty() => format!("{description}\n\n{link}"),
+        _ => link,
+    }
+}
+
+/// Returns the cancelled duplicate and the surviving task
+async fn merge_task_records(
+    pool: &SqlitePool,
+    duplicate: &Task,
+    survivor: &Task,
+) -> Result<(Task, Task), ApiError> {
+    let moved_workspace_ids: HashSet<Uuid> = Workspace::fetch_all(pool, Some(duplicate.id))
+        .await?
+        .into_iter()
+        .map(|workspace| workspace.id)
+        .collect();
+
+    // The survivor takes over the duplicate's parent, but must not end up as
+    // a child of one of its own (moved) workspaces
+    let parent_workspace_id = survivor
+        .parent_workspace_id
+        .or(duplicate.parent_workspace_id)
+        .filter(|id| !moved_workspace_ids.contains(id));
+
+    let mut tx = pool.begin().await?;
+    Workspace::reassign_task(&mut *tx, duplicate.id, survivor.id).await?;
+    TaskImage::reassign_task(&mut *tx, duplicate.id, survivor.id).await?;
+    TaskLabel::reassign_task(&mut *tx, duplicate.id, survivor.id).await?;
+    if parent_workspace_id != survivor.parent_workspace_id {
+        Task::update_parent_workspace_id(&mut *tx, survivor.id, parent_workspace_id).await?;
+    }
+    let duplicate = Task::cancel_as_duplicate(
+        &mut *tx,
+        duplicate.id,
+        &merged_description(duplicate, survivor),
+    )
+    .await?;
+    tx.commit().await?;
+
+    let survivor = Task::find_by_id(pool, survivor.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    Ok((duplicate, survivor))
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct ShareTaskResponse {
+    pub shared_task_id: Uuid,
+}
+
+pub async fn share_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ShareTaskResponse>>, ApiError> {
+    let Ok(publisher) = deployment.share_publisher() else {
+        return Err(ShareError::MissingConfig("share publisher unavailable").into());
+    };
+    let profile = deployment
+        .auth_context()
+        .cached_profile()
+        .await
+        .ok_or(ShareError::MissingAuth)?;
+    let shared_task_id = publisher.share_task(task.id, profile.user_id).await?;
+
+    let props = serde_json::json!({
+        "task_id": task.id,
+        "shared_task_id": shared_task_id,
+    });
+    deployment
+        .track_if_analytics_allowed("start_sharing_task", props)
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(ShareTaskResponse {
+        shared_task_id,
+    })))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct EnrichTaskQuery {
+    /// Return the brief without saving it to the description
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct EnrichTaskResponse {
+    pub task: Task,
+    pub brief: TaskBrief,
+}
+
+/// Append a brief of the repo files relevant to the task to its description
+pub async fn enrich_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<EnrichTaskQuery>,
+) -> Result<ResponseJson<ApiResponse<EnrichTaskResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repos = deployment
+        .project()
+        .get_repositories(pool, task.project_id)
+        .await?;
+    // The embedding index only adds to the keyword matches, so its failures
+    // do not fail the enrichment
+    let embedder = Embedder::from_config(&*deployment.config().read().await);
+    let suggested = match embedder {
+        Some(embedder) => embedding_index::suggest(
+            pool,
+            &embedder,
+            &repos,
+            &task,
+            embedding_index::DEFAULT_SUGGESTIONS,
+        )
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Embedding suggestions failed for task {}: {}", task.id, e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+    let brief = task_enrichment::enrich(
+        deployment.file_search_cache().as_ref(),
+        &repos,
+        &task,
+        &suggested,
+    )
+    .await;
+
+    let task = if query.preview {
+        task
+    } else {
+        let description = task_enrichment::append_brief(task.description.as_deref(), &brief.brief);
+        Task::update_description(pool, task.id, Some(&description)).await?
+    };
+
+    Ok(ResponseJson(ApiResponse::success(EnrichTaskResponse {
+        task,
+        brief,
+    })))
+}
+
+pub async fn get_context_pack(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskContextPack>>>, ApiError> {
+    let pack = TaskContextPack::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(pack)))
+}
+
+pub async fn update_context_pack(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpsertTaskContextPack>,
+) -> Result<ResponseJson<ApiResponse<TaskContextPack>>, ApiError> {
+    let pack = TaskContextPack::upsert(&deployment.db().pool, task.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(pack)))
+}
+
+pub async fn delete_context_pack(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskContextPack::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// The rendered pack; `null` when the task has none
+pub async fn preview_context_pack(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, ApiError> {
+    let rendered = context_pack::preview(&deployment.db().pool, &task).await?;
+    Ok(ResponseJson(ApiResponse::success(rendered)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SuggestedContextQuery {
+    pub limit: Option<usize>,
+}
+
+async fn suggested_files(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    limit: Option<usize>,
+) -> Result<Vec<SuggestedFile>, ApiError> {
+    let embedder = Embedder::from_config(&*deployment.config().read().await)
+        .ok_or(EmbeddingError::Disabled)?;
+    let pool = &deployment.db().pool;
+    let repos = deployment
+        .project()
+        .get_repositories(pool, task.project_id)
+        .await?;
+    let limit = limit
+        .unwrap_or(embedding_index::DEFAULT_SUGGESTIONS)
+        .clamp(1, 50);
+    Ok(embedding_index::suggest(pool, &embedder, &repos, task, limit).await?)
+}
+
+/// Files most relevant to the task according to the embedding index
+pub async fn get_suggested_context(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SuggestedContextQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SuggestedFile>>>, ApiError> {
+    let files = suggested_files(&deployment, &task, query.limit).await?;
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+/// Add the suggested files that are not in the task's context pack yet
+pub async fn add_suggested_context(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SuggestedContextQuery>,
+) -> Result<ResponseJson<ApiResponse<TaskContextPack>>, ApiError> {
+    let files = suggested_files(&deployment, &task, query.limit).await?;
+    let pool = &deployment.db().pool;
+    let mut items = TaskContextPack::find_by_task_id(pool, task.id)
+        .await?
+        .map(|pack| pack.items.0)
+        .unwrap_or_default();
+    for file in files {
+        let item = ContextItem::File { path: file.path };
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+
+    let payload = UpsertTaskContextPack {
+        delivery: None,
+        items,
+    };
+    payload.validate().map_err(ApiError::Validation)?;
+    let pack = TaskContextPack::upsert(pool, task.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(pack)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SimilarTasksLimitQuery {
+    pub limit: Option<usize>,
+}
+
+/// Completed tasks of the same project resembling this one
+pub async fn get_similar_tasks(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SimilarTasksLimitQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SimilarTask>>>, ApiError> {
+    let embedder = task_knowledge::embedder(&*deployment.config().read().await);
+    let similar = task_knowledge::similar(
+        &deployment.db().pool,
+        &embedder,
+        task.project_id,
+        &task.title,
+        task.description.as_deref(),
+        Some(task.id),
+        query
+            .limit
+            .unwrap_or(task_knowledge::DEFAULT_LIMIT)
+            .clamp(1, 20),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(similar)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskEpicRequest {
+    /// `null` takes the task out of its epic
+    pub epic_id: Option<Uuid>,
+}
+
+pub async fn set_task_epic(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskEpicRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(epic_id) = payload.epic_id {
+        Epic::find_by_id(pool, epic_id)
+            .await?
+            .filter(|epic| epic.project_id == task.project_id)
+            .ok_or_else(|| ApiError::BadRequest("Epic not found in project".to_string()))?;
+    }
+    Epic::set_for_task(pool, task.id, payload.epic_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskAssigneeRequest {
+    /// `null` unassigns the task
+    pub user_id: Option<Uuid>,
+}
+
+pub async fn set_task_assignee(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskAssigneeRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskAssignee>>>, ApiError> {
+    let assignee = TaskAssignee::set(&deployment.db().pool, task.id, payload.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(assignee)))
+}
+
+pub async fn get_estimate(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskEstimate>>>, ApiError> {
+    let estimate = TaskEstimate::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(estimate)))
+}
+
+pub async fn update_estimate(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    ValidatedJson(payload): ValidatedJson<UpsertTaskEstimate>,
+) -> Result<ResponseJson<ApiResponse<TaskEstimate>>, ApiError> {
+    let estimate = TaskEstimate::upsert(&deployment.db().pool, task.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(estimate)))
+}
+
+pub async fn delete_estimate(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskEstimate::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Priority, due date and preferred executor of the task
+pub async fn get_planning(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskPlanning>>>, ApiError> {
+    let planning = TaskPlanning::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(planning)))
+}
+
+pub async fn update_planning(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertTaskPlanning>,
+) -> Result<ResponseJson<ApiResponse<TaskPlanning>>, ApiError> {
+    let planning = TaskPlanning::upsert(&deployment.db().pool, task.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(planning)))
+}
+
+/// Files kept with the task, such as the voice note it came from
+pub async fn get_attachments(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttachment>>>, ApiError> {
+    let attachments = TaskAttachment::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(attachments)))
+}
+
+/// Notable events in the task's history, newest first, such as conflicting
+/// edits to a shared task
+pub async fn get_task_activity(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskActivity>>>, ApiError> {
+    let activity = TaskActivity::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(activity)))
+}
+
+pub async fn serve_attachment(
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Path((task_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let attachment = TaskAttachment::find_by_id(pool, attachment_id)
+        .await?
+        .filter(|attachment| attachment.task_id == task_id)
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    let task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    ensure_project_access(&deployment, user.as_deref(), task.project_id).await?;
+
+    let data = tokio::fs::read(attachments::absolute_path(&attachment)).await?;
+    let content_type = attachment
+        .mime_type
+        .as_deref()
+        .unwrap_or("application/octet-stream");
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_LENGTH, data.len())
+        .body(Body::from(data))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+/// Time tracked on the task, agent runs included
+pub async fn get_task_time(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskTime>>, ApiError> {
+    let time = TaskTime::collect(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(time)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartTimerRequest {
+    pub note: Option<String>,
+}
+
+pub async fn start_timer(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+    Json(payload): Json<StartTimerRequest>,
+) -> Result<ResponseJson<ApiResponse<TimeEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let user_id = user.map(|Extension(user)| user.user_id);
+    if TimeEntry::find_running(pool, task.id, user_id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(
+            "A timer is already running for this task".to_string(),
+        ));
+    }
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|note| !note.is_empty());
+    let entry = TimeEntry::start(pool, task.id, user_id, note).await?;
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub async fn stop_timer(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
+) -> Result<ResponseJson<ApiResponse<TimeEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let user_id = user.map(|Extension(user)| user.user_id);
+    let running = TimeEntry::find_running(pool, task.id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("No timer is running for this task".to_string()))?;
+    let entry = TimeEntry::stop(pool, running.id).await?;
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let task_actions_router = Router::new()
+        .route("/", put(update_task))
+        .route("/", delete(delete_task))
+        .route("/share", post(share_task))
+        .route("/move", post(move_task))
+        .route("/enrich", post(enrich_task))
+        .route(
+            "/context-pack",
+            get(get_context_pack)
+                .put(update_context_pack)
+                .delete(delete_context_pack),
+        )
+        .route("/context-pack/preview", get(preview_context_pack))
+        .route("/context-pack/suggested", post(add_suggested_context))
+        .route("/suggested-context", get(get_suggested_context))
+        .route("/similar", get(get_similar_tasks))
+        .route("/fan-out", get(get_fan_out).post(fan_out_task))
+        .route("/epic", put(set_task_epic))
+        .route("/assignee", put(set_task_assignee))
+        .route(
+            "/estimate",
+            get(get_estimate)
+                .put(update_estimate)
+                .delete(delete_estimate),
+        )
+        .route("/planning", get(get_planning).put(update_planning))
+        .route("/attachments", get(get_attachments))
+        .route("/activity", get(get_task_activity))
+        .route("/time", get(get_task_time))
+        .route("/time/start", post(start_timer))
+        .route("/time/stop", post(stop_timer));
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
@@ -519,8 +1586,411 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/active/stream/ws", get(stream_active_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/trace", get(trace_task))
+        .route("/trace/blame", get(trace_blame))
+        .route("/{task_id}/merge-into/{other_id}", post(merge_task))
+        .route(
+            "/{task_id}/attachments/{attachment_id}/file",
+            get(serve_attachment),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
     Router::new().nest("/tasks", inner)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use db::{
+        DBService,
+        models::{project::CreateProject, repo::Repo, task_label::CreateTaskLabel},
+    };
+
+    use super::*;
+
+    async fn create_project(pool: &SqlitePool, name: &str) -> Uuid {
+        Project::create(
+            pool,
+            &CreateProject {
+                name: name.to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn create_repo(pool: &SqlitePool) -> Uuid {
+        Repo::find_or_create(pool, Path::new("/tmp/vk-test-repo"), "repo")
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn create_label(pool: &SqlitePool, project_id: Uuid) -> Uuid {
+        TaskLabel::create(
+            pool,
+            &CreateTaskLabel {
+                project_id,
+                name: "bug".to_string(),
+                color: "#d73a4a".to_string(),
+            },
+        )
+        .await
+        .unwrap()
+        .id
+    }
+
+    fn new_task(project_id: Uuid, label_ids: Vec<Uuid>) -> CreateTask {
+        CreateTask {
+            label_ids: Some(label_ids),
+            ..CreateTask::from_title_description(project_id, "Task".to_string(), None)
+        }
+    }
+
+    fn new_workspace() -> CreateWorkspace {
+        CreateWorkspace {
+            branch: "vk/test".to_string(),
+            agent_working_dir: None,
+        }
+    }
+
+    fn on_main(repo_id: Uuid) -> [CreateWorkspaceRepo; 1] {
+        [CreateWorkspaceRepo {
+            repo_id,
+            target_branch: "main".to_string(),
+            path_scope: None,
+        }]
+    }
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn assert_nothing_inserted(pool: &SqlitePool) {
+        for table in [
+            "tasks",
+            "task_images",
+            "task_label_associations",
+            "workspaces",
+            "workspace_repos",
+        ] {
+            assert_eq!(count(pool, table).await, 0, "{table} has leftover rows");
+        }
+    }
+
+    #[tokio::test]
+    async fn inserts_task_with_labels_workspace_and_repos() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+        let repo_id = create_repo(&db.pool).await;
+
+        let (task, workspace) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &on_main(repo_id),
+            &[],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(workspace.task_id, task.id);
+        assert_eq!(count(&db.pool, "task_label_associations").await, 1);
+        assert_eq!(count(&db.pool, "workspace_repos").await, 1);
+    }
+
+    #[tokio::test]
+    async fn board_rows_include_labels_and_attempt_status() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+        let (task, _) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &[],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let rows = Task::find_by_project_id_with_attempt_status(&db.pool, project_id)
+            .await
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.task.id, task.id);
+        assert_eq!(
+            row.labels.iter().map(|l| l.id).collect::<Vec<_>>(),
+            [label_id]
+        );
+        assert!(!row.has_in_progress_attempt && !row.last_attempt_failed);
+        assert_eq!(row.executor, "unknown");
+        assert_eq!((row.pr_number, row.checklist_items), (None, None));
+    }
+
+    #[tokio::test]
+    async fn unknown_repo_rolls_back_everything() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+
+        let result = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &on_main(Uuid::new_v4()),
+            &[],
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_nothing_inserted(&db.pool).await;
+    }
+
+    #[tokio::test]
+    async fn unknown_label_rolls_back_everything() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+
+        let result = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![Uuid::new_v4()]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &[],
+            &[],
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_nothing_inserted(&db.pool).await;
+    }
+
+    #[tokio::test]
+    async fn unknown_image_rolls_back_everything() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let task = CreateTask {
+            image_ids: Some(vec![Uuid::new_v4()]),
+            ..new_task(project_id, Vec::new())
+        };
+
+        let result = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &task,
+            Uuid::new_v4(),
+            &new_workspace(),
+            &[],
+            &[],
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_nothing_inserted(&db.pool).await;
+    }
+
+    #[tokio::test]
+    async fn deleting_unstarted_task_removes_its_workspace() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+        let repo_id = create_repo(&db.pool).await;
+        let (task, _) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &on_main(repo_id),
+            &[],
+        )
+        .await
+        .unwrap();
+
+        // The compensating delete after a failed start relies on these cascades
+        Task::delete(&db.pool, task.id).await.unwrap();
+        assert_nothing_inserted(&db.pool).await;
+    }
+
+    #[tokio::test]
+    async fn moving_task_keeps_workspace_and_remaps_labels_by_name() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+        let repo_id = create_repo(&db.pool).await;
+        let destination = create_project(&db.pool, "Destination").await;
+        ProjectRepo::create(&db.pool, destination, repo_id)
+            .await
+            .unwrap();
+        let (task, workspace) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &on_main(repo_id),
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let moved = move_task_to_project(&db.pool, &task, destination)
+            .await
+            .unwrap();
+
+        assert_eq!(moved.project_id, destination);
+        let workspaces = Workspace::fetch_all(&db.pool, Some(task.id)).await.unwrap();
+        assert_eq!(
+            workspaces.iter().map(|w| w.id).collect::<Vec<_>>(),
+            [workspace.id]
+        );
+        let labels = TaskLabel::find_by_task_id(&db.pool, task.id).await.unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(
+            (
+                labels[0].project_id,
+                labels[0].name.as_str(),
+                labels[0].color.as_str()
+            ),
+            (destination, "bug", "#d73a4a")
+        );
+    }
+
+    #[tokio::test]
+    async fn moving_task_requires_its_repos_in_the_destination() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let repo_id = create_repo(&db.pool).await;
+        let destination = create_project(&db.pool, "Destination").await;
+        let (task, _) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, Vec::new()),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &on_main(repo_id),
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let result = move_task_to_project(&db.pool, &task, destination).await;
+
+        assert!(matches!(result, Err(ApiError::Conflict(_))));
+        let unchanged = Task::find_by_id(&db.pool, task.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.project_id, project_id);
+    }
+
+    #[tokio::test]
+    async fn merging_moves_workspaces_and_labels_and_cancels_duplicate() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let label_id = create_label(&db.pool, project_id).await;
+        let (duplicate, workspace) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, vec![label_id]),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &[],
+            &[],
+        )
+        .await
+        .unwrap();
+        let survivor = Task::create(&db.pool, &new_task(project_id, Vec::new()), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let (duplicate, survivor) = merge_task_records(&db.pool, &duplicate, &survivor)
+            .await
+            .unwrap();
+
+        assert_eq!(duplicate.status, TaskStatus::Cancelled);
+        assert!(
+            duplicate
+                .description
+                .unwrap()
+                .contains(&survivor.id.to_string())
+        );
+        let workspaces = Workspace::fetch_all(&db.pool, Some(survivor.id))
+            .await
+            .unwrap();
+        assert_eq!(
+            workspaces.iter().map(|w| w.id).collect::<Vec<_>>(),
+            [workspace.id]
+        );
+        let labels = TaskLabel::find_by_task_id(&db.pool, survivor.id)
+            .await
+            .unwrap();
+        assert_eq!(labels.iter().map(|l| l.id).collect::<Vec<_>>(), [label_id]);
+        assert!(
+            TaskLabel::find_by_task_id(&db.pool, duplicate.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn promoting_an_attempt_archives_the_others() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let project_id = create_project(&db.pool, "Project").await;
+        let (task, first) = insert_task_with_workspace(
+            &db.pool,
+            Uuid::new_v4(),
+            &new_task(project_id, Vec::new()),
+            Uuid::new_v4(),
+            &new_workspace(),
+            &[],
+            &[],
+        )
+        .await
+        .unwrap();
+        let second = Workspace::create(&db.pool, &new_workspace(), Uuid::new_v4(), task.id)
+            .await
+            .unwrap();
+
+        let archived = Workspace::promote(&db.pool, task.id, second.id)
+            .await
+            .unwrap();
+        assert_eq!(archived, [first.id]);
+        assert_eq!(
+            Workspace::find_canonical_id(&db.pool, task.id)
+                .await
+                .unwrap(),
+            Some(second.id)
+        );
+
+        Workspace::promote(&db.pool, task.id, first.id)
+            .await
+            .unwrap();
+        let rows = Task::find_by_project_id_with_attempt_status(&db.pool, project_id)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].canonical_workspace_id, Some(first.id));
+        let first = Workspace::find_by_id(&db.pool, first.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!first.archived);
+    }
+}
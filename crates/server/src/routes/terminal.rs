@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use axum::{
-    Router,
+    Extension, Router,
     extract::{
         Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
@@ -14,9 +14,10 @@ use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use services::services::multi_user::RequestUser;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::ensure_workspace_access};
 
 #[derive(Debug, Deserialize)]
 pub struct TerminalQuery {
@@ -52,8 +53,10 @@ enum TerminalMessage {
 pub async fn terminal_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    user: Option<Extension<RequestUser>>,
     Query(query): Query<TerminalQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    ensure_workspace_access(&deployment, user.as_deref(), query.workspace_id).await?;
     let attempt = Workspace::find_by_id(&deployment.db().pool, query.workspace_id)
         .await?
         .ok_or_else(|| ApiError::BadRequest("Attempt not found".to_string()))?;
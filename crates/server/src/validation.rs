@@ -0,0 +1,31 @@
+use axum::{
+    Json,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use utils::validation::Validate;
+
+use crate::error::ApiError;
+
+/// JSON body extractor that also runs the payload's [`Validate`] rules,
+/// rejecting with field-level errors before the handler runs.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(payload) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        payload
+            .validate()
+            .map_err(|errors| ApiError::Validation(errors).into_response())?;
+        Ok(Self(payload))
+    }
+}
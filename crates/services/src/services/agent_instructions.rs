@@ -0,0 +1,188 @@
+//! Agent instruction files (CLAUDE.md, AGENTS.md, .cursorrules) rendered
+//! from a project's canonical instructions.
+//!
+//! The registered repos are only written when asked to; worktrees get the
+//! current rendering before every coding agent run.
+
+use std::{collections::HashMap, io, path::Path};
+
+use db::models::{
+    agent_instructions::{AgentInstructionFile, ProjectAgentInstructions},
+    project::Project,
+    repo::Repo,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::prompt_library;
+
+/// One instruction file in a repo, compared with the project's rendering
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct InstructionFileStatus {
+    pub file: AgentInstructionFile,
+    pub file_name: String,
+    /// `None` when the file does not exist
+    pub content: Option<String>,
+    /// Whether the project's instructions cover this file
+    pub managed: bool,
+    /// The file matches the project's rendering; `false` for unmanaged files
+    pub in_sync: bool,
+}
+
+/// The project's instructions for `file` in `repo_name`
+pub fn render(
+    instructions: &ProjectAgentInstructions,
+    project_name: &str,
+    repo_name: &str,
+    file: AgentInstructionFile,
+) -> String {
+    let variables = HashMap::from([
+        ("project_name".to_string(), project_name.to_string()),
+        ("repo_name".to_string(), repo_name.to_string()),
+        ("file_name".to_string(), file.file_name().to_string()),
+    ]);
+    let mut rendered = prompt_library::render(&instructions.content, &variables).rendered;
+    if !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+    rendered
+}
+
+async fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Every instruction file in `dir`, the root of a repo or of its worktree
+pub async fn read_files(
+    dir: &Path,
+    instructions: Option<&ProjectAgentInstructions>,
+    project_name: &str,
+    repo_name: &str,
+) -> io::Result<Vec<InstructionFileStatus>> {
+    let mut statuses = Vec::with_capacity(AgentInstructionFile::ALL.len());
+    for file in AgentInstructionFile::ALL {
+        let content = read_optional(&dir.join(file.file_name())).await?;
+        let expected = instructions
+            .filter(|i| i.files.contains(&file))
+            .map(|i| render(i, project_name, repo_name, file));
+        statuses.push(InstructionFileStatus {
+            file,
+            file_name: file.file_name().to_string(),
+            in_sync: expected.is_some() && expected == content,
+            managed: expected.is_some(),
+            content,
+        });
+    }
+    Ok(statuses)
+}
+
+pub async fn write_file(dir: &Path, file: AgentInstructionFile, content: &str) -> io::Result<()> {
+    tokio::fs::write(dir.join(file.file_name()), content).await
+}
+
+/// Write the project's rendering into `dir`, leaving files that already match
+/// untouched. Returns the files that were written.
+pub async fn apply(
+    dir: &Path,
+    instructions: &ProjectAgentInstructions,
+    project_name: &str,
+    repo_name: &str,
+) -> io::Result<Vec<AgentInstructionFile>> {
+    let mut written = Vec::new();
+    for file in instructions.files.iter().copied() {
+        let rendered = render(instructions, project_name, repo_name, file);
+        let path = dir.join(file.file_name());
+        if read_optional(&path).await?.as_deref() == Some(rendered.as_str()) {
+            continue;
+        }
+        tokio::fs::write(&path, rendered).await?;
+        written.push(file);
+    }
+    Ok(written)
+}
+
+/// Bring the instruction files of every repo in a workspace up to date.
+/// Failures to write are logged so they never block a run.
+pub async fn sync_workspace(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    workspace_dir: &Path,
+    repos: &[Repo],
+) -> Result<(), sqlx::Error> {
+    let Some(project) = Project::find_by_id(pool, project_id).await? else {
+        return Ok(());
+    };
+    let Some(instructions) = Project::find_agent_instructions(pool, project_id).await? else {
+        return Ok(());
+    };
+    for repo in repos {
+        let worktree = workspace_dir.join(&repo.name);
+        if !worktree.is_dir() {
+            continue;
+        }
+        match apply(&worktree, &instructions, &project.name, &repo.name).await {
+            Ok(written) if !written.is_empty() => tracing::info!(
+                "Updated {} agent instruction file(s) in {}",
+                written.len(),
+                worktree.display()
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                "Failed to write agent instructions in {}: {}",
+                worktree.display(),
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn apply_renders_configured_files_and_reports_sync() {
+        let dir = TempDir::new().unwrap();
+        let instructions = ProjectAgentInstructions {
+            content: "# {repo_name} in {project_name}\nSee {file_name}.".to_string(),
+            files: vec![
+                AgentInstructionFile::ClaudeMd,
+                AgentInstructionFile::AgentsMd,
+            ],
+        };
+
+        let written = apply(dir.path(), &instructions, "Kanban", "api")
+            .await
+            .unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("AGENTS.md")).unwrap(),
+            "# api in Kanban\nSee AGENTS.md.\n"
+        );
+        assert!(
+            apply(dir.path(), &instructions, "Kanban", "api")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let statuses = read_files(dir.path(), Some(&instructions), "Kanban", "api")
+            .await
+            .unwrap();
+        let cursor = statuses
+            .iter()
+            .find(|s| s.file == AgentInstructionFile::CursorRules)
+            .unwrap();
+        assert!(!cursor.managed && cursor.content.is_none());
+        assert!(statuses.iter().filter(|s| s.managed).all(|s| s.in_sync));
+    }
+}
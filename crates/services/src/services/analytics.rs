@@ -1,4 +1,32 @@
-use serde_json::Value;
+//! Opt-in, anonymous usage statistics.
+//!
+//! Events are only recorded while `analytics_enabled` is set in the config.
+//! They are queued in memory and periodically flushed in batches to a
+//! PostHog-compatible `/batch/` endpoint (PostHog cloud or a self-hosted
+//! collector). The queue can be inspected through the API before anything
+//! leaves the machine.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::config::Config;
+
+pub const ANALYTICS_ENDPOINT_ENV: &str = "VK_ANALYTICS_ENDPOINT";
+pub const ANALYTICS_API_KEY_ENV: &str = "VK_ANALYTICS_API_KEY";
+
+/// Oldest events are dropped beyond this many queued events
+const MAX_QUEUED_EVENTS: usize = 1000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_BATCH_SIZE: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct AnalyticsContext {
@@ -6,30 +34,222 @@ pub struct AnalyticsContext {
     pub analytics_service: AnalyticsService,
 }
 
-#[derive(Debug, Clone)]
-pub struct AnalyticsConfig;
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalyticsConfig {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub api_key: Option<String>,
+}
 
 impl AnalyticsConfig {
-    pub fn new() -> Option<Self> {
-        // Analytics disabled - return None
-        None
+    /// Settings from the user config; the environment overrides the endpoint and key
+    pub fn from_config(config: &Config) -> Self {
+        let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        Self {
+            enabled: config.analytics_enabled,
+            endpoint: non_empty(std::env::var(ANALYTICS_ENDPOINT_ENV).ok())
+                .or_else(|| non_empty(config.analytics_endpoint.clone())),
+            api_key: non_empty(std::env::var(ANALYTICS_API_KEY_ENV).ok())
+                .or_else(|| non_empty(config.analytics_api_key.clone())),
+        }
     }
 }
 
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct QueuedEvent {
+    pub event: String,
+    pub distinct_id: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub properties: Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AnalyticsQueue {
+    pub enabled: bool,
+    /// Where events are sent; `None` keeps them local until one is configured
+    pub endpoint: Option<String>,
+    pub events: Vec<QueuedEvent>,
+}
+
 #[derive(Clone, Debug)]
-pub struct AnalyticsService;
+pub struct AnalyticsService {
+    config: Arc<RwLock<AnalyticsConfig>>,
+    queue: Arc<RwLock<VecDeque<QueuedEvent>>>,
+    client: reqwest::Client,
+}
 
 impl AnalyticsService {
-    pub fn new(_config: AnalyticsConfig) -> Self {
-        Self
+    pub fn new(config: AnalyticsConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            queue: Arc::new(RwLock::new(VecDeque::new())),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn current_config(&self) -> AnalyticsConfig {
+        self.config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Apply changed settings; disabling analytics discards anything queued
+    pub fn update_config(&self, config: AnalyticsConfig) {
+        if !config.enabled {
+            self.queue
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        }
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    pub fn track_event(&self, user_id: &str, event_name: &str, properties: Option<Value>) {
+        if !self.current_config().enabled {
+            return;
+        }
+
+        let mut properties = properties.unwrap_or_else(|| json!({}));
+        if let Value::Object(map) = &mut properties {
+            map.insert("version".to_string(), json!(env!("CARGO_PKG_VERSION")));
+            map.insert("os".to_string(), json!(std::env::consts::OS));
+        }
+
+        let mut queue = self.queue.write().unwrap_or_else(|e| e.into_inner());
+        if queue.len() >= MAX_QUEUED_EVENTS {
+            queue.pop_front();
+        }
+        queue.push_back(QueuedEvent {
+            event: event_name.to_string(),
+            distinct_id: user_id.to_string(),
+            properties,
+            timestamp: Utc::now(),
+        });
     }
 
-    pub fn track_event(&self, _user_id: &str, _event_name: &str, _properties: Option<Value>) {
-        // No-op: analytics disabled
+    /// Snapshot of the pending events, for users to review what would be sent
+    pub fn queued(&self) -> AnalyticsQueue {
+        let config = self.current_config();
+        AnalyticsQueue {
+            enabled: config.enabled,
+            endpoint: config.endpoint,
+            events: self
+                .queue
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Send queued events in batches. Failed batches are put back for the next flush.
+    pub async fn flush(&self) {
+        let config = self.current_config();
+        let (true, Some(endpoint)) = (config.enabled, config.endpoint) else {
+            return;
+        };
+        let url = format!("{}/batch/", endpoint.trim_end_matches('/'));
+
+        loop {
+            let batch: Vec<QueuedEvent> = {
+                let mut queue = self.queue.write().unwrap_or_else(|e| e.into_inner());
+                let count = queue.len().min(MAX_BATCH_SIZE);
+                queue.drain(..count).collect()
+            };
+            if batch.is_empty() {
+                return;
+            }
+
+            let body = json!({
+                "api_key": config.api_key,
+                "batch": batch,
+            });
+            let result = self
+                .client
+                .post(&url)
+                .json(&body)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            if let Err(e) = result {
+                tracing::debug!("Failed to send analytics batch: {}", e);
+                let mut queue = self.queue.write().unwrap_or_else(|e| e.into_inner());
+                for event in batch.into_iter().rev() {
+                    if queue.len() < MAX_QUEUED_EVENTS {
+                        queue.push_front(event);
+                    }
+                }
+                return;
+            }
+        }
+    }
+
+    /// Flush the queue in the background on a fixed interval
+    pub fn spawn_flusher(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                service.flush().await;
+            }
+        });
     }
 }
 
-/// Stub function that returns a fixed user ID since analytics is disabled
+/// Anonymous id for this installation, generated once and kept in the data directory
 pub fn generate_user_id() -> String {
-    "local-user".to_string()
+    let path = utils::assets::asset_dir().join("analytics_id");
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return existing.to_string();
+        }
+    }
+
+    let user_id = format!("npm_user_{}", Uuid::new_v4().simple());
+    if let Err(e) = std::fs::write(&path, &user_id) {
+        tracing::warn!("Failed to persist analytics id: {}", e);
+    }
+    user_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_config() -> AnalyticsConfig {
+        AnalyticsConfig {
+            enabled: true,
+            endpoint: None,
+            api_key: None,
+        }
+    }
+
+    #[test]
+    fn ignores_events_while_disabled() {
+        let service = AnalyticsService::new(AnalyticsConfig::default());
+        service.track_event("user", "task_created", None);
+        assert!(service.queued().events.is_empty());
+    }
+
+    #[test]
+    fn disabling_clears_queue() {
+        let service = AnalyticsService::new(enabled_config());
+        service.track_event(
+            "user",
+            "task_created",
+            Some(json!({ "executor": "CLAUDE_CODE" })),
+        );
+        assert_eq!(service.queued().events.len(), 1);
+
+        service.update_config(AnalyticsConfig::default());
+        assert!(service.queued().events.is_empty());
+    }
 }
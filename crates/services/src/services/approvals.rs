@@ -261,6 +261,13 @@ impl Approvals {
         map.get(execution_process_id).cloned()
     }
 
+    /// Execution process that raised a pending approval request
+    pub fn pending_execution_process_id(&self, id: &str) -> Option<Uuid> {
+        self.pending
+            .get(id)
+            .map(|entry| entry.value().execution_process_id)
+    }
+
     /// Check which execution processes have pending approvals.
     /// Returns a set of execution_process_ids that have at least one pending approval.
     pub fn get_pending_execution_process_ids(
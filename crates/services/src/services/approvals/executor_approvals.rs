@@ -7,7 +7,11 @@ use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals,
+    i18n::{Text, tr, tr_with},
+    notification::NotificationService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
@@ -60,12 +64,12 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         let task_name = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
             .await
             .map(|ctx| ctx.task.title)
-            .unwrap_or_else(|_| "Unknown task".to_string());
+            .unwrap_or_else(|_| tr(Text::UnknownTask).to_string());
 
         self.notification_service
             .notify(
-                &format!("Approval Needed: {}", task_name),
-                &format!("Tool '{}' requires approval", tool_name),
+                &tr_with(Text::ApprovalNeededTitle, &[("task", &task_name)]),
+                &tr_with(Text::ApprovalNeededBody, &[("tool", tool_name)]),
             )
             .await;
 
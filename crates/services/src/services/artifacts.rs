@@ -0,0 +1,229 @@
+//! Files an attempt produces beyond its diff, such as built binaries,
+//! coverage reports and screenshots from test runs.
+//!
+//! Every attempt gets a directory under the cache, handed to its runs as
+//! `VK_ARTIFACTS_DIR`. Whatever a run leaves there is recorded when it
+//! finishes, and files elsewhere in the worktree can be registered by hand.
+//! Artifacts outlive the worktree and are pruned once older than the
+//! configured retention.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    attempt_artifact::{AttemptArtifact, CreateAttemptArtifact},
+    workspace::Workspace,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Environment variable pointing runs at their attempt's artifacts directory
+pub const ARTIFACTS_DIR_ENV: &str = "VK_ARTIFACTS_DIR";
+
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid artifact name: {0}")]
+    InvalidName(String),
+    #[error("Artifact source is not a file: {0}")]
+    NotAFile(String),
+}
+
+pub fn artifacts_dir() -> PathBuf {
+    utils::cache_dir().join("artifacts")
+}
+
+/// Directory the attempt's runs write their artifacts to
+pub fn workspace_dir(workspace_id: Uuid) -> PathBuf {
+    artifacts_dir().join(workspace_id.to_string())
+}
+
+pub fn absolute_path(artifact: &AttemptArtifact) -> PathBuf {
+    artifacts_dir().join(&artifact.file_path)
+}
+
+/// Check that `name` is a plain relative path that stays inside the
+/// artifacts directory, and normalize its separators
+pub fn validate_name(name: &str) -> Result<String, ArtifactError> {
+    let invalid = || ArtifactError::InvalidName(name.to_string());
+    let mut parts = Vec::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str().ok_or_else(invalid)?),
+            Component::CurDir => {}
+            _ => return Err(invalid()),
+        }
+    }
+    if parts.is_empty() {
+        return Err(invalid());
+    }
+    Ok(parts.join("/"))
+}
+
+/// Record everything in the attempt's artifacts directory that is new or
+/// changed since it was last recorded, crediting `execution_process_id`
+pub async fn collect(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    execution_process_id: Uuid,
+) -> Result<Vec<AttemptArtifact>, ArtifactError> {
+    let dir = workspace_dir(workspace_id);
+    if !tokio::fs::try_exists(&dir).await? {
+        return Ok(Vec::new());
+    }
+
+    let known: HashMap<String, AttemptArtifact> =
+        AttemptArtifact::find_by_workspace_id(pool, workspace_id)
+            .await?
+            .into_iter()
+            .map(|artifact| (artifact.name.clone(), artifact))
+            .collect();
+    let files = tokio::task::spawn_blocking(move || list_files(&dir))
+        .await
+        .map_err(std::io::Error::other)?;
+
+    let mut collected = Vec::new();
+    for (name, size, modified) in files {
+        let unchanged = known
+            .get(&name)
+            .is_some_and(|artifact| artifact.size_bytes == size && modified <= artifact.created_at);
+        if unchanged {
+            continue;
+        }
+        let artifact = AttemptArtifact::upsert(
+            pool,
+            workspace_id,
+            &CreateAttemptArtifact {
+                execution_process_id: Some(execution_process_id),
+                file_path: format!("{workspace_id}/{name}"),
+                name,
+                size_bytes: size,
+            },
+        )
+        .await?;
+        collected.push(artifact);
+    }
+    Ok(collected)
+}
+
+/// Copy `source` into the attempt's artifacts directory as `name` and
+/// record it
+pub async fn register(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    source: &Path,
+    name: &str,
+) -> Result<AttemptArtifact, ArtifactError> {
+    let name = validate_name(name)?;
+    let metadata = tokio::fs::metadata(source).await?;
+    if !metadata.is_file() {
+        return Err(ArtifactError::NotAFile(source.display().to_string()));
+    }
+
+    let target = workspace_dir(workspace_id).join(&name);
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::copy(source, &target).await?;
+
+    Ok(AttemptArtifact::upsert(
+        pool,
+        workspace_id,
+        &CreateAttemptArtifact {
+            execution_process_id,
+            file_path: format!("{workspace_id}/{name}"),
+            name,
+            size_bytes: metadata.len() as i64,
+        },
+    )
+    .await?)
+}
+
+pub async fn delete(pool: &SqlitePool, artifact: &AttemptArtifact) -> Result<(), ArtifactError> {
+    AttemptArtifact::delete(pool, artifact.id).await?;
+    remove_file(&absolute_path(artifact)).await;
+    Ok(())
+}
+
+/// Delete artifacts older than `retention_days`, and the directories of
+/// workspaces that no longer exist. A retention of zero keeps artifacts
+/// until their workspace is deleted.
+pub async fn prune(pool: &SqlitePool, retention_days: u32) -> Result<(), ArtifactError> {
+    if retention_days > 0 {
+        let expired = AttemptArtifact::delete_older_than(pool, i64::from(retention_days)).await?;
+        if !expired.is_empty() {
+            tracing::info!("Pruning {} expired artifacts", expired.len());
+        }
+        for artifact in &expired {
+            remove_file(&absolute_path(artifact)).await;
+        }
+    }
+
+    let Ok(mut entries) = tokio::fs::read_dir(artifacts_dir()).await else {
+        return Ok(());
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(workspace_id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| Uuid::parse_str(name).ok())
+        else {
+            continue;
+        };
+        if Workspace::find_by_id(pool, workspace_id).await?.is_none() {
+            tracing::debug!("Removing artifacts of deleted workspace {}", workspace_id);
+            let _ = tokio::fs::remove_dir_all(entry.path()).await;
+        }
+    }
+    Ok(())
+}
+
+async fn remove_file(path: &Path) {
+    if let Err(e) = tokio::fs::remove_file(path).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove artifact {}: {}", path.display(), e);
+    }
+}
+
+/// Regular files under `dir` as (name relative to `dir`, size, modified),
+/// without following symlinks out of it
+fn list_files(dir: &Path) -> Vec<(String, i64, DateTime<Utc>)> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let name = entry.path().strip_prefix(dir).ok()?.to_str()?;
+            let name = validate_name(name).ok()?;
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().ok()?.into();
+            Some((name, metadata.len() as i64, modified))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_paths_inside_the_directory() {
+        assert_eq!(
+            validate_name("./coverage/index.html").unwrap(),
+            "coverage/index.html"
+        );
+        assert!(validate_name("../secrets").is_err());
+        assert!(validate_name("/etc/passwd").is_err());
+        assert!(validate_name("reports/../../x").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
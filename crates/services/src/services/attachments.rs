@@ -0,0 +1,70 @@
+//! Files kept with tasks, stored under the cache directory.
+
+use std::{collections::HashSet, path::PathBuf};
+
+use db::models::task_attachment::{CreateTaskAttachment, TaskAttachment};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub fn attachments_dir() -> PathBuf {
+    utils::cache_dir().join("attachments")
+}
+
+pub fn absolute_path(attachment: &TaskAttachment) -> PathBuf {
+    attachments_dir().join(&attachment.file_path)
+}
+
+/// Write `data` to the attachments directory and record it for `task_id`
+pub async fn store(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    data: &[u8],
+    original_name: &str,
+    mime_type: Option<String>,
+) -> Result<TaskAttachment, sqlx::Error> {
+    let dir = attachments_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+    let extension = std::path::Path::new(original_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|ext| format!(".{}", ext.to_ascii_lowercase()))
+        .unwrap_or_default();
+    let file_path = format!("{}{extension}", Uuid::new_v4());
+    tokio::fs::write(dir.join(&file_path), data).await?;
+
+    let created = TaskAttachment::create(
+        pool,
+        task_id,
+        &CreateTaskAttachment {
+            file_path: file_path.clone(),
+            original_name: original_name.to_string(),
+            mime_type,
+            size_bytes: data.len() as i64,
+        },
+    )
+    .await;
+    if created.is_err() {
+        let _ = tokio::fs::remove_file(dir.join(&file_path)).await;
+    }
+    created
+}
+
+/// Remove files whose task, and so attachment row, is gone
+pub async fn delete_orphaned_files(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let known: HashSet<String> = TaskAttachment::all_file_paths(pool)
+        .await?
+        .into_iter()
+        .collect();
+    let Ok(mut entries) = tokio::fs::read_dir(attachments_dir()).await else {
+        return Ok(());
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !known.contains(&name) {
+            tracing::debug!("Removing orphaned attachment {}", name);
+            let _ = tokio::fs::remove_file(entry.path()).await;
+        }
+    }
+    Ok(())
+}
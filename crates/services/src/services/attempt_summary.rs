@@ -0,0 +1,182 @@
+//! Short summaries of what an attempt changed.
+//!
+//! Derived from the coding agent's own final message: a `Summary` section is
+//! preferred when the agent wrote one, otherwise the opening sentences are
+//! used. Markdown is flattened so the result reads well in commit messages,
+//! PR bodies and tooltips.
+
+/// Sentences kept in a summary
+const MAX_SENTENCES: usize = 3;
+const MAX_SUMMARY_CHARS: usize = 500;
+/// Conventional limit for a commit subject line
+const MAX_TITLE_CHARS: usize = 72;
+
+/// Condense an agent's final message into at most three sentences
+pub fn summarize(message: &str) -> Option<String> {
+    let text = flatten_markdown(summary_section(message).unwrap_or(message));
+    let sentences = split_sentences(&text);
+    if sentences.is_empty() {
+        return None;
+    }
+
+    let mut summary = sentences
+        .into_iter()
+        .take(MAX_SENTENCES)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if summary.chars().count() > MAX_SUMMARY_CHARS {
+        summary = truncate_chars(&summary, MAX_SUMMARY_CHARS);
+    }
+    Some(summary)
+}
+
+/// Commit message from a summary: the first sentence as subject, the full
+/// summary as body when it says more
+pub fn commit_message(summary: &str) -> String {
+    let first = split_sentences(summary)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| summary.trim().to_string());
+    let title = if first.chars().count() > MAX_TITLE_CHARS {
+        truncate_chars(&first, MAX_TITLE_CHARS)
+    } else {
+        first.trim_end_matches('.').to_string()
+    };
+
+    if summary.trim() == first {
+        title
+    } else {
+        format!("{title}\n\n{}", summary.trim())
+    }
+}
+
+/// Text under a "Summary" heading or after a "Summary:" label, up to the next heading
+fn summary_section(message: &str) -> Option<&str> {
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        let trimmed = line
+            .trim()
+            .trim_start_matches('#')
+            .trim()
+            .trim_matches('*')
+            .trim();
+        let lower = trimmed.to_ascii_lowercase();
+        if lower == "summary" || lower == "summary:" {
+            let rest = &message[offset + line.len()..];
+            let end = rest
+                .find("\n#")
+                .or_else(|| rest.find("\n**"))
+                .unwrap_or(rest.len());
+            let section = rest[..end].trim();
+            return (!section.is_empty()).then_some(section);
+        }
+        if let Some(inline) = lower.strip_prefix("summary:")
+            && !inline.trim().is_empty()
+        {
+            let start = line.to_ascii_lowercase().find("summary:")? + "summary:".len();
+            return Some(message[offset + start..].trim());
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Drop code blocks and markdown markers, joining everything into one line
+fn flatten_markdown(text: &str) -> String {
+    let mut in_code_block = false;
+    let mut parts = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || trimmed.is_empty() {
+            continue;
+        }
+        let stripped = trimmed
+            .trim_start_matches('#')
+            .trim_start_matches(['-', '*', '+', '>'])
+            .trim_start();
+        let stripped = stripped
+            .split_once(". ")
+            .filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_, rest)| rest)
+            .unwrap_or(stripped);
+        let cleaned = stripped.replace("**", "").replace('`', "");
+        // Lead-ins like "Here's what I changed:" carry no information
+        if cleaned.is_empty() || cleaned.ends_with(':') {
+            continue;
+        }
+        // List items and headings rarely end with punctuation; treat each as a sentence
+        if cleaned.ends_with(['.', '!', '?']) {
+            parts.push(cleaned);
+        } else {
+            parts.push(format!("{cleaned}."));
+        }
+    }
+    parts.join(" ")
+}
+
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let at_boundary =
+            matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|next| next.is_whitespace());
+        if at_boundary {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let truncated: String = text.chars().take(max.saturating_sub(3)).collect();
+    format!("{}...", truncated.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_summary_section() {
+        let message = "I looked around first.\n\n## Summary\n- Added a retry to the uploader\n- Updated tests\n\n## Notes\nNothing else.";
+        assert_eq!(
+            summarize(message).as_deref(),
+            Some("Added a retry to the uploader. Updated tests.")
+        );
+    }
+
+    #[test]
+    fn keeps_first_three_sentences() {
+        let message = "Fixed the login bug. The token was not refreshed. Tests pass. Let me know!";
+        assert_eq!(
+            summarize(message).as_deref(),
+            Some("Fixed the login bug. The token was not refreshed. Tests pass.")
+        );
+    }
+
+    #[test]
+    fn builds_commit_message() {
+        assert_eq!(
+            commit_message("Fixed the login bug. Tests pass."),
+            "Fixed the login bug\n\nFixed the login bug. Tests pass."
+        );
+        assert_eq!(
+            commit_message("Fixed the login bug."),
+            "Fixed the login bug"
+        );
+    }
+}
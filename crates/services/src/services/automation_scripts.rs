@@ -0,0 +1,393 @@
+//! Per-project Rhai scripts run when a task is updated or an execution
+//! finishes.
+//!
+//! Scripts see the event as the `event` constant (`event.event`,
+//! `event.task`, `event.data`) and act through a small API: `set_labels`,
+//! `add_label`, `remove_label`, `post_comment` and `start_follow_up`. The
+//! calls are collected while the script runs and applied afterwards, so a
+//! script that fails or hits a limit changes nothing. Scripts cannot load
+//! modules or `eval` code, and their run time, operations and data sizes are
+//! capped.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use db::models::{
+    merge::{Merge, MergeStatus},
+    project_script::{ProjectScript, ScriptEvent},
+    repo::Repo,
+    task::Task,
+    task_label::TaskLabel,
+    workspace::{Workspace, WorkspaceError},
+};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Position, Scope};
+use serde_json::json;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{git::GitService, git_host::GitHostService};
+
+const MAX_RUN_TIME: Duration = Duration::from_secs(2);
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+/// API calls one run may make
+const MAX_ACTIONS: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum AutomationScriptError {
+    #[error("Invalid script: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+}
+
+/// A call a script made to the automation API
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScriptAction {
+    SetLabels(Vec<String>),
+    AddLabel(String),
+    RemoveLabel(String),
+    PostComment(String),
+    StartFollowUp(String),
+}
+
+type Actions = Arc<Mutex<Vec<ScriptAction>>>;
+
+fn record(actions: &Actions, action: ScriptAction) -> Result<(), Box<EvalAltResult>> {
+    let mut actions = actions.lock().unwrap();
+    if actions.len() >= MAX_ACTIONS {
+        return Err(format!("more than {MAX_ACTIONS} API calls").into());
+    }
+    actions.push(action);
+    Ok(())
+}
+
+fn build_engine(actions: &Actions) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .on_print(|text| tracing::info!("automation script: {}", text))
+        .on_debug(|text, _, pos| tracing::debug!("automation script {}: {}", pos, text));
+
+    let started = Instant::now();
+    engine.on_progress(move |_| {
+        (started.elapsed() > MAX_RUN_TIME).then(|| Dynamic::from("time limit exceeded"))
+    });
+
+    let a = actions.clone();
+    engine.register_fn("set_labels", move |names: Array| {
+        let names = names
+            .into_iter()
+            .map(|name| name.into_string())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|ty| format!("set_labels expects strings, got {ty}"))?;
+        record(&a, ScriptAction::SetLabels(names))
+    });
+    let a = actions.clone();
+    engine.register_fn("add_label", move |name: &str| {
+        record(&a, ScriptAction::AddLabel(name.to_string()))
+    });
+    let a = actions.clone();
+    engine.register_fn("remove_label", move |name: &str| {
+        record(&a, ScriptAction::RemoveLabel(name.to_string()))
+    });
+    let a = actions.clone();
+    engine.register_fn("post_comment", move |body: &str| {
+        record(&a, ScriptAction::PostComment(body.to_string()))
+    });
+    let a = actions.clone();
+    engine.register_fn("start_follow_up", move |prompt: &str| {
+        record(&a, ScriptAction::StartFollowUp(prompt.to_string()))
+    });
+    engine
+}
+
+/// Compile `source` without running it, to reject syntax errors on save
+pub fn check(source: &str) -> Result<(), AutomationScriptError> {
+    build_engine(&Actions::default())
+        .compile(source)
+        .map(|_| ())
+        .map_err(|e| AutomationScriptError::Invalid(e.to_string()))
+}
+
+/// Run `source` against `event` and return the API calls it made
+fn evaluate(source: &str, event: &serde_json::Value) -> Result<Vec<ScriptAction>, String> {
+    let actions = Actions::default();
+    let engine = build_engine(&actions);
+    let event = rhai::serde::to_dynamic(event).map_err(|e| e.to_string())?;
+    let mut scope = Scope::new();
+    scope.push_constant("event", event);
+    engine
+        .run_with_scope(&mut scope, source)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(reason, Position::NONE) => reason.to_string(),
+            EvalAltResult::ErrorTerminated(reason, pos) => format!("{reason} ({pos})"),
+            e => e.to_string(),
+        })?;
+    Ok(std::mem::take(&mut *actions.lock().unwrap()))
+}
+
+/// Run the enabled scripts of the task's project for `event` and apply their
+/// label and comment calls. Follow-up prompts are returned for the caller to
+/// start, since only it knows which session to continue.
+pub async fn run(
+    pool: &SqlitePool,
+    git: &GitService,
+    task: &Task,
+    event: ScriptEvent,
+    data: serde_json::Value,
+) -> Result<Vec<String>, AutomationScriptError> {
+    let scripts = ProjectScript::find_for_event(pool, task.project_id, event).await?;
+    if scripts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let labels = TaskLabel::find_by_task_id(pool, task.id).await?;
+    let payload = json!({
+        "event": event,
+        "project_id": task.project_id,
+        "task": {
+            "id": task.id,
+            "title": task.title,
+            "description": task.description,
+            "status": task.status,
+            "labels": labels.iter().map(|l| &l.name).collect::<Vec<_>>(),
+        },
+        "data": data,
+    });
+    let payload = Arc::new(payload);
+
+    let mut actions = Vec::new();
+    for script in scripts {
+        let payload = payload.clone();
+        let source = script.source.clone();
+        let outcome = tokio::task::spawn_blocking(move || evaluate(&source, &payload))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+        match outcome {
+            Ok(found) => actions.extend(found),
+            Err(e) => tracing::warn!(
+                "Automation script '{}' ({}) failed on {}: {}",
+                script.name,
+                script.id,
+                event.as_str(),
+                e
+            ),
+        }
+    }
+
+    apply_labels(pool, task, &actions).await?;
+    let comments: Vec<&str> = actions
+        .iter()
+        .filter_map(|action| match action {
+            ScriptAction::PostComment(body) => Some(body.as_str()),
+            _ => None,
+        })
+        .collect();
+    if !comments.is_empty() {
+        post_comments(pool, git, task.id, &comments).await?;
+    }
+
+    Ok(actions
+        .into_iter()
+        .filter_map(|action| match action {
+            ScriptAction::StartFollowUp(prompt) => Some(prompt),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Run `run` in the background for an event with no session to follow up
+pub fn spawn(
+    pool: SqlitePool,
+    git: GitService,
+    task: Task,
+    event: ScriptEvent,
+    data: serde_json::Value,
+) {
+    tokio::spawn(async move {
+        match run(&pool, &git, &task, event, data).await {
+            Ok(follow_ups) if !follow_ups.is_empty() => tracing::warn!(
+                "Ignoring {} follow-up(s) requested by {} scripts of task {}",
+                follow_ups.len(),
+                event.as_str(),
+                task.id
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to run {} scripts: {}", event.as_str(), e),
+        }
+    });
+}
+
+/// Apply label calls in order. Names are matched against the project's
+/// labels; scripts cannot create labels.
+async fn apply_labels(
+    pool: &SqlitePool,
+    task: &Task,
+    actions: &[ScriptAction],
+) -> Result<(), sqlx::Error> {
+    if !actions.iter().any(|action| {
+        matches!(
+            action,
+            ScriptAction::SetLabels(_) | ScriptAction::AddLabel(_) | ScriptAction::RemoveLabel(_)
+        )
+    }) {
+        return Ok(());
+    }
+
+    let project_labels = TaskLabel::find_by_project_id(pool, task.project_id).await?;
+    let find = |name: &str| {
+        let id = project_labels
+            .iter()
+            .find(|label| label.name == name)
+            .map(|label| label.id);
+        if id.is_none() {
+            tracing::warn!("Automation script used unknown label '{}'", name);
+        }
+        id
+    };
+
+    let current: Vec<Uuid> = TaskLabel::find_by_task_id(pool, task.id)
+        .await?
+        .into_iter()
+        .map(|label| label.id)
+        .collect();
+    let mut label_ids = current.clone();
+    for action in actions {
+        match action {
+            ScriptAction::SetLabels(names) => {
+                label_ids = names.iter().filter_map(|name| find(name)).collect();
+            }
+            ScriptAction::AddLabel(name) => {
+                if let Some(id) = find(name) {
+                    label_ids.push(id);
+                }
+            }
+            ScriptAction::RemoveLabel(name) => {
+                if let Some(id) = find(name) {
+                    label_ids.retain(|existing| *existing != id);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut seen = HashSet::new();
+    label_ids.retain(|id| seen.insert(*id));
+
+    if label_ids != current {
+        TaskLabel::sync_task_labels(pool, task.id, &label_ids).await?;
+    }
+    Ok(())
+}
+
+/// Post each comment on every open pull request of the task's attempts
+async fn post_comments(
+    pool: &SqlitePool,
+    git: &GitService,
+    task_id: Uuid,
+    comments: &[&str],
+) -> Result<(), AutomationScriptError> {
+    let mut posted = false;
+    for workspace in Workspace::fetch_all(pool, Some(task_id)).await? {
+        for merge in Merge::find_by_workspace_id(pool, workspace.id).await? {
+            let Merge::Pr(pr) = merge else {
+                continue;
+            };
+            if !matches!(pr.pr_info.status, MergeStatus::Open) {
+                continue;
+            }
+            let Some(repo) = Repo::find_by_id(pool, pr.repo_id).await? else {
+                continue;
+            };
+            let remote_url = match git
+                .resolve_remote_name_for_branch(&repo.path, &workspace.branch)
+                .and_then(|remote| git.get_remote_url(&repo.path, &remote))
+            {
+                Ok(url) => url,
+                Err(e) => {
+                    tracing::warn!("No remote for repo {}: {}", repo.name, e);
+                    continue;
+                }
+            };
+            let git_host = match GitHostService::from_url(&remote_url) {
+                Ok(git_host) => git_host,
+                Err(e) => {
+                    tracing::warn!("Cannot comment on PR #{}: {}", pr.pr_info.number, e);
+                    continue;
+                }
+            };
+            for body in comments {
+                match git_host
+                    .add_pr_comment(&repo.path, &remote_url, pr.pr_info.number, body)
+                    .await
+                {
+                    Ok(()) => posted = true,
+                    Err(e) => tracing::warn!(
+                        "Failed to post script comment on PR #{}: {}",
+                        pr.pr_info.number,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+    if !posted {
+        tracing::info!(
+            "Automation script comments for task {} had no open pull request to go to",
+            task_id
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_record_api_calls_from_the_event() {
+        let event = json!({ "event": "task_updated", "task": { "status": "inreview" } });
+        let actions = evaluate(
+            r#"
+                if event.task.status == "inreview" {
+                    add_label("needs-review");
+                    post_comment("Ready for review");
+                }
+            "#,
+            &event,
+        )
+        .unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                ScriptAction::AddLabel("needs-review".to_string()),
+                ScriptAction::PostComment("Ready for review".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        assert!(evaluate("loop {}", &json!({})).is_err());
+        assert!(evaluate("for i in 0..100 { add_label(\"x\"); }", &json!({})).is_err());
+        assert!(check("import \"fs\" as fs;").is_ok());
+        assert!(evaluate("import \"fs\" as fs;", &json!({})).is_err());
+        assert!(check("eval(\"1\")").is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! Declarative project bootstrap for headless deployments.
+//!
+//! A bootstrap file describes the projects, repositories, labels and executor
+//! profiles a server should have. On startup the database is reconciled to
+//! match: missing entities are created and drifted labels are updated. Nothing
+//! that is absent from the file is deleted, so a bootstrap file can be applied
+//! on top of a database that is also edited through the UI.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    project::{CreateProject, Project},
+    project_repo::CreateProjectRepo,
+    task_label::{CreateTaskLabel, TaskLabel, UpdateTaskLabel},
+};
+use executors::profile::{ExecutorConfigs, ProfileError};
+use gray_matter::engine::Engine;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use super::{
+    project::{ProjectService, ProjectServiceError},
+    repo::RepoService,
+};
+
+pub(crate) fn default_label_color() -> String {
+    "#6b7280".to_string()
+}
+
+#[derive(Debug, Error)]
+pub enum BootstrapError {
+    #[error("Failed to read bootstrap file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid bootstrap file: {0}")]
+    Parse(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Project(#[from] ProjectServiceError),
+    #[error(transparent)]
+    Profile(#[from] ProfileError),
+}
+
+/// Root of a bootstrap file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BootstrapSpec {
+    #[serde(default)]
+    pub projects: Vec<ProjectSpec>,
+    /// Executor profile overrides, same format as profiles.json
+    #[serde(default)]
+    pub executor_profiles: Option<ExecutorConfigs>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectSpec {
+    pub name: String,
+    #[serde(default)]
+    pub repositories: Vec<RepositorySpec>,
+    #[serde(default)]
+    pub labels: Vec<LabelSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositorySpec {
+    pub path: String,
+    /// Defaults to the directory name of `path`
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabelSpec {
+    pub name: String,
+    #[serde(default = "default_label_color")]
+    pub color: String,
+}
+
+/// Summary of the changes applied while reconciling a bootstrap file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BootstrapReport {
+    pub projects_created: Vec<String>,
+    pub repositories_added: Vec<String>,
+    pub labels_created: Vec<String>,
+    pub labels_updated: Vec<String>,
+    pub profiles_updated: bool,
+}
+
+impl RepositorySpec {
+    fn display_name(&self) -> String {
+        self.display_name.clone().unwrap_or_else(|| {
+            Path::new(&self.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.path.clone())
+        })
+    }
+}
+
+impl BootstrapSpec {
+    /// Parse a bootstrap file, choosing YAML or JSON from the file extension
+    pub fn from_file(path: &Path) -> Result<Self, BootstrapError> {
+        let content = std::fs::read_to_string(path).map_err(|source| BootstrapError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+
+        if is_yaml {
+            Self::from_yaml(&content)
+        } else {
+            Self::from_json(&content)
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, BootstrapError> {
+        serde_json::from_str(content).map_err(|e| BootstrapError::Parse(e.to_string()))
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self, BootstrapError> {
+        gray_matter::engine::YAML::parse(content)
+            .deserialize()
+            .map_err(|e| BootstrapError::Parse(e.to_string()))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct BootstrapService;
+
+impl BootstrapService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reconcile the database with the given spec. Creation is idempotent:
+    /// running the same spec twice produces an empty report the second time.
+    pub async fn reconcile(
+        &self,
+        pool: &SqlitePool,
+        project_service: &ProjectService,
+        repo_service: &RepoService,
+        spec: &BootstrapSpec,
+    ) -> Result<BootstrapReport, BootstrapError> {
+        let mut report = BootstrapReport::default();
+
+        for project_spec in &spec.projects {
+            self.reconcile_project(
+                pool,
+                project_service,
+                repo_service,
+                project_spec,
+                &mut report,
+            )
+            .await?;
+        }
+
+        if let Some(profiles) = &spec.executor_profiles
+            && *profiles != ExecutorConfigs::get_cached()
+        {
+            profiles.save_overrides()?;
+            ExecutorConfigs::reload();
+            report.profiles_updated = true;
+        }
+
+        Ok(report)
+    }
+
+    async fn reconcile_project(
+        &self,
+        pool: &SqlitePool,
+        project_service: &ProjectService,
+        repo_service: &RepoService,
+        spec: &ProjectSpec,
+        report: &mut BootstrapReport,
+    ) -> Result<(), BootstrapError> {
+        let existing = Project::find_all(pool)
+            .await?
+            .into_iter()
+            .find(|project| project.name == spec.name);
+
+        let project = match existing {
+            Some(project) => {
+                let current_paths: Vec<PathBuf> = project_service
+                    .get_repositories(pool, project.id)
+                    .await?
+                    .into_iter()
+                    .map(|repo| repo.path)
+                    .collect();
+
+                for repo_spec in &spec.repositories {
+                    let path = repo_service
+                        .normalize_path(&repo_spec.path)
+                        .map_err(ProjectServiceError::Io)?;
+                    if current_paths.contains(&path) {
+                        continue;
+                    }
+                    project_service
+                        .add_repository(
+                            pool,
+                            repo_service,
+                            project.id,
+                            &CreateProjectRepo {
+                                display_name: repo_spec.display_name(),
+                                git_repo_path: repo_spec.path.clone(),
+                            },
+                        )
+                        .await?;
+                    report
+                        .repositories_added
+                        .push(format!("{}: {}", spec.name, repo_spec.path));
+                }
+                project
+            }
+            None => {
+                let project = project_service
+                    .create_project(
+                        pool,
+                        repo_service,
+                        CreateProject {
+                            name: spec.name.clone(),
+                            repositories: spec
+                                .repositories
+                                .iter()
+                                .map(|repo_spec| CreateProjectRepo {
+                                    display_name: repo_spec.display_name(),
+                                    git_repo_path: repo_spec.path.clone(),
+                                })
+                                .collect(),
+                        },
+                    )
+                    .await?;
+                report.projects_created.push(spec.name.clone());
+                project
+            }
+        };
+
+        let labels = TaskLabel::find_by_project_id(pool, project.id).await?;
+        for label_spec in &spec.labels {
+            match labels.iter().find(|label| label.name == label_spec.name) {
+                Some(label) if label.color != label_spec.color => {
+                    TaskLabel::update(
+                        pool,
+                        label.id,
+                        &UpdateTaskLabel {
+                            name: None,
+                            color: Some(label_spec.color.clone()),
+                        },
+                    )
+                    .await?;
+                    report
+                        .labels_updated
+                        .push(format!("{}: {}", spec.name, label_spec.name));
+                }
+                Some(_) => {}
+                None => {
+                    TaskLabel::create(
+                        pool,
+                        &CreateTaskLabel {
+                            project_id: project.id,
+                            name: label_spec.name.clone(),
+                            color: label_spec.color.clone(),
+                        },
+                    )
+                    .await?;
+                    report
+                        .labels_created
+                        .push(format!("{}: {}", spec.name, label_spec.name));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_spec_with_defaults() {
+        let spec = BootstrapSpec::from_json(
+            r##"{
+                "projects": [{
+                    "name": "api",
+                    "repositories": [{ "path": "/srv/repos/api" }],
+                    "labels": [{ "name": "bug", "color": "#ff0000" }, { "name": "chore" }]
+                }]
+            }"##,
+        )
+        .unwrap();
+
+        assert!(spec.executor_profiles.is_none());
+        let project = &spec.projects[0];
+        assert_eq!(project.repositories[0].display_name(), "api");
+        assert_eq!(project.labels[0].color, "#ff0000");
+        assert_eq!(project.labels[1].color, default_label_color());
+    }
+
+    #[test]
+    fn rejects_unknown_shape() {
+        assert!(matches!(
+            BootstrapSpec::from_json(r#"{ "projects": "nope" }"#),
+            Err(BootstrapError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,217 @@
+//! Time limits and cancellation for the external commands behind git and
+//! GitHub operations.
+//!
+//! `git` and `gh` run on blocking threads, and a push waiting on a stalled
+//! network or a credential prompt would otherwise hold its request handler
+//! forever. Commands run here are killed once they exceed their timeout or
+//! their cancellation token fires, and the caller gets a typed error instead.
+
+use std::{
+    io::{self, Read, Write},
+    process::{Child, Command, ExitStatus, Output, Stdio},
+    sync::RwLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use super::config::Config;
+
+/// How often a running command is checked for its deadline and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub const DEFAULT_LOCAL_TIMEOUT_SECS: u32 = 600;
+pub const DEFAULT_NETWORK_TIMEOUT_SECS: u32 = 300;
+
+/// Limits for commands that only touch the local repository and for those
+/// that talk to a remote (fetch, push, ls-remote, `gh`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationTimeouts {
+    pub local: Duration,
+    pub network: Duration,
+}
+
+impl Default for OperationTimeouts {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static TIMEOUTS: RwLock<OperationTimeouts> = RwLock::new(OperationTimeouts::DEFAULT);
+
+impl OperationTimeouts {
+    const DEFAULT: Self = Self {
+        local: Duration::from_secs(DEFAULT_LOCAL_TIMEOUT_SECS as u64),
+        network: Duration::from_secs(DEFAULT_NETWORK_TIMEOUT_SECS as u64),
+    };
+
+    /// Limits from the config, in seconds. Zero keeps the default.
+    pub fn from_secs(local: u32, network: u32) -> Self {
+        let defaults = Self::DEFAULT;
+        Self {
+            local: if local == 0 {
+                defaults.local
+            } else {
+                Duration::from_secs(u64::from(local))
+            },
+            network: if network == 0 {
+                defaults.network
+            } else {
+                Duration::from_secs(u64::from(network))
+            },
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::from_secs(
+            config.git_local_timeout_secs,
+            config.git_network_timeout_secs,
+        )
+    }
+
+    /// Use `timeouts` for every command started from now on
+    pub fn set_current(timeouts: Self) {
+        *TIMEOUTS.write().unwrap_or_else(|e| e.into_inner()) = timeouts;
+    }
+
+    pub fn current() -> Self {
+        *TIMEOUTS.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BoundedCommandError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("timed out after {}s", .0.as_secs())]
+    Timeout(Duration),
+    #[error("cancelled")]
+    Cancelled,
+}
+
+/// Run `cmd` to completion with its output captured, writing `stdin` to it
+/// first. The process is killed when `timeout` passes or `cancel` fires.
+pub fn run(
+    cmd: &mut Command,
+    stdin: Option<&[u8]>,
+    timeout: Duration,
+    cancel: Option<&CancellationToken>,
+) -> Result<Output, BoundedCommandError> {
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()?;
+    // Drain both pipes on their own threads so a chatty command cannot block
+    // on a full pipe while we wait for it to exit
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+
+    if let Some(input) = stdin
+        && let Some(mut child_stdin) = child.stdin.take()
+        && let Err(e) = child_stdin.write_all(input)
+    {
+        kill(&mut child);
+        return Err(e.into());
+    }
+
+    let status = wait(&mut child, timeout, cancel)?;
+    Ok(Output {
+        status,
+        stdout: stdout.map(join_reader).unwrap_or_default(),
+        stderr: stderr.map(join_reader).unwrap_or_default(),
+    })
+}
+
+fn wait(
+    child: &mut Child,
+    timeout: Duration,
+    cancel: Option<&CancellationToken>,
+) -> Result<ExitStatus, BoundedCommandError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            kill(child);
+            return Err(BoundedCommandError::Cancelled);
+        }
+        if Instant::now() >= deadline {
+            kill(child);
+            return Err(BoundedCommandError::Timeout(timeout));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Kill and reap the child. Its output readers are left to finish on their
+/// own, since helpers it spawned (ssh, credential managers) may still hold
+/// the pipes open.
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn read_to_end(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+fn join_reader(reader: thread::JoinHandle<Vec<u8>>) -> Vec<u8> {
+    reader.join().unwrap_or_default()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_output_of_quick_commands() {
+        let output = run(
+            Command::new("sh").args(["-c", "cat; echo err >&2"]),
+            Some(b"hello"),
+            Duration::from_secs(5),
+            None,
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn kills_commands_past_their_timeout() {
+        let started = Instant::now();
+        let result = run(
+            Command::new("sleep").arg("5"),
+            None,
+            Duration::from_millis(200),
+            None,
+        );
+        assert!(matches!(result, Err(BoundedCommandError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn kills_cancelled_commands() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = run(
+            Command::new("sleep").arg("5"),
+            None,
+            Duration::from_secs(5),
+            Some(&cancel),
+        );
+        assert!(matches!(result, Err(BoundedCommandError::Cancelled)));
+    }
+}
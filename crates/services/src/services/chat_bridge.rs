@@ -0,0 +1,302 @@
+//! Task creation from chat: Slack slash commands and Telegram bot webhooks.
+//!
+//! Each linked channel maps to one project. The message text uses the
+//! quick-add syntax (see [`super::quick_add`]), so `/vk Fix login #bug
+//! @claude-code` creates a labelled task for Claude Code. `status <task id>`
+//! replies with the task's status and latest pull request.
+//!
+//! Webhooks are answered right away. Starting an attempt runs afterwards and
+//! its outcome is posted as a follow-up: to the slash command's
+//! `response_url` on Slack, through the Bot API on Telegram.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+/// Oldest Slack request accepted, against replays
+const MAX_SLACK_REQUEST_AGE_SECS: i64 = 5 * 60;
+
+const FOLLOW_UP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const HELP: &str = "Send a task as `Fix login bug #bug !high @claude-code ^friday`, \
+                        or `status <task id>` to check on one.";
+
+/// Check Slack's `X-Slack-Signature` (`v0=<hex>`), an HMAC-SHA256 of
+/// `v0:<timestamp>:<body>` under the app's signing secret
+pub fn verify_slack_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now.timestamp() - sent_at).abs() > MAX_SLACK_REQUEST_AGE_SECS {
+        return false;
+    }
+    let Some(expected) = signature
+        .strip_prefix("v0=")
+        .and_then(|hex_signature| hex::decode(hex_signature).ok())
+    else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("v0:{timestamp}:").as_bytes());
+    mac.update(body);
+    mac.finalize().into_bytes()[..].ct_eq(&expected).into()
+}
+
+/// Check Telegram's `X-Telegram-Bot-Api-Secret-Token`, the secret given to
+/// `setWebhook`
+pub fn verify_telegram_secret(expected: &str, provided: &str) -> bool {
+    expected.as_bytes().ct_eq(provided.as_bytes()).into()
+}
+
+/// The fields of a Slack slash command we use
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlackCommand {
+    pub channel_id: String,
+    pub text: String,
+    /// Where to post replies after the initial response
+    pub response_url: Option<String>,
+}
+
+impl SlackCommand {
+    /// Parse the form-encoded body Slack posts
+    pub fn from_form(body: &[u8]) -> Option<Self> {
+        let mut channel_id = None;
+        let mut text = String::new();
+        let mut response_url = None;
+        for (key, value) in url::form_urlencoded::parse(body) {
+            match key.as_ref() {
+                "channel_id" => channel_id = Some(value.into_owned()),
+                "text" => text = value.into_owned(),
+                "response_url" => response_url = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        Some(Self {
+            channel_id: channel_id?,
+            text,
+            response_url,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUpdate {
+    pub message: Option<TelegramMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramMessage {
+    pub chat: TelegramChat,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramChat {
+    pub id: i64,
+}
+
+impl TelegramUpdate {
+    /// Chat id and argument text of a `/task` command (`/task@bot` in
+    /// groups); other updates are ignored
+    pub fn task_command(&self) -> Option<(i64, String)> {
+        let message = self.message.as_ref()?;
+        let text = message.text.as_deref()?.trim_start();
+        let (command, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let command = command.split('@').next()?;
+        (command == "/task").then(|| (message.chat.id, rest.trim().to_string()))
+    }
+}
+
+/// What a chat message asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatRequest {
+    Help,
+    Status(Uuid),
+    /// Quick-add text of a new task
+    Create(String),
+}
+
+impl ChatRequest {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("help") {
+            return Self::Help;
+        }
+        if let Some((word, id)) = text.split_once(char::is_whitespace)
+            && word.eq_ignore_ascii_case("status")
+            && let Ok(task_id) = Uuid::parse_str(id.trim())
+        {
+            return Self::Status(task_id);
+        }
+        Self::Create(text.to_string())
+    }
+}
+
+/// Synchronous reply to a Slack slash command, visible to the channel
+#[derive(Debug, Serialize)]
+pub struct SlackReply {
+    pub response_type: &'static str,
+    pub text: String,
+}
+
+impl SlackReply {
+    pub fn new(text: String) -> Self {
+        Self {
+            response_type: "in_channel",
+            text,
+        }
+    }
+}
+
+/// Reply to a Telegram webhook, sent by returning a Bot API call as the
+/// response body
+#[derive(Debug, Serialize)]
+pub struct TelegramReply {
+    pub method: &'static str,
+    pub chat_id: i64,
+    pub text: String,
+    pub disable_web_page_preview: bool,
+}
+
+impl TelegramReply {
+    pub fn new(chat_id: i64, text: String) -> Self {
+        Self {
+            method: "sendMessage",
+            chat_id,
+            text,
+            disable_web_page_preview: true,
+        }
+    }
+}
+
+/// Where to send a message after the webhook has been answered
+#[derive(Debug, Clone)]
+pub enum ChatFollowUp {
+    Slack { response_url: String },
+    Telegram { bot_token: String, chat_id: i64 },
+}
+
+impl ChatFollowUp {
+    pub async fn send(&self, text: String) -> Result<(), reqwest::Error> {
+        let request = match self {
+            Self::Slack { response_url } => reqwest::Client::new()
+                .post(response_url)
+                .json(&SlackReply::new(text)),
+            Self::Telegram { bot_token, chat_id } => reqwest::Client::new()
+                .post(format!(
+                    "https://api.telegram.org/bot{bot_token}/sendMessage"
+                ))
+                .json(&TelegramReply::new(*chat_id, text)),
+        };
+        request
+            .timeout(FOLLOW_UP_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{timestamp}:").as_bytes());
+        mac.update(body);
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn slack_signature_must_match_and_be_recent() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let body = b"channel_id=C1&text=Fix+login";
+        let signature = sign("secret", "1700000000", body);
+
+        assert!(verify_slack_signature(
+            "secret",
+            "1700000000",
+            &signature,
+            body,
+            now
+        ));
+        assert!(!verify_slack_signature(
+            "other",
+            "1700000000",
+            &signature,
+            body,
+            now
+        ));
+        assert!(!verify_slack_signature(
+            "secret",
+            "1700000000",
+            &signature,
+            b"channel_id=C1&text=Drop+db",
+            now
+        ));
+
+        let stale = sign("secret", "1699999000", body);
+        assert!(!verify_slack_signature(
+            "secret",
+            "1699999000",
+            &stale,
+            body,
+            now
+        ));
+    }
+
+    #[test]
+    fn parses_slack_form_and_telegram_commands() {
+        assert_eq!(
+            SlackCommand::from_form(
+                b"token=x&channel_id=C1&text=Fix+login+%23bug\
+                  &response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2F1"
+            ),
+            Some(SlackCommand {
+                channel_id: "C1".to_string(),
+                text: "Fix login #bug".to_string(),
+                response_url: Some("https://hooks.slack.com/commands/1".to_string()),
+            })
+        );
+
+        let update: TelegramUpdate = serde_json::from_str(
+            r#"{"update_id":1,"message":{"chat":{"id":-42},"text":"/task@vk_bot Fix login"}}"#,
+        )
+        .unwrap();
+        assert_eq!(update.task_command(), Some((-42, "Fix login".to_string())));
+
+        let chatter: TelegramUpdate =
+            serde_json::from_str(r#"{"message":{"chat":{"id":1},"text":"hello"}}"#).unwrap();
+        assert_eq!(chatter.task_command(), None);
+    }
+
+    #[test]
+    fn parses_chat_requests() {
+        let id = Uuid::new_v4();
+        assert_eq!(ChatRequest::parse("  "), ChatRequest::Help);
+        assert_eq!(
+            ChatRequest::parse(&format!("status {id}")),
+            ChatRequest::Status(id)
+        );
+        assert_eq!(
+            ChatRequest::parse("status page is down"),
+            ChatRequest::Create("status page is down".to_string())
+        );
+    }
+}
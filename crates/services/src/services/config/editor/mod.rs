@@ -1,10 +1,11 @@
-use std::{path::Path, str::FromStr};
+use std::{ffi::OsString, path::Path, str::FromStr};
 
 use executors::{command::CommandBuilder, executors::ExecutorError};
 use serde::{Deserialize, Serialize};
 use strum_macros::{EnumIter, EnumString};
 use thiserror::Error;
 use ts_rs::TS;
+use url::{Url, form_urlencoded};
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Error)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -133,14 +134,78 @@ impl EditorConfig {
     }
 
     pub async fn open_file(&self, path: &Path) -> Result<Option<String>, EditorOpenError> {
-        if let Some(url) = self.remote_url(path) {
+        self.open_file_at(path, None).await
+    }
+
+    /// Open `path` at `line` (1-based) when given. Returns the URL to open
+    /// instead when a remote SSH host is configured.
+    pub async fn open_file_at(
+        &self,
+        path: &Path,
+        line: Option<u32>,
+    ) -> Result<Option<String>, EditorOpenError> {
+        // files must contain a line and column number
+        let line_col = match line {
+            Some(line) => format!(":{line}:1"),
+            None if path.is_file() => ":1:1".to_string(),
+            None => String::new(),
+        };
+        if let Some(url) = self.remote_url(path, &line_col) {
             return Ok(Some(url));
         }
-        self.spawn_local(path).await?;
+        self.spawn_local_at(path, line).await?;
         Ok(None)
     }
 
-    fn remote_url(&self, path: &Path) -> Option<String> {
+    /// URL that opens `file` (relative to `project_root`) in this editor at
+    /// `line` and `column`, both 1-based. Uses the remote SSH form when a
+    /// remote host is configured. `None` for editors without a URL scheme.
+    pub fn deep_link(
+        &self,
+        project_root: &Path,
+        file: &str,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> Option<String> {
+        let path = project_root.join(file);
+        let line_col = match (line, column) {
+            (Some(line), Some(column)) => format!(":{line}:{column}"),
+            (Some(line), None) => format!(":{line}:1"),
+            (None, _) if path.is_file() => ":1:1".to_string(),
+            (None, _) => String::new(),
+        };
+        if self.remote_ssh_host.is_some() {
+            return self.remote_url(&path, &line_col);
+        }
+
+        let scheme = match self.editor_type {
+            EditorType::VsCode => "vscode",
+            EditorType::VsCodeInsiders => "vscode-insiders",
+            EditorType::Cursor => "cursor",
+            EditorType::Windsurf => "windsurf",
+            EditorType::GoogleAntigravity => "antigravity",
+            EditorType::Zed => "zed",
+            EditorType::IntelliJ => {
+                // Toolbox navigation URLs take the project by name and a
+                // 0-based line
+                let project = project_root.file_name()?.to_string_lossy();
+                let target = match line {
+                    Some(line) => format!("{file}:{}", line.saturating_sub(1)),
+                    None => file.to_string(),
+                };
+                let query = form_urlencoded::Serializer::new(String::new())
+                    .append_pair("project", &project)
+                    .append_pair("path", &target)
+                    .finish();
+                return Some(format!("jetbrains://idea/navigate/reference?{query}"));
+            }
+            EditorType::Xcode | EditorType::Custom => return None,
+        };
+        let encoded = Url::from_file_path(&path).ok()?;
+        Some(format!("{scheme}://file{}{line_col}", encoded.path()))
+    }
+
+    fn remote_url(&self, path: &Path, line_col: &str) -> Option<String> {
         let remote_host = self.remote_ssh_host.as_ref()?;
         let user_part = self
             .remote_ssh_user
@@ -161,18 +226,20 @@ impl EditorConfig {
             _ => return None,
         };
 
-        // files must contain a line and column number
-        let line_col = if path.is_file() { ":1:1" } else { "" };
         Some(format!(
             "{scheme}://vscode-remote/ssh-remote+{user_part}{remote_host}{path_str}{line_col}"
         ))
     }
 
     pub async fn spawn_local(&self, path: &Path) -> Result<(), EditorOpenError> {
+        self.spawn_local_at(path, None).await
+    }
+
+    async fn spawn_local_at(&self, path: &Path, line: Option<u32>) -> Result<(), EditorOpenError> {
         let (executable, args) = self.resolve_command().await?;
 
         let mut cmd = std::process::Command::new(&executable);
-        cmd.args(&args).arg(path);
+        cmd.args(&args).args(self.path_args(path, line));
         cmd.spawn().map_err(|e| EditorOpenError::LaunchFailed {
             executable: executable.to_string_lossy().into_owned(),
             details: e.to_string(),
@@ -181,6 +248,30 @@ impl EditorConfig {
         Ok(())
     }
 
+    /// Arguments that open `path`, at `line` when the editor has a syntax for it
+    fn path_args(&self, path: &Path, line: Option<u32>) -> Vec<OsString> {
+        let at_line = |line: u32| OsString::from(format!("{}:{line}", path.to_string_lossy()));
+        match (line, &self.editor_type) {
+            (
+                Some(line),
+                EditorType::VsCode
+                | EditorType::VsCodeInsiders
+                | EditorType::Cursor
+                | EditorType::Windsurf
+                | EditorType::GoogleAntigravity,
+            ) => vec!["--goto".into(), at_line(line)],
+            (Some(line), EditorType::Zed) => vec![at_line(line)],
+            (Some(line), EditorType::IntelliJ) => {
+                vec!["--line".into(), line.to_string().into(), path.into()]
+            }
+            (Some(line), EditorType::Xcode) => {
+                vec!["-l".into(), line.to_string().into(), path.into()]
+            }
+            // Custom commands get the bare path, their line syntax is unknown
+            (None, _) | (Some(_), EditorType::Custom) => vec![path.into()],
+        }
+    }
+
     pub fn with_override(&self, editor_type_str: Option<&str>) -> Self {
         if let Some(editor_type_str) = editor_type_str {
             let editor_type =
@@ -196,3 +287,93 @@ impl EditorConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_deep_links_per_editor() {
+        let root = Path::new("/work/vk-1234/my app");
+        let link = |editor_type| {
+            EditorConfig::new(editor_type, None, None, None).deep_link(
+                root,
+                "src/main.rs",
+                Some(42),
+                Some(7),
+            )
+        };
+
+        assert_eq!(
+            link(EditorType::VsCode).as_deref(),
+            Some("vscode://file/work/vk-1234/my%20app/src/main.rs:42:7")
+        );
+        assert_eq!(
+            link(EditorType::Zed).as_deref(),
+            Some("zed://file/work/vk-1234/my%20app/src/main.rs:42:7")
+        );
+        assert_eq!(
+            link(EditorType::IntelliJ).as_deref(),
+            Some("jetbrains://idea/navigate/reference?project=my+app&path=src%2Fmain.rs%3A41")
+        );
+        assert_eq!(link(EditorType::Xcode), None);
+
+        let remote = EditorConfig::new(
+            EditorType::VsCode,
+            None,
+            Some("devbox".to_string()),
+            Some("ann".to_string()),
+        );
+        assert_eq!(
+            remote
+                .deep_link(root, "src/main.rs", Some(3), None)
+                .as_deref(),
+            Some(
+                "vscode://vscode-remote/ssh-remote+ann@devbox/work/vk-1234/my app/src/main.rs:3:1"
+            )
+        );
+    }
+
+    #[test]
+    fn opens_files_at_a_line_with_each_editors_syntax() {
+        let path = Path::new("/work/app/src/lib.rs");
+        let args = |editor_type, line| {
+            EditorConfig::new(editor_type, None, None, None)
+                .path_args(path, line)
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            args(EditorType::Cursor, Some(12)),
+            vec!["--goto", "/work/app/src/lib.rs:12"]
+        );
+        assert_eq!(
+            args(EditorType::Zed, Some(12)),
+            vec!["/work/app/src/lib.rs:12"]
+        );
+        assert_eq!(
+            args(EditorType::IntelliJ, Some(12)),
+            vec!["--line", "12", "/work/app/src/lib.rs"]
+        );
+        assert_eq!(
+            args(EditorType::Custom, Some(12)),
+            vec!["/work/app/src/lib.rs"]
+        );
+        assert_eq!(args(EditorType::VsCode, None), vec!["/work/app/src/lib.rs"]);
+    }
+
+    #[tokio::test]
+    async fn remote_editors_get_a_url_for_the_line() {
+        let remote = EditorConfig::new(EditorType::VsCode, None, Some("devbox".to_string()), None);
+        let url = remote
+            .open_file_at(Path::new("/work/app/src/lib.rs"), Some(12))
+            .await
+            .unwrap();
+        assert_eq!(
+            url.as_deref(),
+            Some("vscode://vscode-remote/ssh-remote+devbox/work/app/src/lib.rs:12:1")
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use super::secrets;
+
 pub mod editor;
 mod versions;
 
@@ -29,6 +31,9 @@ pub type ShowcaseState = versions::v14::ShowcaseState;
 pub type GitCommitTitleMode = versions::v14::GitCommitTitleMode;
 pub type GitAutoPushMode = versions::v14::GitAutoPushMode;
 pub type SendMessageShortcut = versions::v14::SendMessageShortcut;
+pub type EmbeddingProvider = versions::v14::EmbeddingProvider;
+pub type TargetSyncMode = versions::v14::TargetSyncMode;
+pub type TranscriptionProvider = versions::v14::TranscriptionProvider;
 pub use versions::v14::DEFAULT_COMMIT_TITLE_PROMPT;
 
 /// Will always return config, trying old schemas or eventually returning default
@@ -42,12 +47,13 @@ pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
     }
 }
 
-/// Saves the config to the given path
+/// Saves the config to the given path. Tokens are left out; they are kept
+/// in the secret store.
 pub async fn save_config_to_file(
     config: &Config,
     config_path: &PathBuf,
 ) -> Result<(), ConfigError> {
-    let raw_config = serde_json::to_string_pretty(config)?;
+    let raw_config = serde_json::to_string_pretty(&secrets::without_secrets(config))?;
     std::fs::write(config_path, raw_config)?;
     Ok(())
 }
@@ -79,6 +79,34 @@ fn default_send_message_shortcut() -> SendMessageShortcut {
     SendMessageShortcut::default()
 }
 
+fn default_sensitive_file_patterns() -> Vec<String> {
+    crate::services::sensitive_files::default_patterns()
+}
+
+fn default_target_sync_threshold() -> u32 {
+    20
+}
+
+fn default_task_trailer() -> Option<String> {
+    Some("VK-Task".to_string())
+}
+
+fn default_live_worktree_watch() -> bool {
+    true
+}
+
+fn default_git_local_timeout_secs() -> u32 {
+    crate::services::bounded_command::DEFAULT_LOCAL_TIMEOUT_SECS
+}
+
+fn default_git_network_timeout_secs() -> u32 {
+    crate::services::bounded_command::DEFAULT_NETWORK_TIMEOUT_SECS
+}
+
+fn default_artifact_retention_days() -> u32 {
+    30
+}
+
 /// modo de auto-push después de commits
 #[derive(Clone, Debug, Serialize, Deserialize, TS, Default, PartialEq)]
 pub enum GitAutoPushMode {
@@ -91,6 +119,42 @@ pub enum GitAutoPushMode {
     IfPrExists,
 }
 
+/// Where file embeddings for context suggestions come from
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default, PartialEq)]
+pub enum EmbeddingProvider {
+    /// No index is built
+    #[default]
+    Disabled,
+    /// Hashed identifier features computed locally; nothing leaves the machine
+    Local,
+    /// An OpenAI-compatible `/embeddings` endpoint
+    Api,
+}
+
+/// How voice notes are turned into text
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default, PartialEq)]
+pub enum TranscriptionProvider {
+    /// Voice notes are not accepted
+    #[default]
+    Disabled,
+    /// A local command such as whisper.cpp, run on the audio file
+    Local,
+    /// An OpenAI-compatible `/audio/transcriptions` endpoint
+    Api,
+}
+
+/// What the periodic target branch sync does with attempts that fell behind
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default, PartialEq)]
+pub enum TargetSyncMode {
+    /// Target branches are not fetched in the background
+    #[default]
+    Disabled,
+    /// Mark attempts that fell behind as needing a rebase
+    Notify,
+    /// Rebase attempts that are clean and idle, mark the others
+    AutoRebase,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -154,6 +218,79 @@ pub struct Config {
     /// shortcut for sending messages in chat
     #[serde(default = "default_send_message_shortcut")]
     pub send_message_shortcut: SendMessageShortcut,
+    /// opt-in anonymous usage statistics
+    #[serde(default)]
+    pub analytics_enabled: bool,
+    /// PostHog-compatible endpoint for usage statistics (cloud or self-hosted)
+    #[serde(default)]
+    pub analytics_endpoint: Option<String>,
+    #[serde(default)]
+    pub analytics_api_key: Option<String>,
+    /// `.gitignore`-style patterns whose file contents are withheld from diff-derived text
+    #[serde(default = "default_sensitive_file_patterns")]
+    pub sensitive_file_patterns: Vec<String>,
+    /// embedding index over repo files used to suggest task context
+    #[serde(default)]
+    pub embedding_provider: EmbeddingProvider,
+    /// OpenAI-compatible embeddings endpoint for the `Api` provider
+    #[serde(default)]
+    pub embedding_endpoint: Option<String>,
+    /// model name sent to the embeddings endpoint
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    #[serde(default)]
+    pub embedding_api_key: Option<String>,
+    /// add similar completed tasks to the initial prompt of attempts
+    #[serde(default)]
+    pub similar_tasks_in_prompt: bool,
+    /// periodic fetch of the target branches of active attempts
+    #[serde(default)]
+    pub target_sync_mode: TargetSyncMode,
+    /// commits an attempt may fall behind its target branch before it is synced
+    #[serde(default = "default_target_sync_threshold")]
+    pub target_sync_threshold: u32,
+    /// git trailer naming the task in auto-commits, merge commits and PR bodies
+    #[serde(default = "default_task_trailer")]
+    pub task_trailer: Option<String>,
+    /// watch active worktrees for hand edits and refresh their state live
+    #[serde(default = "default_live_worktree_watch")]
+    pub live_worktree_watch: bool,
+    /// stash hand-edited files before an agent run and restore them after it
+    #[serde(default)]
+    pub auto_stash_hand_edits: bool,
+    /// transcription of voice notes into tasks
+    #[serde(default)]
+    pub transcription_provider: TranscriptionProvider,
+    /// command for the `Local` provider; `{input}` is the audio file and the
+    /// transcript is read from stdout
+    #[serde(default)]
+    pub transcription_command: Option<String>,
+    /// OpenAI-compatible transcriptions endpoint for the `Api` provider
+    #[serde(default)]
+    pub transcription_endpoint: Option<String>,
+    /// model name sent to the transcriptions endpoint
+    #[serde(default)]
+    pub transcription_model: Option<String>,
+    #[serde(default)]
+    pub transcription_api_key: Option<String>,
+    /// seconds a local git command may run before it is killed
+    #[serde(default = "default_git_local_timeout_secs")]
+    pub git_local_timeout_secs: u32,
+    /// seconds a fetch, push or `gh` call may run before it is killed
+    #[serde(default = "default_git_network_timeout_secs")]
+    pub git_network_timeout_secs: u32,
+    /// days attempt artifacts are kept; 0 keeps them until the attempt is deleted
+    #[serde(default = "default_artifact_retention_days")]
+    pub artifact_retention_days: u32,
+    /// Bitbucket Cloud account the app password belongs to
+    #[serde(default)]
+    pub bitbucket_username: Option<String>,
+    /// app password with pull request read/write scope, used with the username
+    #[serde(default)]
+    pub bitbucket_app_password: Option<String>,
+    /// workspace or repository access token, used instead of an app password
+    #[serde(default)]
+    pub bitbucket_access_token: Option<String>,
 }
 
 impl Config {
@@ -188,6 +325,31 @@ impl Config {
             open_pr_in_browser: default_open_pr_in_browser(),
             commit_reminder: default_commit_reminder(),
             send_message_shortcut: default_send_message_shortcut(),
+            analytics_enabled: false,
+            analytics_endpoint: None,
+            analytics_api_key: None,
+            sensitive_file_patterns: default_sensitive_file_patterns(),
+            embedding_provider: EmbeddingProvider::default(),
+            embedding_endpoint: None,
+            embedding_model: None,
+            embedding_api_key: None,
+            similar_tasks_in_prompt: false,
+            target_sync_mode: TargetSyncMode::default(),
+            target_sync_threshold: default_target_sync_threshold(),
+            task_trailer: default_task_trailer(),
+            live_worktree_watch: default_live_worktree_watch(),
+            auto_stash_hand_edits: false,
+            transcription_provider: TranscriptionProvider::default(),
+            transcription_command: None,
+            transcription_endpoint: None,
+            transcription_model: None,
+            transcription_api_key: None,
+            git_local_timeout_secs: default_git_local_timeout_secs(),
+            git_network_timeout_secs: default_git_network_timeout_secs(),
+            artifact_retention_days: default_artifact_retention_days(),
+            bitbucket_username: None,
+            bitbucket_app_password: None,
+            bitbucket_access_token: None,
         }
     }
 
@@ -249,6 +411,31 @@ impl Default for Config {
             open_pr_in_browser: true,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            analytics_enabled: false,
+            analytics_endpoint: None,
+            analytics_api_key: None,
+            sensitive_file_patterns: default_sensitive_file_patterns(),
+            embedding_provider: EmbeddingProvider::default(),
+            embedding_endpoint: None,
+            embedding_model: None,
+            embedding_api_key: None,
+            similar_tasks_in_prompt: false,
+            target_sync_mode: TargetSyncMode::default(),
+            target_sync_threshold: default_target_sync_threshold(),
+            task_trailer: default_task_trailer(),
+            live_worktree_watch: default_live_worktree_watch(),
+            auto_stash_hand_edits: false,
+            transcription_provider: TranscriptionProvider::default(),
+            transcription_command: None,
+            transcription_endpoint: None,
+            transcription_model: None,
+            transcription_api_key: None,
+            git_local_timeout_secs: default_git_local_timeout_secs(),
+            git_network_timeout_secs: default_git_network_timeout_secs(),
+            artifact_retention_days: default_artifact_retention_days(),
+            bitbucket_username: None,
+            bitbucket_app_password: None,
+            bitbucket_access_token: None,
         }
     }
 }
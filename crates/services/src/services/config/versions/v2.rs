@@ -137,6 +137,14 @@ pub struct GitHubConfig {
     pub username: Option<String>,
     pub primary_email: Option<String>,
     pub default_pr_base: Option<String>,
+    /// GitHub App installation used instead of the `gh` CLI when set
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub app_installation_id: Option<i64>,
+    /// GitHub Enterprise Server hostnames, such as `github.acme.corp`, whose
+    /// remotes are handled like github.com ones
+    #[serde(default)]
+    pub enterprise_hosts: Vec<String>,
 }
 
 impl From<v1::GitHubConfig> for GitHubConfig {
@@ -147,6 +155,8 @@ impl From<v1::GitHubConfig> for GitHubConfig {
             username: old.username,
             primary_email: old.primary_email,
             default_pr_base: old.default_pr_base,
+            app_installation_id: None,
+            enterprise_hosts: Vec::new(),
         }
     }
 }
@@ -186,6 +196,8 @@ impl Default for GitHubConfig {
             username: None,
             primary_email: None,
             default_pr_base: Some("main".to_string()),
+            app_installation_id: None,
+            enterprise_hosts: Vec::new(),
         }
     }
 }
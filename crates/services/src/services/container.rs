@@ -9,7 +9,9 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        agent_prompt::{AgentPrompt, AgentPromptKind},
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        execution_log_event::ExecutionLogEvent,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -18,6 +20,7 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_process_token_usage::ExecutionProcessTokenUsage,
         project::{Project, UpdateProject},
         project_repo::ProjectRepo,
         repo::Repo,
@@ -34,7 +37,10 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{ConversationPatch, patch::extract_normalized_entry_from_patch},
+    },
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{StreamExt, future};
@@ -49,8 +55,13 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    agent_instructions, context_pack,
+    embedding_index::Embedder,
+    environment_snapshot, execution_events,
     git::{GitService, GitServiceError},
+    i18n::{Text, tr_with},
     notification::NotificationService,
+    preview_capture, task_knowledge,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
@@ -193,16 +204,17 @@ pub trait ContainerService {
             return;
         }
 
-        let title = format!("Task Complete: {}", ctx.task.title);
+        let title = tr_with(Text::TaskCompleteTitle, &[("task", &ctx.task.title)]);
+        let branch = format!("{:?}", ctx.workspace.branch);
+        let executor = format!("{:?}", ctx.session.executor);
+        let args = [
+            ("task", ctx.task.title.as_str()),
+            ("branch", branch.as_str()),
+            ("executor", executor.as_str()),
+        ];
         let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
-            ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
-            ),
+            ExecutionProcessStatus::Completed => tr_with(Text::TaskCompletedBody, &args),
+            ExecutionProcessStatus::Failed => tr_with(Text::TaskFailedBody, &args),
             _ => {
                 tracing::warn!(
                     "Tried to notify workspace completion for {} but process is still running!",
@@ -583,8 +595,37 @@ pub trait ContainerService {
         map.get(uuid).cloned()
     }
 
+    /// URL of the workspace's running dev server, detected from its output
+    async fn dev_server_url(&self, workspace_id: Uuid) -> Option<String> {
+        let dev_servers =
+            ExecutionProcess::find_running_dev_servers_by_workspace(&self.db().pool, workspace_id)
+                .await
+                .ok()?;
+        for process in dev_servers {
+            let Some(store) = self.get_msg_store_by_id(&process.id).await else {
+                continue;
+            };
+            let output: String = store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect();
+            if let Some(url) = preview_capture::detect_dev_server_url(&output) {
+                return Some(url);
+            }
+        }
+        None
+    }
+
     async fn git_branch_prefix(&self) -> String;
 
+    /// Embedder used to add similar past tasks to initial prompts; `None`
+    /// leaves them out
+    async fn similar_tasks_embedder(&self) -> Option<Embedder>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -849,7 +890,42 @@ pub trait ContainerService {
                         LogMsg::Finished => {
                             break;
                         }
-                        LogMsg::JsonPatch(_) | LogMsg::Ready => continue,
+                        LogMsg::JsonPatch(patch) => {
+                            let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+                            else {
+                                continue;
+                            };
+
+                            for event in execution_events::events_from_entry(index, &entry) {
+                                if let Err(e) =
+                                    ExecutionLogEvent::upsert(&db.pool, execution_id, &event).await
+                                {
+                                    tracing::error!(
+                                        "Failed to store log event for execution {}: {}",
+                                        execution_id,
+                                        e
+                                    );
+                                }
+                            }
+
+                            // Keep token usage so it can be aggregated without the live logs
+                            if let NormalizedEntryType::TokenUsageInfo(usage) = entry.entry_type
+                                && let Err(e) = ExecutionProcessTokenUsage::record(
+                                    &db.pool,
+                                    execution_id,
+                                    usage.total_tokens as i64,
+                                    Some(usage.model_context_window as i64),
+                                )
+                                .await
+                            {
+                                tracing::error!(
+                                    "Failed to record token usage for execution {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
+                        LogMsg::Ready => continue,
                     }
                 }
             }
@@ -898,7 +974,37 @@ pub trait ContainerService {
         )
         .await?;
 
-        let prompt = task.to_prompt();
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let prompt = match &workspace.container_ref {
+            Some(container_ref) => {
+                let workspace_repos =
+                    WorkspaceRepo::find_repos_for_workspace(&self.db().pool, workspace.id).await?;
+                context_pack::prepare_prompt(
+                    &self.db().pool,
+                    &task,
+                    Path::new(container_ref),
+                    &workspace_repos,
+                    working_dir.as_deref(),
+                    task.to_prompt(),
+                )
+                .await?
+            }
+            None => task.to_prompt(),
+        };
+        let prompt = match self.similar_tasks_embedder().await {
+            Some(embedder) => {
+                task_knowledge::append_to_prompt(&self.db().pool, &embedder, &task, prompt).await
+            }
+            None => prompt,
+        };
+        let model = Project::find_executor_defaults(&self.db().pool, project.id)
+            .await?
+            .model_for(&executor_profile_id);
 
         let repos_with_setup: Vec<_> = project_repos
             .iter()
@@ -909,17 +1015,12 @@ pub trait ContainerService {
 
         let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
 
-        let working_dir = workspace
-            .agent_working_dir
-            .as_ref()
-            .filter(|dir| !dir.is_empty())
-            .cloned();
-
         let coding_action = ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                model,
             }),
             cleanup_action.map(Box::new),
         );
@@ -996,6 +1097,16 @@ pub trait ContainerService {
             .map(std::path::PathBuf::from)
             .ok_or_else(|| ContainerError::Other(anyhow!("Container ref not found")))?;
 
+        if run_reason == &ExecutionProcessRunReason::CodingAgent {
+            agent_instructions::sync_workspace(
+                &self.db().pool,
+                task.project_id,
+                &workspace_root,
+                &repositories,
+            )
+            .await?;
+        }
+
         let mut repo_states = Vec::with_capacity(repositories.len());
         for repo in &repositories {
             let repo_path = workspace_root.join(&repo.name);
@@ -1047,6 +1158,20 @@ pub trait ContainerService {
             .await?;
         }
 
+        if let Some((kind, prompt)) = AgentPromptKind::classify(executor_action, run_reason) {
+            AgentPrompt::create(&self.db().pool, execution_process.id, kind, prompt).await?;
+        }
+
+        if run_reason == &ExecutionProcessRunReason::CodingAgent {
+            environment_snapshot::spawn(
+                self.db().pool.clone(),
+                workspace.id,
+                execution_process.id,
+                workspace_root,
+                repositories,
+            );
+        }
+
         if let Err(start_error) = self
             .start_execution_inner(workspace, &execution_process, executor_action)
             .await
@@ -0,0 +1,299 @@
+//! Context packs: files, directory listings, pull request links and docs
+//! attached to a task and handed to every attempt, either appended to the
+//! initial prompt or written to `.vibe-context/CONTEXT.md` in the agent's
+//! working directory.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    project_repo::ProjectRepo,
+    repo::Repo,
+    task::Task,
+    task_context_pack::{ContextDelivery, ContextItem, TaskContextPack},
+};
+use ignore::WalkBuilder;
+use sqlx::SqlitePool;
+use utils::path::VIBE_CONTEXT_DIR;
+
+const CONTEXT_FILE_NAME: &str = "CONTEXT.md";
+const MAX_FILE_CHARS: usize = 20_000;
+const MAX_PACK_CHARS: usize = 100_000;
+const MAX_LISTING_DEPTH: usize = 3;
+const MAX_LISTING_ENTRIES: usize = 200;
+
+/// A repo checkout that item paths are resolved against
+#[derive(Debug, Clone)]
+pub struct ContextRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl ContextRoot {
+    /// The repos of a workspace, as checked out under `workspace_dir`
+    pub fn for_workspace(workspace_dir: &Path, repos: &[Repo]) -> Vec<Self> {
+        repos
+            .iter()
+            .map(|repo| ContextRoot {
+                name: repo.name.clone(),
+                path: workspace_dir.join(&repo.name),
+            })
+            .collect()
+    }
+
+    /// The registered checkouts of `repos`, for previews outside a workspace
+    pub fn for_repos(repos: &[Repo]) -> Vec<Self> {
+        repos
+            .iter()
+            .map(|repo| ContextRoot {
+                name: repo.name.clone(),
+                path: repo.path.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Find `path` under the roots. A leading repo name selects that repo;
+/// otherwise the first repo that has the path wins.
+fn resolve(roots: &[ContextRoot], path: &str) -> Option<PathBuf> {
+    let path = path.trim().trim_start_matches("./");
+    for root in roots {
+        if let Some(rest) = path.strip_prefix(&format!("{}/", root.name)) {
+            let candidate = root.path.join(rest);
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+    roots
+        .iter()
+        .map(|root| root.path.join(path))
+        .find(|candidate| candidate.exists())
+}
+
+fn truncate(text: &str, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => (text[..end].to_string(), true),
+        None => (text.to_string(), false),
+    }
+}
+
+fn render_file(roots: &[ContextRoot], path: &str) -> String {
+    let Some(resolved) = resolve(roots, path) else {
+        return format!("### `{path}`\n\n_Not found in the workspace._\n");
+    };
+    match std::fs::read_to_string(&resolved) {
+        Ok(content) => {
+            let (content, truncated) = truncate(&content, MAX_FILE_CHARS);
+            let mut out = format!("### `{path}`\n\n````\n{content}");
+            if !content.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str("````\n");
+            if truncated {
+                out.push_str(&format!("\n_Truncated to {MAX_FILE_CHARS} characters._\n"));
+            }
+            out
+        }
+        Err(e) => format!("### `{path}`\n\n_Could not be read: {e}_\n"),
+    }
+}
+
+fn render_directory(roots: &[ContextRoot], path: &str) -> String {
+    let Some(resolved) = resolve(roots, path).filter(|p| p.is_dir()) else {
+        return format!("### `{path}/`\n\n_Not found in the workspace._\n");
+    };
+    let mut entries: Vec<String> = WalkBuilder::new(&resolved)
+        .max_depth(Some(MAX_LISTING_DEPTH))
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(&resolved).ok()?;
+            if relative.as_os_str().is_empty() {
+                return None;
+            }
+            let mut name = relative.to_string_lossy().replace('\\', "/");
+            if entry.file_type().is_some_and(|t| t.is_dir()) {
+                name.push('/');
+            }
+            Some(name)
+        })
+        .collect();
+    entries.sort();
+    let total = entries.len();
+    entries.truncate(MAX_LISTING_ENTRIES);
+
+    let mut out = format!("### `{path}/`\n\n");
+    for entry in &entries {
+        out.push_str(&format!("- {entry}\n"));
+    }
+    if total > entries.len() {
+        out.push_str(&format!(
+            "\n_{} more entries not listed._\n",
+            total - entries.len()
+        ));
+    }
+    out
+}
+
+/// Render the pack as Markdown, resolving paths against `roots`
+pub fn render(items: &[ContextItem], roots: &[ContextRoot]) -> String {
+    let mut sections = Vec::new();
+    let mut links = Vec::new();
+    for item in items {
+        match item {
+            ContextItem::File { path } => sections.push(render_file(roots, path)),
+            ContextItem::Directory { path } => {
+                sections.push(render_directory(roots, path.trim_end_matches('/')))
+            }
+            ContextItem::PullRequest { url, title } => links.push(match title {
+                Some(title) => format!("- [{title}]({url})\n"),
+                None => format!("- {url}\n"),
+            }),
+            ContextItem::Doc {
+                title,
+                url,
+                content,
+            } => {
+                let mut out = format!("### {title}\n\n");
+                if let Some(url) = url {
+                    out.push_str(&format!("{url}\n\n"));
+                }
+                if let Some(content) = content.as_deref().filter(|c| !c.trim().is_empty()) {
+                    out.push_str(content.trim_end());
+                    out.push('\n');
+                }
+                sections.push(out);
+            }
+        }
+    }
+    if !links.is_empty() {
+        sections.insert(
+            0,
+            format!("### Related pull requests\n\n{}", links.concat()),
+        );
+    }
+
+    let body = sections.join("\n");
+    let (body, truncated) = truncate(&body, MAX_PACK_CHARS);
+    let mut out = format!("## Task context\n\n{body}");
+    if truncated {
+        out.push_str(&format!(
+            "\n\n_Context truncated to {MAX_PACK_CHARS} characters._\n"
+        ));
+    }
+    out
+}
+
+async fn render_blocking(items: Vec<ContextItem>, roots: Vec<ContextRoot>) -> String {
+    tokio::task::spawn_blocking(move || render(&items, &roots))
+        .await
+        .unwrap_or_default()
+}
+
+/// The task's pack as an attempt would see it, rendered from the project's
+/// registered repos rather than a worktree; `None` when the task has no pack
+pub async fn preview(pool: &SqlitePool, task: &Task) -> Result<Option<String>, sqlx::Error> {
+    let Some(pack) = TaskContextPack::find_by_task_id(pool, task.id).await? else {
+        return Ok(None);
+    };
+    let repos = ProjectRepo::find_repos_for_project(pool, task.project_id).await?;
+    Ok(Some(
+        render_blocking(pack.items.0, ContextRoot::for_repos(&repos)).await,
+    ))
+}
+
+/// Add the task's context pack to an attempt's initial prompt, writing the
+/// context file first when the pack is delivered as a file. The prompt is
+/// returned unchanged when the task has no pack.
+pub async fn prepare_prompt(
+    pool: &SqlitePool,
+    task: &Task,
+    workspace_dir: &Path,
+    repos: &[Repo],
+    working_dir: Option<&str>,
+    prompt: String,
+) -> Result<String, sqlx::Error> {
+    let Some(pack) = TaskContextPack::find_by_task_id(pool, task.id).await? else {
+        return Ok(prompt);
+    };
+    if pack.items.is_empty() {
+        return Ok(prompt);
+    }
+
+    let roots = ContextRoot::for_workspace(workspace_dir, repos);
+    let rendered = render_blocking(pack.items.0, roots).await;
+
+    match pack.delivery {
+        ContextDelivery::Prompt => Ok(format!("{prompt}\n\n{rendered}")),
+        ContextDelivery::File => {
+            let context_dir = workspace_dir
+                .join(working_dir.unwrap_or_default())
+                .join(VIBE_CONTEXT_DIR);
+            match write_context_file(&context_dir, &rendered).await {
+                Ok(()) => Ok(format!(
+                    "{prompt}\n\nContext for this task is in `{VIBE_CONTEXT_DIR}/{CONTEXT_FILE_NAME}`; read it before starting."
+                )),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to write context file for task {}, adding it to the prompt: {}",
+                        task.id,
+                        e
+                    );
+                    Ok(format!("{prompt}\n\n{rendered}"))
+                }
+            }
+        }
+    }
+}
+
+async fn write_context_file(context_dir: &Path, rendered: &str) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(context_dir).await?;
+    // Keep the context out of the attempt's commits
+    tokio::fs::write(context_dir.join(".gitignore"), "*\n").await?;
+    tokio::fs::write(context_dir.join(CONTEXT_FILE_NAME), rendered).await
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn renders_files_listings_and_links() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("api");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "pub fn answer() -> u32 { 42 }").unwrap();
+        let roots = vec![ContextRoot {
+            name: "api".to_string(),
+            path: repo,
+        }];
+
+        let rendered = render(
+            &[
+                ContextItem::File {
+                    path: "api/src/lib.rs".to_string(),
+                },
+                ContextItem::Directory {
+                    path: "src".to_string(),
+                },
+                ContextItem::File {
+                    path: "missing.rs".to_string(),
+                },
+                ContextItem::PullRequest {
+                    url: "https://github.com/o/r/pull/7".to_string(),
+                    title: Some("Earlier fix".to_string()),
+                },
+            ],
+            &roots,
+        );
+
+        assert!(rendered.contains("pub fn answer() -> u32 { 42 }\n````"));
+        assert!(rendered.contains("### `src/`\n\n- lib.rs\n"));
+        assert!(rendered.contains("### `missing.rs`\n\n_Not found in the workspace._"));
+        assert!(rendered.contains("- [Earlier fix](https://github.com/o/r/pull/7)"));
+    }
+}
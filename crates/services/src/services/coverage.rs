@@ -0,0 +1,343 @@
+//! Optional coverage hook run after an attempt finishes.
+//!
+//! Repos with coverage settings have their command run in the attempt's
+//! worktree; the lcov or cobertura report it writes is compared with the
+//! coverage of the merge base on the target branch. Baselines are measured
+//! in a temporary worktree and cached per commit, so each target branch
+//! commit is only measured once.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use db::models::{
+    coverage::{CoverageBaseline, CoverageReport, CreateCoverageReport, RepoCoverageSettings},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+
+use super::git::GitCli;
+
+/// Coverage runs are usually full test runs; give them plenty of time
+const COVERAGE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Error)]
+pub enum CoverageError {
+    #[error("Coverage command failed: {0}")]
+    Command(String),
+    #[error("Coverage command timed out")]
+    Timeout,
+    #[error("Coverage report not found at {0}")]
+    ReportMissing(PathBuf),
+    #[error("Unrecognized coverage report format")]
+    UnknownFormat,
+    #[error("Git CLI error: {0}")]
+    Git(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoverageSummary {
+    pub lines_covered: i64,
+    pub lines_total: i64,
+}
+
+impl CoverageSummary {
+    pub fn percent(&self) -> f64 {
+        if self.lines_total == 0 {
+            return 100.0;
+        }
+        self.lines_covered as f64 * 100.0 / self.lines_total as f64
+    }
+}
+
+/// Line totals from an lcov or cobertura report
+pub fn parse_report(content: &str) -> Option<CoverageSummary> {
+    if content.contains("<coverage") {
+        parse_cobertura(content)
+    } else {
+        parse_lcov(content)
+    }
+}
+
+fn parse_lcov(content: &str) -> Option<CoverageSummary> {
+    let mut summary = CoverageSummary {
+        lines_covered: 0,
+        lines_total: 0,
+    };
+    let mut found = false;
+    for line in content.lines() {
+        if let Some(total) = line.strip_prefix("LF:") {
+            summary.lines_total += total.trim().parse::<i64>().ok()?;
+            found = true;
+        } else if let Some(hit) = line.strip_prefix("LH:") {
+            summary.lines_covered += hit.trim().parse::<i64>().ok()?;
+        }
+    }
+    found.then_some(summary)
+}
+
+fn parse_cobertura(content: &str) -> Option<CoverageSummary> {
+    let start = content.find("<coverage")?;
+    let tag = &content[start..start + content[start..].find('>')?];
+    if let (Some(covered), Some(total)) = (
+        xml_attribute(tag, "lines-covered"),
+        xml_attribute(tag, "lines-valid"),
+    ) {
+        return Some(CoverageSummary {
+            lines_covered: covered.parse().ok()?,
+            lines_total: total.parse().ok()?,
+        });
+    }
+
+    // Older reports only carry per-line hits
+    let mut summary = CoverageSummary {
+        lines_covered: 0,
+        lines_total: 0,
+    };
+    for line_tag in content.split("<line ").skip(1) {
+        let hits = xml_attribute(line_tag, "hits")?.parse::<i64>().ok()?;
+        summary.lines_total += 1;
+        if hits > 0 {
+            summary.lines_covered += 1;
+        }
+    }
+    (summary.lines_total > 0).then_some(summary)
+}
+
+pub(crate) fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {name}=\"");
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+/// Run the coverage command in `dir` and read the report it produces
+pub async fn measure(
+    settings: &RepoCoverageSettings,
+    dir: &Path,
+) -> Result<CoverageSummary, CoverageError> {
+    let report_path = dir.join(&settings.report_path);
+    // A stale report from an earlier run must not be mistaken for this one
+    let _ = tokio::fs::remove_file(&report_path).await;
+
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = Command::new(shell_cmd);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .arg(shell_arg)
+        .arg(&settings.command)
+        .current_dir(dir);
+
+    let output = tokio::time::timeout(COVERAGE_TIMEOUT, command.output())
+        .await
+        .map_err(|_| CoverageError::Timeout)??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+        return Err(CoverageError::Command(
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+        ));
+    }
+
+    let content = tokio::fs::read_to_string(&report_path)
+        .await
+        .map_err(|_| CoverageError::ReportMissing(report_path.clone()))?;
+    parse_report(&content).ok_or(CoverageError::UnknownFormat)
+}
+
+async fn git(dir: &Path, args: Vec<String>) -> Result<String, CoverageError> {
+    let dir = dir.to_path_buf();
+    tokio::task::spawn_blocking(move || GitCli::new().git(&dir, args))
+        .await
+        .map_err(|e| CoverageError::Git(e.to_string()))?
+        .map(|out| out.trim().to_string())
+        .map_err(|e| CoverageError::Git(e.to_string()))
+}
+
+/// Coverage of `commit_sha`, measured in a temporary worktree unless cached
+async fn baseline(
+    pool: &SqlitePool,
+    settings: &RepoCoverageSettings,
+    repo_path: &Path,
+    commit_sha: &str,
+) -> Result<CoverageBaseline, CoverageError> {
+    if let Some(cached) = CoverageBaseline::find(pool, settings.repo_id, commit_sha).await? {
+        return Ok(cached);
+    }
+
+    let worktree = std::env::temp_dir().join(format!(
+        "vk-coverage-{}-{}",
+        settings.repo_id.simple(),
+        &commit_sha[..commit_sha.len().min(12)]
+    ));
+    let worktree_arg = worktree.to_string_lossy().to_string();
+    git(
+        repo_path,
+        vec![
+            "worktree".into(),
+            "add".into(),
+            "--detach".into(),
+            worktree_arg.clone(),
+            commit_sha.to_string(),
+        ],
+    )
+    .await?;
+
+    let measured = measure(settings, &worktree).await;
+
+    if let Err(e) = git(
+        repo_path,
+        vec![
+            "worktree".into(),
+            "remove".into(),
+            "--force".into(),
+            worktree_arg,
+        ],
+    )
+    .await
+    {
+        tracing::warn!("Failed to remove coverage worktree {:?}: {}", worktree, e);
+    }
+
+    let summary = measured?;
+    let baseline = CoverageBaseline {
+        repo_id: settings.repo_id,
+        commit_sha: commit_sha.to_string(),
+        lines_covered: summary.lines_covered,
+        lines_total: summary.lines_total,
+    };
+    CoverageBaseline::upsert(pool, &baseline).await?;
+    Ok(baseline)
+}
+
+/// Measure coverage for every repo of the workspace that has a coverage hook
+pub async fn measure_workspace(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Vec<CoverageReport>, CoverageError> {
+    let Some(container_ref) = &workspace.container_ref else {
+        return Ok(Vec::new());
+    };
+
+    let mut reports = Vec::new();
+    for repo in
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?
+    {
+        let Some(settings) = RepoCoverageSettings::find_by_repo_id(pool, repo.repo.id).await?
+        else {
+            continue;
+        };
+        let worktree = PathBuf::from(container_ref).join(&repo.repo.name);
+
+        let head = git(&worktree, vec!["rev-parse".into(), "HEAD".into()]).await?;
+        let summary = measure(&settings, &worktree).await?;
+
+        // Without a baseline the attempt's coverage is still worth recording
+        let merge_base = git(
+            &worktree,
+            vec![
+                "merge-base".into(),
+                "HEAD".into(),
+                repo.target_branch.clone(),
+            ],
+        )
+        .await;
+        let baseline = match merge_base {
+            Ok(sha) => baseline(pool, &settings, &repo.repo.path, &sha)
+                .await
+                .inspect_err(|e| {
+                    tracing::warn!(
+                        "Failed to measure baseline coverage for repo {}: {}",
+                        repo.repo.name,
+                        e
+                    )
+                })
+                .ok(),
+            Err(e) => {
+                tracing::warn!("No merge base for repo {}: {}", repo.repo.name, e);
+                None
+            }
+        };
+
+        let delta = baseline.as_ref().map(|b| {
+            summary.percent()
+                - CoverageSummary {
+                    lines_covered: b.lines_covered,
+                    lines_total: b.lines_total,
+                }
+                .percent()
+        });
+        let auto_pr_blocked =
+            settings.block_auto_pr && delta.is_some_and(|delta| delta < -settings.max_drop);
+
+        reports.push(
+            CoverageReport::create(
+                pool,
+                &CreateCoverageReport {
+                    workspace_id: workspace.id,
+                    repo_id: repo.repo.id,
+                    commit_sha: head,
+                    lines_covered: summary.lines_covered,
+                    lines_total: summary.lines_total,
+                    baseline,
+                    delta,
+                    auto_pr_blocked,
+                },
+            )
+            .await?,
+        );
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lcov_totals() {
+        let lcov =
+            "SF:src/a.rs\nLF:10\nLH:7\nend_of_record\nSF:src/b.rs\nLF:10\nLH:3\nend_of_record\n";
+        assert_eq!(
+            parse_report(lcov),
+            Some(CoverageSummary {
+                lines_covered: 10,
+                lines_total: 20
+            })
+        );
+    }
+
+    #[test]
+    fn parses_cobertura_totals() {
+        let with_totals = r#"<?xml version="1.0"?><coverage line-rate="0.5" lines-covered="5" lines-valid="10"></coverage>"#;
+        assert_eq!(
+            parse_report(with_totals),
+            Some(CoverageSummary {
+                lines_covered: 5,
+                lines_total: 10
+            })
+        );
+
+        let per_line = r#"<coverage line-rate="0.5"><lines><line number="1" hits="2"/><line number="2" hits="0"/></lines></coverage>"#;
+        assert_eq!(
+            parse_report(per_line),
+            Some(CoverageSummary {
+                lines_covered: 1,
+                lines_total: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,640 @@
+//! Dependency changes made by an attempt.
+//!
+//! Manifest and lockfile diffs are parsed on both sides and compared, so
+//! reviewers see which dependencies an agent added, removed or moved to
+//! another version. Manifests name the direct dependencies; lockfiles in the
+//! same directory supply the resolved versions and any transitive changes.
+//! A project allowlist, when set, flags direct dependencies that were added
+//! without being approved.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+/// Heading of the Markdown section, also used when attaching it to a PR body
+pub const DEPENDENCY_HEADING: &str = "## Dependency changes";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+impl Ecosystem {
+    fn label(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "Cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Python => "Python",
+            Ecosystem::Go => "Go",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum DependencyChangeKind {
+    Added,
+    Removed,
+    Upgraded,
+    Downgraded,
+    /// Versions changed in a way that is not a simple upgrade or downgrade
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct DependencyChange {
+    pub repo_id: Option<Uuid>,
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub kind: DependencyChangeKind,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+    /// Manifest or lockfile the change was read from
+    pub file: String,
+    /// Declared in a manifest rather than only pulled in through a lockfile
+    pub direct: bool,
+    /// Added direct dependency that is not on the project allowlist
+    pub unapproved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DependencyChanges {
+    pub changes: Vec<DependencyChange>,
+    /// Dependency files whose content was too large to compare
+    pub skipped_files: Vec<String>,
+    /// Markdown summary, empty when nothing changed
+    pub markdown: String,
+}
+
+impl DependencyChanges {
+    pub fn unapproved(&self) -> impl Iterator<Item = &DependencyChange> {
+        self.changes.iter().filter(|change| change.unapproved)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Manifest,
+    Lockfile,
+}
+
+type Versions = BTreeMap<String, BTreeSet<String>>;
+
+fn classify(path: &str) -> Option<(Ecosystem, FileKind)> {
+    let file_name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    match file_name.as_str() {
+        "cargo.toml" => Some((Ecosystem::Cargo, FileKind::Manifest)),
+        "cargo.lock" => Some((Ecosystem::Cargo, FileKind::Lockfile)),
+        "package.json" => Some((Ecosystem::Npm, FileKind::Manifest)),
+        "package-lock.json" => Some((Ecosystem::Npm, FileKind::Lockfile)),
+        "go.mod" => Some((Ecosystem::Go, FileKind::Manifest)),
+        name if name.starts_with("requirements") && name.ends_with(".txt") => {
+            Some((Ecosystem::Python, FileKind::Manifest))
+        }
+        _ => None,
+    }
+}
+
+fn parse(ecosystem: Ecosystem, kind: FileKind, content: &str) -> Versions {
+    match (ecosystem, kind) {
+        (Ecosystem::Cargo, FileKind::Manifest) => parse_cargo_toml(content),
+        (Ecosystem::Cargo, FileKind::Lockfile) => parse_cargo_lock(content),
+        (Ecosystem::Npm, FileKind::Manifest) => parse_package_json(content),
+        (Ecosystem::Npm, FileKind::Lockfile) => parse_package_lock(content),
+        (Ecosystem::Python, _) => parse_requirements(content),
+        (Ecosystem::Go, _) => parse_go_mod(content),
+    }
+}
+
+fn insert(versions: &mut Versions, name: &str, version: &str) {
+    versions
+        .entry(name.to_string())
+        .or_default()
+        .insert(version.to_string());
+}
+
+fn quoted_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let start = text.find(&format!("{key} = \""))? + key.len() + 4;
+    let end = text[start..].find('"')?;
+    Some(&text[start..start + end])
+}
+
+/// Version requirement of a Cargo.toml dependency, or how it is sourced
+fn cargo_requirement(spec: &str) -> String {
+    let spec = spec.trim();
+    if let Some(version) = spec.strip_prefix('"').and_then(|s| s.split('"').next()) {
+        return version.to_string();
+    }
+    if let Some(version) = quoted_value(spec, "version") {
+        return version.to_string();
+    }
+    ["workspace", "path", "git"]
+        .into_iter()
+        .find(|source| spec.contains(&format!("{source} =")))
+        .unwrap_or("*")
+        .to_string()
+}
+
+fn parse_cargo_toml(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    let mut in_dependencies = false;
+    // `[dependencies.foo]` tables name a single dependency
+    let mut table_dependency: Option<(String, String)> = None;
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some((name, spec)) = table_dependency.take() {
+                insert(&mut versions, &name, &cargo_requirement(&spec));
+            }
+            in_dependencies = section.ends_with("dependencies");
+            if let Some((table, name)) = section.rsplit_once('.')
+                && table.ends_with("dependencies")
+            {
+                table_dependency = Some((name.to_string(), String::new()));
+            }
+            continue;
+        }
+        if let Some((_, spec)) = table_dependency.as_mut() {
+            spec.push_str(line);
+            spec.push(' ');
+        } else if in_dependencies && let Some((key, spec)) = line.split_once('=') {
+            // Dotted keys like `serde.workspace = true`
+            let (name, spec) = match key.trim().split_once('.') {
+                Some((name, field)) => (name, format!("{field} = {}", spec.trim())),
+                None => (key.trim(), spec.to_string()),
+            };
+            insert(&mut versions, name, &cargo_requirement(&spec));
+        }
+    }
+    if let Some((name, spec)) = table_dependency {
+        insert(&mut versions, &name, &cargo_requirement(&spec));
+    }
+    versions
+}
+
+fn parse_cargo_lock(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    for package in content.split("[[package]]").skip(1) {
+        if let (Some(name), Some(version)) = (
+            quoted_value(package, "name"),
+            quoted_value(package, "version"),
+        ) {
+            insert(&mut versions, name, version);
+        }
+    }
+    versions
+}
+
+fn parse_package_json(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    let Ok(json) = serde_json::from_str::<Value>(content) else {
+        return versions;
+    };
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ] {
+        for (name, version) in json[section].as_object().into_iter().flatten() {
+            insert(&mut versions, name, version.as_str().unwrap_or("*"));
+        }
+    }
+    versions
+}
+
+fn parse_package_lock(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    let Ok(json) = serde_json::from_str::<Value>(content) else {
+        return versions;
+    };
+    if let Some(packages) = json["packages"].as_object() {
+        // lockfileVersion 2 and 3 key packages by their install path
+        for (path, package) in packages {
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if let Some(version) = package["version"].as_str() {
+                insert(&mut versions, name, version);
+            }
+        }
+    } else if let Some(dependencies) = json["dependencies"].as_object() {
+        for (name, package) in dependencies {
+            if let Some(version) = package["version"].as_str() {
+                insert(&mut versions, name, version);
+            }
+        }
+    }
+    versions
+}
+
+fn parse_requirements(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let line = line.split(';').next().unwrap_or_default().trim();
+        let split = line.find(['=', '>', '<', '~', '!']).unwrap_or(line.len());
+        let name = line[..split].split('[').next().unwrap_or_default().trim();
+        let version = line[split..]
+            .trim_start_matches(['=', '>', '<', '~', '!'])
+            .trim();
+        if !name.is_empty() {
+            insert(
+                &mut versions,
+                &name.to_lowercase().replace('_', "-"),
+                if version.is_empty() { "*" } else { version },
+            );
+        }
+    }
+    versions
+}
+
+fn parse_go_mod(content: &str) -> Versions {
+    let mut versions = Versions::new();
+    let mut in_require = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let requirement = if in_require {
+            if line == ")" {
+                in_require = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            if rest.trim() == "(" {
+                in_require = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        if let Some((module, version)) = requirement.split_once(char::is_whitespace) {
+            insert(&mut versions, module, version.trim());
+        }
+    }
+    versions
+}
+
+/// Numeric components of a version, ignoring requirement operators like `^`
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit())
+        .take_while(|part| !part.is_empty())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+fn compare(old: &BTreeSet<String>, new: &BTreeSet<String>) -> Option<DependencyChangeKind> {
+    if old == new {
+        return None;
+    }
+    if old.is_empty() {
+        return Some(DependencyChangeKind::Added);
+    }
+    if new.is_empty() {
+        return Some(DependencyChangeKind::Removed);
+    }
+    if old.len() == 1 && new.len() == 1 {
+        let (old_parts, new_parts) = (
+            version_parts(old.first().unwrap()),
+            version_parts(new.first().unwrap()),
+        );
+        if !old_parts.is_empty() && !new_parts.is_empty() {
+            return Some(match new_parts.cmp(&old_parts) {
+                Ordering::Greater => DependencyChangeKind::Upgraded,
+                Ordering::Less => DependencyChangeKind::Downgraded,
+                Ordering::Equal => DependencyChangeKind::Changed,
+            });
+        }
+    }
+    Some(DependencyChangeKind::Changed)
+}
+
+fn joined(versions: &BTreeSet<String>) -> Option<String> {
+    (!versions.is_empty()).then(|| versions.iter().cloned().collect::<Vec<_>>().join(", "))
+}
+
+/// Whether `name` matches an allowlist entry; a trailing `*` matches a prefix
+pub fn is_allowlisted(allowlist: &[String], name: &str) -> bool {
+    allowlist.iter().any(|entry| match entry.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => entry == name,
+    })
+}
+
+struct ParsedFile {
+    repo_id: Option<Uuid>,
+    path: String,
+    ecosystem: Ecosystem,
+    kind: FileKind,
+    old: Versions,
+    new: Versions,
+}
+
+impl ParsedFile {
+    fn directory(&self) -> &str {
+        self.path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+    }
+
+    fn same_project(&self, other: &ParsedFile) -> bool {
+        self.repo_id == other.repo_id
+            && self.ecosystem == other.ecosystem
+            && self.directory() == other.directory()
+    }
+}
+
+/// Dependency changes in `diffs`. Added direct dependencies are flagged as
+/// unapproved when an allowlist is given and does not cover them.
+pub fn analyze(diffs: &[Diff], allowlist: Option<&[String]>) -> DependencyChanges {
+    let mut files = Vec::new();
+    let mut skipped_files = Vec::new();
+    for diff in diffs {
+        let Some(path) = diff.new_path.as_deref().or(diff.old_path.as_deref()) else {
+            continue;
+        };
+        let Some((ecosystem, kind)) = classify(path) else {
+            continue;
+        };
+        if diff.content_omitted {
+            skipped_files.push(path.to_string());
+            continue;
+        }
+        files.push(ParsedFile {
+            repo_id: diff.repo_id,
+            path: path.to_string(),
+            ecosystem,
+            kind,
+            old: parse(
+                ecosystem,
+                kind,
+                diff.old_content.as_deref().unwrap_or_default(),
+            ),
+            new: parse(
+                ecosystem,
+                kind,
+                diff.new_content.as_deref().unwrap_or_default(),
+            ),
+        });
+    }
+
+    let mut changes = Vec::new();
+    for manifest in files.iter().filter(|f| f.kind == FileKind::Manifest) {
+        let lockfile = files
+            .iter()
+            .find(|f| f.kind == FileKind::Lockfile && f.same_project(manifest));
+        let names: BTreeSet<&String> = manifest.old.keys().chain(manifest.new.keys()).collect();
+        for name in names {
+            let (old, new) = (
+                manifest.old.get(name).cloned().unwrap_or_default(),
+                manifest.new.get(name).cloned().unwrap_or_default(),
+            );
+            let Some(kind) = compare(&old, &new) else {
+                continue;
+            };
+            // Prefer the resolved versions when the lockfile changed with the manifest
+            let resolved = lockfile
+                .map(|lock| {
+                    (
+                        lock.old.get(name).cloned().unwrap_or_default(),
+                        lock.new.get(name).cloned().unwrap_or_default(),
+                    )
+                })
+                .filter(|(old, new)| !old.is_empty() || !new.is_empty());
+            let (old, new, kind) = match resolved {
+                Some((lock_old, lock_new)) => {
+                    let lock_kind = compare(&lock_old, &lock_new).unwrap_or(kind);
+                    (lock_old, lock_new, lock_kind)
+                }
+                None => (old, new, kind),
+            };
+            changes.push(DependencyChange {
+                repo_id: manifest.repo_id,
+                ecosystem: manifest.ecosystem,
+                name: name.clone(),
+                kind,
+                old_version: joined(&old),
+                new_version: joined(&new),
+                file: manifest.path.clone(),
+                direct: true,
+                unapproved: kind == DependencyChangeKind::Added
+                    && allowlist.is_some_and(|list| !is_allowlisted(list, name)),
+            });
+        }
+    }
+
+    for lockfile in files.iter().filter(|f| f.kind == FileKind::Lockfile) {
+        let manifest = files
+            .iter()
+            .find(|f| f.kind == FileKind::Manifest && f.same_project(lockfile));
+        let names: BTreeSet<&String> = lockfile.old.keys().chain(lockfile.new.keys()).collect();
+        for name in names {
+            // Direct dependencies were already reported from the manifest
+            if manifest.is_some_and(|m| m.old.contains_key(name) || m.new.contains_key(name)) {
+                continue;
+            }
+            let (old, new) = (
+                lockfile.old.get(name).cloned().unwrap_or_default(),
+                lockfile.new.get(name).cloned().unwrap_or_default(),
+            );
+            if let Some(kind) = compare(&old, &new) {
+                changes.push(DependencyChange {
+                    repo_id: lockfile.repo_id,
+                    ecosystem: lockfile.ecosystem,
+                    name: name.clone(),
+                    kind,
+                    old_version: joined(&old),
+                    new_version: joined(&new),
+                    file: lockfile.path.clone(),
+                    direct: false,
+                    unapproved: false,
+                });
+            }
+        }
+    }
+
+    let markdown = render_markdown(&changes);
+    DependencyChanges {
+        changes,
+        skipped_files,
+        markdown,
+    }
+}
+
+fn describe(change: &DependencyChange) -> String {
+    let versions = match (&change.old_version, &change.new_version) {
+        (Some(old), Some(new)) => format!("{old} → {new}"),
+        (None, Some(new)) => new.clone(),
+        (Some(old), None) => old.clone(),
+        (None, None) => String::new(),
+    };
+    format!(
+        "`{}` {} ({}, `{}`)",
+        change.name,
+        versions,
+        change.ecosystem.label(),
+        change.file
+    )
+}
+
+fn render_markdown(changes: &[DependencyChange]) -> String {
+    let direct: Vec<&DependencyChange> = changes.iter().filter(|c| c.direct).collect();
+    let transitive = changes.len() - direct.len();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut markdown = format!("{DEPENDENCY_HEADING}\n\n");
+    for (kind, label) in [
+        (DependencyChangeKind::Added, "Added"),
+        (DependencyChangeKind::Upgraded, "Upgraded"),
+        (DependencyChangeKind::Downgraded, "Downgraded"),
+        (DependencyChangeKind::Changed, "Changed"),
+        (DependencyChangeKind::Removed, "Removed"),
+    ] {
+        let group: Vec<&&DependencyChange> = direct.iter().filter(|c| c.kind == kind).collect();
+        if group.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("**{label}**\n"));
+        for change in group {
+            markdown.push_str(&format!("- {}", describe(change)));
+            if change.unapproved {
+                markdown.push_str(" ⚠️ not on the project allowlist");
+            }
+            markdown.push('\n');
+        }
+        markdown.push('\n');
+    }
+    if transitive > 0 {
+        markdown.push_str(&format!(
+            "{transitive} transitive dependencies changed in lockfiles.\n"
+        ));
+    }
+    markdown.trim_end().to_string()
+}
+
+/// Attach the dependency section to a PR body, replacing one added earlier
+pub fn append_to_body(body: Option<&str>, section: &str) -> String {
+    let body = body.map(|b| match b.find(DEPENDENCY_HEADING) {
+        Some(start) => {
+            let before = b[..start].trim_end();
+            let rest = &b[start + DEPENDENCY_HEADING.len()..];
+            match rest.find("\n## ") {
+                Some(next) => format!("{before}\n\n{}", rest[next..].trim_start()),
+                None => before.to_string(),
+            }
+        }
+        None => b.to_string(),
+    });
+    match body.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+        Some(body) => format!("{body}\n\n{section}"),
+        None => section.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn modified(path: &str, old: &str, new: &str) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }
+    }
+
+    #[test]
+    fn resolves_cargo_changes_from_manifest_and_lockfile() {
+        let diffs = [
+            modified(
+                "crates/app/Cargo.toml",
+                "[package]\nname = \"app\"\n\n[dependencies]\nserde = \"1.0\"\nrand = \"0.8\"\n",
+                "[package]\nname = \"app\"\n\n[dependencies]\nserde = { version = \"1.1\", features = [\"derive\"] }\nregex = \"1\"\n\n[dependencies.tokio]\nversion = \"1.40\"\n",
+            ),
+            modified(
+                "crates/app/Cargo.lock",
+                "[[package]]\nname = \"serde\"\nversion = \"1.0.200\"\n\n[[package]]\nname = \"rand\"\nversion = \"0.8.5\"\n",
+                "[[package]]\nname = \"serde\"\nversion = \"1.0.210\"\n\n[[package]]\nname = \"regex\"\nversion = \"1.10.0\"\n\n[[package]]\nname = \"memchr\"\nversion = \"2.7.0\"\n\n[[package]]\nname = \"tokio\"\nversion = \"1.40.0\"\n",
+            ),
+        ];
+        let allowlist = vec!["serde".to_string(), "tok*".to_string()];
+        let result = analyze(&diffs, Some(&allowlist));
+
+        let find = |name: &str| result.changes.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(find("serde").kind, DependencyChangeKind::Upgraded);
+        assert_eq!(find("serde").new_version.as_deref(), Some("1.0.210"));
+        assert_eq!(find("rand").kind, DependencyChangeKind::Removed);
+        assert!(find("regex").unapproved);
+        assert!(!find("tokio").unapproved);
+        assert!(!find("memchr").direct);
+        assert_eq!(result.unapproved().count(), 1);
+        assert!(result.markdown.contains("1 transitive dependencies"));
+    }
+
+    #[test]
+    fn compares_npm_python_and_go_manifests() {
+        let diffs = [
+            modified(
+                "web/package.json",
+                r#"{"dependencies": {"react": "^18.2.0"}}"#,
+                r#"{"dependencies": {"react": "^18.3.1"}, "devDependencies": {"vitest": "^1.0.0"}}"#,
+            ),
+            modified(
+                "requirements.txt",
+                "Django==4.2\n",
+                "django==4.1 # pinned\nrequests>=2\n",
+            ),
+            modified(
+                "go.mod",
+                "module x\n\nrequire (\n\tgithub.com/a/b v1.2.0\n)\n",
+                "module x\n\nrequire (\n\tgithub.com/a/b v1.3.0 // indirect\n)\nrequire github.com/c/d v0.1.0\n",
+            ),
+        ];
+        let result = analyze(&diffs, None);
+        let kind = |name: &str| result.changes.iter().find(|c| c.name == name).unwrap().kind;
+        assert_eq!(kind("react"), DependencyChangeKind::Upgraded);
+        assert_eq!(kind("vitest"), DependencyChangeKind::Added);
+        assert_eq!(kind("django"), DependencyChangeKind::Downgraded);
+        assert_eq!(kind("requests"), DependencyChangeKind::Added);
+        assert_eq!(kind("github.com/a/b"), DependencyChangeKind::Upgraded);
+        assert_eq!(kind("github.com/c/d"), DependencyChangeKind::Added);
+        assert!(result.changes.iter().all(|c| !c.unapproved));
+    }
+
+    #[test]
+    fn replaces_earlier_section_in_body() {
+        let body = "Summary\n\n## Dependency changes\n\nold\n\n## Review checklist\n\n- [ ] x";
+        assert_eq!(
+            append_to_body(Some(body), "## Dependency changes\n\nnew"),
+            "Summary\n\n## Review checklist\n\n- [ ] x\n\n## Dependency changes\n\nnew"
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Budgeted renderings of attempt diffs for title and description prompts.
+//!
+//! Cutting a diff at a fixed length keeps the first files and drops the
+//! rest. A digest instead lists per-file stats for the whole change set and
+//! then fills the remaining budget with hunks picked round-robin across
+//! files, so every file gets represented before any file gets a second hunk.
+//! For change sets too large for one prompt, [`chunks`] splits every hunk
+//! into budget-sized parts that can be summarized separately and combined.
+
+use serde::Serialize;
+use ts_rs::TS;
+use utils::diff::{Diff, DiffChangeKind, compute_line_change_counts, create_unified_diff_hunks};
+
+/// Default prompt budget, in characters
+pub const DEFAULT_BUDGET: usize = 8000;
+/// Smallest budget accepted; anything lower cannot hold a useful digest
+pub const MIN_BUDGET: usize = 500;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileStat {
+    pub path: String,
+    pub change: DiffChangeKind,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DiffDigest {
+    pub files: Vec<FileStat>,
+    /// Stats header followed by the selected hunks, within the budget
+    pub text: String,
+    pub included_hunks: usize,
+    pub total_hunks: usize,
+}
+
+struct FileHunks {
+    stat: FileStat,
+    hunks: Vec<String>,
+}
+
+fn collect(diffs: &[Diff]) -> Vec<FileHunks> {
+    diffs
+        .iter()
+        .map(|diff| {
+            let path = diff
+                .new_path
+                .clone()
+                .or_else(|| diff.old_path.clone())
+                .unwrap_or_default();
+            let old = diff.old_content.as_deref().unwrap_or_default();
+            let new = diff.new_content.as_deref().unwrap_or_default();
+            let (hunks, counted) = if diff.content_omitted {
+                (Vec::new(), (0, 0))
+            } else {
+                (
+                    create_unified_diff_hunks(old, new),
+                    compute_line_change_counts(old, new),
+                )
+            };
+            FileHunks {
+                stat: FileStat {
+                    path,
+                    change: diff.change.clone(),
+                    additions: diff.additions.unwrap_or(counted.0),
+                    deletions: diff.deletions.unwrap_or(counted.1),
+                },
+                hunks,
+            }
+        })
+        .collect()
+}
+
+fn change_marker(change: &DiffChangeKind) -> char {
+    match change {
+        DiffChangeKind::Added => 'A',
+        DiffChangeKind::Deleted => 'D',
+        DiffChangeKind::Renamed => 'R',
+        DiffChangeKind::Copied => 'C',
+        DiffChangeKind::Modified | DiffChangeKind::PermissionChange => 'M',
+    }
+}
+
+fn stats_header(files: &[FileHunks], budget: usize) -> String {
+    let additions: usize = files.iter().map(|f| f.stat.additions).sum();
+    let deletions: usize = files.iter().map(|f| f.stat.deletions).sum();
+    let mut header = format!("{} files changed, +{additions} -{deletions}\n", files.len());
+    for (index, file) in files.iter().enumerate() {
+        let line = format!(
+            "{} {} (+{} -{})\n",
+            change_marker(&file.stat.change),
+            file.stat.path,
+            file.stat.additions,
+            file.stat.deletions
+        );
+        // Keep at least half of the budget for hunks
+        if header.len() + line.len() > budget / 2 {
+            header.push_str(&format!("... and {} more files\n", files.len() - index));
+            break;
+        }
+        header.push_str(&line);
+    }
+    header
+}
+
+fn file_header(path: &str) -> String {
+    format!("\n--- {path}\n")
+}
+
+/// Shorten a hunk to at most `limit` characters on line boundaries
+fn clip_hunk(hunk: &str, limit: usize) -> String {
+    if hunk.len() <= limit {
+        return hunk.to_string();
+    }
+    let lines: Vec<&str> = hunk.split_inclusive('\n').collect();
+    let mut clipped = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        // Leave room for the marker line
+        if clipped.len() + line.len() + 32 > limit {
+            clipped.push_str(&format!("... ({} more lines)\n", lines.len() - index));
+            break;
+        }
+        clipped.push_str(line);
+    }
+    clipped
+}
+
+/// Digest of `diffs` in at most `budget` characters
+pub fn digest(diffs: &[Diff], budget: usize) -> DiffDigest {
+    let budget = budget.max(MIN_BUDGET);
+    let files = collect(diffs);
+    let mut text = stats_header(&files, budget);
+    let total_hunks = files.iter().map(|f| f.hunks.len()).sum();
+
+    // No single hunk may take more than a quarter of the budget
+    let hunk_limit = budget / 4;
+    let mut remaining = budget.saturating_sub(text.len());
+    let mut selected: Vec<Vec<String>> = vec![Vec::new(); files.len()];
+    let mut next = vec![0; files.len()];
+    loop {
+        let mut progressed = false;
+        for (index, file) in files.iter().enumerate() {
+            let Some(hunk) = file.hunks.get(next[index]) else {
+                continue;
+            };
+            next[index] += 1;
+            let hunk = clip_hunk(hunk, hunk_limit);
+            let cost = hunk.len()
+                + if selected[index].is_empty() {
+                    file_header(&file.stat.path).len()
+                } else {
+                    0
+                };
+            // A hunk that does not fit is skipped; smaller ones may still fit
+            if cost <= remaining {
+                remaining -= cost;
+                selected[index].push(hunk);
+            }
+            progressed = true;
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    let mut included_hunks = 0;
+    for (file, hunks) in files.iter().zip(&selected) {
+        if hunks.is_empty() {
+            continue;
+        }
+        text.push_str(&file_header(&file.stat.path));
+        for hunk in hunks {
+            text.push_str(hunk);
+        }
+        included_hunks += hunks.len();
+    }
+    if included_hunks < total_hunks {
+        let note = format!("\n({} of {total_hunks} hunks shown)\n", included_hunks);
+        if text.len() + note.len() <= budget {
+            text.push_str(&note);
+        }
+    }
+
+    DiffDigest {
+        files: files.into_iter().map(|f| f.stat).collect(),
+        text,
+        included_hunks,
+        total_hunks,
+    }
+}
+
+/// Every hunk of `diffs`, packed in file order into parts of at most
+/// `budget` characters, for summarizing each part before combining them
+pub fn chunks(diffs: &[Diff], budget: usize) -> Vec<String> {
+    let budget = budget.max(MIN_BUDGET);
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for file in collect(diffs) {
+        let header = file_header(&file.stat.path);
+        let mut header_written = false;
+        for hunk in &file.hunks {
+            let hunk = clip_hunk(hunk, budget.saturating_sub(header.len()));
+            if current.len() + header.len() + hunk.len() > budget && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                header_written = false;
+            }
+            if !header_written {
+                current.push_str(&header);
+                header_written = true;
+            }
+            current.push_str(&hunk);
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified(path: &str, old: &str, new: &str) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }
+    }
+
+    /// File with `hunks` separate changes, far enough apart to form separate hunks
+    fn file_with_hunks(path: &str, hunks: usize) -> Diff {
+        let old: String = (0..hunks * 20).map(|i| format!("line {i}\n")).collect();
+        let new: String = (0..hunks * 20)
+            .map(|i| {
+                if i % 20 == 10 {
+                    format!("changed {i} {}\n", "x".repeat(80))
+                } else {
+                    format!("line {i}\n")
+                }
+            })
+            .collect();
+        modified(path, &old, &new)
+    }
+
+    #[test]
+    fn represents_every_file_before_repeating_one() {
+        let diffs: Vec<Diff> = (0..6)
+            .map(|i| file_with_hunks(&format!("src/file{i}.rs"), 10))
+            .collect();
+        let result = digest(&diffs, 3000);
+
+        assert!(result.text.len() <= 3000);
+        assert_eq!(result.files.len(), 6);
+        assert_eq!(result.total_hunks, 60);
+        assert!(result.included_hunks < 60);
+        for i in 0..6 {
+            assert!(result.text.contains(&format!("--- src/file{i}.rs")));
+        }
+        assert!(result.text.starts_with("6 files changed, +60 -60\n"));
+    }
+
+    #[test]
+    fn chunks_cover_every_hunk_within_budget() {
+        let diffs: Vec<Diff> = (0..3)
+            .map(|i| file_with_hunks(&format!("src/file{i}.rs"), 8))
+            .collect();
+        let parts = chunks(&diffs, 1000);
+
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.len() <= 1000));
+        let changed_lines: usize = parts
+            .iter()
+            .map(|part| part.matches("+changed").count())
+            .sum();
+        assert_eq!(changed_lines, 24);
+    }
+}
@@ -0,0 +1,187 @@
+//! Per-project ignore patterns for attempt diffs.
+//!
+//! Patterns use `.gitignore` syntax and apply on top of the repo's own
+//! ignore files, so tracked but uninteresting files (generated code,
+//! fixtures, vendored sources) can be kept out of diff views and of the
+//! context handed to agents. A repo's path scope hides everything outside
+//! of it the same way.
+
+use db::models::{project::Project, workspace::Workspace};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use sqlx::SqlitePool;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use super::path_scope;
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffIgnore {
+    patterns: Vec<String>,
+    matcher: Option<Gitignore>,
+    /// Repo-relative directory outside of which everything is ignored
+    scope: Option<String>,
+}
+
+impl DiffIgnore {
+    /// Build a matcher from `patterns`, skipping any that do not parse
+    pub fn new(patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        let mut valid = Vec::new();
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match builder.add_line(None, pattern) {
+                Ok(_) => valid.push(pattern.to_string()),
+                Err(e) => tracing::warn!("Skipping invalid diff ignore pattern {pattern:?}: {e}"),
+            }
+        }
+        if valid.is_empty() {
+            return Self::default();
+        }
+        match builder.build() {
+            Ok(matcher) => Self {
+                patterns: valid,
+                matcher: Some(matcher),
+                scope: None,
+            },
+            Err(e) => {
+                tracing::warn!("Failed to build diff ignore matcher: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    pub async fn for_project(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::new(
+            &Project::find_diff_ignore_patterns(pool, project_id).await?,
+        ))
+    }
+
+    pub async fn for_workspace(
+        pool: &SqlitePool,
+        workspace: &Workspace,
+    ) -> Result<Self, sqlx::Error> {
+        match workspace.parent_task(pool).await? {
+            Some(task) => Self::for_project(pool, task.project_id).await,
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// These patterns, also ignoring every path outside `scope`
+    pub fn with_scope(mut self, scope: Option<&str>) -> Self {
+        self.scope = scope.and_then(path_scope::normalize);
+        self
+    }
+
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_none() && self.scope.is_none()
+    }
+
+    /// Whether a repo-relative path is covered by the patterns or lies
+    /// outside the scope
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            return false;
+        }
+        if let Some(scope) = &self.scope
+            && !path_scope::contains(scope, path)
+        {
+            return true;
+        }
+        self.matcher
+            .as_ref()
+            .is_some_and(|matcher| matcher.matched_path_or_any_parents(path, false).is_ignore())
+    }
+
+    /// Drop diffs whose path is ignored; renames are kept unless both sides are
+    pub fn retain(&self, diffs: &mut Vec<Diff>) {
+        if self.is_empty() {
+            return;
+        }
+        diffs.retain(|diff| {
+            let paths = [diff.old_path.as_deref(), diff.new_path.as_deref()];
+            !paths
+                .into_iter()
+                .flatten()
+                .all(|path| self.is_ignored(path))
+        });
+    }
+}
+
+/// Error message for the first pattern that is not valid `.gitignore` syntax
+pub fn validate_patterns(patterns: &[String]) -> Result<(), String> {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| format!("Invalid pattern {pattern:?}: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn diff(old: Option<&str>, new: Option<&str>) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: old.map(str::to_string),
+            new_path: new.map(str::to_string),
+            old_content: None,
+            new_content: None,
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }
+    }
+
+    #[test]
+    fn matches_gitignore_style_patterns() {
+        let ignore = DiffIgnore::new(&[
+            "vendor/".to_string(),
+            "*.snap".to_string(),
+            "/generated".to_string(),
+            "!keep.snap".to_string(),
+        ]);
+        assert!(ignore.is_ignored("vendor/lib/a.rs"));
+        assert!(ignore.is_ignored("src/__snapshots__/x.snap"));
+        assert!(ignore.is_ignored("generated/types.ts"));
+        assert!(!ignore.is_ignored("src/generated/types.ts"));
+        assert!(!ignore.is_ignored("tests/keep.snap"));
+        assert!(!ignore.is_ignored("src/main.rs"));
+    }
+
+    #[test]
+    fn keeps_renames_out_of_ignored_paths() {
+        let ignore = DiffIgnore::new(&["fixtures/".to_string()]);
+        let mut diffs = vec![
+            diff(Some("fixtures/a.json"), Some("fixtures/a.json")),
+            diff(Some("fixtures/b.json"), Some("tests/b.json")),
+            diff(None, Some("src/lib.rs")),
+        ];
+        ignore.retain(&mut diffs);
+        assert_eq!(diffs.len(), 2);
+        assert!(DiffIgnore::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn scope_hides_paths_outside_it() {
+        let ignore = DiffIgnore::new(&["*.snap".to_string()]).with_scope(Some("packages/web/"));
+        assert!(!ignore.is_ignored("packages/web/src/app.ts"));
+        assert!(ignore.is_ignored("packages/web/src/app.snap"));
+        assert!(ignore.is_ignored("packages/api/src/main.rs"));
+        assert!(ignore.is_ignored("package.json"));
+        assert!(!DiffIgnore::default().with_scope(Some("web")).is_empty());
+    }
+}
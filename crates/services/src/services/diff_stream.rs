@@ -30,6 +30,7 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    diff_ignore::DiffIgnore,
     filesystem_watcher::{self, FilesystemWatcherError},
     git::{Commit, DiffTarget, GitService, GitServiceError},
 };
@@ -175,6 +176,8 @@ pub struct DiffStreamArgs {
     pub base_commit: Commit,
     pub stats_only: bool,
     pub path_prefix: Option<String>,
+    /// Project patterns whose matches are left out of the stream
+    pub ignore: DiffIgnore,
 }
 
 struct DiffStreamManager {
@@ -422,15 +425,17 @@ impl DiffStreamManager {
         let base = self.current_base_commit.clone();
         let stats_only = self.args.stats_only;
         let cumulative = self.cumulative.clone();
+        let ignore = self.args.ignore.clone();
 
         tokio::task::spawn_blocking(move || {
-            let diffs = git.get_diffs(
+            let mut diffs = git.get_diffs(
                 DiffTarget::Worktree {
                     worktree_path: &worktree,
                     base_commit: &base,
                 },
                 None,
             )?;
+            ignore.retain(&mut diffs);
 
             let mut processed_diffs = Vec::with_capacity(diffs.len());
             for mut diff in diffs {
@@ -479,8 +484,9 @@ impl DiffStreamManager {
         events: Vec<DebouncedEvent>,
         canonical_worktree: &Path,
     ) -> Result<(), DiffStreamError> {
-        let changed_paths =
+        let mut changed_paths =
             extract_changed_paths(&events, canonical_worktree, &self.args.worktree_path);
+        changed_paths.retain(|path| !self.args.ignore.is_ignored(path));
 
         if changed_paths.is_empty() {
             return Ok(());
@@ -0,0 +1,294 @@
+//! Optional browser test hook run after an attempt finishes.
+//!
+//! Projects with e2e settings have their command (Playwright, Cypress and
+//! the like) run in the attempt's workspace against its dev server. The JUnit
+//! report it writes is stored per test on the attempt, and failing tests
+//! block auto-PR when the project asks for it, like the coverage gate.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use db::models::{
+    e2e_test::{
+        CreateE2eTestResult, CreateE2eTestRun, E2eTestReport, E2eTestRun, E2eTestStatus,
+        ProjectE2eSettings,
+    },
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use super::coverage::xml_attribute;
+
+/// Browser suites are slow; give them as long as a coverage run
+const E2E_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Failure messages are kept short; the full output is in the report
+const MAX_MESSAGE_LINES: usize = 5;
+
+#[derive(Debug, Error)]
+pub enum E2eTestError {
+    #[error("Browser test command failed: {0}")]
+    Command(String),
+    #[error("Browser test command timed out")]
+    Timeout,
+    #[error("Test report not found at {0}")]
+    ReportMissing(PathBuf),
+    #[error("Unrecognized test report format; expected JUnit XML")]
+    UnknownFormat,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Test cases of a JUnit XML report, as written by Playwright, Cypress and
+/// most other runners
+pub fn parse_junit(content: &str) -> Option<Vec<CreateE2eTestResult>> {
+    if !content.contains("<testcase") {
+        return if content.contains("<testsuite") {
+            Some(Vec::new())
+        } else {
+            None
+        };
+    }
+
+    // Cases outside any <testsuite> get no suite
+    let mut suites: Vec<(Option<String>, &str)> = vec![(None, content)];
+    if content.contains("<testsuite ") {
+        suites = content
+            .split("<testsuite ")
+            .skip(1)
+            .map(|segment| {
+                let tag = &segment[..segment.find('>').unwrap_or(segment.len())];
+                (
+                    xml_attribute(&format!(" {tag}"), "name").map(unescape),
+                    segment,
+                )
+            })
+            .collect();
+    }
+
+    let mut results = Vec::new();
+    for (suite, segment) in suites {
+        for case in segment.split("<testcase").skip(1) {
+            let tag_end = case.find('>')?;
+            let tag = &case[..tag_end];
+            let body = if tag.ends_with('/') {
+                ""
+            } else {
+                let body = &case[tag_end + 1..];
+                &body[..body.find("</testcase>").unwrap_or(body.len())]
+            };
+
+            let failure = ["<failure", "<error"]
+                .iter()
+                .find_map(|marker| body.find(marker).map(|start| &body[start..]));
+            let status = if failure.is_some() {
+                E2eTestStatus::Failed
+            } else if body.contains("<skipped") {
+                E2eTestStatus::Skipped
+            } else {
+                E2eTestStatus::Passed
+            };
+
+            results.push(CreateE2eTestResult {
+                suite: suite
+                    .clone()
+                    .or_else(|| xml_attribute(tag, "classname").map(unescape)),
+                name: unescape(xml_attribute(tag, "name")?),
+                status,
+                duration_ms: xml_attribute(tag, "time")
+                    .and_then(|time| time.parse::<f64>().ok())
+                    .map(|secs| (secs * 1000.0).round() as i64),
+                message: failure.and_then(failure_message),
+            });
+        }
+    }
+    Some(results)
+}
+
+/// `message` attribute of a `<failure>` or `<error>` element, or its text
+fn failure_message(element: &str) -> Option<String> {
+    let tag_end = element.find('>')?;
+    let tag = &element[..tag_end];
+    let text = match xml_attribute(tag, "message") {
+        Some(message) => unescape(message),
+        None if tag.ends_with('/') => return None,
+        None => {
+            let body = &element[tag_end + 1..];
+            let body = &body[..body.find("</").unwrap_or(body.len())];
+            unescape(
+                body.trim()
+                    .trim_start_matches("<![CDATA[")
+                    .trim_end_matches("]]>"),
+            )
+        }
+    };
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_MESSAGE_LINES)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// Run the test command in `dir` against `url` and read the report it writes
+pub async fn run_tests(
+    settings: &ProjectE2eSettings,
+    dir: &Path,
+    url: &str,
+) -> Result<Vec<CreateE2eTestResult>, E2eTestError> {
+    let report_path = dir.join(&settings.report_path);
+    // A stale report from an earlier run must not be mistaken for this one
+    let _ = tokio::fs::remove_file(&report_path).await;
+
+    let quoted_url = shlex::try_quote(url)
+        .map(|quoted| quoted.into_owned())
+        .unwrap_or_else(|_| url.to_string());
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = Command::new(shell_cmd);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .arg(shell_arg)
+        .arg(settings.command.replace("{url}", &quoted_url))
+        .current_dir(dir)
+        // Read by most Playwright configs, and by Cypress as `baseUrl`
+        .env("BASE_URL", url)
+        .env("CYPRESS_BASE_URL", url)
+        .env("PLAYWRIGHT_JUNIT_OUTPUT_NAME", &report_path);
+
+    let output = tokio::time::timeout(E2E_TIMEOUT, command.output())
+        .await
+        .map_err(|_| E2eTestError::Timeout)??;
+
+    // Failing tests exit non-zero too; only a missing report means the
+    // command itself failed
+    let content = match tokio::fs::read_to_string(&report_path).await {
+        Ok(content) => content,
+        Err(_) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+            return Err(E2eTestError::Command(
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+            ));
+        }
+        Err(_) => return Err(E2eTestError::ReportMissing(report_path)),
+    };
+    parse_junit(&content).ok_or(E2eTestError::UnknownFormat)
+}
+
+/// Run the project's browser tests for the workspace and store the results.
+/// `None` when the workspace has no worktree yet.
+pub async fn run_workspace(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    settings: &ProjectE2eSettings,
+    execution_process_id: Option<Uuid>,
+    url: &str,
+) -> Result<Option<E2eTestReport>, E2eTestError> {
+    let Some(container_ref) = &workspace.container_ref else {
+        return Ok(None);
+    };
+
+    let root = PathBuf::from(container_ref);
+    let dir = match settings
+        .working_dir
+        .as_deref()
+        .filter(|dir| !dir.trim().is_empty())
+    {
+        Some(dir) => root.join(dir),
+        None => match WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+            .await?
+            .as_slice()
+        {
+            [repo] => root.join(&repo.name),
+            _ => root,
+        },
+    };
+
+    let results = run_tests(settings, &dir, url).await?;
+    let failed = results
+        .iter()
+        .filter(|r| r.status == E2eTestStatus::Failed)
+        .count();
+    let report = E2eTestRun::create(
+        pool,
+        &CreateE2eTestRun {
+            workspace_id: workspace.id,
+            execution_process_id,
+            url: url.to_string(),
+            auto_pr_blocked: settings.block_auto_pr && failed > 0,
+        },
+        &results,
+    )
+    .await?;
+    Ok(Some(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_junit_test_cases() {
+        let report = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" skipped="1">
+  <testsuite name="login.spec.ts" tests="3">
+    <testcase name="shows the form" classname="login.spec.ts" time="1.25"></testcase>
+    <testcase name="rejects a &quot;bad&quot; password" classname="login.spec.ts" time="2.5">
+      <failure message="expected 401 &amp; got 200" type="FAILURE"><![CDATA[stack]]></failure>
+    </testcase>
+    <testcase name="remembers the user" classname="login.spec.ts" time="0">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+
+        let results = parse_junit(report).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].status, E2eTestStatus::Passed);
+        assert_eq!(results[0].duration_ms, Some(1250));
+        assert_eq!(results[0].suite.as_deref(), Some("login.spec.ts"));
+        assert_eq!(results[1].name, "rejects a \"bad\" password");
+        assert_eq!(results[1].status, E2eTestStatus::Failed);
+        assert_eq!(
+            results[1].message.as_deref(),
+            Some("expected 401 & got 200")
+        );
+        assert_eq!(results[2].status, E2eTestStatus::Skipped);
+    }
+
+    #[test]
+    fn reads_failure_text_without_message() {
+        let report = r#"<testsuite name="cart"><testcase name="adds items"><error>Timed out
+waiting for selector</error></testcase></testsuite>"#;
+        let results = parse_junit(report).unwrap();
+        assert_eq!(
+            results[0].message.as_deref(),
+            Some("Timed out\nwaiting for selector")
+        );
+        assert_eq!(parse_junit("not a report"), None);
+    }
+}
@@ -0,0 +1,429 @@
+//! Optional embedding index over the files of registered repos.
+//!
+//! Files are embedded either locally, as feature-hashed identifier tokens,
+//! or through an OpenAI-compatible `/embeddings` endpoint. Vectors are kept
+//! per repo and only recomputed when a file's contents change. A task's title
+//! and description are embedded the same way and the closest files by cosine
+//! similarity are suggested as context for it.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use db::models::{file_embedding::FileEmbedding, repo::Repo, task::Task};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    config::{Config, EmbeddingProvider},
+    task_enrichment::strip_brief,
+};
+
+pub const DEFAULT_ENDPOINT: &str = "https://api.openai.com/v1/embeddings";
+pub const DEFAULT_MODEL: &str = "text-embedding-3-small";
+pub const DEFAULT_SUGGESTIONS: usize = 10;
+
+const LOCAL_DIMENSIONS: usize = 512;
+/// Larger files are left out of the index
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+const MAX_FILES_PER_REPO: usize = 5000;
+/// Inputs sent to an endpoint are cut to stay within model context limits
+const MAX_INPUT_CHARS: usize = 8000;
+const BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum EmbeddingError {
+    #[error("The embedding index is disabled in the settings")]
+    Disabled,
+    #[error("Embedding request failed: {0}")]
+    Request(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Produces vectors for file contents and task descriptions
+#[derive(Debug, Clone)]
+pub enum Embedder {
+    Local,
+    Api {
+        client: reqwest::Client,
+        endpoint: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl Embedder {
+    /// The configured embedder; `None` while the index is disabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        match config.embedding_provider {
+            EmbeddingProvider::Disabled => None,
+            EmbeddingProvider::Local => Some(Self::Local),
+            EmbeddingProvider::Api => Some(Self::Api {
+                client: reqwest::Client::new(),
+                endpoint: non_empty(&config.embedding_endpoint)
+                    .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string()),
+                model: non_empty(&config.embedding_model)
+                    .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+                api_key: non_empty(&config.embedding_api_key),
+            }),
+        }
+    }
+
+    /// Identifies the vector space; vectors from different embedders are
+    /// never compared
+    pub fn model_id(&self) -> String {
+        match self {
+            Self::Local => format!("local-hash-{LOCAL_DIMENSIONS}"),
+            Self::Api { model, .. } => format!("api:{model}"),
+        }
+    }
+
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        match self {
+            Self::Local => Ok(inputs.iter().map(|input| local_embedding(input)).collect()),
+            Self::Api {
+                client,
+                endpoint,
+                model,
+                api_key,
+            } => {
+                let inputs: Vec<&str> = inputs
+                    .iter()
+                    .map(|input| match input.char_indices().nth(MAX_INPUT_CHARS) {
+                        Some((end, _)) => &input[..end],
+                        None => input.as_str(),
+                    })
+                    .collect();
+                let mut request = client
+                    .post(endpoint)
+                    .json(&json!({ "model": model, "input": inputs }));
+                if let Some(api_key) = api_key {
+                    request = request.bearer_auth(api_key);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+                let mut body: EmbeddingResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+                if body.data.len() != inputs.len() {
+                    return Err(EmbeddingError::Request(format!(
+                        "expected {} embeddings, got {}",
+                        inputs.len(),
+                        body.data.len()
+                    )));
+                }
+                body.data.sort_by_key(|d| d.index);
+                Ok(body.data.into_iter().map(|d| d.embedding).collect())
+            }
+        }
+    }
+}
+
+/// Identifier tokens of `text`, with compound identifiers also split into
+/// their parts (`parseConfigFile` gives `parseconfigfile`, `parse`,
+/// `config` and `file`)
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.len() < 2 {
+            continue;
+        }
+        let mut parts: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut previous_lower = false;
+        for c in word.chars() {
+            if c.is_uppercase() && previous_lower && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        parts.push(current);
+
+        tokens.push(word.to_lowercase());
+        if parts.len() > 1 {
+            tokens.extend(parts.into_iter().filter(|p| p.len() >= 2));
+        }
+    }
+    tokens
+}
+
+fn fnv1a(token: &str) -> u64 {
+    token.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Signed feature hashing of the text's tokens, with sublinear term
+/// frequency so that repeated boilerplate does not dominate
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for token in tokens(text) {
+        *counts.entry(token).or_default() += 1;
+    }
+    let mut vector = vec![0.0f32; LOCAL_DIMENSIONS];
+    for (token, count) in counts {
+        let hash = fnv1a(&token);
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % LOCAL_DIMENSIONS as u64) as usize] += sign * (1.0 + (count as f32).ln());
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// What an index refresh did
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct IndexStats {
+    /// Files embedded because they were new or changed
+    pub embedded: usize,
+    pub unchanged: usize,
+    /// Index entries of files that no longer exist
+    pub removed: usize,
+}
+
+/// A file that is likely relevant to a task
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SuggestedFile {
+    /// Path as used in context packs: prefixed with the repo name when the
+    /// project has several repos
+    pub path: String,
+    pub repo_id: Uuid,
+    /// Path relative to the repo root
+    pub relative_path: String,
+    /// Cosine similarity to the task, higher is closer
+    pub score: f32,
+}
+
+struct ScannedFile {
+    path: String,
+    hash: String,
+    /// Only read for files whose hash changed
+    content: Option<String>,
+}
+
+/// Hash every indexable file of the repo, keeping the contents of those
+/// whose hash differs from `known`
+fn scan_repo(root: &Path, known: &HashMap<String, String>) -> Vec<ScannedFile> {
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(root).hidden(true).build().flatten() {
+        if files.len() >= MAX_FILES_PER_REPO {
+            break;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || !entry
+                .metadata()
+                .is_ok_and(|metadata| metadata.len() <= MAX_FILE_BYTES)
+        {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        // Binary and non-UTF-8 files carry nothing worth embedding
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let path = relative.to_string_lossy().replace('\\', "/");
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        let content = (known.get(&path) != Some(&hash)).then_some(content);
+        files.push(ScannedFile {
+            path,
+            hash,
+            content,
+        });
+    }
+    files
+}
+
+/// Bring the repo's index up to date, embedding only new and changed files
+pub async fn index_repo(
+    pool: &SqlitePool,
+    embedder: &Embedder,
+    repo: &Repo,
+) -> Result<IndexStats, EmbeddingError> {
+    let model = embedder.model_id();
+    let known: HashMap<String, String> = FileEmbedding::find_hashes(pool, repo.id, &model)
+        .await?
+        .into_iter()
+        .collect();
+
+    let root = repo.path.clone();
+    let scan_known = known.clone();
+    let files = tokio::task::spawn_blocking(move || scan_repo(&root, &scan_known))
+        .await
+        .unwrap_or_default();
+
+    let mut stats = IndexStats::default();
+    let (changed, unchanged): (Vec<ScannedFile>, Vec<ScannedFile>) =
+        files.into_iter().partition(|f| f.content.is_some());
+    stats.unchanged = unchanged.len();
+
+    for batch in changed.chunks(BATCH_SIZE) {
+        // The path says as much about a file as its contents
+        let inputs: Vec<String> = batch
+            .iter()
+            .map(|f| format!("{}\n{}", f.path, f.content.as_deref().unwrap_or_default()))
+            .collect();
+        let vectors = embedder.embed(&inputs).await?;
+        for (file, vector) in batch.iter().zip(vectors) {
+            FileEmbedding::upsert(pool, repo.id, &file.path, &file.hash, &model, &vector).await?;
+        }
+        stats.embedded += batch.len();
+    }
+
+    let present: HashSet<&str> = changed
+        .iter()
+        .chain(&unchanged)
+        .map(|f| f.path.as_str())
+        .collect();
+    for path in known.keys() {
+        if !present.contains(path.as_str()) {
+            FileEmbedding::delete(pool, repo.id, path).await?;
+            stats.removed += 1;
+        }
+    }
+
+    if stats.embedded > 0 || stats.removed > 0 {
+        tracing::info!(
+            "Embedding index of {}: {} embedded, {} removed",
+            repo.name,
+            stats.embedded,
+            stats.removed
+        );
+    }
+    Ok(stats)
+}
+
+/// The text a task is matched on
+fn task_query(task: &Task) -> String {
+    match task.description.as_deref().map(strip_brief) {
+        Some(description) if !description.trim().is_empty() => {
+            format!("{}\n{}", task.title, description)
+        }
+        _ => task.title.clone(),
+    }
+}
+
+/// The files of `repos` closest to the task, best first. The index of each
+/// repo is refreshed first so edits since the last request are taken into
+/// account.
+pub async fn suggest(
+    pool: &SqlitePool,
+    embedder: &Embedder,
+    repos: &[Repo],
+    task: &Task,
+    limit: usize,
+) -> Result<Vec<SuggestedFile>, EmbeddingError> {
+    for repo in repos {
+        index_repo(pool, embedder, repo).await?;
+    }
+
+    let Some(query) = embedder.embed(&[task_query(task)]).await?.pop() else {
+        return Ok(Vec::new());
+    };
+    let model = embedder.model_id();
+    let multi_repo = repos.len() > 1;
+
+    let mut suggestions = Vec::new();
+    for repo in repos {
+        for embedding in FileEmbedding::find_by_repo(pool, repo.id, &model).await? {
+            let score = cosine_similarity(&query, &embedding.decode());
+            if score <= 0.0 {
+                continue;
+            }
+            suggestions.push(SuggestedFile {
+                path: if multi_repo {
+                    format!("{}/{}", repo.name, embedding.path)
+                } else {
+                    embedding.path.clone()
+                },
+                repo_id: repo.id,
+                relative_path: embedding.path,
+                score,
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_compound_identifiers() {
+        assert_eq!(
+            tokens("parseConfigFile(auth_token)"),
+            vec![
+                "parseconfigfile",
+                "parse",
+                "config",
+                "file",
+                "auth",
+                "token"
+            ]
+        );
+    }
+
+    #[test]
+    fn local_embedding_ranks_related_files_first() {
+        let query = local_embedding("Fix the login timeout in the auth session handler");
+        let auth = local_embedding(
+            "src/auth/session.rs\nfn refresh_session(login: &Login) { let timeout = SESSION_TIMEOUT; }",
+        );
+        let css = local_embedding("styles/theme.css\n.button { color: red; margin: 4px; }");
+
+        assert!(cosine_similarity(&query, &auth) > cosine_similarity(&query, &css));
+        assert!((cosine_similarity(&auth, &auth) - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,302 @@
+//! Env files rendered into each new worktree from project templates.
+//!
+//! Templates are plain text with `{{...}}` placeholders:
+//!
+//! - `{{port:NAME}}` a free local port, allocated once per workspace and name
+//! - `{{secret:NAME}}` a secret from the secret store that is passed to agents
+//! - `{{branch}}`, `{{workspace_id}}`, `{{repo_name}}`, `{{worktree_path}}`
+//!
+//! Rendered files are never overwritten unless asked to, and are added to the
+//! repo's `info/exclude` when no ignore rule covers them so that resolved
+//! secrets do not end up in a commit.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    io::{self, Write},
+    net::TcpListener,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use db::models::{
+    env_template::{ProjectEnvTemplate, WorkspacePort},
+    repo::Repo,
+    workspace::Workspace,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::secrets::SecretStore;
+
+const PORT_RANGE: RangeInclusive<u16> = 20000..=39999;
+const SECRET_PREFIX: &str = "secret:";
+const PORT_PREFIX: &str = "port:";
+const VARIABLES: [&str; 4] = ["branch", "workspace_id", "repo_name", "worktree_path"];
+
+#[derive(Debug, Error)]
+pub enum EnvTemplateError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+    #[error("No free port left for '{0}'")]
+    NoFreePort(String),
+}
+
+/// What a template refers to, for checking it before it is saved
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct EnvTemplateCheck {
+    pub ports: Vec<String>,
+    pub secrets: Vec<String>,
+    /// Referenced secrets that are not in the secret store
+    pub missing_secrets: Vec<String>,
+    /// Placeholders that are neither ports, secrets nor known variables;
+    /// they are left as written
+    pub unknown: Vec<String>,
+}
+
+/// Trimmed contents of every `{{...}}` in `template`, in order
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        found.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    found
+}
+
+/// `template` with every placeholder `resolve` knows replaced; the others
+/// are kept verbatim
+pub fn render(template: &str, mut resolve: impl FnMut(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match resolve(after[..end].trim()) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+pub async fn check(secrets: &SecretStore, template: &str) -> EnvTemplateCheck {
+    let available = secrets.agent_env().await;
+    let mut ports = BTreeSet::new();
+    let mut referenced = BTreeSet::new();
+    let mut unknown = BTreeSet::new();
+    for placeholder in placeholders(template) {
+        if let Some(name) = placeholder.strip_prefix(PORT_PREFIX) {
+            ports.insert(name.trim().to_string());
+        } else if let Some(name) = placeholder.strip_prefix(SECRET_PREFIX) {
+            referenced.insert(name.trim().to_string());
+        } else if !VARIABLES.contains(&placeholder) {
+            unknown.insert(placeholder.to_string());
+        }
+    }
+    EnvTemplateCheck {
+        missing_secrets: referenced
+            .iter()
+            .filter(|name| !available.contains_key(*name))
+            .cloned()
+            .collect(),
+        ports: ports.into_iter().collect(),
+        secrets: referenced.into_iter().collect(),
+        unknown: unknown.into_iter().collect(),
+    }
+}
+
+fn is_port_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// The workspace's port for `name`, allocating one that no other workspace
+/// holds and nothing is listening on
+pub async fn allocate_port(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    name: &str,
+) -> Result<u16, EnvTemplateError> {
+    let find = || async {
+        WorkspacePort::find_by_workspace_id(pool, workspace_id)
+            .await
+            .map(|ports| ports.into_iter().find(|p| p.name == name).map(|p| p.port))
+    };
+    if let Some(port) = find().await? {
+        return Ok(port);
+    }
+
+    let used: HashSet<u16> = WorkspacePort::find_all_ports(pool)
+        .await?
+        .into_iter()
+        .collect();
+    // Start at a different place per workspace so that concurrent
+    // allocations rarely race for the same port
+    let span = (PORT_RANGE.end() - PORT_RANGE.start()) as u32 + 1;
+    let offset = (workspace_id.as_u128() % span as u128) as u32;
+    for step in 0..span {
+        let port = PORT_RANGE.start() + ((offset + step) % span) as u16;
+        if used.contains(&port) || !is_port_free(port) {
+            continue;
+        }
+        if WorkspacePort::try_create(pool, workspace_id, name, port)
+            .await?
+            .is_some()
+        {
+            return Ok(port);
+        }
+        // Lost a race, either for this port or for the name itself
+        if let Some(port) = find().await? {
+            return Ok(port);
+        }
+    }
+    Err(EnvTemplateError::NoFreePort(name.to_string()))
+}
+
+/// Add `relative` to the repo's `info/exclude` unless git already ignores it.
+/// The exclude file is shared by the repo and all of its worktrees.
+fn exclude_from_git(worktree: &Path, relative: &str) -> Result<(), EnvTemplateError> {
+    let repo = git2::Repository::open(worktree)?;
+    if repo.is_path_ignored(relative)? {
+        return Ok(());
+    }
+    let exclude = repo.commondir().join("info").join("exclude");
+    let entry = format!("/{}", relative.replace('\\', "/"));
+    let existing = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == entry) {
+        return Ok(());
+    }
+    if let Some(parent) = exclude.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&exclude)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{entry}")?;
+    Ok(())
+}
+
+/// Render the project's templates into the workspace's worktrees. Existing
+/// files are left alone unless `overwrite` is set. Returns the written files.
+pub async fn materialize(
+    pool: &SqlitePool,
+    secrets: &SecretStore,
+    project_id: Uuid,
+    workspace: &Workspace,
+    workspace_dir: &Path,
+    repos: &[Repo],
+    overwrite: bool,
+) -> Result<Vec<PathBuf>, EnvTemplateError> {
+    let templates = ProjectEnvTemplate::find_by_project_id(pool, project_id).await?;
+    if templates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let available = secrets.agent_env().await;
+
+    let mut written = Vec::new();
+    for template in &templates {
+        let mut ports = HashMap::new();
+        for placeholder in placeholders(&template.content) {
+            if let Some(name) = placeholder.strip_prefix(PORT_PREFIX) {
+                let name = name.trim();
+                let port = allocate_port(pool, workspace.id, name).await?;
+                ports.insert(name.to_string(), port.to_string());
+            }
+        }
+
+        for repo in repos
+            .iter()
+            .filter(|repo| template.repo_id.is_none_or(|id| id == repo.id))
+        {
+            let worktree = workspace_dir.join(&repo.name);
+            let target = worktree.join(&template.path);
+            if !worktree.is_dir() || (target.exists() && !overwrite) {
+                continue;
+            }
+
+            let worktree_path = worktree.to_string_lossy().to_string();
+            let content = render(&template.content, |placeholder| {
+                if let Some(name) = placeholder.strip_prefix(PORT_PREFIX) {
+                    return ports.get(name.trim()).cloned();
+                }
+                if let Some(name) = placeholder.strip_prefix(SECRET_PREFIX) {
+                    let name = name.trim();
+                    return Some(available.get(name).cloned().unwrap_or_else(|| {
+                        tracing::warn!(
+                            "Secret '{}' used by env template {} is not set",
+                            name,
+                            template.path
+                        );
+                        String::new()
+                    }));
+                }
+                match placeholder {
+                    "branch" => Some(workspace.branch.clone()),
+                    "workspace_id" => Some(workspace.id.to_string()),
+                    "repo_name" => Some(repo.name.clone()),
+                    "worktree_path" => Some(worktree_path.clone()),
+                    _ => None,
+                }
+            });
+
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&target, content).await?;
+            if let Err(e) = exclude_from_git(&worktree, &template.path) {
+                tracing::warn!(
+                    "Failed to exclude {} from git in {}: {}",
+                    template.path,
+                    worktree.display(),
+                    e
+                );
+            }
+            written.push(target);
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_and_keeps_the_rest() {
+        let template =
+            "PORT={{ port:web }}\nTOKEN={{secret:API_TOKEN}}\nHOME=${HOME}\nX={{other}}\nTAIL={{";
+        assert_eq!(
+            placeholders(template),
+            vec!["port:web", "secret:API_TOKEN", "other"]
+        );
+        let rendered = render(template, |placeholder| match placeholder {
+            "port:web" => Some("20001".to_string()),
+            "secret:API_TOKEN" => Some("abc".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            rendered,
+            "PORT=20001\nTOKEN=abc\nHOME=${HOME}\nX={{other}}\nTAIL={{"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Snapshots of the toolchain an attempt's coding agent ran with.
+//!
+//! Before each coding agent run the versions of common tools and the hashes of
+//! lockfiles and toolchain pins in the attempt's repos are recorded, so that an
+//! attempt that works and one that fails can be compared long after their
+//! worktrees are gone. A snapshot is only stored when something changed since
+//! the attempt's previous one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use db::models::{
+    repo::Repo,
+    workspace_environment::{
+        CreateWorkspaceEnvironment, LockfileHash, ToolVersion, WorkspaceEnvironment,
+    },
+};
+use futures::future;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tokio::process::Command;
+use ts_rs::TS;
+use utils::shell::resolve_executable_path;
+use uuid::Uuid;
+
+/// Reported name, executable and version arguments
+const TOOLS: &[(&str, &str, &[&str])] = &[
+    ("node", "node", &["--version"]),
+    ("npm", "npm", &["--version"]),
+    ("pnpm", "pnpm", &["--version"]),
+    ("yarn", "yarn", &["--version"]),
+    ("bun", "bun", &["--version"]),
+    ("rustc", "rustc", &["--version"]),
+    ("cargo", "cargo", &["--version"]),
+    ("python", "python3", &["--version"]),
+    ("uv", "uv", &["--version"]),
+    ("go", "go", &["version"]),
+    ("java", "java", &["-version"]),
+    ("git", "git", &["--version"]),
+];
+
+/// Lockfiles and toolchain pins looked for at the root of each repo
+const PINNED_FILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lock",
+    "bun.lockb",
+    "poetry.lock",
+    "uv.lock",
+    "Pipfile.lock",
+    "go.sum",
+    "Gemfile.lock",
+    "composer.lock",
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    ".tool-versions",
+];
+
+const TOOL_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum EnvironmentDifferenceKind {
+    Platform,
+    Tool,
+    Lockfile,
+}
+
+/// Something that differs between two snapshots; `None` means absent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct EnvironmentDifference {
+    pub kind: EnvironmentDifferenceKind,
+    /// Tool name, `os`/`arch`, or `repo/path` of a lockfile
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+fn first_line(bytes: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+async fn tool_version(program: &str, args: &[&str], dir: &Path) -> Option<String> {
+    let path = resolve_executable_path(program).await?;
+    let output = tokio::time::timeout(
+        TOOL_TIMEOUT,
+        Command::new(path)
+            .args(args)
+            .current_dir(dir)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    // Some tools, java among them, print their version to stderr
+    first_line(&output.stdout).or_else(|| first_line(&output.stderr))
+}
+
+/// Tool versions as seen from the first repo's worktree, so that per-directory
+/// pins such as `rust-toolchain.toml` or `.nvmrc` are honoured by shims
+pub async fn capture(workspace_dir: &Path, repos: &[Repo]) -> CreateWorkspaceEnvironment {
+    let dir = repos
+        .first()
+        .map(|repo| workspace_dir.join(&repo.name))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| workspace_dir.to_path_buf());
+    let tools = future::join_all(TOOLS.iter().map(|(name, program, args)| {
+        let dir = &dir;
+        async move {
+            ToolVersion {
+                name: name.to_string(),
+                version: tool_version(program, args, dir).await,
+            }
+        }
+    }))
+    .await;
+
+    let mut lockfiles = Vec::new();
+    for repo in repos {
+        for file in PINNED_FILES {
+            if let Ok(bytes) = tokio::fs::read(workspace_dir.join(&repo.name).join(file)).await {
+                lockfiles.push(LockfileHash {
+                    repo: repo.name.clone(),
+                    path: file.to_string(),
+                    sha256: format!("{:x}", Sha256::digest(&bytes)),
+                });
+            }
+        }
+    }
+
+    CreateWorkspaceEnvironment {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        tools,
+        lockfiles,
+    }
+}
+
+/// Capture the environment and store it unless it matches the attempt's
+/// latest snapshot
+pub async fn record(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    workspace_dir: &Path,
+    repos: &[Repo],
+) -> Result<Option<WorkspaceEnvironment>, sqlx::Error> {
+    let snapshot = capture(workspace_dir, repos).await;
+    if WorkspaceEnvironment::find_latest(pool, workspace_id)
+        .await?
+        .is_some_and(|latest| latest.matches(&snapshot))
+    {
+        return Ok(None);
+    }
+    WorkspaceEnvironment::create(pool, workspace_id, execution_process_id, &snapshot)
+        .await
+        .map(Some)
+}
+
+/// Record in the background so that starting the run is not delayed
+pub fn spawn(
+    pool: SqlitePool,
+    workspace_id: Uuid,
+    execution_process_id: Uuid,
+    workspace_dir: PathBuf,
+    repos: Vec<Repo>,
+) {
+    tokio::spawn(async move {
+        if let Err(e) = record(
+            &pool,
+            workspace_id,
+            Some(execution_process_id),
+            &workspace_dir,
+            &repos,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to record environment of workspace {}: {}",
+                workspace_id,
+                e
+            );
+        }
+    });
+}
+
+fn push_differences(
+    differences: &mut Vec<EnvironmentDifference>,
+    kind: EnvironmentDifferenceKind,
+    left: BTreeMap<String, Option<String>>,
+    right: BTreeMap<String, Option<String>>,
+) {
+    let names: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    for name in names {
+        let left = left.get(name).cloned().flatten();
+        let right = right.get(name).cloned().flatten();
+        if left != right {
+            differences.push(EnvironmentDifference {
+                kind,
+                name: name.clone(),
+                left,
+                right,
+            });
+        }
+    }
+}
+
+/// Everything that differs between two snapshots
+pub fn compare(
+    left: &WorkspaceEnvironment,
+    right: &WorkspaceEnvironment,
+) -> Vec<EnvironmentDifference> {
+    let platform = |env: &WorkspaceEnvironment| {
+        BTreeMap::from([
+            ("os".to_string(), Some(env.os.clone())),
+            ("arch".to_string(), Some(env.arch.clone())),
+        ])
+    };
+    let tools = |env: &WorkspaceEnvironment| {
+        env.tools
+            .iter()
+            .map(|tool| (tool.name.clone(), tool.version.clone()))
+            .collect()
+    };
+    let lockfiles = |env: &WorkspaceEnvironment| {
+        env.lockfiles
+            .iter()
+            .map(|file| {
+                (
+                    format!("{}/{}", file.repo, file.path),
+                    Some(file.sha256.clone()),
+                )
+            })
+            .collect()
+    };
+
+    let mut differences = Vec::new();
+    push_differences(
+        &mut differences,
+        EnvironmentDifferenceKind::Platform,
+        platform(left),
+        platform(right),
+    );
+    push_differences(
+        &mut differences,
+        EnvironmentDifferenceKind::Tool,
+        tools(left),
+        tools(right),
+    );
+    push_differences(
+        &mut differences,
+        EnvironmentDifferenceKind::Lockfile,
+        lockfiles(left),
+        lockfiles(right),
+    );
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn snapshot(node: Option<&str>, lock: Option<&str>) -> WorkspaceEnvironment {
+        WorkspaceEnvironment {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            execution_process_id: None,
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            tools: Json(vec![
+                ToolVersion {
+                    name: "node".to_string(),
+                    version: node.map(str::to_string),
+                },
+                ToolVersion {
+                    name: "git".to_string(),
+                    version: Some("git version 2.43.0".to_string()),
+                },
+            ]),
+            lockfiles: Json(
+                lock.map(|sha256| LockfileHash {
+                    repo: "web".to_string(),
+                    path: "package-lock.json".to_string(),
+                    sha256: sha256.to_string(),
+                })
+                .into_iter()
+                .collect(),
+            ),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn compare_lists_changed_and_missing_entries() {
+        let differences = compare(
+            &snapshot(Some("v20.11.0"), Some("aaa")),
+            &snapshot(None, Some("bbb")),
+        );
+        assert_eq!(
+            differences,
+            vec![
+                EnvironmentDifference {
+                    kind: EnvironmentDifferenceKind::Tool,
+                    name: "node".to_string(),
+                    left: Some("v20.11.0".to_string()),
+                    right: None,
+                },
+                EnvironmentDifference {
+                    kind: EnvironmentDifferenceKind::Lockfile,
+                    name: "web/package-lock.json".to_string(),
+                    left: Some("aaa".to_string()),
+                    right: Some("bbb".to_string()),
+                },
+            ]
+        );
+        assert!(compare(&snapshot(None, None), &snapshot(None, None)).is_empty());
+    }
+}
@@ -22,6 +22,7 @@ pub mod types;
 
 pub use patches::{
     execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    worktree_state_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
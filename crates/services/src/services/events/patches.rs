@@ -8,6 +8,8 @@ use db::models::{
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
 
+use crate::services::worktree_watch::WorktreeState;
+
 // Shared helper to escape JSON Pointer segments
 fn escape_pointer_segment(s: &str) -> String {
     s.replace('~', "~0").replace('/', "~1")
@@ -249,3 +251,21 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating worktree state patches.
+/// All patches use path "/worktree_state" - filtering is done by matching workspace_id in the value.
+pub mod worktree_state_patch {
+    use super::*;
+
+    pub const WORKTREE_STATE_PATH: &str = "/worktree_state";
+
+    pub fn replace(state: &WorktreeState) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: WORKTREE_STATE_PATH
+                .try_into()
+                .expect("Worktree state path should be valid"),
+            value: serde_json::to_value(state)
+                .expect("Worktree state serialization should not fail"),
+        })])
+    }
+}
@@ -3,12 +3,14 @@ use std::{collections::HashMap, sync::Arc};
 use db::models::{
     execution_process::ExecutionProcess,
     project::{Project, ProjectWithTaskCounts},
+    project_owner::ProjectOwner,
     scratch::Scratch,
     task::{ActiveTaskWithProject, Task, TaskWithAttemptStatus},
     workspace::Workspace,
 };
 use futures::StreamExt;
 use serde_json::json;
+use sqlx::SqlitePool;
 use tokio::sync::RwLock;
 use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 use utils::log_msg::LogMsg;
@@ -16,9 +18,10 @@ use uuid::Uuid;
 
 use super::{
     EventService,
-    patches::execution_process_patch,
+    patches::{execution_process_patch, worktree_state_patch::WORKTREE_STATE_PATH},
     types::{EventError, EventPatch, RecordTypes},
 };
+use crate::services::worktree_watch;
 
 impl EventService {
     /// Stream raw task messages for a specific project with initial snapshot
@@ -400,9 +403,11 @@ impl EventService {
         Ok(combined_stream)
     }
 
-    /// Stream raw project messages with initial snapshot
+    /// Stream raw project messages with initial snapshot. With an `owner`
+    /// (multi-user mode) only that user's projects are included.
     pub async fn stream_projects_raw(
         &self,
+        owner: Option<Uuid>,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
     {
         fn build_projects_snapshot(projects: Vec<ProjectWithTaskCounts>) -> LogMsg {
@@ -428,8 +433,20 @@ impl EventService {
             LogMsg::JsonPatch(serde_json::from_value(patch).unwrap())
         }
 
+        async fn visible_projects(
+            pool: &SqlitePool,
+            owner: Option<Uuid>,
+        ) -> Result<Vec<ProjectWithTaskCounts>, sqlx::Error> {
+            let mut projects = Project::find_all_with_task_counts(pool).await?;
+            if let Some(owner) = owner {
+                let owned = ProjectOwner::project_ids_for_user(pool, owner).await?;
+                projects.retain(|project| owned.contains(&project.project.id));
+            }
+            Ok(projects)
+        }
+
         // Get initial snapshot of projects with task counts
-        let projects = Project::find_all_with_task_counts(&self.db.pool).await?;
+        let projects = visible_projects(&self.db.pool, owner).await?;
         let initial_msg = build_projects_snapshot(projects);
 
         let db_pool = self.db.pool.clone();
@@ -444,6 +461,22 @@ impl EventService {
                             if let Some(patch_op) = patch.0.first()
                                 && patch_op.path().starts_with("/projects")
                             {
+                                // Removals are always forwarded: the owner row is
+                                // already gone by the time the patch arrives
+                                if let Some(owner) = owner
+                                    && !matches!(patch_op, json_patch::PatchOperation::Remove(_))
+                                {
+                                    let project_id = patch_op
+                                        .path()
+                                        .strip_prefix("/projects/")
+                                        .and_then(|rest| rest.split('/').next())
+                                        .and_then(|id| Uuid::parse_str(id).ok())?;
+                                    let project_owner =
+                                        ProjectOwner::find_owner(&db_pool, project_id).await;
+                                    if !matches!(project_owner, Ok(Some(id)) if id == owner) {
+                                        return None;
+                                    }
+                                }
                                 return Some(Ok(LogMsg::JsonPatch(patch)));
                             }
                             None
@@ -455,7 +488,7 @@ impl EventService {
                                 "projects stream lagged; resyncing snapshot"
                             );
 
-                            match Project::find_all_with_task_counts(&db_pool).await {
+                            match visible_projects(&db_pool, owner).await {
                                 Ok(projects) => Some(Ok(build_projects_snapshot(projects))),
                                 Err(err) => {
                                     tracing::error!(
@@ -687,6 +720,48 @@ impl EventService {
         Ok(combined_stream)
     }
 
+    /// Stream the uncommitted files of an attempt, refreshed on hand edits
+    pub async fn stream_worktree_state_raw(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let state = worktree_watch::worktree_state(&self.db.pool, workspace).await?;
+        let initial_patch = json!([{
+            "op": "replace",
+            "path": WORKTREE_STATE_PATH,
+            "value": state
+        }]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let id_str = workspace.id.to_string();
+        let filtered_stream =
+            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
+                let id_str = id_str.clone();
+                async move {
+                    match msg_result {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            if let Some(op) = patch.0.first()
+                                && op.path() == WORKTREE_STATE_PATH
+                                && let json_patch::PatchOperation::Replace(r) = op
+                                && r.value.get("workspace_id").and_then(|v| v.as_str())
+                                    == Some(id_str.as_str())
+                            {
+                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                            }
+                            None
+                        }
+                        Ok(other) => Some(Ok(other)),
+                        Err(_) => None,
+                    }
+                }
+            });
+
+        let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
+        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+        Ok(combined_stream)
+    }
+
     pub async fn stream_workspaces_raw(
         &self,
         archived: Option<bool>,
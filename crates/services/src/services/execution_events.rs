@@ -0,0 +1,163 @@
+//! Typed events from an execution's normalized output.
+//!
+//! Executors normalize agent output into conversation entries that are only
+//! kept in memory and rebuilt from the raw logs on demand. Each entry is
+//! mapped here to the events stored as it is ingested: messages, tool calls
+//! and their results, errors and token usage.
+
+use db::models::execution_log_event::{ExecutionLogEventData, ExecutionLogEventKind};
+use executors::logs::{NormalizedEntry, NormalizedEntryType, ToolStatus};
+use serde::Serialize;
+
+/// Events for the entry at `index`. Entries without a lasting meaning, like
+/// loading indicators and next-action prompts, produce none.
+pub fn events_from_entry(index: usize, entry: &NormalizedEntry) -> Vec<ExecutionLogEventData> {
+    let event = |kind| ExecutionLogEventData {
+        entry_index: index as i64,
+        kind,
+        role: None,
+        tool_name: None,
+        status: None,
+        content: entry.content.clone(),
+        data: None,
+        total_tokens: None,
+        model_context_window: None,
+    };
+    let message = |role: &str| ExecutionLogEventData {
+        role: Some(role.to_string()),
+        ..event(ExecutionLogEventKind::Message)
+    };
+
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => vec![message("user")],
+        NormalizedEntryType::UserFeedback { denied_tool } => vec![ExecutionLogEventData {
+            tool_name: Some(denied_tool.clone()),
+            ..message("user")
+        }],
+        NormalizedEntryType::AssistantMessage => vec![message("assistant")],
+        NormalizedEntryType::SystemMessage => vec![message("system")],
+        NormalizedEntryType::Thinking => vec![message("thinking")],
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => {
+            let call = ExecutionLogEventData {
+                tool_name: Some(tool_name.clone()),
+                status: tag(status, "status"),
+                data: serde_json::to_value(action_type).ok(),
+                ..event(ExecutionLogEventKind::ToolCall)
+            };
+            if is_finished(status) {
+                let result = ExecutionLogEventData {
+                    kind: ExecutionLogEventKind::ToolResult,
+                    ..call.clone()
+                };
+                vec![call, result]
+            } else {
+                vec![call]
+            }
+        }
+        NormalizedEntryType::ErrorMessage { error_type } => vec![ExecutionLogEventData {
+            status: tag(error_type, "type"),
+            ..event(ExecutionLogEventKind::Error)
+        }],
+        NormalizedEntryType::TokenUsageInfo(usage) => vec![ExecutionLogEventData {
+            total_tokens: Some(i64::from(usage.total_tokens)),
+            model_context_window: Some(i64::from(usage.model_context_window)),
+            ..event(ExecutionLogEventKind::Usage)
+        }],
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => Vec::new(),
+    }
+}
+
+/// Whether the tool call has an outcome, as opposed to still running or
+/// waiting for approval
+fn is_finished(status: &ToolStatus) -> bool {
+    matches!(
+        status,
+        ToolStatus::Success | ToolStatus::Failed | ToolStatus::Denied { .. } | ToolStatus::TimedOut
+    )
+}
+
+/// The serde tag of an internally tagged enum value, e.g. `pending_approval`
+fn tag(value: &impl Serialize, field: &str) -> Option<String> {
+    serde_json::to_value(value)
+        .ok()?
+        .get(field)?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{ActionType, TokenUsageInfo};
+
+    use super::*;
+
+    #[test]
+    fn finished_tool_calls_also_produce_a_result() {
+        let tool_use = |status| {
+            NormalizedEntry::new(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "bash".to_string(),
+                    action_type: ActionType::CommandRun {
+                        command: "cargo test".to_string(),
+                        result: None,
+                    },
+                    status,
+                },
+                "cargo test".to_string(),
+            )
+        };
+
+        let running = events_from_entry(3, &tool_use(ToolStatus::Created));
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].kind, ExecutionLogEventKind::ToolCall);
+        assert_eq!(running[0].status.as_deref(), Some("created"));
+        assert_eq!(running[0].data.as_ref().unwrap()["action"], "command_run");
+
+        let done = events_from_entry(3, &tool_use(ToolStatus::Failed));
+        let kinds: Vec<_> = done.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ExecutionLogEventKind::ToolCall,
+                ExecutionLogEventKind::ToolResult
+            ]
+        );
+        assert!(done.iter().all(|event| event.entry_index == 3
+            && event.tool_name.as_deref() == Some("bash")
+            && event.status.as_deref() == Some("failed")));
+    }
+
+    #[test]
+    fn maps_messages_usage_and_transient_entries() {
+        let thinking = events_from_entry(
+            0,
+            &NormalizedEntry::new(NormalizedEntryType::Thinking, "hmm".to_string()),
+        );
+        assert_eq!(thinking[0].kind, ExecutionLogEventKind::Message);
+        assert_eq!(thinking[0].role.as_deref(), Some("thinking"));
+
+        let usage = events_from_entry(
+            1,
+            &NormalizedEntry::new(
+                NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+                    total_tokens: 1200,
+                    model_context_window: 200_000,
+                }),
+                String::new(),
+            ),
+        );
+        assert_eq!(usage[0].kind, ExecutionLogEventKind::Usage);
+        assert_eq!(usage[0].total_tokens, Some(1200));
+        assert_eq!(usage[0].model_context_window, Some(200_000));
+
+        let loading = events_from_entry(
+            2,
+            &NormalizedEntry::new(NormalizedEntryType::Loading, String::new()),
+        );
+        assert!(loading.is_empty());
+    }
+}
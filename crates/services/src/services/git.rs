@@ -7,13 +7,14 @@ use git2::{
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
 
 mod cli;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
-pub use cli::{GitCli, GitCliError};
+pub use cli::{GitCli, GitCliError, SshIdentity};
 
 use super::{file_ranker::FileStat, git_host::github::GitHubRepoInfo};
 
@@ -43,7 +44,9 @@ pub enum GitServiceError {
 }
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
-pub struct GitService {}
+pub struct GitService {
+    cancel: Option<CancellationToken>,
+}
 
 // Max inline diff size for UI (in bytes). Files larger than this will have
 // their contents omitted from the diff stream to avoid UI crashes.
@@ -157,7 +160,20 @@ impl Default for GitService {
 impl GitService {
     /// Create a new GitService for the given repository path
     pub fn new() -> Self {
-        Self {}
+        Self { cancel: None }
+    }
+
+    /// A service whose git commands are killed once `cancel` fires, for
+    /// callers that give up on an operation, e.g. a closed request. Work done
+    /// in-process through libgit2 is not interrupted.
+    pub fn with_cancellation(cancel: CancellationToken) -> Self {
+        Self {
+            cancel: Some(cancel),
+        }
+    }
+
+    fn cli(&self) -> GitCli {
+        GitCli::with_cancellation(self.cancel.clone())
     }
 
     pub fn is_branch_name_valid(&self, name: &str) -> bool {
@@ -282,7 +298,7 @@ impl GitService {
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
-        let git = GitCli::new();
+        let git = self.cli();
         let has_changes = git
             .has_changes(path)
             .map_err(|e| GitServiceError::InvalidRepository(format!("git status failed: {e}")))?;
@@ -322,7 +338,7 @@ impl GitService {
                         ))
                     })?;
 
-                let git = GitCli::new();
+                let git = self.cli();
                 let cli_opts = StatusDiffOptions {
                     path_filter: path_filter.map(|fs| fs.iter().map(|s| s.to_string()).collect()),
                 };
@@ -778,7 +794,7 @@ impl GitService {
         repo_path: &Path,
         branch_name: &str,
     ) -> Result<Option<std::path::PathBuf>, GitServiceError> {
-        let git_cli = GitCli::new();
+        let git_cli = self.cli();
         let worktrees = git_cli.list_worktrees(repo_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git worktree list failed: {e}"))
         })?;
@@ -821,7 +837,7 @@ impl GitService {
         match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
             Some(base_checkout_path) => {
                 // base branch is checked out somewhere - use CLI merge
-                let git_cli = GitCli::new();
+                let git_cli = self.cli();
 
                 // Safety check: base branch has no staged changes
                 if git_cli
@@ -928,6 +944,21 @@ impl GitService {
         )
     }
 
+    /// Update a remote-tracking branch from its remote; local branches are left
+    /// as they are
+    pub fn fetch_remote_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
+        if branch_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &branch_ref)?;
+        }
+        Ok(())
+    }
+
     pub fn get_base_commit(
         &self,
         repo_path: &Path,
@@ -1073,7 +1104,7 @@ impl GitService {
         target_branch: &str,
         task_branch: &str,
     ) -> Result<String, GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         Ok(git.merge_base(worktree_path, target_branch, task_branch)?)
     }
 
@@ -1112,7 +1143,7 @@ impl GitService {
         &self,
         worktree_path: &Path,
     ) -> Result<(usize, usize), GitServiceError> {
-        let cli = GitCli::new();
+        let cli = self.cli();
         let st = cli
             .get_worktree_status(worktree_path)
             .map_err(|e| GitServiceError::InvalidRepository(format!("git status failed: {e}")))?;
@@ -1173,7 +1204,7 @@ impl GitService {
             // Avoid clobbering uncommitted changes unless explicitly forced
             self.check_worktree_clean(&repo)?;
         }
-        let cli = GitCli::new();
+        let cli = self.cli();
         cli.git(worktree_path, ["reset", "--hard", commit_sha])
             .map_err(|e| {
                 GitServiceError::InvalidRepository(format!("git reset --hard failed: {e}"))
@@ -1191,7 +1222,7 @@ impl GitService {
         branch: &str,
         create_branch: bool,
     ) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.worktree_add(repo_path, worktree_path, branch, create_branch)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
         Ok(())
@@ -1204,7 +1235,7 @@ impl GitService {
         worktree_path: &Path,
         force: bool,
     ) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.worktree_remove(repo_path, worktree_path, force)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
         Ok(())
@@ -1217,14 +1248,14 @@ impl GitService {
         old_path: &Path,
         new_path: &Path,
     ) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.worktree_move(repo_path, old_path, new_path)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
         Ok(())
     }
 
     pub fn prune_worktrees(&self, repo_path: &Path) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.worktree_prune(repo_path)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
         Ok(())
@@ -1360,7 +1391,7 @@ impl GitService {
 
         // If a rebase is already in progress, refuse to proceed instead of
         // aborting (which might destroy user changes mid-rebase).
-        let git = GitCli::new();
+        let git = self.cli();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
             return Err(GitServiceError::RebaseInProgress);
         }
@@ -1491,9 +1522,23 @@ impl GitService {
         Ok(())
     }
 
+    /// Delete a local branch. Fails while a worktree still has it checked out.
+    pub fn delete_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        branch.delete()?;
+        Ok(())
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.is_rebase_in_progress(worktree_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git rebase state check failed: {e}"))
         })
@@ -1503,7 +1548,7 @@ impl GitService {
         &self,
         worktree_path: &Path,
     ) -> Result<Option<ConflictOp>, GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
             return Ok(Some(ConflictOp::Rebase));
         }
@@ -1527,7 +1572,7 @@ impl GitService {
         &self,
         worktree_path: &Path,
     ) -> Result<Vec<String>, GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.get_conflicted_files(worktree_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git diff for conflicts failed: {e}"))
         })
@@ -1535,14 +1580,14 @@ impl GitService {
 
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         git.abort_rebase(worktree_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git rebase --abort failed: {e}"))
         })
     }
 
     pub fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
-        let git = GitCli::new();
+        let git = self.cli();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
             // If there are no conflicted files, prefer `git rebase --quit` to clean up metadata
             let has_conflicts = !self
@@ -1631,34 +1676,6 @@ impl GitService {
     }
 }
 
-/// parsea una URL de GitHub (HTTPS o SSH) para extraer owner y repo
-fn parse_github_url(url: &str) -> Result<(String, String), GitServiceError> {
-    // HTTPS: https://github.com/owner/repo.git o https://github.com/owner/repo
-    // SSH: git@github.com:owner/repo.git
-
-    let url = url.trim_end_matches(".git");
-
-    if let Some(path) = url
-        .strip_prefix("https://github.com/")
-        .or_else(|| url.strip_prefix("http://github.com/"))
-    {
-        let parts: Vec<&str> = path.split('/').collect();
-        if parts.len() >= 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    } else if let Some(path) = url.strip_prefix("git@github.com:") {
-        let parts: Vec<&str> = path.split('/').collect();
-        if parts.len() >= 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    }
-
-    Err(GitServiceError::InvalidRepository(format!(
-        "Failed to parse GitHub URL: {}",
-        url
-    )))
-}
-
 impl GitService {
     /// Extract GitHub owner and repo name from git repo path
     pub fn get_github_repo_info(
@@ -1690,10 +1707,10 @@ impl GitService {
             .url()
             .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
 
-        // parsear URL de GitHub (soporta HTTPS y SSH)
-        let (owner, repo_name) = parse_github_url(url)?;
-
-        Ok(GitHubRepoInfo { owner, repo_name })
+        // soporta HTTPS y SSH, en github.com y en los hosts Enterprise configurados
+        GitHubRepoInfo::from_remote_url(url).ok_or_else(|| {
+            GitServiceError::InvalidRepository(format!("Failed to parse GitHub URL: {url}"))
+        })
     }
 
     pub fn get_remote_name_from_branch_name(
@@ -1718,7 +1735,7 @@ impl GitService {
         repo_path: &Path,
         remote_name: &str,
     ) -> Result<String, GitServiceError> {
-        let cli = GitCli::new();
+        let cli = self.cli();
         cli.get_remote_url(repo_path, remote_name)
             .map_err(GitServiceError::GitCLI)
     }
@@ -1729,12 +1746,30 @@ impl GitService {
         remote_url: &str,
         branch_name: &str,
     ) -> Result<bool, GitServiceError> {
-        let git_cli = GitCli::new();
+        let git_cli = self.cli();
         git_cli
             .check_remote_branch_exists(repo_path, remote_url, branch_name)
             .map_err(GitServiceError::GitCLI)
     }
 
+    /// Delete `branch_name` from the remote it tracks, or the default remote;
+    /// returns false when the remote did not have it
+    pub fn delete_remote_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<bool, GitServiceError> {
+        let remote_name = self.resolve_remote_name_for_branch(repo_path, branch_name)?;
+        let remote_url = self.get_remote_url(repo_path, &remote_name)?;
+        if !self.check_remote_branch_exists(repo_path, &remote_url, branch_name)? {
+            return Ok(false);
+        }
+        self.cli()
+            .push_delete(repo_path, &remote_url, branch_name)
+            .map_err(GitServiceError::GitCLI)?;
+        Ok(true)
+    }
+
     pub fn resolve_remote_name_for_branch(
         &self,
         repo_path: &Path,
@@ -1793,7 +1828,7 @@ impl GitService {
                 "Worktree has uncommitted changes before push, auto-committing: {}",
                 e
             );
-            let git_cli = GitCli::new();
+            let git_cli = self.cli();
             git_cli.add_all(worktree_path)?;
             git_cli.commit(worktree_path, "Auto-commit uncommitted changes before push")?;
             tracing::info!("Auto-committed changes before push");
@@ -1837,7 +1872,7 @@ impl GitService {
             remote_url,
             branch_name
         );
-        let git_cli = GitCli::new();
+        let git_cli = self.cli();
         if let Err(e) = git_cli.push(worktree_path, remote_url, branch_name, force) {
             tracing::error!("Push to remote failed: {}", e);
             return Err(e.into());
@@ -1873,7 +1908,7 @@ impl GitService {
             .url()
             .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
 
-        let git_cli = GitCli::new();
+        let git_cli = self.cli();
         if let Err(e) = git_cli.fetch_with_refspec(repo.path(), remote_url, refspec) {
             tracing::error!("Fetch from GitHub failed: {}", e);
             return Err(e.into());
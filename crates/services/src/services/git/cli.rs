@@ -16,16 +16,27 @@
 //! `git` CLI, while keeping libgit2 for read‑only graph queries and credentialed
 //! network operations when useful.
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
-    io::Write as _,
-    path::Path,
-    process::{Command, Stdio},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{LazyLock, RwLock},
+    time::Duration,
 };
 
 use thiserror::Error;
-use utils::shell::resolve_executable_path_blocking; // TODO: make GitCli async
+use tokio_util::sync::CancellationToken;
+use utils::{assets::git_credentials_path, shell::resolve_executable_path_blocking}; // TODO: make GitCli async
 
-use crate::services::{filesystem_watcher::ALWAYS_SKIP_DIRS, git::Commit};
+use crate::services::{
+    bounded_command::{self, BoundedCommandError, OperationTimeouts},
+    filesystem_watcher::ALWAYS_SKIP_DIRS,
+    git::Commit,
+};
+
+/// SSH identities configured per repo, keyed by the canonical repo root
+static SSH_IDENTITIES: LazyLock<RwLock<HashMap<PathBuf, SshIdentity>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
 
 #[derive(Debug, Error)]
 pub enum GitCliError {
@@ -39,10 +50,28 @@ pub enum GitCliError {
     PushRejected(String),
     #[error("rebase in progress in this worktree")]
     RebaseInProgress,
+    #[error("git command timed out after {}s", .0.as_secs())]
+    Timeout(Duration),
+    #[error("git command cancelled")]
+    Cancelled,
 }
 
+impl From<BoundedCommandError> for GitCliError {
+    fn from(err: BoundedCommandError) -> Self {
+        match err {
+            BoundedCommandError::Timeout(limit) => GitCliError::Timeout(limit),
+            BoundedCommandError::Cancelled => GitCliError::Cancelled,
+            BoundedCommandError::Io(e) => GitCliError::CommandFailed(e.to_string()),
+        }
+    }
+}
+
+/// Every command is killed once it exceeds the configured
+/// [`OperationTimeouts`] or the optional cancellation token fires
 #[derive(Clone, Default)]
-pub struct GitCli;
+pub struct GitCli {
+    cancel: Option<CancellationToken>,
+}
 
 /// Parsed change type from `git diff --name-status` output
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -77,10 +106,52 @@ pub struct StatusDiffOptions {
     pub path_filter: Option<Vec<String>>, // pathspecs to limit diff
 }
 
+/// SSH identity used for a repo's network operations via `GIT_SSH_COMMAND`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SshIdentity {
+    /// Keys from an ssh-agent; `None` uses `SSH_AUTH_SOCK`
+    Agent { socket: Option<PathBuf> },
+    /// A single private key, offered to the server exclusively
+    KeyFile(PathBuf),
+}
+
+impl SshIdentity {
+    /// Value for `GIT_SSH_COMMAND`
+    pub fn ssh_command(&self) -> String {
+        match self {
+            SshIdentity::Agent { socket: None } => "ssh -o IdentityAgent=SSH_AUTH_SOCK".to_string(),
+            SshIdentity::Agent {
+                socket: Some(socket),
+            } => format!("ssh -o IdentityAgent={}", shell_quote(socket)),
+            SshIdentity::KeyFile(key) => {
+                format!("ssh -i {} -o IdentitiesOnly=yes", shell_quote(key))
+            }
+        }
+    }
+}
+
 impl GitCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Kill the running command and fail with `GitCliError::Cancelled` once
+    /// `cancel` fires
+    pub fn with_cancellation(cancel: Option<CancellationToken>) -> Self {
+        Self { cancel }
     }
+
+    /// Use `identity` for fetches and pushes of the repo at `repo_path`, or
+    /// fall back to the user's SSH setup when `None`
+    pub fn set_ssh_identity(repo_path: &Path, identity: Option<SshIdentity>) {
+        let key = dunce::canonicalize(repo_path).unwrap_or_else(|_| repo_path.to_path_buf());
+        let mut identities = SSH_IDENTITIES.write().unwrap_or_else(|e| e.into_inner());
+        match identity {
+            Some(identity) => identities.insert(key, identity),
+            None => identities.remove(&key),
+        };
+    }
+
     /// Run `git -C <repo> worktree add <path> <branch>` (optionally creating the branch with -b)
     pub fn worktree_add(
         &self,
@@ -107,6 +178,20 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <worktree> sparse-checkout set --cone <dirs>`, limiting the
+    /// worktree's checkout to `dirs` and the files at the root
+    pub fn sparse_checkout_set(
+        &self,
+        worktree_path: &Path,
+        dirs: &[&str],
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let mut args: Vec<OsString> = vec!["sparse-checkout".into(), "set".into(), "--cone".into()];
+        args.extend(dirs.iter().map(OsString::from));
+        self.git(worktree_path, args)?;
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -256,7 +341,13 @@ impl GitCli {
             "-z",
             "--untracked-files=normal",
         ]);
-        let out = self.git_impl(worktree_path, args, None, None)?;
+        let out = self.git_impl(
+            worktree_path,
+            args,
+            None,
+            None,
+            OperationTimeouts::current().local,
+        )?;
         let mut entries = Vec::new();
         let mut uncommitted_tracked = 0usize;
         let mut untracked = 0usize;
@@ -367,7 +458,7 @@ impl GitCli {
         remote_url: &str,
         refspec: &str,
     ) -> Result<(), GitCliError> {
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.network_envs(repo_path);
 
         let args = [
             OsString::from("fetch"),
@@ -375,7 +466,7 @@ impl GitCli {
             OsString::from(refspec),
         ];
 
-        match self.git_with_env(repo_path, args, &envs) {
+        match self.git_network(repo_path, args, &envs) {
             Ok(_) => Ok(()),
             Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
             Err(err) => Err(err),
@@ -395,7 +486,7 @@ impl GitCli {
         } else {
             format!("refs/heads/{branch}:refs/heads/{branch}")
         };
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.network_envs(repo_path);
 
         let args = [
             OsString::from("push"),
@@ -403,7 +494,29 @@ impl GitCli {
             OsString::from(refspec),
         ];
 
-        match self.git_with_env(repo_path, args, &envs) {
+        match self.git_network(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Delete a branch from the given remote using native git authentication.
+    pub fn push_delete(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = self.network_envs(repo_path);
+
+        let args = [
+            OsString::from("push"),
+            OsString::from(remote_url),
+            OsString::from(format!(":refs/heads/{branch}")),
+        ];
+
+        match self.git_network(repo_path, args, &envs) {
             Ok(_) => Ok(()),
             Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
             Err(err) => Err(err),
@@ -417,7 +530,7 @@ impl GitCli {
         remote_url: &str,
         branch_name: &str,
     ) -> Result<bool, GitCliError> {
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.network_envs(repo_path);
 
         let args = [
             OsString::from("ls-remote"),
@@ -426,13 +539,130 @@ impl GitCli {
             OsString::from(format!("refs/heads/{branch_name}")),
         ];
 
-        match self.git_with_env(repo_path, args, &envs) {
+        match self.git_network(repo_path, args, &envs) {
             Ok(output) => Ok(!output.trim().is_empty()),
             Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
             Err(err) => Err(err),
         }
     }
 
+    /// Check that the remote is reachable with the current git credentials,
+    /// without prompting (HTTPS credential helpers or non-interactive SSH).
+    pub fn check_remote_access(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<(), GitCliError> {
+        let mut envs = self.network_envs(repo_path);
+        let ssh = self
+            .ssh_identity(repo_path)
+            .map(|identity| identity.ssh_command())
+            .unwrap_or_else(|| "ssh".to_string());
+        envs.push((
+            OsString::from("GIT_SSH_COMMAND"),
+            OsString::from(format!("{ssh} -o BatchMode=yes -o ConnectTimeout=10")),
+        ));
+
+        let args = [
+            OsString::from("ls-remote"),
+            OsString::from("--exit-code"),
+            OsString::from(remote_url),
+            OsString::from("HEAD"),
+        ];
+
+        match self.git_network(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Credential helper git uses for `https://<host>` in this repo, if any
+    pub fn credential_helper(&self, repo_path: &Path, host: &str) -> Option<String> {
+        let url = format!("https://{host}");
+        self.git(
+            repo_path,
+            [
+                "config",
+                "--get-urlmatch",
+                "credential.helper",
+                url.as_str(),
+            ],
+        )
+        .ok()
+        .map(|helper| helper.trim().to_string())
+        .filter(|helper| !helper.is_empty())
+    }
+
+    /// Whether a credential helper supplies credentials for `https://<host>`
+    /// without prompting. The credentials themselves are discarded.
+    pub fn has_https_credentials(&self, repo_path: &Path, host: &str) -> bool {
+        let mut envs = self.network_envs(repo_path);
+        envs.push((OsString::from("GCM_INTERACTIVE"), OsString::from("never")));
+        let input = format!("protocol=https\nhost={host}\n\n");
+        match self.git_with_stdin(
+            repo_path,
+            ["credential", "fill"],
+            Some(&envs),
+            input.as_bytes(),
+        ) {
+            Ok(output) => output.lines().any(|line| line.starts_with("password=")),
+            Err(_) => false,
+        }
+    }
+
+    /// Hand a token for `https://<host>` to the credential helper configured
+    /// for it, or to the app's own credential store when there is none
+    pub fn store_https_credentials(
+        &self,
+        repo_path: &Path,
+        host: &str,
+        username: &str,
+        token: &str,
+    ) -> Result<(), GitCliError> {
+        let mut envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        if self.credential_helper(repo_path, host).is_none() {
+            let store = git_credentials_path();
+            create_private_file(&store)
+                .map_err(|e| GitCliError::CommandFailed(format!("{}: {e}", store.display())))?;
+            envs.extend(Self::credential_store_envs(&store));
+        }
+        let input =
+            format!("protocol=https\nhost={host}\nusername={username}\npassword={token}\n\n");
+        self.git_with_stdin(
+            repo_path,
+            ["credential", "approve"],
+            Some(&envs),
+            input.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Tracked text files whose contents mention `needle` (case-insensitive)
+    pub fn grep_files(&self, repo_path: &Path, needle: &str) -> Result<Vec<String>, GitCliError> {
+        let args = [
+            "grep",
+            "--files-with-matches",
+            "-I",
+            "--ignore-case",
+            "--fixed-strings",
+            "-e",
+            needle,
+        ];
+        match self.git(repo_path, args) {
+            Ok(output) => Ok(output
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()),
+            // git grep exits with 1 and no output when nothing matches
+            Err(GitCliError::CommandFailed(msg)) if msg == "Command failed with no output" => {
+                Ok(Vec::new())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn get_remote_url(
         &self,
         repo_path: &Path,
@@ -708,6 +938,66 @@ impl GitCli {
 
 // Private methods
 impl GitCli {
+    /// Environment for commands that talk to remotes: never prompt, fall
+    /// back to tokens stored through the app when no other helper has one,
+    /// and use the SSH identity configured for the repo
+    fn network_envs(&self, repo_path: &Path) -> Vec<(OsString, OsString)> {
+        let mut envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let store = git_credentials_path();
+        if store.exists() {
+            envs.extend(Self::credential_store_envs(&store));
+        }
+        if let Some(identity) = self.ssh_identity(repo_path) {
+            envs.push((
+                OsString::from("GIT_SSH_COMMAND"),
+                OsString::from(identity.ssh_command()),
+            ));
+        }
+        envs
+    }
+
+    /// SSH identity registered for the repo owning `path`, which may be the
+    /// repo itself, one of its worktrees or its git directory
+    fn ssh_identity(&self, path: &Path) -> Option<SshIdentity> {
+        if SSH_IDENTITIES
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+        {
+            return None;
+        }
+        let common_dir = self
+            .git(
+                path,
+                ["rev-parse", "--path-format=absolute", "--git-common-dir"],
+            )
+            .ok()?;
+        let common_dir = dunce::canonicalize(common_dir.trim()).ok()?;
+        let identities = SSH_IDENTITIES.read().unwrap_or_else(|e| e.into_inner());
+        // Non-bare repos keep their git directory at `<root>/.git`
+        identities
+            .get(&common_dir)
+            .or_else(|| common_dir.parent().and_then(|root| identities.get(root)))
+            .cloned()
+    }
+
+    /// Add git's `store` helper reading `store` after any configured helpers
+    fn credential_store_envs(store: &Path) -> [(OsString, OsString); 3] {
+        // Helpers run through the shell, and data directories can contain spaces
+        let quoted = shell_quote(store);
+        [
+            (OsString::from("GIT_CONFIG_COUNT"), OsString::from("1")),
+            (
+                OsString::from("GIT_CONFIG_KEY_0"),
+                OsString::from("credential.helper"),
+            ),
+            (
+                OsString::from("GIT_CONFIG_VALUE_0"),
+                OsString::from(format!("store --file={quoted}")),
+            ),
+        ]
+    }
+
     fn classify_cli_error(&self, msg: String) -> GitCliError {
         let lower = msg.to_ascii_lowercase();
         if lower.contains("authentication failed")
@@ -759,6 +1049,7 @@ impl GitCli {
         args: I,
         envs: Option<&[(OsString, OsString)]>,
         stdin: Option<&[u8]>,
+        timeout: Duration,
     ) -> Result<Vec<u8>, GitCliError>
     where
         I: IntoIterator<Item = S>,
@@ -779,37 +1070,22 @@ impl GitCli {
             cmd.arg(a);
         }
 
-        if stdin.is_some() {
-            cmd.stdin(Stdio::piped());
-        } else {
-            cmd.stdin(Stdio::null());
-        }
-
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-
+        // stdin can carry credentials, so only its size is logged
         tracing::trace!(
-            stdin = ?stdin.as_ref().map(|s| String::from_utf8_lossy(s)),
+            stdin_bytes = ?stdin.map(<[u8]>::len),
             repo = ?repo_path,
             "Running git command: {:?}",
             cmd
         );
 
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
-
-        let stdin_write_result = if let Some(input) = stdin
-            && let Some(mut child_stdin) = child.stdin.take()
-        {
-            Some(child_stdin.write_all(input))
-        } else {
-            None
-        };
-
-        let out = child
-            .wait_with_output()
-            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        let out = bounded_command::run(&mut cmd, stdin, timeout, self.cancel.as_ref()).map_err(
+            |err| {
+                if matches!(err, BoundedCommandError::Timeout(_)) {
+                    tracing::warn!(repo = ?repo_path, "git command timed out: {:?}", cmd);
+                }
+                GitCliError::from(err)
+            },
+        )?;
 
         if !out.status.success() {
             let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
@@ -822,11 +1098,6 @@ impl GitCli {
             };
             return Err(GitCliError::CommandFailed(combined));
         }
-        if let Some(Err(e)) = stdin_write_result {
-            return Err(GitCliError::CommandFailed(format!(
-                "failed to write to git stdin: {e}"
-            )));
-        }
         Ok(out.stdout)
     }
 
@@ -835,7 +1106,13 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, None, None)?;
+        let out = self.git_impl(
+            repo_path,
+            args,
+            None,
+            None,
+            OperationTimeouts::current().local,
+        )?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -849,7 +1126,35 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, Some(envs), None)?;
+        let out = self.git_impl(
+            repo_path,
+            args,
+            Some(envs),
+            None,
+            OperationTimeouts::current().local,
+        )?;
+        Ok(String::from_utf8_lossy(&out).to_string())
+    }
+
+    /// Like `git_with_env`, for commands that talk to a remote and get the
+    /// network timeout
+    fn git_network<I, S>(
+        &self,
+        repo_path: &Path,
+        args: I,
+        envs: &[(OsString, OsString)],
+    ) -> Result<String, GitCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let out = self.git_impl(
+            repo_path,
+            args,
+            Some(envs),
+            None,
+            OperationTimeouts::current().network,
+        )?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -864,7 +1169,13 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, envs, Some(stdin))?;
+        let out = self.git_impl(
+            repo_path,
+            args,
+            envs,
+            Some(stdin),
+            OperationTimeouts::current().local,
+        )?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -936,3 +1247,20 @@ pub struct WorktreeStatus {
     pub untracked: usize,
     pub entries: Vec<StatusEntry>,
 }
+
+/// Create `path` readable only by the current user, keeping existing contents
+/// Single-quote `path` for commands git runs through the shell
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+fn create_private_file(path: &Path) -> std::io::Result<()> {
+    let mut opts = std::fs::OpenOptions::new();
+    opts.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    opts.open(path).map(|_| ())
+}
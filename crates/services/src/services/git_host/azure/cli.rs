@@ -5,6 +5,7 @@
 
 use std::{
     ffi::{OsStr, OsString},
+    io::Write,
     path::Path,
     process::Command,
 };
@@ -12,10 +13,11 @@ use std::{
 use chrono::{DateTime, Utc};
 use db::models::merge::{MergeStatus, PullRequestInfo};
 use serde::Deserialize;
+use tempfile::NamedTempFile;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{CreatePrRequest, UnifiedPrComment};
+use crate::services::git_host::types::{CreatePrRequest, OpenPrInfo, UnifiedPrComment};
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -30,6 +32,10 @@ pub struct AzureRepoInfo {
 #[serde(rename_all = "camelCase")]
 struct AzPrResponse {
     pull_request_id: i64,
+    #[serde(default)]
+    title: String,
+    source_ref_name: Option<String>,
+    target_ref_name: Option<String>,
     status: Option<String>,
     closed_date: Option<String>,
     repository: Option<AzRepository>,
@@ -364,6 +370,34 @@ impl AzCli {
         Self::parse_pr_list_response(&raw)
     }
 
+    pub fn list_open_prs(
+        &self,
+        organization_url: &str,
+        project: &str,
+        repo_name: &str,
+    ) -> Result<Vec<OpenPrInfo>, AzCliError> {
+        let raw = self.run(
+            [
+                "repos",
+                "pr",
+                "list",
+                "--organization",
+                organization_url,
+                "--project",
+                project,
+                "--repository",
+                repo_name,
+                "--status",
+                "active",
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+
+        Self::parse_open_pr_list_response(&raw)
+    }
+
     pub fn get_pr_threads(
         &self,
         organization_url: &str,
@@ -393,6 +427,51 @@ impl AzCli {
         Self::parse_pr_threads(&raw)
     }
 
+    /// Open a new comment thread on a pull request via `az devops invoke`.
+    pub fn add_pr_comment(
+        &self,
+        organization_url: &str,
+        project_id: &str,
+        repo_id: &str,
+        pr_id: i64,
+        body: &str,
+    ) -> Result<(), AzCliError> {
+        let thread = serde_json::json!({
+            "comments": [{ "parentCommentId": 0, "content": body, "commentType": 1 }],
+            "status": 1,
+        });
+        let mut in_file = NamedTempFile::new()
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to create temp file: {e}")))?;
+        in_file
+            .write_all(thread.to_string().as_bytes())
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to write body: {e}")))?;
+
+        let mut args: Vec<OsString> = Vec::with_capacity(20);
+        args.push(OsString::from("devops"));
+        args.push(OsString::from("invoke"));
+        args.push(OsString::from("--area"));
+        args.push(OsString::from("git"));
+        args.push(OsString::from("--resource"));
+        args.push(OsString::from("pullRequestThreads"));
+        args.push(OsString::from("--route-parameters"));
+        args.push(OsString::from(format!("project={}", project_id)));
+        args.push(OsString::from(format!("repositoryId={}", repo_id)));
+        args.push(OsString::from(format!("pullRequestId={}", pr_id)));
+        args.push(OsString::from("--http-method"));
+        args.push(OsString::from("POST"));
+        args.push(OsString::from("--in-file"));
+        args.push(in_file.path().as_os_str().to_os_string());
+        args.push(OsString::from("--organization"));
+        args.push(OsString::from(organization_url));
+        args.push(OsString::from("--api-version"));
+        args.push(OsString::from("7.0"));
+        args.push(OsString::from("--output"));
+        args.push(OsString::from("json"));
+
+        self.run(args, None)?;
+        Ok(())
+    }
+
     /// Parse PR URL to extract organization and PR ID.
     ///
     /// Only extracts the minimal info needed for `az repos pr show`.
@@ -450,6 +529,28 @@ impl AzCli {
         Ok(prs.into_iter().map(Self::az_pr_to_info).collect())
     }
 
+    fn parse_open_pr_list_response(raw: &str) -> Result<Vec<OpenPrInfo>, AzCliError> {
+        let prs: Vec<AzPrResponse> = serde_json::from_str(raw.trim()).map_err(|e| {
+            AzCliError::UnexpectedOutput(format!("Failed to parse PR list: {e}; raw: {raw}"))
+        })?;
+        Ok(prs
+            .into_iter()
+            .map(|pr| {
+                let branch = |name: &Option<String>| {
+                    let name = name.as_deref().unwrap_or_default();
+                    name.strip_prefix("refs/heads/").unwrap_or(name).to_string()
+                };
+                OpenPrInfo {
+                    head_branch: branch(&pr.source_ref_name),
+                    base_branch: branch(&pr.target_ref_name),
+                    title: pr.title.clone(),
+                    number: pr.pull_request_id,
+                    url: Self::az_pr_to_info(pr).url,
+                }
+            })
+            .collect())
+    }
+
     /// Convert Azure PR response to PullRequestInfo.
     fn az_pr_to_info(pr: AzPrResponse) -> PullRequestInfo {
         let url = pr
@@ -589,6 +690,28 @@ mod tests {
         assert!(AzCli::parse_pr_url("https://dev.azure.com/myorg/myproject/_git/myrepo").is_none());
     }
 
+    #[test]
+    fn test_parse_open_pr_list() {
+        let raw = r#"[{
+            "pullRequestId": 12,
+            "title": "Add widgets",
+            "sourceRefName": "refs/heads/vk/widgets",
+            "targetRefName": "refs/heads/main",
+            "status": "active",
+            "repository": { "webUrl": "https://dev.azure.com/myorg/myproject/_git/myrepo" }
+        }]"#;
+        let prs = AzCli::parse_open_pr_list_response(raw).unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].number, 12);
+        assert_eq!(prs[0].title, "Add widgets");
+        assert_eq!(prs[0].head_branch, "vk/widgets");
+        assert_eq!(prs[0].base_branch, "main");
+        assert_eq!(
+            prs[0].url,
+            "https://dev.azure.com/myorg/myproject/_git/myrepo/pullrequest/12"
+        );
+    }
+
     #[test]
     fn test_map_azure_status() {
         assert!(matches!(
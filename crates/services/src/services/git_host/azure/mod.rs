@@ -248,11 +248,72 @@ impl GitHostProvider for AzureDevOpsProvider {
 
     async fn list_open_prs(
         &self,
-        _repo_path: &Path,
-        _remote_url: &str,
+        repo_path: &Path,
+        remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError> {
-        // TODO: Implement list_open_prs for Azure DevOps
-        Err(GitHostError::UnsupportedProvider)
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+
+        (|| async {
+            let cli = self.az_cli.clone();
+            let organization_url = repo_info.organization_url.clone();
+            let project = repo_info.project.clone();
+            let repo_name = repo_info.repo_name.clone();
+
+            let prs = task::spawn_blocking(move || {
+                cli.list_open_prs(&organization_url, &project, &repo_name)
+            })
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute Azure CLI for listing open PRs: {err}"
+                ))
+            })?;
+            prs.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn add_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || {
+            cli.add_pr_comment(
+                &repo_info.organization_url,
+                &repo_info.project_id,
+                &repo_info.repo_id,
+                pr_number,
+                &body,
+            )
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for commenting on PR: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
     }
 
     fn provider_kind(&self) -> ProviderKind {
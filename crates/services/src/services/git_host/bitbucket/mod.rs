@@ -0,0 +1,257 @@
+//! Bitbucket Cloud hosting service implementation.
+
+mod rest;
+
+use std::{
+    future::Future,
+    path::Path,
+    sync::{LazyLock, RwLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::PullRequestInfo;
+pub use rest::{BitbucketCredentials, BitbucketRestClient};
+use secrecy::SecretString;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+};
+use crate::services::config::Config;
+
+/// Credentials from the user config
+static CONFIG_CREDENTIALS: LazyLock<RwLock<Option<BitbucketCredentials>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// Workspace and repository slug of a Bitbucket Cloud repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitbucketRepoInfo {
+    pub workspace: String,
+    pub repo_slug: String,
+}
+
+impl BitbucketRepoInfo {
+    /// Parse `https://[user@]bitbucket.org/{workspace}/{repo}[.git]`,
+    /// `git@bitbucket.org:{workspace}/{repo}.git` or the `ssh://` form
+    pub fn from_remote_url(remote_url: &str) -> Option<Self> {
+        let (_, path) = remote_url.trim().split_once("bitbucket.org")?;
+        let path = path.strip_prefix(':').or_else(|| path.strip_prefix('/'))?;
+        let mut parts = path.split('/');
+        let workspace = parts.next().filter(|s| !s.is_empty())?;
+        let repo_slug = parts.next()?.trim_end_matches(".git");
+        if repo_slug.is_empty() {
+            return None;
+        }
+        Some(Self {
+            workspace: workspace.to_string(),
+            repo_slug: repo_slug.to_string(),
+        })
+    }
+}
+
+impl BitbucketCredentials {
+    /// Credentials configured in the settings; an access token wins over an
+    /// app password
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        if let Some(token) = non_empty(&config.bitbucket_access_token) {
+            return Some(Self::AccessToken(SecretString::from(token)));
+        }
+        Some(Self::AppPassword {
+            username: non_empty(&config.bitbucket_username)?,
+            password: SecretString::from(non_empty(&config.bitbucket_app_password)?),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BitbucketProvider {
+    client: Option<BitbucketRestClient>,
+}
+
+impl BitbucketProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            client: Self::config_credentials().map(BitbucketRestClient::new),
+        })
+    }
+
+    /// Set the credentials used for API calls
+    pub fn set_config_credentials(credentials: Option<BitbucketCredentials>) {
+        *CONFIG_CREDENTIALS
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = credentials;
+    }
+
+    fn config_credentials() -> Option<BitbucketCredentials> {
+        CONFIG_CREDENTIALS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn client(&self) -> Result<&BitbucketRestClient, GitHostError> {
+        self.client.as_ref().ok_or_else(|| {
+            GitHostError::AuthFailed(
+                "Bitbucket credentials are not configured; add an app password or access token in the settings"
+                    .to_string(),
+            )
+        })
+    }
+
+    fn parse_repo_info(remote_url: &str) -> Result<BitbucketRepoInfo, GitHostError> {
+        BitbucketRepoInfo::from_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!("Not a Bitbucket repository URL: {remote_url}"))
+        })
+    }
+
+    async fn with_retry<T, F, Fut>(operation: F) -> Result<T, GitHostError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GitHostError>>,
+    {
+        operation
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Bitbucket API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for BitbucketProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let client = self.client()?;
+        let repo_info = Self::parse_repo_info(remote_url)?;
+        let head_repo_info = request
+            .head_repo_url
+            .as_deref()
+            .map(Self::parse_repo_info)
+            .transpose()?;
+
+        let pr = Self::with_retry(|| async {
+            client
+                .create_pr(request, &repo_info, head_repo_info.as_ref())
+                .await
+        })
+        .await?;
+
+        info!(
+            "Created Bitbucket PR #{} for branch {}",
+            pr.number, request.head_branch
+        );
+        Ok(pr)
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let client = self.client()?;
+        Self::with_retry(|| async { client.view_pr(pr_url).await }).await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let client = self.client()?;
+        let repo_info = Self::parse_repo_info(remote_url)?;
+        Self::with_retry(|| async { client.list_prs_for_branch(&repo_info, branch_name).await })
+            .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let client = self.client()?;
+        let repo_info = Self::parse_repo_info(remote_url)?;
+        let mut comments =
+            Self::with_retry(|| async { client.get_pr_comments(&repo_info, pr_number).await })
+                .await?;
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let client = self.client()?;
+        let repo_info = Self::parse_repo_info(remote_url)?;
+        Self::with_retry(|| async { client.list_open_prs(&repo_info).await }).await
+    }
+
+    async fn add_pr_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let client = self.client()?;
+        let repo_info = Self::parse_repo_info(remote_url)?;
+        // Not retried, a comment that went through but timed out would be
+        // posted twice
+        client.add_pr_comment(&repo_info, pr_number, body).await
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Bitbucket
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_remote_urls() {
+        let expected = Some(BitbucketRepoInfo {
+            workspace: "acme".to_string(),
+            repo_slug: "widgets".to_string(),
+        });
+        for url in [
+            "https://bitbucket.org/acme/widgets",
+            "https://jane@bitbucket.org/acme/widgets.git",
+            "git@bitbucket.org:acme/widgets.git",
+            "ssh://git@bitbucket.org/acme/widgets.git",
+        ] {
+            assert_eq!(BitbucketRepoInfo::from_remote_url(url), expected, "{url}");
+        }
+        assert_eq!(
+            BitbucketRepoInfo::from_remote_url("https://github.com/acme/widgets"),
+            None
+        );
+    }
+}
@@ -0,0 +1,502 @@
+//! Bitbucket Cloud REST API (2.0) access.
+//!
+//! Bitbucket has no CLI comparable to `gh` or `az`, so every operation goes
+//! through the API, authenticated with an app password or an access token.
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::BitbucketRepoInfo;
+use crate::services::git_host::types::{
+    CreatePrRequest, GitHostError, OpenPrInfo, UnifiedPrComment,
+};
+
+const BITBUCKET_API_BASE: &str = "https://api.bitbucket.org/2.0";
+const USER_AGENT: &str = "VibeKanban/1.0";
+const PAGE_SIZE: &str = "50";
+
+/// How requests to Bitbucket are authenticated
+#[derive(Clone)]
+pub enum BitbucketCredentials {
+    /// Username with an app password, sent as basic auth
+    AppPassword {
+        username: String,
+        password: SecretString,
+    },
+    /// Workspace, project or repository access token, sent as a bearer token
+    AccessToken(SecretString),
+}
+
+impl std::fmt::Debug for BitbucketCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AppPassword { username, .. } => f
+                .debug_struct("AppPassword")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            Self::AccessToken(_) => f.debug_tuple("AccessToken").finish_non_exhaustive(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    values: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestLink {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct RestLinks {
+    html: Option<RestLink>,
+}
+
+#[derive(Deserialize)]
+struct RestBranch {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct RestEndpoint {
+    branch: RestBranch,
+}
+
+#[derive(Deserialize)]
+struct RestCommit {
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct RestPullRequest {
+    id: i64,
+    #[serde(default)]
+    title: String,
+    state: String,
+    links: RestLinks,
+    source: RestEndpoint,
+    destination: RestEndpoint,
+    merge_commit: Option<RestCommit>,
+    updated_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct RestUser {
+    display_name: Option<String>,
+    nickname: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestContent {
+    #[serde(default)]
+    raw: String,
+}
+
+#[derive(Deserialize)]
+struct RestInline {
+    path: String,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct RestComment {
+    id: i64,
+    user: Option<RestUser>,
+    content: RestContent,
+    created_on: DateTime<Utc>,
+    links: Option<RestLinks>,
+    inline: Option<RestInline>,
+    #[serde(default)]
+    deleted: bool,
+}
+
+#[derive(Serialize)]
+struct BranchRef<'a> {
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+struct RepositoryRef {
+    full_name: String,
+}
+
+#[derive(Serialize)]
+struct SourceRef<'a> {
+    branch: BranchRef<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<RepositoryRef>,
+}
+
+#[derive(Serialize)]
+struct DestinationRef<'a> {
+    branch: BranchRef<'a>,
+}
+
+#[derive(Serialize)]
+struct CreatePullRequestBody<'a> {
+    title: &'a str,
+    description: &'a str,
+    source: SourceRef<'a>,
+    destination: DestinationRef<'a>,
+    draft: bool,
+}
+
+#[derive(Clone)]
+pub struct BitbucketRestClient {
+    client: Client,
+    credentials: BitbucketCredentials,
+}
+
+impl std::fmt::Debug for BitbucketRestClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitbucketRestClient")
+            .finish_non_exhaustive()
+    }
+}
+
+impl BitbucketRestClient {
+    pub fn new(credentials: BitbucketCredentials) -> Self {
+        Self {
+            client: Client::new(),
+            credentials,
+        }
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        let builder = match &self.credentials {
+            BitbucketCredentials::AppPassword { username, password } => {
+                builder.basic_auth(username, Some(password.expose_secret()))
+            }
+            BitbucketCredentials::AccessToken(token) => builder.bearer_auth(token.expose_secret()),
+        };
+        builder
+            .header("Accept", "application/json")
+            .header("User-Agent", USER_AGENT)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T, GitHostError> {
+        let response =
+            self.request(builder).send().await.map_err(|e| {
+                GitHostError::PullRequest(format!("Bitbucket API request failed: {e}"))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(Self::status_error(status, message));
+        }
+
+        response.json::<T>().await.map_err(|e| {
+            GitHostError::UnexpectedOutput(format!("Invalid Bitbucket API response: {e}"))
+        })
+    }
+
+    fn status_error(status: StatusCode, message: String) -> GitHostError {
+        match status {
+            StatusCode::UNAUTHORIZED => GitHostError::AuthFailed(message),
+            StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(message),
+            StatusCode::NOT_FOUND => GitHostError::RepoNotFoundOrNoAccess(message),
+            _ => GitHostError::PullRequest(format!("Bitbucket API error {status}: {message}")),
+        }
+    }
+
+    /// Follow the `next` links of a paged listing
+    async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, GitHostError> {
+        let mut items = Vec::new();
+        let mut page: Page<T> = self
+            .send(
+                self.client
+                    .get(url)
+                    .query(query)
+                    .query(&[("pagelen", PAGE_SIZE)]),
+            )
+            .await?;
+        loop {
+            items.extend(page.values);
+            let Some(next) = page.next else {
+                return Ok(items);
+            };
+            // The next link already carries the query
+            page = self.send(self.client.get(next)).await?;
+        }
+    }
+
+    fn pull_requests_url(repo: &BitbucketRepoInfo) -> String {
+        format!(
+            "{BITBUCKET_API_BASE}/repositories/{}/{}/pullrequests",
+            repo.workspace, repo.repo_slug
+        )
+    }
+
+    pub async fn create_pr(
+        &self,
+        request: &CreatePrRequest,
+        repo: &BitbucketRepoInfo,
+        head_repo: Option<&BitbucketRepoInfo>,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let pr: RestPullRequest =
+            self.send(self.client.post(Self::pull_requests_url(repo)).json(
+                &CreatePullRequestBody {
+                    title: &request.title,
+                    description: request.body.as_deref().unwrap_or(""),
+                    source: SourceRef {
+                        branch: BranchRef {
+                            name: &request.head_branch,
+                        },
+                        repository: head_repo.filter(|head| *head != repo).map(|head| {
+                            RepositoryRef {
+                                full_name: format!("{}/{}", head.workspace, head.repo_slug),
+                            }
+                        }),
+                    },
+                    destination: DestinationRef {
+                        branch: BranchRef {
+                            name: &request.base_branch,
+                        },
+                    },
+                    draft: request.draft.unwrap_or(false),
+                },
+            ))
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    pub async fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (repo, number) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            GitHostError::PullRequest(format!("Not a Bitbucket pull request URL: {pr_url}"))
+        })?;
+        let pr: RestPullRequest = self
+            .send(
+                self.client
+                    .get(format!("{}/{number}", Self::pull_requests_url(&repo))),
+            )
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    pub async fn list_prs_for_branch(
+        &self,
+        repo: &BitbucketRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let filter = format!(
+            "source.branch.name=\"{}\"",
+            branch.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        // Without an explicit state only open pull requests are listed
+        let prs: Vec<RestPullRequest> = self
+            .get_paginated(
+                &Self::pull_requests_url(repo),
+                &[
+                    ("q", filter.as_str()),
+                    ("state", "OPEN"),
+                    ("state", "MERGED"),
+                    ("state", "DECLINED"),
+                    ("state", "SUPERSEDED"),
+                ],
+            )
+            .await?;
+        Ok(prs.into_iter().map(Self::pr_to_info).collect())
+    }
+
+    pub async fn list_open_prs(
+        &self,
+        repo: &BitbucketRepoInfo,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let prs: Vec<RestPullRequest> = self
+            .get_paginated(&Self::pull_requests_url(repo), &[("state", "OPEN")])
+            .await?;
+        Ok(prs
+            .into_iter()
+            .map(|pr| OpenPrInfo {
+                number: pr.id,
+                url: pr.links.html.map(|link| link.href).unwrap_or_default(),
+                title: pr.title,
+                head_branch: pr.source.branch.name,
+                base_branch: pr.destination.branch.name,
+            })
+            .collect())
+    }
+
+    pub async fn add_pr_comment(
+        &self,
+        repo: &BitbucketRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let _: serde_json::Value = self
+            .send(
+                self.client
+                    .post(format!(
+                        "{}/{pr_number}/comments",
+                        Self::pull_requests_url(repo)
+                    ))
+                    .json(&serde_json::json!({ "content": { "raw": body } })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// General and inline comments, with inline ones reported as review
+    /// comments on their file and line
+    pub async fn get_pr_comments(
+        &self,
+        repo: &BitbucketRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let comments: Vec<RestComment> = self
+            .get_paginated(
+                &format!("{}/{pr_number}/comments", Self::pull_requests_url(repo)),
+                &[],
+            )
+            .await?;
+        Ok(comments
+            .into_iter()
+            .filter(|c| !c.deleted)
+            .map(Self::comment_to_unified)
+            .collect())
+    }
+
+    fn comment_to_unified(c: RestComment) -> UnifiedPrComment {
+        let author = c
+            .user
+            .and_then(|u| u.nickname.or(u.display_name))
+            .unwrap_or_else(|| "unknown".to_string());
+        let url = c.links.and_then(|links| links.html).map(|link| link.href);
+        match c.inline {
+            Some(inline) => {
+                // `to` is the line in the new file, `from` in the old one
+                let (line, side) = match (inline.to, inline.from) {
+                    (Some(to), _) => (Some(to), Some("RIGHT".to_string())),
+                    (None, Some(from)) => (Some(from), Some("LEFT".to_string())),
+                    (None, None) => (None, None),
+                };
+                UnifiedPrComment::Review {
+                    id: c.id,
+                    author,
+                    author_association: None,
+                    body: c.content.raw,
+                    created_at: c.created_on,
+                    url,
+                    path: inline.path,
+                    line,
+                    side,
+                    diff_hunk: None,
+                }
+            }
+            None => UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author,
+                author_association: None,
+                body: c.content.raw,
+                created_at: c.created_on,
+                url,
+            },
+        }
+    }
+
+    fn pr_to_info(pr: RestPullRequest) -> PullRequestInfo {
+        let status = match pr.state.as_str() {
+            "OPEN" => MergeStatus::Open,
+            "MERGED" => MergeStatus::Merged,
+            "DECLINED" | "SUPERSEDED" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        let merged = matches!(status, MergeStatus::Merged);
+        PullRequestInfo {
+            number: pr.id,
+            url: pr.links.html.map(|link| link.href).unwrap_or_default(),
+            status,
+            // Bitbucket has no merge timestamp; a merged PR is not updated
+            // again, so its last update is when it was merged
+            merged_at: pr.updated_on.filter(|_| merged),
+            merge_commit_sha: pr.merge_commit.map(|c| c.hash).filter(|_| merged),
+        }
+    }
+
+    /// Split `https://bitbucket.org/{workspace}/{repo}/pull-requests/{id}`
+    /// into its parts
+    fn parse_pr_url(pr_url: &str) -> Option<(BitbucketRepoInfo, i64)> {
+        let path = pr_url
+            .trim_end_matches('/')
+            .split("bitbucket.org/")
+            .nth(1)?;
+        let mut parts = path.split('/');
+        let workspace = parts.next()?.to_string();
+        let repo_slug = parts.next()?.to_string();
+        if parts.next()? != "pull-requests" {
+            return None;
+        }
+        let number = parts.next()?.parse().ok()?;
+        Some((
+            BitbucketRepoInfo {
+                workspace,
+                repo_slug,
+            },
+            number,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pr_urls() {
+        assert_eq!(
+            BitbucketRestClient::parse_pr_url(
+                "https://bitbucket.org/acme/widgets/pull-requests/42/overview"
+            ),
+            Some((
+                BitbucketRepoInfo {
+                    workspace: "acme".to_string(),
+                    repo_slug: "widgets".to_string(),
+                },
+                42
+            ))
+        );
+        assert_eq!(
+            BitbucketRestClient::parse_pr_url("https://bitbucket.org/acme/widgets/src/main"),
+            None
+        );
+    }
+
+    #[test]
+    fn maps_pull_request_states() {
+        let pr = |state: &str| -> RestPullRequest {
+            serde_json::from_value(serde_json::json!({
+                "id": 7,
+                "title": "Add widgets",
+                "state": state,
+                "links": { "html": { "href": "https://bitbucket.org/acme/widgets/pull-requests/7" } },
+                "source": { "branch": { "name": "vk/widgets" } },
+                "destination": { "branch": { "name": "main" } },
+                "merge_commit": { "hash": "abc123" },
+                "updated_on": "2026-01-02T03:04:05Z"
+            }))
+            .unwrap()
+        };
+
+        let merged = BitbucketRestClient::pr_to_info(pr("MERGED"));
+        assert!(matches!(merged.status, MergeStatus::Merged));
+        assert_eq!(merged.merge_commit_sha.as_deref(), Some("abc123"));
+        assert!(merged.merged_at.is_some());
+
+        let declined = BitbucketRestClient::pr_to_info(pr("DECLINED"));
+        assert!(matches!(declined.status, MergeStatus::Closed));
+        assert!(declined.merge_commit_sha.is_none());
+        assert!(declined.merged_at.is_none());
+    }
+}
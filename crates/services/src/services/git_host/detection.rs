@@ -1,17 +1,19 @@
 //! Git hosting provider detection from repository URLs.
 
-use super::types::ProviderKind;
+use super::{github::GitHubProvider, types::ProviderKind};
 
 /// Detect the git hosting provider from a remote URL.
 ///
 /// Supports:
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
-/// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
+/// - GitHub Enterprise: configured enterprise hosts, or URLs containing `github.`
+///   (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - Bitbucket Cloud: `https://bitbucket.org/workspace/repo` or `git@bitbucket.org:workspace/repo.git`
 pub fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
-    if url_lower.contains("github.com") {
+    if url_lower.contains("github.com") || GitHubProvider::is_enterprise_url(url) {
         return ProviderKind::GitHub;
     }
 
@@ -28,6 +30,10 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::AzureDevOps;
     }
 
+    if url_lower.contains("bitbucket.org") {
+        return ProviderKind::Bitbucket;
+    }
+
     // GitHub Enterprise (contains "github." but not the Azure patterns above)
     if url_lower.contains("github.") {
         return ProviderKind::GitHub;
@@ -42,6 +48,7 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
 /// - GitHub: `https://github.com/owner/repo/pull/123`
 /// - GitHub Enterprise: `https://github.company.com/owner/repo/pull/123`
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo/pullrequest/123`
+/// - Bitbucket Cloud: `https://bitbucket.org/workspace/repo/pull-requests/123`
 #[cfg(test)]
 fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     let url_lower = pr_url.to_lowercase();
@@ -143,11 +150,27 @@ mod tests {
             ProviderKind::Unknown
         );
         assert_eq!(
-            detect_provider_from_url("https://bitbucket.org/owner/repo"),
+            detect_provider_from_url("https://bitbucket.example.com/owner/repo"),
             ProviderKind::Unknown
         );
     }
 
+    #[test]
+    fn test_bitbucket_cloud() {
+        assert_eq!(
+            detect_provider_from_url("https://bitbucket.org/owner/repo"),
+            ProviderKind::Bitbucket
+        );
+        assert_eq!(
+            detect_provider_from_url("git@bitbucket.org:owner/repo.git"),
+            ProviderKind::Bitbucket
+        );
+        assert_eq!(
+            detect_provider_from_pr_url("https://bitbucket.org/owner/repo/pull-requests/7"),
+            ProviderKind::Bitbucket
+        );
+    }
+
     #[test]
     fn test_pr_url_github() {
         assert_eq!(
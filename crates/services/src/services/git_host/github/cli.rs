@@ -8,6 +8,7 @@ use std::{
     io::Write,
     path::Path,
     process::Command,
+    time::Duration,
 };
 
 use chrono::{DateTime, Utc};
@@ -15,10 +16,14 @@ use db::models::merge::{MergeStatus, PullRequestInfo};
 use serde::Deserialize;
 use tempfile::NamedTempFile;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{
-    CreatePrRequest, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+use crate::services::{
+    bounded_command::{self, BoundedCommandError, OperationTimeouts},
+    git_host::types::{
+        CreatePrRequest, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -26,38 +31,98 @@ pub struct GitHubRepoInfo {
     pub owner: String,
 
     pub repo_name: String,
+
+    /// GitHub Enterprise Server hostname; `None` for github.com
+    pub host: Option<String>,
 }
 
 impl GitHubRepoInfo {
-    /// Parse GitHub repo info from a remote URL
+    /// Parse GitHub repo info from a remote URL, accepting the configured
+    /// GitHub Enterprise hosts besides github.com
     pub fn from_remote_url(url: &str) -> Option<Self> {
+        Self::from_remote_url_with_hosts(url, &super::GitHubProvider::enterprise_hosts())
+    }
+
+    pub(crate) fn from_remote_url_with_hosts(
+        url: &str,
+        enterprise_hosts: &[String],
+    ) -> Option<Self> {
         // Handle various GitHub URL formats:
         // - https://github.com/owner/repo.git
         // - git@github.com:owner/repo.git
-        // - https://github.com/owner/repo
+        // - ssh://git@github.com/owner/repo.git
+        // - the same forms on an enterprise host, e.g. https://github.acme.corp/owner/repo
 
-        let url = url.trim_end_matches('/').trim_end_matches(".git");
+        let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
 
-        if let Some(https_part) = url.strip_prefix("https://github.com/") {
-            let parts: Vec<&str> = https_part.split('/').collect();
-            if parts.len() >= 2 {
-                return Some(GitHubRepoInfo {
-                    owner: parts[0].to_string(),
-                    repo_name: parts[1].to_string(),
-                });
-            }
-        } else if let Some(ssh_part) = url.strip_prefix("git@github.com:") {
-            let parts: Vec<&str> = ssh_part.split('/').collect();
-            if parts.len() >= 2 {
-                return Some(GitHubRepoInfo {
-                    owner: parts[0].to_string(),
-                    repo_name: parts[1].to_string(),
-                });
+        let (authority, path) = if let Some(rest) = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .or_else(|| url.strip_prefix("ssh://"))
+        {
+            rest.split_once('/')?
+        } else {
+            // scp-like syntax: git@host:owner/repo
+            let (authority, path) = url.split_once(':')?;
+            if authority.contains('/') {
+                return None;
             }
+            (authority, path)
+        };
+
+        // Drop credentials and port, e.g. `git@host:22` or `token@host`
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?.to_ascii_lowercase();
+
+        let host = if host == "github.com" || host == "www.github.com" {
+            None
+        } else if enterprise_hosts
+            .iter()
+            .any(|configured| normalize_host(configured) == host)
+        {
+            Some(host)
+        } else {
+            return None;
+        };
+
+        let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        if parts.len() >= 2 && !parts[0].is_empty() && !parts[1].is_empty() {
+            return Some(GitHubRepoInfo {
+                owner: parts[0].to_string(),
+                repo_name: parts[1].to_string(),
+                host,
+            });
         }
 
         None
     }
+
+    pub fn is_enterprise(&self) -> bool {
+        self.host.is_some()
+    }
+
+    /// Value for `gh --repo`: `OWNER/REPO`, or `HOST/OWNER/REPO` on an
+    /// enterprise host
+    pub fn repo_arg(&self) -> String {
+        match &self.host {
+            Some(host) => format!("{host}/{}/{}", self.owner, self.repo_name),
+            None => format!("{}/{}", self.owner, self.repo_name),
+        }
+    }
+}
+
+/// Bare lowercase hostname of a configured host, which users may enter with
+/// a scheme or trailing slash
+pub(crate) fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let host = host
+        .strip_prefix("https://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host);
+    host.split('/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
 }
 
 #[derive(Deserialize)]
@@ -141,6 +206,19 @@ struct GhPrListExtendedResponse {
     base_ref_name: String,
 }
 
+#[derive(Deserialize)]
+struct GhApiUserResponse {
+    login: String,
+}
+
+/// Authentication details of the account `gh` is logged into
+#[derive(Debug, Clone)]
+pub struct GhAuthStatus {
+    pub login: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_remaining: Option<u64>,
+}
+
 #[derive(Debug, Error)]
 pub enum GhCliError {
     #[error("GitHub CLI (`gh`) executable not found or not runnable")]
@@ -151,14 +229,38 @@ pub enum GhCliError {
     AuthFailed(String),
     #[error("GitHub CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("GitHub CLI command timed out after {}s", .0.as_secs())]
+    Timeout(Duration),
+    #[error("GitHub CLI command cancelled")]
+    Cancelled,
+}
+
+impl From<BoundedCommandError> for GhCliError {
+    fn from(err: BoundedCommandError) -> Self {
+        match err {
+            BoundedCommandError::Timeout(limit) => GhCliError::Timeout(limit),
+            BoundedCommandError::Cancelled => GhCliError::Cancelled,
+            BoundedCommandError::Io(e) => GhCliError::CommandFailed(e.to_string()),
+        }
+    }
 }
 
+/// Every `gh` call talks to GitHub, so it gets the network timeout of
+/// [`OperationTimeouts`]
 #[derive(Debug, Clone, Default)]
-pub struct GhCli;
+pub struct GhCli {
+    cancel: Option<CancellationToken>,
+}
 
 impl GhCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Kill the running command and fail with `GhCliError::Cancelled` once
+    /// `cancel` fires
+    pub fn with_cancellation(cancel: Option<CancellationToken>) -> Self {
+        Self { cancel }
     }
 
     /// Ensure the GitHub CLI binary is discoverable.
@@ -181,9 +283,12 @@ impl GhCli {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd
-            .output()
-            .map_err(|err| GhCliError::CommandFailed(err.to_string()))?;
+        let output = bounded_command::run(
+            &mut cmd,
+            None,
+            OperationTimeouts::current().network,
+            self.cancel.as_ref(),
+        )?;
 
         if output.status.success() {
             return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -210,6 +315,19 @@ impl GhCli {
         Err(GhCliError::CommandFailed(stderr))
     }
 
+    /// First line of `gh --version`, e.g. `gh version 2.62.0 (2024-11-14)`.
+    pub fn version(&self) -> Result<String, GhCliError> {
+        let raw = self.run(["--version"], None)?;
+        Ok(raw.lines().next().unwrap_or_default().trim().to_string())
+    }
+
+    /// Query the authenticated user, reading token scopes and the remaining
+    /// rate limit from the response headers.
+    pub fn auth_status(&self) -> Result<GhAuthStatus, GhCliError> {
+        let raw = self.run(["api", "--include", "user"], None)?;
+        Self::parse_auth_status(&raw)
+    }
+
     /// Get repository info (owner and name) from a remote URL.
     pub fn get_repo_info(
         &self,
@@ -226,10 +344,48 @@ impl GhCli {
             Some(repo_path),
         )?;
         tracing::debug!("get_repo_info: raw response: {}", raw);
-        Self::parse_repo_info_response(&raw)
+        let host = GitHubRepoInfo::from_remote_url(remote_url).and_then(|info| info.host);
+        Self::parse_repo_info_response(&raw, host)
+    }
+
+    fn parse_auth_status(raw: &str) -> Result<GhAuthStatus, GhCliError> {
+        let normalized = raw.replace("\r\n", "\n");
+        let (headers, body) = normalized.split_once("\n\n").ok_or_else(|| {
+            GhCliError::UnexpectedOutput(format!("Missing headers in gh api response: {raw}"))
+        })?;
+
+        let header = |name: &str| {
+            headers.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.trim()
+                    .eq_ignore_ascii_case(name)
+                    .then(|| value.trim().to_string())
+            })
+        };
+
+        let user: GhApiUserResponse = serde_json::from_str(body.trim()).map_err(|e| {
+            GhCliError::UnexpectedOutput(format!("Failed to parse gh api user response: {e}"))
+        })?;
+
+        Ok(GhAuthStatus {
+            login: user.login,
+            scopes: header("X-OAuth-Scopes")
+                .map(|scopes| {
+                    scopes
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            rate_limit_remaining: header("X-RateLimit-Remaining").and_then(|v| v.parse().ok()),
+        })
     }
 
-    fn parse_repo_info_response(raw: &str) -> Result<GitHubRepoInfo, GhCliError> {
+    fn parse_repo_info_response(
+        raw: &str,
+        host: Option<String>,
+    ) -> Result<GitHubRepoInfo, GhCliError> {
         let resp: GhRepoViewResponse = serde_json::from_str(raw).map_err(|e| {
             GhCliError::UnexpectedOutput(format!("Failed to parse gh repo view response: {e}"))
         })?;
@@ -243,6 +399,7 @@ impl GhCli {
         Ok(GitHubRepoInfo {
             owner: resp.owner.login,
             repo_name: resp.name,
+            host,
         })
     }
 
@@ -254,8 +411,7 @@ impl GhCli {
     pub fn create_pr(
         &self,
         request: &CreatePrRequest,
-        owner: &str,
-        repo_name: &str,
+        repo_info: &GitHubRepoInfo,
         repo_path: &Path,
     ) -> Result<PullRequestInfo, GhCliError> {
         // Write body to temp file to avoid shell escaping and length issues
@@ -270,7 +426,7 @@ impl GhCli {
         args.push(OsString::from("pr"));
         args.push(OsString::from("create"));
         args.push(OsString::from("--repo"));
-        args.push(OsString::from(format!("{}/{}", owner, repo_name)));
+        args.push(OsString::from(repo_info.repo_arg()));
         args.push(OsString::from("--head"));
         args.push(OsString::from(&request.head_branch));
         args.push(OsString::from("--base"));
@@ -306,8 +462,7 @@ impl GhCli {
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         branch: &str,
     ) -> Result<Vec<PullRequestInfo>, GhCliError> {
         let raw = self.run(
@@ -315,7 +470,7 @@ impl GhCli {
                 "pr",
                 "list",
                 "--repo",
-                &format!("{owner}/{repo}"),
+                &repo_info.repo_arg(),
                 "--state",
                 "all",
                 "--head",
@@ -328,13 +483,13 @@ impl GhCli {
         Self::parse_pr_list(&raw)
     }
 
-    pub fn list_open_prs(&self, owner: &str, repo: &str) -> Result<Vec<OpenPrInfo>, GhCliError> {
+    pub fn list_open_prs(&self, repo_info: &GitHubRepoInfo) -> Result<Vec<OpenPrInfo>, GhCliError> {
         let raw = self.run(
             [
                 "pr",
                 "list",
                 "--repo",
-                &format!("{owner}/{repo}"),
+                &repo_info.repo_arg(),
                 "--state",
                 "open",
                 "--json",
@@ -348,8 +503,7 @@ impl GhCli {
     /// Fetch comments for a pull request.
     pub fn get_pr_comments(
         &self,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         pr_number: i64,
     ) -> Result<Vec<PrComment>, GhCliError> {
         let raw = self.run(
@@ -358,7 +512,7 @@ impl GhCli {
                 "view",
                 &pr_number.to_string(),
                 "--repo",
-                &format!("{owner}/{repo}"),
+                &repo_info.repo_arg(),
                 "--json",
                 "comments",
             ],
@@ -367,28 +521,119 @@ impl GhCli {
         Self::parse_pr_comments(&raw)
     }
 
+    /// Run `gh pr comment` with the body passed through a temp file.
+    pub fn add_pr_comment(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        let mut body_file = NamedTempFile::new()
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to create temp file: {e}")))?;
+        body_file
+            .write_all(body.as_bytes())
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to write body: {e}")))?;
+
+        self.run(
+            [
+                OsString::from("pr"),
+                OsString::from("comment"),
+                OsString::from(pr_number.to_string()),
+                OsString::from("--repo"),
+                OsString::from(repo_info.repo_arg()),
+                OsString::from("--body-file"),
+                body_file.path().as_os_str().to_os_string(),
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
     /// Fetch inline review comments for a pull request via API.
     pub fn get_pr_review_comments(
         &self,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         pr_number: i64,
     ) -> Result<Vec<PrReviewComment>, GhCliError> {
+        let mut args = vec![
+            "api".to_string(),
+            format!(
+                "repos/{}/{}/pulls/{pr_number}/comments",
+                repo_info.owner, repo_info.repo_name
+            ),
+        ];
+        // `gh api` talks to github.com unless told otherwise
+        if let Some(host) = &repo_info.host {
+            args.push("--hostname".to_string());
+            args.push(host.clone());
+        }
+        let raw = self.run(args, None)?;
+        Self::parse_pr_review_comments(&raw)
+    }
+
+    /// Create a GitHub repo from a template repo and clone it into
+    /// `parent_dir/<name>`. `name` may be `NAME` or `OWNER/NAME`.
+    pub fn create_repo_from_template(
+        &self,
+        template: &str,
+        name: &str,
+        private: bool,
+        parent_dir: &Path,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "repo",
+                "create",
+                name,
+                "--template",
+                template,
+                if private { "--private" } else { "--public" },
+                "--clone",
+            ],
+            Some(parent_dir),
+        )?;
+        Ok(())
+    }
+
+    /// Run `gh release create --draft` in the repo, with the notes passed
+    /// through a temp file. Returns the URL of the draft release.
+    pub fn create_draft_release(
+        &self,
+        repo_path: &Path,
+        tag: &str,
+        title: &str,
+        notes: &str,
+    ) -> Result<String, GhCliError> {
+        let mut notes_file = NamedTempFile::new()
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to create temp file: {e}")))?;
+        notes_file
+            .write_all(notes.as_bytes())
+            .map_err(|e| GhCliError::CommandFailed(format!("Failed to write notes: {e}")))?;
+
         let raw = self.run(
             [
-                "api",
-                &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments"),
+                OsString::from("release"),
+                OsString::from("create"),
+                OsString::from(tag),
+                OsString::from("--draft"),
+                OsString::from("--title"),
+                OsString::from(title),
+                OsString::from("--notes-file"),
+                notes_file.path().as_os_str().to_os_string(),
             ],
-            None,
+            Some(repo_path),
         )?;
-        Self::parse_pr_review_comments(&raw)
+        raw.lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("http"))
+            .map(str::to_string)
+            .ok_or_else(|| GhCliError::UnexpectedOutput(raw.clone()))
     }
 
     pub fn pr_checkout(
         &self,
         repo_path: &Path,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         pr_number: i64,
     ) -> Result<(), GhCliError> {
         self.run(
@@ -397,7 +642,7 @@ impl GhCli {
                 "checkout",
                 &pr_number.to_string(),
                 "--repo",
-                &format!("{owner}/{repo}"),
+                &repo_info.repo_arg(),
                 "--force",
             ],
             Some(repo_path),
@@ -560,3 +805,48 @@ impl GhCli {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_github_com_remotes() {
+        for url in [
+            "https://github.com/acme/widgets.git",
+            "http://github.com/acme/widgets",
+            "git@github.com:acme/widgets.git",
+            "ssh://git@github.com/acme/widgets.git",
+        ] {
+            let info = GitHubRepoInfo::from_remote_url_with_hosts(url, &[]).unwrap();
+            assert_eq!(
+                (info.owner.as_str(), info.repo_name.as_str()),
+                ("acme", "widgets")
+            );
+            assert_eq!(info.host, None, "{url}");
+            assert_eq!(info.repo_arg(), "acme/widgets");
+        }
+    }
+
+    #[test]
+    fn parses_enterprise_remotes_only_for_configured_hosts() {
+        let hosts = vec!["https://GitHub.Acme.corp/".to_string()];
+        for url in [
+            "https://github.acme.corp/team/project.git",
+            "git@github.acme.corp:team/project.git",
+            "ssh://git@github.acme.corp:2222/team/project",
+        ] {
+            let info = GitHubRepoInfo::from_remote_url_with_hosts(url, &hosts).unwrap();
+            assert_eq!(info.host.as_deref(), Some("github.acme.corp"), "{url}");
+            assert_eq!(info.repo_arg(), "github.acme.corp/team/project");
+        }
+
+        assert!(
+            GitHubRepoInfo::from_remote_url_with_hosts("https://github.other.corp/a/b", &hosts)
+                .is_none()
+        );
+        assert!(
+            GitHubRepoInfo::from_remote_url_with_hosts("https://gitlab.com/a/b", &hosts).is_none()
+        );
+    }
+}
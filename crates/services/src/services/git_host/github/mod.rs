@@ -1,21 +1,46 @@
 //! GitHub hosting service implementation.
 
 mod cli;
+mod rest;
 
-use std::{path::Path, time::Duration};
+use std::{
+    path::Path,
+    sync::{LazyLock, RwLock},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 use cli::GhCliError;
-pub use cli::{GhCli, GitHubRepoInfo};
+pub use cli::{GhAuthStatus, GhCli, GitHubRepoInfo};
 use db::models::merge::PullRequestInfo;
+pub use rest::GitHubRestClient;
+use secrecy::SecretString;
 use tokio::task;
 use tracing::info;
+use utils::shell::resolve_executable_path;
 
 use super::{
     GitHostProvider,
     types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
 };
+use crate::services::github_app::GitHubAppAuth;
+
+/// Personal access token from the user config, used when `gh` is not installed
+static CONFIG_TOKEN: LazyLock<RwLock<Option<SecretString>>> = LazyLock::new(|| RwLock::new(None));
+
+/// GitHub Enterprise Server hostnames from the user config
+static ENTERPRISE_HOSTS: LazyLock<RwLock<Vec<String>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Token for REST calls in place of the `gh` CLI: the GitHub App installation's
+/// when there is one, otherwise the configured token only if `gh` is missing
+fn rest_token(
+    app_token: Option<SecretString>,
+    config_token: Option<SecretString>,
+    gh_installed: bool,
+) -> Option<SecretString> {
+    app_token.or_else(|| config_token.filter(|_| !gh_installed))
+}
 
 #[derive(Debug, Clone)]
 pub struct GitHubProvider {
@@ -29,6 +54,123 @@ impl GitHubProvider {
         })
     }
 
+    /// Set the token used for direct REST calls when the `gh` CLI is unavailable
+    pub fn set_config_token(token: Option<String>) {
+        let token = token
+            .filter(|t| !t.trim().is_empty())
+            .map(SecretString::from);
+        *CONFIG_TOKEN.write().unwrap_or_else(|e| e.into_inner()) = token;
+    }
+
+    pub fn has_config_token() -> bool {
+        Self::config_token().is_some()
+    }
+
+    /// Set the GitHub Enterprise Server hosts whose remotes are handled as GitHub
+    pub fn set_enterprise_hosts(hosts: &[String]) {
+        let hosts = hosts
+            .iter()
+            .map(|host| cli::normalize_host(host))
+            .filter(|host| !host.is_empty())
+            .collect();
+        *ENTERPRISE_HOSTS.write().unwrap_or_else(|e| e.into_inner()) = hosts;
+    }
+
+    pub fn enterprise_hosts() -> Vec<String> {
+        ENTERPRISE_HOSTS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Whether `url`, a remote or PR URL, points at a configured enterprise host
+    pub fn is_enterprise_url(url: &str) -> bool {
+        GitHubRepoInfo::from_remote_url(url).is_some_and(|info| info.is_enterprise())
+    }
+
+    fn config_token() -> Option<SecretString> {
+        CONFIG_TOKEN
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// REST client to use instead of the `gh` CLI: the GitHub App installation
+    /// when one is set up, otherwise the configured token if `gh` is missing.
+    /// The REST client only talks to github.com, so enterprise hosts always go
+    /// through `gh`.
+    async fn rest_client(&self, url: &str) -> Option<GitHubRestClient> {
+        if Self::is_enterprise_url(url) {
+            return None;
+        }
+        let app_token = GitHubAppAuth::global_token().await;
+        let config_token = Self::config_token();
+        let gh_installed = app_token.is_none()
+            && config_token.is_some()
+            && resolve_executable_path("gh").await.is_some();
+        let from_app = app_token.is_some();
+        let token = rest_token(app_token, config_token, gh_installed)?;
+        if !from_app {
+            tracing::debug!("GitHub CLI not found, using REST API with configured token");
+        }
+        Some(GitHubRestClient::new(token))
+    }
+
+    fn parse_repo_info(remote_url: &str) -> Result<GitHubRepoInfo, GitHostError> {
+        GitHubRepoInfo::from_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!("Not a GitHub repository URL: {remote_url}"))
+        })
+    }
+
+    async fn create_pr_rest(
+        &self,
+        client: &GitHubRestClient,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target_repo_info = Self::parse_repo_info(remote_url)?;
+
+        let mut request = request.clone();
+        if let Some(head_url) = &request.head_repo_url {
+            let head_repo_info = Self::parse_repo_info(head_url)?;
+            if head_repo_info.owner != target_repo_info.owner {
+                request.head_branch = format!("{}:{}", head_repo_info.owner, request.head_branch);
+            }
+        }
+
+        let pr = (|| async {
+            client
+                .create_pr(
+                    &request,
+                    &target_repo_info.owner,
+                    &target_repo_info.repo_name,
+                )
+                .await
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await?;
+
+        info!(
+            "Created GitHub PR #{} for branch {} via REST API",
+            pr.number, request.head_branch
+        );
+        Ok(pr)
+    }
+
     async fn get_repo_info(
         &self,
         remote_url: &str,
@@ -48,27 +190,22 @@ impl GitHubProvider {
     async fn fetch_general_comments(
         &self,
         cli: &GhCli,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         pr_number: i64,
     ) -> Result<Vec<super::types::PrComment>, GitHostError> {
         let cli = cli.clone();
-        let owner = owner.to_string();
-        let repo = repo.to_string();
 
         (|| async {
             let cli = cli.clone();
-            let owner = owner.clone();
-            let repo = repo.clone();
+            let repo_info = repo_info.clone();
 
-            let comments =
-                task::spawn_blocking(move || cli.get_pr_comments(&owner, &repo, pr_number))
-                    .await
-                    .map_err(|err| {
-                        GitHostError::PullRequest(format!(
-                            "Failed to execute GitHub CLI for fetching PR comments: {err}"
-                        ))
-                    })?;
+            let comments = task::spawn_blocking(move || cli.get_pr_comments(&repo_info, pr_number))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for fetching PR comments: {err}"
+                    ))
+                })?;
             comments.map_err(GitHostError::from)
         })
         .retry(
@@ -92,21 +229,17 @@ impl GitHubProvider {
     async fn fetch_review_comments(
         &self,
         cli: &GhCli,
-        owner: &str,
-        repo: &str,
+        repo_info: &GitHubRepoInfo,
         pr_number: i64,
     ) -> Result<Vec<super::types::PrReviewComment>, GitHostError> {
         let cli = cli.clone();
-        let owner = owner.to_string();
-        let repo = repo.to_string();
 
         (|| async {
             let cli = cli.clone();
-            let owner = owner.clone();
-            let repo = repo.clone();
+            let repo_info = repo_info.clone();
 
             let comments =
-                task::spawn_blocking(move || cli.get_pr_review_comments(&owner, &repo, pr_number))
+                task::spawn_blocking(move || cli.get_pr_review_comments(&repo_info, pr_number))
                     .await
                     .map_err(|err| {
                         GitHostError::PullRequest(format!(
@@ -152,6 +285,8 @@ impl From<GhCliError> for GitHostError {
                 }
             }
             GhCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+            GhCliError::Timeout(limit) => GitHostError::Timeout(*limit),
+            GhCliError::Cancelled => GitHostError::Cancelled,
         }
     }
 }
@@ -164,6 +299,10 @@ impl GitHostProvider for GitHubProvider {
         remote_url: &str,
         request: &CreatePrRequest,
     ) -> Result<PullRequestInfo, GitHostError> {
+        if let Some(client) = self.rest_client(remote_url).await {
+            return self.create_pr_rest(&client, remote_url, request).await;
+        }
+
         // Get owner/repo from the remote URL (target repo for the PR).
         let target_repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
@@ -185,20 +324,18 @@ impl GitHostProvider for GitHubProvider {
         (|| async {
             let cli = self.gh_cli.clone();
             let request = request_clone.clone();
-            let owner = target_repo_info.owner.clone();
-            let repo_name = target_repo_info.repo_name.clone();
+            let repo_info = target_repo_info.clone();
             let repo_path = repo_path.to_path_buf();
 
-            let cli_result = task::spawn_blocking(move || {
-                cli.create_pr(&request, &owner, &repo_name, &repo_path)
-            })
-            .await
-            .map_err(|err| {
-                GitHostError::PullRequest(format!(
-                    "Failed to execute GitHub CLI for PR creation: {err}"
-                ))
-            })?
-            .map_err(GitHostError::from)?;
+            let cli_result =
+                task::spawn_blocking(move || cli.create_pr(&request, &repo_info, &repo_path))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::PullRequest(format!(
+                            "Failed to execute GitHub CLI for PR creation: {err}"
+                        ))
+                    })?
+                    .map_err(GitHostError::from)?;
 
             info!(
                 "Created GitHub PR #{} for branch {}",
@@ -226,6 +363,10 @@ impl GitHostProvider for GitHubProvider {
     }
 
     async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        if let Some(client) = self.rest_client(pr_url).await {
+            return client.view_pr(pr_url).await;
+        }
+
         let cli = self.gh_cli.clone();
         let url = pr_url.to_string();
 
@@ -265,6 +406,13 @@ impl GitHostProvider for GitHubProvider {
         remote_url: &str,
         branch_name: &str,
     ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        if let Some(client) = self.rest_client(remote_url).await {
+            let repo_info = Self::parse_repo_info(remote_url)?;
+            return client
+                .list_prs_for_branch(&repo_info.owner, &repo_info.repo_name, branch_name)
+                .await;
+        }
+
         let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
         let cli = self.gh_cli.clone();
@@ -272,18 +420,16 @@ impl GitHostProvider for GitHubProvider {
 
         (|| async {
             let cli = cli.clone();
-            let owner = repo_info.owner.clone();
-            let repo_name = repo_info.repo_name.clone();
+            let repo_info = repo_info.clone();
             let branch = branch.clone();
 
-            let prs =
-                task::spawn_blocking(move || cli.list_prs_for_branch(&owner, &repo_name, &branch))
-                    .await
-                    .map_err(|err| {
-                        GitHostError::PullRequest(format!(
-                            "Failed to execute GitHub CLI for listing PRs: {err}"
-                        ))
-                    })?;
+            let prs = task::spawn_blocking(move || cli.list_prs_for_branch(&repo_info, &branch))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for listing PRs: {err}"
+                    ))
+                })?;
             prs.map_err(GitHostError::from)
         })
         .retry(
@@ -310,16 +456,26 @@ impl GitHostProvider for GitHubProvider {
         remote_url: &str,
         pr_number: i64,
     ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
-        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let (general_result, review_result) = if let Some(client) =
+            self.rest_client(remote_url).await
+        {
+            let repo_info = Self::parse_repo_info(remote_url)?;
+            tokio::join!(
+                client.get_pr_comments(&repo_info.owner, &repo_info.repo_name, pr_number),
+                client.get_pr_review_comments(&repo_info.owner, &repo_info.repo_name, pr_number)
+            )
+        } else {
+            let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
-        // Fetch both types of comments in parallel
-        let cli1 = self.gh_cli.clone();
-        let cli2 = self.gh_cli.clone();
+            // Fetch both types of comments in parallel
+            let cli1 = self.gh_cli.clone();
+            let cli2 = self.gh_cli.clone();
 
-        let (general_result, review_result) = tokio::join!(
-            self.fetch_general_comments(&cli1, &repo_info.owner, &repo_info.repo_name, pr_number),
-            self.fetch_review_comments(&cli2, &repo_info.owner, &repo_info.repo_name, pr_number)
-        );
+            tokio::join!(
+                self.fetch_general_comments(&cli1, &repo_info, pr_number),
+                self.fetch_review_comments(&cli2, &repo_info, pr_number)
+            )
+        };
 
         let general_comments = general_result?;
         let review_comments = review_result?;
@@ -364,16 +520,22 @@ impl GitHostProvider for GitHubProvider {
         repo_path: &Path,
         remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        if let Some(client) = self.rest_client(remote_url).await {
+            let repo_info = Self::parse_repo_info(remote_url)?;
+            return client
+                .list_open_prs(&repo_info.owner, &repo_info.repo_name)
+                .await;
+        }
+
         let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
         let cli = self.gh_cli.clone();
 
         (|| async {
             let cli = cli.clone();
-            let owner = repo_info.owner.clone();
-            let repo_name = repo_info.repo_name.clone();
+            let repo_info = repo_info.clone();
 
-            let prs = task::spawn_blocking(move || cli.list_open_prs(&owner, &repo_name))
+            let prs = task::spawn_blocking(move || cli.list_open_prs(&repo_info))
                 .await
                 .map_err(|err| {
                     GitHostError::PullRequest(format!(
@@ -400,7 +562,67 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn add_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        if let Some(client) = self.rest_client(remote_url).await {
+            let repo_info = Self::parse_repo_info(remote_url)?;
+            return client
+                .add_pr_comment(&repo_info.owner, &repo_info.repo_name, pr_number, body)
+                .await;
+        }
+
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || cli.add_pr_comment(&repo_info, pr_number, &body))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for commenting on PR: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use secrecy::ExposeSecret;
+
+    use super::*;
+
+    fn token(value: &str) -> Option<SecretString> {
+        Some(SecretString::from(value.to_string()))
+    }
+
+    fn chosen(token: Option<SecretString>) -> Option<String> {
+        token.map(|token| token.expose_secret().to_string())
+    }
+
+    #[test]
+    fn configured_token_is_only_used_without_the_gh_cli() {
+        assert_eq!(
+            chosen(rest_token(None, token("pat"), false)).as_deref(),
+            Some("pat")
+        );
+        assert_eq!(chosen(rest_token(None, token("pat"), true)), None);
+        assert_eq!(chosen(rest_token(None, None, false)), None);
+    }
+
+    #[test]
+    fn app_installation_token_wins_over_the_configured_one() {
+        assert_eq!(
+            chosen(rest_token(token("app"), token("pat"), true)).as_deref(),
+            Some("app")
+        );
+    }
+}
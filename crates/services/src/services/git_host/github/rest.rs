@@ -0,0 +1,368 @@
+//! Direct GitHub REST API access for when the `gh` CLI is not used.
+//!
+//! Authenticates with a bearer token (for example a GitHub App installation
+//! token) and exposes the same operations as [`super::GhCli`].
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::services::git_host::types::{
+    CreatePrRequest, GitHostError, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment,
+    ReviewCommentUser,
+};
+
+const GITHUB_API_BASE: &str = "https://api.github.com";
+const USER_AGENT: &str = "VibeKanban/1.0";
+const PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct RestUser {
+    login: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
+
+#[derive(Deserialize)]
+struct RestPullRequest {
+    number: i64,
+    html_url: String,
+    #[serde(default)]
+    title: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: RestRef,
+    base: RestRef,
+}
+
+#[derive(Deserialize)]
+struct RestIssueComment {
+    id: i64,
+    user: Option<RestUser>,
+    #[serde(default)]
+    author_association: String,
+    #[serde(default)]
+    body: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct RestReviewComment {
+    id: i64,
+    user: Option<RestUser>,
+    #[serde(default)]
+    body: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    path: String,
+    line: Option<i64>,
+    side: Option<String>,
+    #[serde(default)]
+    diff_hunk: String,
+    #[serde(default)]
+    author_association: String,
+}
+
+#[derive(Serialize)]
+struct CreatePullRequestBody<'a> {
+    title: &'a str,
+    body: &'a str,
+    head: &'a str,
+    base: &'a str,
+    draft: bool,
+}
+
+#[derive(Clone)]
+pub struct GitHubRestClient {
+    client: Client,
+    token: SecretString,
+}
+
+impl std::fmt::Debug for GitHubRestClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubRestClient").finish_non_exhaustive()
+    }
+}
+
+impl GitHubRestClient {
+    pub fn new(token: SecretString) -> Self {
+        Self {
+            client: Client::new(),
+            token,
+        }
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        builder
+            .bearer_auth(self.token.expose_secret())
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", USER_AGENT)
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T, GitHostError> {
+        let response =
+            self.request(builder).send().await.map_err(|e| {
+                GitHostError::PullRequest(format!("GitHub API request failed: {e}"))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(Self::status_error(status, message));
+        }
+
+        response.json::<T>().await.map_err(|e| {
+            GitHostError::UnexpectedOutput(format!("Invalid GitHub API response: {e}"))
+        })
+    }
+
+    fn status_error(status: StatusCode, message: String) -> GitHostError {
+        match status {
+            StatusCode::UNAUTHORIZED => GitHostError::AuthFailed(message),
+            StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(message),
+            StatusCode::NOT_FOUND => GitHostError::RepoNotFoundOrNoAccess(message),
+            _ => GitHostError::PullRequest(format!("GitHub API error {status}: {message}")),
+        }
+    }
+
+    async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, GitHostError> {
+        let mut items = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_str = page.to_string();
+            let per_page = PAGE_SIZE.to_string();
+            let batch: Vec<T> = self
+                .send(
+                    self.client
+                        .get(url)
+                        .query(query)
+                        .query(&[("per_page", per_page.as_str()), ("page", page_str.as_str())]),
+                )
+                .await?;
+            let count = batch.len();
+            items.extend(batch);
+            if count < PAGE_SIZE {
+                return Ok(items);
+            }
+            page += 1;
+        }
+    }
+
+    pub async fn create_pr(
+        &self,
+        request: &CreatePrRequest,
+        owner: &str,
+        repo_name: &str,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let pr: RestPullRequest = self
+            .send(
+                self.client
+                    .post(format!("{GITHUB_API_BASE}/repos/{owner}/{repo_name}/pulls"))
+                    .json(&CreatePullRequestBody {
+                        title: &request.title,
+                        body: request.body.as_deref().unwrap_or(""),
+                        head: &request.head_branch,
+                        base: &request.base_branch,
+                        draft: request.draft.unwrap_or(false),
+                    }),
+            )
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    pub async fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (owner, repo, number) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            GitHostError::PullRequest(format!("Not a GitHub pull request URL: {pr_url}"))
+        })?;
+        let pr: RestPullRequest = self
+            .send(self.client.get(format!(
+                "{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls/{number}"
+            )))
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    pub async fn list_prs_for_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let head = format!("{owner}:{branch}");
+        let prs: Vec<RestPullRequest> = self
+            .get_paginated(
+                &format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls"),
+                &[("state", "all"), ("head", head.as_str())],
+            )
+            .await?;
+        Ok(prs.into_iter().map(Self::pr_to_info).collect())
+    }
+
+    pub async fn list_open_prs(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let prs: Vec<RestPullRequest> = self
+            .get_paginated(
+                &format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls"),
+                &[("state", "open")],
+            )
+            .await?;
+        Ok(prs
+            .into_iter()
+            .map(|pr| OpenPrInfo {
+                number: pr.number,
+                url: pr.html_url,
+                title: pr.title,
+                head_branch: pr.head.ref_name,
+                base_branch: pr.base.ref_name,
+            })
+            .collect())
+    }
+
+    pub async fn add_pr_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let _: serde_json::Value = self
+            .send(
+                self.client
+                    .post(format!(
+                        "{GITHUB_API_BASE}/repos/{owner}/{repo}/issues/{pr_number}/comments"
+                    ))
+                    .json(&serde_json::json!({ "body": body })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_pr_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrComment>, GitHostError> {
+        let comments: Vec<RestIssueComment> = self
+            .get_paginated(
+                &format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/issues/{pr_number}/comments"),
+                &[],
+            )
+            .await?;
+        Ok(comments
+            .into_iter()
+            .map(|c| PrComment {
+                id: c.id.to_string(),
+                author: PrCommentAuthor {
+                    login: c
+                        .user
+                        .and_then(|u| u.login)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                },
+                author_association: c.author_association,
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect())
+    }
+
+    pub async fn get_pr_review_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrReviewComment>, GitHostError> {
+        let comments: Vec<RestReviewComment> = self
+            .get_paginated(
+                &format!("{GITHUB_API_BASE}/repos/{owner}/{repo}/pulls/{pr_number}/comments"),
+                &[],
+            )
+            .await?;
+        Ok(comments
+            .into_iter()
+            .map(|c| PrReviewComment {
+                id: c.id,
+                user: ReviewCommentUser {
+                    login: c
+                        .user
+                        .and_then(|u| u.login)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                },
+                body: c.body,
+                created_at: c.created_at,
+                html_url: c.html_url,
+                path: c.path,
+                line: c.line,
+                side: c.side,
+                diff_hunk: c.diff_hunk,
+                author_association: c.author_association,
+            })
+            .collect())
+    }
+
+    fn pr_to_info(pr: RestPullRequest) -> PullRequestInfo {
+        let status = match (pr.state.as_str(), pr.merged_at) {
+            (_, Some(_)) => MergeStatus::Merged,
+            ("open", None) => MergeStatus::Open,
+            ("closed", None) => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestInfo {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha.filter(|_| pr.merged_at.is_some()),
+        }
+    }
+
+    /// Split `https://github.com/{owner}/{repo}/pull/{number}` into its parts
+    fn parse_pr_url(pr_url: &str) -> Option<(String, String, i64)> {
+        let path = pr_url.trim_end_matches('/').split("github.com/").nth(1)?;
+        let mut parts = path.split('/');
+        let owner = parts.next()?.to_string();
+        let repo = parts.next()?.to_string();
+        if parts.next()? != "pull" {
+            return None;
+        }
+        let number = parts.next()?.parse().ok()?;
+        Some((owner, repo, number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pr_urls() {
+        assert_eq!(
+            GitHubRestClient::parse_pr_url("https://github.com/acme/widgets/pull/42"),
+            Some(("acme".to_string(), "widgets".to_string(), 42))
+        );
+        assert_eq!(
+            GitHubRestClient::parse_pr_url("https://github.com/acme/widgets/issues/42"),
+            None
+        );
+    }
+}
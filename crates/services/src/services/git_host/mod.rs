@@ -2,6 +2,7 @@ mod detection;
 mod types;
 
 pub mod azure;
+pub mod bitbucket;
 pub mod github;
 
 use std::path::Path;
@@ -15,7 +16,7 @@ pub use types::{
     ProviderKind, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{azure::AzureDevOpsProvider, bitbucket::BitbucketProvider, github::GitHubProvider};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -49,6 +50,15 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError>;
 
+    /// Post a general (non-review) comment on a pull request
+    async fn add_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -56,6 +66,7 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    Bitbucket(BitbucketProvider),
 }
 
 impl GitHostService {
@@ -63,6 +74,7 @@ impl GitHostService {
         match detect_provider_from_url(url) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::Bitbucket => Ok(Self::Bitbucket(BitbucketProvider::new()?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
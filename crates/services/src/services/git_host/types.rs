@@ -8,6 +8,7 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    Bitbucket,
     Unknown,
 }
 
@@ -16,6 +17,7 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::Bitbucket => write!(f, "Bitbucket"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
@@ -50,6 +52,10 @@ pub enum GitHostError {
     UnsupportedProvider,
     #[error("CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("Operation timed out after {}s", .0.as_secs())]
+    Timeout(std::time::Duration),
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 impl GitHostError {
@@ -61,6 +67,8 @@ impl GitHostError {
                 | GitHostError::RepoNotFoundOrNoAccess(_)
                 | GitHostError::CliNotInstalled { .. }
                 | GitHostError::UnsupportedProvider
+                | GitHostError::Timeout(_)
+                | GitHostError::Cancelled
         )
     }
 }
@@ -0,0 +1,290 @@
+//! GitHub App authentication for the local server.
+//!
+//! When `GITHUB_APP_ID`, `GITHUB_APP_PRIVATE_KEY` and `GITHUB_APP_SLUG` are set,
+//! users can install the app on their account instead of installing and logging
+//! into the `gh` CLI. GitHub operations then use short-lived installation tokens.
+
+use std::{
+    collections::HashMap,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use remote::{
+    config::GitHubAppConfig,
+    github_app::{GitHubAppError, GitHubAppService, InstallationInfo},
+};
+use secrecy::SecretString;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Installation tokens are valid for an hour; refresh well before expiry
+const TOKEN_TTL: Duration = Duration::from_secs(50 * 60);
+/// How long an installation has to come back to the callback
+const STATE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Installs started but never finished are dropped oldest first past this
+const MAX_PENDING_STATES: usize = 100;
+
+static GLOBAL: OnceLock<GitHubAppAuth> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum GitHubAppAuthError {
+    #[error("Invalid or expired installation state")]
+    InvalidState,
+    #[error("GitHub App installation is suspended")]
+    Suspended,
+    #[error(transparent)]
+    GitHubApp(#[from] GitHubAppError),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GitHubAppStatus {
+    pub configured: bool,
+    pub app_slug: Option<String>,
+    #[ts(type = "number | null")]
+    pub installation_id: Option<i64>,
+    pub account_login: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GitHubAppInstallUrl {
+    pub url: String,
+    pub state: String,
+}
+
+struct CachedToken {
+    token: SecretString,
+    fetched_at: Instant,
+}
+
+/// Installation states handed out and not yet returned, with when they
+/// were issued
+#[derive(Default)]
+struct PendingStates(HashMap<String, Instant>);
+
+impl PendingStates {
+    fn insert(&mut self, state: String, now: Instant) {
+        self.0
+            .retain(|_, issued_at| now.duration_since(*issued_at) < STATE_TTL);
+        if self.0.len() >= MAX_PENDING_STATES
+            && let Some(oldest) = self
+                .0
+                .iter()
+                .min_by_key(|(_, issued_at)| **issued_at)
+                .map(|(state, _)| state.clone())
+        {
+            self.0.remove(&oldest);
+        }
+        self.0.insert(state, now);
+    }
+
+    /// Consume `state`; false if it was never issued or has expired
+    fn take(&mut self, state: &str, now: Instant) -> bool {
+        self.0
+            .remove(state)
+            .is_some_and(|issued_at| now.duration_since(issued_at) < STATE_TTL)
+    }
+}
+
+pub struct GitHubAppAuth {
+    service: GitHubAppService,
+    installation_id: RwLock<Option<i64>>,
+    account_login: RwLock<Option<String>>,
+    token: RwLock<Option<CachedToken>>,
+    pending_states: RwLock<PendingStates>,
+}
+
+impl GitHubAppAuth {
+    /// Build from the environment; `None` if no app is configured
+    pub fn from_env() -> Option<Self> {
+        let app_id = std::env::var("GITHUB_APP_ID").ok()?.parse::<u64>().ok()?;
+        let private_key = std::env::var("GITHUB_APP_PRIVATE_KEY").ok()?;
+        let app_slug = std::env::var("GITHUB_APP_SLUG").ok()?;
+
+        if BASE64_STANDARD.decode(private_key.as_bytes()).is_err() {
+            tracing::warn!("GITHUB_APP_PRIVATE_KEY is not valid base64, GitHub App disabled");
+            return None;
+        }
+
+        // The local server never receives webhooks
+        let config = GitHubAppConfig {
+            app_id,
+            private_key: SecretString::from(private_key),
+            webhook_secret: SecretString::from(String::new()),
+            app_slug,
+        };
+
+        match GitHubAppService::new(&config, reqwest::Client::new()) {
+            Ok(service) => Some(Self {
+                service,
+                installation_id: RwLock::new(None),
+                account_login: RwLock::new(None),
+                token: RwLock::new(None),
+                pending_states: RwLock::new(PendingStates::default()),
+            }),
+            Err(e) => {
+                tracing::warn!("Failed to initialize GitHub App: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Initialize the process-wide instance, restoring a saved installation
+    pub async fn init_global(installation_id: Option<i64>) {
+        let Some(auth) = Self::from_env() else {
+            return;
+        };
+        *auth.installation_id.write().await = installation_id;
+        tracing::info!(
+            "GitHub App '{}' enabled (installation: {:?})",
+            auth.service.app_slug(),
+            installation_id
+        );
+        let _ = GLOBAL.set(auth);
+    }
+
+    pub fn global() -> Option<&'static Self> {
+        GLOBAL.get()
+    }
+
+    /// Start an installation; the returned state must come back on the callback
+    pub async fn install_url(&self) -> GitHubAppInstallUrl {
+        let state = Uuid::new_v4().to_string();
+        self.pending_states
+            .write()
+            .await
+            .insert(state.clone(), Instant::now());
+        GitHubAppInstallUrl {
+            url: format!(
+                "https://github.com/apps/{}/installations/new?state={}",
+                self.service.app_slug(),
+                state
+            ),
+            state,
+        }
+    }
+
+    /// Validate the callback and switch to the new installation
+    pub async fn complete_installation(
+        &self,
+        installation_id: i64,
+        state: &str,
+    ) -> Result<InstallationInfo, GitHubAppAuthError> {
+        if !self
+            .pending_states
+            .write()
+            .await
+            .take(state, Instant::now())
+        {
+            return Err(GitHubAppAuthError::InvalidState);
+        }
+
+        let installation = self.service.get_installation(installation_id).await?;
+        if installation.suspended_at.is_some() {
+            return Err(GitHubAppAuthError::Suspended);
+        }
+
+        // Make sure the installation can actually mint tokens before using it
+        let token = self.service.get_installation_token(installation_id).await?;
+
+        *self.installation_id.write().await = Some(installation_id);
+        *self.account_login.write().await = Some(installation.account.login.clone());
+        *self.token.write().await = Some(CachedToken {
+            token: SecretString::from(token),
+            fetched_at: Instant::now(),
+        });
+
+        Ok(installation)
+    }
+
+    pub async fn clear_installation(&self) {
+        *self.installation_id.write().await = None;
+        *self.account_login.write().await = None;
+        *self.token.write().await = None;
+    }
+
+    pub async fn installation_id(&self) -> Option<i64> {
+        *self.installation_id.read().await
+    }
+
+    /// Installation token for API calls, refreshed when close to expiry
+    pub async fn installation_token(&self) -> Result<Option<SecretString>, GitHubAppAuthError> {
+        let Some(installation_id) = self.installation_id().await else {
+            return Ok(None);
+        };
+
+        if let Some(cached) = self.token.read().await.as_ref()
+            && cached.fetched_at.elapsed() < TOKEN_TTL
+        {
+            return Ok(Some(cached.token.clone()));
+        }
+
+        let token = SecretString::from(self.service.get_installation_token(installation_id).await?);
+        *self.token.write().await = Some(CachedToken {
+            token: token.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(Some(token))
+    }
+
+    pub async fn status(&self) -> GitHubAppStatus {
+        GitHubAppStatus {
+            configured: true,
+            app_slug: Some(self.service.app_slug().to_string()),
+            installation_id: self.installation_id().await,
+            account_login: self.account_login.read().await.clone(),
+        }
+    }
+
+    /// Status of the process-wide instance, if any
+    pub async fn global_status() -> GitHubAppStatus {
+        match Self::global() {
+            Some(auth) => auth.status().await,
+            None => GitHubAppStatus {
+                configured: false,
+                app_slug: None,
+                installation_id: None,
+                account_login: None,
+            },
+        }
+    }
+
+    /// Token of the process-wide installation; `None` when not installed or on error
+    pub async fn global_token() -> Option<SecretString> {
+        let auth = Self::global()?;
+        match auth.installation_token().await {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::warn!("Failed to get GitHub App installation token: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn states_expire_and_are_capped() {
+        let start = Instant::now();
+        let mut states = PendingStates::default();
+        states.insert("fresh".to_string(), start);
+        states.insert("stale".to_string(), start);
+        assert!(states.take("fresh", start + Duration::from_secs(60)));
+        assert!(!states.take("fresh", start + Duration::from_secs(60)));
+        assert!(!states.take("stale", start + STATE_TTL));
+
+        for n in 0..MAX_PENDING_STATES + 10 {
+            states.insert(n.to_string(), start + Duration::from_millis(n as u64));
+        }
+        assert_eq!(states.0.len(), MAX_PENDING_STATES);
+        assert!(!states.take("0", start));
+        assert!(states.take(&(MAX_PENDING_STATES + 9).to_string(), start + STATE_TTL / 2));
+    }
+}
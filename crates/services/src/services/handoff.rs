@@ -0,0 +1,133 @@
+//! Context for a different executor taking over a session.
+//!
+//! A new agent cannot resume another agent's conversation, so it starts fresh
+//! from what the user asked so far, how each turn ended and a digest of the
+//! changes already in the workspace.
+
+use db::models::coding_agent_turn::CodingAgentTurn;
+use utils::diff::Diff;
+
+use super::{attempt_summary, diff_digest};
+
+/// Prompt budget for the changes made so far, in characters
+const DIFF_BUDGET: usize = 4000;
+/// Only the latest turns are replayed; older ones rarely matter any more
+const MAX_TURNS: usize = 10;
+
+/// Initial prompt for the executor taking over. `instructions` replaces the
+/// default request to carry on where the previous agent stopped.
+pub fn handoff_prompt(
+    from_executor: Option<&str>,
+    turns: &[CodingAgentTurn],
+    diffs: &[Diff],
+    instructions: Option<&str>,
+) -> String {
+    let previous = from_executor.map_or_else(
+        || "another coding agent".to_string(),
+        |executor| format!("another coding agent ({executor})"),
+    );
+    let mut prompt = format!(
+        "You are taking over this task from {previous}. Its work so far is already in \
+         this workspace; build on it rather than starting over.\n\n"
+    );
+
+    let turns: Vec<&CodingAgentTurn> = turns
+        .iter()
+        .filter(|turn| turn.prompt.as_deref().is_some_and(|p| !p.trim().is_empty()))
+        .collect();
+    if !turns.is_empty() {
+        prompt.push_str("<previous_turns>\n");
+        let skipped = turns.len().saturating_sub(MAX_TURNS);
+        if skipped > 0 {
+            prompt.push_str(&format!("({skipped} earlier turns omitted)\n"));
+        }
+        for (index, turn) in turns.iter().enumerate().skip(skipped) {
+            let request = turn.prompt.as_deref().unwrap_or_default().trim();
+            prompt.push_str(&format!("{}. User: {request}\n", index + 1));
+            if let Some(outcome) = turn.summary.as_deref().and_then(attempt_summary::summarize) {
+                prompt.push_str(&format!("   Agent: {outcome}\n"));
+            }
+        }
+        prompt.push_str("</previous_turns>\n\n");
+    }
+
+    if diffs.is_empty() {
+        prompt.push_str("No changes have been made yet.\n\n");
+    } else {
+        let digest = diff_digest::digest(diffs, DIFF_BUDGET);
+        prompt.push_str("<changes_so_far>\n");
+        prompt.push_str(digest.text.trim_end());
+        prompt.push_str("\n</changes_so_far>\n\n");
+    }
+
+    match instructions.map(str::trim).filter(|i| !i.is_empty()) {
+        Some(instructions) => prompt.push_str(instructions),
+        None => prompt
+            .push_str("Continue the task from where the previous agent left off and finish it."),
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use utils::diff::DiffChangeKind;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn turn(prompt: &str, summary: Option<&str>) -> CodingAgentTurn {
+        CodingAgentTurn {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            agent_session_id: None,
+            prompt: Some(prompt.to_string()),
+            summary: summary.map(str::to_string),
+            seen: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn replays_turns_and_changes() {
+        let diffs = vec![Diff {
+            change: DiffChangeKind::Added,
+            old_path: None,
+            new_path: Some("src/lib.rs".to_string()),
+            old_content: None,
+            new_content: Some("fn main() {}\n".to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }];
+        let prompt = handoff_prompt(
+            Some("CLAUDE_CODE"),
+            &[
+                turn("Add a parser", Some("Added the parser skeleton.")),
+                turn("Fix the tests", None),
+            ],
+            &diffs,
+            None,
+        );
+
+        assert!(prompt.contains("another coding agent (CLAUDE_CODE)"));
+        assert!(prompt.contains("1. User: Add a parser\n   Agent: Added the parser skeleton."));
+        assert!(prompt.contains("2. User: Fix the tests\n"));
+        assert!(prompt.contains("src/lib.rs"));
+        assert!(prompt.ends_with("finish it."));
+    }
+
+    #[test]
+    fn keeps_only_the_latest_turns() {
+        let turns: Vec<_> = (1..=12).map(|i| turn(&format!("step {i}"), None)).collect();
+        let prompt = handoff_prompt(None, &turns, &[], Some("Only fix the build."));
+
+        assert!(prompt.contains("(2 earlier turns omitted)"));
+        assert!(!prompt.contains("step 2\n"));
+        assert!(prompt.contains("3. User: step 3"));
+        assert!(prompt.contains("No changes have been made yet."));
+        assert!(prompt.ends_with("Only fix the build."));
+    }
+}
@@ -0,0 +1,417 @@
+//! Translations for user-facing strings produced by the server.
+//!
+//! Covers notification texts, API error messages and the language agents are
+//! asked to answer in for default prompts. The locale follows the configured
+//! `UiLanguage`; with `Browser` the server falls back to its own `LANG`, since
+//! it cannot see the browser's preference. Missing translations use English.
+
+use std::sync::{LazyLock, RwLock};
+
+use super::config::UiLanguage;
+
+static CURRENT_LOCALE: LazyLock<RwLock<Locale>> =
+    LazyLock::new(|| RwLock::new(Locale::from_env().unwrap_or_default()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+    Ja,
+    Es,
+    Ko,
+    ZhHans,
+    ZhHant,
+}
+
+impl Locale {
+    pub fn from_language(language: UiLanguage) -> Self {
+        match language {
+            UiLanguage::Browser => Self::from_env().unwrap_or_default(),
+            UiLanguage::En => Self::En,
+            UiLanguage::Fr => Self::Fr,
+            UiLanguage::Ja => Self::Ja,
+            UiLanguage::Es => Self::Es,
+            UiLanguage::Ko => Self::Ko,
+            UiLanguage::ZhHans => Self::ZhHans,
+            UiLanguage::ZhHant => Self::ZhHant,
+        }
+    }
+
+    fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_posix(&value))
+    }
+
+    /// Parse POSIX locale names such as `fr_FR.UTF-8` or `zh_TW`
+    fn from_posix(value: &str) -> Option<Self> {
+        let tag = value
+            .split(['.', '@'])
+            .next()?
+            .replace('-', "_")
+            .to_lowercase();
+        let mut parts = tag.split('_');
+        let language = parts.next()?;
+        let region = parts.next().unwrap_or_default();
+        match language {
+            "en" => Some(Self::En),
+            "fr" => Some(Self::Fr),
+            "ja" => Some(Self::Ja),
+            "es" => Some(Self::Es),
+            "ko" => Some(Self::Ko),
+            "zh" if matches!(region, "tw" | "hk" | "mo" | "hant") => Some(Self::ZhHant),
+            "zh" => Some(Self::ZhHans),
+            _ => None,
+        }
+    }
+
+    /// Locale for the configured language; updated whenever the config is saved
+    pub fn current() -> Self {
+        *CURRENT_LOCALE.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_current(language: UiLanguage) {
+        *CURRENT_LOCALE.write().unwrap_or_else(|e| e.into_inner()) = Self::from_language(language);
+    }
+
+    /// English name of the language, for instructing agents
+    pub fn english_name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::Fr => "French",
+            Self::Ja => "Japanese",
+            Self::Es => "Spanish",
+            Self::Ko => "Korean",
+            Self::ZhHans => "Simplified Chinese",
+            Self::ZhHant => "Traditional Chinese",
+        }
+    }
+}
+
+/// Server-produced strings. Placeholders are written as `{name}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    /// `{task}`
+    TaskCompleteTitle,
+    /// `{task}`, `{branch}`, `{executor}`
+    TaskCompletedBody,
+    /// `{task}`, `{branch}`, `{executor}`
+    TaskFailedBody,
+    /// `{task}`
+    ApprovalNeededTitle,
+    /// `{tool}`
+    ApprovalNeededBody,
+    UnknownTask,
+    ImageInvalidFormat,
+    /// `{size}`, `{max}` in MB
+    ImageTooLarge,
+    ImageNotFound,
+    ImageProcessingFailed,
+    UploadFailed,
+    Unauthorized,
+    RebaseInProgress,
+    RemoteTimeout,
+    RemoteUnavailable,
+    RemoteError,
+    ResourceNotFound,
+    AccessDenied,
+}
+
+impl Text {
+    fn english(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "Task Complete: {task}",
+            Self::TaskCompletedBody => {
+                "✅ '{task}' completed successfully\nBranch: {branch}\nExecutor: {executor}"
+            }
+            Self::TaskFailedBody => {
+                "❌ '{task}' execution failed\nBranch: {branch}\nExecutor: {executor}"
+            }
+            Self::ApprovalNeededTitle => "Approval Needed: {task}",
+            Self::ApprovalNeededBody => "Tool '{tool}' requires approval",
+            Self::UnknownTask => "Unknown task",
+            Self::ImageInvalidFormat => {
+                "This file type is not supported. Please upload an image file (PNG, JPG, GIF, WebP, or BMP)."
+            }
+            Self::ImageTooLarge => {
+                "This image is too large ({size} MB). Maximum file size is {max} MB."
+            }
+            Self::ImageNotFound => "Image not found.",
+            Self::ImageProcessingFailed => "Failed to process image. Please try again.",
+            Self::UploadFailed => {
+                "Failed to upload file. Please ensure the file is valid and try again."
+            }
+            Self::Unauthorized => "Unauthorized. Please sign in again.",
+            Self::RebaseInProgress => {
+                "A rebase is already in progress. Resolve conflicts or abort the rebase, then retry."
+            }
+            Self::RemoteTimeout => "Remote service timeout. Please try again.",
+            Self::RemoteUnavailable => "Remote service unavailable. Please try again.",
+            Self::RemoteError => "Remote service error. Please try again.",
+            Self::ResourceNotFound => "The requested resource was not found.",
+            Self::AccessDenied => "Access denied.",
+        }
+    }
+
+    fn french(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "Tâche terminée : {task}",
+            Self::TaskCompletedBody => {
+                "✅ '{task}' s'est terminée avec succès\nBranche : {branch}\nExécuteur : {executor}"
+            }
+            Self::TaskFailedBody => {
+                "❌ L'exécution de '{task}' a échoué\nBranche : {branch}\nExécuteur : {executor}"
+            }
+            Self::ApprovalNeededTitle => "Approbation requise : {task}",
+            Self::ApprovalNeededBody => "L'outil '{tool}' nécessite une approbation",
+            Self::UnknownTask => "Tâche inconnue",
+            Self::ImageInvalidFormat => {
+                "Ce type de fichier n'est pas pris en charge. Veuillez envoyer une image (PNG, JPG, GIF, WebP ou BMP)."
+            }
+            Self::ImageTooLarge => {
+                "Cette image est trop volumineuse ({size} Mo). La taille maximale est de {max} Mo."
+            }
+            Self::ImageNotFound => "Image introuvable.",
+            Self::ImageProcessingFailed => "Échec du traitement de l'image. Veuillez réessayer.",
+            Self::UploadFailed => {
+                "Échec de l'envoi du fichier. Vérifiez que le fichier est valide et réessayez."
+            }
+            Self::Unauthorized => "Non autorisé. Veuillez vous reconnecter.",
+            Self::RebaseInProgress => {
+                "Un rebase est déjà en cours. Résolvez les conflits ou annulez le rebase, puis réessayez."
+            }
+            Self::RemoteTimeout => "Le service distant ne répond pas. Veuillez réessayer.",
+            Self::RemoteUnavailable => "Service distant indisponible. Veuillez réessayer.",
+            Self::RemoteError => "Erreur du service distant. Veuillez réessayer.",
+            Self::ResourceNotFound => "La ressource demandée est introuvable.",
+            Self::AccessDenied => "Accès refusé.",
+        }
+    }
+
+    fn japanese(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "タスク完了: {task}",
+            Self::TaskCompletedBody => {
+                "✅ '{task}' が正常に完了しました\nブランチ: {branch}\nエグゼキューター: {executor}"
+            }
+            Self::TaskFailedBody => {
+                "❌ '{task}' の実行に失敗しました\nブランチ: {branch}\nエグゼキューター: {executor}"
+            }
+            Self::ApprovalNeededTitle => "承認が必要です: {task}",
+            Self::ApprovalNeededBody => "ツール '{tool}' の実行には承認が必要です",
+            Self::UnknownTask => "不明なタスク",
+            Self::ImageInvalidFormat => {
+                "このファイル形式はサポートされていません。画像ファイル (PNG, JPG, GIF, WebP, BMP) をアップロードしてください。"
+            }
+            Self::ImageTooLarge => {
+                "画像が大きすぎます ({size} MB)。最大ファイルサイズは {max} MB です。"
+            }
+            Self::ImageNotFound => "画像が見つかりません。",
+            Self::ImageProcessingFailed => "画像の処理に失敗しました。もう一度お試しください。",
+            Self::UploadFailed => {
+                "ファイルのアップロードに失敗しました。ファイルが有効か確認して、もう一度お試しください。"
+            }
+            Self::Unauthorized => "認証されていません。もう一度サインインしてください。",
+            Self::RebaseInProgress => {
+                "リベースが既に進行中です。競合を解決するかリベースを中止してから、再試行してください。"
+            }
+            Self::RemoteTimeout => {
+                "リモートサービスがタイムアウトしました。もう一度お試しください。"
+            }
+            Self::RemoteUnavailable => "リモートサービスを利用できません。もう一度お試しください。",
+            Self::RemoteError => "リモートサービスでエラーが発生しました。もう一度お試しください。",
+            Self::ResourceNotFound => "要求されたリソースが見つかりません。",
+            Self::AccessDenied => "アクセスが拒否されました。",
+        }
+    }
+
+    fn spanish(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "Tarea completada: {task}",
+            Self::TaskCompletedBody => {
+                "✅ '{task}' se completó correctamente\nRama: {branch}\nEjecutor: {executor}"
+            }
+            Self::TaskFailedBody => {
+                "❌ La ejecución de '{task}' falló\nRama: {branch}\nEjecutor: {executor}"
+            }
+            Self::ApprovalNeededTitle => "Aprobación necesaria: {task}",
+            Self::ApprovalNeededBody => "La herramienta '{tool}' requiere aprobación",
+            Self::UnknownTask => "Tarea desconocida",
+            Self::ImageInvalidFormat => {
+                "Este tipo de archivo no es compatible. Sube una imagen (PNG, JPG, GIF, WebP o BMP)."
+            }
+            Self::ImageTooLarge => {
+                "Esta imagen es demasiado grande ({size} MB). El tamaño máximo es {max} MB."
+            }
+            Self::ImageNotFound => "Imagen no encontrada.",
+            Self::ImageProcessingFailed => "No se pudo procesar la imagen. Inténtalo de nuevo.",
+            Self::UploadFailed => {
+                "No se pudo subir el archivo. Asegúrate de que el archivo es válido e inténtalo de nuevo."
+            }
+            Self::Unauthorized => "No autorizado. Vuelve a iniciar sesión.",
+            Self::RebaseInProgress => {
+                "Ya hay un rebase en curso. Resuelve los conflictos o cancela el rebase y vuelve a intentarlo."
+            }
+            Self::RemoteTimeout => {
+                "Tiempo de espera agotado en el servicio remoto. Inténtalo de nuevo."
+            }
+            Self::RemoteUnavailable => "Servicio remoto no disponible. Inténtalo de nuevo.",
+            Self::RemoteError => "Error del servicio remoto. Inténtalo de nuevo.",
+            Self::ResourceNotFound => "No se encontró el recurso solicitado.",
+            Self::AccessDenied => "Acceso denegado.",
+        }
+    }
+
+    fn korean(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "작업 완료: {task}",
+            Self::TaskCompletedBody => {
+                "✅ '{task}' 작업이 성공적으로 완료되었습니다\n브랜치: {branch}\n실행기: {executor}"
+            }
+            Self::TaskFailedBody => {
+                "❌ '{task}' 실행에 실패했습니다\n브랜치: {branch}\n실행기: {executor}"
+            }
+            Self::ApprovalNeededTitle => "승인 필요: {task}",
+            Self::ApprovalNeededBody => "'{tool}' 도구를 사용하려면 승인이 필요합니다",
+            Self::UnknownTask => "알 수 없는 작업",
+            Self::ImageInvalidFormat => {
+                "지원되지 않는 파일 형식입니다. 이미지 파일(PNG, JPG, GIF, WebP 또는 BMP)을 업로드해 주세요."
+            }
+            Self::ImageTooLarge => {
+                "이미지가 너무 큽니다({size} MB). 최대 파일 크기는 {max} MB입니다."
+            }
+            Self::ImageNotFound => "이미지를 찾을 수 없습니다.",
+            Self::ImageProcessingFailed => "이미지를 처리하지 못했습니다. 다시 시도해 주세요.",
+            Self::UploadFailed => {
+                "파일을 업로드하지 못했습니다. 파일이 올바른지 확인한 후 다시 시도해 주세요."
+            }
+            Self::Unauthorized => "인증되지 않았습니다. 다시 로그인해 주세요.",
+            Self::RebaseInProgress => {
+                "이미 리베이스가 진행 중입니다. 충돌을 해결하거나 리베이스를 중단한 후 다시 시도해 주세요."
+            }
+            Self::RemoteTimeout => "원격 서비스 응답 시간이 초과되었습니다. 다시 시도해 주세요.",
+            Self::RemoteUnavailable => "원격 서비스를 사용할 수 없습니다. 다시 시도해 주세요.",
+            Self::RemoteError => "원격 서비스 오류입니다. 다시 시도해 주세요.",
+            Self::ResourceNotFound => "요청한 리소스를 찾을 수 없습니다.",
+            Self::AccessDenied => "접근이 거부되었습니다.",
+        }
+    }
+
+    fn simplified_chinese(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "任务完成：{task}",
+            Self::TaskCompletedBody => "✅ '{task}' 已成功完成\n分支：{branch}\n执行器：{executor}",
+            Self::TaskFailedBody => "❌ '{task}' 执行失败\n分支：{branch}\n执行器：{executor}",
+            Self::ApprovalNeededTitle => "需要审批：{task}",
+            Self::ApprovalNeededBody => "工具 '{tool}' 需要审批",
+            Self::UnknownTask => "未知任务",
+            Self::ImageInvalidFormat => {
+                "不支持此文件类型。请上传图片文件（PNG、JPG、GIF、WebP 或 BMP）。"
+            }
+            Self::ImageTooLarge => "图片过大（{size} MB）。最大文件大小为 {max} MB。",
+            Self::ImageNotFound => "未找到图片。",
+            Self::ImageProcessingFailed => "图片处理失败，请重试。",
+            Self::UploadFailed => "文件上传失败。请确认文件有效后重试。",
+            Self::Unauthorized => "未授权。请重新登录。",
+            Self::RebaseInProgress => "已有变基正在进行。请解决冲突或中止变基后重试。",
+            Self::RemoteTimeout => "远程服务超时，请重试。",
+            Self::RemoteUnavailable => "远程服务不可用，请重试。",
+            Self::RemoteError => "远程服务出错，请重试。",
+            Self::ResourceNotFound => "未找到请求的资源。",
+            Self::AccessDenied => "访问被拒绝。",
+        }
+    }
+
+    fn traditional_chinese(self) -> &'static str {
+        match self {
+            Self::TaskCompleteTitle => "任務完成：{task}",
+            Self::TaskCompletedBody => "✅ '{task}' 已成功完成\n分支：{branch}\n執行器：{executor}",
+            Self::TaskFailedBody => "❌ '{task}' 執行失敗\n分支：{branch}\n執行器：{executor}",
+            Self::ApprovalNeededTitle => "需要核准：{task}",
+            Self::ApprovalNeededBody => "工具 '{tool}' 需要核准",
+            Self::UnknownTask => "未知任務",
+            Self::ImageInvalidFormat => {
+                "不支援此檔案類型。請上傳圖片檔案（PNG、JPG、GIF、WebP 或 BMP）。"
+            }
+            Self::ImageTooLarge => "圖片過大（{size} MB）。檔案大小上限為 {max} MB。",
+            Self::ImageNotFound => "找不到圖片。",
+            Self::ImageProcessingFailed => "圖片處理失敗，請再試一次。",
+            Self::UploadFailed => "檔案上傳失敗。請確認檔案有效後再試一次。",
+            Self::Unauthorized => "未授權。請重新登入。",
+            Self::RebaseInProgress => "已有 rebase 正在進行。請解決衝突或中止 rebase 後再試一次。",
+            Self::RemoteTimeout => "遠端服務逾時，請再試一次。",
+            Self::RemoteUnavailable => "遠端服務無法使用，請再試一次。",
+            Self::RemoteError => "遠端服務發生錯誤，請再試一次。",
+            Self::ResourceNotFound => "找不到要求的資源。",
+            Self::AccessDenied => "存取遭拒。",
+        }
+    }
+
+    pub fn in_locale(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => self.english(),
+            Locale::Fr => self.french(),
+            Locale::Ja => self.japanese(),
+            Locale::Es => self.spanish(),
+            Locale::Ko => self.korean(),
+            Locale::ZhHans => self.simplified_chinese(),
+            Locale::ZhHant => self.traditional_chinese(),
+        }
+    }
+}
+
+/// Translate `text` into the current locale
+pub fn tr(text: Text) -> &'static str {
+    text.in_locale(Locale::current())
+}
+
+/// Translate `text` into the current locale, filling in `{name}` placeholders
+pub fn tr_with(text: Text, args: &[(&str, &str)]) -> String {
+    fill(tr(text), args)
+}
+
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |out, (name, value)| {
+            out.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Ask the agent to answer in the user's language. Only applied to built-in
+/// prompts; custom prompts are sent as written.
+pub fn localize_default_prompt(prompt: &str) -> String {
+    match Locale::current() {
+        Locale::En => prompt.to_string(),
+        locale => format!(
+            "{prompt}\n\nWrite your response in {}.",
+            locale.english_name()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_posix_locales() {
+        assert_eq!(Locale::from_posix("fr_FR.UTF-8"), Some(Locale::Fr));
+        assert_eq!(Locale::from_posix("zh_TW"), Some(Locale::ZhHant));
+        assert_eq!(Locale::from_posix("zh_CN.UTF-8"), Some(Locale::ZhHans));
+        assert_eq!(Locale::from_posix("C"), None);
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(
+            fill(
+                Text::ApprovalNeededBody.in_locale(Locale::Es),
+                &[("tool", "bash")]
+            ),
+            "La herramienta 'bash' requiere aprobación"
+        );
+    }
+}
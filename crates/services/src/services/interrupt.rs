@@ -0,0 +1,121 @@
+//! Context carried from an interrupted agent turn into the next follow-up.
+
+use db::models::execution_interrupt::ExecutionInterrupt;
+use executors::logs::{NormalizedEntry, NormalizedEntryType};
+
+/// Keep the tail of long partial output; the latest work matters most
+const MAX_PARTIAL_OUTPUT_CHARS: usize = 8_000;
+
+/// What the agent said and did in its current turn, i.e. after the last user
+/// message. `None` if it had not produced anything yet.
+pub fn partial_output(entries: &[NormalizedEntry]) -> Option<String> {
+    let turn_start = entries
+        .iter()
+        .rposition(|entry| matches!(entry.entry_type, NormalizedEntryType::UserMessage))
+        .map_or(0, |index| index + 1);
+
+    let lines: Vec<String> = entries[turn_start..]
+        .iter()
+        .filter_map(|entry| {
+            let content = entry.content.trim();
+            if content.is_empty() {
+                return None;
+            }
+            match &entry.entry_type {
+                NormalizedEntryType::AssistantMessage => Some(content.to_string()),
+                NormalizedEntryType::ToolUse { tool_name, .. } => {
+                    Some(format!("- [{tool_name}] {content}"))
+                }
+                NormalizedEntryType::ErrorMessage { .. } => Some(format!("- [error] {content}")),
+                _ => None,
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let output = lines.join("\n");
+    let chars = output.chars().count();
+    if chars <= MAX_PARTIAL_OUTPUT_CHARS {
+        return Some(output);
+    }
+    let tail: String = output
+        .chars()
+        .skip(chars - MAX_PARTIAL_OUTPUT_CHARS)
+        .collect();
+    Some(format!("[...]\n{tail}"))
+}
+
+/// Prefix a follow-up prompt with the context of the turn it interrupts
+pub fn resume_prompt(interrupt: &ExecutionInterrupt, prompt: &str) -> String {
+    let mut context =
+        String::from("Your previous turn was interrupted by the user before you finished.");
+    match interrupt.partial_output.as_deref() {
+        Some(output) => {
+            context.push_str(" Here is what you had done so far:\n\n<interrupted_turn>\n");
+            context.push_str(output);
+            context.push_str("\n</interrupted_turn>");
+        }
+        None => context.push_str(" You had not produced any output yet."),
+    }
+    format!("{context}\n\n{prompt}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::logs::{ActionType, ToolStatus};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn partial_output_covers_only_the_interrupted_turn() {
+        let entries = vec![
+            entry(NormalizedEntryType::UserMessage, "first task"),
+            entry(NormalizedEntryType::AssistantMessage, "done with the first"),
+            entry(NormalizedEntryType::UserMessage, "second task"),
+            entry(NormalizedEntryType::Thinking, "hmm"),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Looking at the parser",
+            ),
+            entry(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "Read".to_string(),
+                    action_type: ActionType::FileRead {
+                        path: "src/parser.rs".to_string(),
+                    },
+                    status: ToolStatus::Success,
+                },
+                "src/parser.rs",
+            ),
+        ];
+
+        let output = partial_output(&entries).unwrap();
+        assert_eq!(output, "Looking at the parser\n- [Read] src/parser.rs");
+
+        let interrupt = ExecutionInterrupt {
+            execution_process_id: Uuid::new_v4(),
+            agent_session_id: Some("agent-session".to_string()),
+            partial_output: Some(output),
+            resumed_at: None,
+            created_at: Utc::now(),
+        };
+        let prompt = resume_prompt(&interrupt, "Use the lexer instead");
+        assert!(prompt.contains("<interrupted_turn>\nLooking at the parser"));
+        assert!(prompt.ends_with("\n\nUse the lexer instead"));
+
+        assert_eq!(partial_output(&entries[..3]), None);
+    }
+}
@@ -0,0 +1,734 @@
+//! Persistent background jobs.
+//!
+//! Work that used to run in a detached `tokio::spawn` is stored in the `jobs`
+//! table and picked up by a small worker pool, so it survives a crash or
+//! restart. Failed jobs are retried with exponential backoff; once out of
+//! attempts they stay in the dead-letter state until retried by hand.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use db::{
+    DBService,
+    models::{
+        e2e_test::ProjectE2eSettings,
+        job::{Job, JobStatus},
+        project::Project,
+        repo::Repo,
+        task::Task,
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use serde::{Deserialize, Serialize};
+use sqlx::Error as SqlxError;
+use thiserror::Error;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use super::{
+    coverage::{self, CoverageError},
+    e2e_tests::{self, E2eTestError},
+    git::{GitService, GitServiceError},
+    security_audit::{self, SecurityAuditError},
+    workspace_manager::{WorkspaceError, WorkspaceManager},
+};
+
+const WORKER_COUNT: usize = 2;
+/// How often idle workers look for due retries
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+const COMPLETED_RETENTION_DAYS: i64 = 7;
+
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error(transparent)]
+    Database(#[from] SqlxError),
+    #[error("Invalid job payload: {0}")]
+    Payload(#[from] serde_json::Error),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Coverage(#[from] CoverageError),
+    #[error(transparent)]
+    SecurityAudit(#[from] SecurityAuditError),
+    #[error(transparent)]
+    E2eTests(#[from] E2eTestError),
+}
+
+/// Work the queue knows how to run, stored as the job payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobKind {
+    /// Remove the worktrees of a deleted task, then any repos it orphaned
+    CleanupTask {
+        task_id: Uuid,
+        workspace_dirs: Vec<PathBuf>,
+        repositories: Vec<Repo>,
+    },
+    /// Remove the worktrees of a deleted workspace
+    CleanupWorkspace {
+        workspace_id: Uuid,
+        workspace_dir: PathBuf,
+        repositories: Vec<Repo>,
+    },
+    /// Remove the worktrees of an archived workspace, then its branch
+    DeleteWorkspaceBranch {
+        workspace_id: Uuid,
+        workspace_dir: Option<PathBuf>,
+        branch: String,
+        repositories: Vec<Repo>,
+    },
+    /// Clean up the branch of a merged PR as the project's branch cleanup
+    /// settings ask: delete it from the remote of `remote_repo`, then remove
+    /// the worktrees and the branch from `local_repositories`
+    DeleteMergedBranch {
+        workspace_id: Uuid,
+        branch: String,
+        remote_repo: Option<Repo>,
+        workspace_dir: Option<PathBuf>,
+        local_repositories: Vec<Repo>,
+    },
+    MeasureCoverage {
+        workspace_id: Uuid,
+    },
+    SecurityAudit {
+        workspace_id: Uuid,
+    },
+    /// Run the project's browser tests against the server at `url`, resolved
+    /// when the job is enqueued since dev server output is not persisted
+    RunE2eTests {
+        workspace_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        url: String,
+    },
+}
+
+impl JobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CleanupTask { .. } => "cleanup_task",
+            Self::CleanupWorkspace { .. } => "cleanup_workspace",
+            Self::DeleteWorkspaceBranch { .. } => "delete_workspace_branch",
+            Self::DeleteMergedBranch { .. } => "delete_merged_branch",
+            Self::MeasureCoverage { .. } => "measure_coverage",
+            Self::SecurityAudit { .. } => "security_audit",
+            Self::RunE2eTests { .. } => "run_e2e_tests",
+        }
+    }
+
+    /// Cleanup is idempotent and cheap to retry; coverage, audits and browser
+    /// tests rerun long commands, so a failure goes straight to the
+    /// dead-letter state.
+    fn max_attempts(&self) -> i64 {
+        match self {
+            Self::CleanupTask { .. }
+            | Self::CleanupWorkspace { .. }
+            | Self::DeleteWorkspaceBranch { .. }
+            | Self::DeleteMergedBranch { .. } => 5,
+            Self::MeasureCoverage { .. }
+            | Self::SecurityAudit { .. }
+            | Self::RunE2eTests { .. } => 1,
+        }
+    }
+}
+
+fn retry_delay_secs(attempts: i64) -> i64 {
+    BASE_RETRY_DELAY_SECS << attempts.clamp(1, 6).saturating_sub(1)
+}
+
+#[derive(Clone)]
+pub struct JobService {
+    db: DBService,
+    git: GitService,
+    wake: Arc<Notify>,
+}
+
+impl JobService {
+    pub fn new(db: DBService, git: GitService) -> Self {
+        Self {
+            db,
+            git,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn enqueue(&self, job: JobKind) -> Result<Job, JobError> {
+        let payload = serde_json::to_value(&job)?;
+        let job = Job::enqueue(&self.db.pool, job.name(), &payload, job.max_attempts()).await?;
+        self.wake.notify_one();
+        Ok(job)
+    }
+
+    /// Queue the cleanup of a workspace's branch after its PR into `repo_id`
+    /// was merged, if the project asks for any. Local branches are only
+    /// removed once the workspace is archived, and a branch another
+    /// workspace still uses is kept.
+    pub async fn enqueue_merged_branch_cleanup(
+        &self,
+        workspace: &Workspace,
+        repo_id: Uuid,
+    ) -> Result<(), JobError> {
+        let pool = &self.db.pool;
+        let Some(task) = Task::find_by_id(pool, workspace.task_id).await? else {
+            return Ok(());
+        };
+        let cleanup = Project::find_branch_cleanup(pool, task.project_id).await?;
+        let archived = Workspace::find_by_id(pool, workspace.id)
+            .await?
+            .is_some_and(|workspace| workspace.archived);
+        let delete_local = cleanup.delete_local_branch && archived;
+        if !cleanup.delete_remote_branch && !delete_local {
+            return Ok(());
+        }
+
+        let repositories = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let in_use = self
+            .repos_using_branch_elsewhere(workspace.id, &workspace.branch, &repositories)
+            .await?;
+        let remote_repo = cleanup
+            .delete_remote_branch
+            .then(|| repositories.iter().find(|repo| repo.id == repo_id).cloned())
+            .flatten()
+            .filter(|repo| !in_use.contains(&repo.id));
+        let delete_local = delete_local && in_use.is_empty();
+        if remote_repo.is_none() && !delete_local {
+            return Ok(());
+        }
+        self.enqueue(JobKind::DeleteMergedBranch {
+            workspace_id: workspace.id,
+            branch: workspace.branch.clone(),
+            remote_repo,
+            workspace_dir: delete_local
+                .then(|| workspace.container_ref.clone().map(PathBuf::from))
+                .flatten(),
+            local_repositories: if delete_local {
+                repositories
+            } else {
+                Vec::new()
+            },
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Ids of `repositories` where an unarchived workspace other than
+    /// `workspace_id` still uses `branch`
+    async fn repos_using_branch_elsewhere(
+        &self,
+        workspace_id: Uuid,
+        branch: &str,
+        repositories: &[Repo],
+    ) -> Result<Vec<Uuid>, JobError> {
+        let mut in_use = Vec::new();
+        for repo in repositories {
+            if Workspace::is_branch_used_elsewhere(&self.db.pool, workspace_id, repo.id, branch)
+                .await?
+            {
+                tracing::info!(
+                    "Keeping branch {} in {}: another workspace still uses it",
+                    branch,
+                    repo.name
+                );
+                in_use.push(repo.id);
+            }
+        }
+        Ok(in_use)
+    }
+
+    /// Enqueue from a context that has no way to report the failure
+    pub async fn enqueue_or_log(&self, job: JobKind) {
+        let name = job.name();
+        if let Err(e) = self.enqueue(job).await {
+            tracing::error!("Failed to enqueue {} job: {}", name, e);
+        }
+    }
+
+    pub async fn list(&self, status: Option<JobStatus>, limit: i64) -> Result<Vec<Job>, JobError> {
+        Ok(Job::list(&self.db.read_pool, status, limit).await?)
+    }
+
+    /// Requeue a dead job; `None` if it does not exist or is not dead
+    pub async fn retry(&self, id: Uuid) -> Result<Option<Job>, JobError> {
+        let job = Job::revive(&self.db.pool, id).await?;
+        if job.is_some() {
+            self.wake.notify_one();
+        }
+        Ok(job)
+    }
+
+    /// Requeue work interrupted by the last shutdown and start the workers
+    pub async fn spawn_workers(&self) {
+        match Job::requeue_interrupted(&self.db.pool).await {
+            Ok(count) if count > 0 => tracing::info!("Requeued {} interrupted jobs", count),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to requeue interrupted jobs: {}", e),
+        }
+        if let Err(e) = Job::prune_completed(&self.db.pool, COMPLETED_RETENTION_DAYS).await {
+            tracing::warn!("Failed to prune completed jobs: {}", e);
+        }
+
+        for worker in 0..WORKER_COUNT {
+            let service = self.clone();
+            tokio::spawn(async move { service.run_worker(worker).await });
+        }
+    }
+
+    async fn run_worker(&self, worker: usize) {
+        tracing::debug!("Job worker {} started", worker);
+        loop {
+            match Job::claim_next(&self.db.pool).await {
+                Ok(Some(job)) => self.execute(job).await,
+                Ok(None) => {
+                    tokio::select! {
+                        _ = self.wake.notified() => {}
+                        _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Job worker {} failed to claim a job: {}", worker, e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn execute(&self, job: Job) {
+        tracing::debug!(
+            "Running {} job {} (attempt {})",
+            job.kind,
+            job.id,
+            job.attempts
+        );
+        let result = match serde_json::from_value::<JobKind>(job.payload.0.clone()) {
+            Ok(kind) => self.run(kind).await,
+            Err(e) => Err(e.into()),
+        };
+
+        let outcome = match result {
+            Ok(()) => Job::mark_completed(&self.db.pool, job.id).await,
+            Err(e @ JobError::Payload(_)) => {
+                Job::mark_dead(&self.db.pool, job.id, &e.to_string()).await
+            }
+            Err(e) if job.attempts < job.max_attempts => {
+                let delay = retry_delay_secs(job.attempts);
+                tracing::warn!(
+                    "{} job {} failed, retrying in {}s: {}",
+                    job.kind,
+                    job.id,
+                    delay,
+                    e
+                );
+                Job::schedule_retry(&self.db.pool, job.id, &e.to_string(), delay).await
+            }
+            Err(e) => {
+                tracing::error!("{} job {} failed permanently: {}", job.kind, job.id, e);
+                Job::mark_dead(&self.db.pool, job.id, &e.to_string()).await
+            }
+        };
+        if let Err(e) = outcome {
+            tracing::error!("Failed to record outcome of job {}: {}", job.id, e);
+        }
+    }
+
+    /// Remove the workspace's worktrees, then its branch from each repo
+    async fn delete_local_branch(
+        &self,
+        workspace_id: Uuid,
+        workspace_dir: Option<&Path>,
+        branch: &str,
+        repositories: &[Repo],
+    ) -> Result<(), JobError> {
+        if let Some(workspace_dir) = workspace_dir {
+            WorkspaceManager::cleanup_workspace(workspace_dir, repositories).await?;
+            Workspace::clear_container_ref(&self.db.pool, workspace_id).await?;
+        }
+        for repo in repositories {
+            match self.git.delete_local_branch(&repo.path, branch) {
+                Ok(()) | Err(GitServiceError::BranchNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&self, job: JobKind) -> Result<(), JobError> {
+        let pool = &self.db.pool;
+        match job {
+            JobKind::CleanupTask {
+                task_id,
+                workspace_dirs,
+                repositories,
+            } => {
+                for workspace_dir in &workspace_dirs {
+                    WorkspaceManager::cleanup_workspace(workspace_dir, &repositories).await?;
+                }
+                let orphaned = Repo::delete_orphaned(pool).await?;
+                if orphaned > 0 {
+                    tracing::info!("Deleted {} orphaned repo records", orphaned);
+                }
+                tracing::info!("Cleanup completed for task {}", task_id);
+            }
+            JobKind::CleanupWorkspace {
+                workspace_id,
+                workspace_dir,
+                repositories,
+            } => {
+                WorkspaceManager::cleanup_workspace(&workspace_dir, &repositories).await?;
+                tracing::info!("Cleanup completed for workspace {}", workspace_id);
+            }
+            JobKind::DeleteWorkspaceBranch {
+                workspace_id,
+                workspace_dir,
+                branch,
+                repositories,
+            } => {
+                self.delete_local_branch(
+                    workspace_id,
+                    workspace_dir.as_deref(),
+                    &branch,
+                    &repositories,
+                )
+                .await?;
+                tracing::info!(
+                    "Deleted branch {} of archived workspace {}",
+                    branch,
+                    workspace_id
+                );
+            }
+            JobKind::DeleteMergedBranch {
+                workspace_id,
+                branch,
+                remote_repo,
+                workspace_dir,
+                local_repositories,
+            } => {
+                // A workspace may have picked the branch up since the job was
+                // queued
+                let mut repositories = local_repositories.clone();
+                if let Some(repo) = &remote_repo
+                    && !repositories.iter().any(|local| local.id == repo.id)
+                {
+                    repositories.push(repo.clone());
+                }
+                let in_use = self
+                    .repos_using_branch_elsewhere(workspace_id, &branch, &repositories)
+                    .await?;
+                let remote_repo = remote_repo.filter(|repo| !in_use.contains(&repo.id));
+                let local_repositories = if in_use.is_empty() {
+                    local_repositories
+                } else {
+                    Vec::new()
+                };
+
+                if let Some(repo) = &remote_repo {
+                    match self.git.delete_remote_branch(&repo.path, &branch) {
+                        Ok(true) => tracing::info!(
+                            "Deleted merged branch {} from the remote of {}",
+                            branch,
+                            repo.name
+                        ),
+                        Ok(false) | Err(GitServiceError::BranchNotFound(_)) => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                if !local_repositories.is_empty() {
+                    self.delete_local_branch(
+                        workspace_id,
+                        workspace_dir.as_deref(),
+                        &branch,
+                        &local_repositories,
+                    )
+                    .await?;
+                    tracing::info!(
+                        "Pruned merged branch {} of workspace {}",
+                        branch,
+                        workspace_id
+                    );
+                }
+            }
+            JobKind::MeasureCoverage { workspace_id } => {
+                let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+                    return Ok(());
+                };
+                let reports = coverage::measure_workspace(pool, &workspace).await?;
+                for report in reports.iter().filter(|r| r.auto_pr_blocked) {
+                    tracing::info!(
+                        "Coverage dropped by {:.2} points in workspace {}; auto-PR blocked",
+                        -report.delta.unwrap_or_default(),
+                        workspace.id
+                    );
+                }
+            }
+            JobKind::SecurityAudit { workspace_id } => {
+                let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+                    return Ok(());
+                };
+                let findings = security_audit::audit_workspace(pool, &self.git, &workspace).await?;
+                if !findings.is_empty() {
+                    tracing::info!(
+                        "Security audit found {} issues in workspace {}",
+                        findings.len(),
+                        workspace.id
+                    );
+                }
+            }
+            JobKind::RunE2eTests {
+                workspace_id,
+                execution_process_id,
+                url,
+            } => {
+                let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+                    return Ok(());
+                };
+                let Some(task) = workspace.parent_task(pool).await? else {
+                    return Ok(());
+                };
+                let Some(settings) =
+                    ProjectE2eSettings::find_by_project_id(pool, task.project_id).await?
+                else {
+                    return Ok(());
+                };
+                let report = e2e_tests::run_workspace(
+                    pool,
+                    &workspace,
+                    &settings,
+                    execution_process_id,
+                    &url,
+                )
+                .await?;
+                if let Some(report) = report.filter(|r| r.run.auto_pr_blocked) {
+                    tracing::info!(
+                        "{} browser tests failed in workspace {}; auto-PR blocked",
+                        report.run.failed,
+                        workspace.id
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        project::{CreateProject, ProjectBranchCleanup},
+        task::CreateTask,
+        workspace::CreateWorkspace,
+        workspace_repo::{CreateWorkspaceRepo, CreateWorkspaceRepoCheckout, WorkspaceRepoCheckout},
+    };
+
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially_and_caps() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(2), 60);
+        assert_eq!(retry_delay_secs(3), 120);
+        assert_eq!(retry_delay_secs(50), retry_delay_secs(6));
+    }
+
+    #[test]
+    fn payload_round_trips_with_kind_tag() {
+        let job = JobKind::MeasureCoverage {
+            workspace_id: Uuid::nil(),
+        };
+        let payload = serde_json::to_value(&job).unwrap();
+        assert_eq!(payload["kind"], job.name());
+        assert!(matches!(
+            serde_json::from_value::<JobKind>(payload).unwrap(),
+            JobKind::MeasureCoverage { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_job_is_retried_then_dead_lettered() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let job = Job::enqueue(pool, "cleanup_task", &serde_json::json!({}), 2)
+            .await
+            .unwrap();
+
+        let claimed = Job::claim_next(pool).await.unwrap().unwrap();
+        assert_eq!((claimed.id, claimed.attempts), (job.id, 1));
+        assert!(Job::claim_next(pool).await.unwrap().is_none());
+
+        Job::schedule_retry(pool, job.id, "boom", 0).await.unwrap();
+        let claimed = Job::claim_next(pool).await.unwrap().unwrap();
+        assert_eq!(claimed.attempts, 2);
+        Job::mark_dead(pool, job.id, "boom").await.unwrap();
+
+        let dead = Job::list(pool, Some(JobStatus::Dead), 10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].last_error.as_deref(), Some("boom"));
+
+        let revived = Job::revive(pool, job.id).await.unwrap().unwrap();
+        assert_eq!((revived.status, revived.attempts), (JobStatus::Pending, 0));
+    }
+
+    /// A workspace on `vk/feature` of one repo, in a project with `cleanup`
+    async fn merged_workspace(db: &DBService, cleanup: &ProjectBranchCleanup) -> (Workspace, Repo) {
+        let project = Project::create(
+            &db.pool,
+            &CreateProject {
+                name: "Project".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        Project::update_branch_cleanup(&db.pool, project.id, cleanup)
+            .await
+            .unwrap();
+        let repo = Repo::find_or_create(&db.pool, Path::new("/tmp/vk-test-repo"), "repo")
+            .await
+            .unwrap();
+        let workspace = new_workspace(db, project.id, &repo, "vk/feature", "main").await;
+        (workspace, repo)
+    }
+
+    async fn new_workspace(
+        db: &DBService,
+        project_id: Uuid,
+        repo: &Repo,
+        branch: &str,
+        target_branch: &str,
+    ) -> Workspace {
+        let task = Task::create(
+            &db.pool,
+            &CreateTask::from_title_description(project_id, "Task".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let workspace = Workspace::create(
+            &db.pool,
+            &CreateWorkspace {
+                branch: branch.to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        WorkspaceRepo::create_many(
+            &db.pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: target_branch.to_string(),
+                path_scope: None,
+            }],
+        )
+        .await
+        .unwrap();
+        workspace
+    }
+
+    async fn pending_jobs(db: &DBService) -> Vec<Job> {
+        Job::list(&db.pool, Some(JobStatus::Pending), 10)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn merged_branch_is_deleted_from_the_remote() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let cleanup = ProjectBranchCleanup {
+            delete_remote_branch: true,
+            delete_local_branch: false,
+        };
+        let (workspace, repo) = merged_workspace(&db, &cleanup).await;
+        let jobs = JobService::new(db.clone(), GitService::new());
+
+        jobs.enqueue_merged_branch_cleanup(&workspace, repo.id)
+            .await
+            .unwrap();
+
+        let queued = pending_jobs(&db).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].kind, "delete_merged_branch");
+        assert_eq!(queued[0].payload["branch"], "vk/feature");
+        assert_eq!(queued[0].payload["remote_repo"]["id"], repo.id.to_string());
+    }
+
+    #[tokio::test]
+    async fn merged_branch_is_kept_without_cleanup_settings() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let (workspace, repo) = merged_workspace(&db, &ProjectBranchCleanup::default()).await;
+        let jobs = JobService::new(db.clone(), GitService::new());
+
+        jobs.enqueue_merged_branch_cleanup(&workspace, repo.id)
+            .await
+            .unwrap();
+
+        assert!(pending_jobs(&db).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn merged_branch_is_kept_while_another_workspace_uses_it() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let cleanup = ProjectBranchCleanup {
+            delete_remote_branch: true,
+            delete_local_branch: true,
+        };
+        let (workspace, repo) = merged_workspace(&db, &cleanup).await;
+        Workspace::set_archived(&db.pool, workspace.id, true)
+            .await
+            .unwrap();
+        let task = Task::find_by_id(&db.pool, workspace.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        // Compares the merged branch against its own in an extra checkout
+        let other = new_workspace(&db, task.project_id, &repo, "vk/other", "main").await;
+        WorkspaceRepoCheckout::create(
+            &db.pool,
+            other.id,
+            &other.branch,
+            &CreateWorkspaceRepoCheckout {
+                repo_id: repo.id,
+                alias: "old".to_string(),
+                source_branch: "vk/feature".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let jobs = JobService::new(db.clone(), GitService::new());
+
+        jobs.enqueue_merged_branch_cleanup(&workspace, repo.id)
+            .await
+            .unwrap();
+        assert!(pending_jobs(&db).await.is_empty());
+
+        // Free again once the other workspace is archived
+        Workspace::set_archived(&db.pool, other.id, true)
+            .await
+            .unwrap();
+        jobs.enqueue_merged_branch_cleanup(&workspace, repo.id)
+            .await
+            .unwrap();
+        assert_eq!(pending_jobs(&db).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn interrupted_jobs_are_requeued() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        Job::enqueue(pool, "cleanup_task", &serde_json::json!({}), 3)
+            .await
+            .unwrap();
+        Job::claim_next(pool).await.unwrap().unwrap();
+
+        assert_eq!(Job::requeue_interrupted(pool).await.unwrap(), 1);
+        assert!(Job::claim_next(pool).await.unwrap().is_some());
+    }
+}
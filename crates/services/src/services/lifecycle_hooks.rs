@@ -0,0 +1,180 @@
+//! User-registered hooks called at attempt lifecycle points.
+//!
+//! Each hook receives `{ "event", "project_id", "data" }` as JSON: command
+//! hooks on stdin (with `VK_HOOK_EVENT` set), HTTP hooks as a POST body.
+//! For `pre_*` events a non-zero exit, a non-2xx response or a response of
+//! `{ "allow": false, "message": "..." }` vetoes the action, and a hook that
+//! cannot be run or times out vetoes it too. For other events failures are
+//! only logged.
+
+use std::{process::Stdio, time::Duration};
+
+use db::models::lifecycle_hook::{HookKind, LifecycleEvent, LifecycleHook};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{io::AsyncWriteExt, process::Command};
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Blocked by {event} hook: {message}")]
+    Vetoed {
+        event: &'static str,
+        hook_id: Uuid,
+        message: String,
+    },
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct HookPayload<'a> {
+    event: LifecycleEvent,
+    project_id: Uuid,
+    data: &'a serde_json::Value,
+}
+
+/// Optional body of an HTTP hook response
+#[derive(Debug, Deserialize)]
+struct HookResponse {
+    allow: Option<bool>,
+    message: Option<String>,
+}
+
+/// Run the hooks registered for `event` in `project_id`, in order. Returns
+/// the first veto for `pre_*` events.
+pub async fn run(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    event: LifecycleEvent,
+    data: serde_json::Value,
+) -> Result<(), HookError> {
+    let hooks = LifecycleHook::find_for_event(pool, project_id, event).await?;
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    let payload = serde_json::to_vec(&HookPayload {
+        event,
+        project_id,
+        data: &data,
+    })
+    .unwrap_or_default();
+
+    for hook in hooks {
+        let timeout = Duration::from_secs(hook.timeout_secs.max(1) as u64);
+        let outcome = match hook.kind {
+            HookKind::Command => run_command(&hook.target, event, &payload, timeout).await,
+            HookKind::Http => run_http(&hook.target, &payload, timeout).await,
+        };
+        if let Err(message) = outcome {
+            if event.can_veto() {
+                return Err(HookError::Vetoed {
+                    event: event.as_str(),
+                    hook_id: hook.id,
+                    message,
+                });
+            }
+            tracing::warn!("{} hook {} failed: {}", event.as_str(), hook.id, message);
+        }
+    }
+    Ok(())
+}
+
+/// Run `run` for an event that cannot veto without waiting for the hooks
+pub fn spawn(pool: SqlitePool, project_id: Uuid, event: LifecycleEvent, data: serde_json::Value) {
+    tokio::spawn(async move {
+        if let Err(e) = run(&pool, project_id, event, data).await {
+            tracing::warn!("Failed to run {} hooks: {}", event.as_str(), e);
+        }
+    });
+}
+
+async fn run_command(
+    command_line: &str,
+    event: LifecycleEvent,
+    payload: &[u8],
+    timeout: Duration,
+) -> Result<(), String> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = Command::new(shell_cmd);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("VK_HOOK_EVENT", event.as_str())
+        .arg(shell_arg)
+        .arg(command_line);
+
+    let wait = async {
+        let mut child = command.spawn().map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // Hooks may exit without reading their input
+            let _ = stdin.write_all(payload).await;
+        }
+        child.wait_with_output().await.map_err(|e| e.to_string())
+    };
+    let output = tokio::time::timeout(timeout, wait)
+        .await
+        .map_err(|_| format!("timed out after {}s", timeout.as_secs()))??;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let message = [stderr.trim(), stdout.trim()]
+        .into_iter()
+        .find(|text| !text.is_empty())
+        .map(|text| text.lines().rev().take(5).collect::<Vec<_>>())
+        .map(|lines| lines.into_iter().rev().collect::<Vec<_>>().join("\n"))
+        .unwrap_or_else(|| format!("exited with {}", output.status));
+    Err(message)
+}
+
+async fn run_http(url: &str, payload: &[u8], timeout: Duration) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(timeout)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_vec())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let body = body.trim();
+        return Err(if body.is_empty() {
+            format!("responded with {status}")
+        } else {
+            body.chars().take(500).collect()
+        });
+    }
+    match serde_json::from_str::<HookResponse>(&body) {
+        Ok(HookResponse {
+            allow: Some(false),
+            message,
+        }) => Err(message.unwrap_or_else(|| "denied".to_string())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_exit_code_decides_the_outcome() {
+        let timeout = Duration::from_secs(10);
+        let event = LifecycleEvent::PrePush;
+        assert!(run_command("exit 0", event, b"{}", timeout).await.is_ok());
+        let err = run_command("echo 'not on fridays' >&2; exit 1", event, b"{}", timeout)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "not on fridays");
+    }
+}
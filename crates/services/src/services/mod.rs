@@ -1,27 +1,76 @@
+pub mod agent_instructions;
 pub mod analytics;
 pub mod approvals;
+pub mod artifacts;
+pub mod attachments;
+pub mod attempt_summary;
 pub mod auth;
+pub mod automation_scripts;
+pub mod bounded_command;
+pub mod bootstrap;
+pub mod chat_bridge;
 pub mod config;
 pub mod container;
+pub mod context_pack;
+pub mod coverage;
+pub mod dependency_changes;
+pub mod diagnostics;
+pub mod diff_digest;
+pub mod diff_ignore;
 pub mod diff_stream;
+pub mod e2e_tests;
+pub mod embedding_index;
+pub mod env_templates;
+pub mod environment_snapshot;
 pub mod events;
+pub mod execution_events;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod git_host;
+pub mod handoff;
+pub mod github_app;
+pub mod i18n;
 pub mod image;
+pub mod interrupt;
+pub mod jobs;
+pub mod lifecycle_hooks;
+pub mod multi_user;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod org_config;
+pub mod path_scope;
 pub mod pr_monitor;
 pub mod project;
+pub mod project_report;
+pub mod project_template;
+pub mod preview_capture;
+pub mod prompt_library;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
+pub mod quick_add;
+pub mod release_notes;
 pub mod remote_client;
 pub mod repo;
+pub mod review_checklist;
+pub mod secrets;
+pub mod security_audit;
+pub mod sensitive_files;
 pub mod share;
 pub mod slash_commands;
+pub mod swimlanes;
+pub mod target_sync;
+pub mod task_enrichment;
+pub mod task_fan_out;
+pub mod task_knowledge;
+pub mod traceability;
+pub mod transcription;
+pub mod transcript;
+pub mod visual_regression;
+pub mod workspace_lock;
 pub mod workspace_manager;
 pub mod worktree_manager;
+pub mod worktree_watch;
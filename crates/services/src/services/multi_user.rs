@@ -0,0 +1,176 @@
+//! Multi-user deployment mode.
+//!
+//! By default the server trusts every request and assumes a single local user.
+//! When `VK_MULTI_USER_JWT_SECRET` is set (to the same secret the remote
+//! service signs its access tokens with) every API request must carry a valid
+//! access token. Projects are then owned by the user who created them and
+//! workspaces are created under a per-user directory. Server settings stay
+//! shared and can only be changed in single-user mode.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_owner::ProjectOwner;
+use json_patch::Patch;
+use remote::{JwtError, JwtService};
+use secrecy::SecretString;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::workspace_manager::USER_WORKSPACES_DIR;
+
+pub const JWT_SECRET_ENV: &str = "VK_MULTI_USER_JWT_SECRET";
+
+#[derive(Debug, Error)]
+pub enum MultiUserError {
+    #[error("Missing access token")]
+    MissingToken,
+    #[error("Invalid access token: {0}")]
+    InvalidToken(#[from] JwtError),
+    #[error("Not found")]
+    NotFound,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Authenticated user attached to a request in multi-user mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestUser {
+    pub user_id: Uuid,
+}
+
+#[derive(Clone)]
+pub struct MultiUserService {
+    jwt: JwtService,
+}
+
+impl MultiUserService {
+    /// Enabled only when the JWT secret is configured
+    pub fn from_env() -> Option<Self> {
+        let secret = std::env::var(JWT_SECRET_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty())?;
+        Some(Self {
+            jwt: JwtService::new(SecretString::from(secret)),
+        })
+    }
+
+    pub fn authenticate(&self, token: Option<&str>) -> Result<RequestUser, MultiUserError> {
+        let token = token.ok_or(MultiUserError::MissingToken)?;
+        let details = self.jwt.decode_access_token(token)?;
+        Ok(RequestUser {
+            user_id: details.user_id,
+        })
+    }
+
+    /// Fails with `NotFound` (rather than forbidden) so other users' project
+    /// ids are not disclosed.
+    pub async fn ensure_project_access(
+        pool: &SqlitePool,
+        user: &RequestUser,
+        project_id: Uuid,
+    ) -> Result<(), MultiUserError> {
+        match ProjectOwner::find_owner(pool, project_id).await? {
+            Some(owner) if owner == user.user_id => Ok(()),
+            _ => Err(MultiUserError::NotFound),
+        }
+    }
+
+    pub async fn ensure_workspace_access(
+        pool: &SqlitePool,
+        user: &RequestUser,
+        workspace_id: Uuid,
+    ) -> Result<(), MultiUserError> {
+        match ProjectOwner::find_owner_by_workspace_id(pool, workspace_id).await? {
+            Some(owner) if owner == user.user_id => Ok(()),
+            _ => Err(MultiUserError::NotFound),
+        }
+    }
+
+    pub async fn visible_project_ids(
+        pool: &SqlitePool,
+        user: &RequestUser,
+    ) -> Result<HashSet<Uuid>, MultiUserError> {
+        Ok(ProjectOwner::project_ids_for_user(pool, user.user_id).await?)
+    }
+
+    pub async fn assign_project(
+        pool: &SqlitePool,
+        user: &RequestUser,
+        project_id: Uuid,
+    ) -> Result<(), MultiUserError> {
+        ProjectOwner::assign(pool, project_id, user.user_id).await?;
+        Ok(())
+    }
+
+    /// Drop task entries of other users' projects from a JSON patch. Entries are
+    /// recognised by their `project_id` field; returns `None` when nothing is left.
+    pub async fn scope_task_patch(
+        pool: &SqlitePool,
+        user: &RequestUser,
+        patch: Patch,
+    ) -> Option<Patch> {
+        let owned = Self::visible_project_ids(pool, user).await.ok()?;
+        let is_visible = |value: &Value| match value.get("project_id") {
+            Some(project_id) => project_id
+                .as_str()
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .is_some_and(|id| owned.contains(&id)),
+            None => true,
+        };
+
+        let mut ops = serde_json::to_value(&patch).ok()?;
+        let ops_array = ops.as_array_mut()?;
+        ops_array.retain_mut(|op| match op.get_mut("value") {
+            Some(Value::Object(entries)) if !entries.contains_key("project_id") => {
+                entries.retain(|_, entry| is_visible(entry));
+                true
+            }
+            Some(value) => is_visible(value),
+            None => true,
+        });
+
+        if ops_array.is_empty() {
+            return None;
+        }
+        serde_json::from_value(ops).ok()
+    }
+
+    /// Workspaces of owned projects live under `<base>/users/<user_id>`
+    pub fn user_workspace_dir(base_dir: &Path, user_id: Uuid) -> PathBuf {
+        base_dir.join(USER_WORKSPACES_DIR).join(user_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_workspace_dirs_are_disjoint() {
+        let base = Path::new("/tmp/vibe-kanban/worktrees");
+        let a = MultiUserService::user_workspace_dir(base, Uuid::new_v4());
+        let b = MultiUserService::user_workspace_dir(base, Uuid::new_v4());
+        assert!(a.starts_with(base));
+        assert!(!a.starts_with(&b) && !b.starts_with(&a));
+    }
+
+    #[test]
+    fn rejects_missing_token() {
+        let service = MultiUserService {
+            jwt: JwtService::new(SecretString::from("c2VjcmV0".to_string())),
+        };
+        assert!(matches!(
+            service.authenticate(None),
+            Err(MultiUserError::MissingToken)
+        ));
+        assert!(matches!(
+            service.authenticate(Some("not-a-jwt")),
+            Err(MultiUserError::InvalidToken(_))
+        ));
+    }
+}
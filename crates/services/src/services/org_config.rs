@@ -0,0 +1,109 @@
+//! Baseline config shared by an organization on the remote server.
+//!
+//! The last pulled baseline is cached in the asset dir and always sits
+//! underneath the user's own settings: executor profiles between the
+//! built-in defaults and `profiles.json`, prompts behind the prompt library,
+//! MCP servers only where the user has no server of the same name, and
+//! sensitive file patterns alongside the config's list.
+
+use std::sync::{LazyLock, RwLock};
+
+use executors::profile::ExecutorConfigs;
+use thiserror::Error;
+use utils::{
+    api::organizations::OrganizationSharedConfig,
+    assets::{org_config_path, org_profiles_path},
+};
+use uuid::Uuid;
+
+use super::remote_client::{RemoteClient, RemoteClientError};
+
+static ORG_CONFIG_CACHE: LazyLock<RwLock<Option<OrganizationSharedConfig>>> =
+    LazyLock::new(|| RwLock::new(load()));
+
+#[derive(Debug, Error)]
+pub enum OrgConfigError {
+    #[error(transparent)]
+    Remote(#[from] RemoteClientError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+fn load() -> Option<OrganizationSharedConfig> {
+    let content = std::fs::read_to_string(org_config_path()).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            tracing::warn!("Failed to parse cached organization config: {}", e);
+            None
+        }
+    }
+}
+
+/// Last pulled baseline, if any
+pub fn current() -> Option<OrganizationSharedConfig> {
+    ORG_CONFIG_CACHE.read().unwrap().clone()
+}
+
+/// Prompt template `name` from the baseline
+pub fn prompt(name: &str) -> Option<String> {
+    ORG_CONFIG_CACHE
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|shared| shared.config.prompts.get(name).cloned())
+}
+
+pub fn sensitive_file_patterns() -> Vec<String> {
+    ORG_CONFIG_CACHE
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|shared| shared.config.sensitive_file_patterns.clone())
+        .unwrap_or_default()
+}
+
+/// Fetch `org_id`'s baseline and make it current; `None` when the
+/// organization has not published one, which also clears the cached copy
+pub async fn pull(
+    client: &RemoteClient,
+    org_id: Uuid,
+) -> Result<Option<OrganizationSharedConfig>, OrgConfigError> {
+    let shared = client.get_organization_shared_config(org_id).await?;
+    match &shared {
+        Some(shared) => store(shared).await?,
+        None => clear().await?,
+    }
+    Ok(shared)
+}
+
+async fn store(shared: &OrganizationSharedConfig) -> Result<(), OrgConfigError> {
+    tokio::fs::write(org_config_path(), serde_json::to_string_pretty(shared)?).await?;
+    match &shared.config.executor_profiles {
+        Some(profiles) => {
+            tokio::fs::write(org_profiles_path(), serde_json::to_string_pretty(profiles)?).await?
+        }
+        None => remove_if_exists(&org_profiles_path()).await?,
+    }
+    *ORG_CONFIG_CACHE.write().unwrap() = Some(shared.clone());
+    ExecutorConfigs::reload();
+    Ok(())
+}
+
+/// Stop applying the organization's baseline
+pub async fn clear() -> Result<(), OrgConfigError> {
+    remove_if_exists(&org_config_path()).await?;
+    remove_if_exists(&org_profiles_path()).await?;
+    *ORG_CONFIG_CACHE.write().unwrap() = None;
+    ExecutorConfigs::reload();
+    Ok(())
+}
+
+async fn remove_if_exists(path: &std::path::Path) -> Result<(), std::io::Error> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
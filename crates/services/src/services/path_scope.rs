@@ -0,0 +1,98 @@
+//! Path scopes limit an attempt to one directory of a monorepo.
+//!
+//! A scoped repo is checked out sparsely (cone mode, so files at the root and
+//! along the way to the scope stay available to tooling), its diffs only show
+//! changes inside the scope, and a single-repo attempt runs its coding agent
+//! from the scope directory.
+
+use std::path::Path;
+
+use utils::validation::ValidationErrors;
+
+use super::git::{GitCli, GitCliError};
+
+/// `scope` without surrounding whitespace and slashes, with `/` separators;
+/// `None` when that leaves nothing, which means the whole repo
+pub fn normalize(scope: &str) -> Option<String> {
+    let scope = scope.trim().replace('\\', "/");
+    let parts: Vec<&str> = scope
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// A relative directory that stays inside the repo
+pub fn validate(errors: &mut ValidationErrors, field: &str, scope: &str) {
+    let trimmed = scope.trim();
+    if trimmed.starts_with('/') || trimmed.starts_with('\\') || trimmed.contains(':') {
+        errors.add(field, "must be relative to the repo root");
+    } else if trimmed
+        .split(['/', '\\'])
+        .any(|part| part == ".." || part == ".git")
+    {
+        errors.add(field, "must stay inside the repo");
+    }
+}
+
+/// Whether a repo-relative path lies inside `scope`
+pub fn contains(scope: &str, path: &str) -> bool {
+    let path = path.trim_start_matches('/');
+    path == scope
+        || path
+            .strip_prefix(scope)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Working directory of the coding agent for a single-repo attempt
+pub fn agent_working_dir(repo_name: &str, scope: Option<&str>) -> String {
+    match scope {
+        Some(scope) => format!("{repo_name}/{scope}"),
+        None => repo_name.to_string(),
+    }
+}
+
+/// Whether the worktree already has a sparse-checkout of its own. Worktrees
+/// keep it in their private git dir, so the main checkout is not affected.
+fn has_sparse_checkout(worktree: &Path) -> bool {
+    git2::Repository::open(worktree)
+        .map(|repo| repo.path().join("info").join("sparse-checkout").exists())
+        .unwrap_or(false)
+}
+
+/// Limit the worktree's checkout to `scope` unless it already is sparse
+pub fn apply_sparse_checkout(
+    git: &GitCli,
+    worktree: &Path,
+    scope: &str,
+) -> Result<bool, GitCliError> {
+    if has_sparse_checkout(worktree) {
+        return Ok(false);
+    }
+    git.sparse_checkout_set(worktree, &[scope])?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_and_matches_scopes() {
+        assert_eq!(
+            normalize(" /packages\\web/ "),
+            Some("packages/web".to_string())
+        );
+        assert_eq!(normalize("./"), None);
+        assert!(contains("packages/web", "packages/web/src/main.ts"));
+        assert!(contains("packages/web", "packages/web"));
+        assert!(!contains("packages/web", "packages/website/index.ts"));
+        assert!(!contains("packages/web", "package.json"));
+
+        let mut errors = ValidationErrors::new();
+        validate(&mut errors, "scope", "packages/../secrets");
+        validate(&mut errors, "scope", "/etc");
+        validate(&mut errors, "scope", "packages/web");
+        assert_eq!(errors.errors.len(), 2);
+    }
+}
@@ -17,6 +17,9 @@ use tracing::{debug, error, info};
 use crate::services::{
     analytics::AnalyticsContext,
     git_host::{self, GitHostError, GitHostProvider},
+    jobs::JobService,
+    share::SharePublisher,
+    task_fan_out,
 };
 
 #[derive(Debug, Error)]
@@ -34,17 +37,23 @@ pub struct PrMonitorService {
     db: DBService,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
+    jobs: JobService,
+    share_publisher: Option<SharePublisher>,
 }
 
 impl PrMonitorService {
     pub async fn spawn(
         db: DBService,
         analytics: Option<AnalyticsContext>,
+        jobs: JobService,
+        share_publisher: Option<SharePublisher>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
+            jobs,
+            share_publisher,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -110,6 +119,16 @@ impl PrMonitorService {
             )
             .await?;
 
+            // Let teammates on a shared task see the PR was merged or closed
+            if let Some(publisher) = &self.share_publisher
+                && let Err(e) = publisher.publish_attempt(pr_merge.workspace_id).await
+            {
+                debug!(
+                    "Failed to publish PR status for workspace {}: {}",
+                    pr_merge.workspace_id, e
+                );
+            }
+
             // If the PR was merged, update the task status to done
             if matches!(&pr_status.status, MergeStatus::Merged)
                 && let Some(workspace) =
@@ -120,12 +139,31 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                if let Err(e) =
+                    task_fan_out::sync_parent_status(&self.db.pool, workspace.task_id).await
+                {
+                    error!(
+                        "Failed to update fan-out parent of task {}: {}",
+                        workspace.task_id, e
+                    );
+                }
 
                 // Archive workspace unless pinned
                 if !workspace.pinned {
                     Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
                 }
 
+                if let Err(e) = self
+                    .jobs
+                    .enqueue_merged_branch_cleanup(&workspace, pr_merge.repo_id)
+                    .await
+                {
+                    error!(
+                        "Failed to queue branch cleanup for workspace {}: {}",
+                        workspace.id, e
+                    );
+                }
+
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
                     && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
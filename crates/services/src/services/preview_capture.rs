@@ -0,0 +1,241 @@
+//! Optional screenshot of the dev server taken after an attempt finishes.
+//!
+//! Projects with preview settings have the running dev server of attempts on
+//! tasks carrying their label captured by a configurable command, headless
+//! Chromium by default. The screenshot is kept as an attempt artifact so
+//! reviewers can see the change without checking out the branch.
+
+use std::{process::Stdio, sync::LazyLock, time::Duration};
+
+use chrono::Utc;
+use db::models::{
+    attempt_artifact::AttemptArtifact, preview::ProjectPreviewSettings, task::Task,
+    task_label::TaskLabel,
+};
+use regex::Regex;
+use sqlx::SqlitePool;
+use tempfile::TempPath;
+use thiserror::Error;
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use super::artifacts::{self, ArtifactError};
+
+/// Used when no command is configured
+pub const DEFAULT_COMMAND: &str = "chromium --headless=new --disable-gpu --hide-scrollbars --window-size=1280,800 --screenshot={output} {url}";
+
+/// Directory inside the attempt's artifacts that screenshots are kept in
+pub const PREVIEWS_DIR: &str = "previews";
+
+/// Page loads can be slow on a dev build, but a screenshot is never worth
+/// more than a minute
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(60);
+
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap());
+static LOCAL_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(https?)://(localhost|127\.0\.0\.1|0\.0\.0\.0|\[[0-9a-f:]+\]|\d{1,3}(?:\.\d{1,3}){3})(:\d{2,5})?(/[^\s'\x22]*)?",
+    )
+    .unwrap()
+});
+static HOST_PORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[[0-9a-f:]+\]|(?:\d{1,3}\.){3}\d{1,3}):(\d{2,5})")
+        .unwrap()
+});
+
+#[derive(Debug, Error)]
+pub enum PreviewError {
+    #[error("No running dev server with a detectable URL")]
+    NoDevServer,
+    #[error("Screenshot command failed: {0}")]
+    Command(String),
+    #[error("Screenshot command timed out")]
+    Timeout,
+    #[error("Screenshot command did not write an image")]
+    ScreenshotMissing,
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// First local URL a dev server printed, such as `http://localhost:5173/`.
+/// Wildcard binds are reached through localhost.
+pub fn detect_dev_server_url(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = ANSI_ESCAPE.replace_all(line, "");
+        if let Some(caps) = LOCAL_URL.captures(&line) {
+            let host = match &caps[2] {
+                "0.0.0.0" | "[::]" => "localhost",
+                host => host,
+            };
+            return Some(format!(
+                "{}://{}{}{}",
+                caps[1].to_lowercase(),
+                host,
+                caps.get(3).map_or("", |m| m.as_str()),
+                caps.get(4).map_or("/", |m| m.as_str()),
+            ));
+        }
+        HOST_PORT.captures(&line).map(|caps| {
+            let scheme = if line.to_lowercase().contains("https") {
+                "https"
+            } else {
+                "http"
+            };
+            format!("{scheme}://localhost:{}/", &caps[1])
+        })
+    })
+}
+
+/// Whether runs of `task` are captured automatically. An empty label
+/// captures every task of the project.
+pub async fn applies_to(
+    pool: &SqlitePool,
+    settings: &ProjectPreviewSettings,
+    task: &Task,
+) -> Result<bool, sqlx::Error> {
+    let label = settings.label.trim();
+    if label.is_empty() {
+        return Ok(true);
+    }
+    Ok(TaskLabel::find_by_task_id(pool, task.id)
+        .await?
+        .iter()
+        .any(|task_label| task_label.name.trim().eq_ignore_ascii_case(label)))
+}
+
+/// Screenshot `url` with `command`, or headless Chromium when it is empty,
+/// and keep it as an artifact of the attempt
+pub async fn capture(
+    pool: &SqlitePool,
+    command: &str,
+    workspace_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    url: &str,
+) -> Result<AttemptArtifact, PreviewError> {
+    let output = screenshot(command, url).await?;
+    let name = format!(
+        "{PREVIEWS_DIR}/preview-{}.png",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let artifact =
+        artifacts::register(pool, workspace_id, execution_process_id, &output, &name).await?;
+    tracing::info!("Captured preview of {} for workspace {}", url, workspace_id);
+    Ok(artifact)
+}
+
+/// Screenshot `url` into a temporary PNG, removed when the path is dropped
+pub async fn screenshot(command: &str, url: &str) -> Result<TempPath, PreviewError> {
+    let output = tempfile::Builder::new()
+        .prefix("vk-preview-")
+        .suffix(".png")
+        .tempfile()?
+        .into_temp_path();
+    // Chromium refuses to overwrite some files; let it create its own
+    std::fs::remove_file(&output)?;
+
+    let template = if command.trim().is_empty() {
+        DEFAULT_COMMAND
+    } else {
+        command
+    };
+    let command_line = render_command(template, url, &output.to_string_lossy());
+    run(&command_line).await?;
+
+    if !tokio::fs::try_exists(&output).await? {
+        return Err(PreviewError::ScreenshotMissing);
+    }
+    Ok(output)
+}
+
+/// Most recent screenshot of the attempt, if any was taken
+pub async fn latest(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Option<AttemptArtifact>, sqlx::Error> {
+    Ok(AttemptArtifact::find_by_workspace_id(pool, workspace_id)
+        .await?
+        .into_iter()
+        .filter(|artifact| artifact.name.starts_with(&format!("{PREVIEWS_DIR}/")))
+        .max_by_key(|artifact| artifact.created_at))
+}
+
+/// Fill the `{url}` and `{output}` placeholders, quoted for the shell
+fn render_command(template: &str, url: &str, output: &str) -> String {
+    let quote = |value: &str| {
+        shlex::try_quote(value)
+            .map(|quoted| quoted.into_owned())
+            .unwrap_or_else(|_| value.to_string())
+    };
+    template
+        .replace("{url}", &quote(url))
+        .replace("{output}", &quote(output))
+}
+
+async fn run(command_line: &str) -> Result<(), PreviewError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = Command::new(shell_cmd);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .arg(shell_arg)
+        .arg(command_line);
+
+    let output = tokio::time::timeout(CAPTURE_TIMEOUT, command.output())
+        .await
+        .map_err(|_| PreviewError::Timeout)??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+        return Err(PreviewError::Command(
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_dev_server_urls() {
+        assert_eq!(
+            detect_dev_server_url("  \x1b[32m➜\x1b[39m  Local:   http://localhost:5173/"),
+            Some("http://localhost:5173/".to_string())
+        );
+        assert_eq!(
+            detect_dev_server_url("compiling...\nListening on http://0.0.0.0:3000"),
+            Some("http://localhost:3000/".to_string())
+        );
+        assert_eq!(
+            detect_dev_server_url("Server ready on 127.0.0.1:8080"),
+            Some("http://localhost:8080/".to_string())
+        );
+        assert_eq!(detect_dev_server_url("Compiled successfully"), None);
+    }
+
+    #[test]
+    fn quotes_placeholders() {
+        let rendered = render_command(
+            "chromium --screenshot={output} {url}",
+            "http://localhost:3000/?a=1&b=2",
+            "/tmp/shot 1.png",
+        );
+        assert_eq!(
+            shlex::split(&rendered).unwrap(),
+            [
+                "chromium",
+                "--screenshot=/tmp/shot 1.png",
+                "http://localhost:3000/?a=1&b=2"
+            ]
+        );
+    }
+}
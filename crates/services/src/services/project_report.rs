@@ -0,0 +1,119 @@
+//! Markdown activity reports for standup notes.
+//!
+//! Built entirely from the project's own records; task lines reuse the
+//! attempt summaries taken from the agents' final messages, so the report
+//! reads as prose without another model call.
+
+use db::models::project_report::{ProjectActivity, ReportPullRequest, ReportTask};
+
+pub fn render_markdown(project_name: &str, activity: &ProjectActivity) -> String {
+    let mut out = format!(
+        "# {project_name}: agent activity since {}\n\n",
+        activity.since.format("%Y-%m-%d %H:%M UTC")
+    );
+    out.push_str(&format!(
+        "{} completed, {} PRs opened, {} PRs merged, {} failed attempts, {} awaiting review.\n",
+        activity.completed_tasks.len(),
+        activity.prs_opened.len(),
+        activity.prs_merged.len(),
+        activity.failed_attempts.len(),
+        activity.pending_reviews.len(),
+    ));
+
+    section(&mut out, "Completed", &activity.completed_tasks, task_line);
+    section(&mut out, "PRs opened", &activity.prs_opened, pr_line);
+    section(&mut out, "PRs merged", &activity.prs_merged, pr_line);
+    section(
+        &mut out,
+        "Failed attempts",
+        &activity.failed_attempts,
+        |a| {
+            let runs = if a.failed_runs == 1 {
+                String::new()
+            } else {
+                format!(", {} failed runs", a.failed_runs)
+            };
+            format!(
+                "- {} (`{}`{}{runs})",
+                a.task_title,
+                a.branch,
+                a.executor
+                    .as_deref()
+                    .map(|e| format!(", {e}"))
+                    .unwrap_or_default(),
+            )
+        },
+    );
+    section(
+        &mut out,
+        "Awaiting review",
+        &activity.pending_reviews,
+        task_line,
+    );
+    out
+}
+
+fn section<T>(out: &mut String, heading: &str, items: &[T], line: impl Fn(&T) -> String) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n## {heading}\n\n"));
+    for item in items {
+        out.push_str(&line(item));
+        out.push('\n');
+    }
+}
+
+fn task_line(task: &ReportTask) -> String {
+    match &task.summary {
+        Some(summary) => format!("- {}: {}", task.title, summary),
+        None => format!("- {}", task.title),
+    }
+}
+
+fn pr_line(pr: &ReportPullRequest) -> String {
+    format!("- [#{}]({}) {}", pr.pr_number, pr.pr_url, pr.task_title)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use db::models::project_report::ReportFailedAttempt;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn renders_only_non_empty_sections() {
+        let at = Utc.with_ymd_and_hms(2026, 1, 5, 9, 0, 0).unwrap();
+        let activity = ProjectActivity {
+            since: at,
+            completed_tasks: vec![ReportTask {
+                id: Uuid::nil(),
+                title: "Retry uploads".to_string(),
+                summary: Some("Added a retry to the uploader.".to_string()),
+                updated_at: at,
+            }],
+            prs_opened: vec![],
+            prs_merged: vec![],
+            failed_attempts: vec![ReportFailedAttempt {
+                task_id: Uuid::nil(),
+                task_title: "Fix login".to_string(),
+                workspace_id: Uuid::nil(),
+                branch: "vk/fix-login".to_string(),
+                executor: Some("CLAUDE_CODE".to_string()),
+                failed_runs: 2,
+                last_failed_at: at,
+            }],
+            pending_reviews: vec![],
+        };
+
+        assert_eq!(
+            render_markdown("Demo", &activity),
+            "# Demo: agent activity since 2026-01-05 09:00 UTC\n\n\
+             1 completed, 0 PRs opened, 0 PRs merged, 1 failed attempts, 0 awaiting review.\n\
+             \n## Completed\n\n- Retry uploads: Added a retry to the uploader.\n\
+             \n## Failed attempts\n\n- Fix login (`vk/fix-login`, CLAUDE_CODE, 2 failed runs)\n"
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! New projects started from a template repository.
+//!
+//! A GitHub template repo becomes a new GitHub repo through
+//! `gh repo create --template`, which is then cloned. Any other git URL is
+//! cloned and re-initialised so that the template's history does not come
+//! along. The new repo is registered as the project's only repository, and
+//! when it has a `tasks.yaml` at its root the board is seeded with the starter
+//! tasks listed there:
+//!
+//! ```yaml
+//! tasks:
+//!   - title: Set up CI
+//!     description: Run the tests on every push
+//!     labels: [chore]
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    project::{CreateProject, Project},
+    project_repo::CreateProjectRepo,
+    repo::Repo,
+    task::{CreateTask, Task},
+    task_label::{CreateTaskLabel, TaskLabel},
+};
+use gray_matter::engine::Engine;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::validation::{
+    MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, MAX_TITLE_CHARS, Validate, ValidationErrors,
+};
+use uuid::Uuid;
+
+use super::{
+    bootstrap::default_label_color,
+    git::{GitCli, GitService, GitServiceError},
+    git_host::github::GhCli,
+    project::{ProjectService, ProjectServiceError},
+    repo::RepoService,
+};
+
+/// Files looked for at the root of the new repo, in order
+const TASK_FILES: [&str; 2] = ["tasks.yaml", "tasks.yml"];
+const MAX_STARTER_TASKS: usize = 200;
+
+#[derive(Debug, Error)]
+pub enum ProjectTemplateError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Project(#[from] ProjectServiceError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Failed to clone template: {0}")]
+    Clone(String),
+    #[error("{0}")]
+    GitHub(String),
+    #[error("Path does not exist: {0}")]
+    PathNotFound(PathBuf),
+    #[error("Directory already exists: {0}")]
+    DirectoryAlreadyExists(PathBuf),
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProjectTemplateSource {
+    /// A GitHub template repo, `OWNER/NAME`; a new GitHub repo named after the
+    /// folder is created from it under the account `gh` is logged into
+    Github { template: String, private: bool },
+    /// Any git URL; its files become the first commit of a fresh repo
+    Git { url: String },
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectFromTemplate {
+    pub name: String,
+    /// Directory the new repo is created in
+    pub parent_path: String,
+    pub folder_name: String,
+    pub source: ProjectTemplateSource,
+    /// Create the starter tasks listed in the template's `tasks.yaml`
+    pub seed_tasks: bool,
+}
+
+impl Validate for CreateProjectFromTemplate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", &self.name, MAX_NAME_CHARS);
+        errors.required_text("parent_path", &self.parent_path, 4096);
+        let folder = self.folder_name.trim();
+        if folder.is_empty() || folder == "." || folder == ".." || folder.contains(['/', '\\']) {
+            errors.add("folder_name", "must be a single directory name");
+        }
+        match &self.source {
+            ProjectTemplateSource::Github { template, .. } => {
+                let parts: Vec<&str> = template.trim().split('/').collect();
+                if parts.len() != 2 || parts.iter().any(|part| part.is_empty()) {
+                    errors.add("source.template", "must be OWNER/NAME");
+                }
+            }
+            ProjectTemplateSource::Git { url } => {
+                let url = url.trim();
+                if url.is_empty() {
+                    errors.add("source.url", "must not be empty");
+                } else if url.starts_with('-') {
+                    errors.add("source.url", "must be a git URL");
+                }
+            }
+        }
+        errors.into_result()
+    }
+}
+
+/// What was created for a project started from a template
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectFromTemplate {
+    pub project: Project,
+    pub repo: Repo,
+    pub tasks: Vec<Task>,
+    /// Why the template's `tasks.yaml` could not be read, if it could not
+    pub tasks_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StarterTasks {
+    #[serde(default)]
+    pub tasks: Vec<StarterTask>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StarterTask {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Label names; missing labels are created
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl StarterTasks {
+    pub fn from_yaml(content: &str) -> Result<Self, String> {
+        let tasks: Self = gray_matter::engine::YAML::parse(content)
+            .deserialize()
+            .map_err(|e| e.to_string())?;
+        if tasks.tasks.len() > MAX_STARTER_TASKS {
+            return Err(format!("at most {MAX_STARTER_TASKS} tasks are supported"));
+        }
+        if let Some(i) = tasks.tasks.iter().position(|task| {
+            task.title.trim().is_empty() || task.title.chars().count() > MAX_TITLE_CHARS
+        }) {
+            return Err(format!(
+                "task {} needs a title of at most {MAX_TITLE_CHARS} characters",
+                i + 1
+            ));
+        }
+        Ok(tasks)
+    }
+}
+
+/// Bring the template's files into `target`, a directory that does not exist
+/// yet under `parent`
+fn fetch_template(
+    git: &GitService,
+    source: &ProjectTemplateSource,
+    parent: &Path,
+    folder_name: &str,
+) -> Result<(), ProjectTemplateError> {
+    let target = parent.join(folder_name);
+    match source {
+        ProjectTemplateSource::Github { template, private } => GhCli::new()
+            .create_repo_from_template(template.trim(), folder_name, *private, parent)
+            .map_err(|e| ProjectTemplateError::GitHub(e.to_string())),
+        ProjectTemplateSource::Git { url } => {
+            GitCli::new()
+                .git(
+                    parent,
+                    ["clone", "--depth", "1", "--", url.trim(), folder_name],
+                )
+                .map_err(|e| ProjectTemplateError::Clone(e.to_string()))?;
+            std::fs::remove_dir_all(target.join(".git"))?;
+            git.initialize_repo_with_main_branch(&target)?;
+            git.commit(&target, "Start from template")?;
+            Ok(())
+        }
+    }
+}
+
+fn read_starter_tasks(repo_path: &Path) -> Option<Result<StarterTasks, String>> {
+    TASK_FILES.iter().find_map(|file| {
+        let content = std::fs::read_to_string(repo_path.join(file)).ok()?;
+        Some(StarterTasks::from_yaml(&content).map_err(|e| format!("{file}: {e}")))
+    })
+}
+
+/// Create the starter tasks, creating labels they refer to by name
+pub async fn seed_tasks(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    starter: &StarterTasks,
+) -> Result<Vec<Task>, sqlx::Error> {
+    // Labels are created up front so the transaction below is the only writer
+    let mut labels = TaskLabel::find_by_project_id(pool, project_id).await?;
+    for name in starter
+        .tasks
+        .iter()
+        .flat_map(|task| task.labels.iter().map(|name| name.trim()))
+    {
+        if name.is_empty()
+            || name.chars().count() > MAX_NAME_CHARS
+            || labels.iter().any(|label| label.name == name)
+        {
+            continue;
+        }
+        let label = TaskLabel::create(
+            pool,
+            &CreateTaskLabel {
+                project_id,
+                name: name.to_string(),
+                color: default_label_color(),
+            },
+        )
+        .await?;
+        labels.push(label);
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut tasks = Vec::with_capacity(starter.tasks.len());
+    for starter_task in &starter.tasks {
+        let label_ids: Vec<Uuid> = labels
+            .iter()
+            .filter(|label| {
+                starter_task
+                    .labels
+                    .iter()
+                    .any(|name| name.trim() == label.name)
+            })
+            .map(|label| label.id)
+            .collect();
+        let description = starter_task
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(|description| description.chars().take(MAX_DESCRIPTION_CHARS).collect());
+        let task = Task::create(
+            &mut *tx,
+            &CreateTask::from_title_description(
+                project_id,
+                starter_task.title.trim().to_string(),
+                description,
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        TaskLabel::associate_many(&mut *tx, task.id, &label_ids).await?;
+        tasks.push(task);
+    }
+    tx.commit().await?;
+    Ok(tasks)
+}
+
+/// Create the repo from the template, register it as a new project and seed
+/// the project's board
+pub async fn create_project(
+    pool: &SqlitePool,
+    project_service: &ProjectService,
+    repo_service: &RepoService,
+    git: &GitService,
+    data: &CreateProjectFromTemplate,
+) -> Result<ProjectFromTemplate, ProjectTemplateError> {
+    let parent = repo_service.normalize_path(&data.parent_path)?;
+    if !parent.is_dir() {
+        return Err(ProjectTemplateError::PathNotFound(parent));
+    }
+    let folder_name = data.folder_name.trim().to_string();
+    let target = parent.join(&folder_name);
+    if target.exists() {
+        return Err(ProjectTemplateError::DirectoryAlreadyExists(target));
+    }
+
+    tokio::task::spawn_blocking({
+        let git = git.clone();
+        let source = data.source.clone();
+        let parent = parent.clone();
+        let folder_name = folder_name.clone();
+        move || fetch_template(&git, &source, &parent, &folder_name)
+    })
+    .await
+    .map_err(|e| ProjectTemplateError::Io(std::io::Error::other(e)))??;
+
+    let project = project_service
+        .create_project(
+            pool,
+            repo_service,
+            CreateProject {
+                name: data.name.trim().to_string(),
+                repositories: vec![CreateProjectRepo {
+                    display_name: folder_name,
+                    git_repo_path: target.to_string_lossy().to_string(),
+                }],
+            },
+        )
+        .await?;
+    let repo = project_service
+        .get_repositories(pool, project.id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ProjectServiceError::RepositoryNotFound)?;
+
+    let (tasks, tasks_error) = match data
+        .seed_tasks
+        .then(|| read_starter_tasks(&target))
+        .flatten()
+    {
+        Some(Ok(starter)) => (seed_tasks(pool, project.id, &starter).await?, None),
+        Some(Err(e)) => {
+            tracing::warn!("Skipped starter tasks of project {}: {}", project.id, e);
+            (Vec::new(), Some(e))
+        }
+        None => (Vec::new(), None),
+    };
+
+    Ok(ProjectFromTemplate {
+        project,
+        repo,
+        tasks,
+        tasks_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_starter_tasks() {
+        let starter = StarterTasks::from_yaml(
+            "tasks:\n  - title: Set up CI\n    labels: [chore]\n  - title: Write a README\n    description: What it is and how to run it\n",
+        )
+        .unwrap();
+        assert_eq!(starter.tasks.len(), 2);
+        assert_eq!(starter.tasks[0].labels, vec!["chore".to_string()]);
+        assert!(starter.tasks[0].description.is_none());
+        assert_eq!(
+            starter.tasks[1].description.as_deref(),
+            Some("What it is and how to run it")
+        );
+
+        assert!(StarterTasks::from_yaml("tasks:\n  - title: \"\"\n").is_err());
+        assert!(StarterTasks::from_yaml("tasks: nope\n").is_err());
+    }
+}
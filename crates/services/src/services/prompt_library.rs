@@ -0,0 +1,92 @@
+//! Rendering and lookup for prompt templates stored in the prompt library.
+//!
+//! Templates use `{name}` placeholders, the same syntax as the built-in
+//! prompts. Unknown placeholders are left untouched so a preview shows
+//! exactly what would be sent.
+
+use std::collections::HashMap;
+
+use db::models::prompt_template::PromptTemplate;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::org_config;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RenderedPrompt {
+    pub rendered: String,
+    /// Placeholders found in the template, in order of first use
+    pub placeholders: Vec<String>,
+    /// Placeholders without a value, left as-is in the output
+    pub missing: Vec<String>,
+}
+
+/// Names of the `{name}` placeholders in `template`, deduplicated
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[..end];
+        if !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !names.iter().any(|n| n == name)
+        {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+pub fn render(template: &str, variables: &HashMap<String, String>) -> RenderedPrompt {
+    let placeholders = placeholders(template);
+    let mut rendered = template.to_string();
+    let mut missing = Vec::new();
+    for name in &placeholders {
+        match variables.get(name) {
+            Some(value) => rendered = rendered.replace(&format!("{{{name}}}"), value),
+            None => missing.push(name.clone()),
+        }
+    }
+    RenderedPrompt {
+        rendered,
+        placeholders,
+        missing,
+    }
+}
+
+/// Content of the library template `name` for a project, falling back to the
+/// global template and then the organization's shared prompts; `None` when
+/// none of them has it
+pub async fn resolve(pool: &SqlitePool, name: &str, project_id: Option<Uuid>) -> Option<String> {
+    match PromptTemplate::find_effective(pool, name, project_id).await {
+        Ok(Some(template)) => Some(template.content),
+        Ok(None) => org_config::prompt(name),
+        Err(e) => {
+            tracing::warn!("Failed to look up prompt template '{}': {}", name, e);
+            org_config::prompt(name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_and_reports_missing() {
+        let variables = HashMap::from([("pr_number".to_string(), "42".to_string())]);
+        let result = render(
+            "PR #{pr_number} at {pr_url} {not a placeholder}",
+            &variables,
+        );
+        assert_eq!(result.rendered, "PR #42 at {pr_url} {not a placeholder}");
+        assert_eq!(result.placeholders, vec!["pr_number", "pr_url"]);
+        assert_eq!(result.missing, vec!["pr_url"]);
+    }
+}
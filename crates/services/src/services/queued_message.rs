@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
@@ -11,6 +11,7 @@ use uuid::Uuid;
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct QueuedMessage {
+    pub id: Uuid,
     /// The session this message is queued for
     pub session_id: Uuid,
     /// The follow-up data (message + variant)
@@ -26,15 +27,21 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and waiting for execution to complete
+    Queued {
+        /// The message that runs next
+        message: QueuedMessage,
+        /// Every queued message in run order, starting with `message`
+        messages: Vec<QueuedMessage>,
+    },
 }
 
 /// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Each session has an ordered queue; one message is started per completed
+/// execution until the queue is drained.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    queue: Arc<DashMap<Uuid, VecDeque<QueuedMessage>>>,
 }
 
 impl QueuedMessageService {
@@ -44,42 +51,108 @@ impl QueuedMessageService {
         }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
+    fn new_message(session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
+        QueuedMessage {
+            id: Uuid::new_v4(),
             session_id,
             data,
             queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
+        }
+    }
+
+    /// Queue a message for a session. Replaces any existing queued messages.
+    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
+        let queued = Self::new_message(session_id, data);
+        self.queue
+            .insert(session_id, VecDeque::from([queued.clone()]));
+        queued
+    }
+
+    /// Append a message to the end of a session's queue
+    pub fn enqueue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
+        let queued = Self::new_message(session_id, data);
+        self.queue
+            .entry(session_id)
+            .or_default()
+            .push_back(queued.clone());
         queued
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Replace the contents of a queued message, keeping its place in the queue
+    pub fn update_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Option<QueuedMessage> {
+        let mut queue = self.queue.get_mut(&session_id)?;
+        let queued = queue.iter_mut().find(|queued| queued.id == message_id)?;
+        queued.data = data;
+        Some(queued.clone())
+    }
+
+    /// Remove one message from a session's queue
+    pub fn cancel_queued_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Option<QueuedMessage> {
+        let removed = {
+            let mut queue = self.queue.get_mut(&session_id)?;
+            let index = queue.iter().position(|queued| queued.id == message_id)?;
+            queue.remove(index)
+        };
+        self.queue
+            .remove_if(&session_id, |_, queue| queue.is_empty());
+        removed
+    }
+
+    /// Cancel/remove every queued message for a session
+    pub fn cancel_queued(&self, session_id: Uuid) -> Vec<QueuedMessage> {
+        self.queue
+            .remove(&session_id)
+            .map(|(_, queue)| queue.into())
+            .unwrap_or_default()
     }
 
-    /// Get the queued message for a session (if any)
+    /// Get the next queued message for a session (if any)
     pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+        self.queue
+            .get(&session_id)
+            .and_then(|queue| queue.front().cloned())
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume the queue one message at a time.
     pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+        let next = self
+            .queue
+            .get_mut(&session_id)
+            .and_then(|mut queue| queue.pop_front());
+        self.queue
+            .remove_if(&session_id, |_, queue| queue.is_empty());
+        next
     }
 
     /// Check if a session has a queued message
     pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+        self.queue
+            .get(&session_id)
+            .is_some_and(|queue| !queue.is_empty())
     }
 
     /// Get queue status for frontend display
     pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
+        let messages: Vec<QueuedMessage> = self
+            .queue
+            .get(&session_id)
+            .map(|queue| queue.iter().cloned().collect())
+            .unwrap_or_default();
+        match messages.first() {
+            Some(message) => QueueStatus::Queued {
+                message: message.clone(),
+                messages,
+            },
             None => QueueStatus::Empty,
         }
     }
@@ -90,3 +163,53 @@ impl Default for QueuedMessageService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+
+    use super::*;
+
+    fn data(message: &str) -> DraftFollowUpData {
+        DraftFollowUpData {
+            message: message.to_string(),
+            executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+        }
+    }
+
+    #[test]
+    fn queue_drains_in_order_with_edits_and_cancellations() {
+        let service = QueuedMessageService::new();
+        let session_id = Uuid::new_v4();
+
+        let first = service.enqueue_message(session_id, data("first"));
+        let second = service.enqueue_message(session_id, data("second"));
+        let third = service.enqueue_message(session_id, data("third"));
+
+        service.update_queued(session_id, third.id, data("third, edited"));
+        assert!(
+            service
+                .cancel_queued_message(session_id, second.id)
+                .is_some()
+        );
+        assert!(
+            service
+                .update_queued(session_id, second.id, data("gone"))
+                .is_none()
+        );
+
+        let QueueStatus::Queued { message, messages } = service.get_status(session_id) else {
+            panic!("queue should not be empty");
+        };
+        assert_eq!(message.id, first.id);
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(service.take_queued(session_id).unwrap().id, first.id);
+        assert_eq!(
+            service.take_queued(session_id).unwrap().data.message,
+            "third, edited"
+        );
+        assert!(!service.has_queued(session_id));
+        assert!(matches!(service.get_status(session_id), QueueStatus::Empty));
+    }
+}
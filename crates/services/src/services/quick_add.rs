@@ -0,0 +1,228 @@
+//! Parsing of one-line task capture, e.g.
+//! `Fix login bug #bug !high @claude-code ^friday`.
+//!
+//! - `#name` adds a label (created if the project has none by that name)
+//! - `!low`, `!medium`, `!high`, `!urgent` set the priority
+//! - `@agent` picks the coding agent, in kebab or snake case
+//! - `^date` sets the due date: `today`, `tomorrow`, a weekday, `3d`, `2w`
+//!   or `YYYY-MM-DD`
+//!
+//! Tokens that don't parse stay in the title, so `#123` or `user@host` are
+//! kept as written. When a marker repeats, the last one wins.
+
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use db::models::{
+    task::{CreateTask, Task},
+    task_label::TaskLabel,
+    task_planning::{TaskPlanning, TaskPriority, UpsertTaskPlanning},
+};
+use executors::executors::BaseCodingAgent;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::validation::{MAX_NAME_CHARS, MAX_TITLE_CHARS, Validate, ValidationErrors};
+use uuid::Uuid;
+
+/// Color given to labels created from quick-add text
+const NEW_LABEL_COLOR: &str = "#6b7280";
+
+/// Task fields parsed from a line of quick-add text
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct QuickAddParse {
+    pub title: String,
+    /// Label names, without the `#`
+    pub labels: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub executor: Option<BaseCodingAgent>,
+    pub due_date: Option<NaiveDate>,
+}
+
+impl QuickAddParse {
+    pub fn planning(&self) -> UpsertTaskPlanning {
+        UpsertTaskPlanning {
+            priority: self.priority,
+            due_date: self.due_date,
+            executor: self.executor,
+        }
+    }
+}
+
+impl Validate for QuickAddParse {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("title", &self.title, MAX_TITLE_CHARS);
+        for label in &self.labels {
+            errors.max_chars("labels", label, MAX_NAME_CHARS);
+        }
+        errors.into_result()
+    }
+}
+
+/// Create the parsed task in `project_id` with its labels and planning, in
+/// one transaction
+pub async fn create_task(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    parsed: &QuickAddParse,
+) -> Result<(Task, Option<TaskPlanning>), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let task = Task::create(
+        &mut *tx,
+        &CreateTask::from_title_description(project_id, parsed.title.clone(), None),
+        Uuid::new_v4(),
+    )
+    .await?;
+    let mut label_ids = Vec::with_capacity(parsed.labels.len());
+    for name in &parsed.labels {
+        label_ids.push(
+            TaskLabel::find_or_create_by_name(&mut *tx, project_id, name, NEW_LABEL_COLOR).await?,
+        );
+    }
+    TaskLabel::associate_many(&mut *tx, task.id, &label_ids).await?;
+    let planning = parsed.planning();
+    let planning = if planning.is_empty() {
+        None
+    } else {
+        Some(TaskPlanning::upsert(&mut *tx, task.id, &planning).await?)
+    };
+    tx.commit().await?;
+    Ok((task, planning))
+}
+
+/// Split `line` into a title and its inline markers; relative dates count
+/// from `today`
+pub fn parse(line: &str, today: NaiveDate) -> QuickAddParse {
+    let mut title = Vec::new();
+    let mut parsed = QuickAddParse {
+        title: String::new(),
+        labels: Vec::new(),
+        priority: None,
+        executor: None,
+        due_date: None,
+    };
+
+    for token in line.split_whitespace() {
+        let recognized = match token.split_at(token.chars().next().map_or(0, char::len_utf8)) {
+            ("#", name) if name.starts_with(char::is_alphabetic) => {
+                if !parsed
+                    .labels
+                    .iter()
+                    .any(|label| label.eq_ignore_ascii_case(name))
+                {
+                    parsed.labels.push(name.to_string());
+                }
+                true
+            }
+            ("!", value) => priority(value).map(|p| parsed.priority = Some(p)).is_some(),
+            ("@", value) => executor(value).map(|e| parsed.executor = Some(e)).is_some(),
+            ("^", value) => due_date(value, today)
+                .map(|d| parsed.due_date = Some(d))
+                .is_some(),
+            _ => false,
+        };
+        if !recognized {
+            title.push(token);
+        }
+    }
+
+    parsed.title = title.join(" ");
+    parsed
+}
+
+fn priority(value: &str) -> Option<TaskPriority> {
+    match value.to_ascii_lowercase().as_str() {
+        "med" | "normal" => Some(TaskPriority::Medium),
+        other => TaskPriority::from_str(other).ok(),
+    }
+}
+
+fn executor(value: &str) -> Option<BaseCodingAgent> {
+    BaseCodingAgent::from_str(&value.replace('-', "_").to_ascii_uppercase()).ok()
+}
+
+fn due_date(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let value = value.to_ascii_lowercase();
+    match value.as_str() {
+        "today" => return Some(today),
+        "tomorrow" | "tmrw" => return today.checked_add_days(Days::new(1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+        return Some(date);
+    }
+    if let Ok(weekday) = Weekday::from_str(&value) {
+        // The next such day, a week out when it is today
+        let ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return today.checked_add_days(Days::new(ahead.into()));
+    }
+    let (count, unit) = value.split_at(value.len().checked_sub(1)?);
+    let count: u64 = count.parse().ok()?;
+    match unit {
+        "d" => today.checked_add_days(Days::new(count)),
+        "w" => today.checked_add_days(Days::new(count.checked_mul(7)?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 4).unwrap()
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_all_markers() {
+        let parsed = parse("Fix login bug #bug !high @claude-code ^friday", today());
+        assert_eq!(
+            parsed,
+            QuickAddParse {
+                title: "Fix login bug".to_string(),
+                labels: vec!["bug".to_string()],
+                priority: Some(TaskPriority::High),
+                executor: Some(BaseCodingAgent::ClaudeCode),
+                due_date: Some(date(3, 6)),
+            }
+        );
+    }
+
+    #[test]
+    fn unrecognized_markers_stay_in_title() {
+        let parsed = parse("Close #123 for ops@example !soon ^someday", today());
+        assert_eq!(parsed.title, "Close #123 for ops@example !soon ^someday");
+        assert!(parsed.labels.is_empty());
+        assert_eq!(parsed.priority, None);
+        assert_eq!(parsed.due_date, None);
+    }
+
+    #[test]
+    fn labels_are_deduplicated_and_last_marker_wins() {
+        let parsed = parse("Ship it #UI #ui #docs !low !urgent", today());
+        assert_eq!(parsed.labels, ["UI", "docs"]);
+        assert_eq!(parsed.priority, Some(TaskPriority::Urgent));
+    }
+
+    #[test]
+    fn resolves_due_dates() {
+        let due = |value| due_date(value, today());
+        assert_eq!(due("today"), Some(today()));
+        assert_eq!(due("tomorrow"), Some(date(3, 5)));
+        assert_eq!(due("wed"), Some(date(3, 11)));
+        assert_eq!(due("Monday"), Some(date(3, 9)));
+        assert_eq!(due("3d"), Some(date(3, 7)));
+        assert_eq!(due("2w"), Some(date(3, 18)));
+        assert_eq!(due("2026-04-01"), Some(date(4, 1)));
+        assert_eq!(due("w"), None);
+        assert_eq!(due("later"), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! Draft release notes from the board.
+//!
+//! Lists the project's tasks merged into a repo since its latest tag, groups
+//! them by their first label and optionally opens a GitHub draft release with
+//! the result through `gh release create --draft`.
+
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::{DateTime, Utc};
+use db::models::{project_report::ReleaseTask, repo::Repo, task_label::TaskLabel};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::validation::{Validate, ValidationErrors};
+use uuid::Uuid;
+
+use super::{
+    git::{GitCli, GitCliError, GitServiceError},
+    git_host::github::GhCli,
+};
+
+const MAX_TAG_CHARS: usize = 100;
+/// Heading of the tasks that have no label
+const UNLABELED: &str = "Other changes";
+
+#[derive(Debug, Error)]
+pub enum ReleaseNotesError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("{0}")]
+    GitHub(String),
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct DraftReleaseRequest {
+    pub repo_id: Uuid,
+    /// Tag of the new release; required to create the draft release
+    pub tag: Option<String>,
+    /// Also create a GitHub draft release with the generated notes
+    #[serde(default)]
+    pub create_release: bool,
+}
+
+impl Validate for DraftReleaseRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        match self.tag.as_deref().map(str::trim) {
+            Some(tag) if tag.is_empty() && self.create_release => {
+                errors.add("tag", "is required to create a release")
+            }
+            Some(tag) if tag.starts_with('-') || tag.contains(char::is_whitespace) => {
+                errors.add("tag", "must be a valid tag name")
+            }
+            Some(tag) => errors.max_chars("tag", tag, MAX_TAG_CHARS),
+            None if self.create_release => errors.add("tag", "is required to create a release"),
+            None => {}
+        }
+        errors.into_result()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReleaseDraft {
+    /// Latest tag reachable from the repo's HEAD; only tasks merged after it
+    /// are listed
+    pub previous_tag: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub tasks: Vec<ReleaseTask>,
+    /// Markdown release notes, grouped by label
+    pub body: String,
+    /// URL of the draft release, when one was created
+    pub release_url: Option<String>,
+}
+
+/// Latest tag reachable from HEAD and when its commit was made
+fn latest_tag(repo_path: &Path) -> Result<Option<(String, DateTime<Utc>)>, GitCliError> {
+    let git = GitCli::new();
+    let tag = match git.git(repo_path, ["describe", "--tags", "--abbrev=0"]) {
+        Ok(tag) => tag.trim().to_string(),
+        // No tags yet
+        Err(GitCliError::CommandFailed(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let committed_at = git.git(
+        repo_path,
+        ["log", "-1", "--format=%cI", &format!("{tag}^{{commit}}")],
+    )?;
+    let committed_at = DateTime::parse_from_rfc3339(committed_at.trim())
+        .map_err(|e| GitCliError::CommandFailed(format!("Unexpected date for {tag}: {e}")))?;
+    Ok(Some((tag, committed_at.with_timezone(&Utc))))
+}
+
+/// Markdown notes with one section per label, in label order, and the
+/// unlabeled tasks last
+pub fn render_body(tasks: &[ReleaseTask]) -> String {
+    if tasks.is_empty() {
+        return "No changes since the previous release.\n".to_string();
+    }
+    let mut sections: BTreeMap<&str, Vec<&ReleaseTask>> = BTreeMap::new();
+    let mut unlabeled = Vec::new();
+    for task in tasks {
+        match task.labels.first() {
+            Some(label) => sections.entry(label.as_str()).or_default().push(task),
+            None => unlabeled.push(task),
+        }
+    }
+
+    let mut out = String::new();
+    let sections = sections
+        .into_iter()
+        .chain((!unlabeled.is_empty()).then_some((UNLABELED, unlabeled)));
+    for (heading, tasks) in sections {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## {heading}\n\n"));
+        for task in tasks {
+            match (task.pr_number, &task.pr_url) {
+                (Some(number), Some(url)) => {
+                    out.push_str(&format!("- {} ([#{number}]({url}))\n", task.task_title))
+                }
+                _ => out.push_str(&format!("- {}\n", task.task_title)),
+            }
+        }
+    }
+    out
+}
+
+/// Collect the tasks merged into `repo` since its latest tag and render them
+/// as release notes, creating the GitHub draft release when asked to
+pub async fn draft_release(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    repo: &Repo,
+    request: &DraftReleaseRequest,
+) -> Result<ReleaseDraft, ReleaseNotesError> {
+    let repo_path = repo.path.clone();
+    let previous = tokio::task::spawn_blocking(move || latest_tag(&repo_path))
+        .await
+        .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?
+        .map_err(GitServiceError::GitCLI)?;
+    let (previous_tag, since) = previous.unzip();
+
+    let mut tasks = ReleaseTask::find_merged_since(pool, project_id, repo.id, since).await?;
+    let mut labels = TaskLabel::find_by_project_tasks(pool, project_id).await?;
+    for task in &mut tasks {
+        task.labels = labels
+            .remove(&task.task_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|label| label.name)
+            .collect();
+    }
+    let body = render_body(&tasks);
+
+    let release_url = match request.tag.as_deref().map(str::trim) {
+        Some(tag) if request.create_release => {
+            let repo_path = repo.path.clone();
+            let tag = tag.to_string();
+            let notes = body.clone();
+            let url = tokio::task::spawn_blocking(move || {
+                GhCli::new().create_draft_release(&repo_path, &tag, &tag, &notes)
+            })
+            .await
+            .map_err(|e| ReleaseNotesError::GitHub(e.to_string()))?
+            .map_err(|e| ReleaseNotesError::GitHub(e.to_string()))?;
+            Some(url)
+        }
+        _ => None,
+    };
+
+    Ok(ReleaseDraft {
+        previous_tag,
+        since,
+        tasks,
+        body,
+        release_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn task(title: &str, labels: &[&str], pr_number: Option<i64>) -> ReleaseTask {
+        ReleaseTask {
+            task_id: Uuid::new_v4(),
+            task_title: title.to_string(),
+            pr_number,
+            pr_url: pr_number.map(|n| format!("https://github.com/acme/app/pull/{n}")),
+            merged_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn groups_tasks_by_first_label() {
+        let tasks = vec![
+            task("Retry uploads", &["fix"], Some(12)),
+            task("Tidy logs", &[], None),
+            task("Dark mode", &["feature", "ui"], Some(15)),
+        ];
+        assert_eq!(
+            render_body(&tasks),
+            "## feature\n\n- Dark mode ([#15](https://github.com/acme/app/pull/15))\n\
+             \n## fix\n\n- Retry uploads ([#12](https://github.com/acme/app/pull/12))\n\
+             \n## Other changes\n\n- Tidy logs\n"
+        );
+        assert_eq!(render_body(&[]), "No changes since the previous release.\n");
+    }
+}
@@ -4,9 +4,20 @@ use std::time::Duration;
 
 use backon::{ExponentialBuilder, Retryable};
 use chrono::Duration as ChronoDuration;
-use remote::routes::tasks::{
-    AssignSharedTaskRequest, CheckTasksRequest, CreateSharedTaskRequest, SharedTaskResponse,
-    UpdateSharedTaskRequest,
+use remote::{
+    db::{
+        mentions::Mention, shared_task_attempts::SharedTaskAttempt, task_claims::SharedTaskClaim,
+        task_execution_status::SharedTaskExecutionStatus, tasks::SharedTask,
+    },
+    routes::{
+        mentions::{MarkMentionsReadRequest, MarkMentionsReadResponse},
+        tasks::{
+            AssignSharedTaskRequest, CheckTasksRequest, ClaimSharedTaskRequest,
+            CreateSharedTaskRequest, ImportSharedTasksRequest, ImportSharedTasksResponse,
+            ReportExecutionStatusRequest, ReportSharedTaskAttemptRequest, SharedTaskResponse,
+            UpdateSharedTaskRequest,
+        },
+    },
 };
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -24,8 +35,10 @@ use utils::{
             AcceptInvitationResponse, CreateInvitationRequest, CreateInvitationResponse,
             CreateOrganizationRequest, CreateOrganizationResponse, GetInvitationResponse,
             GetOrganizationResponse, ListInvitationsResponse, ListMembersResponse,
-            ListOrganizationsResponse, Organization, RevokeInvitationRequest,
-            UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
+            ListOrganizationsResponse, MemberNotificationPreferences, Organization,
+            OrganizationSharedConfig, ResendInvitationRequest, RevokeInvitationRequest,
+            UpdateMemberNotificationPreferencesRequest, UpdateMemberRoleRequest,
+            UpdateMemberRoleResponse, UpdateOrganizationRequest, UpdateSharedConfigRequest,
         },
         projects::{ListProjectsResponse, RemoteProject},
     },
@@ -382,6 +395,19 @@ impl RemoteClient {
             .map_err(|e| RemoteClientError::Serde(e.to_string()))
     }
 
+    async fn put_authed<T, B>(&self, path: &str, body: &B) -> Result<T, RemoteClientError>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        let res = self
+            .send(reqwest::Method::PUT, path, true, Some(body))
+            .await?;
+        res.json::<T>()
+            .await
+            .map_err(|e| RemoteClientError::Serde(e.to_string()))
+    }
+
     async fn delete_authed(&self, path: &str) -> Result<(), RemoteClientError> {
         self.send(reqwest::Method::DELETE, path, true, None::<&()>)
             .await?;
@@ -465,6 +491,37 @@ impl RemoteClient {
             .await
     }
 
+    /// Fetches the baseline config an organization shares with its members.
+    pub async fn get_organization_shared_config(
+        &self,
+        org_id: Uuid,
+    ) -> Result<Option<OrganizationSharedConfig>, RemoteClientError> {
+        self.get_authed(&format!("/v1/organizations/{org_id}/shared-config"))
+            .await
+    }
+
+    /// Publishes the baseline config shared with an organization's members.
+    pub async fn update_organization_shared_config(
+        &self,
+        org_id: Uuid,
+        request: &UpdateSharedConfigRequest,
+    ) -> Result<OrganizationSharedConfig, RemoteClientError> {
+        self.put_authed(
+            &format!("/v1/organizations/{org_id}/shared-config"),
+            request,
+        )
+        .await
+    }
+
+    /// Withdraws an organization's shared baseline config.
+    pub async fn delete_organization_shared_config(
+        &self,
+        org_id: Uuid,
+    ) -> Result<(), RemoteClientError> {
+        self.delete_authed(&format!("/v1/organizations/{org_id}/shared-config"))
+            .await
+    }
+
     /// Creates an invitation to an organization.
     pub async fn create_invitation(
         &self,
@@ -500,6 +557,19 @@ impl RemoteClient {
         .await
     }
 
+    /// Re-sends an invitation email with a fresh link.
+    pub async fn resend_invitation(
+        &self,
+        org_id: Uuid,
+        request: &ResendInvitationRequest,
+    ) -> Result<CreateInvitationResponse, RemoteClientError> {
+        self.post_authed(
+            &format!("/v1/organizations/{org_id}/invitations/resend"),
+            Some(request),
+        )
+        .await
+    }
+
     /// Accepts an invitation.
     pub async fn accept_invitation(
         &self,
@@ -545,6 +615,30 @@ impl RemoteClient {
         .await
     }
 
+    /// Fetches the current user's notification preferences in an organization.
+    pub async fn get_notification_preferences(
+        &self,
+        org_id: Uuid,
+    ) -> Result<MemberNotificationPreferences, RemoteClientError> {
+        self.get_authed(&format!(
+            "/v1/organizations/{org_id}/members/me/notification-preferences"
+        ))
+        .await
+    }
+
+    /// Updates the current user's notification preferences in an organization.
+    pub async fn update_notification_preferences(
+        &self,
+        org_id: Uuid,
+        request: &UpdateMemberNotificationPreferencesRequest,
+    ) -> Result<MemberNotificationPreferences, RemoteClientError> {
+        self.put_authed(
+            &format!("/v1/organizations/{org_id}/members/me/notification-preferences"),
+            request,
+        )
+        .await
+    }
+
     /// Creates a shared task.
     pub async fn create_shared_task(
         &self,
@@ -553,6 +647,19 @@ impl RemoteClient {
         self.post_authed("/v1/tasks", Some(request)).await
     }
 
+    /// Creates tags and shared tasks in a remote project in bulk.
+    pub async fn import_shared_tasks(
+        &self,
+        request: &ImportSharedTasksRequest,
+    ) -> Result<ImportSharedTasksResponse, RemoteClientError> {
+        self.post_authed("/v1/tasks/import", Some(request)).await
+    }
+
+    /// Gets a shared task as it currently stands on the server.
+    pub async fn get_shared_task(&self, task_id: Uuid) -> Result<SharedTask, RemoteClientError> {
+        self.get_authed(&format!("/v1/tasks/{task_id}")).await
+    }
+
     /// Updates a shared task.
     pub async fn update_shared_task(
         &self,
@@ -591,11 +698,91 @@ impl RemoteClient {
             .map_err(|e| RemoteClientError::Serde(e.to_string()))
     }
 
+    /// Claims a shared task for the current user, or renews their claim.
+    /// Fails with a 409 `Http` error when another user holds the claim.
+    pub async fn claim_shared_task(
+        &self,
+        task_id: Uuid,
+        request: &ClaimSharedTaskRequest,
+    ) -> Result<SharedTaskClaim, RemoteClientError> {
+        self.post_authed(&format!("/v1/tasks/{task_id}/claim"), Some(request))
+            .await
+    }
+
+    /// Gets the unexpired claim on a shared task, if any.
+    pub async fn get_shared_task_claim(
+        &self,
+        task_id: Uuid,
+    ) -> Result<Option<SharedTaskClaim>, RemoteClientError> {
+        self.get_authed(&format!("/v1/tasks/{task_id}/claim")).await
+    }
+
+    /// Releases the current user's claim on a shared task.
+    pub async fn release_shared_task_claim(&self, task_id: Uuid) -> Result<(), RemoteClientError> {
+        self.delete_authed(&format!("/v1/tasks/{task_id}/claim"))
+            .await
+    }
+
+    /// Reports the latest agent lifecycle state of a shared task.
+    pub async fn report_shared_task_execution_status(
+        &self,
+        task_id: Uuid,
+        request: &ReportExecutionStatusRequest,
+    ) -> Result<(), RemoteClientError> {
+        self.send(
+            reqwest::Method::POST,
+            &format!("/v1/tasks/{task_id}/execution-status"),
+            true,
+            Some(request),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Records the summary and pull request state of an attempt on a shared task.
+    pub async fn report_shared_task_attempt(
+        &self,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        request: &ReportSharedTaskAttemptRequest,
+    ) -> Result<SharedTaskAttempt, RemoteClientError> {
+        self.put_authed(
+            &format!("/v1/tasks/{task_id}/attempts/{attempt_id}"),
+            request,
+        )
+        .await
+    }
+
+    /// Lists the latest agent lifecycle state of shared tasks in a project.
+    pub async fn list_shared_task_execution_statuses(
+        &self,
+        project_id: Uuid,
+    ) -> Result<Vec<SharedTaskExecutionStatus>, RemoteClientError> {
+        self.get_authed(&format!(
+            "/v1/tasks/execution-status?project_id={project_id}"
+        ))
+        .await
+    }
+
     /// Checks if shared tasks exist.
     pub async fn check_tasks(&self, task_ids: Vec<Uuid>) -> Result<Vec<Uuid>, RemoteClientError> {
         let request = CheckTasksRequest { task_ids };
         self.post_authed("/v1/tasks/check", Some(&request)).await
     }
+
+    /// Lists the current user's unread @mentions across organizations.
+    pub async fn list_unread_mentions(&self) -> Result<Vec<Mention>, RemoteClientError> {
+        self.get_authed("/v1/mentions/unread").await
+    }
+
+    /// Marks the current user's mentions as read.
+    pub async fn mark_mentions_read(
+        &self,
+        request: &MarkMentionsReadRequest,
+    ) -> Result<MarkMentionsReadResponse, RemoteClientError> {
+        self.post_authed("/v1/mentions/mark-read", Some(request))
+            .await
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -1,12 +1,15 @@
 use std::path::{Path, PathBuf};
 
-use db::models::repo::Repo as RepoModel;
+use db::models::{
+    repo::Repo as RepoModel,
+    repo_ssh_key::{RepoSshKey, SshKeyMode, UpsertRepoSshKey},
+};
 use sqlx::SqlitePool;
 use thiserror::Error;
 use utils::path::expand_tilde;
 use uuid::Uuid;
 
-use super::git::{GitService, GitServiceError};
+use super::git::{GitCli, GitService, GitServiceError, SshIdentity};
 
 #[derive(Debug, Error)]
 pub enum RepoError {
@@ -28,6 +31,8 @@ pub enum RepoError {
     Git(#[from] GitServiceError),
     #[error("Invalid folder name: {0}")]
     InvalidFolderName(String),
+    #[error("Invalid SSH key configuration: {0}")]
+    InvalidSshKey(String),
 }
 
 pub type Result<T> = std::result::Result<T, RepoError>;
@@ -91,6 +96,82 @@ impl RepoService {
             .ok_or(RepoError::NotFound)
     }
 
+    /// Configure the SSH identity used when fetching from and pushing to the repo
+    pub async fn set_ssh_key(
+        &self,
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertRepoSshKey,
+    ) -> Result<RepoSshKey> {
+        let repo = self.get_by_id(pool, repo_id).await?;
+        let data = Self::normalize_ssh_key(data)?;
+        let key = RepoSshKey::upsert(pool, repo_id, &data).await?;
+        GitCli::set_ssh_identity(&repo.path, Some(Self::ssh_identity(&key)));
+        Ok(key)
+    }
+
+    pub async fn clear_ssh_key(&self, pool: &SqlitePool, repo_id: Uuid) -> Result<()> {
+        let repo = self.get_by_id(pool, repo_id).await?;
+        RepoSshKey::delete(pool, repo_id).await?;
+        GitCli::set_ssh_identity(&repo.path, None);
+        Ok(())
+    }
+
+    /// Register the stored SSH identities with the git CLI
+    pub async fn load_ssh_keys(&self, pool: &SqlitePool) -> Result<()> {
+        for key in RepoSshKey::find_all(pool).await? {
+            if let Some(repo) = self.find_by_id(pool, key.repo_id).await? {
+                GitCli::set_ssh_identity(&repo.path, Some(Self::ssh_identity(&key)));
+            }
+        }
+        Ok(())
+    }
+
+    fn normalize_ssh_key(data: &UpsertRepoSshKey) -> Result<UpsertRepoSshKey> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(expand_tilde)
+        };
+        match data.mode {
+            SshKeyMode::KeyFile => {
+                let key_path = non_empty(&data.key_path).ok_or_else(|| {
+                    RepoError::InvalidSshKey("a key path is required".to_string())
+                })?;
+                if !key_path.is_file() {
+                    return Err(RepoError::InvalidSshKey(format!(
+                        "key file not found: {}",
+                        key_path.display()
+                    )));
+                }
+                Ok(UpsertRepoSshKey {
+                    mode: SshKeyMode::KeyFile,
+                    key_path: Some(key_path.to_string_lossy().to_string()),
+                    agent_socket: None,
+                })
+            }
+            SshKeyMode::Agent => Ok(UpsertRepoSshKey {
+                mode: SshKeyMode::Agent,
+                key_path: None,
+                agent_socket: non_empty(&data.agent_socket)
+                    .map(|socket| socket.to_string_lossy().to_string()),
+            }),
+        }
+    }
+
+    fn ssh_identity(key: &RepoSshKey) -> SshIdentity {
+        match key.mode {
+            SshKeyMode::KeyFile => {
+                SshIdentity::KeyFile(PathBuf::from(key.key_path.clone().unwrap_or_default()))
+            }
+            SshKeyMode::Agent => SshIdentity::Agent {
+                socket: key.agent_socket.as_ref().map(PathBuf::from),
+            },
+        }
+    }
+
     pub async fn init_repo(
         &self,
         pool: &SqlitePool,
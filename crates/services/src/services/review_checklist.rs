@@ -0,0 +1,393 @@
+//! Human-review checklists for attempt diffs.
+//!
+//! Large agent diffs are hard to review line by line, so this points the
+//! reviewer at the parts that deserve attention: sensitive paths, source
+//! changes without matching test changes, and edits to public declarations.
+//! The analysis is heuristic and only looks at paths and changed lines.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::diff::{Diff, DiffChangeKind};
+
+/// Heading of the Markdown checklist, also used when attaching it to a PR body
+pub const CHECKLIST_HEADING: &str = "## Review checklist";
+
+/// Changed lines above which a single file is called out as a large change
+const LARGE_FILE_LINES: usize = 400;
+/// Declarations listed per file before the rest are summarized as a count
+const MAX_DECLARATIONS_PER_FILE: usize = 5;
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "py", "go", "java", "kt", "rb", "cs", "cpp", "c", "swift",
+];
+
+/// Path fragments that make a file worth a closer look, with the reason shown
+const RISKY_PATHS: &[(&str, &str)] = &[
+    ("migration", "database migration"),
+    ("auth", "authentication"),
+    ("oauth", "authentication"),
+    ("password", "credentials handling"),
+    ("secret", "credentials handling"),
+    ("token", "credentials handling"),
+    ("crypto", "cryptography"),
+    ("permission", "access control"),
+    ("payment", "payments"),
+    ("billing", "payments"),
+    (".github/workflows", "CI configuration"),
+    (".gitlab-ci", "CI configuration"),
+    ("dockerfile", "container build"),
+    (".env", "environment configuration"),
+];
+
+const DEPENDENCY_FILES: &[&str] = &[
+    "cargo.toml",
+    "cargo.lock",
+    "package.json",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "go.mod",
+    "go.sum",
+    "requirements.txt",
+    "pyproject.toml",
+    "gemfile",
+];
+
+/// Content markers that warrant a second look wherever they are added
+const RISKY_CONTENT: &[(&str, &str)] = &[
+    ("unsafe ", "adds `unsafe` code"),
+    ("dangerouslySetInnerHTML", "renders raw HTML"),
+    ("eval(", "evaluates dynamic code"),
+    ("Command::new(", "spawns processes"),
+    ("child_process", "spawns processes"),
+    ("DROP TABLE", "drops a table"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ChecklistCategory {
+    RiskyArea,
+    MissingTests,
+    PublicApi,
+    LargeChange,
+}
+
+impl ChecklistCategory {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::RiskyArea => "Risky areas",
+            Self::MissingTests => "Missing tests",
+            Self::PublicApi => "Public API changes",
+            Self::LargeChange => "Large changes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ChecklistItem {
+    pub category: ChecklistCategory,
+    pub path: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewChecklist {
+    pub items: Vec<ChecklistItem>,
+    /// Markdown task list, ready to paste into a PR body
+    pub markdown: String,
+}
+
+pub fn generate(diffs: &[Diff]) -> ReviewChecklist {
+    let mut items = Vec::new();
+    let mut changed_sources = Vec::new();
+    let mut touches_tests = false;
+
+    for diff in diffs {
+        let Some(path) = diff.new_path.as_deref().or(diff.old_path.as_deref()) else {
+            continue;
+        };
+        let lower = path.to_lowercase();
+        let (added, removed) = changed_lines(diff);
+
+        if is_test_path(&lower) || added.iter().any(|l| l.contains("#[cfg(test)]")) {
+            touches_tests = true;
+        } else if is_source_path(&lower) {
+            changed_sources.push(path.to_string());
+        }
+
+        let mut reasons: Vec<&str> = Vec::new();
+        for (fragment, reason) in RISKY_PATHS {
+            if lower.contains(fragment) && !reasons.contains(reason) {
+                reasons.push(reason);
+            }
+        }
+        let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+        if DEPENDENCY_FILES.contains(&file_name) {
+            reasons.push("dependency changes");
+        }
+        for (marker, reason) in RISKY_CONTENT {
+            if added.iter().any(|l| l.contains(marker)) && !reasons.contains(reason) {
+                reasons.push(reason);
+            }
+        }
+        if matches!(diff.change, DiffChangeKind::Deleted) && is_source_path(&lower) {
+            reasons.push("file deleted; check nothing still depends on it");
+        }
+        if !reasons.is_empty() {
+            items.push(ChecklistItem {
+                category: ChecklistCategory::RiskyArea,
+                path: Some(path.to_string()),
+                text: format!("Check `{path}`: {}", reasons.join(", ")),
+            });
+        }
+
+        let removed_api = public_declarations(&removed);
+        let added_api = public_declarations(&added);
+        if !removed_api.is_empty() {
+            items.push(ChecklistItem {
+                category: ChecklistCategory::PublicApi,
+                path: Some(path.to_string()),
+                text: format!(
+                    "Confirm callers of changed or removed declarations in `{path}` are updated: {}",
+                    list_declarations(&removed_api)
+                ),
+            });
+        } else if !added_api.is_empty() {
+            items.push(ChecklistItem {
+                category: ChecklistCategory::PublicApi,
+                path: Some(path.to_string()),
+                text: format!(
+                    "Review new public declarations in `{path}`: {}",
+                    list_declarations(&added_api)
+                ),
+            });
+        }
+
+        let changed = if diff.content_omitted {
+            diff.additions.unwrap_or(0) + diff.deletions.unwrap_or(0)
+        } else {
+            added.len() + removed.len()
+        };
+        if changed > LARGE_FILE_LINES {
+            items.push(ChecklistItem {
+                category: ChecklistCategory::LargeChange,
+                path: Some(path.to_string()),
+                text: format!("Review `{path}` in full: about {changed} lines changed"),
+            });
+        }
+    }
+
+    if !changed_sources.is_empty() && !touches_tests {
+        let shown: Vec<String> = changed_sources
+            .iter()
+            .take(MAX_DECLARATIONS_PER_FILE)
+            .map(|p| format!("`{p}`"))
+            .collect();
+        let more = changed_sources.len().saturating_sub(shown.len());
+        let mut files = shown.join(", ");
+        if more > 0 {
+            files.push_str(&format!(" and {more} more"));
+        }
+        items.push(ChecklistItem {
+            category: ChecklistCategory::MissingTests,
+            path: None,
+            text: format!("No tests were added or changed; confirm coverage for {files}"),
+        });
+    }
+
+    let markdown = render_markdown(&items);
+    ReviewChecklist { items, markdown }
+}
+
+/// Lines only present after (added) and only present before (removed) the change
+fn changed_lines(diff: &Diff) -> (Vec<String>, Vec<String>) {
+    let old = diff.old_content.as_deref().unwrap_or("");
+    let new = diff.new_content.as_deref().unwrap_or("");
+    let old_lines: HashSet<&str> = old.lines().map(str::trim).collect();
+    let new_lines: HashSet<&str> = new.lines().map(str::trim).collect();
+
+    let added = new
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !old_lines.contains(l))
+        .map(str::to_string)
+        .collect();
+    let removed = old
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !new_lines.contains(l))
+        .map(str::to_string)
+        .collect();
+    (added, removed)
+}
+
+fn is_test_path(lower: &str) -> bool {
+    lower.contains("/tests/")
+        || lower.starts_with("tests/")
+        || lower.contains("__tests__")
+        || lower.contains(".test.")
+        || lower.contains(".spec.")
+        || lower.contains("_test.")
+        || lower
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| name.starts_with("test_"))
+}
+
+fn is_source_path(lower: &str) -> bool {
+    lower
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| SOURCE_EXTENSIONS.contains(&ext))
+}
+
+/// Signatures of exported items among `lines`
+fn public_declarations(lines: &[String]) -> Vec<String> {
+    const PREFIXES: &[&str] = &[
+        "pub fn ",
+        "pub async fn ",
+        "pub struct ",
+        "pub enum ",
+        "pub trait ",
+        "pub type ",
+        "pub const ",
+        "pub mod ",
+        "export function ",
+        "export async function ",
+        "export const ",
+        "export interface ",
+        "export type ",
+        "export class ",
+        "export default ",
+    ];
+    lines
+        .iter()
+        .filter(|l| PREFIXES.iter().any(|p| l.starts_with(p)))
+        .map(|l| {
+            l.split(['{', '(', '=', '<'])
+                .next()
+                .unwrap_or(l)
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+fn list_declarations(declarations: &[String]) -> String {
+    let mut list = declarations
+        .iter()
+        .take(MAX_DECLARATIONS_PER_FILE)
+        .map(|d| format!("`{d}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let more = declarations.len().saturating_sub(MAX_DECLARATIONS_PER_FILE);
+    if more > 0 {
+        list.push_str(&format!(" and {more} more"));
+    }
+    list
+}
+
+fn render_markdown(items: &[ChecklistItem]) -> String {
+    let mut out = format!("{CHECKLIST_HEADING}\n");
+    if items.is_empty() {
+        out.push_str("\nNothing stood out; a regular review should do.\n");
+        return out;
+    }
+    for category in [
+        ChecklistCategory::RiskyArea,
+        ChecklistCategory::PublicApi,
+        ChecklistCategory::MissingTests,
+        ChecklistCategory::LargeChange,
+    ] {
+        let lines: Vec<&ChecklistItem> = items.iter().filter(|i| i.category == category).collect();
+        if lines.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n\n", category.heading()));
+        for item in lines {
+            out.push_str(&format!("- [ ] {}\n", item.text));
+        }
+    }
+    out
+}
+
+/// PR body with the checklist appended, replacing one attached earlier
+pub fn append_to_body(body: Option<&str>, checklist: &str) -> String {
+    let body = body.map(|b| match b.find(CHECKLIST_HEADING) {
+        Some(index) => b[..index].trim_end(),
+        None => b.trim_end(),
+    });
+    match body.filter(|b| !b.is_empty()) {
+        Some(body) => format!("{body}\n\n{checklist}"),
+        None => checklist.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified(path: &str, old: &str, new: &str) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }
+    }
+
+    #[test]
+    fn flags_risky_paths_api_changes_and_missing_tests() {
+        let diffs = vec![
+            modified(
+                "src/auth/session.rs",
+                "pub fn refresh(token: &str) -> bool {\n    true\n}\n",
+                "pub fn refresh(token: &str, force: bool) -> bool {\n    force\n}\n",
+            ),
+            modified("README.md", "a\n", "b\n"),
+        ];
+        let checklist = generate(&diffs);
+        let categories: Vec<_> = checklist.items.iter().map(|i| i.category).collect();
+        assert_eq!(
+            categories,
+            vec![
+                ChecklistCategory::RiskyArea,
+                ChecklistCategory::PublicApi,
+                ChecklistCategory::MissingTests,
+            ]
+        );
+        assert!(
+            checklist
+                .markdown
+                .contains("- [ ] Confirm callers of changed or removed declarations in `src/auth/session.rs` are updated: `pub fn refresh`")
+        );
+    }
+
+    #[test]
+    fn test_changes_satisfy_missing_tests() {
+        let diffs = vec![
+            modified("src/lib.rs", "fn a() {}\n", "fn b() {}\n"),
+            modified("tests/lib.rs", "", "#[test]\nfn b_works() {}\n"),
+        ];
+        assert!(generate(&diffs).items.is_empty());
+    }
+
+    #[test]
+    fn replaces_previously_attached_checklist() {
+        let body = append_to_body(
+            Some("Fixes login"),
+            &format!("{CHECKLIST_HEADING}\n- [ ] a"),
+        );
+        assert_eq!(
+            append_to_body(Some(&body), &format!("{CHECKLIST_HEADING}\n- [ ] b")),
+            format!("Fixes login\n\n{CHECKLIST_HEADING}\n- [ ] b")
+        );
+    }
+}
@@ -0,0 +1,546 @@
+//! Encrypted storage for API keys and tokens.
+//!
+//! Secrets live in the macOS keychain where available, otherwise in a file
+//! encrypted with AES-256-GCM under a random key kept next to it. Tokens the
+//! user config used to hold in plaintext (GitHub, analytics, embeddings,
+//! transcription) are kept here and filled into the in-memory config at
+//! startup. The chat integration secrets are only read by the server; every
+//! other secret is an agent provider key such as `ANTHROPIC_API_KEY`, passed
+//! to coding agents as an environment variable of the same name.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+
+use super::config::Config;
+
+pub const GITHUB_PAT: &str = "GITHUB_PAT";
+pub const GITHUB_OAUTH_TOKEN: &str = "GITHUB_OAUTH_TOKEN";
+pub const ANALYTICS_API_KEY: &str = "VK_ANALYTICS_API_KEY";
+pub const EMBEDDING_API_KEY: &str = "VK_EMBEDDING_API_KEY";
+pub const TRANSCRIPTION_API_KEY: &str = "VK_TRANSCRIPTION_API_KEY";
+pub const BITBUCKET_APP_PASSWORD: &str = "BITBUCKET_APP_PASSWORD";
+pub const BITBUCKET_ACCESS_TOKEN: &str = "BITBUCKET_ACCESS_TOKEN";
+pub const SLACK_SIGNING_SECRET: &str = "VK_SLACK_SIGNING_SECRET";
+pub const TELEGRAM_WEBHOOK_SECRET: &str = "VK_TELEGRAM_WEBHOOK_SECRET";
+pub const TELEGRAM_BOT_TOKEN: &str = "VK_TELEGRAM_BOT_TOKEN";
+
+/// Secrets backing fields of the user config, set through the settings
+const CONFIG_SECRETS: [&str; 7] = [
+    GITHUB_PAT,
+    GITHUB_OAUTH_TOKEN,
+    ANALYTICS_API_KEY,
+    EMBEDDING_API_KEY,
+    TRANSCRIPTION_API_KEY,
+    BITBUCKET_APP_PASSWORD,
+    BITBUCKET_ACCESS_TOKEN,
+];
+
+/// Secrets of the chat integrations, kept from coding agents
+const INTEGRATION_SECRETS: [&str; 3] = [
+    SLACK_SIGNING_SECRET,
+    TELEGRAM_WEBHOOK_SECRET,
+    TELEGRAM_BOT_TOKEN,
+];
+
+fn passed_to_agents(name: &str) -> bool {
+    !CONFIG_SECRETS.contains(&name) && !INTEGRATION_SECRETS.contains(&name)
+}
+
+const MASK: &str = "••••";
+
+#[derive(Debug, Error)]
+pub enum SecretsError {
+    #[error("Secret names must look like environment variables (A-Z, 0-9, _): {0}")]
+    InvalidName(String),
+    #[error("{0} is set through the settings")]
+    ConfigManaged(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A stored secret as shown to the user
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretInfo {
+    pub name: String,
+    /// The value with all but its last characters hidden
+    pub masked: String,
+    /// Whether coding agents receive it as an environment variable
+    pub passed_to_agents: bool,
+}
+
+/// Hide all but the last four characters of `value`, or all of it when it
+/// is too short for those to be safe to show
+pub fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        return MASK.to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{MASK}{tail}")
+}
+
+/// The config's secret fields with the store name each is kept under
+fn config_fields(config: &mut Config) -> [(&'static str, &mut Option<String>); 7] {
+    [
+        (GITHUB_PAT, &mut config.github.pat),
+        (GITHUB_OAUTH_TOKEN, &mut config.github.oauth_token),
+        (ANALYTICS_API_KEY, &mut config.analytics_api_key),
+        (EMBEDDING_API_KEY, &mut config.embedding_api_key),
+        (TRANSCRIPTION_API_KEY, &mut config.transcription_api_key),
+        (BITBUCKET_APP_PASSWORD, &mut config.bitbucket_app_password),
+        (BITBUCKET_ACCESS_TOKEN, &mut config.bitbucket_access_token),
+    ]
+}
+
+/// Copy of `config` fit for the config file, with its secrets left out
+pub fn without_secrets(config: &Config) -> Config {
+    let mut config = config.clone();
+    for (_, field) in config_fields(&mut config) {
+        *field = None;
+    }
+    config
+}
+
+/// Copy of `config` fit for API responses, with its secrets masked
+pub fn masked(config: &Config) -> Config {
+    let mut config = config.clone();
+    for (_, field) in config_fields(&mut config) {
+        if let Some(value) = field {
+            *value = mask(value);
+        }
+    }
+    config
+}
+
+/// Clients send configs back with the masks they were given; put the real
+/// values from `current` back in those fields
+pub fn restore_masked(config: &mut Config, current: &Config) {
+    let mut current = current.clone();
+    for ((_, field), (_, current)) in config_fields(config)
+        .into_iter()
+        .zip(config_fields(&mut current))
+    {
+        if let (Some(value), Some(current)) = (field.as_ref(), current.as_ref())
+            && *value == mask(current)
+        {
+            *field = Some(current.clone());
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), SecretsError> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(SecretsError::InvalidName(name.to_string()));
+    }
+    if CONFIG_SECRETS.contains(&name) {
+        return Err(SecretsError::ConfigManaged(name.to_string()));
+    }
+    Ok(())
+}
+
+type StoredSecrets = BTreeMap<String, String>;
+
+pub struct SecretStore {
+    backend: Backend,
+    secrets: RwLock<BTreeMap<String, SecretString>>,
+}
+
+impl SecretStore {
+    pub fn new(path: PathBuf, key_path: PathBuf) -> Self {
+        Self {
+            backend: Backend::detect(path, key_path),
+            secrets: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    pub async fn load(&self) -> std::io::Result<()> {
+        let stored = self.backend.load().await?;
+        *self.secrets.write().await = stored
+            .into_iter()
+            .map(|(name, value)| (name, SecretString::from(value)))
+            .collect();
+        Ok(())
+    }
+
+    pub async fn get(&self, name: &str) -> Option<SecretString> {
+        self.secrets.read().await.get(name).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<SecretInfo> {
+        self.secrets
+            .read()
+            .await
+            .iter()
+            .map(|(name, value)| SecretInfo {
+                name: name.clone(),
+                masked: mask(value.expose_secret()),
+                passed_to_agents: passed_to_agents(name),
+            })
+            .collect()
+    }
+
+    /// Provider keys to pass to coding agents
+    pub async fn agent_env(&self) -> HashMap<String, String> {
+        self.secrets
+            .read()
+            .await
+            .iter()
+            .filter(|(name, _)| passed_to_agents(name))
+            .map(|(name, value)| (name.clone(), value.expose_secret().to_string()))
+            .collect()
+    }
+
+    pub async fn set(&self, name: &str, value: &str) -> Result<(), SecretsError> {
+        validate_name(name)?;
+        self.update(|secrets| {
+            secrets.insert(name.to_string(), SecretString::from(value.to_string()));
+        })
+        .await
+    }
+
+    /// `false` if there was no such secret
+    pub async fn remove(&self, name: &str) -> Result<bool, SecretsError> {
+        validate_name(name)?;
+        let mut removed = false;
+        self.update(|secrets| removed = secrets.remove(name).is_some())
+            .await?;
+        Ok(removed)
+    }
+
+    /// Move secrets still in plaintext in `config` into the store, then fill
+    /// its secret fields from the store
+    pub async fn hydrate_config(&self, config: &mut Config) -> Result<(), SecretsError> {
+        self.store_config_secrets(config).await?;
+        let secrets = self.secrets.read().await;
+        for (name, field) in config_fields(config) {
+            *field = secrets
+                .get(name)
+                .map(|value| value.expose_secret().to_string());
+        }
+        Ok(())
+    }
+
+    /// Keep the store in step with the secret fields of `config`. Fields
+    /// left empty clear the stored secret.
+    pub async fn sync_config(&self, config: &Config) -> Result<(), SecretsError> {
+        let mut config = config.clone();
+        self.update(|secrets| {
+            for (name, field) in config_fields(&mut config) {
+                match field.take().filter(|value| !value.trim().is_empty()) {
+                    Some(value) => {
+                        secrets.insert(name.to_string(), SecretString::from(value));
+                    }
+                    None => {
+                        secrets.remove(name);
+                    }
+                }
+            }
+        })
+        .await
+    }
+
+    async fn store_config_secrets(&self, config: &Config) -> Result<(), SecretsError> {
+        let mut config = config.clone();
+        let plaintext: Vec<(&str, String)> = config_fields(&mut config)
+            .into_iter()
+            .filter_map(|(name, field)| {
+                field
+                    .take()
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| (name, value))
+            })
+            .collect();
+        if plaintext.is_empty() {
+            return Ok(());
+        }
+        tracing::info!(
+            "Moving {} config secret(s) into the secret store",
+            plaintext.len()
+        );
+        self.update(|secrets| {
+            for (name, value) in plaintext {
+                secrets.insert(name.to_string(), SecretString::from(value));
+            }
+        })
+        .await
+    }
+
+    /// Apply `change` and persist the result; the in-memory copy only changes
+    /// once the backend has the new secrets
+    async fn update(
+        &self,
+        change: impl FnOnce(&mut BTreeMap<String, SecretString>),
+    ) -> Result<(), SecretsError> {
+        let mut secrets = self.secrets.write().await;
+        let mut updated = secrets.clone();
+        change(&mut updated);
+        let stored: StoredSecrets = updated
+            .iter()
+            .map(|(name, value)| (name.clone(), value.expose_secret().to_string()))
+            .collect();
+        self.backend.save(&stored).await?;
+        *secrets = updated;
+        Ok(())
+    }
+}
+
+enum Backend {
+    File(FileBackend),
+    #[cfg(target_os = "macos")]
+    Keychain(KeychainBackend),
+}
+
+impl Backend {
+    fn detect(path: PathBuf, key_path: PathBuf) -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            let use_file = match std::env::var("SECRETS_BACKEND") {
+                Ok(v) if v.eq_ignore_ascii_case("file") => true,
+                Ok(v) if v.eq_ignore_ascii_case("keychain") => false,
+                _ => cfg!(debug_assertions),
+            };
+            if use_file {
+                tracing::info!("Secrets backend: encrypted file");
+                Backend::File(FileBackend { path, key_path })
+            } else {
+                tracing::info!("Secrets backend: keychain");
+                Backend::Keychain(KeychainBackend)
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            tracing::info!("Secrets backend: encrypted file");
+            Backend::File(FileBackend { path, key_path })
+        }
+    }
+
+    async fn load(&self) -> std::io::Result<StoredSecrets> {
+        match self {
+            Backend::File(b) => b.load(),
+            #[cfg(target_os = "macos")]
+            Backend::Keychain(b) => b.load(),
+        }
+    }
+
+    async fn save(&self, secrets: &StoredSecrets) -> std::io::Result<()> {
+        match self {
+            Backend::File(b) => b.save(secrets),
+            #[cfg(target_os = "macos")]
+            Backend::Keychain(b) => b.save(secrets),
+        }
+    }
+}
+
+struct FileBackend {
+    path: PathBuf,
+    key_path: PathBuf,
+}
+
+impl FileBackend {
+    const NONCE_SIZE: usize = 12; // 96 bits for AES-256-GCM
+
+    fn load(&self) -> std::io::Result<StoredSecrets> {
+        if !self.path.exists() {
+            return Ok(StoredSecrets::new());
+        }
+        let encrypted = std::fs::read_to_string(&self.path)?;
+        match self.decrypt(encrypted.trim()) {
+            Some(secrets) => Ok(secrets),
+            None => {
+                tracing::warn!("failed to decrypt secrets file, renaming to .bad");
+                let _ = std::fs::rename(&self.path, self.path.with_extension("bad"));
+                Ok(StoredSecrets::new())
+            }
+        }
+    }
+
+    fn decrypt(&self, encrypted: &str) -> Option<StoredSecrets> {
+        let decoded = STANDARD.decode(encrypted).ok()?;
+        if decoded.len() < Self::NONCE_SIZE {
+            return None;
+        }
+        let key = self.existing_key()?;
+        let cipher = Aes256Gcm::new(&key);
+        let nonce_bytes: [u8; Self::NONCE_SIZE] = decoded[..Self::NONCE_SIZE].try_into().ok()?;
+        let plaintext = cipher
+            .decrypt(&Nonce::from(nonce_bytes), &decoded[Self::NONCE_SIZE..])
+            .ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+
+    fn save(&self, secrets: &StoredSecrets) -> std::io::Result<()> {
+        let key = self.key()?;
+        let cipher = Aes256Gcm::new(&key);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(secrets)?;
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| std::io::Error::other("failed to encrypt secrets"))?;
+
+        let mut combined = nonce.to_vec();
+        combined.extend_from_slice(&ciphertext);
+        let tmp = self.path.with_extension("tmp");
+        write_private(&tmp, STANDARD.encode(combined).as_bytes())?;
+        std::fs::rename(&tmp, &self.path)
+    }
+
+    fn existing_key(&self) -> Option<Key<Aes256Gcm>> {
+        let bytes: [u8; 32] = std::fs::read(&self.key_path).ok()?.try_into().ok()?;
+        Some(Key::<Aes256Gcm>::from(bytes))
+    }
+
+    /// The machine's key, created on first use
+    fn key(&self) -> std::io::Result<Key<Aes256Gcm>> {
+        if self.key_path.exists() {
+            return self
+                .existing_key()
+                .ok_or_else(|| std::io::Error::other("secrets key file is corrupt"));
+        }
+        let key = Aes256Gcm::generate_key(&mut OsRng);
+        write_private(&self.key_path, key.as_slice())?;
+        Ok(key)
+    }
+}
+
+/// Write a file only the current user can read
+fn write_private(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut opts = std::fs::OpenOptions::new();
+    opts.create(true).truncate(true).write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut file = opts.open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+#[cfg(target_os = "macos")]
+struct KeychainBackend;
+
+#[cfg(target_os = "macos")]
+impl KeychainBackend {
+    const SERVICE_NAME: &'static str = concat!(env!("CARGO_PKG_NAME"), ":secrets");
+    const ACCOUNT_NAME: &'static str = "default";
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    fn load(&self) -> std::io::Result<StoredSecrets> {
+        use security_framework::passwords::get_generic_password;
+
+        match get_generic_password(Self::SERVICE_NAME, Self::ACCOUNT_NAME) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                tracing::warn!(?error, "failed to parse keychain secrets; ignoring entry");
+                StoredSecrets::new()
+            })),
+            Err(e) if e.code() == Self::ERR_SEC_ITEM_NOT_FOUND => Ok(StoredSecrets::new()),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    }
+
+    fn save(&self, secrets: &StoredSecrets) -> std::io::Result<()> {
+        use security_framework::passwords::set_generic_password;
+
+        let bytes = serde_json::to_vec(secrets).map_err(std::io::Error::other)?;
+        set_generic_password(Self::SERVICE_NAME, Self::ACCOUNT_NAME, &bytes)
+            .map_err(std::io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_store(dir: &std::path::Path) -> SecretStore {
+        SecretStore {
+            backend: Backend::File(FileBackend {
+                path: dir.join("secrets.enc"),
+                key_path: dir.join("secrets.key"),
+            }),
+            secrets: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn secrets_round_trip_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_store(dir.path());
+        store
+            .set("ANTHROPIC_API_KEY", "sk-ant-0123456789abcd")
+            .await
+            .unwrap();
+
+        let on_disk = std::fs::read_to_string(dir.path().join("secrets.enc")).unwrap();
+        assert!(!on_disk.contains("sk-ant"));
+
+        let reloaded = file_store(dir.path());
+        reloaded.load().await.unwrap();
+        assert_eq!(
+            reloaded
+                .get("ANTHROPIC_API_KEY")
+                .await
+                .unwrap()
+                .expose_secret(),
+            "sk-ant-0123456789abcd"
+        );
+        assert_eq!(reloaded.list().await[0].masked, "••••abcd");
+        assert!(matches!(
+            reloaded.set(GITHUB_PAT, "ghp_x").await,
+            Err(SecretsError::ConfigManaged(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn config_secrets_move_into_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_store(dir.path());
+        let mut config = Config::default();
+        config.github.pat = Some("ghp_0123456789abcdef".to_string());
+
+        store.hydrate_config(&mut config).await.unwrap();
+        assert!(without_secrets(&config).github.pat.is_none());
+        assert!(store.agent_env().await.is_empty());
+
+        let mut edited = masked(&config);
+        assert_eq!(edited.github.pat.as_deref(), Some("••••cdef"));
+        restore_masked(&mut edited, &config);
+        assert_eq!(edited.github.pat, config.github.pat);
+    }
+
+    #[tokio::test]
+    async fn integration_secrets_stay_with_the_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = file_store(dir.path());
+        store
+            .set(SLACK_SIGNING_SECRET, "0123456789abcdef")
+            .await
+            .unwrap();
+        store.set("OPENAI_API_KEY", "sk-0123456789").await.unwrap();
+
+        let env = store.agent_env().await;
+        assert_eq!(env.keys().collect::<Vec<_>>(), ["OPENAI_API_KEY"]);
+        assert!(!store.list().await[1].passed_to_agents);
+    }
+}
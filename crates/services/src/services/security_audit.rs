@@ -0,0 +1,445 @@
+//! Optional security audit run over an attempt's worktree.
+//!
+//! Each repo opts into scanners (`cargo audit`, `npm audit`, `semgrep`).
+//! Their JSON output is normalized into findings stored against the attempt,
+//! summarized on the attempt's pull request, and high or critical findings
+//! can hold back auto-PR.
+
+use std::{path::Path, process::Stdio, time::Duration};
+
+use db::models::{
+    merge::{Merge, MergeStatus},
+    security_audit::{
+        CreateSecurityFinding, FindingSeverity, RepoSecurityAuditSettings, SecurityFinding,
+        SecurityScanner,
+    },
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::process::Command;
+
+use super::{
+    git::GitService,
+    git_host::{GitHostError, GitHostProvider, GitHostService},
+};
+
+const SCANNER_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Findings listed in a PR comment before the rest are summarized as a count
+const MAX_COMMENT_FINDINGS: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum SecurityAuditError {
+    #[error("{scanner} failed: {message}")]
+    Scanner {
+        scanner: &'static str,
+        message: String,
+    },
+    #[error("{0} timed out")]
+    Timeout(&'static str),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+}
+
+fn scanner_name(scanner: SecurityScanner) -> &'static str {
+    match scanner {
+        SecurityScanner::CargoAudit => "cargo audit",
+        SecurityScanner::NpmAudit => "npm audit",
+        SecurityScanner::Semgrep => "semgrep",
+    }
+}
+
+/// Parse `cargo audit --json`. Advisories carry no severity of their own, so
+/// every known vulnerability is reported as high.
+pub fn parse_cargo_audit(output: &Value) -> Vec<CreateSecurityFinding> {
+    output["vulnerabilities"]["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|vulnerability| {
+            let advisory = &vulnerability["advisory"];
+            let package = &vulnerability["package"];
+            CreateSecurityFinding {
+                scanner: SecurityScanner::CargoAudit,
+                severity: FindingSeverity::High,
+                rule_id: advisory["id"].as_str().unwrap_or("unknown").to_string(),
+                title: advisory["title"]
+                    .as_str()
+                    .unwrap_or("Vulnerable dependency")
+                    .to_string(),
+                location: package["name"]
+                    .as_str()
+                    .map(|name| match package["version"].as_str() {
+                        Some(version) => format!("{name}@{version}"),
+                        None => name.to_string(),
+                    }),
+            }
+        })
+        .collect()
+}
+
+/// Parse `npm audit --json` (npm 7 and later)
+pub fn parse_npm_audit(output: &Value) -> Vec<CreateSecurityFinding> {
+    let Some(vulnerabilities) = output["vulnerabilities"].as_object() else {
+        return Vec::new();
+    };
+    vulnerabilities
+        .iter()
+        .map(|(name, vulnerability)| {
+            // `via` holds advisories, or names of vulnerable dependencies
+            let advisory = vulnerability["via"]
+                .as_array()
+                .and_then(|via| via.iter().find(|v| v.is_object()));
+            CreateSecurityFinding {
+                scanner: SecurityScanner::NpmAudit,
+                severity: match vulnerability["severity"].as_str() {
+                    Some("critical") => FindingSeverity::Critical,
+                    Some("high") => FindingSeverity::High,
+                    Some("moderate") => FindingSeverity::Medium,
+                    Some("low") => FindingSeverity::Low,
+                    _ => FindingSeverity::Info,
+                },
+                rule_id: advisory
+                    .and_then(|a| a["url"].as_str())
+                    .unwrap_or(name)
+                    .to_string(),
+                title: advisory
+                    .and_then(|a| a["title"].as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Depends on a vulnerable version of {name}")),
+                location: Some(match vulnerability["range"].as_str() {
+                    Some(range) => format!("{name}@{range}"),
+                    None => name.clone(),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Parse `semgrep scan --json`
+pub fn parse_semgrep(output: &Value) -> Vec<CreateSecurityFinding> {
+    output["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|result| {
+            let extra = &result["extra"];
+            CreateSecurityFinding {
+                scanner: SecurityScanner::Semgrep,
+                severity: match extra["severity"].as_str() {
+                    Some("ERROR") => FindingSeverity::High,
+                    Some("WARNING") => FindingSeverity::Medium,
+                    _ => FindingSeverity::Low,
+                },
+                rule_id: result["check_id"].as_str().unwrap_or("unknown").to_string(),
+                title: extra["message"]
+                    .as_str()
+                    .unwrap_or("Semgrep finding")
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                location: result["path"].as_str().map(|path| {
+                    match result["start"]["line"].as_i64() {
+                        Some(line) => format!("{path}:{line}"),
+                        None => path.to_string(),
+                    }
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Run a scanner that prints JSON. Scanners exit non-zero when they find
+/// something, so the output is used whenever it parses.
+async fn run_json(
+    scanner: SecurityScanner,
+    program: &str,
+    args: &[&str],
+    dir: &Path,
+) -> Result<Value, SecurityAuditError> {
+    let name = scanner_name(scanner);
+    let mut command = Command::new(program);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .args(args)
+        .current_dir(dir);
+
+    let output = tokio::time::timeout(SCANNER_TIMEOUT, command.output())
+        .await
+        .map_err(|_| SecurityAuditError::Timeout(name))?
+        .map_err(|e| SecurityAuditError::Scanner {
+            scanner: name,
+            message: e.to_string(),
+        })?;
+
+    serde_json::from_slice(&output.stdout).map_err(|_| SecurityAuditError::Scanner {
+        scanner: name,
+        message: String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .last()
+            .unwrap_or("no JSON output")
+            .to_string(),
+    })
+}
+
+async fn run_scanner(
+    scanner: SecurityScanner,
+    settings: &RepoSecurityAuditSettings,
+    dir: &Path,
+) -> Result<Vec<CreateSecurityFinding>, SecurityAuditError> {
+    match scanner {
+        SecurityScanner::CargoAudit => {
+            if !dir.join("Cargo.lock").exists() {
+                return Ok(Vec::new());
+            }
+            let output = run_json(scanner, "cargo", &["audit", "--json"], dir).await?;
+            Ok(parse_cargo_audit(&output))
+        }
+        SecurityScanner::NpmAudit => {
+            if !dir.join("package-lock.json").exists() {
+                return Ok(Vec::new());
+            }
+            let output = run_json(scanner, "npm", &["audit", "--json"], dir).await?;
+            Ok(parse_npm_audit(&output))
+        }
+        SecurityScanner::Semgrep => {
+            let config = settings.semgrep_config.as_deref().unwrap_or("auto");
+            let output = run_json(
+                scanner,
+                "semgrep",
+                &["scan", "--json", "--quiet", "--config", config],
+                dir,
+            )
+            .await?;
+            Ok(parse_semgrep(&output))
+        }
+    }
+}
+
+/// Markdown summary posted on the pull request
+pub fn summary_comment(findings: &[SecurityFinding]) -> String {
+    let mut comment = String::from("## Security audit\n\n");
+    if findings.is_empty() {
+        comment.push_str("No findings from the configured scanners.\n");
+        return comment;
+    }
+
+    let counts: Vec<String> = [
+        (FindingSeverity::Critical, "critical"),
+        (FindingSeverity::High, "high"),
+        (FindingSeverity::Medium, "medium"),
+        (FindingSeverity::Low, "low"),
+        (FindingSeverity::Info, "info"),
+    ]
+    .iter()
+    .filter_map(|(severity, label)| {
+        let count = findings.iter().filter(|f| f.severity == *severity).count();
+        (count > 0).then(|| format!("{count} {label}"))
+    })
+    .collect();
+    comment.push_str(&format!(
+        "{} findings: {}.\n\n| Severity | Scanner | Finding | Location |\n|---|---|---|---|\n",
+        findings.len(),
+        counts.join(", ")
+    ));
+
+    let mut sorted: Vec<&SecurityFinding> = findings.iter().collect();
+    sorted.sort_by(|a, b| b.severity.cmp(&a.severity));
+    for finding in sorted.iter().take(MAX_COMMENT_FINDINGS) {
+        comment.push_str(&format!(
+            "| {:?} | {} | {} (`{}`) | {} |\n",
+            finding.severity,
+            scanner_name(finding.scanner),
+            finding.title.replace('|', "\\|"),
+            finding.rule_id,
+            finding
+                .location
+                .as_deref()
+                .map(|l| format!("`{l}`"))
+                .unwrap_or_default()
+        ));
+    }
+    if findings.len() > MAX_COMMENT_FINDINGS {
+        comment.push_str(&format!(
+            "\n{} more findings are listed in the attempt.\n",
+            findings.len() - MAX_COMMENT_FINDINGS
+        ));
+    }
+    comment
+}
+
+/// Post the findings for `repo_id` on a pull request, if the repo asks for it
+pub async fn annotate_pr(
+    pool: &SqlitePool,
+    workspace_id: uuid::Uuid,
+    repo_id: uuid::Uuid,
+    repo_path: &Path,
+    remote_url: &str,
+    pr_number: i64,
+) -> Result<(), SecurityAuditError> {
+    let Some(settings) = RepoSecurityAuditSettings::find_by_repo_id(pool, repo_id).await? else {
+        return Ok(());
+    };
+    if !settings.comment_on_pr || settings.scanners.is_empty() {
+        return Ok(());
+    }
+    let findings: Vec<SecurityFinding> = SecurityFinding::find_by_workspace_id(pool, workspace_id)
+        .await?
+        .into_iter()
+        .filter(|f| f.repo_id == repo_id)
+        .collect();
+
+    let git_host = GitHostService::from_url(remote_url)?;
+    git_host
+        .add_pr_comment(
+            repo_path,
+            remote_url,
+            pr_number,
+            &summary_comment(&findings),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Run the configured scanners on every repo of the workspace, replacing
+/// earlier findings, and comment on pull requests that are already open
+pub async fn audit_workspace(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<Vec<SecurityFinding>, SecurityAuditError> {
+    let Some(container_ref) = &workspace.container_ref else {
+        return Ok(Vec::new());
+    };
+
+    let mut all_findings = Vec::new();
+    for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+        let Some(settings) = RepoSecurityAuditSettings::find_by_repo_id(pool, repo.id).await?
+        else {
+            continue;
+        };
+        if settings.scanners.is_empty() {
+            continue;
+        }
+
+        let worktree = Path::new(container_ref).join(&repo.name);
+        let mut findings = Vec::new();
+        for scanner in settings.scanners.iter().copied() {
+            match run_scanner(scanner, &settings, &worktree).await {
+                Ok(found) => findings.extend(found),
+                Err(e) => tracing::warn!("Security scan of repo {} failed: {}", repo.name, e),
+            }
+        }
+        all_findings.extend(
+            SecurityFinding::replace_for_repo(pool, workspace.id, repo.id, &findings).await?,
+        );
+
+        for merge in Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await? {
+            let Merge::Pr(pr) = merge else {
+                continue;
+            };
+            if !matches!(pr.pr_info.status, MergeStatus::Open) {
+                continue;
+            }
+            let remote_url = git
+                .resolve_remote_name_for_branch(&repo.path, &workspace.branch)
+                .and_then(|remote| git.get_remote_url(&repo.path, &remote));
+            let result = match remote_url {
+                Ok(remote_url) => {
+                    annotate_pr(
+                        pool,
+                        workspace.id,
+                        repo.id,
+                        &repo.path,
+                        &remote_url,
+                        pr.pr_info.number,
+                    )
+                    .await
+                }
+                Err(e) => {
+                    tracing::warn!("No remote for repo {}: {}", repo.name, e);
+                    continue;
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Failed to comment security findings on PR #{}: {}",
+                    pr.pr_info.number,
+                    e
+                );
+            }
+        }
+    }
+    Ok(all_findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_npm_audit_severities() {
+        let output = json!({
+            "vulnerabilities": {
+                "lodash": {
+                    "severity": "critical",
+                    "range": "<4.17.21",
+                    "via": [{ "title": "Prototype Pollution", "url": "https://github.com/advisories/GHSA-1" }]
+                },
+                "express": { "severity": "moderate", "via": ["qs"] }
+            }
+        });
+        let findings = parse_npm_audit(&output);
+        assert_eq!(findings.len(), 2);
+        let lodash = findings
+            .iter()
+            .find(|f| f.location.as_deref() == Some("lodash@<4.17.21"))
+            .unwrap();
+        assert_eq!(lodash.severity, FindingSeverity::Critical);
+        assert_eq!(lodash.title, "Prototype Pollution");
+        let express = findings.iter().find(|f| f.rule_id == "express").unwrap();
+        assert_eq!(express.severity, FindingSeverity::Medium);
+    }
+
+    #[test]
+    fn parses_semgrep_and_cargo_audit() {
+        let semgrep = json!({
+            "results": [{
+                "check_id": "rust.lang.security.unsafe-usage",
+                "path": "src/lib.rs",
+                "start": { "line": 12 },
+                "extra": { "message": "Unsafe block\nmore detail", "severity": "ERROR" }
+            }]
+        });
+        assert_eq!(
+            parse_semgrep(&semgrep),
+            vec![CreateSecurityFinding {
+                scanner: SecurityScanner::Semgrep,
+                severity: FindingSeverity::High,
+                rule_id: "rust.lang.security.unsafe-usage".to_string(),
+                title: "Unsafe block".to_string(),
+                location: Some("src/lib.rs:12".to_string()),
+            }]
+        );
+
+        let cargo = json!({
+            "vulnerabilities": { "list": [{
+                "advisory": { "id": "RUSTSEC-2024-0001", "title": "Memory corruption" },
+                "package": { "name": "foo", "version": "1.0.0" }
+            }]}
+        });
+        let findings = parse_cargo_audit(&cargo);
+        assert_eq!(findings[0].rule_id, "RUSTSEC-2024-0001");
+        assert_eq!(findings[0].location.as_deref(), Some("foo@1.0.0"));
+    }
+}
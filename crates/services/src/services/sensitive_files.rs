@@ -0,0 +1,234 @@
+//! Files whose contents never leave the worktree in diff-derived text.
+//!
+//! The global list lives in the config and starts from
+//! [`DEFAULT_SENSITIVE_PATTERNS`], extended by the organization's shared
+//! patterns; projects can add patterns or drop global ones. Matching diffs
+//! keep their path and line counts but lose their contents before they reach
+//! digests, checklists or prompts.
+
+use db::models::{project::Project, workspace::Workspace};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::diff::{Diff, compute_line_change_counts};
+use uuid::Uuid;
+
+use super::org_config;
+
+/// Built-in patterns, in `.gitignore` syntax
+pub const DEFAULT_SENSITIVE_PATTERNS: &[&str] = &[
+    ".env",
+    ".env.*",
+    "!.env.example",
+    "!.env.sample",
+    "*.pem",
+    "*.key",
+    "*.p12",
+    "*.pfx",
+    "*.keystore",
+    "*.jks",
+    "id_rsa*",
+    "id_ed25519*",
+    ".npmrc",
+    ".pypirc",
+    ".netrc",
+    ".aws/credentials",
+    "credentials.json",
+    "service-account*.json",
+    "secrets.*",
+];
+
+pub fn default_patterns() -> Vec<String> {
+    DEFAULT_SENSITIVE_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Project changes to the global list
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectSensitivePatterns {
+    pub added: Vec<String>,
+    /// Global patterns that do not apply to this project
+    pub removed: Vec<String>,
+}
+
+/// Global patterns minus the project's removals, plus its additions
+pub fn effective_patterns(global: &[String], project: &ProjectSensitivePatterns) -> Vec<String> {
+    let mut patterns: Vec<String> = global
+        .iter()
+        .filter(|p| !project.removed.contains(p))
+        .cloned()
+        .collect();
+    for pattern in &project.added {
+        if !patterns.contains(pattern) {
+            patterns.push(pattern.clone());
+        }
+    }
+    patterns
+}
+
+/// Config patterns plus those shared by the organization
+fn with_org_patterns(global: &[String], shared: Vec<String>) -> Vec<String> {
+    let mut patterns = global.to_vec();
+    for pattern in shared {
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+    patterns
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SensitiveFileRules {
+    patterns: Vec<String>,
+    matcher: Option<Gitignore>,
+}
+
+impl SensitiveFileRules {
+    /// Build a matcher from `patterns`, skipping any that do not parse
+    pub fn new(patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        let mut valid = Vec::new();
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match builder.add_line(None, pattern) {
+                Ok(_) => valid.push(pattern.to_string()),
+                Err(e) => {
+                    tracing::warn!("Skipping invalid sensitive file pattern {pattern:?}: {e}")
+                }
+            }
+        }
+        match builder.build() {
+            Ok(matcher) if !valid.is_empty() => Self {
+                patterns: valid,
+                matcher: Some(matcher),
+            },
+            Ok(_) => Self::default(),
+            Err(e) => {
+                tracing::warn!("Failed to build sensitive file matcher: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Rules for a project, given the global patterns from the config
+    pub async fn for_project(
+        pool: &SqlitePool,
+        global: &[String],
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let (added, removed) = Project::find_sensitive_patterns(pool, project_id).await?;
+        Ok(Self::new(&effective_patterns(
+            &with_org_patterns(global, org_config::sensitive_file_patterns()),
+            &ProjectSensitivePatterns { added, removed },
+        )))
+    }
+
+    pub async fn for_workspace(
+        pool: &SqlitePool,
+        global: &[String],
+        workspace: &Workspace,
+    ) -> Result<Self, sqlx::Error> {
+        match workspace.parent_task(pool).await? {
+            Some(task) => Self::for_project(pool, global, task.project_id).await,
+            None => Ok(Self::new(&with_org_patterns(
+                global,
+                org_config::sensitive_file_patterns(),
+            ))),
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Pattern that marks a repo-relative path as sensitive, if any
+    pub fn matching_pattern(&self, path: &str) -> Option<String> {
+        let matcher = self.matcher.as_ref()?;
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            return None;
+        }
+        match matcher.matched_path_or_any_parents(path, false) {
+            ignore::Match::Ignore(glob) => Some(glob.original().to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn is_sensitive(&self, path: &str) -> bool {
+        self.matching_pattern(path).is_some()
+    }
+
+    /// Drop the contents of sensitive files, keeping paths and line counts
+    pub fn sanitize(&self, diffs: &mut [Diff]) {
+        for diff in diffs {
+            let sensitive = [diff.old_path.as_deref(), diff.new_path.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|path| self.is_sensitive(path));
+            if !sensitive || diff.content_omitted {
+                continue;
+            }
+            if diff.additions.is_none() || diff.deletions.is_none() {
+                let (additions, deletions) = compute_line_change_counts(
+                    diff.old_content.as_deref().unwrap_or_default(),
+                    diff.new_content.as_deref().unwrap_or_default(),
+                );
+                diff.additions = Some(additions);
+                diff.deletions = Some(deletions);
+            }
+            diff.old_content = None;
+            diff.new_content = None;
+            diff.content_omitted = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_cover_secrets_but_not_plain_config() {
+        let rules = SensitiveFileRules::new(&default_patterns());
+        assert_eq!(rules.matching_pattern(".env").as_deref(), Some(".env"));
+        assert_eq!(
+            rules.matching_pattern("apps/web/.env.local").as_deref(),
+            Some(".env.*")
+        );
+        assert!(rules.is_sensitive("deploy/certs/server.pem"));
+        assert!(!rules.is_sensitive(".env.example"));
+        assert!(!rules.is_sensitive("config.json"));
+        assert!(!rules.is_sensitive("src/keyboard.rs"));
+    }
+
+    #[test]
+    fn project_rules_adjust_global_patterns() {
+        let project = ProjectSensitivePatterns {
+            added: vec!["config/prod.json".to_string()],
+            removed: vec!["*.key".to_string()],
+        };
+        let rules = SensitiveFileRules::new(&effective_patterns(&default_patterns(), &project));
+        assert!(rules.is_sensitive("config/prod.json"));
+        assert!(!rules.is_sensitive("fixtures/test.key"));
+        assert!(rules.is_sensitive("id_rsa.pub"));
+    }
+
+    #[test]
+    fn organization_patterns_extend_the_global_list() {
+        let global = vec![".env".to_string(), "*.pem".to_string()];
+        let shared = vec!["*.pem".to_string(), "secrets/**".to_string()];
+        let patterns = with_org_patterns(&global, shared);
+        assert_eq!(patterns, vec![".env", "*.pem", "secrets/**"]);
+
+        // Projects can still drop a pattern the organization shares
+        let project = ProjectSensitivePatterns {
+            added: Vec::new(),
+            removed: vec!["secrets/**".to_string()],
+        };
+        let rules = SensitiveFileRules::new(&effective_patterns(&patterns, &project));
+        assert!(!rules.is_sensitive("secrets/api.txt"));
+        assert!(SensitiveFileRules::new(&patterns).is_sensitive("secrets/api.txt"));
+    }
+}
@@ -1,9 +1,12 @@
+mod color;
 mod config;
+mod merge;
 mod publisher;
 mod status;
 
 pub use config::ShareConfig;
-pub use publisher::{SharePublisher, SharedTaskDetails};
+pub use publisher::{PublishedProjectContents, SharePublisher, SharedTaskDetails};
+pub use remote::db::task_execution_status::SharedTaskExecutionState;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -0,0 +1,68 @@
+//! Local labels store hex colors while remote tags use HSL ("H S% L%").
+
+/// Used for labels whose color cannot be parsed
+const FALLBACK_HSL: &str = "0 0% 50%";
+
+/// Convert a `#rgb` or `#rrggbb` color to the remote tag format
+pub(super) fn hex_to_hsl(hex: &str) -> String {
+    parse_hex(hex)
+        .map(|(r, g, b)| rgb_to_hsl(r, g, b))
+        .unwrap_or_else(|| FALLBACK_HSL.to_string())
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    let digits = hex.strip_prefix('#')?;
+    let expanded: String = match digits.len() {
+        3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 => digits.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(expanded.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> String {
+    let (r, g, b) = (
+        f64::from(r) / 255.0,
+        f64::from(g) / 255.0,
+        f64::from(b) / 255.0,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    let (hue, saturation) = if delta == 0.0 {
+        (0.0, 0.0)
+    } else {
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, saturation)
+    };
+
+    format!(
+        "{} {}% {}%",
+        hue.round() as u16 % 360,
+        (saturation * 100.0).round() as u8,
+        (lightness * 100.0).round() as u8
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_hex_to_hsl() {
+        assert_eq!(hex_to_hsl("#ff0000"), "0 100% 50%");
+        assert_eq!(hex_to_hsl("#1f6feb"), "216 84% 52%");
+        assert_eq!(hex_to_hsl("#fff"), "0 0% 100%");
+        assert_eq!(hex_to_hsl("not a color"), FALLBACK_HSL);
+    }
+}
@@ -0,0 +1,216 @@
+//! Three-way merge of a shared task's fields when it was edited both locally
+//! and by a teammate since the last sync.
+//!
+//! Title and description go to whichever side was edited last and are flagged
+//! as conflicts. Status never moves backwards: the further-along status wins.
+
+use chrono::{DateTime, Utc};
+use db::models::task::{Task, TaskStatus};
+
+/// The fields of a shared task that are kept in sync
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncedFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+}
+
+impl From<&Task> for SyncedFields {
+    fn from(task: &Task) -> Self {
+        Self {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldConflict {
+    pub field: &'static str,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub resolved: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub merged: SyncedFields,
+    pub conflicts: Vec<FieldConflict>,
+}
+
+/// Merge `local` and `remote` edits made since `base`, the fields as of the
+/// last sync
+pub fn merge(
+    base: &SyncedFields,
+    local: &SyncedFields,
+    local_updated_at: DateTime<Utc>,
+    remote: &SyncedFields,
+    remote_updated_at: DateTime<Utc>,
+) -> MergeOutcome {
+    let local_is_newer = local_updated_at >= remote_updated_at;
+    let mut conflicts = Vec::new();
+
+    let title = merge_field(&base.title, &local.title, &remote.title, |l, r| {
+        if local_is_newer { l } else { r }
+    });
+    let description = merge_field(
+        &base.description,
+        &local.description,
+        &remote.description,
+        |l, r| if local_is_newer { l } else { r },
+    );
+    let status = merge_field(&base.status, &local.status, &remote.status, |l, r| {
+        if precedence(&l) >= precedence(&r) {
+            l
+        } else {
+            r
+        }
+    });
+
+    if let Some(title) = &title.conflict {
+        conflicts.push(FieldConflict {
+            field: "title",
+            local: Some(title.0.clone()),
+            remote: Some(title.1.clone()),
+            resolved: Some(title.2.clone()),
+        });
+    }
+    if let Some(description) = &description.conflict {
+        conflicts.push(FieldConflict {
+            field: "description",
+            local: description.0.clone(),
+            remote: description.1.clone(),
+            resolved: description.2.clone(),
+        });
+    }
+    if let Some(status) = &status.conflict {
+        conflicts.push(FieldConflict {
+            field: "status",
+            local: Some(status.0.to_string()),
+            remote: Some(status.1.to_string()),
+            resolved: Some(status.2.to_string()),
+        });
+    }
+
+    MergeOutcome {
+        merged: SyncedFields {
+            title: title.value,
+            description: description.value,
+            status: status.value,
+        },
+        conflicts,
+    }
+}
+
+struct MergedField<T> {
+    value: T,
+    /// Local, remote and resolved values when both sides changed the field
+    conflict: Option<(T, T, T)>,
+}
+
+fn merge_field<T: Clone + PartialEq>(
+    base: &T,
+    local: &T,
+    remote: &T,
+    resolve: impl FnOnce(T, T) -> T,
+) -> MergedField<T> {
+    if local == remote || remote == base {
+        return MergedField {
+            value: local.clone(),
+            conflict: None,
+        };
+    }
+    if local == base {
+        return MergedField {
+            value: remote.clone(),
+            conflict: None,
+        };
+    }
+    let value = resolve(local.clone(), remote.clone());
+    MergedField {
+        conflict: Some((local.clone(), remote.clone(), value.clone())),
+        value,
+    }
+}
+
+/// How far along a status is; completed work outranks a cancellation
+fn precedence(status: &TaskStatus) -> u8 {
+    match status {
+        TaskStatus::Todo => 0,
+        TaskStatus::InProgress => 1,
+        TaskStatus::InReview => 2,
+        TaskStatus::Cancelled => 3,
+        TaskStatus::Done => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn fields(title: &str, description: Option<&str>, status: TaskStatus) -> SyncedFields {
+        SyncedFields {
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            status,
+        }
+    }
+
+    #[test]
+    fn edits_to_different_fields_are_combined() {
+        let now = Utc::now();
+        let base = fields("Fix login", None, TaskStatus::Todo);
+        let local = fields("Fix login flow", None, TaskStatus::Todo);
+        let remote = fields("Fix login", Some("Steps inside"), TaskStatus::InProgress);
+
+        let outcome = merge(&base, &local, now, &remote, now - Duration::minutes(1));
+
+        assert_eq!(
+            outcome.merged,
+            fields(
+                "Fix login flow",
+                Some("Steps inside"),
+                TaskStatus::InProgress
+            )
+        );
+        assert!(outcome.conflicts.is_empty());
+    }
+
+    #[test]
+    fn conflicting_text_goes_to_the_last_writer() {
+        let now = Utc::now();
+        let base = fields("Fix login", None, TaskStatus::Todo);
+        let local = fields("Fix login (local)", None, TaskStatus::Todo);
+        let remote = fields("Fix login (remote)", None, TaskStatus::Todo);
+
+        let outcome = merge(&base, &local, now - Duration::minutes(1), &remote, now);
+
+        assert_eq!(outcome.merged.title, "Fix login (remote)");
+        assert_eq!(
+            outcome.conflicts,
+            vec![FieldConflict {
+                field: "title",
+                local: Some("Fix login (local)".to_string()),
+                remote: Some("Fix login (remote)".to_string()),
+                resolved: Some("Fix login (remote)".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn conflicting_status_keeps_the_furthest_along() {
+        let now = Utc::now();
+        let base = fields("Fix login", None, TaskStatus::InProgress);
+        let local = fields("Fix login", None, TaskStatus::Cancelled);
+        let remote = fields("Fix login", None, TaskStatus::Done);
+
+        let outcome = merge(&base, &local, now, &remote, now - Duration::minutes(1));
+
+        assert_eq!(outcome.merged.status, TaskStatus::Done);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].field, "status");
+    }
+}
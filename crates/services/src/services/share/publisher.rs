@@ -1,16 +1,32 @@
+use chrono::{DateTime, Utc};
 use db::{
     DBService,
     models::{
+        merge::{Merge, MergeStatus, PullRequestInfo},
         project::Project,
+        project_share_rule::ProjectShareRule,
+        shared_task_sync_base::SharedTaskSyncBase,
         task::{CreateTask, Task, TaskStatus},
+        task_activity::{CreateTaskActivity, TaskActivity, TaskActivityKind},
+        task_label::TaskLabel,
+        workspace::Workspace,
     },
 };
-use remote::routes::tasks::{
-    AssignSharedTaskRequest, CreateSharedTaskRequest, SharedTaskResponse, UpdateSharedTaskRequest,
+use remote::{
+    db::{task_execution_status::SharedTaskExecutionState, types::PullRequestStatus},
+    routes::tasks::{
+        AssignSharedTaskRequest, CreateSharedTaskRequest, ImportSharedTaskRequest,
+        ImportSharedTasksRequest, ImportTagRequest, ReportExecutionStatusRequest,
+        ReportSharedTaskAttemptRequest, SharedTaskResponse, UpdateSharedTaskRequest,
+    },
 };
 use uuid::Uuid;
 
-use super::{ShareError, status};
+use super::{
+    ShareError, color,
+    merge::{self, SyncedFields},
+    status,
+};
 use crate::services::remote_client::RemoteClient;
 
 #[derive(Clone)]
@@ -28,6 +44,13 @@ pub struct SharedTaskDetails {
     pub status: TaskStatus,
 }
 
+/// What was pushed when a local project was published
+#[derive(Debug, Clone, Copy)]
+pub struct PublishedProjectContents {
+    pub tags_published: usize,
+    pub tasks_published: usize,
+}
+
 impl SharePublisher {
     pub fn new(db: DBService, client: RemoteClient) -> Self {
         Self { db, client }
@@ -59,29 +82,203 @@ impl SharePublisher {
         let remote_task = self.client.create_shared_task(&payload).await?;
 
         Task::set_shared_task_id(&self.db.pool, task.id, Some(remote_task.task.id)).await?;
+        self.record_sync_base(
+            task.id,
+            &SyncedFields::from(&task),
+            Some(remote_task.task.updated_at),
+        )
+        .await?;
         Ok(remote_task.task.id)
     }
 
-    pub async fn update_shared_task(&self, task: &Task) -> Result<(), ShareError> {
+    /// Share or unshare a task according to its project's share rules. Tasks
+    /// shared by hand are never unshared here, and tasks shared by a rule are
+    /// assigned to the signed-in user. Returns whether anything changed.
+    pub async fn apply_share_rules(&self, task_id: Uuid) -> Result<bool, ShareError> {
+        let task = Task::find_by_id(&self.db.pool, task_id)
+            .await?
+            .ok_or(ShareError::TaskNotFound(task_id))?;
+        let rules = ProjectShareRule::find_by_project_id(&self.db.pool, task.project_id).await?;
+        let label_ids: Vec<Uuid> = TaskLabel::find_by_task_id(&self.db.pool, task.id)
+            .await?
+            .into_iter()
+            .map(|label| label.id)
+            .collect();
+        let wanted = rules
+            .iter()
+            .any(|rule| rule.matches(&task.status, &label_ids));
+
+        match task.shared_task_id {
+            None if wanted => {
+                let project = Project::find_by_id(&self.db.pool, task.project_id)
+                    .await?
+                    .ok_or(ShareError::ProjectNotFound(task.project_id))?;
+                if project.remote_project_id.is_none() {
+                    return Ok(false);
+                }
+                let user_id = self.client.profile().await?.user_id;
+                self.share_task(task.id, user_id).await?;
+                ProjectShareRule::mark_rule_shared(&self.db.pool, task.id).await?;
+                Ok(true)
+            }
+            Some(shared_task_id)
+                if !wanted && ProjectShareRule::is_rule_shared(&self.db.pool, task.id).await? =>
+            {
+                self.delete_shared_task(shared_task_id).await?;
+                ProjectShareRule::unmark_rule_shared(&self.db.pool, task.id).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Apply share rules to every task of a project, after its rules change
+    pub async fn apply_project_share_rules(&self, project_id: Uuid) -> Result<usize, ShareError> {
+        let tasks = Task::find_by_project_id(&self.db.pool, project_id).await?;
+        let mut changed = 0;
+        for task in tasks {
+            if self.apply_share_rules(task.id).await? {
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Push a project's labels and open tasks to the remote project it was just
+    /// linked to. The pushed tasks become shared tasks assigned to the caller.
+    pub async fn publish_project_contents(
+        &self,
+        project_id: Uuid,
+        remote_project_id: Uuid,
+    ) -> Result<PublishedProjectContents, ShareError> {
+        let labels = TaskLabel::find_by_project_id(&self.db.pool, project_id).await?;
+        let tasks = Task::find_open_unshared_by_project_id(&self.db.pool, project_id).await?;
+
+        let request = ImportSharedTasksRequest {
+            project_id: remote_project_id,
+            tags: labels
+                .iter()
+                .map(|label| ImportTagRequest {
+                    name: label.name.clone(),
+                    color: color::hex_to_hsl(&label.color),
+                })
+                .collect(),
+            tasks: tasks
+                .iter()
+                .map(|task| ImportSharedTaskRequest {
+                    title: task.title.clone(),
+                    description: task.description.clone(),
+                    status: status::to_remote(&task.status),
+                })
+                .collect(),
+        };
+        let response = self.client.import_shared_tasks(&request).await?;
+        if response.tasks.len() != tasks.len() {
+            return Err(ShareError::InvalidResponse);
+        }
+
+        for (task, remote_task) in tasks.iter().zip(&response.tasks) {
+            Task::set_shared_task_id(&self.db.pool, task.id, Some(remote_task.id)).await?;
+            self.record_sync_base(
+                task.id,
+                &SyncedFields::from(task),
+                Some(remote_task.updated_at),
+            )
+            .await?;
+        }
+
+        Ok(PublishedProjectContents {
+            tags_published: response.tags.len(),
+            tasks_published: response.tasks.len(),
+        })
+    }
+
+    /// Push local edits to a shared task, merged with any a teammate made since
+    /// the last sync. Returns the task as merged; conflicting edits are
+    /// recorded as task activity.
+    pub async fn update_shared_task(&self, task: &Task) -> Result<Task, ShareError> {
         // early exit if task has not been shared
         let Some(shared_task_id) = task.shared_task_id else {
-            return Ok(());
+            return Ok(task.clone());
         };
 
-        let payload = UpdateSharedTaskRequest {
-            title: Some(task.title.clone()),
-            description: task.description.clone(),
-            status: Some(status::to_remote(&task.status)),
+        let remote = self.client.get_shared_task(shared_task_id).await?;
+        let local = SyncedFields::from(task);
+        let remote_fields = SyncedFields {
+            title: remote.title,
+            description: remote.description,
+            status: status::from_remote(&remote.status),
+        };
+        // Without a base nothing is known about remote edits, so local edits
+        // win as they did before bases were recorded
+        let base = match SharedTaskSyncBase::find_by_task_id(&self.db.pool, task.id).await? {
+            Some(base) => SyncedFields {
+                title: base.title,
+                description: base.description,
+                status: base.status,
+            },
+            None => remote_fields.clone(),
         };
+        let outcome = merge::merge(
+            &base,
+            &local,
+            task.updated_at,
+            &remote_fields,
+            remote.updated_at,
+        );
 
-        self.client
+        let payload = UpdateSharedTaskRequest {
+            title: Some(outcome.merged.title.clone()),
+            description: outcome.merged.description.clone(),
+            status: Some(status::to_remote(&outcome.merged.status)),
+        };
+        let response = self
+            .client
             .update_shared_task(shared_task_id, &payload)
             .await?;
+        self.record_sync_base(task.id, &outcome.merged, Some(response.task.updated_at))
+            .await?;
 
-        Ok(())
+        for conflict in &outcome.conflicts {
+            tracing::info!(
+                "Resolved conflicting {} edits on shared task {}",
+                conflict.field,
+                shared_task_id
+            );
+            TaskActivity::create(
+                &self.db.pool,
+                task.id,
+                &CreateTaskActivity {
+                    kind: TaskActivityKind::SyncConflict,
+                    field: Some(conflict.field.to_string()),
+                    local_value: conflict.local.clone(),
+                    remote_value: conflict.remote.clone(),
+                    resolved_value: conflict.resolved.clone(),
+                },
+            )
+            .await?;
+        }
+
+        if outcome.merged == local {
+            return Ok(task.clone());
+        }
+        let merged = Task::update(
+            &self.db.pool,
+            task.id,
+            task.project_id,
+            outcome.merged.title,
+            outcome.merged.description,
+            outcome.merged.status,
+            task.parent_workspace_id,
+            task.use_ralph_wiggum,
+            task.ralph_max_iterations,
+            task.ralph_completion_promise.clone(),
+        )
+        .await?;
+        Ok(merged)
     }
 
-    pub async fn update_shared_task_by_id(&self, task_id: Uuid) -> Result<(), ShareError> {
+    pub async fn update_shared_task_by_id(&self, task_id: Uuid) -> Result<Task, ShareError> {
         let task = Task::find_by_id(&self.db.pool, task_id)
             .await?
             .ok_or(ShareError::TaskNotFound(task_id))?;
@@ -89,6 +286,79 @@ impl SharePublisher {
         self.update_shared_task(&task).await
     }
 
+    /// Remember the fields both sides agreed on, the base for the next merge
+    async fn record_sync_base(
+        &self,
+        task_id: Uuid,
+        fields: &SyncedFields,
+        remote_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ShareError> {
+        SharedTaskSyncBase::upsert(
+            &self.db.pool,
+            &SharedTaskSyncBase {
+                task_id,
+                title: fields.title.clone(),
+                description: fields.description.clone(),
+                status: fields.status.clone(),
+                remote_updated_at,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Report an attempt lifecycle event for a task, if it has been shared
+    pub async fn report_execution_status(
+        &self,
+        task: &Task,
+        state: SharedTaskExecutionState,
+        detail: Option<String>,
+    ) -> Result<(), ShareError> {
+        let Some(shared_task_id) = task.shared_task_id else {
+            return Ok(());
+        };
+
+        self.client
+            .report_shared_task_execution_status(
+                shared_task_id,
+                &ReportExecutionStatusRequest { state, detail },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Publish an attempt's summary and latest pull request state so teammates
+    /// see it on their boards, if its task has been shared
+    pub async fn publish_attempt(&self, workspace_id: Uuid) -> Result<(), ShareError> {
+        let Some(workspace) = Workspace::find_by_id(&self.db.pool, workspace_id).await? else {
+            return Ok(());
+        };
+        let Some(task) = workspace.parent_task(&self.db.pool).await? else {
+            return Ok(());
+        };
+        let Some(shared_task_id) = task.shared_task_id else {
+            return Ok(());
+        };
+
+        let summary = Workspace::find_summary(&self.db.pool, workspace.id).await?;
+        // Merges are ordered newest first
+        let pr = Merge::find_by_workspace_id(&self.db.pool, workspace.id)
+            .await?
+            .into_iter()
+            .find_map(|merge| match merge {
+                Merge::Pr(pr) => Some(pr.pr_info),
+                Merge::Direct(_) => None,
+            });
+
+        let request = attempt_report(workspace.branch, summary, pr.as_ref());
+        self.client
+            .report_shared_task_attempt(shared_task_id, workspace.id, &request)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn assign_shared_task(
         &self,
         shared_task_id: Uuid,
@@ -145,6 +415,8 @@ impl SharePublisher {
 
         let id = Uuid::new_v4();
         let task = Task::create(&self.db.pool, &create_task, id).await?;
+        self.record_sync_base(task.id, &SyncedFields::from(&task), None)
+            .await?;
 
         Ok(Some(task))
     }
@@ -202,3 +474,73 @@ impl SharePublisher {
         Ok(())
     }
 }
+
+/// What teammates see of an attempt: its branch, summary and latest PR
+fn attempt_report(
+    branch: String,
+    summary: Option<String>,
+    pr: Option<&PullRequestInfo>,
+) -> ReportSharedTaskAttemptRequest {
+    ReportSharedTaskAttemptRequest {
+        branch,
+        summary,
+        pr_url: pr.map(|pr| pr.url.clone()),
+        pr_number: pr.and_then(|pr| i32::try_from(pr.number).ok()),
+        pr_status: pr.and_then(|pr| match pr.status {
+            MergeStatus::Open => Some(PullRequestStatus::Open),
+            MergeStatus::Merged => Some(PullRequestStatus::Merged),
+            MergeStatus::Closed => Some(PullRequestStatus::Closed),
+            MergeStatus::Unknown => None,
+        }),
+        pr_merged_at: pr.and_then(|pr| pr.merged_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(number: i64, status: MergeStatus) -> PullRequestInfo {
+        PullRequestInfo {
+            number,
+            url: format!("https://github.com/acme/widgets/pull/{number}"),
+            status,
+            merged_at: None,
+            merge_commit_sha: None,
+        }
+    }
+
+    #[test]
+    fn attempt_report_carries_the_latest_pr_state() {
+        let merged_at = Utc::now();
+        let merged = PullRequestInfo {
+            merged_at: Some(merged_at),
+            ..pr(42, MergeStatus::Merged)
+        };
+        let report = attempt_report(
+            "vk/1a2b-fix".to_string(),
+            Some("Fixed it".to_string()),
+            Some(&merged),
+        );
+        assert_eq!(report.pr_number, Some(42));
+        assert_eq!(report.pr_status, Some(PullRequestStatus::Merged));
+        assert_eq!(report.pr_merged_at, Some(merged_at));
+        assert_eq!(
+            report.pr_url.as_deref(),
+            Some("https://github.com/acme/widgets/pull/42")
+        );
+
+        // Unknown states and numbers that do not fit are left out
+        let report = attempt_report(
+            "vk/1a2b-fix".to_string(),
+            None,
+            Some(&pr(i64::MAX, MergeStatus::Unknown)),
+        );
+        assert_eq!(report.pr_number, None);
+        assert_eq!(report.pr_status, None);
+        assert!(report.pr_url.is_some());
+
+        let report = attempt_report("vk/1a2b-fix".to_string(), None, None);
+        assert!(report.pr_url.is_none() && report.pr_status.is_none());
+    }
+}
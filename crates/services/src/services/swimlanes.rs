@@ -0,0 +1,199 @@
+//! Board swimlanes: a project's tasks grouped by epic, assignee or label.
+
+use std::collections::HashMap;
+
+use db::models::{
+    epic::Epic,
+    task::{Task, TaskWithAttemptStatus},
+    task_assignee::TaskAssignee,
+    task_label::TaskLabel,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum LaneGrouping {
+    Epic,
+    Assignee,
+    /// A task with several labels shows up in each of their lanes
+    Label,
+}
+
+/// One swimlane and its tasks, in board order
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoardLane {
+    /// Epic, user or label id; `None` for the lane of tasks without one
+    pub id: Option<Uuid>,
+    /// Epic title or label name
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub tasks: Vec<TaskWithAttemptStatus>,
+}
+
+impl BoardLane {
+    fn new(id: Option<Uuid>, name: Option<String>, color: Option<String>) -> Self {
+        Self {
+            id,
+            name,
+            color,
+            tasks: Vec::new(),
+        }
+    }
+}
+
+/// Spreads tasks over `lanes` by the lane ids `lanes_of` returns; tasks
+/// without a known lane end up in a trailing lane with no id, which is left
+/// out when empty. Lanes for ids not in `lanes` are added in order of first use.
+fn fill_lanes(
+    mut lanes: Vec<BoardLane>,
+    tasks: Vec<TaskWithAttemptStatus>,
+    lanes_of: impl Fn(&TaskWithAttemptStatus) -> Vec<Uuid>,
+) -> Vec<BoardLane> {
+    let mut index: HashMap<Uuid, usize> = lanes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, lane)| lane.id.map(|id| (id, i)))
+        .collect();
+    let mut ungrouped = BoardLane::new(None, None, None);
+
+    for task in tasks {
+        let ids = lanes_of(&task);
+        if ids.is_empty() {
+            ungrouped.tasks.push(task);
+            continue;
+        }
+        for id in ids {
+            let i = *index.entry(id).or_insert_with(|| {
+                lanes.push(BoardLane::new(Some(id), None, None));
+                lanes.len() - 1
+            });
+            lanes[i].tasks.push(task.clone());
+        }
+    }
+
+    if !ungrouped.tasks.is_empty() {
+        lanes.push(ungrouped);
+    }
+    lanes
+}
+
+pub async fn lanes(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    grouping: LaneGrouping,
+) -> Result<Vec<BoardLane>, sqlx::Error> {
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+
+    let lanes = match grouping {
+        LaneGrouping::Epic => {
+            let epics = Epic::find_by_project_id(pool, project_id).await?;
+            let epic_of = Epic::find_by_project_tasks(pool, project_id).await?;
+            let lanes = epics
+                .into_iter()
+                .map(|epic| BoardLane::new(Some(epic.id), Some(epic.title), epic.color))
+                .collect();
+            fill_lanes(lanes, tasks, |task| {
+                epic_of.get(&task.task.id).copied().into_iter().collect()
+            })
+        }
+        LaneGrouping::Assignee => {
+            let assignee_of = TaskAssignee::find_by_project_tasks(pool, project_id).await?;
+            fill_lanes(Vec::new(), tasks, |task| {
+                assignee_of
+                    .get(&task.task.id)
+                    .copied()
+                    .into_iter()
+                    .collect()
+            })
+        }
+        LaneGrouping::Label => {
+            let lanes = TaskLabel::find_by_project_id(pool, project_id)
+                .await?
+                .into_iter()
+                .map(|label| BoardLane::new(Some(label.id), Some(label.name), Some(label.color)))
+                .collect();
+            fill_lanes(lanes, tasks, |task| {
+                task.labels.iter().map(|label| label.id).collect()
+            })
+        }
+    };
+    Ok(lanes)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::task::TaskStatus;
+
+    use super::*;
+
+    fn task(title: &str) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            task: Task {
+                id: Uuid::new_v4(),
+                project_id: Uuid::nil(),
+                title: title.to_string(),
+                description: None,
+                status: TaskStatus::Todo,
+                parent_workspace_id: None,
+                shared_task_id: None,
+                use_ralph_wiggum: false,
+                ralph_max_iterations: None,
+                ralph_completion_promise: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: String::new(),
+            pr_number: None,
+            pr_url: None,
+            latest_summary: None,
+            labels: Vec::new(),
+            checklist_items: None,
+            canonical_workspace_id: None,
+        }
+    }
+
+    #[test]
+    fn keeps_lane_order_and_trails_ungrouped_tasks() {
+        let (first, second, unknown) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let lanes = vec![
+            BoardLane::new(Some(first), Some("First".into()), None),
+            BoardLane::new(Some(second), Some("Second".into()), None),
+        ];
+        let tasks = vec![task("a"), task("b"), task("c"), task("d")];
+        let lane_of: HashMap<Uuid, Vec<Uuid>> = HashMap::from([
+            (tasks[0].task.id, vec![second]),
+            (tasks[1].task.id, vec![first, second]),
+            (tasks[3].task.id, vec![unknown]),
+        ]);
+
+        let lanes = fill_lanes(lanes, tasks, |task| {
+            lane_of.get(&task.task.id).cloned().unwrap_or_default()
+        });
+
+        let titles: Vec<(Option<Uuid>, Vec<&str>)> = lanes
+            .iter()
+            .map(|lane| {
+                (
+                    lane.id,
+                    lane.tasks.iter().map(|t| t.task.title.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                (Some(first), vec!["b"]),
+                (Some(second), vec!["a", "b"]),
+                (Some(unknown), vec!["d"]),
+                (None, vec!["c"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Periodic sync of long-running attempts with their target branches.
+//!
+//! Every tick the target branch of each repo of an active attempt is fetched
+//! and compared with the attempt branch. Once the attempt is more than the
+//! configured number of commits behind it is either rebased, when the mode
+//! allows it and the worktree is clean and idle, or marked as needing a rebase
+//! and announced through the `needs_rebase` lifecycle hooks.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess, lifecycle_hook::LifecycleEvent, repo::Repo,
+        task::Task, workspace::Workspace, workspace_repo::WorkspaceRepo,
+        workspace_target_sync::WorkspaceTargetSync,
+    },
+};
+use serde_json::json;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+
+use crate::services::{
+    config::{Config, TargetSyncMode},
+    git::{GitService, GitServiceError},
+    lifecycle_hooks,
+};
+
+#[derive(Debug, Error)]
+enum TargetSyncError {
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("Sync task failed: {0}")]
+    Join(String),
+}
+
+/// What to do with an attempt repo after comparing it with its target branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncAction {
+    /// Close enough to the target branch
+    Record,
+    Rebase,
+    Flag,
+}
+
+fn decide(mode: &TargetSyncMode, behind: usize, threshold: u32) -> SyncAction {
+    if behind <= threshold as usize {
+        SyncAction::Record
+    } else if *mode == TargetSyncMode::AutoRebase {
+        SyncAction::Rebase
+    } else {
+        SyncAction::Flag
+    }
+}
+
+/// Service that keeps active attempts close to their target branches
+pub struct TargetSyncService {
+    db: DBService,
+    git: GitService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl TargetSyncService {
+    pub async fn spawn(
+        db: DBService,
+        git: GitService,
+        config: Arc<RwLock<Config>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            git,
+            config,
+            poll_interval: Duration::from_secs(30 * 60),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting target branch sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            let (mode, threshold) = {
+                let config = self.config.read().await;
+                (
+                    config.target_sync_mode.clone(),
+                    config.target_sync_threshold,
+                )
+            };
+            if mode == TargetSyncMode::Disabled {
+                continue;
+            }
+            if let Err(e) = self.sync_all(&mode, threshold).await {
+                error!("Error syncing attempts with their target branches: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all(&self, mode: &TargetSyncMode, threshold: u32) -> Result<(), TargetSyncError> {
+        let workspaces = Workspace::find_active(&self.db.pool).await?;
+        if workspaces.is_empty() {
+            debug!("No active attempts to sync");
+            return Ok(());
+        }
+
+        for workspace in workspaces {
+            let workspace_repos =
+                WorkspaceRepo::find_by_workspace_id(&self.db.pool, workspace.id).await?;
+            for workspace_repo in workspace_repos {
+                if let Err(e) = self
+                    .sync_repo(&workspace, &workspace_repo, mode, threshold)
+                    .await
+                {
+                    warn!(
+                        "Failed to sync workspace {} repo {} with {}: {}",
+                        workspace.id, workspace_repo.repo_id, workspace_repo.target_branch, e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_repo(
+        &self,
+        workspace: &Workspace,
+        workspace_repo: &WorkspaceRepo,
+        mode: &TargetSyncMode,
+        threshold: u32,
+    ) -> Result<(), TargetSyncError> {
+        let pool = &self.db.pool;
+        let Some(repo) = Repo::find_by_id(pool, workspace_repo.repo_id).await? else {
+            return Ok(());
+        };
+        let target_branch = workspace_repo.target_branch.clone();
+
+        let behind = tokio::task::spawn_blocking({
+            let git = self.git.clone();
+            let repo_path = repo.path.clone();
+            let branch = workspace.branch.clone();
+            let target_branch = target_branch.clone();
+            move || {
+                git.fetch_remote_branch(&repo_path, &target_branch)?;
+                git.get_branch_status(&repo_path, &branch, &target_branch)
+                    .map(|(_, behind)| behind)
+            }
+        })
+        .await
+        .map_err(|e| TargetSyncError::Join(e.to_string()))??;
+
+        let reason = match decide(mode, behind, threshold) {
+            SyncAction::Record => {
+                WorkspaceTargetSync::record(
+                    pool,
+                    workspace.id,
+                    repo.id,
+                    &target_branch,
+                    behind as i64,
+                    false,
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+            SyncAction::Rebase => match self.try_rebase(workspace, &repo, &target_branch).await? {
+                None => {
+                    info!(
+                        "Rebased workspace {} repo {} onto {} ({} commits behind)",
+                        workspace.id, repo.name, target_branch, behind
+                    );
+                    WorkspaceTargetSync::mark_rebased(pool, workspace.id, repo.id, &target_branch)
+                        .await?;
+                    return Ok(());
+                }
+                Some(reason) => Some(reason),
+            },
+            SyncAction::Flag => None,
+        };
+
+        let already_flagged = WorkspaceTargetSync::find(pool, workspace.id, repo.id)
+            .await?
+            .is_some_and(|sync| sync.needs_rebase);
+        WorkspaceTargetSync::record(
+            pool,
+            workspace.id,
+            repo.id,
+            &target_branch,
+            behind as i64,
+            true,
+            reason.as_deref(),
+        )
+        .await?;
+        if already_flagged {
+            return Ok(());
+        }
+
+        info!(
+            "Workspace {} repo {} is {} commits behind {} and needs a rebase",
+            workspace.id, repo.name, behind, target_branch
+        );
+        if let Some(task) = Task::find_by_id(pool, workspace.task_id).await? {
+            lifecycle_hooks::spawn(
+                pool.clone(),
+                task.project_id,
+                LifecycleEvent::NeedsRebase,
+                json!({
+                    "task_id": task.id,
+                    "workspace_id": workspace.id,
+                    "repo_id": repo.id,
+                    "branch": workspace.branch,
+                    "target_branch": target_branch,
+                    "behind": behind,
+                    "reason": reason,
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Rebase the attempt branch onto its target; returns why it was not
+    /// rebased, if it was not
+    async fn try_rebase(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+    ) -> Result<Option<String>, TargetSyncError> {
+        let Some(container_ref) = &workspace.container_ref else {
+            return Ok(Some("the attempt has no worktree".to_string()));
+        };
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        if !worktree_path.exists() {
+            return Ok(Some("the attempt has no worktree".to_string()));
+        }
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            &self.db.pool,
+            workspace.id,
+        )
+        .await?
+        {
+            return Ok(Some("a process is running in the attempt".to_string()));
+        }
+
+        let git = self.git.clone();
+        let repo_path = repo.path.clone();
+        let branch = workspace.branch.clone();
+        let target_branch = target_branch.to_string();
+        tokio::task::spawn_blocking(move || -> Result<Option<String>, TargetSyncError> {
+            if !git.is_worktree_clean(&worktree_path)? {
+                return Ok(Some("the worktree has uncommitted changes".to_string()));
+            }
+            match git.rebase_branch(
+                &repo_path,
+                &worktree_path,
+                &target_branch,
+                &target_branch,
+                &branch,
+            ) {
+                Ok(_) => Ok(None),
+                Err(GitServiceError::MergeConflicts { .. }) => {
+                    git.abort_conflicts(&worktree_path)?;
+                    Ok(Some("rebasing would cause conflicts".to_string()))
+                }
+                Err(e @ GitServiceError::RebaseInProgress)
+                | Err(e @ GitServiceError::WorktreeDirty(..)) => Ok(Some(e.to_string())),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .await
+        .map_err(|e| TargetSyncError::Join(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebases_only_past_the_threshold() {
+        assert_eq!(
+            decide(&TargetSyncMode::AutoRebase, 20, 20),
+            SyncAction::Record
+        );
+        assert_eq!(
+            decide(&TargetSyncMode::AutoRebase, 21, 20),
+            SyncAction::Rebase
+        );
+        assert_eq!(decide(&TargetSyncMode::Notify, 21, 20), SyncAction::Flag);
+        assert_eq!(decide(&TargetSyncMode::Notify, 0, 0), SyncAction::Record);
+    }
+}
@@ -0,0 +1,355 @@
+//! Expands terse task descriptions with context gathered from the repos.
+//!
+//! Keywords from the title and description are matched against file paths
+//! (through the file search cache) and file contents (`git grep`), and
+//! combined with suggestions from the embedding index when it is enabled. The
+//! best matches are written up as a short brief that is appended to the task
+//! description, so the agent starts out knowing where to look.
+
+use std::{collections::HashMap, path::Path};
+
+use db::models::{project::SearchMatchType, repo::Repo, task::Task};
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::{
+    embedding_index::SuggestedFile,
+    file_search::{FileSearchCache, SearchMode},
+    git::GitCli,
+};
+
+/// Heading that marks the generated section, so enriching again replaces it
+pub const BRIEF_HEADING: &str = "## Context (auto-generated)";
+
+const MAX_KEYWORDS: usize = 8;
+const MAX_FILES: usize = 10;
+/// Files larger than this are listed without an excerpt
+const MAX_EXCERPT_FILE_BYTES: u64 = 512 * 1024;
+const MAX_EXCERPT_CHARS: usize = 120;
+
+const STOPWORDS: &[&str] = &[
+    "about",
+    "add",
+    "after",
+    "all",
+    "also",
+    "and",
+    "any",
+    "are",
+    "before",
+    "but",
+    "can",
+    "could",
+    "does",
+    "each",
+    "for",
+    "from",
+    "have",
+    "into",
+    "it's",
+    "make",
+    "more",
+    "new",
+    "not",
+    "now",
+    "should",
+    "some",
+    "that",
+    "the",
+    "then",
+    "there",
+    "this",
+    "use",
+    "when",
+    "where",
+    "which",
+    "while",
+    "will",
+    "with",
+    "would",
+    "you",
+    "your",
+    "fix",
+    "update",
+    "change",
+    "issue",
+    "bug",
+    "task",
+    "implement",
+    "support",
+];
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RelevantFile {
+    /// Path prefixed with the repo name
+    pub path: String,
+    /// Keywords matching the file's path
+    pub path_matches: Vec<String>,
+    /// Keywords found in the file's contents
+    pub content_matches: Vec<String>,
+    /// First line mentioning a keyword, if any
+    pub excerpt: Option<String>,
+    /// Similarity to the task in the embedding index, when it suggested the file
+    pub similarity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskBrief {
+    pub keywords: Vec<String>,
+    pub files: Vec<RelevantFile>,
+    /// Markdown section appended to the description
+    pub brief: String,
+}
+
+/// Significant words from the task, most specific first
+pub fn extract_keywords(title: &str, description: Option<&str>) -> Vec<String> {
+    let text = match description {
+        Some(description) => format!("{title} {}", strip_brief(description)),
+        None => title.to_string(),
+    };
+
+    let mut keywords: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-')) {
+        let word = word.trim_matches('-').to_lowercase();
+        if word.chars().count() < 3
+            || word.chars().all(|c| c.is_ascii_digit())
+            || STOPWORDS.contains(&word.as_str())
+            || keywords.contains(&word)
+        {
+            continue;
+        }
+        keywords.push(word);
+    }
+    // Longer words tend to be identifiers rather than filler
+    keywords.sort_by_key(|word| std::cmp::Reverse(word.len()));
+    keywords.truncate(MAX_KEYWORDS);
+    keywords
+}
+
+/// Description without a previously generated brief
+pub fn strip_brief(description: &str) -> &str {
+    match description.find(BRIEF_HEADING) {
+        Some(index) => description[..index].trim_end(),
+        None => description,
+    }
+}
+
+/// Description with `brief` replacing any earlier generated section
+pub fn append_brief(description: Option<&str>, brief: &str) -> String {
+    match description
+        .map(strip_brief)
+        .filter(|d| !d.trim().is_empty())
+    {
+        Some(description) => format!("{description}\n\n{brief}"),
+        None => brief.to_string(),
+    }
+}
+
+#[derive(Default)]
+struct Candidate {
+    repo_path: std::path::PathBuf,
+    relative_path: String,
+    path_matches: Vec<String>,
+    content_matches: Vec<String>,
+    similarity: Option<f32>,
+    git_score: i64,
+}
+
+impl Candidate {
+    fn score(&self) -> i64 {
+        // A file matching several keywords beats one that is merely edited often;
+        // a close embedding match counts about as much as a path match
+        let similarity = (self.similarity.unwrap_or(0.0) * 3_000_000.0) as i64;
+        (self.path_matches.len() as i64 * 3 + self.content_matches.len() as i64) * 1_000_000
+            + similarity
+            + self.git_score
+    }
+}
+
+pub async fn enrich(
+    cache: &FileSearchCache,
+    repos: &[Repo],
+    task: &Task,
+    suggested: &[SuggestedFile],
+) -> TaskBrief {
+    let keywords = extract_keywords(&task.title, task.description.as_deref());
+    let mut candidates: HashMap<String, Candidate> = HashMap::new();
+    let multi_repo = repos.len() > 1;
+
+    for repo in repos {
+        let display = |path: &str| {
+            if multi_repo {
+                format!("{}/{}", repo.name, path)
+            } else {
+                path.to_string()
+            }
+        };
+
+        for keyword in &keywords {
+            let path_results = cache
+                .search_repo(&repo.path, keyword, SearchMode::TaskForm)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("File search failed for repo {}: {}", repo.name, e);
+                    Vec::new()
+                });
+            for result in path_results
+                .into_iter()
+                .filter(|r| r.is_file && !matches!(r.match_type, SearchMatchType::DirectoryName))
+            {
+                let candidate =
+                    candidates
+                        .entry(display(&result.path))
+                        .or_insert_with(|| Candidate {
+                            repo_path: repo.path.clone(),
+                            relative_path: result.path.clone(),
+                            ..Default::default()
+                        });
+                candidate.git_score = candidate.git_score.max(result.score);
+                if !candidate.path_matches.contains(keyword) {
+                    candidate.path_matches.push(keyword.clone());
+                }
+            }
+
+            let repo_path = repo.path.clone();
+            let needle = keyword.clone();
+            let content_results =
+                tokio::task::spawn_blocking(move || GitCli::new().grep_files(&repo_path, &needle))
+                    .await
+                    .ok()
+                    .and_then(Result::ok)
+                    .unwrap_or_default();
+            for path in content_results {
+                let candidate = candidates
+                    .entry(display(&path))
+                    .or_insert_with(|| Candidate {
+                        repo_path: repo.path.clone(),
+                        relative_path: path.clone(),
+                        ..Default::default()
+                    });
+                if !candidate.content_matches.contains(keyword) {
+                    candidate.content_matches.push(keyword.clone());
+                }
+            }
+        }
+    }
+
+    for suggestion in suggested {
+        let Some(repo) = repos.iter().find(|r| r.id == suggestion.repo_id) else {
+            continue;
+        };
+        let candidate = candidates
+            .entry(suggestion.path.clone())
+            .or_insert_with(|| Candidate {
+                repo_path: repo.path.clone(),
+                relative_path: suggestion.relative_path.clone(),
+                ..Default::default()
+            });
+        candidate.similarity = Some(suggestion.score);
+    }
+
+    let mut ranked: Vec<(String, Candidate)> = candidates.into_iter().collect();
+    ranked.sort_by(|(a_path, a), (b_path, b)| {
+        b.score().cmp(&a.score()).then_with(|| a_path.cmp(b_path))
+    });
+    ranked.truncate(MAX_FILES);
+
+    let mut files = Vec::with_capacity(ranked.len());
+    for (path, candidate) in ranked {
+        let excerpt = excerpt(
+            &candidate.repo_path.join(&candidate.relative_path),
+            &candidate.content_matches,
+        )
+        .await;
+        files.push(RelevantFile {
+            path,
+            path_matches: candidate.path_matches,
+            content_matches: candidate.content_matches,
+            excerpt,
+            similarity: candidate.similarity,
+        });
+    }
+
+    let brief = render_brief(&keywords, &files);
+    TaskBrief {
+        keywords,
+        files,
+        brief,
+    }
+}
+
+/// First line of the file mentioning one of `keywords`
+async fn excerpt(path: &Path, keywords: &[String]) -> Option<String> {
+    if keywords.is_empty() {
+        return None;
+    }
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if metadata.len() > MAX_EXCERPT_FILE_BYTES {
+        return None;
+    }
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    let line = content.lines().find(|line| {
+        let lower = line.to_lowercase();
+        keywords.iter().any(|k| lower.contains(k.as_str()))
+    })?;
+    let line = line.trim();
+    Some(if line.chars().count() > MAX_EXCERPT_CHARS {
+        format!(
+            "{}...",
+            line.chars().take(MAX_EXCERPT_CHARS).collect::<String>()
+        )
+    } else {
+        line.to_string()
+    })
+}
+
+fn render_brief(keywords: &[String], files: &[RelevantFile]) -> String {
+    let mut brief = format!("{BRIEF_HEADING}\n");
+    if files.is_empty() {
+        brief.push_str("\nNo files in the repository matched the task's keywords.\n");
+        return brief;
+    }
+
+    brief.push_str(&format!(
+        "\nFiles likely relevant to this task (matched on: {}):\n\n",
+        keywords.join(", ")
+    ));
+    for file in files {
+        let mut reasons = Vec::new();
+        if !file.path_matches.is_empty() {
+            reasons.push(format!("path matches {}", file.path_matches.join(", ")));
+        }
+        if !file.content_matches.is_empty() {
+            reasons.push(format!("mentions {}", file.content_matches.join(", ")));
+        }
+        if let Some(similarity) = file.similarity {
+            reasons.push(format!("similar to the task ({similarity:.2})"));
+        }
+        brief.push_str(&format!("- `{}` ({})\n", file.path, reasons.join("; ")));
+        if let Some(excerpt) = &file.excerpt {
+            brief.push_str(&format!("  > {}\n", excerpt.replace('`', "'")));
+        }
+    }
+    brief.push_str("\nStart by reading these files before making changes.\n");
+    brief
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_keywords_without_stopwords() {
+        assert_eq!(
+            extract_keywords("Fix the login timeout in AuthService", None),
+            vec!["authservice", "timeout", "login"]
+        );
+    }
+
+    #[test]
+    fn replaces_previous_brief() {
+        let first = append_brief(Some("Do the thing"), &format!("{BRIEF_HEADING}\n- a"));
+        let second = append_brief(Some(&first), &format!("{BRIEF_HEADING}\n- b"));
+        assert_eq!(second, format!("Do the thing\n\n{BRIEF_HEADING}\n- b"));
+    }
+}
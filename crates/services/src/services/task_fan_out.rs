@@ -0,0 +1,139 @@
+//! Fan a task out across repos or monorepo packages.
+//!
+//! Each target gets a child task whose description is rendered from a prompt
+//! template, such as "bump dependency X" applied to every package. The
+//! children's pull requests are rolled up into the parent, which moves to in
+//! review and then done as they are opened and merged.
+
+use db::models::{
+    task::{Task, TaskStatus},
+    task_fan_out::TaskFanOut,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Used when no template is given
+pub const DEFAULT_PROMPT_TEMPLATE: &str = "{description}\n\nApply this change to {target} only.";
+
+/// Fill the `{title}`, `{description}`, `{repo}`, `{path}` and `{target}`
+/// placeholders of `template` for one target. `{target}` is the package
+/// directory inside the repo, or the repo itself when there is none.
+pub fn render_prompt(
+    template: &str,
+    parent: &Task,
+    repo_name: &str,
+    path_scope: Option<&str>,
+) -> String {
+    let description = parent
+        .description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+        .unwrap_or(&parent.title);
+    let target = match path_scope {
+        Some(path) => format!("`{path}` in the {repo_name} repository"),
+        None => format!("the {repo_name} repository"),
+    };
+    let template = if template.trim().is_empty() {
+        DEFAULT_PROMPT_TEMPLATE
+    } else {
+        template
+    };
+    template
+        .replace("{title}", &parent.title)
+        .replace("{description}", description)
+        .replace("{repo}", repo_name)
+        .replace("{path}", path_scope.unwrap_or("."))
+        .replace("{target}", &target)
+}
+
+/// Title of the child task for one target
+pub fn child_title(parent: &Task, repo_name: &str, path_scope: Option<&str>) -> String {
+    match path_scope {
+        Some(path) => format!("{} ({repo_name}/{path})", parent.title),
+        None => format!("{} ({repo_name})", parent.title),
+    }
+}
+
+/// Move the parent of a fan-out child to match its children's pull requests.
+/// Returns the parent's new status when it changed.
+pub async fn sync_parent_status(
+    pool: &SqlitePool,
+    child_task_id: Uuid,
+) -> Result<Option<TaskStatus>, sqlx::Error> {
+    let Some(parent_id) = TaskFanOut::find_parent_task_id(pool, child_task_id).await? else {
+        return Ok(None);
+    };
+    let Some(parent) = Task::find_by_id(pool, parent_id).await? else {
+        return Ok(None);
+    };
+    // A parent closed by hand stays closed
+    if matches!(parent.status, TaskStatus::Done | TaskStatus::Cancelled) {
+        return Ok(None);
+    }
+
+    let fan_out = TaskFanOut::find_by_parent_task_id(pool, parent_id).await?;
+    match fan_out.parent_status() {
+        Some(status) if status != parent.status => {
+            Task::update_status(pool, parent_id, status.clone()).await?;
+            tracing::info!(
+                "Fan-out task {} moved to {} ({} of {} PRs merged)",
+                parent_id,
+                status,
+                fan_out.merged_prs,
+                fan_out.children.len()
+            );
+            Ok(Some(status))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn parent(description: Option<&str>) -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Bump serde to 1.0.200".to_string(),
+            description: description.map(str::to_string),
+            status: TaskStatus::Todo,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            use_ralph_wiggum: false,
+            ralph_max_iterations: None,
+            ralph_completion_promise: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn renders_prompt_per_target() {
+        let task = parent(Some("Update serde and fix any breakage."));
+        assert_eq!(
+            render_prompt("", &task, "monorepo", Some("packages/api")),
+            "Update serde and fix any breakage.\n\nApply this change to `packages/api` in the monorepo repository only."
+        );
+        assert_eq!(
+            render_prompt("{title} in {repo} at {path}", &parent(None), "web", None),
+            "Bump serde to 1.0.200 in web at ."
+        );
+    }
+
+    #[test]
+    fn titles_children_by_target() {
+        let task = parent(None);
+        assert_eq!(
+            child_title(&task, "monorepo", Some("packages/api")),
+            "Bump serde to 1.0.200 (monorepo/packages/api)"
+        );
+        assert_eq!(
+            child_title(&task, "web", None),
+            "Bump serde to 1.0.200 (web)"
+        );
+    }
+}
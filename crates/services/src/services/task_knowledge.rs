@@ -0,0 +1,260 @@
+//! Knowledge base of completed tasks.
+//!
+//! Done tasks are indexed lazily, whenever a project's past tasks are looked
+//! up: the title, the description, the summaries of what the attempts changed
+//! and the pull request that shipped them are embedded together. New tasks
+//! are compared against them to point users, and optionally the coding agent
+//! through the initial prompt, at similar past tasks and how they were solved.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    merge::{Merge, MergeStatus},
+    task::Task,
+    task_knowledge::{TaskKnowledge, UpsertTaskKnowledge},
+    workspace::{Workspace, WorkspaceError},
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    config::Config,
+    embedding_index::{Embedder, EmbeddingError, cosine_similarity},
+    task_enrichment::strip_brief,
+};
+
+pub const DEFAULT_LIMIT: usize = 5;
+/// Matches below this similarity are noise rather than related work
+const MIN_SCORE: f32 = 0.2;
+const BATCH_SIZE: usize = 32;
+const PROMPT_HEADING: &str = "## Similar past tasks";
+
+#[derive(Debug, Error)]
+pub enum TaskKnowledgeError {
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+}
+
+/// A completed task that resembles the one being looked at
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SimilarTask {
+    pub task_id: Uuid,
+    pub title: String,
+    /// What the task's attempts changed
+    pub diff_summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub completed_at: DateTime<Utc>,
+    /// Cosine similarity, higher is closer
+    pub score: f32,
+}
+
+/// The configured embedder, or the local one so that past tasks can be
+/// found without setting up the embedding index
+pub fn embedder(config: &Config) -> Embedder {
+    Embedder::from_config(config).unwrap_or(Embedder::Local)
+}
+
+fn query_text(title: &str, description: Option<&str>) -> String {
+    match description.map(strip_brief) {
+        Some(description) if !description.trim().is_empty() => {
+            format!("{title}\n{description}")
+        }
+        _ => title.to_string(),
+    }
+}
+
+fn index_text(entry: &UpsertTaskKnowledge) -> String {
+    let mut text = query_text(&entry.title, entry.description.as_deref());
+    if let Some(summary) = &entry.diff_summary {
+        text.push('\n');
+        text.push_str(summary);
+    }
+    text
+}
+
+/// What is remembered of a done task: its attempts' summaries and the pull
+/// request that shipped it, preferring a merged one
+async fn gather(pool: &SqlitePool, task: &Task) -> Result<UpsertTaskKnowledge, TaskKnowledgeError> {
+    let mut summaries: Vec<String> = Vec::new();
+    let mut pr_url = None;
+    for workspace in Workspace::fetch_all(pool, Some(task.id)).await? {
+        if let Some(summary) = Workspace::find_summary(pool, workspace.id).await?
+            && !summaries.contains(&summary)
+        {
+            summaries.push(summary);
+        }
+        for merge in Merge::find_by_workspace_id(pool, workspace.id).await? {
+            if let Merge::Pr(pr) = merge
+                && (pr_url.is_none() || matches!(pr.pr_info.status, MergeStatus::Merged))
+            {
+                pr_url = Some(pr.pr_info.url);
+            }
+        }
+    }
+
+    Ok(UpsertTaskKnowledge {
+        title: task.title.clone(),
+        description: task
+            .description
+            .as_deref()
+            .map(|d| strip_brief(d).to_string())
+            .filter(|d| !d.trim().is_empty()),
+        diff_summary: (!summaries.is_empty()).then(|| summaries.join("\n")),
+        pr_url,
+    })
+}
+
+/// Index the project's done tasks that are new or changed, and forget those
+/// that were reopened. Returns how many tasks were indexed.
+pub async fn sync_project(
+    pool: &SqlitePool,
+    embedder: &Embedder,
+    project_id: Uuid,
+) -> Result<usize, TaskKnowledgeError> {
+    TaskKnowledge::delete_stale(pool, project_id).await?;
+    let model = embedder.model_id();
+    let tasks = TaskKnowledge::find_unindexed(pool, project_id, &model).await?;
+    for batch in tasks.chunks(BATCH_SIZE) {
+        let mut entries = Vec::with_capacity(batch.len());
+        for task in batch {
+            entries.push(gather(pool, task).await?);
+        }
+        let inputs: Vec<String> = entries.iter().map(index_text).collect();
+        let vectors = embedder.embed(&inputs).await?;
+        for ((task, entry), vector) in batch.iter().zip(&entries).zip(vectors) {
+            TaskKnowledge::upsert(pool, task, entry, &model, &vector).await?;
+        }
+    }
+    if !tasks.is_empty() {
+        tracing::info!(
+            "Indexed {} completed task(s) of project {}",
+            tasks.len(),
+            project_id
+        );
+    }
+    Ok(tasks.len())
+}
+
+/// Completed tasks of the project closest to the given title and
+/// description, best first; `exclude` leaves out the task being looked at
+pub async fn similar(
+    pool: &SqlitePool,
+    embedder: &Embedder,
+    project_id: Uuid,
+    title: &str,
+    description: Option<&str>,
+    exclude: Option<Uuid>,
+    limit: usize,
+) -> Result<Vec<SimilarTask>, TaskKnowledgeError> {
+    sync_project(pool, embedder, project_id).await?;
+    let Some(query) = embedder
+        .embed(&[query_text(title, description)])
+        .await?
+        .pop()
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut matches: Vec<SimilarTask> =
+        TaskKnowledge::find_by_project_id(pool, project_id, &embedder.model_id())
+            .await?
+            .into_iter()
+            .filter(|entry| Some(entry.task_id) != exclude)
+            .filter_map(|entry| {
+                let score = cosine_similarity(&query, &entry.decode());
+                (score >= MIN_SCORE).then(|| SimilarTask {
+                    task_id: entry.task_id,
+                    title: entry.title,
+                    diff_summary: entry.diff_summary,
+                    pr_url: entry.pr_url,
+                    completed_at: entry.completed_at,
+                    score,
+                })
+            })
+            .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Markdown section listing `similar` for the coding agent
+pub fn render_for_prompt(similar: &[SimilarTask]) -> Option<String> {
+    if similar.is_empty() {
+        return None;
+    }
+    let mut section = format!(
+        "{PROMPT_HEADING}\n\nThese completed tasks look related; how they were solved may help with this one.\n\n"
+    );
+    for task in similar {
+        section.push_str(&format!("- {}", task.title));
+        if let Some(pr_url) = &task.pr_url {
+            section.push_str(&format!(" ({pr_url})"));
+        }
+        section.push('\n');
+        if let Some(summary) = &task.diff_summary {
+            for line in summary.lines().filter(|l| !l.trim().is_empty()) {
+                section.push_str(&format!("  {}\n", line.trim()));
+            }
+        }
+    }
+    Some(section)
+}
+
+/// `prompt` followed by the task's similar past tasks, if there are any.
+/// Lookup failures are logged so they never block a run.
+pub async fn append_to_prompt(
+    pool: &SqlitePool,
+    embedder: &Embedder,
+    task: &Task,
+    prompt: String,
+) -> String {
+    match similar(
+        pool,
+        embedder,
+        task.project_id,
+        &task.title,
+        task.description.as_deref(),
+        Some(task.id),
+        DEFAULT_LIMIT,
+    )
+    .await
+    {
+        Ok(similar) => match render_for_prompt(&similar) {
+            Some(section) => format!("{prompt}\n\n{section}"),
+            None => prompt,
+        },
+        Err(e) => {
+            tracing::warn!("Failed to find similar tasks for task {}: {}", task.id, e);
+            prompt
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_past_tasks_with_links_and_summaries() {
+        let section = render_for_prompt(&[SimilarTask {
+            task_id: Uuid::nil(),
+            title: "Retry flaky uploads".to_string(),
+            diff_summary: Some("Wrapped the upload in a retry loop.".to_string()),
+            pr_url: Some("https://github.com/acme/app/pull/7".to_string()),
+            completed_at: Utc::now(),
+            score: 0.8,
+        }])
+        .unwrap();
+        assert!(section.starts_with(PROMPT_HEADING));
+        assert!(section.contains(
+            "- Retry flaky uploads (https://github.com/acme/app/pull/7)\n  Wrapped the upload in a retry loop.\n"
+        ));
+        assert!(render_for_prompt(&[]).is_none());
+    }
+}
@@ -0,0 +1,394 @@
+//! Links from commits and PRs back to the tasks they came from.
+//!
+//! Auto-commits, merge commits and PR bodies carry a git trailer naming the
+//! task (`VK-Task: <uuid>` by default, see `Config::task_trailer`). A commit
+//! is traced back through the merges and agent commits recorded in the
+//! database first, and through that trailer in the repos' history otherwise.
+//! A line of a file is traced by blaming it to a commit first.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process_repo_state::ExecutionProcessRepoState, merge::Merge, repo::Repo, task::Task,
+    workspace::Workspace,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::git::{GitCli, GitCliError};
+
+#[derive(Debug, Error)]
+pub enum TraceError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("{} is not inside a registered repository", .0.display())]
+    UnknownRepo(PathBuf),
+    #[error("git blame failed: {0}")]
+    Blame(String),
+}
+
+/// Shortest commit prefix accepted for a lookup
+const MIN_SHA_CHARS: usize = 7;
+
+/// A trailer key is a single token of letters, digits and `-`
+pub fn is_valid_trailer_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('-')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(": ")
+        .is_some_and(|(key, _)| is_valid_trailer_key(key))
+}
+
+/// Add `key: task_id` to the trailer block at the end of `message`, starting
+/// one when the last paragraph is not already made of trailers
+pub fn append_trailer(message: &str, key: &str, task_id: Uuid) -> String {
+    if parse_trailer(message, key) == Some(task_id) {
+        return message.to_string();
+    }
+    let message = message.trim_end();
+    let trailer = format!("{key}: {task_id}");
+    if message.is_empty() {
+        return trailer;
+    }
+    let in_trailer_block = message
+        .rsplit_once("\n\n")
+        .is_some_and(|(_, last)| last.lines().all(is_trailer_line));
+    if in_trailer_block {
+        format!("{message}\n{trailer}")
+    } else {
+        format!("{message}\n\n{trailer}")
+    }
+}
+
+/// Task ID carried by the `key` trailer of a commit message or PR body
+pub fn parse_trailer(message: &str, key: &str) -> Option<Uuid> {
+    message.lines().rev().find_map(|line| {
+        let (line_key, value) = line.trim().split_once(':')?;
+        if !line_key.trim().eq_ignore_ascii_case(key) {
+            return None;
+        }
+        Uuid::parse_str(value.trim()).ok()
+    })
+}
+
+/// How a commit or PR was traced back to its task
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum TraceSource {
+    /// A direct merge or merged PR recorded for the attempt
+    Merge,
+    /// A pull request opened for the attempt
+    PullRequest,
+    /// A commit made during one of the attempt's runs
+    AgentCommit,
+    /// The task trailer of the commit message
+    CommitTrailer,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskTrace {
+    pub task: Task,
+    /// Attempt the commit or PR belongs to, when known
+    pub workspace_id: Option<Uuid>,
+    pub source: TraceSource,
+}
+
+async fn trace_workspace(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    source: TraceSource,
+) -> Result<Option<TaskTrace>, sqlx::Error> {
+    let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+        return Ok(None);
+    };
+    Ok(Task::find_by_id(pool, workspace.task_id)
+        .await?
+        .map(|task| TaskTrace {
+            task,
+            workspace_id: Some(workspace.id),
+            source,
+        }))
+}
+
+/// Task a commit came from. `sha` may be abbreviated; `repos` are searched for
+/// the commit's `trailer_key` trailer when no recorded merge or agent commit
+/// matches.
+pub async fn find_by_commit(
+    pool: &SqlitePool,
+    repos: &[Repo],
+    trailer_key: Option<&str>,
+    sha: &str,
+) -> Result<Option<TaskTrace>, sqlx::Error> {
+    let sha = sha.trim().to_ascii_lowercase();
+    if sha.len() < MIN_SHA_CHARS || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(None);
+    }
+
+    if let Some(workspace_id) = Merge::find_workspace_id_by_commit(pool, &sha).await?
+        && let Some(trace) = trace_workspace(pool, workspace_id, TraceSource::Merge).await?
+    {
+        return Ok(Some(trace));
+    }
+    if let Some(workspace_id) =
+        ExecutionProcessRepoState::find_workspace_id_by_commit(pool, &sha).await?
+        && let Some(trace) = trace_workspace(pool, workspace_id, TraceSource::AgentCommit).await?
+    {
+        return Ok(Some(trace));
+    }
+    let Some(trailer_key) = trailer_key else {
+        return Ok(None);
+    };
+
+    let repo_paths: Vec<_> = repos.iter().map(|repo| repo.path.clone()).collect();
+    let message = tokio::task::spawn_blocking(move || {
+        let git = GitCli::new();
+        repo_paths.iter().find_map(|path| {
+            git.git(
+                path,
+                ["log", "-1", "--format=%B", &format!("{sha}^{{commit}}")],
+            )
+            .ok()
+        })
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some(task_id) = message.and_then(|message| parse_trailer(&message, trailer_key)) else {
+        return Ok(None);
+    };
+    Ok(Task::find_by_id(pool, task_id)
+        .await?
+        .map(|task| TaskTrace {
+            task,
+            workspace_id: None,
+            source: TraceSource::CommitTrailer,
+        }))
+}
+
+/// Task a pull request was opened for
+pub async fn find_by_pr_url(
+    pool: &SqlitePool,
+    pr_url: &str,
+) -> Result<Option<TaskTrace>, sqlx::Error> {
+    let pr_url = pr_url.trim().trim_end_matches('/');
+    if pr_url.is_empty() {
+        return Ok(None);
+    }
+    match Merge::find_workspace_id_by_pr_url(pool, pr_url).await? {
+        Some(workspace_id) => trace_workspace(pool, workspace_id, TraceSource::PullRequest).await,
+        None => Ok(None),
+    }
+}
+
+/// Commit that last touched a line, as reported by `git blame --porcelain`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlamedLine {
+    commit: String,
+    author: Option<String>,
+    authored_at: Option<DateTime<Utc>>,
+    summary: Option<String>,
+}
+
+/// Parse the header of `git blame --porcelain` output for a single line.
+/// Returns `None` for lines that are not committed yet.
+fn parse_porcelain_blame(output: &str) -> Option<BlamedLine> {
+    let mut lines = output.lines();
+    let commit = lines
+        .next()?
+        .split_whitespace()
+        .next()?
+        .to_ascii_lowercase();
+    if commit.chars().all(|c| c == '0') {
+        return None;
+    }
+    let mut blamed = BlamedLine {
+        commit,
+        author: None,
+        authored_at: None,
+        summary: None,
+    };
+    // Header lines end at the tab-prefixed line content
+    for line in lines.take_while(|line| !line.starts_with('\t')) {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => blamed.author = Some(value.to_string()),
+            "author-time" => {
+                blamed.authored_at = value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            }
+            "summary" => blamed.summary = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(blamed)
+}
+
+/// Main checkout a path belongs to; worktrees resolve to the repo they were
+/// added from
+fn main_checkout(git: &GitCli, path: &Path) -> Option<PathBuf> {
+    let common_dir = git
+        .git(
+            path,
+            ["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        )
+        .ok()?;
+    let common_dir = PathBuf::from(common_dir.trim());
+    let root = match common_dir.file_name() {
+        Some(name) if name == ".git" => common_dir.parent()?.to_path_buf(),
+        _ => common_dir,
+    };
+    std::fs::canonicalize(root).ok()
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BlameTrace {
+    /// Commit that last touched the line
+    pub commit: String,
+    pub author: Option<String>,
+    pub authored_at: Option<DateTime<Utc>>,
+    /// First line of the commit message
+    pub summary: Option<String>,
+    /// Task the commit came from, if it can be traced
+    pub trace: Option<TaskTrace>,
+    /// API path of the originating attempt's transcript
+    pub transcript_url: Option<String>,
+}
+
+/// Task behind `line` (1-based) of `file`. `repo_path` is a registered repo
+/// or one of its worktrees; `file` is relative to it or absolute. Returns
+/// `None` when the line is not committed yet.
+pub async fn find_by_blame(
+    pool: &SqlitePool,
+    repos: &[Repo],
+    trailer_key: Option<&str>,
+    repo_path: &Path,
+    file: &str,
+    line: u32,
+) -> Result<Option<BlameTrace>, TraceError> {
+    if line == 0 {
+        return Err(TraceError::Blame("Line numbers start at 1".to_string()));
+    }
+    let candidates: Vec<_> = repos
+        .iter()
+        .map(|repo| (repo.id, repo.path.clone()))
+        .collect();
+    let repo_path = repo_path.to_path_buf();
+    let file = file.to_string();
+    let (repo_id, blamed) = tokio::task::spawn_blocking(move || {
+        let git = GitCli::new();
+        let root = main_checkout(&git, &repo_path)
+            .ok_or_else(|| TraceError::UnknownRepo(repo_path.clone()))?;
+        let repo_id = candidates
+            .iter()
+            .find(|(_, path)| std::fs::canonicalize(path).is_ok_and(|path| path == root))
+            .map(|(id, _)| *id)
+            .ok_or_else(|| TraceError::UnknownRepo(repo_path.clone()))?;
+        let range = format!("{line},{line}");
+        let output = git
+            .git(
+                &repo_path,
+                [
+                    "blame",
+                    "--porcelain",
+                    "-L",
+                    range.as_str(),
+                    "--",
+                    file.as_str(),
+                ],
+            )
+            .map_err(|e| match e {
+                GitCliError::CommandFailed(msg) => TraceError::Blame(msg),
+                other => TraceError::Blame(other.to_string()),
+            })?;
+        Ok::<_, TraceError>((repo_id, parse_porcelain_blame(&output)))
+    })
+    .await
+    .map_err(|e| TraceError::Blame(e.to_string()))??;
+    let Some(blamed) = blamed else {
+        return Ok(None);
+    };
+
+    let repos: Vec<_> = repos
+        .iter()
+        .filter(|repo| repo.id == repo_id)
+        .cloned()
+        .collect();
+    let trace = find_by_commit(pool, &repos, trailer_key, &blamed.commit).await?;
+    let transcript_url = trace
+        .as_ref()
+        .and_then(|trace| trace.workspace_id)
+        .map(|workspace_id| format!("/api/task-attempts/{workspace_id}/transcript"));
+    Ok(Some(BlameTrace {
+        commit: blamed.commit,
+        author: blamed.author,
+        authored_at: blamed.authored_at,
+        summary: blamed.summary,
+        trace,
+        transcript_url,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_parses_trailers() {
+        let task_id = Uuid::parse_str("3f1c9b2e-8a4d-4e5f-9c1a-2b3c4d5e6f70").unwrap();
+
+        let message = append_trailer("Fix login\n\nRetry on timeout.", "VK-Task", task_id);
+        assert_eq!(
+            message,
+            "Fix login\n\nRetry on timeout.\n\nVK-Task: 3f1c9b2e-8a4d-4e5f-9c1a-2b3c4d5e6f70"
+        );
+        assert_eq!(append_trailer(&message, "VK-Task", task_id), message);
+        assert_eq!(parse_trailer(&message, "vk-task"), Some(task_id));
+
+        let signed = append_trailer(
+            "Fix login\n\nSigned-off-by: Ann <ann@example.com>",
+            "VK-Task",
+            task_id,
+        );
+        assert!(signed.ends_with(
+            "Signed-off-by: Ann <ann@example.com>\nVK-Task: 3f1c9b2e-8a4d-4e5f-9c1a-2b3c4d5e6f70"
+        ));
+
+        assert_eq!(parse_trailer("Fix login", "VK-Task"), None);
+        assert!(!is_valid_trailer_key("VK Task"));
+    }
+
+    #[test]
+    fn parses_porcelain_blame() {
+        let output = "4b825dc642cb6eb9a060e54bf8d69288fbee4904 12 12 1\n\
+            author Ann\n\
+            author-mail <ann@example.com>\n\
+            author-time 1700000000\n\
+            author-tz +0000\n\
+            summary Fix login retries\n\
+            filename src/login.rs\n\
+            \tretry(3);\n";
+        let blamed = parse_porcelain_blame(output).unwrap();
+        assert_eq!(blamed.commit, "4b825dc642cb6eb9a060e54bf8d69288fbee4904");
+        assert_eq!(blamed.author.as_deref(), Some("Ann"));
+        assert_eq!(blamed.summary.as_deref(), Some("Fix login retries"));
+        assert_eq!(
+            blamed.authored_at,
+            DateTime::from_timestamp(1_700_000_000, 0)
+        );
+
+        let uncommitted = "0000000000000000000000000000000000000000 3 3 1\n\
+            author Not Committed Yet\n\
+            \tretry(3);\n";
+        assert_eq!(parse_porcelain_blame(uncommitted), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Exportable transcripts of an attempt's agent conversation.
+//!
+//! Every coding agent run of the attempt contributes one turn: the prompt it
+//! was sent and the normalized entries it produced. Tool calls that touch
+//! sensitive files lose their contents before the transcript leaves the
+//! server, following the same rules as attempt diffs.
+
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::models::agent_prompt::AgentPromptKind;
+use executors::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryType,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use super::{container::ContainerService, sensitive_files::SensitiveFileRules};
+
+/// Replayed log normalization has no end marker; once it has been quiet
+/// this long its output is taken as complete
+const NORMALIZE_IDLE: Duration = Duration::from_millis(500);
+
+const OMITTED: &str = "[contents omitted: sensitive file]";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+/// One coding agent run: what it was asked and what it did
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptTurn {
+    pub execution_process_id: Uuid,
+    pub executor: Option<String>,
+    pub prompt_kind: Option<AgentPromptKind>,
+    pub prompt: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct Transcript {
+    pub workspace_id: Uuid,
+    pub task_title: String,
+    pub exported_at: DateTime<Utc>,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+/// Normalized entries of an execution process, in conversation order.
+/// Running processes contribute what they have produced so far.
+pub async fn collect_entries<C>(container: &C, execution_process_id: Uuid) -> Vec<NormalizedEntry>
+where
+    C: ContainerService + Sync,
+{
+    if let Some(store) = container.get_msg_store_by_id(&execution_process_id).await {
+        return entries_from_history(&store.get_history());
+    }
+
+    let mut history = Vec::new();
+    if let Some(mut stream) = container
+        .stream_normalized_logs(&execution_process_id)
+        .await
+    {
+        while let Ok(Some(Ok(msg))) = tokio::time::timeout(NORMALIZE_IDLE, stream.next()).await {
+            if matches!(msg, LogMsg::Finished) {
+                break;
+            }
+            history.push(msg);
+        }
+    }
+    entries_from_history(&history)
+}
+
+/// Fold the normalized-entry patches of a log history into the conversation
+/// they describe, leaving out UI-only entries
+pub fn entries_from_history(history: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut entries = BTreeMap::new();
+    for msg in history {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+
+    entries
+        .into_values()
+        .filter(|entry| {
+            !matches!(
+                entry.entry_type,
+                NormalizedEntryType::Loading
+                    | NormalizedEntryType::NextAction { .. }
+                    | NormalizedEntryType::TokenUsageInfo(_)
+            )
+        })
+        .collect()
+}
+
+/// Strip the contents of sensitive files from tool calls. `worktree` is the
+/// attempt's workspace directory, so absolute paths can be matched too.
+pub fn sanitize(transcript: &mut Transcript, rules: &SensitiveFileRules, worktree: Option<&Path>) {
+    let is_sensitive = |path: &str| {
+        let relative = worktree
+            .and_then(|root| Path::new(path).strip_prefix(root).ok())
+            .map(|relative| relative.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string());
+        // Paths under the workspace root start with the repo's directory
+        let in_repo = relative
+            .trim_start_matches('/')
+            .split_once('/')
+            .map(|(_, rest)| rest.to_string());
+        rules.is_sensitive(&relative) || in_repo.is_some_and(|path| rules.is_sensitive(&path))
+    };
+
+    for entry in transcript
+        .turns
+        .iter_mut()
+        .flat_map(|turn| turn.entries.iter_mut())
+    {
+        let NormalizedEntryType::ToolUse { action_type, .. } = &mut entry.entry_type else {
+            continue;
+        };
+        let omit = match action_type {
+            ActionType::FileRead { path } => is_sensitive(path),
+            ActionType::FileEdit { path, changes } => {
+                let sensitive = is_sensitive(path);
+                if sensitive {
+                    changes.clear();
+                }
+                sensitive
+            }
+            ActionType::CommandRun { command, result } => {
+                let sensitive = command.split_whitespace().any(|arg| is_sensitive(arg));
+                if sensitive && let Some(result) = result {
+                    result.output = None;
+                }
+                sensitive
+            }
+            _ => false,
+        };
+        if omit {
+            entry.content = OMITTED.to_string();
+            entry.metadata = None;
+        }
+    }
+}
+
+pub fn to_markdown(transcript: &Transcript) -> String {
+    let mut out = format!(
+        "# {}\n\nAttempt `{}`, exported {}.\n",
+        transcript.task_title,
+        transcript.workspace_id,
+        transcript.exported_at.to_rfc3339()
+    );
+
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        let mut heading = format!("\n## Turn {}", i + 1);
+        if let Some(executor) = &turn.executor {
+            heading.push_str(&format!(" · {executor}"));
+        }
+        if let Some(kind) = turn.prompt_kind {
+            heading.push_str(&format!(" · {}", kind_label(kind)));
+        }
+        out.push_str(&heading);
+        out.push_str(&format!("\n\n_{}_\n", turn.started_at.to_rfc3339()));
+
+        if let Some(prompt) = &turn.prompt {
+            out.push_str(&format!("\n### Prompt\n\n{}\n", prompt.trim()));
+        }
+
+        for entry in &turn.entries {
+            let content = entry.content.trim();
+            match &entry.entry_type {
+                // Usually an echo of the prompt shown above
+                NormalizedEntryType::UserMessage
+                    if turn.prompt.as_deref().map(str::trim) == Some(content) => {}
+                NormalizedEntryType::UserMessage => {
+                    out.push_str(&format!("\n**User:** {content}\n"))
+                }
+                NormalizedEntryType::UserFeedback { denied_tool } => {
+                    out.push_str(&format!("\n**User denied `{denied_tool}`:** {content}\n"))
+                }
+                NormalizedEntryType::AssistantMessage => {
+                    out.push_str(&format!("\n**Assistant:** {content}\n"))
+                }
+                NormalizedEntryType::Thinking => out.push_str(&format!("\n> _{content}_\n")),
+                NormalizedEntryType::SystemMessage => {
+                    out.push_str(&format!("\n_System: {content}_\n"))
+                }
+                NormalizedEntryType::ErrorMessage { .. } => {
+                    out.push_str(&format!("\n**Error:** {content}\n"))
+                }
+                NormalizedEntryType::ToolUse {
+                    tool_name,
+                    action_type,
+                    ..
+                } => {
+                    out.push_str(&format!("\n**Tool `{tool_name}`:** {content}\n"));
+                    if let ActionType::CommandRun {
+                        result: Some(result),
+                        ..
+                    } = action_type
+                        && let Some(output) = result.output.as_deref()
+                        && !output.trim().is_empty()
+                    {
+                        out.push_str(&format!("\n```\n{}\n```\n", output.trim_end()));
+                    }
+                }
+                NormalizedEntryType::Loading
+                | NormalizedEntryType::NextAction { .. }
+                | NormalizedEntryType::TokenUsageInfo(_) => {}
+            }
+        }
+    }
+    out
+}
+
+fn kind_label(kind: AgentPromptKind) -> &'static str {
+    match kind {
+        AgentPromptKind::Initial => "initial prompt",
+        AgentPromptKind::FollowUp => "follow-up",
+        AgentPromptKind::PrDescription => "PR description",
+        AgentPromptKind::AddressComments => "review comments",
+        AgentPromptKind::Review => "review",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{CommandRunResult, ToolStatus};
+
+    use super::*;
+
+    fn tool(action_type: ActionType) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status: ToolStatus::Success,
+            },
+            content: "API_KEY=hunter2".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn sensitive_tool_calls_lose_their_contents() {
+        let mut transcript = Transcript {
+            workspace_id: Uuid::new_v4(),
+            task_title: "Task".to_string(),
+            exported_at: Utc::now(),
+            turns: vec![TranscriptTurn {
+                execution_process_id: Uuid::new_v4(),
+                executor: None,
+                prompt_kind: Some(AgentPromptKind::Initial),
+                prompt: Some("Do it".to_string()),
+                started_at: Utc::now(),
+                entries: vec![
+                    tool(ActionType::FileRead {
+                        path: "/work/vk-1/app/.env".to_string(),
+                    }),
+                    tool(ActionType::CommandRun {
+                        command: "cat .env".to_string(),
+                        result: Some(CommandRunResult {
+                            exit_status: None,
+                            output: Some("API_KEY=hunter2".to_string()),
+                        }),
+                    }),
+                    tool(ActionType::FileRead {
+                        path: "src/main.rs".to_string(),
+                    }),
+                ],
+            }],
+        };
+
+        sanitize(
+            &mut transcript,
+            &SensitiveFileRules::new(&[".env".to_string()]),
+            Some(Path::new("/work/vk-1")),
+        );
+
+        let entries = &transcript.turns[0].entries;
+        assert_eq!(entries[0].content, OMITTED);
+        assert_eq!(entries[1].content, OMITTED);
+        assert!(matches!(
+            &entries[1].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { result: Some(result), .. },
+                ..
+            } if result.output.is_none()
+        ));
+        assert_eq!(entries[2].content, "API_KEY=hunter2");
+        assert!(!to_markdown(&transcript).contains("hunter2\n```"));
+    }
+}
@@ -0,0 +1,258 @@
+//! Transcription of voice notes, either by a local command such as
+//! whisper.cpp or through an OpenAI-compatible `/audio/transcriptions`
+//! endpoint.
+
+use std::{path::Path, process::Stdio, time::Duration};
+
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::process::Command;
+
+use super::config::{Config, TranscriptionProvider};
+
+pub const DEFAULT_ENDPOINT: &str = "https://api.openai.com/v1/audio/transcriptions";
+pub const DEFAULT_MODEL: &str = "whisper-1";
+/// Largest upload the usual endpoints take
+pub const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Error)]
+pub enum TranscriptionError {
+    #[error("Voice notes are disabled in the settings")]
+    Disabled,
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+    #[error("The transcription command is not set")]
+    MissingCommand,
+    #[error("Transcription command failed: {0}")]
+    Command(String),
+    #[error("Transcription request failed: {0}")]
+    Request(String),
+    #[error("The transcript is empty")]
+    Empty,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Mime type of an audio file the transcribers accept, by extension
+pub fn audio_mime_type(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name).extension()?.to_str()?;
+    Some(match extension.to_ascii_lowercase().as_str() {
+        "mp3" | "mpga" | "mpeg" => "audio/mpeg",
+        "m4a" | "mp4" => "audio/mp4",
+        "wav" => "audio/wav",
+        "webm" => "audio/webm",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "flac" => "audio/flac",
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum Transcriber {
+    Local {
+        command: Option<String>,
+    },
+    Api {
+        client: reqwest::Client,
+        endpoint: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+impl Transcriber {
+    /// The configured transcriber; `None` while voice notes are disabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        match config.transcription_provider {
+            TranscriptionProvider::Disabled => None,
+            TranscriptionProvider::Local => Some(Self::Local {
+                command: non_empty(&config.transcription_command),
+            }),
+            TranscriptionProvider::Api => Some(Self::Api {
+                client: reqwest::Client::new(),
+                endpoint: non_empty(&config.transcription_endpoint)
+                    .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string()),
+                model: non_empty(&config.transcription_model)
+                    .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+                api_key: non_empty(&config.transcription_api_key),
+            }),
+        }
+    }
+
+    pub async fn transcribe(
+        &self,
+        data: &[u8],
+        file_name: &str,
+    ) -> Result<String, TranscriptionError> {
+        let mime_type = audio_mime_type(file_name)
+            .ok_or_else(|| TranscriptionError::UnsupportedFormat(file_name.to_string()))?;
+        let text = match self {
+            Self::Local { command } => {
+                let command = command
+                    .as_deref()
+                    .ok_or(TranscriptionError::MissingCommand)?;
+                let extension = Path::new(file_name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("audio");
+                let file = tempfile::Builder::new()
+                    .suffix(&format!(".{extension}"))
+                    .tempfile()?;
+                tokio::fs::write(file.path(), data).await?;
+                run_command(command, file.path()).await?
+            }
+            Self::Api {
+                client,
+                endpoint,
+                model,
+                api_key,
+            } => {
+                let part = reqwest::multipart::Part::bytes(data.to_vec())
+                    .file_name(file_name.to_string())
+                    .mime_str(mime_type)
+                    .map_err(|e| TranscriptionError::Request(e.to_string()))?;
+                let form = reqwest::multipart::Form::new()
+                    .text("model", model.clone())
+                    .part("file", part);
+                let mut request = client.post(endpoint).multipart(form);
+                if let Some(api_key) = api_key {
+                    request = request.bearer_auth(api_key);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| TranscriptionError::Request(e.to_string()))?;
+                let body: TranscriptionResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| TranscriptionError::Request(e.to_string()))?;
+                body.text
+            }
+        };
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return Err(TranscriptionError::Empty);
+        }
+        Ok(text)
+    }
+}
+
+/// Arguments of `command` with `{input}` replaced by `input`, which is
+/// appended when the command doesn't mention it
+fn command_args(command: &str, input: &Path) -> Option<Vec<String>> {
+    let input = input.to_string_lossy();
+    let mut args = shlex::split(command)?;
+    if args.is_empty() {
+        return None;
+    }
+    if args.iter().any(|arg| arg.contains("{input}")) {
+        for arg in &mut args {
+            *arg = arg.replace("{input}", &input);
+        }
+    } else {
+        args.push(input.into_owned());
+    }
+    Some(args)
+}
+
+async fn run_command(command: &str, input: &Path) -> Result<String, TranscriptionError> {
+    let args = command_args(command, input).ok_or(TranscriptionError::MissingCommand)?;
+    let child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| TranscriptionError::Command(format!("{}: {e}", args[0])))?;
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| TranscriptionError::Command("timed out".to_string()))??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TranscriptionError::Command(format!(
+            "{} ({})",
+            stderr.trim(),
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Task title from a transcript: its first sentence, shortened at a word
+/// boundary when long
+pub fn title_from_transcript(transcript: &str) -> String {
+    let transcript = transcript.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sentence = transcript
+        .split_inclusive(['.', '!', '?'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_end_matches('.');
+    if sentence.chars().count() <= MAX_TITLE_CHARS {
+        return sentence.to_string();
+    }
+    let cut: String = sentence.chars().take(MAX_TITLE_CHARS).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > MAX_TITLE_CHARS / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches([',', ';', ':']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_or_appends_the_input() {
+        let input = Path::new("/tmp/note 1.m4a");
+        assert_eq!(
+            command_args("whisper-cli -m 'base model.bin' -nt -f {input}", input).unwrap(),
+            [
+                "whisper-cli",
+                "-m",
+                "base model.bin",
+                "-nt",
+                "-f",
+                "/tmp/note 1.m4a"
+            ]
+        );
+        assert_eq!(
+            command_args("transcribe --quiet", input).unwrap(),
+            ["transcribe", "--quiet", "/tmp/note 1.m4a"]
+        );
+        assert_eq!(command_args("  ", input), None);
+    }
+
+    #[test]
+    fn titles_use_the_first_sentence() {
+        assert_eq!(
+            title_from_transcript("Add dark mode to settings. It should follow the OS."),
+            "Add dark mode to settings"
+        );
+        let long = "We should really look into why the export job keeps timing out \
+                    for the larger customers every night";
+        let title = title_from_transcript(long);
+        assert!(title.ends_with('…'));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+        assert!(long.starts_with(title.trim_end_matches('…')));
+    }
+
+    #[test]
+    fn recognizes_audio_formats() {
+        assert_eq!(audio_mime_type("memo.M4A"), Some("audio/mp4"));
+        assert_eq!(audio_mime_type("memo.webm"), Some("audio/webm"));
+        assert_eq!(audio_mime_type("memo.txt"), None);
+    }
+}
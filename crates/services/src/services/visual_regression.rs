@@ -0,0 +1,416 @@
+//! Visual regression check between the target branch and an attempt.
+//!
+//! Each configured route is screenshotted on the deployment serving the
+//! target branch and on the attempt's dev server, and the two images are
+//! compared pixel by pixel. Routes changing more than the project's threshold
+//! are flagged and summarised on the attempt's pull requests, so unintended
+//! visual changes from an agent's edits are caught in review.
+
+use std::path::Path;
+
+use db::models::{
+    merge::{Merge, MergeStatus},
+    preview::{CreateVisualComparison, ProjectPreviewSettings, VisualComparison},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use image::{ImageFormat, Rgba, RgbaImage};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    artifacts::{self, ArtifactError},
+    git::GitService,
+    git_host::{GitHostError, GitHostProvider, GitHostService},
+    preview_capture::{self, PreviewError},
+};
+
+/// Directory inside the attempt's artifacts that comparisons are kept in
+pub const VISUAL_DIR: &str = "visual";
+
+/// Channel differences up to this are anti-aliasing and font hinting noise
+const CHANNEL_TOLERANCE: u8 = 16;
+
+/// Colour of changed pixels in the diff image
+const HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 80, 255]);
+
+const MAX_COMMENT_ROUTES: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum VisualRegressionError {
+    #[error("Configure routes and a baseline URL to compare screenshots")]
+    NotConfigured,
+    #[error("Invalid URL {0}")]
+    InvalidUrl(String),
+    #[error("Failed to compare screenshots: {0}")]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Preview(#[from] PreviewError),
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Result of comparing two screenshots
+pub struct PixelDiff {
+    pub changed_percent: f64,
+    /// Changed pixels highlighted over a faded copy of the attempt
+    pub image: RgbaImage,
+}
+
+/// Compare two screenshots pixel by pixel. When their sizes differ, the area
+/// covered by only one of them counts as changed.
+pub fn diff_images(baseline: &RgbaImage, attempt: &RgbaImage) -> PixelDiff {
+    let width = baseline.width().max(attempt.width());
+    let height = baseline.height().max(attempt.height());
+    let mut image = RgbaImage::new(width, height);
+    let mut changed: u64 = 0;
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        *pixel = match (
+            baseline.get_pixel_checked(x, y),
+            attempt.get_pixel_checked(x, y),
+        ) {
+            (Some(before), Some(after)) if !differs(before, after) => faded(after),
+            _ => {
+                changed += 1;
+                HIGHLIGHT
+            }
+        };
+    }
+
+    let total = u64::from(width) * u64::from(height);
+    let changed_percent = if total == 0 {
+        0.0
+    } else {
+        changed as f64 * 100.0 / total as f64
+    };
+    PixelDiff {
+        changed_percent,
+        image,
+    }
+}
+
+fn differs(before: &Rgba<u8>, after: &Rgba<u8>) -> bool {
+    before
+        .0
+        .iter()
+        .zip(after.0.iter())
+        .any(|(before, after)| before.abs_diff(*after) > CHANNEL_TOLERANCE)
+}
+
+/// Light grey version of a pixel, so the highlighted changes stand out
+fn faded(pixel: &Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = pixel.0;
+    let luma = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000;
+    let light = (255 - (255 - luma) / 3) as u8;
+    Rgba([light, light, light, 255])
+}
+
+/// `route` on the server at `base`
+fn route_url(base: &str, route: &str) -> Result<String, VisualRegressionError> {
+    Url::parse(base)
+        .and_then(|base| base.join(route))
+        .map(String::from)
+        .map_err(|_| VisualRegressionError::InvalidUrl(format!("{base}{route}")))
+}
+
+/// File name part for a route: `/` is `root`, `/settings/profile` is
+/// `settings-profile`
+fn route_slug(route: &str) -> String {
+    let slug: String = route
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "root".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn compare_route(
+    pool: &SqlitePool,
+    settings: &ProjectPreviewSettings,
+    workspace_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    baseline_url: &str,
+    attempt_url: &str,
+    index: usize,
+    route: &str,
+) -> Result<CreateVisualComparison, VisualRegressionError> {
+    let baseline_shot =
+        preview_capture::screenshot(&settings.command, &route_url(baseline_url, route)?).await?;
+    let attempt_shot =
+        preview_capture::screenshot(&settings.command, &route_url(attempt_url, route)?).await?;
+    let diff = diff_images(
+        &image::open(&baseline_shot)?.to_rgba8(),
+        &image::open(&attempt_shot)?.to_rgba8(),
+    );
+    let diff_shot = tempfile::Builder::new()
+        .prefix("vk-visual-diff-")
+        .suffix(".png")
+        .tempfile()?
+        .into_temp_path();
+    diff.image.save_with_format(&diff_shot, ImageFormat::Png)?;
+
+    // Numbered so that `/a-b` and `/a/b` do not overwrite each other
+    let prefix = format!("{VISUAL_DIR}/{index:02}-{}", route_slug(route));
+    let name = |kind: &str| format!("{prefix}-{kind}.png");
+    let baseline = artifacts::register(
+        pool,
+        workspace_id,
+        execution_process_id,
+        &baseline_shot,
+        &name("baseline"),
+    )
+    .await?;
+    let attempt = artifacts::register(
+        pool,
+        workspace_id,
+        execution_process_id,
+        &attempt_shot,
+        &name("attempt"),
+    )
+    .await?;
+    let diff_artifact = artifacts::register(
+        pool,
+        workspace_id,
+        execution_process_id,
+        &diff_shot,
+        &name("diff"),
+    )
+    .await?;
+
+    Ok(CreateVisualComparison {
+        route: route.to_string(),
+        baseline_artifact_id: baseline.id,
+        attempt_artifact_id: attempt.id,
+        diff_artifact_id: diff_artifact.id,
+        changed_percent: diff.changed_percent,
+        flagged: diff.changed_percent > settings.diff_threshold,
+    })
+}
+
+/// Compare every configured route between the baseline deployment and the
+/// attempt's server at `attempt_url`, replacing earlier comparisons
+pub async fn compare(
+    pool: &SqlitePool,
+    settings: &ProjectPreviewSettings,
+    workspace_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    attempt_url: &str,
+) -> Result<Vec<VisualComparison>, VisualRegressionError> {
+    let baseline_url = settings
+        .baseline_url
+        .as_deref()
+        .filter(|url| !url.trim().is_empty())
+        .ok_or(VisualRegressionError::NotConfigured)?;
+    if settings.visual_routes.is_empty() {
+        return Err(VisualRegressionError::NotConfigured);
+    }
+
+    let mut comparisons = Vec::with_capacity(settings.visual_routes.len());
+    for (index, route) in settings.visual_routes.iter().enumerate() {
+        comparisons.push(
+            compare_route(
+                pool,
+                settings,
+                workspace_id,
+                execution_process_id,
+                baseline_url,
+                attempt_url,
+                index,
+                route,
+            )
+            .await?,
+        );
+    }
+    let stored = VisualComparison::replace_for_workspace(pool, workspace_id, &comparisons).await?;
+    tracing::info!(
+        "Compared {} routes for workspace {}, {} flagged",
+        stored.len(),
+        workspace_id,
+        stored.iter().filter(|c| c.flagged).count()
+    );
+    Ok(stored)
+}
+
+/// Markdown summary posted on the pull request
+pub fn summary_comment(comparisons: &[VisualComparison], threshold: f64) -> String {
+    let mut comment = String::from("## Visual changes\n\n");
+    let flagged = comparisons.iter().filter(|c| c.flagged).count();
+    if flagged == 0 {
+        comment.push_str(&format!(
+            "No route changed more than {threshold}% of its pixels compared with the target branch.\n"
+        ));
+        return comment;
+    }
+
+    comment.push_str(&format!(
+        "{flagged} of {} routes changed more than {threshold}% of their pixels compared with the \
+         target branch. Check that these changes are intended.\n\n\
+         | Route | Changed pixels | |\n|---|---|---|\n",
+        comparisons.len()
+    ));
+    for comparison in comparisons.iter().take(MAX_COMMENT_ROUTES) {
+        comment.push_str(&format!(
+            "| `{}` | {:.2}% | {} |\n",
+            comparison.route.replace('|', "\\|"),
+            comparison.changed_percent,
+            if comparison.flagged { "changed" } else { "" }
+        ));
+    }
+    if comparisons.len() > MAX_COMMENT_ROUTES {
+        comment.push_str(&format!(
+            "\n{} more routes are listed in the attempt.\n",
+            comparisons.len() - MAX_COMMENT_ROUTES
+        ));
+    }
+    comment.push_str("\nBefore, after and diff screenshots are kept with the attempt.\n");
+    comment
+}
+
+/// Post the workspace's comparisons on a pull request, if the project asks
+/// for it
+pub async fn annotate_pr(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    project_id: Uuid,
+    repo_path: &Path,
+    remote_url: &str,
+    pr_number: i64,
+) -> Result<(), VisualRegressionError> {
+    let Some(settings) = ProjectPreviewSettings::find_by_project_id(pool, project_id).await? else {
+        return Ok(());
+    };
+    if !settings.comment_on_pr {
+        return Ok(());
+    }
+    let comparisons = VisualComparison::find_by_workspace_id(pool, workspace_id).await?;
+    if comparisons.is_empty() {
+        return Ok(());
+    }
+
+    let git_host = GitHostService::from_url(remote_url)?;
+    git_host
+        .add_pr_comment(
+            repo_path,
+            remote_url,
+            pr_number,
+            &summary_comment(&comparisons, settings.diff_threshold),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Compare the configured routes for the workspace and comment on pull
+/// requests that are already open
+pub async fn compare_workspace(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    settings: &ProjectPreviewSettings,
+    execution_process_id: Option<Uuid>,
+    attempt_url: &str,
+) -> Result<Vec<VisualComparison>, VisualRegressionError> {
+    let comparisons = compare(
+        pool,
+        settings,
+        workspace.id,
+        execution_process_id,
+        attempt_url,
+    )
+    .await?;
+    if !settings.comment_on_pr {
+        return Ok(comparisons);
+    }
+
+    for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+        for merge in Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await? {
+            let Merge::Pr(pr) = merge else {
+                continue;
+            };
+            if !matches!(pr.pr_info.status, MergeStatus::Open) {
+                continue;
+            }
+            let remote_url = match git
+                .resolve_remote_name_for_branch(&repo.path, &workspace.branch)
+                .and_then(|remote| git.get_remote_url(&repo.path, &remote))
+            {
+                Ok(remote_url) => remote_url,
+                Err(e) => {
+                    tracing::warn!("No remote for repo {}: {}", repo.name, e);
+                    continue;
+                }
+            };
+            if let Err(e) = annotate_pr(
+                pool,
+                workspace.id,
+                settings.project_id,
+                &repo.path,
+                &remote_url,
+                pr.pr_info.number,
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Failed to comment visual changes on PR #{}: {}",
+                    pr.pr_info.number,
+                    e
+                );
+            }
+        }
+    }
+    Ok(comparisons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_changed_and_resized_areas() {
+        let baseline = RgbaImage::from_pixel(10, 10, Rgba([255, 255, 255, 255]));
+        let mut attempt = baseline.clone();
+        // Below the tolerance, like anti-aliasing
+        attempt.put_pixel(0, 0, Rgba([250, 250, 250, 255]));
+        assert_eq!(diff_images(&baseline, &attempt).changed_percent, 0.0);
+
+        for x in 0..10 {
+            attempt.put_pixel(x, 9, Rgba([0, 0, 0, 255]));
+        }
+        let diff = diff_images(&baseline, &attempt);
+        assert_eq!(diff.changed_percent, 10.0);
+        assert_eq!(*diff.image.get_pixel(3, 9), HIGHLIGHT);
+
+        let taller = RgbaImage::from_pixel(10, 20, Rgba([255, 255, 255, 255]));
+        assert_eq!(diff_images(&baseline, &taller).changed_percent, 50.0);
+    }
+
+    #[test]
+    fn resolves_routes_and_slugs() {
+        assert_eq!(
+            route_url("http://localhost:5173/", "/settings").unwrap(),
+            "http://localhost:5173/settings"
+        );
+        assert_eq!(
+            route_url("https://staging.example.com", "/").unwrap(),
+            "https://staging.example.com/"
+        );
+        assert_eq!(route_slug("/"), "root");
+        assert_eq!(
+            route_slug("/settings/profile?tab=1"),
+            "settings-profile-tab-1"
+        );
+    }
+}
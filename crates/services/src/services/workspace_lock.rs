@@ -0,0 +1,419 @@
+//! Advisory workspace locks shared between server instances.
+//!
+//! Two instances pointed at the same workspace directory will happily run git
+//! operations on the same worktrees and corrupt them. Each instance therefore
+//! writes a small lock file into every workspace it operates on and refreshes
+//! it on a heartbeat. A lock whose heartbeat is older than [`STALE_AFTER`] is
+//! considered abandoned (the owning instance crashed) and can be taken over.
+//!
+//! The lock file is only read and written under an OS file lock, so two
+//! instances checking it at the same time cannot both take it. Releasing a
+//! lock empties the file rather than deleting it, which would let an instance
+//! that already opened the old file lock an inode no one else sees.
+
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use db::{DBService, models::instance::Instance};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use utils::port_file::is_pid_alive;
+use uuid::Uuid;
+
+pub const LOCK_FILE_NAME: &str = ".vk-instance.lock";
+
+/// How often held locks and the instance registry row are refreshed
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Locks not refreshed for this long belong to a dead instance
+pub const STALE_AFTER: Duration = Duration::from_secs(90);
+
+static INSTANCE_ID: LazyLock<Uuid> = LazyLock::new(Uuid::new_v4);
+
+static HELD_LOCKS: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Error)]
+pub enum WorkspaceLockError {
+    #[error(
+        "Workspace {path} is in use by another running instance (pid {pid} on {hostname}, instance {instance_id})"
+    )]
+    LockedByOtherInstance {
+        path: PathBuf,
+        instance_id: Uuid,
+        pid: u32,
+        hostname: String,
+    },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Contents of a workspace lock file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub instance_id: Uuid,
+    pub pid: u32,
+    pub hostname: String,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            instance_id: WorkspaceLock::instance_id(),
+            pid: std::process::id(),
+            hostname: WorkspaceLock::hostname(),
+            heartbeat_at: Utc::now(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        let stale_after = chrono::Duration::from_std(STALE_AFTER).unwrap_or_default();
+        Utc::now() - self.heartbeat_at > stale_after
+    }
+}
+
+pub struct WorkspaceLock;
+
+impl WorkspaceLock {
+    /// Identifier of this server process, stable for its lifetime
+    pub fn instance_id() -> Uuid {
+        *INSTANCE_ID
+    }
+
+    pub fn hostname() -> String {
+        #[cfg(unix)]
+        if let Some(hostname) = nix::unistd::gethostname()
+            .ok()
+            .and_then(|name| name.into_string().ok())
+        {
+            return hostname;
+        }
+        std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".to_string())
+    }
+
+    fn lock_path(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join(LOCK_FILE_NAME)
+    }
+
+    /// Read the current lock owner, if the lock file exists and is readable
+    pub async fn read_owner(workspace_dir: &Path) -> Option<LockOwner> {
+        let lock_path = Self::lock_path(workspace_dir);
+        blocking(move || {
+            let mut file = File::open(lock_path)?;
+            file.lock_shared()?;
+            Ok(read_locked(&mut file))
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Returns the owner if the workspace is held by another live instance
+    pub async fn held_by_other(workspace_dir: &Path) -> Option<LockOwner> {
+        Self::read_owner(workspace_dir)
+            .await
+            .filter(|owner| owner.instance_id != Self::instance_id() && !owner.is_stale())
+    }
+
+    /// Take the lock for this instance. Fails if another live instance holds it.
+    pub async fn acquire(workspace_dir: &Path) -> Result<(), WorkspaceLockError> {
+        let mut held = HELD_LOCKS.lock().await;
+
+        let lock_path = Self::lock_path(workspace_dir);
+        let other = blocking(move || {
+            with_locked_file(&lock_path, |file| match read_locked(file) {
+                Some(owner) if owner.instance_id != Self::instance_id() && !owner.is_stale() => {
+                    Ok(Some(owner))
+                }
+                _ => write_locked(file, &LockOwner::current()).map(|()| None),
+            })
+        })
+        .await?;
+
+        if let Some(owner) = other {
+            return Err(WorkspaceLockError::LockedByOtherInstance {
+                path: workspace_dir.to_path_buf(),
+                instance_id: owner.instance_id,
+                pid: owner.pid,
+                hostname: owner.hostname,
+            });
+        }
+
+        held.insert(workspace_dir.to_path_buf());
+        Ok(())
+    }
+
+    /// Drop the lock if this instance holds it
+    pub async fn release(workspace_dir: &Path) {
+        let mut held = HELD_LOCKS.lock().await;
+        held.remove(workspace_dir);
+
+        let lock_path = Self::lock_path(workspace_dir);
+        let released = blocking(move || {
+            if !lock_path.exists() {
+                return Ok(());
+            }
+            with_locked_file(&lock_path, |file| match read_locked(file) {
+                Some(owner) if owner.instance_id == Self::instance_id() => file.set_len(0),
+                _ => Ok(()),
+            })
+        })
+        .await;
+        if let Err(e) = released {
+            tracing::debug!(
+                "Failed to release workspace lock {}: {}",
+                workspace_dir.display(),
+                e
+            );
+        }
+    }
+
+    /// Release every lock held by this instance (on shutdown)
+    pub async fn release_all() {
+        let held: Vec<PathBuf> = HELD_LOCKS.lock().await.iter().cloned().collect();
+        for workspace_dir in held {
+            Self::release(&workspace_dir).await;
+        }
+    }
+
+    /// Refresh the heartbeat of a held lock. Returns false when another
+    /// instance took the lock over in the meantime.
+    async fn refresh(workspace_dir: &Path) -> io::Result<bool> {
+        let lock_path = Self::lock_path(workspace_dir);
+        blocking(move || {
+            with_locked_file(&lock_path, |file| match read_locked(file) {
+                Some(owner) if owner.instance_id != Self::instance_id() => Ok(false),
+                _ => write_locked(file, &LockOwner::current()).map(|()| true),
+            })
+        })
+        .await
+    }
+
+    /// Refresh every held lock. Locks whose workspace disappeared or that
+    /// another instance took over are forgotten.
+    async fn refresh_held() {
+        let mut held = HELD_LOCKS.lock().await;
+        let mut gone = Vec::new();
+
+        for workspace_dir in held.iter() {
+            if !workspace_dir.exists() {
+                gone.push(workspace_dir.clone());
+                continue;
+            }
+            match Self::refresh(workspace_dir).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::warn!(
+                        "Workspace lock {} was taken over by another instance",
+                        workspace_dir.display()
+                    );
+                    gone.push(workspace_dir.clone());
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to refresh workspace lock {}: {}",
+                    workspace_dir.display(),
+                    e
+                ),
+            }
+        }
+
+        for workspace_dir in gone {
+            held.remove(&workspace_dir);
+        }
+    }
+
+    /// Register this instance in the database and keep it, and every held
+    /// workspace lock, alive until the process exits.
+    pub async fn spawn_heartbeat(db: DBService, workspace_base_dir: PathBuf) {
+        let stale_after = chrono::Duration::from_std(STALE_AFTER).unwrap_or_default();
+        let base_dir = workspace_base_dir.to_string_lossy().to_string();
+
+        if let Err(e) = Instance::delete_stale(&db.pool, stale_after).await {
+            tracing::warn!("Failed to prune stale instances: {}", e);
+        }
+
+        match Instance::find_live(&db.pool, stale_after).await {
+            Ok(live) => {
+                for other in live.iter().filter(|i| i.workspace_dir == base_dir) {
+                    tracing::warn!(
+                        "Another instance (pid {} on {}) is using workspace directory {}; workspaces it holds will be skipped",
+                        other.pid,
+                        other.hostname,
+                        base_dir
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to list running instances: {}", e),
+        }
+
+        if let Err(e) = Instance::register(
+            &db.pool,
+            Self::instance_id(),
+            std::process::id() as i64,
+            &Self::hostname(),
+            &base_dir,
+        )
+        .await
+        {
+            tracing::warn!("Failed to register instance: {}", e);
+        }
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                Self::refresh_held().await;
+                if let Err(e) = Instance::heartbeat(&db.pool, Self::instance_id()).await {
+                    tracing::warn!("Failed to record instance heartbeat: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Ids of the instances that are still running. An instance registered on
+    /// this host whose process is gone is left out even while its heartbeat is
+    /// fresh, as after a crash followed by a quick restart.
+    pub async fn live_instance_ids(pool: &SqlitePool) -> Result<HashSet<Uuid>, sqlx::Error> {
+        let stale_after = chrono::Duration::from_std(STALE_AFTER).unwrap_or_default();
+        let hostname = Self::hostname();
+        let own_pid = std::process::id() as i64;
+        let mut live = HashSet::new();
+        for instance in Instance::find_live(pool, stale_after).await? {
+            let running = instance.id == Self::instance_id()
+                || instance.hostname != hostname
+                || (instance.pid != own_pid && is_pid_alive(instance.pid as u32).await);
+            if running {
+                live.insert(instance.id);
+            }
+        }
+        Ok(live)
+    }
+
+    /// Remove this instance from the registry and release its locks
+    pub async fn shutdown(db: &DBService) {
+        Self::release_all().await;
+        if let Err(e) = Instance::deregister(&db.pool, Self::instance_id()).await {
+            tracing::warn!("Failed to deregister instance: {}", e);
+        }
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> io::Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Run `f` on the lock file, created if missing, while holding an exclusive
+/// OS lock on it. The OS lock is dropped with the file.
+fn with_locked_file<T>(
+    lock_path: &Path,
+    f: impl FnOnce(&mut File) -> io::Result<T>,
+) -> io::Result<T> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path)?;
+    file.lock()?;
+    f(&mut file)
+}
+
+/// Owner recorded in a locked file; `None` when it is empty or unreadable
+fn read_locked(file: &mut File) -> Option<LockOwner> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_locked(file: &mut File, owner: &LockOwner) -> io::Result<()> {
+    let content = serde_json::to_vec(owner).map_err(io::Error::other)?;
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&content)?;
+    file.sync_data()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_owner(workspace_dir: &Path, instance_id: Uuid, heartbeat_at: DateTime<Utc>) {
+        let owner = LockOwner {
+            instance_id,
+            pid: 4242,
+            hostname: "other-host".to_string(),
+            heartbeat_at,
+        };
+        std::fs::write(
+            workspace_dir.join(LOCK_FILE_NAME),
+            serde_json::to_string(&owner).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn refuses_lock_held_by_live_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let other = Uuid::new_v4();
+        write_owner(dir.path(), other, Utc::now());
+
+        match WorkspaceLock::acquire(dir.path()).await {
+            Err(WorkspaceLockError::LockedByOtherInstance { instance_id, .. }) => {
+                assert_eq!(instance_id, other)
+            }
+            result => panic!("expected the lock to be refused, got {result:?}"),
+        }
+        assert_eq!(
+            WorkspaceLock::held_by_other(dir.path())
+                .await
+                .map(|owner| owner.instance_id),
+            Some(other)
+        );
+    }
+
+    #[tokio::test]
+    async fn takes_over_stale_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        write_owner(
+            dir.path(),
+            Uuid::new_v4(),
+            Utc::now() - chrono::Duration::minutes(10),
+        );
+
+        WorkspaceLock::acquire(dir.path()).await.unwrap();
+        let owner = WorkspaceLock::read_owner(dir.path()).await.unwrap();
+        assert_eq!(owner.instance_id, WorkspaceLock::instance_id());
+
+        WorkspaceLock::release(dir.path()).await;
+        assert!(WorkspaceLock::read_owner(dir.path()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn refresh_moves_held_lock_heartbeat_forward() {
+        let dir = tempfile::tempdir().unwrap();
+        WorkspaceLock::acquire(dir.path()).await.unwrap();
+        let last_beat = Utc::now() - chrono::Duration::minutes(1);
+        write_owner(dir.path(), WorkspaceLock::instance_id(), last_beat);
+
+        WorkspaceLock::refresh_held().await;
+        let owner = WorkspaceLock::read_owner(dir.path()).await.unwrap();
+        assert_eq!(owner.instance_id, WorkspaceLock::instance_id());
+        assert!(owner.heartbeat_at > last_beat);
+
+        WorkspaceLock::release(dir.path()).await;
+    }
+}
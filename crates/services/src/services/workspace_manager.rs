@@ -1,12 +1,21 @@
 use std::path::{Path, PathBuf};
 
-use db::models::{repo::Repo, workspace::Workspace as DbWorkspace};
+use db::models::{
+    repo::Repo, workspace::Workspace as DbWorkspace, workspace_repo::WorkspaceRepoCheckout,
+};
+use git2::{BranchType, Repository};
 use sqlx::{Pool, Sqlite};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use super::worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+use super::{
+    workspace_lock::{WorkspaceLock, WorkspaceLockError},
+    worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager},
+};
+
+/// Subdirectory of the workspace base dir holding per-user workspace dirs
+pub const USER_WORKSPACES_DIR: &str = "users";
 
 #[derive(Debug, Clone)]
 pub struct RepoWorkspaceInput {
@@ -33,6 +42,8 @@ pub enum WorkspaceError {
     NoRepositories,
     #[error("Partial workspace creation failed: {0}")]
     PartialCreation(String),
+    #[error(transparent)]
+    Locked(#[from] WorkspaceLockError),
 }
 
 /// Info about a single repo's worktree within a workspace
@@ -72,6 +83,7 @@ impl WorkspaceManager {
         );
 
         tokio::fs::create_dir_all(workspace_dir).await?;
+        WorkspaceLock::acquire(workspace_dir).await?;
 
         let mut created_worktrees: Vec<RepoWorktree> = Vec::new();
 
@@ -109,6 +121,7 @@ impl WorkspaceManager {
 
                     // Rollback: cleanup all worktrees we've created so far
                     Self::cleanup_created_worktrees(&created_worktrees).await;
+                    WorkspaceLock::release(workspace_dir).await;
 
                     // Also remove the workspace directory if it's empty
                     if let Err(cleanup_err) = tokio::fs::remove_dir(workspace_dir).await {
@@ -150,12 +163,14 @@ impl WorkspaceManager {
         // Try legacy migration first (single repo projects only)
         // Old layout had worktree directly at workspace_dir; new layout has it at workspace_dir/{repo_name}
         if repos.len() == 1 && Self::migrate_legacy_worktree(workspace_dir, &repos[0]).await? {
+            WorkspaceLock::acquire(workspace_dir).await?;
             return Ok(());
         }
 
         if !workspace_dir.exists() {
             tokio::fs::create_dir_all(workspace_dir).await?;
         }
+        WorkspaceLock::acquire(workspace_dir).await?;
 
         for repo in repos {
             let worktree_path = workspace_dir.join(&repo.name);
@@ -173,6 +188,79 @@ impl WorkspaceManager {
         Ok(())
     }
 
+    /// Ensure the worktrees of a workspace's extra checkouts exist, creating
+    /// each checkout's branch from its source branch the first time
+    pub async fn ensure_checkouts_exist(
+        workspace_dir: &Path,
+        checkouts: &[(Repo, WorkspaceRepoCheckout)],
+    ) -> Result<(), WorkspaceError> {
+        for (repo, checkout) in checkouts {
+            let worktree_path = workspace_dir.join(checkout.dir_name(&repo.name));
+            let branch_exists = tokio::task::spawn_blocking({
+                let repo_path = repo.path.clone();
+                let branch = checkout.branch.clone();
+                move || {
+                    let Ok(repo) = Repository::open(&repo_path) else {
+                        return false;
+                    };
+                    repo.find_branch(&branch, BranchType::Local).is_ok()
+                }
+            })
+            .await
+            .unwrap_or(false);
+
+            debug!(
+                "Ensuring checkout '{}' of repo '{}' exists at {}",
+                checkout.alias,
+                repo.name,
+                worktree_path.display()
+            );
+            WorktreeManager::create_worktree(
+                &repo.path,
+                &checkout.branch,
+                &worktree_path,
+                &checkout.source_branch,
+                !branch_exists,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the worktree of an extra checkout, keeping its branch
+    pub async fn remove_checkout(
+        workspace_dir: &Path,
+        repo: &Repo,
+        checkout: &WorkspaceRepoCheckout,
+    ) -> Result<(), WorkspaceError> {
+        let worktree_path = workspace_dir.join(checkout.dir_name(&repo.name));
+        WorktreeManager::cleanup_worktree(&WorktreeCleanup::new(
+            worktree_path,
+            Some(repo.path.clone()),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Worktrees in `workspace_dir` other than the primary ones of `repos`,
+    /// i.e. extra checkouts
+    async fn other_worktrees(workspace_dir: &Path, repos: &[Repo]) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        let Ok(mut entries) = tokio::fs::read_dir(workspace_dir).await else {
+            return found;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let is_primary = repos
+                .iter()
+                .any(|repo| entry.file_name().to_string_lossy() == repo.name);
+            if !is_primary && path.join(".git").is_file() {
+                found.push(path);
+            }
+        }
+        found
+    }
+
     /// Clean up all worktrees in a workspace
     pub async fn cleanup_workspace(
         workspace_dir: &Path,
@@ -180,13 +268,31 @@ impl WorkspaceManager {
     ) -> Result<(), WorkspaceError> {
         info!("Cleaning up workspace at {}", workspace_dir.display());
 
-        let cleanup_data: Vec<WorktreeCleanup> = repos
+        if let Some(owner) = WorkspaceLock::held_by_other(workspace_dir).await {
+            return Err(WorkspaceLockError::LockedByOtherInstance {
+                path: workspace_dir.to_path_buf(),
+                instance_id: owner.instance_id,
+                pid: owner.pid,
+                hostname: owner.hostname,
+            }
+            .into());
+        }
+        WorkspaceLock::release(workspace_dir).await;
+
+        let mut cleanup_data: Vec<WorktreeCleanup> = repos
             .iter()
             .map(|repo| {
                 let worktree_path = workspace_dir.join(&repo.name);
                 WorktreeCleanup::new(worktree_path, Some(repo.path.clone()))
             })
             .collect();
+        // Extra checkouts find their repo through their own git metadata
+        cleanup_data.extend(
+            Self::other_worktrees(workspace_dir, repos)
+                .await
+                .into_iter()
+                .map(|path| WorktreeCleanup::new(path, None)),
+        );
 
         WorktreeManager::batch_cleanup_worktrees(&cleanup_data).await?;
 
@@ -304,6 +410,21 @@ impl WorkspaceManager {
     }
 
     async fn cleanup_orphans_in_directory(db: &Pool<Sqlite>, workspace_base_dir: &Path) {
+        Self::cleanup_orphans_in_single_directory(db, workspace_base_dir).await;
+
+        // Multi-user mode nests workspaces one level deeper, per user
+        let users_dir = workspace_base_dir.join(USER_WORKSPACES_DIR);
+        if let Ok(entries) = std::fs::read_dir(&users_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    Self::cleanup_orphans_in_single_directory(db, &path).await;
+                }
+            }
+        }
+    }
+
+    async fn cleanup_orphans_in_single_directory(db: &Pool<Sqlite>, workspace_base_dir: &Path) {
         if !workspace_base_dir.exists() {
             debug!(
                 "Workspace base directory {} does not exist, skipping orphan cleanup",
@@ -334,7 +455,18 @@ impl WorkspaceManager {
             };
 
             let path = entry.path();
-            if !path.is_dir() {
+            if !path.is_dir() || entry.file_name() == USER_WORKSPACES_DIR {
+                continue;
+            }
+
+            // Workspaces held by another live instance are not ours to clean up
+            if let Some(owner) = WorkspaceLock::held_by_other(&path).await {
+                debug!(
+                    "Skipping workspace {} held by instance {} (pid {})",
+                    path.display(),
+                    owner.instance_id,
+                    owner.pid
+                );
                 continue;
             }
 
@@ -0,0 +1,530 @@
+//! Live view of hand edits in attempt worktrees.
+//!
+//! Every active attempt gets a filesystem watcher on its workspace directory.
+//! Changes made while none of the attempt's processes is running can only come
+//! from the user: they refresh the attempt's worktree state, which is pushed to
+//! the event stream, and in `Manual` commit title mode they queue a pending
+//! commit for every repo that has uncommitted changes.
+//!
+//! Writes to a hand-edited file while an agent runs are reported as conflicts.
+//! With `auto_stash_hand_edits` the hand-edited files are stashed before an
+//! agent run and popped again once it ends, so the agent never sees them.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, LazyLock, RwLock as StdRwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        pending_commit::{CreatePendingCommit, PendingCommit},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use futures::StreamExt;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::{sync::RwLock, task::JoinHandle, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use crate::services::{
+    config::{Config, GitCommitTitleMode},
+    events::worktree_state_patch,
+    filesystem_watcher,
+    git::GitCli,
+};
+
+/// Hand edits seen in an attempt's worktree, as `repo/path` entries
+#[derive(Debug, Default)]
+struct HandEdits {
+    at: Option<DateTime<Utc>>,
+    files: BTreeSet<String>,
+    conflicts: BTreeSet<String>,
+}
+
+/// Hand edits per attempt, kept for the initial snapshot of streams
+static HAND_EDITS: LazyLock<StdRwLock<HashMap<Uuid, HandEdits>>> =
+    LazyLock::new(|| StdRwLock::new(HashMap::new()));
+
+fn stash_message(execution_process_id: Uuid) -> String {
+    format!("vibe-kanban: hand edits before {execution_process_id}")
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoWorktreeState {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Uncommitted paths, relative to the repo root
+    pub changed_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeState {
+    pub workspace_id: Uuid,
+    pub repos: Vec<RepoWorktreeState>,
+    /// Last change made while none of the attempt's processes was running
+    pub manual_edit_at: Option<DateTime<Utc>>,
+    /// Uncommitted files changed by hand, as `repo/path`
+    pub hand_edited_files: Vec<String>,
+    /// Hand-edited files written to again while the agent was running
+    pub conflicts: Vec<String>,
+}
+
+fn changed_files(worktree: &Path) -> Vec<String> {
+    match GitCli::new().get_worktree_status(worktree) {
+        Ok(status) => status
+            .entries
+            .into_iter()
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .collect(),
+        Err(e) => {
+            debug!("Could not read status of {}: {}", worktree.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+async fn repo_states(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Vec<RepoWorktreeState>, sqlx::Error> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let root = workspace.container_ref.as_ref().map(PathBuf::from);
+    let repos = tokio::task::spawn_blocking(move || {
+        repos
+            .into_iter()
+            .map(|repo| RepoWorktreeState {
+                changed_files: root
+                    .as_ref()
+                    .map(|root| changed_files(&root.join(&repo.name)))
+                    .unwrap_or_default(),
+                repo_id: repo.id,
+                repo_name: repo.name,
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+    Ok(repos)
+}
+
+fn snapshot(workspace_id: Uuid, repos: Vec<RepoWorktreeState>) -> WorktreeState {
+    let edits = HAND_EDITS.read().unwrap();
+    let edits = edits.get(&workspace_id);
+    WorktreeState {
+        workspace_id,
+        repos,
+        manual_edit_at: edits.and_then(|edits| edits.at),
+        hand_edited_files: edits
+            .map(|edits| edits.files.iter().cloned().collect())
+            .unwrap_or_default(),
+        conflicts: edits
+            .map(|edits| edits.conflicts.iter().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Uncommitted files of every repo of the attempt
+pub async fn worktree_state(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<WorktreeState, sqlx::Error> {
+    let repos = repo_states(pool, workspace).await?;
+    Ok(snapshot(workspace.id, repos))
+}
+
+/// `repo/path` of a watcher event, unless it concerns git metadata
+fn worktree_path(path: &Path, root: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut parts = Vec::new();
+    for part in relative.components() {
+        match part {
+            Component::Normal(name) if name == ".git" => return None,
+            Component::Normal(name) => parts.push(name.to_string_lossy()),
+            _ => {}
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Service that watches the worktrees of active attempts for hand edits
+pub struct WorktreeWatchService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    msg_store: Arc<MsgStore>,
+    poll_interval: Duration,
+}
+
+impl WorktreeWatchService {
+    pub async fn spawn(
+        db: DBService,
+        config: Arc<RwLock<Config>>,
+        msg_store: Arc<MsgStore>,
+    ) -> JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            msg_store,
+            poll_interval: Duration::from_secs(15),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting worktree watch service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut watchers: HashMap<Uuid, JoinHandle<()>> = HashMap::new();
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if !self.config.read().await.live_worktree_watch {
+                for (workspace_id, watcher) in watchers.drain() {
+                    watcher.abort();
+                    HAND_EDITS.write().unwrap().remove(&workspace_id);
+                }
+                continue;
+            }
+
+            let workspaces = match Workspace::find_active(&self.db.pool).await {
+                Ok(workspaces) => workspaces,
+                Err(e) => {
+                    error!("Error loading active attempts to watch: {}", e);
+                    continue;
+                }
+            };
+            let active: HashSet<Uuid> = workspaces.iter().map(|w| w.id).collect();
+            watchers.retain(|workspace_id, watcher| {
+                let keep = active.contains(workspace_id) && !watcher.is_finished();
+                if !keep {
+                    watcher.abort();
+                    HAND_EDITS.write().unwrap().remove(workspace_id);
+                }
+                keep
+            });
+
+            for workspace in workspaces {
+                if watchers.contains_key(&workspace.id) {
+                    continue;
+                }
+                let Some(root) = workspace.container_ref.as_ref().map(PathBuf::from) else {
+                    continue;
+                };
+                if !root.exists() {
+                    continue;
+                }
+                watchers.insert(workspace.id, self.watch(workspace.id, root));
+            }
+        }
+    }
+
+    fn watch(&self, workspace_id: Uuid, root: PathBuf) -> JoinHandle<()> {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let msg_store = self.msg_store.clone();
+        tokio::spawn(async move {
+            let (debouncer, mut rx, canonical_root) =
+                match filesystem_watcher::async_watcher(root.clone()) {
+                    Ok(components) => components,
+                    Err(e) => {
+                        warn!("Failed to watch worktree {}: {}", root.display(), e);
+                        return;
+                    }
+                };
+            let _guard = debouncer;
+            debug!("Watching worktree {} for hand edits", root.display());
+
+            while let Some(result) = rx.next().await {
+                let events = match result {
+                    Ok(events) => events,
+                    Err(errors) => {
+                        warn!("Watcher errors for {}: {:?}", root.display(), errors);
+                        continue;
+                    }
+                };
+                let touched: BTreeSet<String> = events
+                    .iter()
+                    .flat_map(|event| &event.paths)
+                    .filter_map(|path| worktree_path(path, &canonical_root))
+                    .collect();
+                if touched.is_empty() {
+                    continue;
+                }
+                if let Err(e) =
+                    on_change(&db.pool, &config, &msg_store, workspace_id, touched).await
+                {
+                    warn!(
+                        "Failed to refresh worktree state of attempt {}: {}",
+                        workspace_id, e
+                    );
+                }
+            }
+        })
+    }
+}
+
+async fn on_change(
+    pool: &SqlitePool,
+    config: &Arc<RwLock<Config>>,
+    msg_store: &MsgStore,
+    workspace_id: Uuid,
+    touched: BTreeSet<String>,
+) -> Result<(), sqlx::Error> {
+    let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? else {
+        return Ok(());
+    };
+
+    // Agent and script runs write to the worktree too; only the changes made
+    // while nothing runs are the user's
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace_id)
+        .await?
+    {
+        let conflicts = record_conflicts(workspace_id, &touched);
+        if !conflicts.is_empty() {
+            warn!(
+                "Hand-edited files of attempt {} written during a run: {}",
+                workspace_id,
+                conflicts.join(", ")
+            );
+            let state = worktree_state(pool, &workspace).await?;
+            msg_store.push_patch(worktree_state_patch::replace(&state));
+        }
+        return Ok(());
+    }
+
+    let repos = repo_states(pool, &workspace).await?;
+    record_hand_edits(workspace_id, &repos, touched);
+    let state = snapshot(workspace_id, repos);
+    msg_store.push_patch(worktree_state_patch::replace(&state));
+
+    if config.read().await.git_commit_title_mode == GitCommitTitleMode::Manual {
+        queue_pending_commits(pool, &workspace, &state).await?;
+    }
+    Ok(())
+}
+
+/// Keeps the touched files among the uncommitted ones, dropping the files that
+/// were committed or reverted since
+fn record_hand_edits(workspace_id: Uuid, repos: &[RepoWorktreeState], touched: BTreeSet<String>) {
+    let dirty: HashSet<String> = repos
+        .iter()
+        .flat_map(|repo| {
+            repo.changed_files
+                .iter()
+                .map(move |file| format!("{}/{}", repo.repo_name, file))
+        })
+        .collect();
+    let mut edits = HAND_EDITS.write().unwrap();
+    let edits = edits.entry(workspace_id).or_default();
+    edits.at = Some(Utc::now());
+    edits.files.extend(touched);
+    edits.files.retain(|file| dirty.contains(file));
+}
+
+/// Newly touched hand-edited files, remembered as conflicts
+fn record_conflicts(workspace_id: Uuid, touched: &BTreeSet<String>) -> Vec<String> {
+    let mut edits = HAND_EDITS.write().unwrap();
+    let Some(edits) = edits.get_mut(&workspace_id) else {
+        return Vec::new();
+    };
+    let conflicts: Vec<String> = touched
+        .iter()
+        .filter(|file| edits.files.contains(*file) && !edits.conflicts.contains(*file))
+        .cloned()
+        .collect();
+    edits.conflicts.extend(conflicts.iter().cloned());
+    conflicts
+}
+
+/// Stashes the hand-edited files of every repo before an agent run, so they
+/// are out of the agent's way until [`restore_hand_edits`] pops them again.
+/// Returns the names of the repos that had something stashed.
+pub async fn stash_hand_edits(workspace: &Workspace, execution_process_id: Uuid) -> Vec<String> {
+    let Some(root) = workspace.container_ref.as_ref().map(PathBuf::from) else {
+        return Vec::new();
+    };
+    // Forget the files up front: the watcher sees the stash while the run has
+    // started, which must not count as a conflict
+    let files = match HAND_EDITS.write().unwrap().get_mut(&workspace.id) {
+        Some(edits) => {
+            edits.conflicts.clear();
+            std::mem::take(&mut edits.files)
+        }
+        None => return Vec::new(),
+    };
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    let mut by_repo: HashMap<String, Vec<String>> = HashMap::new();
+    for file in files {
+        if let Some((repo, path)) = file.split_once('/') {
+            by_repo
+                .entry(repo.to_string())
+                .or_default()
+                .push(path.to_string());
+        }
+    }
+
+    let message = stash_message(execution_process_id);
+    tokio::task::spawn_blocking(move || {
+        let git = GitCli::new();
+        let mut stashed = Vec::new();
+        for (repo, paths) in by_repo {
+            let worktree = root.join(&repo);
+            let dirty: HashSet<String> = changed_files(&worktree).into_iter().collect();
+            let paths: Vec<String> = paths.into_iter().filter(|p| dirty.contains(p)).collect();
+            if paths.is_empty() {
+                continue;
+            }
+            let mut args = vec![
+                "stash",
+                "push",
+                "--include-untracked",
+                "-m",
+                message.as_str(),
+                "--",
+            ];
+            args.extend(paths.iter().map(String::as_str));
+            match git.git(&worktree, args) {
+                Ok(_) => {
+                    info!("Stashed {} hand-edited file(s) in {}", paths.len(), repo);
+                    stashed.push(repo);
+                }
+                Err(e) => warn!("Failed to stash hand edits in {}: {}", repo, e),
+            }
+        }
+        stashed
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Pops the stash [`stash_hand_edits`] made for a run. A pop that conflicts
+/// with the agent's changes leaves the stash in place for the user.
+pub async fn restore_hand_edits(
+    workspace: &Workspace,
+    repo_names: Vec<String>,
+    execution_process_id: Uuid,
+) {
+    let Some(root) = workspace.container_ref.as_ref().map(PathBuf::from) else {
+        return;
+    };
+    let message = stash_message(execution_process_id);
+    let _ = tokio::task::spawn_blocking(move || {
+        let git = GitCli::new();
+        for repo in repo_names {
+            let worktree = root.join(&repo);
+            let Ok(list) = git.git(&worktree, ["stash", "list", "--format=%gd %s"]) else {
+                continue;
+            };
+            let Some(stash_ref) = list
+                .lines()
+                .find(|line| line.ends_with(&message))
+                .and_then(|line| line.split_whitespace().next())
+            else {
+                continue;
+            };
+            match git.git(&worktree, ["stash", "pop", stash_ref]) {
+                Ok(_) => info!("Restored hand edits in {}", repo),
+                Err(e) => warn!(
+                    "Could not restore hand edits in {}, left in {}: {}",
+                    repo, stash_ref, e
+                ),
+            }
+        }
+    })
+    .await;
+}
+
+/// One pending commit per dirty repo, unless one is already waiting for a title
+async fn queue_pending_commits(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    state: &WorktreeState,
+) -> Result<(), sqlx::Error> {
+    let Some(root) = workspace.container_ref.as_ref().map(PathBuf::from) else {
+        return Ok(());
+    };
+    let queued: HashSet<Uuid> = PendingCommit::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|pending| pending.repo_id)
+        .collect();
+
+    for repo in &state.repos {
+        if repo.changed_files.is_empty() || queued.contains(&repo.repo_id) {
+            continue;
+        }
+        let worktree = root.join(&repo.repo_name);
+        let diff_summary = tokio::task::spawn_blocking(move || GitCli::new().diff_stat(&worktree))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .unwrap_or_else(|| format!("{} changed file(s)", repo.changed_files.len()));
+        PendingCommit::create(
+            pool,
+            &CreatePendingCommit {
+                workspace_id: workspace.id,
+                repo_id: repo.repo_id,
+                repo_path: repo.repo_name.clone(),
+                diff_summary,
+                agent_summary: None,
+            },
+        )
+        .await?;
+        info!(
+            "Queued pending commit for hand edits in attempt {} repo {}",
+            workspace.id, repo.repo_name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_git_metadata() {
+        let root = Path::new("/work/vk-1234");
+        assert_eq!(
+            worktree_path(&root.join("app/src/main.rs"), root).as_deref(),
+            Some("app/src/main.rs")
+        );
+        assert_eq!(worktree_path(&root.join("app/.git/index"), root), None);
+        assert_eq!(worktree_path(Path::new("/elsewhere/file.rs"), root), None);
+    }
+
+    #[test]
+    fn flags_writes_to_hand_edited_files() {
+        let workspace_id = Uuid::new_v4();
+        let repos = vec![RepoWorktreeState {
+            repo_id: Uuid::new_v4(),
+            repo_name: "app".to_string(),
+            changed_files: vec!["src/main.rs".to_string()],
+        }];
+        let touched = BTreeSet::from(["app/src/main.rs".to_string(), "app/README.md".to_string()]);
+        record_hand_edits(workspace_id, &repos, touched.clone());
+
+        assert_eq!(
+            record_conflicts(workspace_id, &touched),
+            ["app/src/main.rs"]
+        );
+        assert!(record_conflicts(workspace_id, &touched).is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 };
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::git::{GitCli, GitCliError, GitService, SshIdentity};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
@@ -1406,3 +1406,14 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn ssh_identity_quotes_key_paths_for_the_shell() {
+    let key = SshIdentity::KeyFile(PathBuf::from("/home/me/deploy keys/it's_mine"));
+    assert_eq!(
+        key.ssh_command(),
+        r"ssh -i '/home/me/deploy keys/it'\''s_mine' -o IdentitiesOnly=yes"
+    );
+    let agent = SshIdentity::Agent { socket: None };
+    assert_eq!(agent.ssh_command(), "ssh -o IdentityAgent=SSH_AUTH_SOCK");
+}
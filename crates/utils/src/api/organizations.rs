@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::validation::{MAX_NAME_CHARS, Validate, ValidationErrors};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[sqlx(type_name = "member_role", rename_all = "lowercase")]
@@ -84,6 +88,35 @@ pub struct UpdateOrganizationRequest {
     pub name: String,
 }
 
+impl Validate for CreateOrganizationRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", self.name.trim(), MAX_NAME_CHARS);
+        // Same rules the remote server applies after lowercasing
+        let slug = self.slug.trim().to_lowercase();
+        if !(3..=63).contains(&slug.len()) {
+            errors.add("slug", "must be between 3 and 63 characters");
+        } else if !slug
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            errors.add(
+                "slug",
+                "can only contain lowercase letters, numbers, hyphens, and underscores",
+            );
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateOrganizationRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("name", self.name.trim(), MAX_NAME_CHARS);
+        errors.into_result()
+    }
+}
+
 // Invitation types
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -105,6 +138,27 @@ pub struct Invitation {
 pub struct CreateInvitationRequest {
     pub email: String,
     pub role: MemberRole,
+    /// Days until the invitation link expires; the server default when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub expires_in_days: Option<u32>,
+}
+
+impl Validate for CreateInvitationRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let email = self.email.trim();
+        let valid_email = email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+        if !valid_email || email.chars().any(char::is_whitespace) {
+            errors.add("email", "must be a valid email address");
+        }
+        if self.expires_in_days == Some(0) {
+            errors.add("expires_in_days", "must be at least 1");
+        }
+        errors.into_result()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -141,6 +195,15 @@ pub struct RevokeInvitationRequest {
     pub invitation_id: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ResendInvitationRequest {
+    pub invitation_id: Uuid,
+    /// Days until the new invitation link expires; the server default when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub expires_in_days: Option<u32>,
+}
+
 // Member types
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -182,3 +245,137 @@ pub struct UpdateMemberRoleResponse {
     pub user_id: Uuid,
     pub role: MemberRole,
 }
+
+// Shared configuration types
+
+/// Baseline settings an organization distributes to its members. Local
+/// instances apply it underneath each user's own settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedConfigBaseline {
+    /// Executor profiles in `profiles.json` format
+    #[serde(default)]
+    pub executor_profiles: Option<serde_json::Value>,
+    /// Prompt library templates by name
+    #[serde(default)]
+    pub prompts: HashMap<String, String>,
+    /// MCP servers by executor (e.g. `CLAUDE_CODE`), then by server name
+    #[serde(default)]
+    pub mcp_servers: HashMap<String, HashMap<String, serde_json::Value>>,
+    /// Sensitive file patterns added to each member's list
+    #[serde(default)]
+    pub sensitive_file_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OrganizationSharedConfig {
+    pub organization_id: Uuid,
+    pub config: SharedConfigBaseline,
+    /// Incremented on every update
+    pub version: i64,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateSharedConfigRequest {
+    pub config: SharedConfigBaseline,
+}
+
+// Notification preference types
+
+/// How often routine shared task activity is rolled up for a member
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum NotificationDigest {
+    /// Every event is delivered on its own
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl NotificationDigest {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationDigest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            other => Err(format!("unknown notification digest '{other}'")),
+        }
+    }
+}
+
+/// Which shared task events a member of an organization is notified about
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MemberNotificationPreferences {
+    /// A task is assigned to the member
+    pub notify_on_assignment: bool,
+    /// The member is mentioned in a task or comment
+    pub notify_on_mention: bool,
+    /// A task the member created or is assigned changes status or agent state
+    pub notify_on_status_change: bool,
+    /// Rolls status changes up into one notification per period
+    pub digest: NotificationDigest,
+}
+
+impl Default for MemberNotificationPreferences {
+    fn default() -> Self {
+        Self {
+            notify_on_assignment: true,
+            notify_on_mention: true,
+            notify_on_status_change: true,
+            digest: NotificationDigest::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateMemberNotificationPreferencesRequest {
+    #[serde(default)]
+    #[ts(optional)]
+    pub notify_on_assignment: Option<bool>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub notify_on_mention: Option<bool>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub notify_on_status_change: Option<bool>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub digest: Option<NotificationDigest>,
+}
+
+impl MemberNotificationPreferences {
+    pub fn apply(&mut self, update: &UpdateMemberNotificationPreferencesRequest) {
+        if let Some(value) = update.notify_on_assignment {
+            self.notify_on_assignment = value;
+        }
+        if let Some(value) = update.notify_on_mention {
+            self.notify_on_mention = value;
+        }
+        if let Some(value) = update.notify_on_status_change {
+            self.notify_on_status_change = value;
+        }
+        if let Some(digest) = update.digest {
+            self.digest = digest;
+        }
+    }
+}
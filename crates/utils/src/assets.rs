@@ -32,10 +32,35 @@ pub fn profiles_path() -> std::path::PathBuf {
     asset_dir().join("profiles.json")
 }
 
+/// Executor profiles pulled from the organization's shared config
+pub fn org_profiles_path() -> std::path::PathBuf {
+    asset_dir().join("org_profiles.json")
+}
+
+/// Last shared config pulled from the organization
+pub fn org_config_path() -> std::path::PathBuf {
+    asset_dir().join("org_config.json")
+}
+
 pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+/// API keys and tokens, encrypted with the key at [`secrets_key_path`]
+pub fn secrets_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.enc")
+}
+
+pub fn secrets_key_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.key")
+}
+
+/// Tokens for HTTPS remotes stored with git's `store` helper, for hosts
+/// without a credential helper of their own
+pub fn git_credentials_path() -> std::path::PathBuf {
+    asset_dir().join("git-credentials")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -19,6 +19,7 @@ pub mod stream_lines;
 pub mod terminal;
 pub mod text;
 pub mod tokio;
+pub mod validation;
 pub mod version;
 
 /// Cache for WSL2 detection result
@@ -1,7 +1,21 @@
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use tokio::fs;
 
+/// One running server instance, as advertised in the port-file registry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstanceEntry {
+    pub port: u16,
+    pub pid: u32,
+    pub workspace_dir: Option<PathBuf>,
+    pub started_at: DateTime<Utc>,
+}
+
 pub async fn write_port_file(port: u16) -> std::io::Result<PathBuf> {
     // allow override for worktree-specific port files
     let path = if let Ok(custom_path) = env::var("VK_PORT_FILE") {
@@ -23,6 +37,13 @@ pub async fn write_port_file(port: u16) -> std::io::Result<PathBuf> {
 }
 
 pub async fn read_port_file(app_name: &str) -> std::io::Result<u16> {
+    // prefer the registry so concurrent instances resolve to the one owning the cwd
+    let cwd = env::current_dir().ok();
+    if let Some(entry) = find_instance(app_name, cwd.as_deref()).await? {
+        tracing::debug!("Resolved port {} from instance registry", entry.port);
+        return Ok(entry.port);
+    }
+
     let dir = env::temp_dir().join(app_name);
     let path = dir.join(format!("{app_name}.port"));
     tracing::debug!("Reading port from {:?}", path);
@@ -35,3 +56,163 @@ pub async fn read_port_file(app_name: &str) -> std::io::Result<u16> {
 
     Ok(port)
 }
+
+fn registry_dir(app_name: &str) -> PathBuf {
+    env::temp_dir().join(app_name).join("instances")
+}
+
+fn registry_entry_path(app_name: &str, pid: u32) -> PathBuf {
+    registry_dir(app_name).join(format!("{pid}.json"))
+}
+
+/// Advertise this process in the instance registry (one JSON file per pid)
+pub async fn register_instance(
+    app_name: &str,
+    port: u16,
+    workspace_dir: Option<&Path>,
+) -> std::io::Result<PathBuf> {
+    let entry = InstanceEntry {
+        port,
+        pid: std::process::id(),
+        workspace_dir: workspace_dir.map(Path::to_path_buf),
+        started_at: Utc::now(),
+    };
+    let path = registry_entry_path(app_name, entry.pid);
+
+    tracing::debug!("Registering instance on port {} at {:?}", port, path);
+
+    fs::create_dir_all(registry_dir(app_name)).await?;
+    let content = serde_json::to_string_pretty(&entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    fs::write(&path, content).await?;
+    Ok(path)
+}
+
+/// Remove this process from the instance registry
+pub async fn unregister_instance(app_name: &str) -> std::io::Result<()> {
+    match fs::remove_file(registry_entry_path(app_name, std::process::id())).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// List live instances, deleting entries whose process is gone
+pub async fn list_instances(app_name: &str) -> std::io::Result<Vec<InstanceEntry>> {
+    let dir = registry_dir(app_name);
+    let mut read_dir = match fs::read_dir(&dir).await {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut instances = Vec::new();
+    while let Some(dir_entry) = read_dir.next_entry().await? {
+        let path = dir_entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let entry = fs::read_to_string(&path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str::<InstanceEntry>(&content).ok());
+
+        match entry {
+            Some(entry) if is_pid_alive(entry.pid).await => instances.push(entry),
+            _ => {
+                tracing::debug!("Removing stale instance registry entry {:?}", path);
+                let _ = fs::remove_file(&path).await;
+            }
+        }
+    }
+
+    instances.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(instances)
+}
+
+/// Pick the instance serving `cwd`: the one whose workspace dir contains it,
+/// otherwise the most recently started instance.
+pub async fn find_instance(
+    app_name: &str,
+    cwd: Option<&Path>,
+) -> std::io::Result<Option<InstanceEntry>> {
+    let instances = list_instances(app_name).await?;
+    Ok(select_instance(instances, cwd))
+}
+
+fn select_instance(instances: Vec<InstanceEntry>, cwd: Option<&Path>) -> Option<InstanceEntry> {
+    if let Some(cwd) = cwd
+        && let Some(owner) = instances.iter().find(|entry| {
+            entry
+                .workspace_dir
+                .as_deref()
+                .is_some_and(|dir| cwd.starts_with(dir))
+        })
+    {
+        return Some(owner.clone());
+    }
+
+    instances.into_iter().next()
+}
+
+/// Whether a process with this pid exists. `EPERM` means it does but
+/// belongs to another user.
+#[cfg(unix)]
+pub async fn is_pid_alive(pid: u32) -> bool {
+    use nix::{errno::Errno, sys::signal::kill, unistd::Pid};
+
+    // 0 and negative pids address process groups, not a process
+    match i32::try_from(pid) {
+        Ok(pid) if pid > 0 => !matches!(kill(Pid::from_raw(pid), None), Err(Errno::ESRCH)),
+        _ => false,
+    }
+}
+
+#[cfg(windows)]
+pub async fn is_pid_alive(pid: u32) -> bool {
+    tokio::task::spawn_blocking(move || {
+        std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+    })
+    .await
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(port: u16, workspace_dir: Option<&str>, started_at: i64) -> InstanceEntry {
+        InstanceEntry {
+            port,
+            pid: 1,
+            workspace_dir: workspace_dir.map(PathBuf::from),
+            started_at: DateTime::from_timestamp(started_at, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn prefers_instance_owning_cwd() {
+        let instances = vec![
+            entry(4000, Some("/tmp/a"), 200),
+            entry(5000, Some("/tmp/b"), 100),
+        ];
+        let selected = select_instance(instances, Some(Path::new("/tmp/b/vk-123/repo")));
+        assert_eq!(selected.map(|e| e.port), Some(5000));
+    }
+
+    #[test]
+    fn falls_back_to_first_instance() {
+        let instances = vec![entry(4000, None, 200), entry(5000, Some("/tmp/b"), 100)];
+        let selected = select_instance(instances, Some(Path::new("/home/user")));
+        assert_eq!(selected.map(|e| e.port), Some(4000));
+    }
+
+    #[tokio::test]
+    async fn current_process_is_alive() {
+        assert!(is_pid_alive(std::process::id()).await);
+        assert!(!is_pid_alive(0).await);
+    }
+}
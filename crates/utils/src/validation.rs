@@ -0,0 +1,140 @@
+//! Field-level validation for request payloads.
+//!
+//! Payload types implement [`Validate`] next to their definition; handlers
+//! reject invalid bodies with the collected [`ValidationErrors`], which the
+//! server returns as the `error_data` of a 400 response.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const MAX_TITLE_CHARS: usize = 255;
+pub const MAX_NAME_CHARS: usize = 100;
+pub const MAX_DESCRIPTION_CHARS: usize = 50_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FieldError {
+    /// Name of the offending field, as it appears in the request body
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS, thiserror::Error)]
+#[error("{}", self.summary())]
+#[ts(export)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// One line describing every error, for the response message
+    pub fn summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+
+    /// Non-blank text of at most `max_chars` characters
+    pub fn required_text(&mut self, field: &str, value: &str, max_chars: usize) {
+        if value.trim().is_empty() {
+            self.add(field, "must not be empty");
+        } else {
+            self.max_chars(field, value, max_chars);
+        }
+    }
+
+    /// Like [`Self::required_text`] when present; absent means unchanged
+    pub fn optional_text(&mut self, field: &str, value: Option<&str>, max_chars: usize) {
+        if let Some(value) = value {
+            self.required_text(field, value, max_chars);
+        }
+    }
+
+    pub fn max_chars(&mut self, field: &str, value: &str, max_chars: usize) {
+        if value.chars().count() > max_chars {
+            self.add(field, format!("must be at most {max_chars} characters"));
+        }
+    }
+
+    /// `#rgb` or `#rrggbb`
+    pub fn hex_color(&mut self, field: &str, value: &str) {
+        if !is_hex_color(value) {
+            self.add(field, "must be a hex color like #1f6feb");
+        }
+    }
+
+    /// Merge a nested payload's errors, prefixing their fields with `prefix.`
+    pub fn nested(&mut self, prefix: &str, result: Result<(), ValidationErrors>) {
+        if let Err(nested) = result {
+            self.errors
+                .extend(nested.errors.into_iter().map(|error| FieldError {
+                    field: format!("{prefix}.{}", error.field),
+                    message: error.message,
+                }));
+        }
+    }
+
+    pub fn branch_name(&mut self, field: &str, value: &str) {
+        if !crate::git::is_valid_branch_name(value) {
+            self.add(field, "is not a valid git branch name");
+        }
+    }
+}
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+pub fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert!(is_hex_color("#fff"));
+        assert!(is_hex_color("#1F6FEB"));
+        assert!(!is_hex_color("1f6feb"));
+        assert!(!is_hex_color("#1f6fe"));
+        assert!(!is_hex_color("#ggg"));
+    }
+
+    #[test]
+    fn collects_every_field_error() {
+        let mut errors = ValidationErrors::new();
+        errors.required_text("title", "   ", MAX_TITLE_CHARS);
+        errors.max_chars("description", &"x".repeat(11), 10);
+        errors.optional_text("name", None, MAX_NAME_CHARS);
+        errors.branch_name("branch", "bad..name");
+
+        let fields: Vec<_> = errors.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["title", "description", "branch"]);
+        assert!(errors.into_result().is_err());
+    }
+}
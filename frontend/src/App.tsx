@@ -5,6 +5,7 @@ import i18n from '@/i18n';
 import { Projects } from '@/pages/Projects';
 import { ProjectTasks } from '@/pages/ProjectTasks';
 import { FullAttemptLogsPage } from '@/pages/FullAttemptLogs';
+import { PublicBoardPage } from '@/pages/PublicBoard';
 import { NormalLayout } from '@/components/layout/NormalLayout';
 import { useAuth } from '@/hooks';
 import { usePreviousPath } from '@/hooks/usePreviousPath';
@@ -108,6 +109,16 @@ function AppContent() {
               }
             />
 
+            {/* Read-only board behind a share link, reachable without an account */}
+            <Route
+              path="/share/:token"
+              element={
+                <LegacyDesignScope>
+                  <PublicBoardPage />
+                </LegacyDesignScope>
+              }
+            />
+
             <Route
               element={
                 <LegacyDesignScope>
@@ -61,6 +61,8 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       null
     );
     const [isDraft, setIsDraft] = useState(false);
+    const [includeReviewChecklist, setIncludeReviewChecklist] =
+      useState(false);
     const [autoGenerateDescription, setAutoGenerateDescription] = useState(
       config?.pr_auto_description_enabled ?? false
     );
@@ -167,6 +169,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         target_branch: prBaseBranch || null,
         draft: isDraft,
         auto_generate_description: autoGenerateDescription,
+        include_review_checklist: includeReviewChecklist,
         repo_id: repoId,
       });
 
@@ -175,6 +178,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         setPrBody('');
         setPrBaseBranch('');
         setIsDraft(false);
+        setIncludeReviewChecklist(false);
         setAutoGenerateDescription(
           config?.pr_auto_description_enabled ?? false
         );
@@ -206,6 +210,12 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           // Only show setup dialog for GitHub CLI on Mac
           if (result.error.provider === 'git_hub' && isMacEnvironment) {
             await showGhCliSetupDialog();
+          } else if (result.error.provider === 'bitbucket') {
+            // Bitbucket is reached through its API, not a CLI
+            setError(
+              'Bitbucket credentials are missing or invalid. Add an app password or access token in the settings.'
+            );
+            setGhCliHelp(null);
           } else {
             const providerName =
               result.error.provider === 'git_hub'
@@ -258,6 +268,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       prBody,
       prTitle,
       isDraft,
+      includeReviewChecklist,
       autoGenerateDescription,
       config?.pr_auto_description_enabled,
       config?.open_pr_in_browser,
@@ -384,6 +395,20 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                     {t('createPrDialog.draftLabel')}
                   </Label>
                 </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="pr-review-checklist"
+                    checked={includeReviewChecklist}
+                    onCheckedChange={setIncludeReviewChecklist}
+                    className="h-5 w-5"
+                  />
+                  <Label
+                    htmlFor="pr-review-checklist"
+                    className="cursor-pointer text-sm"
+                  >
+                    {t('createPrDialog.reviewChecklistLabel')}
+                  </Label>
+                </div>
                 <div className="flex items-center space-x-2">
                   <Checkbox
                     id="pr-open-in-browser"
@@ -292,6 +292,7 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
             task,
             executor_profile_id: value.executorProfileId!,
             repos,
+            ignore_execution_window: false,
           },
           { onSuccess: () => modal.remove() }
         );
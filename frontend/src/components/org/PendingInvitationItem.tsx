@@ -1,22 +1,27 @@
 import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
 import type { Invitation } from 'shared/types';
-import { MemberRole } from 'shared/types';
+import { InvitationStatus, MemberRole } from 'shared/types';
 import { useTranslation } from 'react-i18next';
-import { Trash2 } from 'lucide-react';
+import { RotateCw, Trash2 } from 'lucide-react';
 
 interface PendingInvitationItemProps {
   invitation: Invitation;
   onRevoke?: (invitationId: string) => void;
   isRevoking?: boolean;
+  onResend?: (invitationId: string) => void;
+  isResending?: boolean;
 }
 
 export function PendingInvitationItem({
   invitation,
   onRevoke,
   isRevoking,
+  onResend,
+  isResending,
 }: PendingInvitationItemProps) {
   const { t } = useTranslation('organization');
+  const isExpired = invitation.status === InvitationStatus.EXPIRED;
 
   const handleRevoke = () => {
     const confirmed = window.confirm(
@@ -45,17 +50,32 @@ export function PendingInvitationItem({
         >
           {t('roles.' + invitation.role.toLowerCase())}
         </Badge>
-        <Badge variant="outline">{t('invitationList.pending')}</Badge>
+        <Badge variant={isExpired ? 'destructive' : 'outline'}>
+          {isExpired
+            ? t('invitationList.expired')
+            : t('invitationList.pending')}
+        </Badge>
+      </div>
+      <div className="flex items-center gap-1">
+        <Button
+          variant="ghost"
+          size="icon"
+          onClick={() => onResend?.(invitation.id)}
+          disabled={isResending}
+          title={t('invitationList.resend')}
+        >
+          <RotateCw className="h-4 w-4" />
+        </Button>
+        <Button
+          variant="ghost"
+          size="icon"
+          onClick={handleRevoke}
+          disabled={isRevoking}
+          title="Revoke invitation"
+        >
+          <Trash2 className="h-4 w-4" />
+        </Button>
       </div>
-      <Button
-        variant="ghost"
-        size="icon"
-        onClick={handleRevoke}
-        disabled={isRevoking}
-        title="Revoke invitation"
-      >
-        <Trash2 className="h-4 w-4" />
-      </Button>
     </div>
   );
 }
@@ -16,6 +16,7 @@ import { paths } from '@/lib/paths';
 import { attemptsApi } from '@/lib/api';
 import type { SharedTaskRecord } from '@/hooks/useProjectTasks';
 import { TaskCardHeader } from './TaskCardHeader';
+import { TagList } from '@/components/Tag';
 import { useTranslation } from 'react-i18next';
 import { useAuth } from '@/hooks';
 import { CreateAttemptDialog } from '@/components/dialogs/tasks/CreateAttemptDialog';
@@ -146,7 +147,9 @@ export function TaskCard({
                   )}
                 </Button>
               )}
-              <span>{task.title}</span>
+              <span title={task.latest_summary ?? undefined}>
+                {task.title}
+              </span>
               {task.pr_number != null && task.pr_url && (
                 <a
                   href={task.pr_url}
@@ -209,6 +212,7 @@ export function TaskCard({
             </>
           }
         />
+        <TagList labels={task.labels ?? []} size="sm" />
         {!isCollapsed && task.description && (
           <p className="text-sm text-secondary-foreground break-words">
             {task.description.length > 130
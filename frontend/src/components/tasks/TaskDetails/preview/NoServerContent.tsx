@@ -78,6 +78,7 @@ export function NoServerContent({
       },
       executor_profile_id: config.executor_profile,
       repos,
+      ignore_execution_window: false,
     });
   };
 
@@ -44,6 +44,7 @@ import { VariantSelector } from '@/components/tasks/VariantSelector';
 import { useAttemptBranch } from '@/hooks/useAttemptBranch';
 import { FollowUpConflictSection } from '@/components/tasks/follow-up/FollowUpConflictSection';
 import { ClickedElementsBanner } from '@/components/tasks/ClickedElementsBanner';
+import { HandEditsBanner } from '@/components/tasks/follow-up/HandEditsBanner';
 import WYSIWYGEditor from '@/components/ui/wysiwyg';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useFollowUpSend } from '@/hooks/useFollowUpSend';
@@ -753,6 +754,8 @@ export function TaskFollowUpSection({
             {/* Clicked elements notice and actions */}
             <ClickedElementsBanner />
 
+            <HandEditsBanner workspaceId={workspaceId} />
+
             {/* Queued message indicator */}
             {isQueued && queuedMessage && (
               <div className="flex items-center gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
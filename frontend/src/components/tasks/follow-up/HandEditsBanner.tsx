@@ -0,0 +1,41 @@
+import { AlertTriangle } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { useUserSystem } from '@/components/ConfigProvider';
+import { useWorktreeState } from '@/hooks/useWorktreeState';
+
+type Props = {
+  workspaceId?: string;
+};
+
+export function HandEditsBanner({ workspaceId }: Props) {
+  const { t } = useTranslation('tasks');
+  const { config } = useUserSystem();
+  const { worktreeState } = useWorktreeState(workspaceId);
+
+  const handEdited = worktreeState?.hand_edited_files ?? [];
+  const conflicts = worktreeState?.conflicts ?? [];
+  if (handEdited.length === 0 && conflicts.length === 0) return null;
+
+  return (
+    <Alert variant={conflicts.length > 0 ? 'destructive' : 'default'}>
+      <AlertTriangle className="h-4 w-4" />
+      <AlertDescription className="space-y-1">
+        {conflicts.length > 0 && (
+          <p>
+            {t('followUp.handEditConflicts', {
+              files: conflicts.join(', '),
+            })}
+          </p>
+        )}
+        {handEdited.length > 0 && (
+          <p>
+            {config?.auto_stash_hand_edits
+              ? t('followUp.handEditsStashed')
+              : t('followUp.handEdits', { count: handEdited.length })}
+          </p>
+        )}
+      </AlertDescription>
+    </Alert>
+  );
+}
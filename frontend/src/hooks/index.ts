@@ -34,6 +34,7 @@ export { useOrganizationMutations } from './useOrganizationMutations';
 export { useVariant } from './useVariant';
 export { useRetryProcess } from './useRetryProcess';
 export { useProjects } from './useProjects';
+export { useCapabilities } from './useCapabilities';
 export { useActiveTasksAcrossProjects } from './useActiveTasksAcrossProjects';
 export type { ActiveTaskWithProject } from './useActiveTasksAcrossProjects';
 export { useCollapsedCards } from './useCollapsedCards';
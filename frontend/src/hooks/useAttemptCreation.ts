@@ -28,6 +28,7 @@ export function useAttemptCreation({
         task_id: taskId,
         executor_profile_id: profile,
         repos,
+        ignore_execution_window: false,
       }),
     onSuccess: (newAttempt: Workspace) => {
       queryClient.setQueryData(
@@ -0,0 +1,11 @@
+import { useQuery } from '@tanstack/react-query';
+import { configApi } from '@/lib/api';
+import type { Capabilities } from 'shared/types';
+
+export function useCapabilities() {
+  return useQuery<Capabilities, Error>({
+    queryKey: ['capabilities'],
+    queryFn: () => configApi.getCapabilities(),
+    staleTime: 5 * 60 * 1000,
+  });
+}
@@ -21,9 +21,11 @@ export function useOrganizationInvitations(
       }
       const invitations =
         await organizationsApi.listInvitations(organizationId);
-      // Only return pending invitations
+      // Expired invitations stay listed so they can be resent
       return invitations.filter(
-        (inv) => inv.status === InvitationStatus.PENDING
+        (inv) =>
+          inv.status === InvitationStatus.PENDING ||
+          inv.status === InvitationStatus.EXPIRED
       );
     },
     enabled: !!organizationId && !!isAdmin && !isPersonal,
@@ -17,6 +17,8 @@ interface UseOrganizationMutationsOptions {
   onInviteError?: (err: unknown) => void;
   onRevokeSuccess?: () => void;
   onRevokeError?: (err: unknown) => void;
+  onResendSuccess?: () => void;
+  onResendError?: (err: unknown) => void;
   onRemoveSuccess?: () => void;
   onRemoveError?: (err: unknown) => void;
   onRoleChangeSuccess?: () => void;
@@ -103,6 +105,26 @@ export function useOrganizationMutations(
     },
   });
 
+  const resendInvitation = useMutation({
+    mutationFn: ({
+      orgId,
+      invitationId,
+    }: {
+      orgId: string;
+      invitationId: string;
+    }) => organizationsApi.resendInvitation(orgId, invitationId),
+    onSuccess: (_data, variables) => {
+      queryClient.invalidateQueries({
+        queryKey: ['organization', 'invitations', variables.orgId],
+      });
+      options?.onResendSuccess?.();
+    },
+    onError: (err) => {
+      console.error('Failed to resend invitation:', err);
+      options?.onResendError?.(err);
+    },
+  });
+
   const removeMember = useMutation({
     mutationFn: ({ orgId, userId }: { orgId: string; userId: string }) =>
       organizationsApi.removeMember(orgId, userId),
@@ -169,6 +191,7 @@ export function useOrganizationMutations(
     createOrganization,
     createInvitation,
     revokeInvitation,
+    resendInvitation,
     removeMember,
     updateMemberRole,
     deleteOrganization,
@@ -0,0 +1,13 @@
+import { useQuery } from '@tanstack/react-query';
+import { shareLinksApi } from '@/lib/api';
+import type { PublicBoard } from 'shared/types';
+
+export function usePublicBoard(token?: string) {
+  return useQuery<PublicBoard, Error>({
+    queryKey: ['public-board', token],
+    queryFn: () => shareLinksApi.getPublicBoard(token!),
+    enabled: Boolean(token),
+    refetchInterval: 60 * 1000,
+    retry: false,
+  });
+}
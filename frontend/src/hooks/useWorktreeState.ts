@@ -0,0 +1,45 @@
+import { useCallback } from 'react';
+import { useJsonPatchWsStream } from './useJsonPatchWsStream';
+import type { WorktreeState } from 'shared/types';
+
+type WorktreeStateData = {
+  worktree_state: WorktreeState | null;
+};
+
+export interface UseWorktreeStateResult {
+  worktreeState: WorktreeState | null;
+  isDirty: boolean;
+  isConnected: boolean;
+  error: string | null;
+}
+
+/**
+ * Stream the uncommitted files of an attempt via WebSocket (JSON Patch).
+ * The server refreshes it whenever the worktree is edited by hand.
+ */
+export const useWorktreeState = (
+  attemptId: string | undefined
+): UseWorktreeStateResult => {
+  const endpoint = attemptId
+    ? `/api/task-attempts/${attemptId}/worktree-state/ws`
+    : undefined;
+
+  const initialData = useCallback(
+    (): WorktreeStateData => ({ worktree_state: null }),
+    []
+  );
+
+  const { data, isConnected, error } =
+    useJsonPatchWsStream<WorktreeStateData>(
+      endpoint,
+      !!attemptId,
+      initialData
+    );
+
+  const worktreeState = data?.worktree_state ?? null;
+  const isDirty =
+    worktreeState?.repos.some((repo) => repo.changed_files.length > 0) ??
+    false;
+
+  return { worktreeState, isDirty, isConnected, error };
+};
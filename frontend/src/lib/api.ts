@@ -1,8 +1,10 @@
 // Import all necessary types from shared types
 
 import {
+  AgentPrompt,
   ApprovalStatus,
   ApiResponse,
+  AttemptArtifact,
   Config,
   CreateFollowUpAttempt,
   EditorType,
@@ -13,15 +15,59 @@ import {
   CreateTag,
   DirectoryListResponse,
   DirectoryEntry,
+  ExecutionInterrupt,
+  ExecutionLogEvent,
+  ExecutionLogEventKind,
   ExecutionProcess,
   ExecutionProcessRepoState,
   GitBranch,
   Project,
+  ProjectExecutorDefaults,
+  ProjectBranchCleanup,
+  TaskTrace,
+  BlameTrace,
+  BlameTraceQuery,
+  EditorLinkResponse,
+  OpenPrCommentRequest,
+  DraftReleaseRequest,
+  ReleaseDraft,
+  ExecutionWindow,
+  DeferredStart,
+  ProjectBudget,
+  BudgetStatus,
+  ProjectE2eSettings,
+  UpsertProjectE2eSettings,
+  E2eTestReport,
+  ProjectPreviewSettings,
+  UpsertProjectPreviewSettings,
+  VisualComparison,
+  AgentInstructionFile,
+  ProjectAgentInstructions,
+  InstructionFileStatus,
+  SecretInfo,
+  Capabilities,
+  LifecycleHook,
+  CreateLifecycleHook,
+  UpdateLifecycleHook,
+  ProjectScript,
+  CreateProjectScript,
+  UpdateProjectScript,
+  ProjectEnvTemplate,
+  UpsertProjectEnvTemplate,
+  EnvTemplateCheck,
+  WorkspacePort,
+  WorkspaceRepoCheckout,
+  CreateWorkspaceRepoCheckout,
+  WorkspaceTargetSync,
   Repo,
+  RepoSshKey,
   RepoWithTargetBranch,
   CreateProject,
+  CreateProjectFromTemplate,
+  ProjectFromTemplate,
   CreateProjectRepo,
   UpdateRepo,
+  UpsertRepoSshKey,
   SearchMode,
   SearchResult,
   Task,
@@ -31,6 +77,42 @@ import {
   TaskWithAttemptStatus,
   UpdateProject,
   UpdateTask,
+  MoveTask,
+  TaskContextPack,
+  UpsertTaskContextPack,
+  SuggestedFile,
+  SimilarTask,
+  TaskTime,
+  TimeEntry,
+  ProjectStats,
+  TaskEstimate,
+  UpsertTaskEstimate,
+  TaskFanOut,
+  FanOutTaskRequest,
+  TaskPlanning,
+  UpsertTaskPlanning,
+  QuickAddTaskRequest,
+  QuickAddTaskResponse,
+  ProjectVelocity,
+  ProjectForecast,
+  Epic,
+  CreateEpic,
+  UpdateEpic,
+  BoardLane,
+  LaneGrouping,
+  TaskAssignee,
+  Milestone,
+  CreateMilestone,
+  UpdateMilestone,
+  MilestoneBurndown,
+  ChatChannel,
+  CreateChatChannel,
+  TaskActivity,
+  TaskAttachment,
+  VoiceNoteTask,
+  WorkspaceEnvironment,
+  EnvironmentComparison,
+  IndexStats,
   UpdateTag,
   UserSystemInfo,
   McpServerQuery,
@@ -40,8 +122,16 @@ import {
   GitOperationError,
   ApprovalResponse,
   RebaseTaskAttemptRequest,
+  RegisterArtifactRequest,
   ChangeTargetBranchRequest,
   ChangeTargetBranchResponse,
+  CompareExecutorsRequest,
+  CompareExecutorsResponse,
+  ComparisonReport,
+  PromoteWorkspaceRequest,
+  ReplayPromptRequest,
+  Transcript,
+  PromoteWorkspaceResponse,
   RenameBranchRequest,
   RenameBranchResponse,
   CheckEditorAvailabilityResponse,
@@ -61,11 +151,19 @@ import {
   CreateOrganizationResponse,
   CreateInvitationRequest,
   CreateInvitationResponse,
+  ResendInvitationRequest,
   RevokeInvitationRequest,
   UpdateMemberRoleRequest,
   CreateRemoteProjectRequest,
   LinkToExistingRequest,
+  PublishProjectRequest,
+  PublishProjectResponse,
   UpdateMemberRoleResponse,
+  MemberNotificationPreferences,
+  UpdateMemberNotificationPreferencesRequest,
+  Mention,
+  MarkMentionsReadRequest,
+  MarkMentionsReadResponse,
   Invitation,
   RemoteProject,
   ListInvitationsResponse,
@@ -88,12 +186,20 @@ import {
   Session,
   Workspace,
   StartReviewRequest,
+  HandoffSessionRequest,
+  HandoffSessionResponse,
+  SessionHandoff,
   ReviewError,
   PendingCommit,
   CommitPendingRequest,
   ShareTaskResponse,
   SharedTaskResponse,
   SharedTaskDetails,
+  ProjectShareLink,
+  CreateProjectShareLink,
+  ProjectShareRule,
+  CreateProjectShareRule,
+  PublicBoard,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -249,6 +355,90 @@ export const projectsApi = {
     return handleApiResponse<Project>(response);
   },
 
+  getVelocity: async (projectId: string): Promise<ProjectVelocity> => {
+    const response = await makeRequest(`/api/projects/${projectId}/velocity`);
+    return handleApiResponse<ProjectVelocity>(response);
+  },
+
+  getForecast: async (projectId: string): Promise<ProjectForecast> => {
+    const response = await makeRequest(`/api/projects/${projectId}/forecast`);
+    return handleApiResponse<ProjectForecast>(response);
+  },
+
+  getStats: async (
+    projectId: string,
+    since?: string
+  ): Promise<ProjectStats> => {
+    const query = since ? `?since=${encodeURIComponent(since)}` : '';
+    const response = await makeRequest(
+      `/api/stats/projects/${projectId}${query}`
+    );
+    return handleApiResponse<ProjectStats>(response);
+  },
+
+  quickAddTask: async (
+    projectId: string,
+    data: QuickAddTaskRequest
+  ): Promise<QuickAddTaskResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/quick-add`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QuickAddTaskResponse>(response);
+  },
+
+  createTaskFromVoiceNote: async (
+    projectId: string,
+    file: File
+  ): Promise<VoiceNoteTask> => {
+    const formData = new FormData();
+    formData.append('audio', file);
+
+    const response = await fetch(`/api/projects/${projectId}/tasks/voice`, {
+      method: 'POST',
+      body: formData,
+      credentials: 'include',
+    });
+
+    if (!response.ok) {
+      const errorText = await response.text();
+      throw new ApiError(
+        `Failed to create task from voice note: ${errorText}`,
+        response.status,
+        response
+      );
+    }
+
+    return handleApiResponse<VoiceNoteTask>(response);
+  },
+
+  createFromTemplate: async (
+    data: CreateProjectFromTemplate
+  ): Promise<ProjectFromTemplate> => {
+    const response = await makeRequest('/api/projects/from-template', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectFromTemplate>(response);
+  },
+
+  draftRelease: async (
+    projectId: string,
+    data: DraftReleaseRequest
+  ): Promise<ReleaseDraft> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/release-draft`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ReleaseDraft>(response);
+  },
+
   update: async (id: string, data: UpdateProject): Promise<Project> => {
     const response = await makeRequest(`/api/projects/${id}`, {
       method: 'PUT',
@@ -257,6 +447,219 @@ export const projectsApi = {
     return handleApiResponse<Project>(response);
   },
 
+  getExecutorDefaults: async (
+    projectId: string
+  ): Promise<ProjectExecutorDefaults> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/executor-defaults`
+    );
+    return handleApiResponse<ProjectExecutorDefaults>(response);
+  },
+
+  updateExecutorDefaults: async (
+    projectId: string,
+    data: ProjectExecutorDefaults
+  ): Promise<ProjectExecutorDefaults> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/executor-defaults`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectExecutorDefaults>(response);
+  },
+
+  getBranchCleanup: async (projectId: string): Promise<ProjectBranchCleanup> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/branch-cleanup`
+    );
+    return handleApiResponse<ProjectBranchCleanup>(response);
+  },
+
+  updateBranchCleanup: async (
+    projectId: string,
+    data: ProjectBranchCleanup
+  ): Promise<ProjectBranchCleanup> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/branch-cleanup`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectBranchCleanup>(response);
+  },
+
+  getExecutionWindow: async (
+    projectId: string
+  ): Promise<ExecutionWindow | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/execution-window`
+    );
+    return handleApiResponse<ExecutionWindow | null>(response);
+  },
+
+  updateExecutionWindow: async (
+    projectId: string,
+    window: ExecutionWindow | null
+  ): Promise<ExecutionWindow | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/execution-window`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ window }),
+      }
+    );
+    return handleApiResponse<ExecutionWindow | null>(response);
+  },
+
+  getBudget: async (projectId: string): Promise<ProjectBudget | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/budget`);
+    return handleApiResponse<ProjectBudget | null>(response);
+  },
+
+  updateBudget: async (
+    projectId: string,
+    budget: ProjectBudget | null
+  ): Promise<ProjectBudget | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/budget`, {
+      method: 'PUT',
+      body: JSON.stringify({ budget }),
+    });
+    return handleApiResponse<ProjectBudget | null>(response);
+  },
+
+  getBudgetStatus: async (projectId: string): Promise<BudgetStatus | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/budget/status`
+    );
+    return handleApiResponse<BudgetStatus | null>(response);
+  },
+
+  getPreviewSettings: async (
+    projectId: string
+  ): Promise<ProjectPreviewSettings | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/preview`);
+    return handleApiResponse<ProjectPreviewSettings | null>(response);
+  },
+
+  updatePreviewSettings: async (
+    projectId: string,
+    data: UpsertProjectPreviewSettings
+  ): Promise<ProjectPreviewSettings> => {
+    const response = await makeRequest(`/api/projects/${projectId}/preview`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectPreviewSettings>(response);
+  },
+
+  deletePreviewSettings: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/preview`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getE2eSettings: async (
+    projectId: string
+  ): Promise<ProjectE2eSettings | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/e2e`);
+    return handleApiResponse<ProjectE2eSettings | null>(response);
+  },
+
+  updateE2eSettings: async (
+    projectId: string,
+    data: UpsertProjectE2eSettings
+  ): Promise<ProjectE2eSettings> => {
+    const response = await makeRequest(`/api/projects/${projectId}/e2e`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectE2eSettings>(response);
+  },
+
+  deleteE2eSettings: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/e2e`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getSimilarTasks: async (
+    projectId: string,
+    title: string,
+    description?: string
+  ): Promise<SimilarTask[]> => {
+    const params = new URLSearchParams({ title });
+    if (description) params.set('description', description);
+    const response = await makeRequest(
+      `/api/projects/${projectId}/similar-tasks?${params.toString()}`
+    );
+    return handleApiResponse<SimilarTask[]>(response);
+  },
+
+  getAgentInstructions: async (
+    projectId: string
+  ): Promise<ProjectAgentInstructions | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/agent-instructions`
+    );
+    return handleApiResponse<ProjectAgentInstructions | null>(response);
+  },
+
+  updateAgentInstructions: async (
+    projectId: string,
+    instructions: ProjectAgentInstructions | null
+  ): Promise<ProjectAgentInstructions | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/agent-instructions`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ instructions }),
+      }
+    );
+    return handleApiResponse<ProjectAgentInstructions | null>(response);
+  },
+
+  getRepoAgentInstructions: async (
+    projectId: string,
+    repoId: string
+  ): Promise<InstructionFileStatus[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/agent-instructions`
+    );
+    return handleApiResponse<InstructionFileStatus[]>(response);
+  },
+
+  writeRepoAgentInstructions: async (
+    projectId: string,
+    repoId: string,
+    file: AgentInstructionFile,
+    content: string
+  ): Promise<InstructionFileStatus[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/agent-instructions`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ file, content }),
+      }
+    );
+    return handleApiResponse<InstructionFileStatus[]>(response);
+  },
+
+  applyRepoAgentInstructions: async (
+    projectId: string,
+    repoId: string
+  ): Promise<InstructionFileStatus[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/agent-instructions/apply`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<InstructionFileStatus[]>(response);
+  },
+
   getRemoteMembers: async (
     projectId: string
   ): Promise<RemoteProjectMembersResponse> => {
@@ -335,6 +738,20 @@ export const projectsApi = {
     return handleApiResponse<Project>(response);
   },
 
+  publish: async (
+    localProjectId: string,
+    data: PublishProjectRequest
+  ): Promise<PublishProjectResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${localProjectId}/publish`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PublishProjectResponse>(response);
+  },
+
   unlink: async (projectId: string): Promise<Project> => {
     const response = await makeRequest(`/api/projects/${projectId}/link`, {
       method: 'DELETE',
@@ -384,6 +801,31 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
+  trace: async (query: {
+    commit?: string;
+    prUrl?: string;
+    projectId?: string;
+  }): Promise<TaskTrace | null> => {
+    const params = new URLSearchParams();
+    if (query.commit) params.set('commit', query.commit);
+    if (query.prUrl) params.set('pr_url', query.prUrl);
+    if (query.projectId) params.set('project_id', query.projectId);
+    const response = await makeRequest(`/api/tasks/trace?${params.toString()}`);
+    return handleApiResponse<TaskTrace | null>(response);
+  },
+
+  traceBlame: async (query: BlameTraceQuery): Promise<BlameTrace | null> => {
+    const params = new URLSearchParams({
+      repo_path: query.repo_path,
+      file: query.file,
+      line: String(query.line),
+    });
+    const response = await makeRequest(
+      `/api/tasks/trace/blame?${params.toString()}`
+    );
+    return handleApiResponse<BlameTrace | null>(response);
+  },
+
   create: async (data: CreateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks`, {
       method: 'POST',
@@ -424,6 +866,24 @@ export const tasksApi = {
     return handleApiResponse<ShareTaskResponse>(response);
   },
 
+  move: async (taskId: string, data: MoveTask): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/move`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
+  mergeInto: async (taskId: string, otherTaskId: string): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/merge-into/${otherTaskId}`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
   reassign: async (
     sharedTaskId: string,
     data: { new_assignee_user_id: string | null }
@@ -456,6 +916,152 @@ export const tasksApi = {
     });
     return handleApiResponse<Task | null>(response);
   },
+
+  getContextPack: async (taskId: string): Promise<TaskContextPack | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/context-pack`);
+    return handleApiResponse<TaskContextPack | null>(response);
+  },
+
+  updateContextPack: async (
+    taskId: string,
+    data: UpsertTaskContextPack
+  ): Promise<TaskContextPack> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/context-pack`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskContextPack>(response);
+  },
+
+  deleteContextPack: async (taskId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/context-pack`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  previewContextPack: async (taskId: string): Promise<string | null> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/context-pack/preview`
+    );
+    return handleApiResponse<string | null>(response);
+  },
+
+  getSuggestedContext: async (
+    taskId: string,
+    limit?: number
+  ): Promise<SuggestedFile[]> => {
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/suggested-context${query}`
+    );
+    return handleApiResponse<SuggestedFile[]>(response);
+  },
+
+  addSuggestedContext: async (
+    taskId: string,
+    limit?: number
+  ): Promise<TaskContextPack> => {
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/context-pack/suggested${query}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<TaskContextPack>(response);
+  },
+
+  getSimilar: async (taskId: string): Promise<SimilarTask[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/similar`);
+    return handleApiResponse<SimilarTask[]>(response);
+  },
+
+  getPlanning: async (taskId: string): Promise<TaskPlanning | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/planning`);
+    return handleApiResponse<TaskPlanning | null>(response);
+  },
+
+  updatePlanning: async (
+    taskId: string,
+    data: UpsertTaskPlanning
+  ): Promise<TaskPlanning> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/planning`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskPlanning>(response);
+  },
+
+  getAttachments: async (taskId: string): Promise<TaskAttachment[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/attachments`);
+    return handleApiResponse<TaskAttachment[]>(response);
+  },
+
+  getAttachmentUrl: (taskId: string, attachmentId: string): string =>
+    `/api/tasks/${taskId}/attachments/${attachmentId}/file`,
+
+  getActivity: async (taskId: string): Promise<TaskActivity[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/activity`);
+    return handleApiResponse<TaskActivity[]>(response);
+  },
+
+  getEstimate: async (taskId: string): Promise<TaskEstimate | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/estimate`);
+    return handleApiResponse<TaskEstimate | null>(response);
+  },
+
+  updateEstimate: async (
+    taskId: string,
+    data: UpsertTaskEstimate
+  ): Promise<TaskEstimate> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/estimate`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskEstimate>(response);
+  },
+
+  deleteEstimate: async (taskId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/estimate`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getFanOut: async (taskId: string): Promise<TaskFanOut> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/fan-out`);
+    return handleApiResponse<TaskFanOut>(response);
+  },
+
+  fanOut: async (
+    taskId: string,
+    data: FanOutTaskRequest
+  ): Promise<TaskFanOut> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/fan-out`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskFanOut>(response);
+  },
+
+  getTime: async (taskId: string): Promise<TaskTime> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/time`);
+    return handleApiResponse<TaskTime>(response);
+  },
+
+  startTimer: async (taskId: string, note?: string): Promise<TimeEntry> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/time/start`, {
+      method: 'POST',
+      body: JSON.stringify({ note: note ?? null }),
+    });
+    return handleApiResponse<TimeEntry>(response);
+  },
+
+  stopTimer: async (taskId: string): Promise<TimeEntry> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/time/stop`, {
+      method: 'POST',
+    });
+    return handleApiResponse<TimeEntry>(response);
+  },
 };
 
 // Sessions API
@@ -504,6 +1110,24 @@ export const sessionsApi = {
     });
     return handleApiResponse<ExecutionProcess, ReviewError>(response);
   },
+
+  handoff: async (
+    sessionId: string,
+    data: HandoffSessionRequest
+  ): Promise<HandoffSessionResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/handoff`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<HandoffSessionResponse>(response);
+  },
+
+  getHandoffs: async (workspaceId: string): Promise<SessionHandoff[]> => {
+    const response = await makeRequest(
+      `/api/sessions/handoffs?workspace_id=${workspaceId}`
+    );
+    return handleApiResponse<SessionHandoff[]>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -515,15 +1139,90 @@ export const attemptsApi = {
     return handleApiResponse<TaskRelationships>(response);
   },
 
-  getAll: async (taskId: string): Promise<Workspace[]> => {
-    const response = await makeRequest(`/api/task-attempts?task_id=${taskId}`);
-    return handleApiResponse<Workspace[]>(response);
+  getAll: async (taskId: string): Promise<Workspace[]> => {
+    const response = await makeRequest(`/api/task-attempts?task_id=${taskId}`);
+    return handleApiResponse<Workspace[]>(response);
+  },
+
+  /** Get all workspaces across all tasks (newest first) */
+  getAllWorkspaces: async (): Promise<Workspace[]> => {
+    const response = await makeRequest('/api/task-attempts');
+    return handleApiResponse<Workspace[]>(response);
+  },
+
+  getArtifacts: async (attemptId: string): Promise<AttemptArtifact[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/artifacts`
+    );
+    return handleApiResponse<AttemptArtifact[]>(response);
+  },
+
+  registerArtifact: async (
+    attemptId: string,
+    data: RegisterArtifactRequest
+  ): Promise<AttemptArtifact> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/artifacts`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptArtifact>(response);
+  },
+
+  deleteArtifact: async (
+    attemptId: string,
+    artifactId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/artifacts/${artifactId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getArtifactUrl: (attemptId: string, artifactId: string): string =>
+    `/api/task-attempts/${attemptId}/artifacts/${artifactId}/file`,
+
+  getE2eTests: async (attemptId: string): Promise<E2eTestReport | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-tests`
+    );
+    return handleApiResponse<E2eTestReport | null>(response);
+  },
+
+  runE2eTests: async (attemptId: string): Promise<E2eTestReport | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-tests`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<E2eTestReport | null>(response);
+  },
+
+  capturePreview: async (attemptId: string): Promise<AttemptArtifact> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<AttemptArtifact>(response);
+  },
+
+  getVisualComparisons: async (
+    attemptId: string
+  ): Promise<VisualComparison[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/visual-comparisons`
+    );
+    return handleApiResponse<VisualComparison[]>(response);
   },
 
-  /** Get all workspaces across all tasks (newest first) */
-  getAllWorkspaces: async (): Promise<Workspace[]> => {
-    const response = await makeRequest('/api/task-attempts');
-    return handleApiResponse<Workspace[]>(response);
+  compareVisuals: async (attemptId: string): Promise<VisualComparison[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/visual-comparisons`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<VisualComparison[]>(response);
   },
 
   /** Get total count of workspaces */
@@ -572,6 +1271,16 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  interrupt: async (attemptId: string): Promise<ExecutionInterrupt> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/interrupt`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ExecutionInterrupt>(response);
+  },
+
   delete: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}`, {
       method: 'DELETE',
@@ -619,6 +1328,27 @@ export const attemptsApi = {
     return handleApiResponse<OpenEditorResponse>(response);
   },
 
+  getEditorLink: async (
+    attemptId: string,
+    query: {
+      filePath: string;
+      repoId?: string;
+      line?: number;
+      column?: number;
+      editorType?: string;
+    }
+  ): Promise<EditorLinkResponse> => {
+    const params = new URLSearchParams({ file_path: query.filePath });
+    if (query.repoId) params.set('repo_id', query.repoId);
+    if (query.line) params.set('line', String(query.line));
+    if (query.column) params.set('column', String(query.column));
+    if (query.editorType) params.set('editor_type', query.editorType);
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/editor-link?${params.toString()}`
+    );
+    return handleApiResponse<EditorLinkResponse>(response);
+  },
+
   getBranchStatus: async (attemptId: string): Promise<RepoBranchStatus[]> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/branch-status`
@@ -722,6 +1452,90 @@ export const attemptsApi = {
     return handleApiResponse<RenameBranchResponse>(response);
   },
 
+  compare: async (
+    data: CompareExecutorsRequest
+  ): Promise<CompareExecutorsResponse> => {
+    const response = await makeRequest('/api/task-attempts/compare', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CompareExecutorsResponse>(response);
+  },
+
+  getComparison: async (
+    comparisonGroupId: string
+  ): Promise<ComparisonReport> => {
+    const response = await makeRequest(
+      `/api/task-attempts/comparisons/${comparisonGroupId}`
+    );
+    return handleApiResponse<ComparisonReport>(response);
+  },
+
+  getDeferredStart: async (
+    attemptId: string
+  ): Promise<DeferredStart | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/deferred-start`
+    );
+    return handleApiResponse<DeferredStart | null>(response);
+  },
+
+  getPrompts: async (attemptId: string): Promise<AgentPrompt[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/prompts`
+    );
+    return handleApiResponse<AgentPrompt[]>(response);
+  },
+
+  replayPrompt: async (
+    attemptId: string,
+    data: ReplayPromptRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/prompts/replay`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  getTranscript: async (attemptId: string): Promise<Transcript> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/transcript?format=json`
+    );
+    return handleApiResponse<Transcript>(response);
+  },
+
+  getTranscriptMarkdown: async (attemptId: string): Promise<string> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/transcript?format=markdown`
+    );
+    if (!response.ok) {
+      throw new ApiError(
+        `Failed to export transcript: ${response.statusText}`,
+        response.status,
+        response
+      );
+    }
+    return response.text();
+  },
+
+  promote: async (
+    attemptId: string,
+    data: PromoteWorkspaceRequest
+  ): Promise<PromoteWorkspaceResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/promote`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PromoteWorkspaceResponse>(response);
+  },
+
   abortConflicts: async (
     attemptId: string,
     data: AbortConflictsRequest
@@ -805,6 +1619,21 @@ export const attemptsApi = {
     return handleApiResponse<PrCommentsResponse>(response);
   },
 
+  openPrComment: async (
+    attemptId: string,
+    commentId: bigint,
+    data: OpenPrCommentRequest
+  ): Promise<OpenEditorResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/comments/${commentId}/open`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<OpenEditorResponse>(response);
+  },
+
   /** Mark all coding agent turns for a workspace as seen */
   markSeen: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(
@@ -825,6 +1654,83 @@ export const attemptsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getEnvironment: async (
+    attemptId: string
+  ): Promise<WorkspaceEnvironment[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/environment`
+    );
+    return handleApiResponse<WorkspaceEnvironment[]>(response);
+  },
+
+  compareEnvironment: async (
+    attemptId: string,
+    otherAttemptId: string
+  ): Promise<EnvironmentComparison> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/environment/compare?other=${otherAttemptId}`
+    );
+    return handleApiResponse<EnvironmentComparison>(response);
+  },
+
+  getPorts: async (attemptId: string): Promise<WorkspacePort[]> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/ports`);
+    return handleApiResponse<WorkspacePort[]>(response);
+  },
+
+  rewriteEnvFiles: async (attemptId: string): Promise<string[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/env-files`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<string[]>(response);
+  },
+
+  getTargetSync: async (attemptId: string): Promise<WorkspaceTargetSync[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/target-sync`
+    );
+    return handleApiResponse<WorkspaceTargetSync[]>(response);
+  },
+
+  listCheckouts: async (
+    attemptId: string
+  ): Promise<WorkspaceRepoCheckout[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkouts`
+    );
+    return handleApiResponse<WorkspaceRepoCheckout[]>(response);
+  },
+
+  createCheckout: async (
+    attemptId: string,
+    data: CreateWorkspaceRepoCheckout
+  ): Promise<WorkspaceRepoCheckout> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkouts`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceRepoCheckout>(response);
+  },
+
+  deleteCheckout: async (
+    attemptId: string,
+    checkoutId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkouts/${checkoutId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Execution Process APIs
@@ -843,6 +1749,30 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcessRepoState[]>(response);
   },
 
+  getLogEvents: async (
+    processId: string,
+    kind?: ExecutionLogEventKind
+  ): Promise<ExecutionLogEvent[]> => {
+    const query = kind ? `?kind=${kind}` : '';
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/events${query}`
+    );
+    return handleApiResponse<ExecutionLogEvent[]>(response);
+  },
+
+  searchLogEvents: async (
+    workspaceId: string,
+    q: string,
+    kind?: ExecutionLogEventKind
+  ): Promise<ExecutionLogEvent[]> => {
+    const params = new URLSearchParams({ workspace_id: workspaceId, q });
+    if (kind) params.set('kind', kind);
+    const response = await makeRequest(
+      `/api/execution-processes/events/search?${params.toString()}`
+    );
+    return handleApiResponse<ExecutionLogEvent[]>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
@@ -909,6 +1839,43 @@ export const repoApi = {
     return handleApiResponse<GitBranch[]>(response);
   },
 
+  getSshKey: async (repoId: string): Promise<RepoSshKey | null> => {
+    const response = await makeRequest(`/api/repos/${repoId}/ssh-key`);
+    return handleApiResponse<RepoSshKey | null>(response);
+  },
+
+  setSshKey: async (
+    repoId: string,
+    data: UpsertRepoSshKey
+  ): Promise<RepoSshKey> => {
+    const response = await makeRequest(`/api/repos/${repoId}/ssh-key`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoSshKey>(response);
+  },
+
+  deleteSshKey: async (repoId: string): Promise<void> => {
+    const response = await makeRequest(`/api/repos/${repoId}/ssh-key`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  refreshEmbeddingIndex: async (repoId: string): Promise<IndexStats> => {
+    const response = await makeRequest(`/api/repos/${repoId}/embedding-index`, {
+      method: 'POST',
+    });
+    return handleApiResponse<IndexStats>(response);
+  },
+
+  deleteEmbeddingIndex: async (repoId: string): Promise<void> => {
+    const response = await makeRequest(`/api/repos/${repoId}/embedding-index`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   init: async (data: {
     parent_path: string;
     folder_name: string;
@@ -960,28 +1927,188 @@ export const configApi = {
     const response = await makeRequest('/api/info', { cache: 'no-store' });
     return handleApiResponse<UserSystemInfo>(response);
   },
-  saveConfig: async (config: Config): Promise<Config> => {
-    const response = await makeRequest('/api/config', {
-      method: 'PUT',
-      body: JSON.stringify(config),
-    });
-    return handleApiResponse<Config>(response);
+  getCapabilities: async (): Promise<Capabilities> => {
+    const response = await makeRequest('/api/capabilities', {
+      cache: 'no-store',
+    });
+    return handleApiResponse<Capabilities>(response);
+  },
+  saveConfig: async (config: Config): Promise<Config> => {
+    const response = await makeRequest('/api/config', {
+      method: 'PUT',
+      body: JSON.stringify(config),
+    });
+    return handleApiResponse<Config>(response);
+  },
+  checkEditorAvailability: async (
+    editorType: EditorType
+  ): Promise<CheckEditorAvailabilityResponse> => {
+    const response = await makeRequest(
+      `/api/editors/check-availability?editor_type=${encodeURIComponent(editorType)}`
+    );
+    return handleApiResponse<CheckEditorAvailabilityResponse>(response);
+  },
+  checkAgentAvailability: async (
+    agent: BaseCodingAgent
+  ): Promise<AvailabilityInfo> => {
+    const response = await makeRequest(
+      `/api/agents/check-availability?executor=${encodeURIComponent(agent)}`
+    );
+    return handleApiResponse<AvailabilityInfo>(response);
+  },
+  listSecrets: async (): Promise<SecretInfo[]> => {
+    const response = await makeRequest('/api/secrets');
+    return handleApiResponse<SecretInfo[]>(response);
+  },
+  setSecret: async (name: string, value: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/secrets/${encodeURIComponent(name)}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ value }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+  deleteSecret: async (name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/secrets/${encodeURIComponent(name)}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Lifecycle hooks APIs
+export const hooksApi = {
+  list: async (projectId?: string): Promise<LifecycleHook[]> => {
+    const params = new URLSearchParams();
+    if (projectId) params.set('project_id', projectId);
+    const response = await makeRequest(`/api/hooks?${params.toString()}`);
+    return handleApiResponse<LifecycleHook[]>(response);
+  },
+  create: async (data: CreateLifecycleHook): Promise<LifecycleHook> => {
+    const response = await makeRequest('/api/hooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LifecycleHook>(response);
+  },
+  update: async (
+    hookId: string,
+    data: UpdateLifecycleHook
+  ): Promise<LifecycleHook> => {
+    const response = await makeRequest(`/api/hooks/${hookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LifecycleHook>(response);
+  },
+  delete: async (hookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/hooks/${hookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project automation scripts
+export const scriptsApi = {
+  list: async (projectId: string): Promise<ProjectScript[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/scripts`);
+    return handleApiResponse<ProjectScript[]>(response);
+  },
+  create: async (
+    projectId: string,
+    data: CreateProjectScript
+  ): Promise<ProjectScript> => {
+    const response = await makeRequest(`/api/projects/${projectId}/scripts`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectScript>(response);
+  },
+  update: async (
+    projectId: string,
+    scriptId: string,
+    data: UpdateProjectScript
+  ): Promise<ProjectScript> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/scripts/${scriptId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectScript>(response);
+  },
+  delete: async (projectId: string, scriptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/scripts/${scriptId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project env file templates
+export const envTemplatesApi = {
+  list: async (projectId: string): Promise<ProjectEnvTemplate[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/env-templates`
+    );
+    return handleApiResponse<ProjectEnvTemplate[]>(response);
+  },
+  create: async (
+    projectId: string,
+    data: UpsertProjectEnvTemplate
+  ): Promise<ProjectEnvTemplate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/env-templates`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectEnvTemplate>(response);
+  },
+  check: async (
+    projectId: string,
+    content: string
+  ): Promise<EnvTemplateCheck> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/env-templates/check`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ content }),
+      }
+    );
+    return handleApiResponse<EnvTemplateCheck>(response);
   },
-  checkEditorAvailability: async (
-    editorType: EditorType
-  ): Promise<CheckEditorAvailabilityResponse> => {
+  update: async (
+    projectId: string,
+    templateId: string,
+    data: UpsertProjectEnvTemplate
+  ): Promise<ProjectEnvTemplate> => {
     const response = await makeRequest(
-      `/api/editors/check-availability?editor_type=${encodeURIComponent(editorType)}`
+      `/api/projects/${projectId}/env-templates/${templateId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
     );
-    return handleApiResponse<CheckEditorAvailabilityResponse>(response);
+    return handleApiResponse<ProjectEnvTemplate>(response);
   },
-  checkAgentAvailability: async (
-    agent: BaseCodingAgent
-  ): Promise<AvailabilityInfo> => {
+  delete: async (projectId: string, templateId: string): Promise<void> => {
     const response = await makeRequest(
-      `/api/agents/check-availability?executor=${encodeURIComponent(agent)}`
+      `/api/projects/${projectId}/env-templates/${templateId}`,
+      {
+        method: 'DELETE',
+      }
     );
-    return handleApiResponse<AvailabilityInfo>(response);
+    return handleApiResponse<void>(response);
   },
 };
 
@@ -1019,6 +2146,186 @@ export const tagsApi = {
   },
 };
 
+// Epics and board swimlanes
+export const epicsApi = {
+  list: async (projectId: string): Promise<Epic[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/epics`);
+    return handleApiResponse<Epic[]>(response);
+  },
+
+  create: async (projectId: string, data: CreateEpic): Promise<Epic> => {
+    const response = await makeRequest(`/api/projects/${projectId}/epics`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Epic>(response);
+  },
+
+  update: async (
+    projectId: string,
+    epicId: string,
+    data: UpdateEpic
+  ): Promise<Epic> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/epics/${epicId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Epic>(response);
+  },
+
+  delete: async (projectId: string, epicId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/epics/${epicId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getLanes: async (
+    projectId: string,
+    groupBy: LaneGrouping
+  ): Promise<BoardLane[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/lanes?group_by=${groupBy}`
+    );
+    return handleApiResponse<BoardLane[]>(response);
+  },
+
+  setTaskEpic: async (taskId: string, epicId: string | null): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/epic`, {
+      method: 'PUT',
+      body: JSON.stringify({ epic_id: epicId }),
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  setTaskAssignee: async (
+    taskId: string,
+    userId: string | null
+  ): Promise<TaskAssignee | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/assignee`, {
+      method: 'PUT',
+      body: JSON.stringify({ user_id: userId }),
+    });
+    return handleApiResponse<TaskAssignee | null>(response);
+  },
+};
+
+// Milestones APIs
+export const milestonesApi = {
+  list: async (projectId: string): Promise<Milestone[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/milestones`);
+    return handleApiResponse<Milestone[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateMilestone
+  ): Promise<Milestone> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Milestone>(response);
+  },
+
+  update: async (
+    projectId: string,
+    milestoneId: string,
+    data: UpdateMilestone
+  ): Promise<Milestone> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones/${milestoneId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Milestone>(response);
+  },
+
+  delete: async (projectId: string, milestoneId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones/${milestoneId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getTasks: async (
+    projectId: string,
+    milestoneId: string
+  ): Promise<string[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones/${milestoneId}/tasks`
+    );
+    return handleApiResponse<string[]>(response);
+  },
+
+  setTasks: async (
+    projectId: string,
+    milestoneId: string,
+    taskIds: string[]
+  ): Promise<string[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones/${milestoneId}/tasks`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ task_ids: taskIds }),
+      }
+    );
+    return handleApiResponse<string[]>(response);
+  },
+
+  getBurndown: async (
+    projectId: string,
+    milestoneId: string
+  ): Promise<MilestoneBurndown> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/milestones/${milestoneId}/burndown`
+    );
+    return handleApiResponse<MilestoneBurndown>(response);
+  },
+};
+
+// Chat channels APIs
+export const chatChannelsApi = {
+  list: async (projectId: string): Promise<ChatChannel[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/chat-channels`
+    );
+    return handleApiResponse<ChatChannel[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateChatChannel
+  ): Promise<ChatChannel> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/chat-channels`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ChatChannel>(response);
+  },
+
+  delete: async (projectId: string, channelId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/chat-channels/${channelId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -1305,6 +2612,30 @@ export const organizationsApi = {
     return handleApiResponse<UpdateMemberRoleResponse>(response);
   },
 
+  getNotificationPreferences: async (
+    orgId: string
+  ): Promise<MemberNotificationPreferences> => {
+    const response = await makeRequest(
+      `/api/organizations/${orgId}/notification-preferences`
+    );
+    return handleApiResponse<MemberNotificationPreferences>(response);
+  },
+
+  updateNotificationPreferences: async (
+    orgId: string,
+    data: UpdateMemberNotificationPreferencesRequest
+  ): Promise<MemberNotificationPreferences> => {
+    const response = await makeRequest(
+      `/api/organizations/${orgId}/notification-preferences`,
+      {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<MemberNotificationPreferences>(response);
+  },
+
   listInvitations: async (orgId: string): Promise<Invitation[]> => {
     const response = await makeRequest(
       `/api/organizations/${orgId}/invitations`
@@ -1329,6 +2660,22 @@ export const organizationsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resendInvitation: async (
+    orgId: string,
+    invitationId: string
+  ): Promise<CreateInvitationResponse> => {
+    const body: ResendInvitationRequest = { invitation_id: invitationId };
+    const response = await makeRequest(
+      `/api/organizations/${orgId}/invitations/resend`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(body),
+      }
+    );
+    return handleApiResponse<CreateInvitationResponse>(response);
+  },
+
   deleteOrganization: async (orgId: string): Promise<void> => {
     const response = await makeRequest(`/api/organizations/${orgId}`, {
       method: 'DELETE',
@@ -1337,6 +2684,24 @@ export const organizationsApi = {
   },
 };
 
+// Mentions API
+export const mentionsApi = {
+  listUnread: async (): Promise<Mention[]> => {
+    const response = await makeRequest('/api/mentions/unread');
+    return handleApiResponse<Mention[]>(response);
+  },
+
+  markRead: async (
+    data: MarkMentionsReadRequest = {}
+  ): Promise<MarkMentionsReadResponse> => {
+    const response = await makeRequest('/api/mentions/mark-read', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<MarkMentionsReadResponse>(response);
+  },
+};
+
 // Scratch API
 export const scratchApi = {
   create: async (
@@ -1386,7 +2751,11 @@ export const queueApi = {
    */
   queue: async (
     sessionId: string,
-    data: { message: string; executor_profile_id: ExecutorProfileId }
+    data: {
+      message: string;
+      executor_profile_id: ExecutorProfileId;
+      append?: boolean;
+    }
   ): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
       method: 'POST',
@@ -1396,7 +2765,41 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Edit a queued follow-up message without changing its place in the queue
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; executor_profile_id: ExecutorProfileId }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove one queued follow-up message
+   */
+  cancelMessage: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Cancel every queued follow-up message
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1447,3 +2850,76 @@ export const pendingCommitsApi = {
     return handleApiResponse<number>(response);
   },
 };
+
+export const shareLinksApi = {
+  list: async (projectId: string): Promise<ProjectShareLink[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-links`
+    );
+    return handleApiResponse<ProjectShareLink[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateProjectShareLink
+  ): Promise<ProjectShareLink> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-links`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectShareLink>(response);
+  },
+
+  revoke: async (projectId: string, linkId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-links/${linkId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPublicBoard: async (token: string): Promise<PublicBoard> => {
+    const response = await makeRequest(
+      `/api/public/boards/${encodeURIComponent(token)}`
+    );
+    return handleApiResponse<PublicBoard>(response);
+  },
+};
+
+export const shareRulesApi = {
+  list: async (projectId: string): Promise<ProjectShareRule[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-rules`
+    );
+    return handleApiResponse<ProjectShareRule[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateProjectShareRule
+  ): Promise<ProjectShareRule> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-rules`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectShareRule>(response);
+  },
+
+  remove: async (projectId: string, ruleId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/share-rules/${ruleId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
@@ -0,0 +1,99 @@
+import { useParams } from 'react-router-dom';
+import type { PublicBoardTask, TaskStatus } from 'shared/types';
+import { Loader } from '@/components/ui/loader';
+import { usePublicBoard } from '@/hooks/usePublicBoard';
+import { statusBoardColors, statusLabels } from '@/utils/statusLabels';
+
+const STATUS_ORDER: TaskStatus[] = [
+  'todo',
+  'inprogress',
+  'inreview',
+  'done',
+  'cancelled',
+];
+
+function PublicTaskCard({ task }: { task: PublicBoardTask }) {
+  return (
+    <div className="rounded-md border bg-background p-3 space-y-2">
+      <div className="flex items-start justify-between gap-2">
+        <h3 className="text-sm font-medium">{task.title}</h3>
+        {task.in_progress && (
+          <span className="text-xs text-muted-foreground whitespace-nowrap">
+            In progress
+          </span>
+        )}
+      </div>
+      {task.description && (
+        <p className="text-xs text-muted-foreground line-clamp-3">
+          {task.description}
+        </p>
+      )}
+      {task.labels.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {task.labels.map((label) => (
+            <span
+              key={label.name}
+              className="inline-flex items-center rounded-full px-2 py-0.5 text-xs font-medium"
+              style={{
+                backgroundColor: `${label.color}20`,
+                color: label.color,
+                border: `1px solid ${label.color}40`,
+              }}
+            >
+              {label.name}
+            </span>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+/** Read-only board behind a share link; needs no account */
+export function PublicBoardPage() {
+  const { token } = useParams<{ token: string }>();
+  const { data: board, isLoading, error } = usePublicBoard(token);
+
+  if (isLoading) {
+    return <Loader message="Loading board..." className="min-h-screen" />;
+  }
+
+  if (error || !board) {
+    return (
+      <div className="min-h-screen flex items-center justify-center text-muted-foreground">
+        This link is invalid or has expired.
+      </div>
+    );
+  }
+
+  return (
+    <div className="min-h-screen bg-background p-6 space-y-6">
+      <header>
+        <h1 className="text-xl font-semibold">{board.project_name}</h1>
+        <p className="text-sm text-muted-foreground">Read-only board</p>
+      </header>
+      <div className="grid gap-4 md:grid-cols-3 xl:grid-cols-5">
+        {STATUS_ORDER.map((status) => {
+          const tasks = board.tasks.filter((task) => task.status === status);
+          return (
+            <section key={status} className="space-y-2">
+              <h2 className="flex items-center gap-2 text-sm font-medium">
+                <span
+                  className="h-2 w-2 rounded-full"
+                  style={{
+                    backgroundColor: `hsl(var(${statusBoardColors[status]}))`,
+                  }}
+                />
+                {statusLabels[status]}
+                <span className="text-muted-foreground">{tasks.length}</span>
+              </h2>
+              {tasks.map((task, index) => (
+                <PublicTaskCard key={`${task.title}-${index}`} task={task} />
+              ))}
+            </section>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
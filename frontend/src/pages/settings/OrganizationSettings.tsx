@@ -90,6 +90,7 @@ export function OrganizationSettings() {
     removeMember,
     updateMemberRole,
     revokeInvitation,
+    resendInvitation,
     deleteOrganization,
   } = useOrganizationMutations({
     onRevokeSuccess: () => {
@@ -101,6 +102,15 @@ export function OrganizationSettings() {
         err instanceof Error ? err.message : 'Failed to revoke invitation'
       );
     },
+    onResendSuccess: () => {
+      setSuccess(t('invitationList.resent'));
+      setTimeout(() => setSuccess(null), 3000);
+    },
+    onResendError: (err) => {
+      setError(
+        err instanceof Error ? err.message : 'Failed to resend invitation'
+      );
+    },
     onRemoveSuccess: () => {
       setSuccess('Member removed successfully');
       setTimeout(() => setSuccess(null), 3000);
@@ -208,6 +218,13 @@ export function OrganizationSettings() {
     revokeInvitation.mutate({ orgId: selectedOrgId, invitationId });
   };
 
+  const handleResendInvitation = (invitationId: string) => {
+    if (!selectedOrgId) return;
+
+    setError(null);
+    resendInvitation.mutate({ orgId: selectedOrgId, invitationId });
+  };
+
   const handleRemoveMember = async (userId: string) => {
     if (!selectedOrgId) return;
 
@@ -373,6 +390,8 @@ export function OrganizationSettings() {
                     invitation={invitation}
                     onRevoke={handleRevokeInvitation}
                     isRevoking={revokeInvitation.isPending}
+                    onResend={handleResendInvitation}
+                    isResending={resendInvitation.isPending}
                   />
                 ))}
               </div>
@@ -8,7 +8,7 @@ export type Project = { id: string, organization_id: string, name: string, color
 
 export type Notification = { id: string, organization_id: string, user_id: string, notification_type: NotificationType, payload: JsonValue, issue_id: string | null, comment_id: string | null, seen: boolean, dismissed_at: string | null, created_at: string, };
 
-export type NotificationType = "IssueCommentAdded" | "IssueStatusChanged" | "IssueAssigneeChanged" | "IssueDeleted";
+export type NotificationType = "IssueCommentAdded" | "IssueStatusChanged" | "IssueAssigneeChanged" | "IssueDeleted" | "TaskAssigned" | "TaskStatusChanged" | "TaskActivityDigest" | "Mentioned";
 
 export type Workspace = { id: string, project_id: string, owner_user_id: string, issue_id: string | null, local_workspace_id: string | null, archived: boolean, files_changed: number | null, lines_added: number | null, lines_removed: number | null, created_at: string, updated_at: string, };
 
@@ -38,6 +38,12 @@ export type PullRequestStatus = "open" | "merged" | "closed";
 
 export type PullRequest = { id: string, url: string, number: number, status: PullRequestStatus, merged_at: string | null, merge_commit_sha: string | null, target_branch_name: string, issue_id: string, workspace_id: string | null, created_at: string, updated_at: string, };
 
+export type SharedTaskAttempt = { 
+/**
+ * Id of the workspace on the member's local instance
+ */
+id: string, task_id: string, project_id: string, user_id: string, branch: string, summary: string | null, pr_url: string | null, pr_number: number | null, pr_status: PullRequestStatus | null, pr_merged_at: string | null, created_at: string, updated_at: string, };
+
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
 export type User = { id: string, email: string, first_name: string | null, last_name: string | null, username: string | null, created_at: string, updated_at: string, };
@@ -211,6 +217,12 @@ export const WORKSPACES_SHAPE = defineShape<Workspace>(
   '/v1/shape/project/{project_id}/workspaces'
 );
 
+export const SHARED_TASK_ATTEMPTS_SHAPE = defineShape<SharedTaskAttempt>(
+  'shared_task_attempts',
+  ['project_id'] as const,
+  '/v1/shape/project/{project_id}/shared_task_attempts'
+);
+
 export const ISSUE_ASSIGNEES_SHAPE = defineShape<IssueAssignee>(
   'issue_assignees',
   ['project_id'] as const,
@@ -347,6 +359,15 @@ export const WORKSPACE_ENTITY: EntityDefinition<Workspace> = {
   mutations: null,
 };
 
+export const SHARED_TASK_ATTEMPT_ENTITY: EntityDefinition<SharedTaskAttempt> = {
+  name: 'SharedTaskAttempt',
+  table: 'shared_task_attempts',
+  mutationScope: null,
+  shapeScope: null,
+  shape: SHARED_TASK_ATTEMPTS_SHAPE,
+  mutations: null,
+};
+
 export const ISSUE_ASSIGNEE_ENTITY: EntityDefinition<IssueAssignee, CreateIssueAssigneeRequest, UpdateIssueAssigneeRequest> = {
   name: 'IssueAssignee',
   table: 'issue_assignees',
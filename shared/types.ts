@@ -90,6 +90,209 @@ redirect_to_attempt_on_create?: boolean | null,
  */
 git_auto_push_mode?: string | null, };
 
+export type ExecutorDefault = { executor: BaseCodingAgent, variant: string | null, 
+/**
+ * Model passed to the agent instead of the variant's own
+ */
+model: string | null, };
+
+/**
+ * What happens to an attempt's branch once its PR is merged
+ */
+export type ProjectBranchCleanup = { 
+/**
+ * Delete the branch from the remote the PR was opened from
+ */
+delete_remote_branch: boolean, 
+/**
+ * Remove the worktrees and local branch once the attempt is archived
+ */
+delete_local_branch: boolean, };
+
+export type ProjectExecutorDefaults = { 
+/**
+ * Executor for new attempts; `None` falls back to the global config
+ */
+default_executor: BaseCodingAgent | null, executors: Array<ExecutorDefault>, };
+
+/**
+ * Hours in which a project's attempts may start, in the server's local time
+ */
+export type ExecutionWindow = { 
+/**
+ * Opening time as `HH:MM`
+ */
+start: string, 
+/**
+ * Closing time as `HH:MM`; before `start` means the window runs overnight
+ */
+end: string, 
+/**
+ * ISO weekdays (1 = Monday) on which the window opens; empty means every day
+ */
+days: Array<number>, };
+
+/**
+ * An attempt waiting for its project's execution window to open
+ */
+export type DeferredAttempt = { workspace_id: string, executor_profile_id: ExecutorProfileId, created_at: string, };
+
+/**
+ * What happens when a new run is expected to go over budget
+ */
+export type BudgetEnforcement = "confirm" | "block";
+
+/**
+ * Monthly spending limit for a project's agent runs. Agents report tokens,
+ * not money, so cost is estimated from a flat per-token price.
+ */
+export type ProjectBudget = { monthly_limit_usd: number, 
+/**
+ * Price used to turn token usage into cost
+ */
+usd_per_million_tokens: number, enforcement: BudgetEnforcement, };
+
+/**
+ * Where a project stands against its budget this month
+ */
+export type BudgetStatus = { 
+/**
+ * Calendar month (UTC) as `YYYY-MM`
+ */
+month: string, limit_usd: number, spent_usd: number, remaining_usd: number, percent_used: number, 
+/**
+ * Expected cost of one more run, from the project's recent runs
+ */
+projected_run_usd: number, enforcement: BudgetEnforcement, };
+
+/**
+ * Browser test hook configured for a project
+ */
+export type ProjectE2eSettings = { project_id: string, 
+/**
+ * Shell command running the tests, such as
+ * `npx playwright test --reporter=junit`
+ */
+command: string, 
+/**
+ * JUnit XML report written by the command, relative to `working_dir`
+ */
+report_path: string, 
+/**
+ * Directory inside the workspace to run in; the only repo when unset
+ */
+working_dir: string | null, 
+/**
+ * Server under test; the attempt's dev server when unset
+ */
+url: string | null, 
+/**
+ * Block auto-PR for attempts with failing tests
+ */
+block_auto_pr: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectE2eSettings = { command: string, report_path: string | null, working_dir: string | null, url: string | null, block_auto_pr: boolean | null, };
+
+export type E2eTestStatus = "passed" | "failed" | "skipped";
+
+/**
+ * One run of a project's browser tests against an attempt
+ */
+export type E2eTestRun = { id: string, workspace_id: string, execution_process_id: string | null, url: string, passed: number, failed: number, skipped: number, auto_pr_blocked: boolean, created_at: string, };
+
+export type E2eTestResult = { id: string, run_id: string, suite: string | null, name: string, status: E2eTestStatus, duration_ms: number | null, 
+/**
+ * First lines of the failure message
+ */
+message: string | null, };
+
+/**
+ * Latest run of an attempt with its per-test results
+ */
+export type E2eTestReport = { run: E2eTestRun, results: Array<E2eTestResult>, };
+
+/**
+ * Dev server screenshot configured for a project
+ */
+export type ProjectPreviewSettings = { project_id: string, 
+/**
+ * Shell command writing a screenshot of `{url}` to `{output}`
+ */
+command: string, 
+/**
+ * Page to capture; detected from the dev server output when unset
+ */
+url: string | null, 
+/**
+ * Only tasks carrying this label are captured after their runs
+ */
+label: string, 
+/**
+ * Paths compared between the target branch and the attempt, such as
+ * `/` or `/settings`
+ */
+visual_routes: Array<string>, 
+/**
+ * Where the target branch is served, e.g. a staging deployment
+ */
+baseline_url: string | null, 
+/**
+ * Percentage of changed pixels above which a route is flagged
+ */
+diff_threshold: number, 
+/**
+ * Post the comparison on the attempt's pull requests
+ */
+comment_on_pr: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectPreviewSettings = { 
+/**
+ * Empty uses headless Chromium
+ */
+command: string, url: string | null, label: string | null, visual_routes: Array<string>, baseline_url: string | null, diff_threshold: number | null, comment_on_pr: boolean | null, };
+
+/**
+ * Pixel comparison of one route between the target branch and an attempt
+ */
+export type VisualComparison = { id: string, workspace_id: string, route: string, baseline_artifact_id: string | null, attempt_artifact_id: string | null, 
+/**
+ * Changed pixels highlighted over the attempt's screenshot
+ */
+diff_artifact_id: string | null, changed_percent: number, 
+/**
+ * `changed_percent` exceeded the project's threshold
+ */
+flagged: boolean, created_at: string, };
+
+export type AgentInstructionFile = "claude_md" | "agents_md" | "cursor_rules";
+
+/**
+ * Project-wide agent instructions, rendered into every repo of the project.
+ * `{project_name}`, `{repo_name}` and `{file_name}` are replaced per file.
+ */
+export type ProjectAgentInstructions = { content: string, 
+/**
+ * Files written in each repo and worktree
+ */
+files: Array<AgentInstructionFile>, };
+
+/**
+ * One instruction file in a repo, compared with the project's rendering
+ */
+export type InstructionFileStatus = { file: AgentInstructionFile, file_name: string, 
+/**
+ * `None` when the file does not exist
+ */
+content: string | null, 
+/**
+ * Whether the project's instructions cover this file
+ */
+managed: boolean, 
+/**
+ * The file matches the project's rendering; `false` for unmanaged files
+ */
+in_sync: boolean, };
+
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
  * Ranking score based on git history (higher = more recently/frequently edited)
@@ -108,9 +311,54 @@ export type CreateProjectRepo = { display_name: string, git_repo_path: string, }
 
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
-export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
+export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, 
+/**
+ * Directory of the repo the attempt is limited to
+ */
+path_scope: string | null, };
+
+export type RepoWithTargetBranch = { target_branch: string, 
+/**
+ * Directory of the repo the attempt is limited to
+ */
+path_scope: string | null, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * Extra checkout of one of a workspace's repos, in its own worktree next to
+ * the primary one
+ */
+export type WorkspaceRepoCheckout = { id: string, workspace_id: string, repo_id: string, alias: string, 
+/**
+ * Branch the checkout started from
+ */
+source_branch: string, 
+/**
+ * Branch created for the checkout
+ */
+branch: string, created_at: string, };
+
+export type CreateWorkspaceRepoCheckout = { repo_id: string, 
+/**
+ * Letters, digits, `-` and `_`; names the checkout's directory
+ */
+alias: string, source_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+/**
+ * How far an attempt repo was behind its target branch when last checked
+ */
+export type WorkspaceTargetSync = { workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Commits on the target branch that the attempt branch lacks
+ */
+behind: number, 
+/**
+ * The attempt fell too far behind and could not be rebased automatically
+ */
+needs_rebase: boolean, 
+/**
+ * Why the last automatic rebase did not happen or failed
+ */
+last_error: string | null, rebased_at: string | null, checked_at: string, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -118,11 +366,35 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
+export type PromptTemplate = { id: string, project_id: string | null, name: string, description: string | null, content: string, current_version: number, created_at: string, updated_at: string, };
+
+export type PromptTemplateVersion = { id: string, template_id: string, version: number, content: string, created_at: string, };
+
+export type CreatePromptTemplate = { project_id: string | null, name: string, description: string | null, content: string, };
+
+export type UpdatePromptTemplate = { description: string | null, 
+/**
+ * New content; saved as a new version when it differs from the current one
+ */
+content: string | null, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, use_ralph_wiggum: boolean, ralph_max_iterations: bigint | null, ralph_completion_promise: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, pr_number: bigint | null, pr_url: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, use_ralph_wiggum: boolean, ralph_max_iterations: bigint | null, ralph_completion_promise: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, pr_number: bigint | null, pr_url: string | null, 
+/**
+ * Summary of what the latest attempt changed
+ */
+latest_summary: string | null, labels: Array<TaskLabel>, 
+/**
+ * Items in the latest attempt's review checklist, if one was generated
+ */
+checklist_items: number | null, 
+/**
+ * Attempt promoted as canonical; follow-ups and PRs go to it
+ */
+canonical_workspace_id: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, use_ralph_wiggum: boolean, ralph_max_iterations: bigint | null, ralph_completion_promise: string | null, created_at: string, updated_at: string, };
 
 export type ActiveTaskWithProject = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, pr_number: bigint | null, pr_url: string | null, project_name: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, use_ralph_wiggum: boolean, ralph_max_iterations: bigint | null, ralph_completion_promise: string | null, created_at: string, updated_at: string, };
 
@@ -132,12 +404,32 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, use_ralph_wiggum: boolean | null, ralph_max_iterations: bigint | null, ralph_completion_promise: string | null, label_ids: Array<string> | null, };
 
+export type MoveTask = { 
+/**
+ * Destination project; it must contain every repo the task's workspaces use
+ */
+project_id: string, };
+
 export type TaskLabel = { id: string, project_id: string, name: string, color: string, created_at: string, updated_at: string, };
 
 export type CreateTaskLabel = { project_id: string, name: string, color: string, };
 
 export type UpdateTaskLabel = { name: string | null, color: string | null, };
 
+/**
+ * How a context pack reaches the coding agent
+ */
+export type ContextDelivery = "prompt" | "file";
+
+export type ContextItem = { "type": "file", path: string, } | { "type": "directory", path: string, } | { "type": "pull_request", url: string, title: string | null, } | { "type": "doc", title: string, url: string | null, content: string | null, };
+
+/**
+ * Context attached to a task and rendered for each of its attempts
+ */
+export type TaskContextPack = { task_id: string, delivery: ContextDelivery, items: Array<ContextItem>, created_at: string, updated_at: string, };
+
+export type UpsertTaskContextPack = { delivery: ContextDelivery | null, items: Array<ContextItem>, };
+
 export type DraftFollowUpData = { message: string, executor_profile_id: ExecutorProfileId, };
 
 export type DraftWorkspaceData = { message: string, project_id: string | null, repos: Array<DraftWorkspaceRepo>, selected_profile: ExecutorProfileId | null, };
@@ -182,6 +474,30 @@ export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codin
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type ExecutionLogEventKind = "message" | "tool_call" | "tool_result" | "error" | "usage";
+
+export type ExecutionLogEvent = { id: number, execution_process_id: string, 
+/**
+ * Position of the normalized entry the event comes from
+ */
+entry_index: number, kind: ExecutionLogEventKind, 
+/**
+ * `user`, `assistant`, `system` or `thinking`, for messages
+ */
+role: string | null, tool_name: string | null, 
+/**
+ * Tool call status, or the error type for errors
+ */
+status: string | null, content: string, 
+/**
+ * The tool action, or other structured detail of the entry
+ */
+data: unknown, total_tokens: number | null, model_context_window: number | null, created_at: Date, updated_at: Date, };
+
+export type ExecutionLogEventData = { entry_index: number, kind: ExecutionLogEventKind, role: string | null, tool_name: string | null, status: string | null, content: string, data: unknown, total_tokens: number | null, model_context_window: number | null, };
+
+export type ExecutionLogEventMessage = { "type": "event" } & ExecutionLogEventData | { "type": "finished" };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -212,6 +528,14 @@ export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "cop
 
 export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
 
+export type FieldError = { 
+/**
+ * Name of the offending field, as it appears in the request body
+ */
+field: string, message: string, };
+
+export type ValidationErrors = { errors: Array<FieldError>, };
+
 export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
 
 export type ProfileResponse = { user_id: string, username: string | null, email: string, providers: Array<ProviderProfile>, };
@@ -240,7 +564,11 @@ export type UpdateOrganizationRequest = { name: string, };
 
 export type Invitation = { id: string, organization_id: string, invited_by_user_id: string | null, email: string, role: MemberRole, status: InvitationStatus, token: string, created_at: string, expires_at: string, };
 
-export type CreateInvitationRequest = { email: string, role: MemberRole, };
+export type CreateInvitationRequest = { email: string, role: MemberRole, 
+/**
+ * Days until the invitation link expires; the server default when omitted
+ */
+expires_in_days?: number, };
 
 export type CreateInvitationResponse = { invitation: Invitation, };
 
@@ -252,6 +580,12 @@ export type AcceptInvitationResponse = { organization_id: string, organization_s
 
 export type RevokeInvitationRequest = { invitation_id: string, };
 
+export type ResendInvitationRequest = { invitation_id: string, 
+/**
+ * Days until the new invitation link expires; the server default when omitted
+ */
+expires_in_days?: number, };
+
 export type OrganizationMember = { user_id: string, role: MemberRole, joined_at: string, };
 
 export type OrganizationMemberWithProfile = { user_id: string, role: MemberRole, joined_at: string, first_name: string | null, last_name: string | null, username: string | null, email: string | null, avatar_url: string | null, };
@@ -262,6 +596,70 @@ export type UpdateMemberRoleRequest = { role: MemberRole, };
 
 export type UpdateMemberRoleResponse = { user_id: string, role: MemberRole, };
 
+/**
+ * How often routine shared task activity is rolled up for a member
+ */
+export type NotificationDigest = "off" | "daily" | "weekly";
+
+/**
+ * Which shared task events a member of an organization is notified about
+ */
+export type MemberNotificationPreferences = { 
+/**
+ * A task is assigned to the member
+ */
+notify_on_assignment: boolean, 
+/**
+ * The member is mentioned in a task or comment
+ */
+notify_on_mention: boolean, 
+/**
+ * A task the member created or is assigned changes status or agent state
+ */
+notify_on_status_change: boolean, 
+/**
+ * Rolls status changes up into one notification per period
+ */
+digest: NotificationDigest, };
+
+export type UpdateMemberNotificationPreferencesRequest = { notify_on_assignment?: boolean, notify_on_mention?: boolean, notify_on_status_change?: boolean, digest?: NotificationDigest, };
+
+export type SharedConfigBaseline = { 
+/**
+ * Executor profiles in `profiles.json` format
+ */
+executor_profiles: JsonValue | null, 
+/**
+ * Prompt library templates by name
+ */
+prompts: { [key in string]?: string }, 
+/**
+ * MCP servers by executor (e.g. `CLAUDE_CODE`), then by server name
+ */
+mcp_servers: { [key in string]?: { [key in string]?: JsonValue } }, 
+/**
+ * Sensitive file patterns added to each member's list
+ */
+sensitive_file_patterns: Array<string>, };
+
+export type OrganizationSharedConfig = { organization_id: string, config: SharedConfigBaseline, 
+/**
+ * Incremented on every update
+ */
+version: bigint, updated_by: string | null, updated_at: string, };
+
+export type UpdateSharedConfigRequest = { config: SharedConfigBaseline, };
+
+export type PullSharedConfigResponse = { 
+/**
+ * `None` when the organization has not published a shared config
+ */
+config: OrganizationSharedConfig | null, 
+/**
+ * Executors that received new MCP servers from the shared config
+ */
+mcp_updated: Array<BaseCodingAgent>, };
+
 export type RemoteProject = { id: string, organization_id: string, name: string, color: string, created_at: string, updated_at: string, };
 
 export type ListProjectsResponse = { projects: Array<RemoteProject>, };
@@ -272,264 +670,1608 @@ export type CreateRemoteProjectRequest = { organization_id: string, name: string
 
 export type LinkToExistingRequest = { remote_project_id: string, };
 
-export type RegisterRepoRequest = { path: string, display_name: string | null, };
+export type PublishProjectRequest = { organization_id: string, 
+/**
+ * Name of the remote project; defaults to the local project's name
+ */
+name: string | null, };
 
-export type InitRepoRequest = { parent_path: string, folder_name: string, };
+export type PublishProjectResponse = { project: Project, tags_published: number, tasks_published: number, };
 
-export type TagSearchParams = { search: string | null, };
+export type ProjectReportQuery = { 
+/**
+ * Start of the period; defaults to 24 hours ago
+ */
+since: string | null, };
 
-export type TokenResponse = { access_token: string, expires_at: string | null, };
+export type SimilarTasksQuery = { title: string, description: string | null, limit: number | null, };
 
-export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+export type ProjectReport = { activity: ProjectActivity, 
 /**
- * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ * Ready-to-paste Markdown version of `activity`
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+markdown: string, };
 
-export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+export type DependencyAllowlist = { 
+/**
+ * Dependency names agents may add; a trailing `*` matches a prefix.
+ * `None` turns the check off.
+ */
+allowlist: Array<string> | null, };
 
-export type McpServerQuery = { executor: BaseCodingAgent, };
+export type DiffIgnorePatterns = { 
+/**
+ * `.gitignore`-style patterns left out of attempt diffs and agent context
+ */
+patterns: Array<string>, };
 
-export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+export type SensitivePathTestRequest = { 
+/**
+ * Repo-relative path to check
+ */
+path: string, };
 
-export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, 
+export type SensitivePathTestResult = { sensitive: boolean, 
 /**
- * MCP servers from Claude Code's configuration (user and project level)
- * These are read-only and managed by Claude Code TUI
+ * Pattern that matched, if any
  */
-claude_code_servers: { [key in string]?: McpServerWithSource }, };
+pattern: string | null, 
+/**
+ * Global patterns with the project's changes applied
+ */
+effective_patterns: Array<string>, };
 
-export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+export type ProjectSensitivePatterns = { added: Array<string>, 
+/**
+ * Global patterns that do not apply to this project
+ */
+removed: Array<string>, };
 
-export type CheckEditorAvailabilityResponse = { available: boolean, };
+export type ReportTask = { id: string, title: string, summary: string | null, updated_at: string, };
 
-export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+export type ReportPullRequest = { task_id: string, task_title: string, pr_number: number, pr_url: string, pr_status: string, created_at: string, pr_merged_at: string | null, };
+
+export type ReportFailedAttempt = { task_id: string, task_title: string, workspace_id: string, branch: string, executor: string | null, failed_runs: number, last_failed_at: string, };
+
+export type ProjectActivity = { since: string, completed_tasks: Array<ReportTask>, prs_opened: Array<ReportPullRequest>, prs_merged: Array<ReportPullRequest>, failed_attempts: Array<ReportFailedAttempt>, 
+/**
+ * Tasks currently waiting in review, regardless of `since`
+ */
+pending_reviews: Array<ReportTask>, };
+
+/**
+ * Task whose attempt was merged into a repo, listed in release notes
+ */
+export type ReleaseTask = { task_id: string, task_title: string, pr_number: number | null, pr_url: string | null, merged_at: string, 
+/**
+ * Names of the task's labels
+ */
+labels: Array<string>, };
+
+export type DraftReleaseRequest = { repo_id: string, 
+/**
+ * Tag of the new release; required to create the draft release
+ */
+tag: string | null, 
+/**
+ * Also create a GitHub draft release with the generated notes
+ */
+create_release: boolean, };
+
+export type ReleaseDraft = { 
+/**
+ * Latest tag reachable from the repo's HEAD; only tasks merged after it
+ * are listed
+ */
+previous_tag: string | null, since: string | null, tasks: Array<ReleaseTask>, 
+/**
+ * Markdown release notes, grouped by label
+ */
+body: string, 
+/**
+ * URL of the draft release, when one was created
+ */
+release_url: string | null, };
+
+export type RegisterRepoRequest = { path: string, display_name: string | null, };
+
+export type InitRepoRequest = { parent_path: string, folder_name: string, };
+
+export type TagSearchParams = { search: string | null, };
+
+export type PromptTemplateQuery = { project_id: string | null, };
+
+export type PreviewPromptRequest = { 
+/**
+ * Unsaved content to preview; takes precedence over `template_id`
+ */
+content: string | null, template_id: string | null, variables: { [key in string]?: string }, };
+
+export type RenderedPrompt = { rendered: string, 
+/**
+ * Placeholders found in the template, in order of first use
+ */
+placeholders: Array<string>, 
+/**
+ * Placeholders without a value, left as-is in the output
+ */
+missing: Array<string>, };
+
+export type TokenResponse = { access_token: string, expires_at: string | null, };
+
+export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+/**
+ * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ */
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+
+export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+
+export type McpServerQuery = { executor: BaseCodingAgent, };
+
+export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+
+export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, 
+/**
+ * MCP servers from Claude Code's configuration (user and project level)
+ * These are read-only and managed by Claude Code TUI
+ */
+claude_code_servers: { [key in string]?: McpServerWithSource }, };
+
+export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+
+export type CheckEditorAvailabilityResponse = { available: boolean, };
+
+export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+
+export type CurrentUserResponse = { user_id: string, };
+
+export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Start even if the run is expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+
+export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+
+export type MergeTaskAttemptRequest = { repo_id: string, };
+
+export type PushTaskAttemptRequest = { repo_id: string, };
+
+export type RenameBranchRequest = { new_branch_name: string, };
+
+export type PromoteWorkspaceRequest = { 
+/**
+ * Also delete the branches of the archived attempts, after removing
+ * their worktrees
+ */
+delete_branches: boolean, };
+
+export type PromoteWorkspaceResponse = { workspace: Workspace, archived_workspace_ids: Array<string>, };
+
+export type DiffDigestQuery = { 
+/**
+ * Character budget for the digest and for each chunk
+ */
+budget: number | null, 
+/**
+ * Also split every hunk into budget-sized chunks for map-reduce summaries
+ */
+chunked: boolean, };
+
+export type DiffDigestResponse = { digest: DiffDigest, chunks: Array<string> | null, };
+
+export type EnvironmentCompareQuery = { 
+/**
+ * Attempt to compare against
+ */
+other: string, };
+
+export type EnvironmentComparison = { left: WorkspaceEnvironment, right: WorkspaceEnvironment, differences: Array<EnvironmentDifference>, };
+
+export type RenameBranchResponse = { branch: string, };
+
+export type RenameBranchError = { "type": "empty_branch_name" } | { "type": "invalid_branch_name_format" } | { "type": "open_pull_request" } | { "type": "branch_already_exists", repo_name: string, } | { "type": "rebase_in_progress", repo_name: string, } | { "type": "rename_failed", repo_name: string, message: string, };
+
+export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+
+export type ReviewError = { "type": "process_already_running" };
+
+export type HandoffSessionRequest = { executor_profile_id: ExecutorProfileId, 
+/**
+ * Replaces the default request to carry on where the previous agent
+ * stopped
+ */
+instructions: string | null, confirm_over_budget?: boolean, };
+
+export type HandoffSessionResponse = { session: Session, execution_process: ExecutionProcess, handoff: SessionHandoff, };
+
+/**
+ * A session of an attempt continued by another executor in a new session
+ */
+export type SessionHandoff = { id: string, workspace_id: string, from_session_id: string, to_session_id: string, from_executor: string | null, to_executor: string, created_at: string, };
+
+export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+
+export type OpenEditorResponse = { url: string | null, };
+
+export type EditorLinkQuery = { 
+/**
+ * Repo the file belongs to; may be left out for single-repo attempts
+ */
+repo_id: string | null, 
+/**
+ * Relative to the repo root, e.g. the path of a PR review comment
+ */
+file_path: string, 
+/**
+ * 1-based
+ */
+line: number | null, 
+/**
+ * 1-based
+ */
+column: number | null, editor_type: string | null, };
+
+export type EditorLinkResponse = { 
+/**
+ * Deep link into the editor, or `null` when it has no URL scheme
+ */
+url: string | null, 
+/**
+ * Absolute path of the file in the attempt's worktree
+ */
+path: string, };
+
+export type CreateAndStartTaskRequest = { task: CreateTask, 
+/**
+ * Omit to use the project's default executor, then the global one
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Start now even if the project's execution window is closed
+ */
+ignore_execution_window: boolean, 
+/**
+ * Start even if the run is expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+export type FanOutTaskRequest = { 
+/**
+ * One child task per target: a repo, optionally limited to a package
+ * directory through its path scope
+ */
+targets: Array<WorkspaceRepoInput>, 
+/**
+ * Prompt of each child, with `{title}`, `{description}`, `{repo}`,
+ * `{path}` and `{target}` placeholders; a default template when omitted
+ */
+prompt_template?: string, 
+/**
+ * Omit to use the project's default executor, then the global one
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Start now even if the project's execution window is closed
+ */
+ignore_execution_window: boolean, 
+/**
+ * Start even if the runs are expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+export type UpdateExecutionWindowRequest = { 
+/**
+ * `null` lets attempts start at any time
+ */
+window: ExecutionWindow | null, };
+
+export type UpdateProjectBudgetRequest = { 
+/**
+ * `null` removes the limit
+ */
+budget: ProjectBudget | null, };
+
+export type UpdateAgentInstructionsRequest = { 
+/**
+ * `null` stops managing the repos' instruction files
+ */
+instructions: ProjectAgentInstructions | null, };
+
+export type WriteInstructionFileRequest = { file: AgentInstructionFile, content: string, };
+
+export type ShareTaskResponse = { shared_task_id: string, };
+
+export type EnrichTaskQuery = { 
+/**
+ * Return the brief without saving it to the description
+ */
+preview: boolean, };
+
+export type EnrichTaskResponse = { task: Task, brief: TaskBrief, };
+
+export type RelevantFile = { 
+/**
+ * Path prefixed with the repo name
+ */
+path: string, 
+/**
+ * Keywords matching the file's path
+ */
+path_matches: Array<string>, 
+/**
+ * Keywords found in the file's contents
+ */
+content_matches: Array<string>, 
+/**
+ * First line mentioning a keyword, if any
+ */
+excerpt: string | null, 
+/**
+ * Similarity to the task in the embedding index, when it suggested the file
+ */
+similarity: number | null, };
+
+export type TaskBrief = { keywords: Array<string>, files: Array<RelevantFile>, 
+/**
+ * Markdown section appended to the description
+ */
+brief: string, };
+
+export type SuggestedContextQuery = { limit: number | null, };
+
+export type IndexStats = { 
+/**
+ * Files embedded because they were new or changed
+ */
+embedded: number, unchanged: number, 
+/**
+ * Index entries of files that no longer exist
+ */
+removed: number, };
+
+export type SuggestedFile = { 
+/**
+ * Path as used in context packs: prefixed with the repo name when the
+ * project has several repos
+ */
+path: string, repo_id: string, 
+/**
+ * Path relative to the repo root
+ */
+relative_path: string, 
+/**
+ * Cosine similarity to the task, higher is closer
+ */
+score: number, };
+
+export type SimilarTasksLimitQuery = { limit: number | null, };
+
+export type SimilarTask = { task_id: string, title: string, 
+/**
+ * What the task's attempts changed
+ */
+diff_summary: string | null, pr_url: string | null, completed_at: string, 
+/**
+ * Cosine similarity, higher is closer
+ */
+score: number, };
+
+export type TaskTraceQuery = { 
+/**
+ * Full or abbreviated commit SHA
+ */
+commit: string | null, pr_url: string | null, 
+/**
+ * Only search the history of this project's repos for the task trailer
+ */
+project_id: string | null, };
+
+export type TraceSource = "merge" | "pull_request" | "agent_commit" | "commit_trailer";
+
+export type RepoWorktreeState = { repo_id: string, repo_name: string, 
+/**
+ * Uncommitted paths, relative to the repo root
+ */
+changed_files: Array<string>, };
+
+export type WorktreeState = { workspace_id: string, repos: Array<RepoWorktreeState>, 
+/**
+ * Last change made while none of the attempt's processes was running
+ */
+manual_edit_at: string | null, 
+/**
+ * Uncommitted files changed by hand, as `repo/path`
+ */
+hand_edited_files: Array<string>, 
+/**
+ * Hand-edited files written to again while the agent was running
+ */
+conflicts: Array<string>, };
+
+export type TaskTrace = { task: Task, 
+/**
+ * Attempt the commit or PR belongs to, when known
+ */
+workspace_id: string | null, source: TraceSource, };
+
+export type BlameTraceQuery = { 
+/**
+ * Registered repo or one of its worktrees
+ */
+repo_path: string, 
+/**
+ * Relative to `repo_path`, or absolute
+ */
+file: string, 
+/**
+ * 1-based line number
+ */
+line: number, };
+
+export type BlameTrace = { 
+/**
+ * Commit that last touched the line
+ */
+commit: string, author: string | null, authored_at: string | null, 
+/**
+ * First line of the commit message
+ */
+summary: string | null, 
+/**
+ * Task the commit came from, if it can be traced
+ */
+trace: TaskTrace | null, 
+/**
+ * API path of the originating attempt's transcript
+ */
+transcript_url: string | null, };
+
+export type TimeEntrySource = "manual" | "agent";
+
+export type TimeEntry = { id: string, task_id: string, 
+/**
+ * Who ran a manual timer; `None` for agent runs and in single-user mode
+ */
+user_id: string | null, execution_process_id: string | null, source: TimeEntrySource, note: string | null, started_at: string, 
+/**
+ * `None` while the timer is running
+ */
+ended_at: string | null, created_at: string, };
+
+/**
+ * Tracked seconds split by source; running timers count up to now
+ */
+export type TimeTotals = { manual_seconds: number, agent_seconds: number, };
+
+export type TaskTimeTotals = { task_id: string, task_title: string, manual_seconds: number, agent_seconds: number, };
+
+export type UserTimeTotals = { 
+/**
+ * `None` groups agent runs and timers tracked in single-user mode
+ */
+user_id: string | null, manual_seconds: number, agent_seconds: number, };
+
+/**
+ * Time tracked on one task
+ */
+export type TaskTime = { totals: TimeTotals, entries: Array<TimeEntry>, };
+
+/**
+ * Time tracked on a project's tasks since a point in time
+ */
+export type ProjectTime = { since: string, totals: TimeTotals, tasks: Array<TaskTimeTotals>, users: Array<UserTimeTotals>, };
+
+export type StartTimerRequest = { note: string | null, };
+
+/**
+ * Per-project counters over the same period as the instance stats
+ */
+export type ProjectStats = { time: ProjectTime, };
+
+/**
+ * Size of a task in story points, hours, or both
+ */
+export type TaskEstimate = { task_id: string, points: number | null, hours: number | null, created_at: string, updated_at: string, };
+
+export type UpsertTaskEstimate = { points: number | null, hours: number | null, };
+
+/**
+ * Child task created for one target of a fanned-out task, with the newest
+ * pull request opened from its attempts
+ */
+export type TaskFanOutChild = { task_id: string, title: string, status: TaskStatus, repo_id: string, repo_name: string, 
+/**
+ * Package directory the child is limited to, if any
+ */
+path_scope: string | null, pr_number: number | null, pr_url: string | null, pr_status: MergeStatus | null, created_at: string, };
+
+/**
+ * Children of a fanned-out task with their pull requests rolled up
+ */
+export type TaskFanOut = { parent_task_id: string, children: Array<TaskFanOutChild>, open_prs: number, merged_prs: number, closed_prs: number, 
+/**
+ * Children without a pull request yet
+ */
+pending: number, };
+
+/**
+ * Work completed in one window of a velocity series
+ */
+export type VelocityWindow = { start: string, end: string, completed_tasks: number, 
+/**
+ * Estimates of the completed tasks; unestimated tasks count as zero
+ */
+completed_points: number, completed_hours: number, 
+/**
+ * Time coding agents spent running in the window
+ */
+agent_seconds: number, };
+
+/**
+ * Completed work per rolling window, oldest window first
+ */
+export type ProjectVelocity = { window_days: number, windows: Array<VelocityWindow>, avg_tasks: number, avg_points: number, avg_hours: number, avg_agent_hours: number, };
+
+/**
+ * Open tasks of a project and their estimates
+ */
+export type RemainingWork = { tasks: number, unestimated_tasks: number, points: number, hours: number, };
+
+/**
+ * When the open tasks are expected to be done at the current pace; each date
+ * is `None` when there is nothing to measure the pace by
+ */
+export type ProjectForecast = { velocity: ProjectVelocity, remaining: RemainingWork, 
+/**
+ * From the points completed per window
+ */
+by_points: string | null, 
+/**
+ * From the number of tasks completed per window
+ */
+by_tasks: string | null, 
+/**
+ * From the hour estimates left and the hours agents run per window
+ */
+by_agent_hours: string | null, };
+
+export type VelocityQuery = { 
+/**
+ * Length of a window in days; defaults to 7
+ */
+window_days: number | null, 
+/**
+ * Number of windows ending now; defaults to 4
+ */
+windows: number | null, };
+
+/**
+ * Lightweight parent that groups a project's tasks into a larger initiative
+ */
+export type Epic = { id: string, project_id: string, title: string, description: string | null, 
+/**
+ * Hex color of the epic's swimlane
+ */
+color: string | null, 
+/**
+ * Order of the epic's swimlane on the board
+ */
+position: number, created_at: string, updated_at: string, };
+
+export type CreateEpic = { title: string, description: string | null, color: string | null, };
+
+export type UpdateEpic = { title: string | null, description: string | null, color: string | null, position: number | null, };
+
+/**
+ * Local user a task is assigned to
+ */
+export type TaskAssignee = { task_id: string, user_id: string, assigned_at: string, };
+
+export type LaneGrouping = "epic" | "assignee" | "label";
+
+/**
+ * One swimlane and its tasks, in board order
+ */
+export type BoardLane = { 
+/**
+ * Epic, user or label id; `None` for the lane of tasks without one
+ */
+id: string | null, 
+/**
+ * Epic title or label name
+ */
+name: string | null, color: string | null, tasks: Array<TaskWithAttemptStatus>, };
+
+export type LanesQuery = { group_by: LaneGrouping, };
+
+export type SetTaskEpicRequest = { 
+/**
+ * `null` takes the task out of its epic
+ */
+epic_id: string | null, };
+
+export type SetTaskAssigneeRequest = { 
+/**
+ * `null` unassigns the task
+ */
+user_id: string | null, };
+
+export type Milestone = { id: string, project_id: string, name: string, description: string | null, 
+/**
+ * First day of the burndown; the creation day when unset
+ */
+start_date: string | null, due_date: string | null, created_at: string, updated_at: string, };
+
+export type CreateMilestone = { name: string, description: string | null, start_date: string | null, due_date: string | null, };
+
+export type UpdateMilestone = { name: string | null, description: string | null, start_date: string | null, due_date: string | null, };
+
+/**
+ * Work left in a milestone at the end of one day (UTC)
+ */
+export type BurndownPoint = { date: string, 
+/**
+ * Member tasks that existed and were not done or cancelled
+ */
+remaining_tasks: number, 
+/**
+ * Estimated points of the remaining tasks
+ */
+remaining_points: number, };
+
+export type MilestoneBurndown = { milestone: Milestone, total_tasks: number, total_points: number, 
+/**
+ * One point per day from the start date through today
+ */
+series: Array<BurndownPoint>, };
+
+export type SetMilestoneTasksRequest = { task_ids: Array<string>, };
+
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
+
+/**
+ * Scheduling details of a task, kept beside it on the board
+ */
+export type TaskPlanning = { task_id: string, priority: TaskPriority | null, due_date: string | null, 
+/**
+ * Coding agent to start the task with; the project default when unset
+ */
+executor: BaseCodingAgent | null, created_at: string, updated_at: string, };
+
+export type UpsertTaskPlanning = { priority: TaskPriority | null, due_date: string | null, executor: BaseCodingAgent | null, };
+
+/**
+ * Task fields parsed from a line of quick-add text
+ */
+export type QuickAddParse = { title: string, 
+/**
+ * Label names, without the `#`
+ */
+labels: Array<string>, priority: TaskPriority | null, executor: BaseCodingAgent | null, due_date: string | null, };
+
+export type QuickAddTaskRequest = { 
+/**
+ * One line such as `Fix login bug #bug !high @claude-code ^friday`
+ */
+text: string, 
+/**
+ * The client's local date, for relative due dates; the server's when
+ * omitted
+ */
+today?: string, };
+
+export type QuickAddTaskResponse = { task: Task, parsed: QuickAddParse, planning: TaskPlanning | null, };
+
+export type ChatPlatform = "slack" | "telegram";
+
+/**
+ * A chat channel whose messages create tasks in a project
+ */
+export type ChatChannel = { id: string, project_id: string, platform: ChatPlatform, 
+/**
+ * Slack channel id (`C0123…`) or Telegram chat id
+ */
+channel_id: string, 
+/**
+ * Start an attempt with the project's repos as soon as the task is created
+ */
+auto_start: boolean, created_at: string, };
+
+export type CreateChatChannel = { platform: ChatPlatform, channel_id: string, auto_start: boolean, };
+
+/**
+ * A file kept with a task
+ */
+export type TaskAttachment = { id: string, task_id: string, original_name: string, mime_type: string | null, size_bytes: number, created_at: string, };
+
+export type AttemptArtifact = { id: string, workspace_id: string, 
+/**
+ * The run that produced the file, if it was collected from one
+ */
+execution_process_id: string | null, 
+/**
+ * Path inside the attempt's artifacts directory, e.g. `coverage/index.html`
+ */
+name: string, size_bytes: number, created_at: string, };
+
+export type RegisterArtifactRequest = { 
+/**
+ * File to keep, relative to the workspace root
+ */
+path: string, 
+/**
+ * Name inside the artifacts directory; defaults to `path`
+ */
+name: string | null, execution_process_id: string | null, };
+
+export type TaskActivityKind = "sync_conflict";
+
+export type TaskActivity = { id: string, task_id: string, kind: TaskActivityKind, 
+/**
+ * Task field the event concerns
+ */
+field: string | null, local_value: string | null, remote_value: string | null, 
+/**
+ * Value the task was left with
+ */
+resolved_value: string | null, created_at: string, };
+
+export type VoiceNoteTask = { task: Task, attachment: TaskAttachment, };
+
+export type ChecklistCategory = "risky_area" | "missing_tests" | "public_api" | "large_change";
+
+export type ChecklistItem = { category: ChecklistCategory, path: string | null, text: string, };
+
+export type ReviewChecklist = { items: Array<ChecklistItem>, 
+/**
+ * Markdown task list, ready to paste into a PR body
+ */
+markdown: string, };
+
+export type Ecosystem = "cargo" | "npm" | "python" | "go";
+
+export type DependencyChangeKind = "added" | "removed" | "upgraded" | "downgraded" | "changed";
+
+export type DependencyChange = { repo_id: string | null, ecosystem: Ecosystem, name: string, kind: DependencyChangeKind, old_version: string | null, new_version: string | null, 
+/**
+ * Manifest or lockfile the change was read from
+ */
+file: string, 
+/**
+ * Declared in a manifest rather than only pulled in through a lockfile
+ */
+direct: boolean, 
+/**
+ * Added direct dependency that is not on the project allowlist
+ */
+unapproved: boolean, };
+
+export type DependencyChanges = { changes: Array<DependencyChange>, 
+/**
+ * Dependency files whose content was too large to compare
+ */
+skipped_files: Array<string>, 
+/**
+ * Markdown summary, empty when nothing changed
+ */
+markdown: string, };
+
+export type FileStat = { path: string, change: DiffChangeKind, additions: number, deletions: number, };
+
+export type DiffDigest = { files: Array<FileStat>, 
+/**
+ * Stats header followed by the selected hunks, within the budget
+ */
+text: string, included_hunks: number, total_hunks: number, };
+
+export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, 
+/**
+ * Append the attempt's stored review checklist to the body
+ */
+include_review_checklist: boolean, };
+
+export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+
+export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Start now even if the project's execution window is closed
+ */
+ignore_execution_window: boolean, 
+/**
+ * Start even if the run is expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+export type WorkspaceRepoInput = { repo_id: string, target_branch: string, 
+/**
+ * Directory of a monorepo to limit the attempt to, e.g. `packages/web`
+ */
+path_scope?: string | null, 
+/**
+ * Extra checkouts of the repo at other branches, next to the primary one
+ */
+checkouts?: Array<RepoCheckoutInput> | null, };
+
+export type RepoCheckoutInput = { alias: string, source_branch: string, };
+
+export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+
+export type RunAgentSetupResponse = Record<string, never>;
+
+export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+
+export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+
+export type AbortConflictsRequest = { repo_id: string, };
+
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
+
+export type PushError = { "type": "force_push_required" };
+
+export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
+
+export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+
+export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+
+export type AttachExistingPrRequest = { repo_id: string, };
+
+export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+
+export type GetPrCommentsQuery = { repo_id: string, };
+
+export type OpenPrCommentRequest = { repo_id: string, editor_type: string | null, };
+
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "bitbucket" | "unknown";
+
+export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
+
+export type WorkspaceSummaryRequest = { archived: boolean, };
+
+export type WorkspaceSummary = { workspace_id: string, 
+/**
+ * Session ID of the latest execution process
+ */
+latest_session_id: string | null, 
+/**
+ * Is a tool approval currently pending?
+ */
+has_pending_approval: boolean, 
+/**
+ * Number of files with changes
+ */
+files_changed: number | null, 
+/**
+ * Total lines added across all files
+ */
+lines_added: number | null, 
+/**
+ * Total lines removed across all files
+ */
+lines_removed: number | null, 
+/**
+ * When the latest execution process completed
+ */
+latest_process_completed_at?: string, 
+/**
+ * Status of the latest execution process
+ */
+latest_process_status: ExecutionProcessStatus | null, 
+/**
+ * Is a dev server currently running?
+ */
+has_running_dev_server: boolean, 
+/**
+ * Does this workspace have unseen coding agent turns?
+ */
+has_unseen_turns: boolean, 
+/**
+ * PR status for this workspace (if any PR exists)
+ */
+pr_status: MergeStatus | null, };
+
+export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
+
+export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
+
+export type CompareExecutorsRequest = { task_id: string, 
+/**
+ * One attempt is started per profile
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Start now even if the project's execution window is closed
+ */
+ignore_execution_window: boolean, 
+/**
+ * Start even if the runs are expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+export type CompareExecutorsResponse = { comparison_group_id: string, workspaces: Array<Workspace>, };
+
+export type ComparisonAttempt = { workspace_id: string, executor_profile_id: ExecutorProfileId | null, 
+/**
+ * A coding agent run is still going; the other numbers are partial
+ */
+running: boolean, 
+/**
+ * One of its coding agent runs failed or was killed
+ */
+failed: boolean, files_changed: number | null, lines_added: number | null, lines_removed: number | null, 
+/**
+ * Whether every repo's coverage hook produced a report; `None` when no
+ * repo has one configured or the attempt is still running
+ */
+tests_passed: boolean | null, agent_seconds: number | null, total_tokens: number | null, };
+
+export type ComparisonReport = { comparison_group_id: string, task_id: string, attempts: Array<ComparisonAttempt>, };
+
+export type ReplayPromptRequest = { prompt_id: string, 
+/**
+ * Edited text to send instead of the original
+ */
+prompt: string | null, 
+/**
+ * Start even if the run is expected to go over the project's monthly
+ * budget, when the project asks for confirmation rather than blocking
+ */
+confirm_over_budget?: boolean, };
+
+/**
+ * A deferred attempt and when its project's execution window next opens
+ */
+export type DeferredStart = { workspace_id: string, executor_profile_id: ExecutorProfileId, created_at: string, 
+/**
+ * `None` once the window is open and the attempt is about to start
+ */
+starts_at: string | null, };
+
+export type TranscriptQuery = { format: TranscriptFormat, };
+
+export type TranscriptFormat = "markdown" | "json";
+
+/**
+ * One coding agent run: what it was asked and what it did
+ */
+export type TranscriptTurn = { execution_process_id: string, executor: string | null, prompt_kind: AgentPromptKind | null, prompt: string | null, started_at: string, entries: Array<NormalizedEntry>, };
+
+export type Transcript = { workspace_id: string, task_title: string, exported_at: string, turns: Array<TranscriptTurn>, };
+
+export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
+
+export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+
+export type SearchMode = "taskform" | "settings";
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, workspace_dir: string | null, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, 
+/**
+ * cuando está habilitado, el agente hará commit automático después de cambios exitosos
+ */
+git_auto_commit_enabled: boolean, 
+/**
+ * custom font family override (system fonts will be used if None)
+ */
+font_family: string | null, 
+/**
+ * cuando está habilitado, se cargarán las fuentes de Google (Chivo Mono, Inter, JetBrains Mono)
+ */
+use_google_fonts: boolean, 
+/**
+ * cuando está habilitado, se cargarán los símbolos de Nerd Fonts para iconos en la interfaz
+ */
+use_nerd_fonts: boolean, 
+/**
+ * cuando está habilitado, se muestra el contador de usuarios online de Discord en la barra de navegación
+ */
+discord_counter_enabled: boolean, 
+/**
+ * modo de generación del título de commit para auto-commits
+ */
+git_commit_title_mode: GitCommitTitleMode, 
+/**
+ * prompt personalizado para generación de títulos de commit (modo AiGenerated)
+ */
+git_commit_title_prompt: string | null, 
+/**
+ * cuando está habilitado, se crea automáticamente un PR cuando la tarea pasa a "In Review"
+ */
+auto_pr_on_review_enabled: boolean, 
+/**
+ * cuando está habilitado, los PRs automáticos se crean como draft
+ */
+auto_pr_draft: boolean, 
+/**
+ * cuando está habilitado, redirige automáticamente al intento después de crear una tarea
+ */
+redirect_to_attempt_on_create: boolean, 
+/**
+ * modo de auto-push después de commits exitosos
+ */
+git_auto_push_mode: GitAutoPushMode, 
+/**
+ * cuando está habilitado, abre el PR en una nueva pestaña del navegador después de crearlo
+ */
+open_pr_in_browser: boolean, 
+/**
+ * commit reminder for uncommitted changes (from upstream)
+ */
+commit_reminder: boolean, 
+/**
+ * shortcut for sending messages in chat
+ */
+send_message_shortcut: SendMessageShortcut, 
+/**
+ * opt-in anonymous usage statistics
+ */
+analytics_enabled: boolean, 
+/**
+ * PostHog-compatible endpoint for usage statistics (cloud or self-hosted)
+ */
+analytics_endpoint: string | null, analytics_api_key: string | null, 
+/**
+ * `.gitignore`-style patterns whose file contents are withheld from diff-derived text
+ */
+sensitive_file_patterns: Array<string>, 
+/**
+ * embedding index over repo files used to suggest task context
+ */
+embedding_provider: EmbeddingProvider, 
+/**
+ * OpenAI-compatible embeddings endpoint for the `Api` provider
+ */
+embedding_endpoint: string | null, 
+/**
+ * model name sent to the embeddings endpoint
+ */
+embedding_model: string | null, embedding_api_key: string | null, 
+/**
+ * add similar completed tasks to the initial prompt of attempts
+ */
+similar_tasks_in_prompt: boolean, 
+/**
+ * periodic fetch of the target branches of active attempts
+ */
+target_sync_mode: TargetSyncMode, 
+/**
+ * commits an attempt may fall behind its target branch before it is synced
+ */
+target_sync_threshold: number, 
+/**
+ * git trailer naming the task in auto-commits, merge commits and PR bodies
+ */
+task_trailer: string | null, 
+/**
+ * watch active worktrees for hand edits and refresh their state live
+ */
+live_worktree_watch: boolean, 
+/**
+ * stash hand-edited files before an agent run and restore them after it
+ */
+auto_stash_hand_edits: boolean, 
+/**
+ * transcription of voice notes into tasks
+ */
+transcription_provider: TranscriptionProvider, 
+/**
+ * command for the `Local` provider; `{input}` is the audio file and the
+ * transcript is read from stdout
+ */
+transcription_command: string | null, 
+/**
+ * OpenAI-compatible transcriptions endpoint for the `Api` provider
+ */
+transcription_endpoint: string | null, 
+/**
+ * model name sent to the transcriptions endpoint
+ */
+transcription_model: string | null, transcription_api_key: string | null, 
+/**
+ * seconds a local git command may run before it is killed
+ */
+git_local_timeout_secs: number, 
+/**
+ * seconds a fetch, push or `gh` call may run before it is killed
+ */
+git_network_timeout_secs: number, 
+/**
+ * days attempt artifacts are kept; 0 keeps them until the attempt is deleted
+ */
+artifact_retention_days: number, 
+/**
+ * Bitbucket Cloud account the app password belongs to
+ */
+bitbucket_username: string | null, 
+/**
+ * app password with pull request read/write scope, used with the username
+ */
+bitbucket_app_password: string | null, 
+/**
+ * workspace or repository access token, used instead of an app password
+ */
+bitbucket_access_token: string | null, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
-export type CurrentUserResponse = { user_id: string, };
+export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
-export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
 
-export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+export enum EditorType { VS_CODE = "VS_CODE", VS_CODE_INSIDERS = "VS_CODE_INSIDERS", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", GOOGLE_ANTIGRAVITY = "GOOGLE_ANTIGRAVITY", CUSTOM = "CUSTOM" }
 
-export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+export type EditorOpenError = { "type": "executable_not_found", executable: string, editor_type: EditorType, } | { "type": "invalid_command", details: string, editor_type: EditorType, } | { "type": "launch_failed", executable: string, details: string, editor_type: EditorType, };
 
-export type MergeTaskAttemptRequest = { repo_id: string, };
+export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, 
+/**
+ * GitHub App installation used instead of the `gh` CLI when set
+ */
+app_installation_id: number | null, 
+/**
+ * GitHub Enterprise Server hostnames, such as `github.acme.corp`, whose
+ * remotes are handled like github.com ones
+ */
+enterprise_hosts: Array<string>, };
 
-export type PushTaskAttemptRequest = { repo_id: string, };
+export type QueuedEvent = { event: string, distinct_id: string, properties: Record<string, unknown>, timestamp: string, };
 
-export type RenameBranchRequest = { new_branch_name: string, };
+export type AnalyticsQueue = { enabled: boolean, 
+/**
+ * Where events are sent; `None` keeps them local until one is configured
+ */
+endpoint: string | null, events: Array<QueuedEvent>, };
 
-export type RenameBranchResponse = { branch: string, };
+/**
+ * A stored secret as shown to the user
+ */
+export type SecretInfo = { name: string, 
+/**
+ * The value with all but its last characters hidden
+ */
+masked: string, 
+/**
+ * Whether coding agents receive it as an environment variable
+ */
+passed_to_agents: boolean, };
 
-export type RenameBranchError = { "type": "empty_branch_name" } | { "type": "invalid_branch_name_format" } | { "type": "open_pull_request" } | { "type": "branch_already_exists", repo_name: string, } | { "type": "rebase_in_progress", repo_name: string, } | { "type": "rename_failed", repo_name: string, message: string, };
+export type SetSecretRequest = { value: string, };
 
-export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+/**
+ * Lifecycle point a hook runs at. `pre_*` hooks can veto the action.
+ */
+export type LifecycleEvent = "pre_attempt" | "post_execution" | "pre_push" | "post_merge" | "needs_rebase";
 
-export type ReviewError = { "type": "process_already_running" };
+export type HookKind = "command" | "http";
 
-export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+/**
+ * Command or HTTP endpoint receiving a JSON payload at a lifecycle point
+ */
+export type LifecycleHook = { id: string, 
+/**
+ * `None` runs the hook for every project
+ */
+project_id: string | null, event: LifecycleEvent, kind: HookKind, 
+/**
+ * Shell command for `command` hooks, URL for `http` hooks
+ */
+target: string, timeout_secs: number, enabled: boolean, created_at: string, updated_at: string, };
 
-export type OpenEditorResponse = { url: string | null, };
+export type CreateLifecycleHook = { project_id: string | null, event: LifecycleEvent, kind: HookKind, target: string, timeout_secs: number | null, enabled: boolean | null, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type UpdateLifecycleHook = { target: string | null, timeout_secs: number | null, enabled: boolean | null, };
 
-export type ShareTaskResponse = { shared_task_id: string, };
+export type LifecycleHookQuery = { 
+/**
+ * Hooks that apply to this project (its own and global ones); all hooks when omitted
+ */
+project_id: string | null, };
 
-export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
+/**
+ * Event a project script reacts to
+ */
+export type ScriptEvent = "task_updated" | "execution_finished";
 
-export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+/**
+ * Rhai automation script stored with a project
+ */
+export type ProjectScript = { id: string, project_id: string, name: string, event: ScriptEvent, source: string, enabled: boolean, created_at: string, updated_at: string, };
 
-export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+export type CreateProjectScript = { name: string, event: ScriptEvent, source: string, enabled: boolean | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type UpdateProjectScript = { name: string | null, source: string | null, enabled: boolean | null, };
 
-export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
+/**
+ * Env file rendered into the worktrees of a project's repos
+ */
+export type ProjectEnvTemplate = { id: string, project_id: string, 
+/**
+ * `None` writes the file into every repo of the workspace
+ */
+repo_id: string | null, 
+/**
+ * Path relative to the repo root, e.g. `.env.local`
+ */
+path: string, content: string, created_at: string, updated_at: string, };
 
-export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+export type UpsertProjectEnvTemplate = { repo_id: string | null, path: string, content: string, };
 
-export type RunAgentSetupResponse = Record<string, never>;
+/**
+ * Port handed out to a workspace for a named service
+ */
+export type WorkspacePort = { workspace_id: string, name: string, port: number, created_at: string, };
 
-export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+/**
+ * What a template refers to, for checking it before it is saved
+ */
+export type EnvTemplateCheck = { ports: Array<string>, secrets: Array<string>, 
+/**
+ * Referenced secrets that are not in the secret store
+ */
+missing_secrets: Array<string>, 
+/**
+ * Placeholders that are neither ports, secrets nor known variables;
+ * they are left as written
+ */
+unknown: Array<string>, };
 
-export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+export type CheckEnvTemplateRequest = { content: string, };
 
-export type AbortConflictsRequest = { repo_id: string, };
+export type ProjectTemplateSource = { "type": "github", template: string, private: boolean, } | { "type": "git", url: string, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
+export type CreateProjectFromTemplate = { name: string, 
+/**
+ * Directory the new repo is created in
+ */
+parent_path: string, folder_name: string, source: ProjectTemplateSource, 
+/**
+ * Create the starter tasks listed in the template's `tasks.yaml`
+ */
+seed_tasks: boolean, };
 
-export type PushError = { "type": "force_push_required" };
+/**
+ * What was created for a project started from a template
+ */
+export type ProjectFromTemplate = { project: Project, repo: Repo, tasks: Array<Task>, 
+/**
+ * Why the template's `tasks.yaml` could not be read, if it could not
+ */
+tasks_error: string | null, };
 
-export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
+/**
+ * Whether an optional feature can work on this server, and whether the
+ * config turns it on
+ */
+export type FeatureStatus = { 
+/**
+ * The build and environment support it
+ */
+available: boolean, 
+/**
+ * The config has it switched on
+ */
+enabled: boolean, };
 
-export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * Optional features, keyed by name
  */
-is_rebase_in_progress: boolean, 
+export type FeatureMap = { 
 /**
- * Current conflict operation if any
+ * Open a pull request when an attempt moves to review
  */
-conflict_op: ConflictOp | null, 
+auto_pr: FeatureStatus, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * Generate pull request descriptions with the coding agent
  */
-conflicted_files: Array<string>, };
+pr_auto_description: FeatureStatus, 
+/**
+ * Generate commit titles with the coding agent
+ */
+ai_commit_titles: FeatureStatus, analytics: FeatureStatus, 
+/**
+ * Suggest task context from an embedding index of repo files
+ */
+embedding_index: FeatureStatus, 
+/**
+ * Create tasks from transcribed voice notes
+ */
+voice_notes: FeatureStatus, 
+/**
+ * Share projects and tasks through the remote service
+ */
+remote_sharing: FeatureStatus, 
+/**
+ * Several users with their own accounts on one server
+ */
+multi_user: FeatureStatus, 
+/**
+ * Mock executors for QA builds
+ */
+qa_mode: FeatureStatus, };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+/**
+ * What this server allows, so clients can hide UI that would fail
+ */
+export type Capabilities = { 
+/**
+ * Mutating requests are rejected with 403 (demo or shared instances)
+ */
+read_only: boolean, features: FeatureMap, 
+/**
+ * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ */
+executors: { [key in string]?: Array<BaseAgentCapability> }, };
 
-export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+export type GitHubAppStatus = { configured: boolean, app_slug: string | null, installation_id: number | null, account_login: string | null, };
 
-export type AttachExistingPrRequest = { repo_id: string, };
+export type GitHubAppInstallUrl = { url: string, state: string, };
 
-export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+export type CheckStatus = "pass" | "warn" | "fail";
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+export type DiagnosticCheck = { id: string, name: string, status: CheckStatus, message: string, 
+/**
+ * How to fix a warning or failure
+ */
+remediation: string | null, };
 
-export type GetPrCommentsQuery = { repo_id: string, };
+export type SystemDiagnostics = { 
+/**
+ * Worst status across all checks
+ */
+overall: CheckStatus, checks: Array<DiagnosticCheck>, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+export type RemoteTransport = "https" | "ssh" | "other";
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type RemoteCredentialCheck = { repo_id: string, repo_name: string, remote_name: string, url: string, transport: RemoteTransport, accessible: boolean, auth_failed: boolean, error: string | null, };
 
-export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * Whether git can authenticate to an HTTPS host without prompting
  */
-is_rebase_in_progress: boolean, 
+export type HostCredentialStatus = { host: string, 
 /**
- * Current conflict operation if any
+ * Credential helper configured for the host, if any
  */
-conflict_op: ConflictOp | null, 
+helper: string | null, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * Whether a helper, or a token stored through the app, supplies
+ * credentials for the host
  */
-conflicted_files: Array<string>, };
-
-export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
-
-export type WorkspaceSummaryRequest = { archived: boolean, };
-
-export type WorkspaceSummary = { workspace_id: string, 
+has_credentials: boolean, 
 /**
- * Session ID of the latest execution process
+ * Repos with a remote on the host
  */
-latest_session_id: string | null, 
+repo_names: Array<string>, };
+
+export type GitHubDiagnostics = { gh_available: boolean, gh_version: string | null, default_host: string, logged_in_account: string | null, token_scopes: Array<string>, 
 /**
- * Is a tool approval currently pending?
+ * Required scopes absent from a classic token; empty for fine-grained tokens,
+ * which do not report scopes.
  */
-has_pending_approval: boolean, 
+missing_scopes: Array<string>, rate_limit_remaining: number | null, gh_error: string | null, github_app: GitHubAppStatus, 
 /**
- * Number of files with changes
+ * Whether PR operations can use the configured token without `gh`
  */
-files_changed: number | null, 
+rest_fallback_available: boolean, remotes: Array<RemoteCredentialCheck>, https_hosts: Array<HostCredentialStatus>, };
+
+export type StoreCredentialsRequest = { 
 /**
- * Total lines added across all files
+ * Host of an HTTPS remote, e.g. `github.com`
  */
-lines_added: number | null, 
+host: string, username: string | null, 
 /**
- * Total lines removed across all files
+ * Personal access token for the host
  */
-lines_removed: number | null, 
+token: string, };
+
+export type RepoCoverageSettings = { repo_id: string, 
 /**
- * When the latest execution process completed
+ * Shell command producing the coverage report
  */
-latest_process_completed_at?: string, 
+command: string, 
 /**
- * Status of the latest execution process
+ * lcov or cobertura report written by the command, relative to the repo root
  */
-latest_process_status: ExecutionProcessStatus | null, 
+report_path: string, 
 /**
- * Is a dev server currently running?
+ * Allowed drop in line coverage, in percentage points
  */
-has_running_dev_server: boolean, 
+max_drop: number, 
 /**
- * Does this workspace have unseen coding agent turns?
+ * Block auto-PR for attempts that drop coverage by more than `max_drop`
  */
-has_unseen_turns: boolean, 
+block_auto_pr: boolean, created_at: string, updated_at: string, };
+
+export type UpsertRepoCoverageSettings = { command: string, report_path: string, max_drop: number | null, block_auto_pr: boolean | null, };
+
+export type CoverageReport = { id: string, workspace_id: string, repo_id: string, commit_sha: string, lines_covered: number, lines_total: number, baseline_commit_sha: string | null, baseline_lines_covered: number | null, baseline_lines_total: number | null, 
 /**
- * PR status for this workspace (if any PR exists)
+ * Percentage points gained (positive) or lost (negative) vs. the target branch
  */
-pr_status: MergeStatus | null, };
+delta: number | null, auto_pr_blocked: boolean, created_at: string, };
 
-export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
+/**
+ * Authenticate with the keys of an ssh-agent, or with a single private key
+ * file such as a deploy key
+ */
+export type SshKeyMode = "agent" | "key_file";
 
-export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
+/**
+ * SSH identity used when fetching from and pushing to a repo's remotes
+ */
+export type RepoSshKey = { repo_id: string, mode: SshKeyMode, 
+/**
+ * Private key passed to `ssh -i` in `key_file` mode
+ */
+key_path: string | null, 
+/**
+ * ssh-agent socket used in `agent` mode; defaults to `SSH_AUTH_SOCK`
+ */
+agent_socket: string | null, created_at: string, updated_at: string, };
 
-export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
+export type UpsertRepoSshKey = { mode: SshKeyMode, key_path: string | null, agent_socket: string | null, };
 
-export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
+export type SecurityScanner = "cargo_audit" | "npm_audit" | "semgrep";
 
-export type SearchMode = "taskform" | "settings";
+export type FindingSeverity = "info" | "low" | "medium" | "high" | "critical";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, workspace_dir: string | null, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, 
+export type RepoSecurityAuditSettings = { repo_id: string, scanners: Array<SecurityScanner>, 
 /**
- * cuando está habilitado, el agente hará commit automático después de cambios exitosos
+ * Value passed to `semgrep --config`; defaults to "auto"
  */
-git_auto_commit_enabled: boolean, 
+semgrep_config: string | null, 
 /**
- * custom font family override (system fonts will be used if None)
+ * Block auto-PR while high or critical findings are open
  */
-font_family: string | null, 
+block_auto_pr: boolean, 
 /**
- * cuando está habilitado, se cargarán las fuentes de Google (Chivo Mono, Inter, JetBrains Mono)
+ * Post a summary of the findings on the attempt's pull request
  */
-use_google_fonts: boolean, 
+comment_on_pr: boolean, created_at: string, updated_at: string, };
+
+export type UpsertRepoSecurityAuditSettings = { scanners: Array<SecurityScanner>, semgrep_config: string | null, block_auto_pr: boolean | null, comment_on_pr: boolean | null, };
+
+export type SecurityFinding = { id: string, workspace_id: string, repo_id: string, scanner: SecurityScanner, severity: FindingSeverity, rule_id: string, title: string, 
 /**
- * cuando está habilitado, se cargarán los símbolos de Nerd Fonts para iconos en la interfaz
+ * File and line for code findings, package@version for dependency findings
  */
-use_nerd_fonts: boolean, 
+location: string | null, created_at: string, };
+
+export type ToolVersion = { name: string, version: string | null, };
+
+export type LockfileHash = { repo: string, 
 /**
- * cuando está habilitado, se muestra el contador de usuarios online de Discord en la barra de navegación
+ * Path relative to the repo root
  */
-discord_counter_enabled: boolean, 
+path: string, sha256: string, };
+
+export type WorkspaceEnvironment = { id: string, workspace_id: string, execution_process_id: string | null, os: string, arch: string, tools: Array<ToolVersion>, lockfiles: Array<LockfileHash>, created_at: string, };
+
+export type EnvironmentDifferenceKind = "platform" | "tool" | "lockfile";
+
+export type EnvironmentDifference = { kind: EnvironmentDifferenceKind, 
 /**
- * modo de generación del título de commit para auto-commits
+ * Tool name, `os`/`arch`, or `repo/path` of a lockfile
  */
-git_commit_title_mode: GitCommitTitleMode, 
+name: string, left: string | null, right: string | null, };
+
+export type ExecutorStats = { executor: string, runs: number, completed: number, failed: number, killed: number, 
 /**
- * prompt personalizado para generación de títulos de commit (modo AiGenerated)
+ * Completed runs over finished runs, `None` while nothing has finished
  */
-git_commit_title_prompt: string | null, 
+success_rate: number | null, };
+
+export type InstanceStats = { since: string, attempts_run: number, coding_agent_runs: number, 
 /**
- * cuando está habilitado, se crea automáticamente un PR cuando la tarea pasa a "In Review"
+ * Peak context tokens per coding agent run, summed
  */
-auto_pr_on_review_enabled: boolean, 
+tokens_used: number, 
 /**
- * cuando está habilitado, los PRs automáticos se crean como draft
+ * Repo states where a run moved HEAD, i.e. the agent produced commits
  */
-auto_pr_draft: boolean, 
+runs_with_commits: number, prs_opened: number, prs_merged: number, direct_merges: number, executors: Array<ExecutorStats>, };
+
+export type JobStatus = "pending" | "running" | "completed" | "dead";
+
+export type Job = { id: string, kind: string, payload: unknown, 
 /**
- * cuando está habilitado, redirige automáticamente al intento después de crear una tarea
+ * `dead` jobs ran out of attempts and stay until retried by hand
  */
-redirect_to_attempt_on_create: boolean, 
+status: JobStatus, attempts: number, max_attempts: number, last_error: string | null, 
 /**
- * modo de auto-push después de commits exitosos
+ * Earliest time the job may run; pushed back between retries
  */
-git_auto_push_mode: GitAutoPushMode, 
+run_at: string, started_at: string | null, finished_at: string | null, created_at: string, updated_at: string, };
+
+export type ProjectShareLink = { id: string, project_id: string, token: string, 
 /**
- * cuando está habilitado, abre el PR en una nueva pestaña del navegador después de crearlo
+ * `None` keeps the link valid until it is revoked
  */
-open_pr_in_browser: boolean, 
+expires_at: string | null, created_at: string, };
+
+export type CreateProjectShareLink = { 
 /**
- * commit reminder for uncommitted changes (from upstream)
+ * Days until the link stops working; omit for a link that lasts until revoked
  */
-commit_reminder: boolean, 
+expires_in_days: number | null, };
+
+export type PublicBoardLabel = { name: string, color: string, };
+
+export type PublicBoardTask = { title: string, description: string | null, status: TaskStatus, labels: Array<PublicBoardLabel>, 
 /**
- * shortcut for sending messages in chat
+ * An agent is working on the task right now
  */
-send_message_shortcut: SendMessageShortcut, };
+in_progress: boolean, updated_at: string, };
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type PublicBoard = { project_name: string, tasks: Array<PublicBoardTask>, };
 
-export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
+/**
+ * Shares a project's tasks automatically: every task carrying `label_id`, or
+ * every task at `min_status` or further along. Exactly one is set.
+ */
+export type ProjectShareRule = { id: string, project_id: string, label_id: string | null, min_status: TaskStatus | null, created_at: string, };
 
-export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
+export type CreateProjectShareRule = { label_id: string | null, min_status: TaskStatus | null, };
 
-export enum EditorType { VS_CODE = "VS_CODE", VS_CODE_INSIDERS = "VS_CODE_INSIDERS", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", GOOGLE_ANTIGRAVITY = "GOOGLE_ANTIGRAVITY", CUSTOM = "CUSTOM" }
+export type AgentPromptKind = "initial" | "follow_up" | "pr_description" | "address_comments" | "review";
 
-export type EditorOpenError = { "type": "executable_not_found", executable: string, editor_type: EditorType, } | { "type": "invalid_command", details: string, editor_type: EditorType, } | { "type": "launch_failed", executable: string, details: string, editor_type: EditorType, };
+/**
+ * A prompt as it was sent to an agent
+ */
+export type AgentPrompt = { id: string, execution_process_id: string, session_id: string, kind: AgentPromptKind, prompt: string, replayed_from_id: string | null, created_at: string, };
 
-export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
+/**
+ * A coding agent turn the user interrupted, and where it had got to
+ */
+export type ExecutionInterrupt = { execution_process_id: string, 
+/**
+ * The agent's own session id, so the next turn continues the conversation
+ */
+agent_session_id: string | null, 
+/**
+ * What the agent had said and done in the interrupted turn
+ */
+partial_output: string | null, resumed_at: string | null, created_at: string, };
 
 export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
@@ -543,6 +2285,12 @@ export type GitAutoPushMode = "Never" | "Always" | "IfPrExists";
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
+export type EmbeddingProvider = "Disabled" | "Local" | "Api";
+
+export type TargetSyncMode = "Disabled" | "Notify" | "AutoRebase";
+
+export type TranscriptionProvider = "Disabled" | "Local" | "Api";
+
 export type PendingCommit = { id: string, workspace_id: string, repo_id: string, 
 /**
  * path del repo dentro del workspace
@@ -567,6 +2315,38 @@ export type UserData = { user_id: string, first_name: string | null, last_name:
 
 export type AssigneesQuery = { project_id: string, };
 
+export type SharedTaskClaim = { task_id: string, holder: UserData, claimed_at: string, expires_at: string, };
+
+export type SharedTaskExecutionState = "started" | "executing" | "completed" | "failed" | "pr_opened";
+
+export type SharedTaskExecutionStatus = { task_id: string, 
+/**
+ * Member whose local instance reported the state
+ */
+reporter: UserData, state: SharedTaskExecutionState, detail: string | null, updated_at: string, };
+
+/**
+ * A member @mentioned the current user in a shared task or issue comment.
+ * This mirrors the remote crate's Mention for TypeScript generation.
+ */
+export type PullRequestStatus = "open" | "merged" | "closed";
+
+export type SharedTaskAttempt = { 
+/**
+ * Id of the workspace on the member's local instance
+ */
+id: string, task_id: string, project_id: string, user_id: string, branch: string, summary: string | null, pr_url: string | null, pr_number: number | null, pr_status: PullRequestStatus | null, pr_merged_at: string | null, created_at: string, updated_at: string, };
+
+export type Mention = { id: string, organization_id: string, author: UserData, shared_task_id: string | null, issue_id: string | null, issue_comment_id: string | null, excerpt: string, read_at: string | null, created_at: string, };
+
+export type MarkMentionsReadRequest = { 
+/**
+ * Mentions to mark as read; all unread mentions when omitted
+ */
+mention_ids?: Array<string>, };
+
+export type MarkMentionsReadResponse = { updated: number, };
+
 export type SharedTaskResponse = { task: SharedTask, user: UserData | null, };
 
 export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
@@ -577,7 +2357,7 @@ export type GitRemote = { name: string, url: string | null, };
 
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, 
 /**
  * The session this message is queued for
  */
@@ -591,7 +2371,15 @@ data: DraftFollowUpData,
  */
 queued_at: string, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type QueueStatus = { "status": "empty" } | { "status": "queued", 
+/**
+ * The message that runs next
+ */
+message: QueuedMessage, 
+/**
+ * Every queued message in run order, starting with `message`
+ */
+messages: Array<QueuedMessage>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -707,7 +2495,11 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Model to run instead of the profile's own, from the project's executor defaults
+ */
+model?: string | null, };
 
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, 
 /**
@@ -718,7 +2510,11 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Model to run instead of the profile's own, from the project's executor defaults
+ */
+model?: string | null, };
 
 export type ReviewRequest = { executor_profile_id: ExecutorProfileId, context: Array<RepoReviewContext> | null, prompt: string, 
 /**